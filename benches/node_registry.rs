@@ -0,0 +1,45 @@
+//! Registry queries at 50k validators: full clones versus pages and cached summaries.
+//!
+//! Run with `cargo bench --bench node_registry`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use platarium_core::{select_validators_with_percent, NodeRegistry};
+
+const NODES: usize = 50_000;
+
+fn registry() -> NodeRegistry {
+    let reg = NodeRegistry::new();
+    for i in 0..NODES {
+        reg.register(format!("node-{:06}", i), format!("pk-{}", i), 1_000 + i as u128, 100)
+            .unwrap();
+    }
+    reg
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let reg = registry();
+    let seed = [7u8; 32];
+    let mut group = c.benchmark_group("node_registry_50k");
+    group.sample_size(20);
+    group.bench_function("get_eligible", |b| b.iter(|| black_box(reg.get_eligible().len())));
+    group.bench_function("eligible_summaries_cached", |b| {
+        b.iter(|| black_box(reg.eligible_summaries().len()))
+    });
+    group.bench_function("eligible_summaries_rebuild", |b| {
+        b.iter(|| {
+            reg.set_load(&"node-000000".to_string(), 1, 100).unwrap();
+            black_box(reg.eligible_summaries().len())
+        })
+    });
+    group.bench_function("get_page_1000", |b| {
+        let cursor = "node-025000".to_string();
+        b.iter(|| black_box(reg.get_page(Some(&cursor), 1_000).items.len()))
+    });
+    group.bench_function("select_validators_10pct", |b| {
+        b.iter(|| black_box(select_validators_with_percent(&reg, &seed, 10).unwrap().len()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_queries);
+criterion_main!(benches);
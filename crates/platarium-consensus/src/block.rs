@@ -0,0 +1,53 @@
+//! Block header hashing.
+
+use alloc::vec::Vec;
+
+/// Header bytes hashed into the block hash (producer signature excluded): `block_number` (u64 LE),
+/// `previous_hash`, `timestamp` (i64 LE), `merkle_root`, `state_root`, `producer_id`, the strings as their UTF-8
+/// bytes without separators.
+pub fn block_signing_payload(
+    block_number: u64,
+    previous_hash: &str,
+    timestamp: i64,
+    merkle_root: &str,
+    state_root: &str,
+    producer_id: &str,
+) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(
+        16 + previous_hash.len() + merkle_root.len() + state_root.len() + producer_id.len(),
+    );
+    payload.extend_from_slice(&block_number.to_le_bytes());
+    payload.extend_from_slice(previous_hash.as_bytes());
+    payload.extend_from_slice(&timestamp.to_le_bytes());
+    payload.extend_from_slice(merkle_root.as_bytes());
+    payload.extend_from_slice(state_root.as_bytes());
+    payload.extend_from_slice(producer_id.as_bytes());
+    payload
+}
+
+/// Appends the outbound messages root of a block that emitted messages: a `0x00` byte, `messages:`, then the
+/// root's UTF-8 bytes. Blocks without messages append nothing, so their payload and hash are unchanged.
+pub fn push_messages_root(payload: &mut Vec<u8>, messages_root: &str) {
+    payload.push(0);
+    payload.extend_from_slice(b"messages:");
+    payload.extend_from_slice(messages_root.as_bytes());
+}
+
+/// SHA256 of [`block_signing_payload`].
+pub fn block_hash(
+    block_number: u64,
+    previous_hash: &str,
+    timestamp: i64,
+    merkle_root: &str,
+    state_root: &str,
+    producer_id: &str,
+) -> [u8; 32] {
+    crate::sha256(&block_signing_payload(
+        block_number,
+        previous_hash,
+        timestamp,
+        merkle_root,
+        state_root,
+        producer_id,
+    ))
+}
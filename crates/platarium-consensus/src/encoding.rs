@@ -0,0 +1,347 @@
+//! Canonical encoding of signed messages and transactions.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::wire::Encode;
+
+/// Prefix of every signed preimage under the legacy [`SigningDomain`].
+pub const DOMAIN_SEPARATOR: &str = "PlatariumSignature:";
+
+/// Name of the Platarium signing domain.
+pub const DOMAIN_NAME: &str = "PlatariumSignature";
+
+/// Version of the original, unversioned encoding: the separator is `name:` with no chain id.
+pub const LEGACY_DOMAIN_VERSION: u16 = 0;
+
+/// Version used by [`SigningDomain::for_chain`].
+pub const CURRENT_DOMAIN_VERSION: u16 = 1;
+
+/// Chain id of the legacy domain.
+pub const MAINNET_CHAIN_ID: &str = "platarium-mainnet";
+
+/// What a signed preimage is bound to: a protocol name, an encoding version and a chain.
+///
+/// Versions after [`LEGACY_DOMAIN_VERSION`] prefix the message with `name/v<version>/<chain_id>:`, so a signature
+/// made for one chain or encoding version does not verify under another. The default is the legacy mainnet domain,
+/// whose separator is [`DOMAIN_SEPARATOR`], so existing signatures and transaction hashes are unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SigningDomain {
+    pub name: String,
+    pub version: u16,
+    pub chain_id: String,
+}
+
+impl SigningDomain {
+    pub fn new(name: impl Into<String>, version: u16, chain_id: impl Into<String>) -> Self {
+        Self { name: name.into(), version, chain_id: chain_id.into() }
+    }
+
+    /// The original mainnet domain ([`DOMAIN_SEPARATOR`]).
+    pub fn legacy() -> Self {
+        Self::new(DOMAIN_NAME, LEGACY_DOMAIN_VERSION, MAINNET_CHAIN_ID)
+    }
+
+    /// The current versioned domain for `chain_id`.
+    pub fn for_chain(chain_id: impl Into<String>) -> Self {
+        Self::new(DOMAIN_NAME, CURRENT_DOMAIN_VERSION, chain_id)
+    }
+
+    /// Bytes prepended to the message JSON. The legacy version ignores `chain_id`.
+    pub fn separator(&self) -> String {
+        if self.version == LEGACY_DOMAIN_VERSION {
+            format!("{}:", self.name)
+        } else {
+            format!("{}/v{}/{}:", self.name, self.version, self.chain_id)
+        }
+    }
+}
+
+impl Default for SigningDomain {
+    fn default() -> Self {
+        Self::legacy()
+    }
+}
+
+/// Bytes hashed for a signature: [`DOMAIN_SEPARATOR`] followed by the compact JSON of `message`.
+pub fn message_preimage<T: Serialize + ?Sized>(message: &T) -> Result<Vec<u8>, serde_json::Error> {
+    let json = serde_json::to_vec(message)?;
+    let mut preimage = Vec::with_capacity(DOMAIN_SEPARATOR.len() + json.len());
+    preimage.extend_from_slice(DOMAIN_SEPARATOR.as_bytes());
+    preimage.extend_from_slice(&json);
+    Ok(preimage)
+}
+
+/// SHA256 of [`message_preimage`].
+pub fn hash_message<T: Serialize + ?Sized>(message: &T) -> Result<[u8; 32], serde_json::Error> {
+    Ok(crate::sha256(&message_preimage(message)?))
+}
+
+/// [`message_preimage`] under `domain`: its separator followed by the compact JSON of `message`.
+pub fn message_preimage_in<T: Serialize + ?Sized>(
+    domain: &SigningDomain,
+    message: &T,
+) -> Result<Vec<u8>, serde_json::Error> {
+    let separator = domain.separator();
+    let json = serde_json::to_vec(message)?;
+    let mut preimage = Vec::with_capacity(separator.len() + json.len());
+    preimage.extend_from_slice(separator.as_bytes());
+    preimage.extend_from_slice(&json);
+    Ok(preimage)
+}
+
+/// SHA256 of [`message_preimage_in`].
+pub fn hash_message_in<T: Serialize + ?Sized>(domain: &SigningDomain, message: &T) -> Result<[u8; 32], serde_json::Error> {
+    Ok(crate::sha256(&message_preimage_in(domain, message)?))
+}
+
+/// Prefix of a raw-bytes signing preimage. Distinct from [`DOMAIN_SEPARATOR`], so a signature over bytes that
+/// happen to be JSON is never valid as a message signature, or the other way round.
+pub const BYTES_DOMAIN_SEPARATOR: &str = "PlatariumBytes:";
+
+/// SHA256 of [`BYTES_DOMAIN_SEPARATOR`] followed by `bytes`, hashed incrementally without copying `bytes`.
+pub fn hash_bytes(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = MessageHasher::for_bytes();
+    hasher.update(bytes);
+    hasher.finalize()
+}
+
+/// Incremental SHA256 over a domain separator and then any number of [`update`](Self::update) chunks, for
+/// payloads too large to hold as one preimage. Feeding the compact JSON of a message after [`new`](Self::new)
+/// gives [`hash_message`]; feeding raw bytes after [`for_bytes`](Self::for_bytes) gives [`hash_bytes`].
+#[derive(Debug, Clone)]
+pub struct MessageHasher {
+    hasher: Sha256,
+}
+
+impl MessageHasher {
+    /// Hasher under the legacy [`DOMAIN_SEPARATOR`].
+    pub fn new() -> Self {
+        Self::with_separator(DOMAIN_SEPARATOR)
+    }
+
+    /// Hasher under `domain` (see [`hash_message_in`]).
+    pub fn in_domain(domain: &SigningDomain) -> Self {
+        Self::with_separator(&domain.separator())
+    }
+
+    /// Hasher under [`BYTES_DOMAIN_SEPARATOR`] (see [`hash_bytes`]).
+    pub fn for_bytes() -> Self {
+        Self::with_separator(BYTES_DOMAIN_SEPARATOR)
+    }
+
+    fn with_separator(separator: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(separator.as_bytes());
+        Self { hasher }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) -> &mut Self {
+        self.hasher.update(chunk);
+        self
+    }
+
+    pub fn finalize(self) -> [u8; 32] {
+        self.hasher.finalize().into()
+    }
+}
+
+impl Default for MessageHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Prefix of the binary transaction preimage (see [`SigningPayload::binary`]).
+pub const TX_BINARY_DOMAIN_SEPARATOR: &str = "PlatariumTx/v2:";
+
+/// Data a transaction hash and both of its signatures cover, serialized as compact JSON in field order.
+///
+/// `K` is the transaction kind type; it is serialized only when set, so plain transfers keep their
+/// original hash. Read and write sets are sorted and deduplicated. With [`binary`](Self::binary) set, the same
+/// fields are hashed in their [`crate::wire`] encoding instead.
+#[derive(Debug, Clone, Serialize)]
+pub struct SigningPayload<'a, K> {
+    from: &'a str,
+    to: &'a str,
+    asset: String,
+    amount: u128,
+    fee_uplp: u128,
+    nonce: u64,
+    reads: Vec<&'a str>,
+    writes: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'a K>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deployment_salt: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    valid_until: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain_id: Option<&'a str>,
+    #[serde(skip)]
+    binary: bool,
+}
+
+impl<'a, K: Serialize + Encode> SigningPayload<'a, K> {
+    /// Payload with no declared reads or writes and no kind. `asset` is the canonical asset string.
+    pub fn new(from: &'a str, to: &'a str, asset: String, amount: u128, fee_uplp: u128, nonce: u64) -> Self {
+        Self {
+            from,
+            to,
+            asset,
+            amount,
+            fee_uplp,
+            nonce,
+            reads: Vec::new(),
+            writes: Vec::new(),
+            kind: None,
+            deployment_salt: None,
+            valid_until: None,
+            chain_id: None,
+            binary: false,
+        }
+    }
+
+    pub fn reads(mut self, reads: impl IntoIterator<Item = &'a str>) -> Self {
+        self.reads = sorted_set(reads);
+        self
+    }
+
+    pub fn writes(mut self, writes: impl IntoIterator<Item = &'a str>) -> Self {
+        self.writes = sorted_set(writes);
+        self
+    }
+
+    /// Kind to include; pass `None` for a plain transfer.
+    pub fn kind(mut self, kind: Option<&'a K>) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn deployment_salt(mut self, salt: Option<&'a str>) -> Self {
+        self.deployment_salt = salt;
+        self
+    }
+
+    /// Last block height the transaction may be included at; omitted from the payload when `None`.
+    pub fn valid_until(mut self, height: Option<u64>) -> Self {
+        self.valid_until = height;
+        self
+    }
+
+    /// Chain the transaction is for; omitted from the payload when `None`.
+    pub fn chain_id(mut self, chain_id: Option<&'a str>) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Hash the binary encoding instead of the JSON: [`TX_BINARY_DOMAIN_SEPARATOR`] followed by every field in
+    /// declaration order, with `kind`, `deployment_salt`, `valid_until` and `chain_id` as options.
+    pub fn binary(mut self, binary: bool) -> Self {
+        self.binary = binary;
+        self
+    }
+
+    /// Exact bytes that are hashed.
+    pub fn bytes(&self) -> Vec<u8> {
+        if !self.binary {
+            return message_preimage(self).expect("signing payload serializes to JSON");
+        }
+        let mut out = Vec::from(TX_BINARY_DOMAIN_SEPARATOR.as_bytes());
+        self.from.encode(&mut out);
+        self.to.encode(&mut out);
+        self.asset.encode(&mut out);
+        self.amount.encode(&mut out);
+        self.fee_uplp.encode(&mut out);
+        self.nonce.encode(&mut out);
+        self.reads.encode(&mut out);
+        self.writes.encode(&mut out);
+        self.kind.encode(&mut out);
+        self.deployment_salt.encode(&mut out);
+        self.valid_until.encode(&mut out);
+        self.chain_id.encode(&mut out);
+        out
+    }
+
+    /// SHA256 of [`Self::bytes`].
+    pub fn hash(&self) -> [u8; 32] {
+        crate::sha256(&self.bytes())
+    }
+}
+
+fn sorted_set<'a>(items: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut v: Vec<&str> = items.into_iter().collect();
+    v.sort_unstable();
+    v.dedup();
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_bytes() {
+        let payload = SigningPayload::<()>::new("sender", "receiver", String::from("PLP"), 100, 1, 0)
+            .reads(["b", "a", "b"]);
+        assert_eq!(
+            payload.bytes(),
+            br#"PlatariumSignature:{"from":"sender","to":"receiver","asset":"PLP","amount":100,"fee_uplp":1,"nonce":0,"reads":["a","b"],"writes":[]}"#
+        );
+        assert_eq!(payload.hash(), crate::sha256(&payload.bytes()));
+    }
+
+    #[test]
+    fn test_binary_payload_bytes() {
+        let payload = SigningPayload::<()>::new("s", "r", String::from("PLP"), 100, 1, 0).reads(["a"]).binary(true);
+        let mut expected = Vec::from(&b"PlatariumTx/v2:"[..]);
+        for field in [&b"\0\0\0\x01s"[..], b"\0\0\0\x01r", b"\0\0\0\x03PLP"] {
+            expected.extend_from_slice(field);
+        }
+        expected.extend_from_slice(&100u128.to_be_bytes());
+        expected.extend_from_slice(&1u128.to_be_bytes());
+        expected.extend_from_slice(&0u64.to_be_bytes());
+        expected.extend_from_slice(b"\0\0\0\x01\0\0\0\x01a\0\0\0\0");
+        expected.extend_from_slice(&[0, 0, 0, 0]);
+        assert_eq!(payload.bytes(), expected);
+        assert_ne!(payload.hash(), payload.clone().binary(false).hash());
+    }
+
+    #[test]
+    fn test_signing_domains() {
+        let message = ["a", "b"];
+        assert_eq!(SigningDomain::default().separator(), DOMAIN_SEPARATOR);
+        assert_eq!(message_preimage_in(&SigningDomain::legacy(), &message).unwrap(), message_preimage(&message).unwrap());
+        assert_eq!(
+            message_preimage_in(&SigningDomain::for_chain("testnet"), &message).unwrap(),
+            br#"PlatariumSignature/v1/testnet:["a","b"]"#
+        );
+        let mainnet_v1 = hash_message_in(&SigningDomain::for_chain(MAINNET_CHAIN_ID), &message).unwrap();
+        assert_ne!(mainnet_v1, hash_message(&message).unwrap());
+        assert_ne!(mainnet_v1, hash_message_in(&SigningDomain::for_chain("testnet"), &message).unwrap());
+    }
+
+    #[test]
+    fn test_hash_bytes() {
+        let json = br#"["a","b"]"#;
+        assert_eq!(hash_bytes(json), crate::sha256(b"PlatariumBytes:[\"a\",\"b\"]"));
+        assert_ne!(hash_bytes(json), hash_message(&["a", "b"]).unwrap());
+    }
+
+    #[test]
+    fn test_message_hasher() {
+        let mut hasher = MessageHasher::new();
+        hasher.update(br#"["a","#).update(br#""b"]"#);
+        assert_eq!(hasher.finalize(), hash_message(&["a", "b"]).unwrap());
+        let testnet = SigningDomain::for_chain("testnet");
+        let mut hasher = MessageHasher::in_domain(&testnet);
+        hasher.update(br#"["a","b"]"#);
+        assert_eq!(hasher.finalize(), hash_message_in(&testnet, &["a", "b"]).unwrap());
+        let mut hasher = MessageHasher::for_bytes();
+        hasher.update(b"chunk 1, ").update(b"chunk 2");
+        assert_eq!(hasher.finalize(), hash_bytes(b"chunk 1, chunk 2"));
+    }
+}
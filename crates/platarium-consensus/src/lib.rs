@@ -0,0 +1,43 @@
+//! Consensus-critical code paths of Platarium Core, without `std`.
+//!
+//! Everything here is a pure function of its inputs and needs only `alloc`: SHA256, the canonical
+//! transaction encoding that hashes and signatures cover and its binary wire form, the block header payload, the transaction Merkle
+//! root, bridge message and claim leaves and Merkle proofs, the state roots, and the stateless transaction rules.
+//! `platarium-core` calls into this crate for all of them, so an embedded signer or a WASM light client linking
+//! it computes byte-identical hashes.
+//!
+//! Locks, storage, randomness, key management and the CLI stay in `platarium-core`.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod block;
+pub mod encoding;
+pub mod merkle;
+pub mod messages;
+pub mod state_root;
+pub mod validation;
+pub mod wire;
+
+pub use block::{block_hash, block_signing_payload, push_messages_root};
+pub use encoding::{
+    hash_bytes, hash_message, hash_message_in, message_preimage, message_preimage_in, MessageHasher, SigningDomain,
+    SigningPayload, BYTES_DOMAIN_SEPARATOR, CURRENT_DOMAIN_VERSION, DOMAIN_NAME, DOMAIN_SEPARATOR, LEGACY_DOMAIN_VERSION, MAINNET_CHAIN_ID,
+    TX_BINARY_DOMAIN_SEPARATOR,
+};
+pub use merkle::{merkle_proof, merkle_root, verify_merkle_proof};
+pub use messages::{bridge_claim_leaf, message_leaf, BRIDGE_CLAIM_LEAF_DOMAIN, MESSAGE_LEAF_DOMAIN};
+pub use state_root::{
+    account_key, account_leaf, key_bit, registries_digest, snapshot_state_root, state_commitment, subtree, token_key,
+    token_leaf, TreeNode, Word,
+};
+pub use validation::{check_amount_and_fee, AmountRule, BasicTxError, MIN_FEE_UPLP};
+pub use wire::{frame, unframe, Decode, Encode, Reader, WireError};
+
+use sha2::{Digest, Sha256};
+
+/// SHA256 of `bytes`.
+pub fn sha256(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
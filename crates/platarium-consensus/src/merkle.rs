@@ -0,0 +1,92 @@
+//! Transaction Merkle root.
+
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// Merkle root over 32-byte leaves, or `None` for no leaves. Each layer is sorted before pairing, so leaf order
+/// does not matter; an odd node is paired with itself.
+pub fn merkle_root(mut layer: Vec<[u8; 32]>) -> Option<[u8; 32]> {
+    layer.sort_unstable();
+    while layer.len() > 1 {
+        let mut next: Vec<[u8; 32]> = layer
+            .chunks(2)
+            .map(|pair| {
+                let mut hasher = Sha256::new();
+                hasher.update(pair[0]);
+                hasher.update(pair.get(1).unwrap_or(&pair[0]));
+                hasher.finalize().into()
+            })
+            .collect();
+        next.sort_unstable();
+        layer = next;
+    }
+    layer.pop()
+}
+
+/// Sibling hashes from `leaf` up to the [`merkle_root`] of `leaves`, or `None` if `leaf` is not among them. A node
+/// without a partner in its layer is its own sibling.
+pub fn merkle_proof(mut layer: Vec<[u8; 32]>, leaf: [u8; 32]) -> Option<Vec<[u8; 32]>> {
+    layer.sort_unstable();
+    let mut node = leaf;
+    let mut proof = Vec::new();
+    while layer.len() > 1 {
+        let pos = layer.binary_search(&node).ok()?;
+        let sibling = *layer.get(pos ^ 1).unwrap_or(&node);
+        proof.push(sibling);
+        node = hash_pair(node, sibling);
+        let mut next: Vec<[u8; 32]> = layer
+            .chunks(2)
+            .map(|pair| hash_pair(pair[0], *pair.get(1).unwrap_or(&pair[0])))
+            .collect();
+        next.sort_unstable();
+        layer = next;
+    }
+    (layer.first() == Some(&node)).then_some(proof)
+}
+
+/// True if folding `proof` into `leaf` gives `root`. Pairs are hashed smaller-first, matching the sorted layers
+/// of [`merkle_root`], so the proof needs no left/right flags.
+pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(node, *sibling)) == root
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Sha256::new();
+    hasher.update(lo);
+    hasher.update(hi);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_order_independent_and_odd_duplicated() {
+        let (a, b, c) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        assert_eq!(merkle_root(vec![]), None);
+        assert_eq!(merkle_root(vec![a]), Some(a));
+        assert_eq!(merkle_root(vec![c, a, b]), merkle_root(vec![a, b, c]));
+        let ab = crate::sha256(&[a, b].concat());
+        let cc = crate::sha256(&[c, c].concat());
+        let mut top = [ab, cc];
+        top.sort_unstable();
+        assert_eq!(merkle_root(vec![a, b, c]), Some(crate::sha256(&top.concat())));
+    }
+
+    #[test]
+    fn test_proofs_verify_against_root() {
+        for n in 1..=9u8 {
+            let leaves: Vec<[u8; 32]> = (0..n).map(|i| crate::sha256(&[i])).collect();
+            let root = merkle_root(leaves.clone()).unwrap();
+            for leaf in &leaves {
+                let proof = merkle_proof(leaves.clone(), *leaf).unwrap();
+                assert!(verify_merkle_proof(*leaf, &proof, root), "n={} leaf={:?}", n, leaf);
+                assert!(!verify_merkle_proof(crate::sha256(b"other"), &proof, root));
+            }
+            assert_eq!(merkle_proof(leaves, crate::sha256(b"other")), None);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Bridge message leaves.
+//!
+//! A block commits to the messages it emitted for external bridges with the [`crate::merkle_root`] of their
+//! leaves (see [`crate::push_messages_root`]). A bridge holding a message and its [`crate::merkle_proof`] checks
+//! it against a verified block header with [`crate::verify_merkle_proof`].
+//!
+//! In the other direction, a foreign chain commits to the transfers it locked for Platarium with a Merkle root of
+//! [`bridge_claim_leaf`]s, which a `BridgeClaim` transaction proves against.
+
+use sha2::{Digest, Sha256};
+
+/// Prefix of every message leaf, so a leaf can never equal an inner node or another hash in the protocol.
+pub const MESSAGE_LEAF_DOMAIN: &str = "PlatariumOutboundMessage:";
+
+/// Prefix of every inbound claim leaf.
+pub const BRIDGE_CLAIM_LEAF_DOMAIN: &str = "PlatariumBridgeClaim:";
+
+/// Leaf of message `index` (0-based, in emission order) of block `block_height`:
+/// `SHA256(MESSAGE_LEAF_DOMAIN || block_height (u64 BE) || index (u32 BE) || sender || destination || payload)`,
+/// where each of the last three is prefixed by its length as u32 BE.
+pub fn message_leaf(block_height: u64, index: u32, sender: &str, destination: &str, payload: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(MESSAGE_LEAF_DOMAIN.as_bytes());
+    hasher.update(block_height.to_be_bytes());
+    hasher.update(index.to_be_bytes());
+    for field in [sender.as_bytes(), destination.as_bytes(), payload] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field);
+    }
+    hasher.finalize().into()
+}
+
+/// Leaf of inbound claim `claim_id` from `source_chain`, paying `amount` of `asset` (canonical) to `recipient`:
+/// `SHA256(BRIDGE_CLAIM_LEAF_DOMAIN || source_chain || claim_id || recipient || asset || amount (u128 BE))`, where
+/// each string is prefixed by its length as u32 BE.
+pub fn bridge_claim_leaf(source_chain: &str, claim_id: &str, recipient: &str, asset: &str, amount: u128) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(BRIDGE_CLAIM_LEAF_DOMAIN.as_bytes());
+    for field in [source_chain, claim_id, recipient, asset] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.update(amount.to_be_bytes());
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_are_length_prefixed() {
+        let leaf = message_leaf(7, 0, "PxA", "eth", b"hi");
+        assert_ne!(leaf, message_leaf(7, 0, "PxAe", "th", b"hi"));
+        assert_ne!(leaf, message_leaf(7, 1, "PxA", "eth", b"hi"));
+        assert_ne!(leaf, message_leaf(8, 0, "PxA", "eth", b"hi"));
+        assert_eq!(leaf, message_leaf(7, 0, "PxA", "eth", b"hi"));
+    }
+}
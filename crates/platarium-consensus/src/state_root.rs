@@ -0,0 +1,190 @@
+//! State roots: the legacy snapshot root and the state commitment, a sparse Merkle tree plus a registries digest.
+//!
+//! The account tree is a binary tree of depth 256. Every account has a leaf at [`account_key`] (PLP balance,
+//! nonce, μPLP balance) and one more leaf per token it holds at [`token_key`]. A subtree holding no leaf is
+//! [`TreeNode::EMPTY`]; a subtree holding exactly one leaf is that leaf's node, wherever it sits; any other subtree
+//! hashes its two children. Leaf and inner hashes carry different prefixes, so neither can stand in for the other.
+//! The root depends only on the set of leaves, and any subtree (e.g. a state sync chunk: all keys with a given
+//! prefix) can be checked on its own against the node it should produce.
+//!
+//! The state commitment in block headers is [`state_commitment`] of that root and [`registries_digest`] of the
+//! encoded non-account state (token registry, keys, vesting, stakes, ...).
+
+use sha2::{Digest, Sha256};
+
+/// 256-bit big-endian word of the account commitment.
+pub type Word = [u8; 32];
+
+/// Snapshot state root: SHA256 over every `(address, PLP balance)` then every `(address, nonce)`, each as the
+/// address bytes followed by the little-endian integer. Both lists must be sorted by address.
+pub fn snapshot_state_root<'a>(
+    balances: impl IntoIterator<Item = (&'a str, u128)>,
+    nonces: impl IntoIterator<Item = (&'a str, u64)>,
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for (address, balance) in balances {
+        hasher.update(address.as_bytes());
+        hasher.update(balance.to_le_bytes());
+    }
+    for (address, nonce) in nonces {
+        hasher.update(address.as_bytes());
+        hasher.update(nonce.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Account leaf `SHA256("acct" || len(address) as u64 LE || address || balance LE || nonce LE || μPLP LE)`, or
+/// `None` for an all-zero account.
+pub fn account_leaf(address: &str, plp_balance: u128, nonce: u64, uplp_balance: u128) -> Option<Word> {
+    if plp_balance == 0 && nonce == 0 && uplp_balance == 0 {
+        return None;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(b"acct");
+    hasher.update((address.len() as u64).to_le_bytes());
+    hasher.update(address.as_bytes());
+    hasher.update(plp_balance.to_le_bytes());
+    hasher.update(nonce.to_le_bytes());
+    hasher.update(uplp_balance.to_le_bytes());
+    Some(hasher.finalize().into())
+}
+
+/// Token leaf `SHA256("token" || len(address) as u64 LE || address || len(asset) as u64 LE || asset || balance LE)`,
+/// or `None` for a zero balance. `asset` is the canonical asset code.
+pub fn token_leaf(address: &str, asset: &str, balance: u128) -> Option<Word> {
+    if balance == 0 {
+        return None;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(b"token");
+    hasher.update((address.len() as u64).to_le_bytes());
+    hasher.update(address.as_bytes());
+    hasher.update((asset.len() as u64).to_le_bytes());
+    hasher.update(asset.as_bytes());
+    hasher.update(balance.to_le_bytes());
+    Some(hasher.finalize().into())
+}
+
+/// Position of an account in the account tree: `SHA256("acctkey" || address)`.
+pub fn account_key(address: &str) -> Word {
+    let mut hasher = Sha256::new();
+    hasher.update(b"acctkey");
+    hasher.update(address.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Position of a token balance in the account tree: `SHA256("tokenkey" || len(address) as u64 LE || address ||
+/// asset)`.
+pub fn token_key(address: &str, asset: &str) -> Word {
+    let mut hasher = Sha256::new();
+    hasher.update(b"tokenkey");
+    hasher.update((address.len() as u64).to_le_bytes());
+    hasher.update(address.as_bytes());
+    hasher.update(asset.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Digest of the encoded non-account state: `SHA256("registries" || encoded)`.
+pub fn registries_digest(encoded: &[u8]) -> Word {
+    let mut hasher = Sha256::new();
+    hasher.update(b"registries");
+    hasher.update(encoded);
+    hasher.finalize().into()
+}
+
+/// State commitment: `SHA256("state" || account tree root || registries digest)`.
+pub fn state_commitment(accounts_root: &Word, registries: &Word) -> Word {
+    let mut hasher = Sha256::new();
+    hasher.update(b"state");
+    hasher.update(accounts_root);
+    hasher.update(registries);
+    hasher.finalize().into()
+}
+
+/// Bit `depth` (0 = most significant) of `key`: `false` descends left, `true` right.
+pub fn key_bit(key: &Word, depth: usize) -> bool {
+    key[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+/// Node of the account tree: its hash and the number of leaves below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeNode {
+    pub hash: Word,
+    pub leaves: u64,
+}
+
+impl TreeNode {
+    /// A subtree without leaves.
+    pub const EMPTY: TreeNode = TreeNode { hash: [0u8; 32], leaves: 0 };
+
+    /// Subtree holding only the leaf `leaf` at `key`: `SHA256(0x00 || key || leaf)`.
+    pub fn leaf(key: &Word, leaf: &Word) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update([0u8]);
+        hasher.update(key);
+        hasher.update(leaf);
+        Self { hash: hasher.finalize().into(), leaves: 1 }
+    }
+
+    /// Parent of two sibling subtrees. A single leaf moves up unchanged; otherwise
+    /// `SHA256(0x01 || left || right)`.
+    pub fn parent(left: &Self, right: &Self) -> Self {
+        match (left.leaves, right.leaves) {
+            (0, 0) => Self::EMPTY,
+            (1, 0) => *left,
+            (0, 1) => *right,
+            _ => {
+                let mut hasher = Sha256::new();
+                hasher.update([1u8]);
+                hasher.update(left.hash);
+                hasher.update(right.hash);
+                Self { hash: hasher.finalize().into(), leaves: left.leaves + right.leaves }
+            }
+        }
+    }
+}
+
+/// Subtree at `depth` holding `leaves` as `(key, leaf)`. Keys must be unique, sorted and share
+/// their first `depth` bits; `subtree(0, all)` is the root.
+pub fn subtree(depth: usize, leaves: &[(Word, Word)]) -> TreeNode {
+    match leaves {
+        [] => TreeNode::EMPTY,
+        [(key, leaf)] => TreeNode::leaf(key, leaf),
+        _ if depth >= 256 => TreeNode::EMPTY,
+        _ => {
+            let split = leaves.partition_point(|(key, _)| !key_bit(key, depth));
+            TreeNode::parent(&subtree(depth + 1, &leaves[..split]), &subtree(depth + 1, &leaves[split..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtrees_fold_into_the_root() {
+        let mut leaves: alloc::vec::Vec<(Word, Word)> = (0..20u64)
+            .map(|i| {
+                let address = alloc::format!("acct{}", i);
+                (account_key(&address), account_leaf(&address, 1, i, 0).unwrap())
+            })
+            .collect();
+        leaves.sort();
+        let root = subtree(0, &leaves);
+        assert_eq!(root.leaves, 20);
+        // The four depth-2 subtrees, folded pairwise, give the same root.
+        let quarters: alloc::vec::Vec<TreeNode> = (0..4u8)
+            .map(|q| {
+                let part: alloc::vec::Vec<_> = leaves.iter().copied().filter(|(k, _)| k[0] >> 6 == q).collect();
+                subtree(2, &part)
+            })
+            .collect();
+        let left = TreeNode::parent(&quarters[0], &quarters[1]);
+        let right = TreeNode::parent(&quarters[2], &quarters[3]);
+        assert_eq!(TreeNode::parent(&left, &right), root);
+        // One leaf anywhere is its own node.
+        assert_eq!(subtree(0, &leaves[..1]), TreeNode::leaf(&leaves[0].0, &leaves[0].1));
+        assert_eq!(TreeNode::parent(&TreeNode::EMPTY, &TreeNode::EMPTY), TreeNode::EMPTY);
+    }
+}
@@ -0,0 +1,50 @@
+//! Stateless transaction rules (no signatures, no state).
+
+use core::fmt;
+
+/// Minimum fee in μPLP.
+pub const MIN_FEE_UPLP: u128 = 1;
+
+/// What a transaction kind requires of `amount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountRule {
+    /// Transfers: the amount must be positive.
+    NonZero,
+    /// Administrative kinds: the amount must be 0.
+    Zero,
+    /// Any amount (approvals set an allowance, which may be 0).
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BasicTxError {
+    ZeroAmount,
+    UnexpectedAmount,
+    FeeTooLow { min: u128, got: u128 },
+}
+
+impl fmt::Display for BasicTxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BasicTxError::ZeroAmount => write!(f, "amount must be greater than 0"),
+            BasicTxError::UnexpectedAmount => write!(f, "amount must be 0"),
+            BasicTxError::FeeTooLow { min, got } => write!(f, "fee must be at least {} μPLP, got {}", min, got),
+        }
+    }
+}
+
+/// Checks `amount` against `rule` and the fee against [`MIN_FEE_UPLP`], in that order.
+pub fn check_amount_and_fee(rule: AmountRule, amount: u128, fee_uplp: u128) -> Result<(), BasicTxError> {
+    match rule {
+        AmountRule::NonZero if amount == 0 => return Err(BasicTxError::ZeroAmount),
+        AmountRule::Zero if amount != 0 => return Err(BasicTxError::UnexpectedAmount),
+        _ => {}
+    }
+    if fee_uplp < MIN_FEE_UPLP {
+        return Err(BasicTxError::FeeTooLow {
+            min: MIN_FEE_UPLP,
+            got: fee_uplp,
+        });
+    }
+    Ok(())
+}
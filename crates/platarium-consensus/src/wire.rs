@@ -0,0 +1,269 @@
+//! Canonical binary encoding.
+//!
+//! Integers are fixed width, big-endian (signed ones in two's complement). Strings and byte strings are prefixed by their length as u32 BE, a
+//! sequence by its element count as u32 BE. An `Option` is a `0` byte, or a `1` byte followed by the value; enums
+//! are a one-byte tag followed by their fields. The same value always encodes to the same bytes, independent of
+//! any serializer settings, and [`Decode::from_wire`] rejects trailing bytes.
+//!
+//! For streams, [`frame`] prefixes an encoded value with its length (u32 BE) and [`unframe`] splits one frame off.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Largest frame [`unframe`] accepts.
+pub const MAX_FRAME_LEN: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireError {
+    /// Input ended inside a value or frame.
+    UnexpectedEnd,
+    /// A string is not valid UTF-8.
+    InvalidUtf8,
+    /// Unknown enum or `Option` tag.
+    InvalidTag(u8),
+    /// Bytes left over after a complete value.
+    TrailingBytes(usize),
+    /// Frame length above [`MAX_FRAME_LEN`].
+    FrameTooLarge(usize),
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WireError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            WireError::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+            WireError::InvalidTag(tag) => write!(f, "invalid tag {}", tag),
+            WireError::TrailingBytes(n) => write!(f, "{} trailing bytes", n),
+            WireError::FrameTooLarge(n) => write!(f, "frame of {} bytes exceeds {}", n, MAX_FRAME_LEN),
+        }
+    }
+}
+
+/// Cursor over encoded bytes.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Next `n` bytes.
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], WireError> {
+        if self.bytes.len() < n {
+            return Err(WireError::UnexpectedEnd);
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, WireError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Length or count prefix.
+    pub fn read_len(&mut self) -> Result<usize, WireError> {
+        Ok(u32::decode(self)? as usize)
+    }
+
+    /// Errors unless every byte was read.
+    pub fn finish(self) -> Result<(), WireError> {
+        match self.bytes.len() {
+            0 => Ok(()),
+            n => Err(WireError::TrailingBytes(n)),
+        }
+    }
+}
+
+/// Types with a canonical binary encoding.
+pub trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+
+    fn to_wire(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+}
+
+/// Types that can be read back from [`Encode`] output.
+pub trait Decode: Sized {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError>;
+
+    /// Decodes a whole buffer; trailing bytes are an error.
+    fn from_wire(bytes: &[u8]) -> Result<Self, WireError> {
+        let mut reader = Reader::new(bytes);
+        let value = Self::decode(&mut reader)?;
+        reader.finish()?;
+        Ok(value)
+    }
+}
+
+/// Writes a length or count prefix.
+pub fn encode_len(len: usize, out: &mut Vec<u8>) {
+    (len as u32).encode(out);
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_be_bytes());
+            }
+        }
+
+        impl Decode for $t {
+            fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+                let bytes = reader.take(core::mem::size_of::<$t>())?;
+                Ok(<$t>::from_be_bytes(bytes.try_into().expect("length checked")))
+            }
+        }
+    )*};
+}
+
+impl_int!(u8, u32, u64, u128, i64);
+
+impl Encode for () {
+    fn encode(&self, _out: &mut Vec<u8>) {}
+}
+
+impl Encode for str {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_str().encode(out);
+    }
+}
+
+impl Decode for String {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let len = reader.read_len()?;
+        let bytes = reader.take(len)?;
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| WireError::InvalidUtf8)
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode(out);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let count = reader.read_len()?;
+        // Every element takes at least one byte, so a count beyond the input is malformed.
+        if count > reader.bytes.len() {
+            return Err(WireError::UnexpectedEnd);
+        }
+        (0..count).map(|_| T::decode(reader)).collect()
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        match reader.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(reader)?)),
+            tag => Err(WireError::InvalidTag(tag)),
+        }
+    }
+}
+
+/// `payload` prefixed by its length as u32 BE.
+pub fn frame(payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + payload.len());
+    encode_len(payload.len(), &mut out);
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Splits the first frame off `bytes`: returns its payload and the remaining bytes. `UnexpectedEnd` means the
+/// frame is not complete yet.
+pub fn unframe(bytes: &[u8]) -> Result<(&[u8], &[u8]), WireError> {
+    let mut reader = Reader::new(bytes);
+    let len = reader.read_len()?;
+    if len > MAX_FRAME_LEN {
+        return Err(WireError::FrameTooLarge(len));
+    }
+    let payload = reader.take(len)?;
+    Ok((payload, reader.bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_round_trip_and_layout() {
+        let value: (u64, String, Option<u32>, Vec<String>) = (7, String::from("ab"), Some(1), vec![String::from("c")]);
+        let mut out = Vec::new();
+        value.0.encode(&mut out);
+        value.1.encode(&mut out);
+        value.2.encode(&mut out);
+        value.3.encode(&mut out);
+        assert_eq!(
+            out,
+            [&[0, 0, 0, 0, 0, 0, 0, 7][..], &[0, 0, 0, 2, b'a', b'b'], &[1, 0, 0, 0, 1], &[0, 0, 0, 1, 0, 0, 0, 1, b'c']].concat()
+        );
+        let mut reader = Reader::new(&out);
+        assert_eq!(u64::decode(&mut reader), Ok(7));
+        assert_eq!(String::decode(&mut reader).unwrap(), "ab");
+        assert_eq!(Option::<u32>::decode(&mut reader), Ok(Some(1)));
+        assert_eq!(Vec::<String>::decode(&mut reader).unwrap(), value.3);
+        reader.finish().unwrap();
+
+        assert_eq!(String::from_wire(&[0, 0, 0, 1, b'a', 0]), Err(WireError::TrailingBytes(1)));
+        assert_eq!(Option::<u8>::from_wire(&[2]), Err(WireError::InvalidTag(2)));
+        assert_eq!(Vec::<u8>::from_wire(&[0xff, 0xff, 0xff, 0xff]), Err(WireError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut stream = frame(b"one");
+        stream.extend(frame(b"two"));
+        let (first, rest) = unframe(&stream).unwrap();
+        let (second, rest) = unframe(rest).unwrap();
+        assert_eq!((first, second, rest), (&b"one"[..], &b"two"[..], &[][..]));
+        assert_eq!(unframe(&stream[..5]), Err(WireError::UnexpectedEnd));
+        assert_eq!(unframe(&frame(&[0; MAX_FRAME_LEN + 1])), Err(WireError::FrameTooLarge(MAX_FRAME_LEN + 1)));
+    }
+}
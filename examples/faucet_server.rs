@@ -0,0 +1,201 @@
+//! Test network faucet: sends a fixed amount of PLP to any address, at most once per cooldown per address.
+//!
+//! Clients speak newline-delimited JSON over TCP, like the Core RPC server. A request is `{"address":"Px..."}`
+//! with a full key address, lowercase or checksummed.
+//! The reply is `{"ok":true,"hash":..,"amount":..,"fee_uplp":..,"state_root":..}` or
+//! `{"ok":false,"error":..}`, plus `retry_after_secs` when the address is still cooling down.
+//!
+//! A drip reads the faucet account's nonce (`state_query`) and the current minimum fee (`min_fee_from_load`)
+//! from the node, builds a transfer, signs it with the faucet's software signers (`sign_transaction`) and
+//! submits it with `state_apply_tx`. Drips are serialized so consecutive nonces never race.
+//!
+//! ```bash
+//! platarium-cli serve --listen 127.0.0.1:19500 &
+//! FAUCET_MNEMONIC="..." FAUCET_ALPHANUMERIC="..." \
+//!   cargo run --example faucet_server -- --node 127.0.0.1:19500 --state-file ./data/testnet-state.json
+//! # fund the address printed at startup:
+//! platarium-cli state-credit --state-file ./data/testnet-state.json --address Px... --plp 1000000000 --uplp 1000000 --testnet
+//! echo '{"address":"PxRecipient"}' | nc 127.0.0.1 19600
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use platarium_core::error::{PlatariumError, Result};
+use platarium_core::{address, rpc_call, sign_transaction, software_signers, Asset, Signer, SoftwareSigner, Transaction};
+use serde_json::{json, Value};
+
+#[derive(Parser, Debug)]
+#[command(about = "Test network faucet backed by a Core RPC node")]
+struct Args {
+    /// Core RPC endpoint (`host:port`)
+    #[arg(long, default_value = "127.0.0.1:19500")]
+    node: String,
+    /// State file the node applies transactions to
+    #[arg(long)]
+    state_file: String,
+    /// Address the faucet listens on
+    #[arg(long, default_value = "127.0.0.1:19600")]
+    listen: String,
+    /// PLP sent per drip, in minimal units
+    #[arg(long, default_value = "1000000")]
+    amount: u128,
+    /// Seconds an address must wait between drips
+    #[arg(long, default_value = "3600")]
+    cooldown_secs: u64,
+}
+
+/// Remembers when each address last received a drip.
+struct RateLimiter {
+    cooldown: Duration,
+    last_drip: HashMap<String, Instant>,
+}
+
+impl RateLimiter {
+    fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            last_drip: HashMap::new(),
+        }
+    }
+
+    /// Time left before `address` may receive another drip, if any.
+    fn remaining(&self, address: &str, now: Instant) -> Option<Duration> {
+        let last = self.last_drip.get(address)?;
+        self.cooldown.checked_sub(now.duration_since(*last)).filter(|d| !d.is_zero())
+    }
+
+    fn record(&mut self, address: &str, now: Instant) {
+        self.last_drip.retain(|_, t| now.duration_since(*t) < self.cooldown);
+        self.last_drip.insert(address.to_string(), now);
+    }
+}
+
+struct Faucet {
+    node: String,
+    state_file: String,
+    amount: u128,
+    address: String,
+    main: SoftwareSigner,
+    derived: SoftwareSigner,
+    limiter: RateLimiter,
+}
+
+impl Faucet {
+    fn drip(&mut self, to: &str) -> Result<Value> {
+        let to = &address::decode(to)?;
+        if *to == self.address {
+            return Err(PlatariumError::Validation("invalid recipient address".into()));
+        }
+        let now = Instant::now();
+        if let Some(wait) = self.limiter.remaining(to, now) {
+            return Ok(json!({
+                "ok": false,
+                "error": "address is rate limited",
+                "retry_after_secs": wait.as_secs().max(1),
+            }));
+        }
+
+        let account = rpc_call(&self.node, "state_query", json!({"state_file": self.state_file, "address": self.address}))?;
+        let nonce = account["nonce"].as_u64().ok_or_else(|| node_error("state_query returned no nonce"))?;
+        let fee = rpc_call(&self.node, "min_fee_from_load", json!({"pending_count": 0}))?;
+        let fee_uplp = fee["min_fee_uplp"].as_u64().ok_or_else(|| node_error("min_fee_from_load returned no fee"))?;
+
+        let mut tx = Transaction::new(
+            self.address.clone(),
+            to.to_string(),
+            Asset::PLP,
+            self.amount,
+            fee_uplp as u128,
+            nonce,
+            HashSet::from([self.address.clone(), to.to_string()]),
+            HashSet::from([self.address.clone(), to.to_string()]),
+            String::new(),
+            String::new(),
+        )?;
+        sign_transaction(&mut tx, &self.main, &self.derived)?;
+        let tx_json = serde_json::to_string(&tx).map_err(|e| PlatariumError::State(format!("encode tx: {}", e)))?;
+        let applied = rpc_call(&self.node, "state_apply_tx", json!({"state_file": self.state_file, "tx": tx_json}))?;
+
+        self.limiter.record(to, now);
+        eprintln!("[faucet] sent {} to {} (nonce {}, tx {})", self.amount, to, nonce, tx.hash);
+        Ok(json!({
+            "ok": true,
+            "hash": tx.hash,
+            "amount": self.amount.to_string(),
+            "fee_uplp": fee_uplp,
+            "state_root": applied["state_root"],
+        }))
+    }
+}
+
+fn node_error(msg: &str) -> PlatariumError {
+    PlatariumError::State(format!("node: {}", msg))
+}
+
+fn handle_request(faucet: &Mutex<Faucet>, line: &str) -> Value {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => return json!({"ok": false, "error": format!("invalid request: {}", e)}),
+    };
+    let Some(address) = request["address"].as_str() else {
+        return json!({"ok": false, "error": "missing address"});
+    };
+    match faucet.lock().unwrap().drip(address.trim()) {
+        Ok(v) => v,
+        Err(e) => json!({"ok": false, "error": e.to_string()}),
+    }
+}
+
+fn serve_client(faucet: Arc<Mutex<Faucet>>, stream: TcpStream) {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while matches!(reader.read_line(&mut line), Ok(n) if n > 0) {
+        if !line.trim().is_empty() {
+            let response = handle_request(&faucet, &line);
+            if writeln!(reader.get_mut(), "{}", response).is_err() {
+                break;
+            }
+        }
+        line.clear();
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let mnemonic = std::env::var("FAUCET_MNEMONIC")
+        .map_err(|_| PlatariumError::Validation("FAUCET_MNEMONIC is not set".into()))?;
+    let alphanumeric = std::env::var("FAUCET_ALPHANUMERIC")
+        .map_err(|_| PlatariumError::Validation("FAUCET_ALPHANUMERIC is not set".into()))?;
+    let (main, derived) = software_signers(&mnemonic, &alphanumeric)?;
+    let address = address::address_from_public_key(&main.public_key()?);
+
+    rpc_call(&args.node, "ping", json!({}))?;
+    let faucet = Arc::new(Mutex::new(Faucet {
+        node: args.node,
+        state_file: args.state_file,
+        amount: args.amount,
+        address: address.clone(),
+        main,
+        derived,
+        limiter: RateLimiter::new(Duration::from_secs(args.cooldown_secs)),
+    }));
+
+    let listener = TcpListener::bind(&args.listen)
+        .map_err(|e| PlatariumError::State(format!("tcp bind {}: {}", args.listen, e)))?;
+    eprintln!("[faucet] {} dripping {} PLP units from {}", args.listen, args.amount, address);
+    for stream in listener.incoming() {
+        match stream {
+            Ok(s) => {
+                let faucet = Arc::clone(&faucet);
+                std::thread::spawn(move || serve_client(faucet, s));
+            }
+            Err(e) => eprintln!("[faucet] accept error: {}", e),
+        }
+    }
+    Ok(())
+}
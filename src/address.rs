@@ -0,0 +1,289 @@
+//! Checksummed `Px` addresses.
+//!
+//! An account address is `Px` followed by the hex of the 33-byte compressed secp256k1 public key. The canonical
+//! form, used in transactions, state keys and hashes, is all lowercase. For display, [`encode`] mixes the case of
+//! the hex letters EIP-55-style: letter `i` is uppercase when nibble `i mod 64` of `SHA256(lowercase hex)` is 8
+//! or more.
+//! [`decode`] accepts either the all-lowercase form (no checksum) or a mixed-case form whose checksum matches, and
+//! returns the canonical address, so a single mistyped letter in a checksummed address is caught before funds
+//! move. Upper-casing the whole address is not accepted.
+//!
+//! [`Address::parse`] is the structured entry point; it reports the failing check as an [`AddressError`].
+
+use std::fmt;
+use std::str::FromStr;
+use secp256k1::PublicKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use crate::error::{PlatariumError, Result};
+
+/// Prefix of account addresses.
+pub const ADDRESS_PREFIX: &str = "Px";
+
+/// Hex characters after the prefix (33-byte compressed public key).
+pub const ADDRESS_HEX_LEN: usize = 66;
+
+/// Why a string is not a valid address. Positions are character indices into the whole input.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    #[error("missing {ADDRESS_PREFIX} prefix")]
+    MissingPrefix,
+
+    #[error("expected {expected} hex characters after the prefix, got {got}")]
+    InvalidLength { expected: usize, got: usize },
+
+    #[error("invalid hex character {character:?} at position {position}")]
+    InvalidHex { position: usize, character: char },
+
+    #[error("checksum mismatch (mixed-case address with a wrong letter or letter case)")]
+    ChecksumMismatch,
+
+    #[error("not a valid secp256k1 public key")]
+    InvalidPublicKey,
+
+    #[error("not in canonical lowercase form, expected {0}")]
+    NotCanonical(String),
+}
+
+impl From<AddressError> for PlatariumError {
+    fn from(err: AddressError) -> Self {
+        PlatariumError::Validation(format!("Invalid address: {}", err))
+    }
+}
+
+/// A validated key address, held in canonical (lowercase) form.
+///
+/// Not to be confused with `core::state::Address`, the plain `String` used for state keys, which also covers
+/// system and placeholder accounts; `Address::as_str` / `String::from` convert to it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Address(String);
+
+impl Address {
+    /// Parses a lowercase or checksummed address. Checks, in order: prefix, length, hex characters, checksum
+    /// (mixed case only), and that the bytes are a compressed secp256k1 public key.
+    pub fn parse(address: &str) -> std::result::Result<Self, AddressError> {
+        let body = address.strip_prefix(ADDRESS_PREFIX).ok_or(AddressError::MissingPrefix)?;
+        let got = body.chars().count();
+        if got != ADDRESS_HEX_LEN {
+            return Err(AddressError::InvalidLength {
+                expected: ADDRESS_HEX_LEN,
+                got,
+            });
+        }
+        if let Some((i, character)) = body.chars().enumerate().find(|(_, c)| !c.is_ascii_hexdigit()) {
+            return Err(AddressError::InvalidHex {
+                position: ADDRESS_PREFIX.len() + i,
+                character,
+            });
+        }
+        let lower = body.to_ascii_lowercase();
+        if body != lower && body != checksum_case(&lower) {
+            return Err(AddressError::ChecksumMismatch);
+        }
+        let bytes = hex::decode(&lower).map_err(|_| AddressError::InvalidPublicKey)?;
+        PublicKey::from_slice(&bytes).map_err(|_| AddressError::InvalidPublicKey)?;
+        Ok(Self(format!("{}{}", ADDRESS_PREFIX, lower)))
+    }
+
+    /// Address of `public_key`.
+    pub fn from_public_key(public_key: &PublicKey) -> Self {
+        Self(format!("{}{}", ADDRESS_PREFIX, hex::encode(public_key.serialize())))
+    }
+
+    /// Canonical lowercase form.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Checksummed display form.
+    pub fn checksummed(&self) -> String {
+        format!("{}{}", ADDRESS_PREFIX, checksum_case(&self.0[ADDRESS_PREFIX.len()..]))
+    }
+
+    /// The public key the address encodes.
+    pub fn public_key(&self) -> PublicKey {
+        let bytes = hex::decode(&self.0[ADDRESS_PREFIX.len()..]).expect("validated hex");
+        PublicKey::from_slice(&bytes).expect("validated public key")
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = AddressError;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Address> for String {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+impl AsRef<str> for Address {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Canonical (lowercase) address of `public_key`.
+pub fn address_from_public_key(public_key: &PublicKey) -> String {
+    Address::from_public_key(public_key).into_string()
+}
+
+/// True if `address` is meant as a key address, i.e. starts with `Px`. Such strings must pass
+/// [`Address::parse`], so a dropped, extra or mistyped character (including `-` or a space) is an error rather
+/// than a new account. System accounts (`treasury`, `burn`, ...) have no prefix and are not key addresses.
+pub fn is_key_address(address: &str) -> bool {
+    address.starts_with(ADDRESS_PREFIX)
+}
+
+/// Ok unless `address` is a key address that is invalid or not canonical. Used by `Transaction::validate_basic`
+/// and `State` before an address can receive funds.
+pub fn check(address: &str) -> std::result::Result<(), AddressError> {
+    if !is_key_address(address) {
+        return Ok(());
+    }
+    let parsed = Address::parse(address)?;
+    if parsed.as_str() != address {
+        return Err(AddressError::NotCanonical(parsed.into_string()));
+    }
+    Ok(())
+}
+
+/// Checksummed display form of `address` (canonical or already checksummed).
+pub fn encode(address: &str) -> Result<String> {
+    Ok(Address::parse(address)?.checksummed())
+}
+
+/// Validates `address` and returns its canonical lowercase form; see [`Address::parse`].
+pub fn decode(address: &str) -> Result<String> {
+    Ok(Address::parse(address)?.into_string())
+}
+
+/// Canonical form of a key address; any other address (see [`is_key_address`]) is returned unchanged.
+pub fn normalize(address: &str) -> Result<String> {
+    if is_key_address(address) {
+        decode(address)
+    } else {
+        Ok(address.to_string())
+    }
+}
+
+/// Ok if [`Address::parse`] accepts `address`.
+pub fn validate(address: &str) -> Result<()> {
+    Address::parse(address).map(|_| ()).map_err(PlatariumError::from)
+}
+
+fn checksum_case(lower_hex: &str) -> String {
+    let digest = Sha256::digest(lower_hex.as_bytes());
+    lower_hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let i = i % 64;
+            let nibble = (digest[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::{Secp256k1, SecretKey};
+
+    fn sample() -> String {
+        let secret = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        address_from_public_key(&PublicKey::from_secret_key(&Secp256k1::new(), &secret))
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let canonical = sample();
+        let checksummed = encode(&canonical).unwrap();
+        assert_ne!(checksummed, canonical);
+        assert_eq!(checksummed.to_ascii_lowercase().replace("px", "Px"), canonical);
+        assert_eq!(decode(&checksummed).unwrap(), canonical);
+        assert_eq!(decode(&canonical).unwrap(), canonical);
+        assert_eq!(encode(&checksummed).unwrap(), checksummed);
+        assert!(is_key_address(&checksummed));
+        assert!(!is_key_address("treasury"));
+        for typo in ["PxA", &canonical[..40], &format!("{}-", canonical), &format!("{} ", canonical)] {
+            assert!(is_key_address(typo));
+            assert!(check(typo).is_err(), "{typo}");
+        }
+    }
+
+    #[test]
+    fn test_parse_reports_reason() {
+        let canonical = sample();
+        let parsed = Address::parse(&canonical).unwrap();
+        assert_eq!(parsed.as_str(), canonical);
+        assert_eq!(Address::parse(&parsed.checksummed()).unwrap(), parsed);
+        assert_eq!(Address::from_public_key(&parsed.public_key()), parsed);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), format!("\"{}\"", canonical));
+
+        assert_eq!(Address::parse(&canonical[2..]), Err(AddressError::MissingPrefix));
+        assert_eq!(
+            Address::parse(&canonical[..60]),
+            Err(AddressError::InvalidLength { expected: 66, got: 58 })
+        );
+        let bad_hex = format!("{}g{}", &canonical[..10], &canonical[11..]);
+        assert_eq!(
+            Address::parse(&bad_hex),
+            Err(AddressError::InvalidHex { position: 10, character: 'g' })
+        );
+        assert!(serde_json::from_str::<Address>(&format!("\"{}\"", bad_hex)).is_err());
+
+        // Anything with the prefix must parse; unprefixed system accounts are left alone.
+        assert!(check(&canonical).is_ok());
+        assert!(check("Pxreceiver").is_err());
+        assert!(check("receiver").is_ok());
+        assert!(matches!(check(&bad_hex), Err(AddressError::InvalidHex { .. })));
+        assert!(matches!(check(&canonical[..60]), Err(AddressError::InvalidLength { .. })));
+        assert!(matches!(check(&parsed.checksummed()), Err(AddressError::NotCanonical(_))));
+    }
+
+    #[test]
+    fn test_rejects_typos() {
+        let checksummed = encode(&sample()).unwrap();
+        // Flip the case of one letter.
+        let pos = checksummed[2..].find(|c: char| c.is_ascii_alphabetic()).unwrap() + 2;
+        let mut flipped = checksummed.clone().into_bytes();
+        flipped[pos] ^= 0x20;
+        let err = decode(&String::from_utf8(flipped).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+
+        let upper = format!("Px{}", checksummed[2..].to_ascii_uppercase());
+        assert!(validate(&upper).is_err());
+        assert!(validate(&checksummed[..checksummed.len() - 1]).is_err());
+        assert!(validate(&checksummed[2..]).is_err());
+        assert!(validate(&format!("Px05{}", &sample()[4..])).is_err());
+    }
+}
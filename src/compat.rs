@@ -0,0 +1,63 @@
+//! Stable API surface for downstream node implementations.
+//!
+//! Items re-exported from [`v1`] are **consensus-critical and stable**: their names, signatures and
+//! encodings (transaction hash data, state root, Merkle root, block fields) only change with a major
+//! version of this crate, and any change is also a consensus change. Everything else in the crate is
+//! usable but may change in minor releases; items behind the `experimental` feature (enabled by default)
+//! are explicitly provisional.
+//!
+//! The enums and structs in [`v1`] (`Transaction`, `TxKind`, `TransactionValidationError`, `Asset`,
+//! `ExecutionContext`, `Block`, `Event`, `PlatariumError`) are `#[non_exhaustive]` since API version 2.0. Making
+//! them so was itself a breaking change: downstream code can no longer build these structs with literals or
+//! match the enums exhaustively, and must use their constructors and a wildcard arm. In exchange, new variants
+//! and fields are additions from then on, not breaks.
+//!
+//! `tests/public_api_test.rs` pins the signatures below, so an accidental change fails the build.
+
+/// Version of the stable surface as (major, minor). Minor bumps only add items, variants or fields.
+/// 2.0 (breaking): the v1 enums and structs are `#[non_exhaustive]`, so struct literals and exhaustive matches on
+/// them no longer compile downstream.
+pub const API_VERSION: (u32, u32) = (2, 0);
+
+/// Names of the items in [`v1`], in declaration order. Checked by the public API tests.
+pub const V1_ITEMS: &[&str] = &[
+    "Transaction",
+    "TxKind",
+    "TransactionValidationError",
+    "MIN_FEE_UPLP",
+    "Asset",
+    "State",
+    "StateSnapshot",
+    "SnapshotableState",
+    "Address",
+    "TREASURY_ADDRESS",
+    "ExecutionLogic",
+    "ExecutionContext",
+    "Mempool",
+    "Block",
+    "compute_merkle_root",
+    "assemble_block",
+    "Event",
+    "by_arrival_then_hash",
+    "by_node_id",
+    "by_fee_then_hash",
+    "hash_message",
+    "sign_message",
+    "verify_signature",
+    "PlatariumError",
+    "Result",
+];
+
+/// Stable surface, version 1.
+pub mod v1 {
+    pub use crate::core::transaction::{Transaction, TxKind, TransactionValidationError, MIN_FEE_UPLP};
+    pub use crate::core::asset::Asset;
+    pub use crate::core::state::{State, StateSnapshot, SnapshotableState, Address, TREASURY_ADDRESS};
+    pub use crate::core::execution::{ExecutionLogic, ExecutionContext};
+    pub use crate::core::mempool::Mempool;
+    pub use crate::core::block_assembly::{Block, compute_merkle_root, assemble_block};
+    pub use crate::core::events::Event;
+    pub use crate::core::ordering::{by_arrival_then_hash, by_node_id, by_fee_then_hash};
+    pub use crate::signature::{hash_message, sign_message, verify_signature};
+    pub use crate::error::{PlatariumError, Result};
+}
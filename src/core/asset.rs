@@ -1,10 +1,12 @@
 //! Asset and currency model for multi-asset transactions. PLP is the base network currency. Fee is always in μPLP and is separate from the transaction asset.
 
+use platarium_consensus::wire::{Decode, Encode, Reader, WireError};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Asset identifier for the transaction amount. Fee is always μPLP and is not represented as an `Asset`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum Asset {
     /// Base network currency (Platarium). Amount in minimal units; 1 PLP = 1_000_000 μPLP.
     PLP,
@@ -20,6 +22,15 @@ impl Asset {
             Asset::Token(s) => format!("Token:{}", s),
         }
     }
+
+    /// Inverse of [`as_canonical`](Self::as_canonical); a string without the `Token:` prefix is read as a token
+    /// name.
+    pub fn from_canonical(s: &str) -> Self {
+        if s == "PLP" {
+            return Asset::PLP;
+        }
+        Asset::Token(s.strip_prefix("Token:").unwrap_or(s).to_string())
+    }
 }
 
 impl fmt::Display for Asset {
@@ -28,6 +39,29 @@ impl fmt::Display for Asset {
     }
 }
 
+/// Tag `0` for PLP, `1` followed by the token name.
+impl Encode for Asset {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Asset::PLP => out.push(0),
+            Asset::Token(name) => {
+                out.push(1);
+                name.encode(out);
+            }
+        }
+    }
+}
+
+impl Decode for Asset {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        match reader.u8()? {
+            0 => Ok(Asset::PLP),
+            1 => Ok(Asset::Token(String::decode(reader)?)),
+            tag => Err(WireError::InvalidTag(tag)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,6 +74,8 @@ mod tests {
     #[test]
     fn test_asset_token_canonical() {
         assert_eq!(Asset::Token("USDT".to_string()).as_canonical(), "Token:USDT");
+        assert_eq!(Asset::from_canonical("Token:USDT"), Asset::Token("USDT".to_string()));
+        assert_eq!(Asset::from_canonical("PLP"), Asset::PLP);
     }
 
     #[test]
@@ -0,0 +1,292 @@
+//! Token registry: issuer authority, per-token freeze flags and total supply.
+//!
+//! Every `Asset::Token` may be registered with an issuer address. Only the issuer can mint the token (`TxKind::Mint`) and freeze (pause) or unfreeze transfers of it; while frozen, `State::apply_transfer` rejects any transfer of that token. PLP cannot be registered or frozen.
+//!
+//! A registered token's supply starts at zero, grows with every mint (including bridge claims) and shrinks with every `TxKind::Burn`; `State::check_supply` compares it with the balances held.
+//!
+//! A token registered with `register_wrapped` is a wrapped asset: its issuer is `bridge::BRIDGE_ADDRESS`, it names its source chain, and it is minted only by verified `BridgeClaim` transactions. The registry also holds the bridge's attested foreign headers and minted claim ids (`BridgeLedger`).
+//!
+//! Entries are kept in a `BTreeMap` keyed by the asset's canonical string, so iteration order is deterministic.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::asset::Asset;
+use crate::core::bridge::{BridgeLedger, BRIDGE_ADDRESS};
+use crate::core::state::Address;
+use crate::error::PlatariumError;
+
+/// Errors produced by asset registry operations.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AssetRegistryError {
+    #[error("PLP is the native asset and has no issuer")]
+    NativeAsset,
+
+    #[error("Asset not registered: {0}")]
+    UnknownAsset(String),
+
+    #[error("Asset already registered: {0}")]
+    AlreadyRegistered(String),
+
+    #[error("Sender {sender} is not the issuer of {asset}")]
+    NotIssuer { asset: String, sender: Address },
+
+    #[error("Asset is frozen: {0}")]
+    Frozen(String),
+
+    #[error("Wrapped asset {0} is minted only by bridge claims")]
+    Wrapped(String),
+
+    #[error("Minting {amount} would overflow the supply of {asset}")]
+    SupplyOverflow { asset: String, amount: u128 },
+
+    #[error("Burning {amount} exceeds the supply {supply} of {asset}")]
+    BurnExceedsSupply { asset: String, amount: u128, supply: u128 },
+}
+
+impl From<AssetRegistryError> for PlatariumError {
+    fn from(err: AssetRegistryError) -> Self {
+        PlatariumError::State(format!("Asset registry error: {}", err))
+    }
+}
+
+/// Registry entry for one token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetInfo {
+    /// Address allowed to administer the token.
+    pub issuer: Address,
+    /// When true, transfers of the token are rejected.
+    pub frozen: bool,
+    /// Source chain of a wrapped asset; only bridge claims from it mint the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bridge_chain: Option<String>,
+    /// Minted minus burned, in minimal units.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub supply: u128,
+}
+
+fn is_zero(n: &u128) -> bool {
+    *n == 0
+}
+
+/// Registered tokens keyed by canonical asset string.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetRegistry {
+    assets: BTreeMap<String, AssetInfo>,
+    #[serde(default)]
+    bridge: BridgeLedger,
+}
+
+impl AssetRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `asset` with `issuer`. Errors for PLP or if the token is already registered.
+    pub fn register(&mut self, asset: &Asset, issuer: Address) -> Result<(), AssetRegistryError> {
+        if *asset == Asset::PLP {
+            return Err(AssetRegistryError::NativeAsset);
+        }
+        let key = asset.as_canonical();
+        if self.assets.contains_key(&key) {
+            return Err(AssetRegistryError::AlreadyRegistered(key));
+        }
+        self.assets.insert(
+            key,
+            AssetInfo {
+                issuer,
+                frozen: false,
+                bridge_chain: None,
+                supply: 0,
+            },
+        );
+        Ok(())
+    }
+
+    /// Registers `asset` as a wrapped asset of `source_chain`, issued by [`BRIDGE_ADDRESS`].
+    pub fn register_wrapped(&mut self, asset: &Asset, source_chain: &str) -> Result<(), AssetRegistryError> {
+        self.register(asset, BRIDGE_ADDRESS.to_string())?;
+        if let Some(info) = self.assets.get_mut(&asset.as_canonical()) {
+            info.bridge_chain = Some(source_chain.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn bridge(&self) -> &BridgeLedger {
+        &self.bridge
+    }
+
+    pub fn bridge_mut(&mut self) -> &mut BridgeLedger {
+        &mut self.bridge
+    }
+
+    pub fn get(&self, asset: &Asset) -> Option<&AssetInfo> {
+        self.assets.get(&asset.as_canonical())
+    }
+
+    pub fn issuer(&self, asset: &Asset) -> Option<&Address> {
+        self.get(asset).map(|i| &i.issuer)
+    }
+
+    /// True if `asset` is registered and frozen. Unregistered tokens and PLP are never frozen.
+    pub fn is_frozen(&self, asset: &Asset) -> bool {
+        self.get(asset).map(|i| i.frozen).unwrap_or(false)
+    }
+
+    /// Checks that `sender` is the issuer of `asset`.
+    pub fn check_issuer(&self, asset: &Asset, sender: &Address) -> Result<(), AssetRegistryError> {
+        if *asset == Asset::PLP {
+            return Err(AssetRegistryError::NativeAsset);
+        }
+        let info = self
+            .get(asset)
+            .ok_or_else(|| AssetRegistryError::UnknownAsset(asset.as_canonical()))?;
+        if info.issuer != *sender {
+            return Err(AssetRegistryError::NotIssuer {
+                asset: asset.as_canonical(),
+                sender: sender.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Total supply of a registered token; `None` for PLP and unregistered tokens.
+    pub fn supply(&self, asset: &Asset) -> Option<u128> {
+        self.get(asset).map(|i| i.supply)
+    }
+
+    /// Checks that `sender` may mint `amount` of `asset`: it is the issuer, the token is neither wrapped nor frozen,
+    /// and the supply does not overflow.
+    pub fn check_mint(&self, asset: &Asset, sender: &Address, amount: u128) -> Result<(), AssetRegistryError> {
+        self.check_issuer(asset, sender)?;
+        let key = asset.as_canonical();
+        match self.assets.get(&key) {
+            Some(info) if info.bridge_chain.is_some() => Err(AssetRegistryError::Wrapped(key)),
+            Some(info) if info.frozen => Err(AssetRegistryError::Frozen(key)),
+            _ => self.check_supply_add(asset, amount),
+        }
+    }
+
+    /// Checks that `amount` more of `asset` fits in its supply. Unregistered assets have no tracked supply.
+    pub fn check_supply_add(&self, asset: &Asset, amount: u128) -> Result<(), AssetRegistryError> {
+        match self.supply(asset) {
+            Some(supply) if supply.checked_add(amount).is_none() => Err(AssetRegistryError::SupplyOverflow {
+                asset: asset.as_canonical(),
+                amount,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that burning `amount` of a registered token leaves a non-negative supply.
+    pub fn check_burn(&self, asset: &Asset, amount: u128) -> Result<(), AssetRegistryError> {
+        match self.supply(asset) {
+            Some(supply) if amount > supply => Err(AssetRegistryError::BurnExceedsSupply {
+                asset: asset.as_canonical(),
+                amount,
+                supply,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records a mint of `amount`; a no-op for unregistered assets.
+    pub fn add_supply(&mut self, asset: &Asset, amount: u128) -> Result<(), AssetRegistryError> {
+        self.check_supply_add(asset, amount)?;
+        if let Some(info) = self.assets.get_mut(&asset.as_canonical()) {
+            info.supply += amount;
+        }
+        Ok(())
+    }
+
+    /// Records a burn of `amount`; a no-op for unregistered assets.
+    pub fn remove_supply(&mut self, asset: &Asset, amount: u128) -> Result<(), AssetRegistryError> {
+        self.check_burn(asset, amount)?;
+        if let Some(info) = self.assets.get_mut(&asset.as_canonical()) {
+            info.supply -= amount;
+        }
+        Ok(())
+    }
+
+    /// Sets the freeze flag; only the issuer may do so. Setting the current value again is allowed (idempotent).
+    pub fn set_frozen(
+        &mut self,
+        asset: &Asset,
+        sender: &Address,
+        frozen: bool,
+    ) -> Result<(), AssetRegistryError> {
+        self.check_issuer(asset, sender)?;
+        if let Some(info) = self.assets.get_mut(&asset.as_canonical()) {
+            info.frozen = frozen;
+        }
+        Ok(())
+    }
+
+    /// All registered tokens, sorted by canonical asset string.
+    pub fn entries(&self) -> Vec<(String, AssetInfo)> {
+        self.assets.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usdt() -> Asset {
+        Asset::Token("USDT".to_string())
+    }
+
+    #[test]
+    fn test_register_and_issuer() {
+        let mut reg = AssetRegistry::new();
+        reg.register(&usdt(), "issuer".to_string()).unwrap();
+        assert_eq!(reg.issuer(&usdt()), Some(&"issuer".to_string()));
+        assert_eq!(
+            reg.register(&usdt(), "other".to_string()),
+            Err(AssetRegistryError::AlreadyRegistered("Token:USDT".to_string()))
+        );
+        assert_eq!(reg.register(&Asset::PLP, "x".to_string()), Err(AssetRegistryError::NativeAsset));
+    }
+
+    #[test]
+    fn test_only_issuer_can_freeze() {
+        let mut reg = AssetRegistry::new();
+        reg.register(&usdt(), "issuer".to_string()).unwrap();
+        assert!(matches!(
+            reg.set_frozen(&usdt(), &"mallory".to_string(), true),
+            Err(AssetRegistryError::NotIssuer { .. })
+        ));
+        assert!(!reg.is_frozen(&usdt()));
+        reg.set_frozen(&usdt(), &"issuer".to_string(), true).unwrap();
+        assert!(reg.is_frozen(&usdt()));
+        reg.set_frozen(&usdt(), &"issuer".to_string(), false).unwrap();
+        assert!(!reg.is_frozen(&usdt()));
+    }
+
+    #[test]
+    fn test_supply_tracking() {
+        let mut reg = AssetRegistry::new();
+        reg.register(&usdt(), "issuer".to_string()).unwrap();
+        assert_eq!(reg.supply(&usdt()), Some(0));
+        assert_eq!(reg.supply(&Asset::PLP), None);
+        reg.add_supply(&usdt(), 100).unwrap();
+        assert!(matches!(
+            reg.check_mint(&usdt(), &"issuer".to_string(), u128::MAX),
+            Err(AssetRegistryError::SupplyOverflow { .. })
+        ));
+        reg.remove_supply(&usdt(), 40).unwrap();
+        assert_eq!(
+            reg.remove_supply(&usdt(), 61),
+            Err(AssetRegistryError::BurnExceedsSupply { asset: "Token:USDT".to_string(), amount: 61, supply: 60 })
+        );
+        assert_eq!(reg.supply(&usdt()), Some(60));
+    }
+}
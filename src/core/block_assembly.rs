@@ -15,8 +15,11 @@
 //! Block structure includes Merkle root, state root, block hash, and producer signature.
 
 use sha2::{Sha256, Digest};
+use platarium_consensus::{block_signing_payload, push_messages_root};
 use crate::core::node_registry::{NodeId, NodeRegistry};
+use crate::core::outbound::{messages_root, OutboundMessage};
 use crate::core::state::StateSnapshot;
+use crate::core::transaction::Transaction;
 use crate::error::{PlatariumError, Result};
 use thiserror::Error;
 
@@ -64,7 +67,10 @@ impl From<BlockAssemblyError> for PlatariumError {
 }
 
 /// Block header and producer metadata. Transaction set is represented by hashes for Merkle root computation.
-#[derive(Debug, Clone)]
+///
+/// **Stability:** consensus-critical, stable (`compat::v1`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct Block {
     pub block_number: u64,
     pub previous_hash: String,
@@ -75,41 +81,18 @@ pub struct Block {
     pub block_hash: String,
     pub producer_id: NodeId,
     pub producer_sig: String,
+    /// Merkle root of the block's outbound messages (`core::outbound`); `None` for a block without messages.
+    pub messages_root: Option<String>,
 }
 
 /// Computes the Merkle root from transaction hashes. Empty list yields "0". Deterministic: hashes are sorted before hashing.
 pub fn compute_merkle_root(tx_hashes: &[String]) -> String {
-    if tx_hashes.is_empty() {
-        return "0".to_string();
-    }
-    let mut layer: Vec<[u8; 32]> = tx_hashes
+    let layer: Vec<[u8; 32]> = tx_hashes
         .iter()
         .filter_map(|h| hex::decode(h).ok())
         .filter_map(|v| v.try_into().ok())
         .collect();
-    if layer.is_empty() {
-        return "0".to_string();
-    }
-    layer.sort_by(|a, b| a.cmp(b));
-    while layer.len() > 1 {
-        let mut next = Vec::with_capacity((layer.len() + 1) / 2);
-        for chunk in layer.chunks(2) {
-            let mut hasher = Sha256::new();
-            hasher.update(&chunk[0]);
-            if chunk.len() == 2 {
-                hasher.update(&chunk[1]);
-            } else {
-                hasher.update(&chunk[0]);
-            }
-            let bytes = hasher.finalize();
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(&bytes);
-            next.push(arr);
-        }
-        next.sort_by(|a, b| a.cmp(b));
-        layer = next;
-    }
-    hex::encode(layer[0])
+    platarium_consensus::merkle_root(layer).map_or_else(|| "0".to_string(), hex::encode)
 }
 
 /// Computes the block hash from header fields (excluding producer signature). Deterministic.
@@ -120,15 +103,58 @@ fn compute_block_hash_inner(
     merkle_root: &str,
     state_root: &str,
     producer_id: &str,
+    messages_root: Option<&str>,
 ) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(block_number.to_le_bytes());
-    hasher.update(previous_hash.as_bytes());
-    hasher.update(timestamp.to_le_bytes());
-    hasher.update(merkle_root.as_bytes());
-    hasher.update(state_root.as_bytes());
-    hasher.update(producer_id.as_bytes());
-    hex::encode(hasher.finalize())
+    hex::encode(Sha256::digest(signing_payload_inner(
+        block_number,
+        previous_hash,
+        timestamp,
+        merkle_root,
+        state_root,
+        producer_id,
+        messages_root,
+    )))
+}
+
+/// Block signing payload; the messages root is appended only when present, so blocks without messages hash as before.
+fn signing_payload_inner(
+    block_number: u64,
+    previous_hash: &str,
+    timestamp: i64,
+    merkle_root: &str,
+    state_root: &str,
+    producer_id: &str,
+    messages_root: Option<&str>,
+) -> Vec<u8> {
+    let mut payload =
+        block_signing_payload(block_number, previous_hash, timestamp, merkle_root, state_root, producer_id);
+    if let Some(root) = messages_root {
+        push_messages_root(&mut payload, root);
+    }
+    payload
+}
+
+impl Block {
+    /// Exact bytes whose SHA256 is `block_hash`; the producer signs that hash. For auditors and external signers.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        signing_payload_inner(
+            self.block_number,
+            &self.previous_hash,
+            self.timestamp,
+            &self.merkle_root,
+            &self.state_root,
+            &self.producer_id,
+            self.messages_root.as_deref(),
+        )
+    }
+
+    /// Commits the block to its outbound `messages` (sealed at this height) and recomputes `block_hash`. Call
+    /// before the producer signs.
+    pub fn with_messages(mut self, messages: &[OutboundMessage]) -> Self {
+        self.messages_root = messages_root(messages);
+        self.block_hash = hex::encode(Sha256::digest(self.signing_payload()));
+        self
+    }
 }
 
 /// Returns the dynamic maximum number of transactions per block from mempool size, average TPS, and load.
@@ -156,6 +182,104 @@ pub fn max_block_size_bytes(_mempool_size: usize, _avg_tps: u64, network_load_pc
     }
 }
 
+/// True if `tx` fits a block that already holds `used_bytes` of transaction weight under a `max_bytes` cap (e.g.
+/// [`max_block_size_bytes`]). See `Transaction::weight`.
+pub fn fits_block(tx: &Transaction, used_bytes: u64, max_bytes: u64) -> bool {
+    used_bytes.saturating_add(tx.weight()) <= max_bytes
+}
+
+/// Lower bound for the adaptive block size target (bytes).
+pub const BLOCK_TARGET_MIN_BYTES: u64 = DEFAULT_MAX_BLOCK_SIZE / 4;
+/// Upper bound for the adaptive block size target (bytes).
+pub const BLOCK_TARGET_MAX_BYTES: u64 = DEFAULT_MAX_BLOCK_SIZE * 4;
+/// Desired average block fullness (percent of the target).
+pub const BLOCK_FULLNESS_TARGET_PCT: u64 = 50;
+/// Maximum target change per block: 1/8 (12.5%) of the current target.
+pub const BLOCK_TARGET_MAX_STEP_DENOM: u64 = 8;
+/// Number of recent blocks whose fullness is averaged.
+pub const BLOCK_FULLNESS_WINDOW: usize = 16;
+/// Committed blocks replayed to derive the target (`storage::block_size_target`). At the maximum step the target
+/// crosses the whole `[BLOCK_TARGET_MIN_BYTES, BLOCK_TARGET_MAX_BYTES]` range in 24 blocks.
+pub const BLOCK_TARGET_REPLAY_BLOCKS: u64 = 64;
+
+/// Feedback controller for the per-block size target.
+///
+/// After each block, `record_block(used_bytes)` updates the moving average of block fullness over the last `BLOCK_FULLNESS_WINDOW` blocks and moves the target towards keeping blocks `BLOCK_FULLNESS_TARGET_PCT` full: fuller blocks grow the target, emptier blocks shrink it. The step is proportional to the deviation, at most `target / BLOCK_TARGET_MAX_STEP_DENOM` per block, and the target stays within `[BLOCK_TARGET_MIN_BYTES, BLOCK_TARGET_MAX_BYTES]`. Integer arithmetic only; same block history yields the same target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockSizeController {
+    target_bytes: u64,
+    /// Fullness of recent blocks in percent of the target at the time (oldest first), capped at 100.
+    recent_fullness_pct: Vec<u64>,
+}
+
+impl BlockSizeController {
+    /// Starts at `DEFAULT_MAX_BLOCK_SIZE` with no history.
+    pub fn new() -> Self {
+        Self::with_target(DEFAULT_MAX_BLOCK_SIZE)
+    }
+
+    /// Starts at `target_bytes` (clamped to the protocol bounds).
+    pub fn with_target(target_bytes: u64) -> Self {
+        Self {
+            target_bytes: target_bytes.clamp(BLOCK_TARGET_MIN_BYTES, BLOCK_TARGET_MAX_BYTES),
+            recent_fullness_pct: Vec::with_capacity(BLOCK_FULLNESS_WINDOW),
+        }
+    }
+
+    /// Replays a block history (encoded sizes in bytes, oldest first) from the default target.
+    pub fn from_history(block_sizes: &[u64]) -> Self {
+        let mut c = Self::new();
+        for &used in block_sizes {
+            c.record_block(used);
+        }
+        c
+    }
+
+    /// Current target size for the next block (bytes).
+    pub fn target_bytes(&self) -> u64 {
+        self.target_bytes
+    }
+
+    /// Average fullness (percent) over the window; `BLOCK_FULLNESS_TARGET_PCT` when there is no history.
+    pub fn avg_fullness_pct(&self) -> u64 {
+        if self.recent_fullness_pct.is_empty() {
+            return BLOCK_FULLNESS_TARGET_PCT;
+        }
+        self.recent_fullness_pct.iter().sum::<u64>() / self.recent_fullness_pct.len() as u64
+    }
+
+    /// Records a produced block of `used_bytes` and returns the new target.
+    pub fn record_block(&mut self, used_bytes: u64) -> u64 {
+        let fullness = (used_bytes.saturating_mul(100) / self.target_bytes).min(100);
+        if self.recent_fullness_pct.len() == BLOCK_FULLNESS_WINDOW {
+            self.recent_fullness_pct.remove(0);
+        }
+        self.recent_fullness_pct.push(fullness);
+
+        let avg = self.avg_fullness_pct();
+        let max_step = (self.target_bytes / BLOCK_TARGET_MAX_STEP_DENOM).max(1);
+        if avg > BLOCK_FULLNESS_TARGET_PCT {
+            let dev = avg - BLOCK_FULLNESS_TARGET_PCT;
+            let step = (max_step * dev / (100 - BLOCK_FULLNESS_TARGET_PCT)).max(1);
+            self.target_bytes = self.target_bytes.saturating_add(step);
+        } else if avg < BLOCK_FULLNESS_TARGET_PCT {
+            let dev = BLOCK_FULLNESS_TARGET_PCT - avg;
+            let step = (max_step * dev / BLOCK_FULLNESS_TARGET_PCT).max(1);
+            self.target_bytes = self.target_bytes.saturating_sub(step);
+        }
+        self.target_bytes = self
+            .target_bytes
+            .clamp(BLOCK_TARGET_MIN_BYTES, BLOCK_TARGET_MAX_BYTES);
+        self.target_bytes
+    }
+}
+
+impl Default for BlockSizeController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Returns the dynamic maximum block time in seconds (2–5 s). Higher load yields a shorter window.
 pub fn max_block_time_sec(network_load_pct: u64) -> u64 {
     if network_load_pct >= 80 {
@@ -167,7 +291,7 @@ pub fn max_block_time_sec(network_load_pct: u64) -> u64 {
     }
 }
 
-/// Assembles a block with Merkle root, state root, and block hash. The producer must sign the block hash externally and set `producer_sig`.
+/// Assembles a block with Merkle root, state root (the snapshot's state commitment), and block hash. The producer must sign the block hash externally and set `producer_sig`.
 pub fn assemble_block(
     block_number: u64,
     previous_hash: String,
@@ -178,7 +302,7 @@ pub fn assemble_block(
     producer_sig: String,
 ) -> Block {
     let merkle_root = compute_merkle_root(&transaction_hashes);
-    let state_root = state_snapshot.compute_state_root();
+    let state_root = state_snapshot.commitment_root();
     let block_hash = compute_block_hash_inner(
         block_number,
         &previous_hash,
@@ -186,6 +310,7 @@ pub fn assemble_block(
         &merkle_root,
         &state_root,
         &producer_id,
+        None,
     );
     Block {
         block_number,
@@ -197,6 +322,7 @@ pub fn assemble_block(
         block_hash,
         producer_id,
         producer_sig,
+        messages_root: None,
     }
 }
 
@@ -275,11 +401,42 @@ mod tests {
 
     #[test]
     fn test_block_hash_deterministic() {
-        let h1 = compute_block_hash_inner(1, "prev", 1000, "merkle", "state", "producer");
-        let h2 = compute_block_hash_inner(1, "prev", 1000, "merkle", "state", "producer");
+        let h1 = compute_block_hash_inner(1, "prev", 1000, "merkle", "state", "producer", None);
+        let h2 = compute_block_hash_inner(1, "prev", 1000, "merkle", "state", "producer", None);
         assert_eq!(h1, h2);
     }
 
+    #[test]
+    fn test_block_signing_payload_is_hash_preimage() {
+        let block = assemble_block(3, "prev".into(), 1000, vec![], &crate::core::state::State::new().snapshot(), "producer".into(), String::new());
+        assert_eq!(hex::encode(Sha256::digest(block.signing_payload())), block.block_hash);
+    }
+
+    #[test]
+    fn test_state_root_is_the_state_commitment() {
+        let state = crate::core::state::State::new();
+        state.set_balance(&"alice".to_string(), 5);
+        state.set_uplp_balance(&"bob".to_string(), 7);
+        state.set_asset_balance(&"bob".to_string(), &crate::core::asset::Asset::Token("USDT".into()), 3);
+        let block = assemble_block(1, "prev".into(), 1000, vec![], &state.snapshot(), "producer".into(), String::new());
+        assert_eq!(block.state_root, state.current_root());
+    }
+
+    #[test]
+    fn test_messages_root_is_hashed_only_when_present() {
+        let block = assemble_block(3, "prev".into(), 1000, vec![], &crate::core::state::State::new().snapshot(), "producer".into(), String::new());
+        let unchanged = block.clone().with_messages(&[]);
+        assert_eq!(unchanged.block_hash, block.block_hash);
+        assert_eq!(unchanged.messages_root, None);
+
+        let mut queue = crate::core::outbound::OutboundQueue::new();
+        queue.push("PxA".into(), "eth:bridge".into(), "01").unwrap();
+        let with = block.clone().with_messages(&queue.seal(3));
+        assert!(with.messages_root.is_some());
+        assert_ne!(with.block_hash, block.block_hash);
+        assert_eq!(hex::encode(Sha256::digest(with.signing_payload())), with.block_hash);
+    }
+
     #[test]
     fn test_l2_threshold_70() {
         let votes: Vec<(NodeId, Vote)> = (0..10)
@@ -327,4 +484,33 @@ mod tests {
         assert_eq!(block_leader_index_for_height(3, 3), 0);
         assert_eq!(block_leader_index_for_height(5, 3), 2);
     }
+
+    #[test]
+    fn test_block_size_controller_feedback() {
+        let mut c = BlockSizeController::new();
+        let start = c.target_bytes();
+        // Full blocks grow the target by at most 1/8 per block.
+        let next = c.record_block(u64::MAX);
+        assert!(next > start && next <= start + start / BLOCK_TARGET_MAX_STEP_DENOM);
+        for _ in 0..200 {
+            c.record_block(u64::MAX);
+        }
+        assert_eq!(c.target_bytes(), BLOCK_TARGET_MAX_BYTES);
+        // Empty blocks shrink it down to the floor.
+        for _ in 0..400 {
+            c.record_block(0);
+        }
+        assert_eq!(c.target_bytes(), BLOCK_TARGET_MIN_BYTES);
+        // Half-full blocks keep it stable.
+        let mut c = BlockSizeController::new();
+        for _ in 0..BLOCK_FULLNESS_WINDOW * 2 {
+            let half = c.target_bytes() / 2;
+            c.record_block(half);
+        }
+        assert_eq!(c.target_bytes(), DEFAULT_MAX_BLOCK_SIZE);
+        assert_eq!(
+            BlockSizeController::from_history(&[1, 2, 3]),
+            BlockSizeController::from_history(&[1, 2, 3])
+        );
+    }
 }
@@ -6,9 +6,12 @@ use crate::core::consensus_params::{
     BLOCK_MIN_TX_COUNT, FAUCET_ADDRESS, MEMPOOL_MAX_NONCE_GAP,
 };
 use crate::core::execution::ExecutionLogic;
-use crate::core::fee::calculate_fee_from_load;
-use crate::core::state::State;
-use crate::core::transaction::Transaction;
+use crate::core::ordering;
+use crate::core::invariants::invariant_monitor;
+use crate::core::load::NetworkLoad;
+use crate::core::overlay::OverlayState;
+use crate::core::state::{State, StateSnapshot};
+use crate::core::transaction::{Transaction, TX_FRAME_PREFIX_BYTES};
 use crate::error::{PlatariumError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -61,6 +64,13 @@ impl SnapshotTxFields {
         Transaction::from_gateway_json(&json)
     }
 
+    /// Block weight in bytes (`Transaction::weight`); the framed JSON size if the entry does not parse.
+    pub fn weight(&self) -> u64 {
+        self.to_transaction().map(|tx| tx.weight()).unwrap_or_else(|_| {
+            serde_json::to_vec(self).map(|v| v.len() as u64).unwrap_or(0) + TX_FRAME_PREFIX_BYTES
+        })
+    }
+
     pub fn fee_uplp_u64(&self) -> u64 {
         parse_u64_value(&self.fee_uplp)
             .or_else(|| self.fee.as_ref().and_then(|s| s.parse().ok()))
@@ -94,9 +104,7 @@ pub fn parse_mempool_snapshot(json: &str) -> Result<Vec<MempoolSnapshotEntry>> {
             timestamp,
         });
     }
-    out.sort_by(|a, b| {
-        (a.arrival_index, a.tx.hash.as_str()).cmp(&(b.arrival_index, b.tx.hash.as_str()))
-    });
+    out.sort_by(ordering::by_arrival_then_hash);
     Ok(out)
 }
 
@@ -113,10 +121,14 @@ pub struct MempoolAdmitResult {
 pub struct BlockProposalStatus {
     pub should_propose: bool,
     pub mempool_count: usize,
+    /// Encoded size of the snapshot's transactions in bytes.
+    pub mempool_bytes: u64,
     pub mempool_gas_uplp: u64,
     pub block_gas_cap_uplp: u64,
     pub min_fee_uplp: u64,
     pub oldest_mempool_wait_sec: i64,
+    /// Set after an invariant violation under `InvariantPolicy::HaltBlockProduction`; `should_propose` is then false.
+    pub production_halted: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -125,10 +137,15 @@ pub struct SelectBlockTxsResult {
     pub gas_used: u64,
     pub gas_cap: u64,
     pub tx_count: usize,
+    /// Total weight of the selected transactions, in bytes.
+    pub bytes_used: u64,
+    /// Block size cap the selection was made under: this mempool's load (`NetworkLoad::max_block_size_bytes`), or
+    /// the cap passed to the `_with` variants, e.g. the stored size target (`storage::block_size_target`).
+    pub byte_cap: u64,
 }
 
 pub fn min_fee_from_load_json(pending_count: usize) -> Result<String> {
-    let fee = calculate_fee_from_load(pending_count);
+    let fee = NetworkLoad::from_pending(pending_count).min_fee_uplp();
     Ok(serde_json::json!({ "min_fee_uplp": fee }).to_string())
 }
 
@@ -137,7 +154,7 @@ pub fn mempool_admit(
     tx_json: &str,
     mempool: &[MempoolSnapshotEntry],
 ) -> MempoolAdmitResult {
-    let min_fee = calculate_fee_from_load(mempool.len());
+    let min_fee = NetworkLoad::from_pending(mempool.len()).min_fee_uplp();
     let tx = match Transaction::from_gateway_json(tx_json) {
         Ok(t) => t,
         Err(e) => {
@@ -243,7 +260,7 @@ pub fn mempool_admit(
     }
 
     if tx.nonce == chain_nonce {
-        if let Err(e) = ExecutionLogic::validate_transaction(&tx)
+        if let Err(e) = ExecutionLogic::validate_transaction_on(state, &tx)
             .and_then(|_| ExecutionLogic::check_transaction_applicability(state, &tx))
         {
             return MempoolAdmitResult {
@@ -253,7 +270,7 @@ pub fn mempool_admit(
                 expected_nonce: expected,
             };
         }
-    } else if let Err(e) = ExecutionLogic::validate_transaction(&tx) {
+    } else if let Err(e) = ExecutionLogic::validate_transaction_on(state, &tx) {
         return MempoolAdmitResult {
             accepted: false,
             error: Some(e.to_string()),
@@ -273,9 +290,10 @@ pub fn mempool_admit(
 pub fn block_proposal_status(mempool: &[MempoolSnapshotEntry], now_unix: i64) -> BlockProposalStatus {
     let count = mempool.len();
     let gas = sum_fee_uplp(mempool);
-    let min_fee = calculate_fee_from_load(count);
+    let min_fee = NetworkLoad::from_pending(count).min_fee_uplp();
     let oldest_wait = oldest_wait_sec(mempool, now_unix);
-    let should = if count == 0 {
+    let halted = invariant_monitor().block_production_halted();
+    let should = if count == 0 || halted {
         false
     } else if count >= BLOCK_MIN_TX_COUNT && gas >= BLOCK_MIN_GAS_UPLP {
         true
@@ -285,14 +303,21 @@ pub fn block_proposal_status(mempool: &[MempoolSnapshotEntry], now_unix: i64) ->
     BlockProposalStatus {
         should_propose: should,
         mempool_count: count,
+        mempool_bytes: sum_encoded_bytes(mempool),
         mempool_gas_uplp: gas,
         block_gas_cap_uplp: BLOCK_GAS_CAP_UPLP,
         min_fee_uplp: min_fee,
         oldest_mempool_wait_sec: oldest_wait,
+        production_halted: halted,
     }
 }
 
 pub fn select_block_txs(state: &State, mempool: &[MempoolSnapshotEntry]) -> SelectBlockTxsResult {
+    select_block_txs_with(state, mempool, NetworkLoad::from_pending(mempool.len()).max_block_size_bytes())
+}
+
+/// [`select_block_txs`] under a block size cap of `byte_cap` bytes.
+pub fn select_block_txs_with(state: &State, mempool: &[MempoolSnapshotEntry], byte_cap: u64) -> SelectBlockTxsResult {
     let mut chain_nonce: HashMap<String, u64> = HashMap::new();
     for e in mempool {
         if e.tx.from.is_empty() || e.tx.from == FAUCET_ADDRESS {
@@ -305,6 +330,7 @@ pub fn select_block_txs(state: &State, mempool: &[MempoolSnapshotEntry]) -> Sele
 
     let mut next_nonce = chain_nonce.clone();
     let mut gas_used: u64 = 0;
+    let mut bytes_used: u64 = 0;
     let mut hashes = Vec::new();
 
     for entry in mempool {
@@ -322,8 +348,13 @@ pub fn select_block_txs(state: &State, mempool: &[MempoolSnapshotEntry]) -> Sele
         if gas_used.saturating_add(fee) > BLOCK_GAS_CAP_UPLP {
             continue;
         }
+        let weight = entry.tx.weight();
+        if bytes_used.saturating_add(weight) > byte_cap {
+            continue;
+        }
         hashes.push(entry.tx.hash.clone());
         gas_used += fee;
+        bytes_used += weight;
         if !from.is_empty() {
             next_nonce.insert(from.clone(), entry.tx.nonce + 1);
         }
@@ -334,9 +365,72 @@ pub fn select_block_txs(state: &State, mempool: &[MempoolSnapshotEntry]) -> Sele
         hashes,
         gas_used,
         gas_cap: BLOCK_GAS_CAP_UPLP,
+        bytes_used,
+        byte_cap,
     }
 }
 
+/// [`select_block_txs`] for a builder holding the parent snapshot: each candidate that passes the count, gas and
+/// size caps is also executed, in mempool order, in an [`OverlayState`] over `base` and left out if it would be rejected
+/// (nonce gap, bad signature, insufficient balance, frozen sender). Faucet and sender-less entries are included
+/// without execution, as in `select_block_txs`. Returns the selection and the overlay holding the block's
+/// post-state, to commit once the block is accepted or discard.
+pub fn select_executable_block_txs(
+    base: &StateSnapshot,
+    mempool: &[MempoolSnapshotEntry],
+) -> (SelectBlockTxsResult, OverlayState) {
+    select_executable_block_txs_with(base, mempool, NetworkLoad::from_pending(mempool.len()).max_block_size_bytes())
+}
+
+/// [`select_executable_block_txs`] under a block size cap of `byte_cap` bytes.
+pub fn select_executable_block_txs_with(
+    base: &StateSnapshot,
+    mempool: &[MempoolSnapshotEntry],
+    byte_cap: u64,
+) -> (SelectBlockTxsResult, OverlayState) {
+    let mut overlay = OverlayState::new(base.clone());
+    let mut gas_used: u64 = 0;
+    let mut bytes_used: u64 = 0;
+    let mut hashes = Vec::new();
+
+    for entry in mempool {
+        if hashes.len() >= BLOCK_MAX_TX_COUNT {
+            break;
+        }
+        let fee = entry.tx.fee_uplp_u64();
+        if gas_used.saturating_add(fee) > BLOCK_GAS_CAP_UPLP {
+            continue;
+        }
+        let weight = entry.tx.weight();
+        if bytes_used.saturating_add(weight) > byte_cap {
+            continue;
+        }
+        let from = &entry.tx.from;
+        if !from.is_empty() && from != FAUCET_ADDRESS {
+            let applied = entry
+                .tx
+                .to_transaction()
+                .and_then(|tx| overlay.apply_transaction(&tx));
+            if applied.is_err() {
+                continue;
+            }
+        }
+        hashes.push(entry.tx.hash.clone());
+        gas_used += fee;
+        bytes_used += weight;
+    }
+
+    let result = SelectBlockTxsResult {
+        tx_count: hashes.len(),
+        hashes,
+        gas_used,
+        gas_cap: BLOCK_GAS_CAP_UPLP,
+        bytes_used,
+        byte_cap,
+    };
+    (result, overlay)
+}
+
 /// Next nonce packing will consume for `from` (chain head, then contiguous mempool).
 fn contiguous_next_nonce(mempool: &[MempoolSnapshotEntry], from: &str, chain_nonce: u64) -> u64 {
     let mut next = chain_nonce;
@@ -380,6 +474,13 @@ fn sum_fee_uplp(mempool: &[MempoolSnapshotEntry]) -> u64 {
     mempool.iter().map(|e| e.tx.fee_uplp_u64()).sum()
 }
 
+fn sum_encoded_bytes(mempool: &[MempoolSnapshotEntry]) -> u64 {
+    mempool
+        .iter()
+        .map(|e| serde_json::to_vec(&e.tx).map(|v| v.len() as u64).unwrap_or(0))
+        .sum()
+}
+
 fn oldest_wait_sec(mempool: &[MempoolSnapshotEntry], now_unix: i64) -> i64 {
     let mut oldest = 0i64;
     for e in mempool {
@@ -444,6 +545,24 @@ mod tests {
         assert_eq!(r.hashes, vec!["a", "b"]);
     }
 
+    #[test]
+    fn select_respects_byte_cap() {
+        let state = State::new();
+        let mempool: Vec<_> = (0..1500u64)
+            .map(|i| entry(&format!("h{}", i), &format!("PxS{}", i), 0, 1, i))
+            .collect();
+        let r = select_block_txs(&state, &mempool);
+        assert_eq!(r.byte_cap, NetworkLoad::from_pending(1500).max_block_size_bytes());
+        assert!(r.tx_count < mempool.len());
+        assert!(r.bytes_used <= r.byte_cap);
+        let next = mempool[r.tx_count].tx.weight();
+        assert!(r.bytes_used + next > r.byte_cap);
+
+        let half = select_block_txs_with(&state, &mempool, r.byte_cap / 2);
+        assert_eq!(half.byte_cap, r.byte_cap / 2);
+        assert!(half.tx_count < r.tx_count && half.bytes_used <= half.byte_cap);
+    }
+
     #[test]
     fn select_skips_nonce_gap_in_fifo() {
         let state = State::new();
@@ -544,4 +663,40 @@ mod tests {
         let r = mempool_admit(&state, tx, &pending);
         assert!(!r.accepted);
     }
+
+    fn signed_entry(tx: &Transaction, idx: u64) -> MempoolSnapshotEntry {
+        let mut e = entry(&tx.hash, &tx.from, tx.nonce, tx.fee_uplp as u64, idx);
+        e.tx.to = tx.to.clone();
+        e.tx.amount = serde_json::json!(tx.amount as u64);
+        e.tx.sig_main = tx.sig_main.clone();
+        e.tx.sig_derived = tx.sig_derived.clone();
+        e.tx.pub_main = tx.pub_main.clone();
+        e.tx.pub_derived = tx.pub_derived.clone();
+        e
+    }
+
+    #[test]
+    fn select_executable_skips_unfunded_and_keeps_post_state() {
+        let mut rng = crate::testing::DeterministicRng::new(41);
+        let accounts = rng.accounts(3);
+        let state = State::new();
+        state.set_balance(&accounts[0].address, 100);
+        let to = &accounts[2].address;
+        let mempool = vec![
+            signed_entry(&accounts[0].transfer(to, 60, 1, 0), 0),
+            // Would overdraw after the first transfer.
+            signed_entry(&accounts[0].transfer(to, 60, 1, 1), 1),
+            // Unfunded sender.
+            signed_entry(&accounts[1].transfer(to, 5, 1, 0), 2),
+        ];
+        assert_eq!(select_block_txs(&state, &mempool).tx_count, 3);
+
+        let (r, overlay) = select_executable_block_txs(&state.snapshot(), &mempool);
+        assert_eq!(r.hashes, vec![mempool[0].tx.hash.clone()]);
+        assert_eq!(r.gas_used, 1);
+        assert_eq!(overlay.get_balance(to), 60);
+        overlay.commit_into(&state).unwrap();
+        assert_eq!(state.get_balance(&accounts[0].address), 39);
+        assert_eq!(state.get_nonce(&accounts[0].address), 1);
+    }
 }
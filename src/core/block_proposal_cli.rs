@@ -2,10 +2,11 @@
 
 use crate::core::block_proposal::{
     block_proposal_status, mempool_admit, min_fee_from_load_json, parse_mempool_snapshot,
-    select_block_txs,
+    select_block_txs, select_block_txs_with,
 };
 use crate::core::state_file::load_state_file;
 use crate::error::Result;
+use crate::storage::{block_size_target, RocksStore};
 use std::path::Path;
 
 pub fn min_fee_from_load_cli(pending_count: usize) -> Result<String> {
@@ -31,3 +32,13 @@ pub fn select_block_txs_json(path: &Path, mempool_json: &str) -> Result<String>
     let result = select_block_txs(&state, &mempool);
     Ok(serde_json::to_string(&result).unwrap())
 }
+
+/// [`select_block_txs_json`] capped at the size target derived from the chain stored at `db_path`
+/// (`storage::block_size_target`) instead of the load-based cap.
+pub fn select_block_txs_stored_json(path: &Path, mempool_json: &str, db_path: &Path) -> Result<String> {
+    let state = load_state_file(path)?;
+    let mempool = parse_mempool_snapshot(mempool_json)?;
+    let byte_cap = block_size_target(&RocksStore::open(db_path)?)?.target_bytes();
+    let result = select_block_txs_with(&state, &mempool, byte_cap);
+    Ok(serde_json::to_string(&result).unwrap())
+}
@@ -0,0 +1,411 @@
+//! Inbound bridge: wrapped assets minted from proven foreign-chain transfers.
+//!
+//! Counterpart of `core::outbound`. A foreign chain (or its bridge contract) locks funds for Platarium and commits
+//! to the transfer as a [`BridgeClaim`] leaf (`platarium_consensus::bridge_claim_leaf`) under a Merkle
+//! `claims_root` in one of its headers. A relayer submits a `BridgeClaim { claim_id, proof }` transaction whose
+//! `to`, `asset` and `amount` are the recipient and what to mint; the relayer pays the fee. [`verify_inbound`]
+//! checks the [`ForeignHeaderProof`] in two steps:
+//! 1. a [`ForeignLightClient`] confirms that `claims_root` is final at `height` on the source chain;
+//! 2. the claim leaf's Merkle path (`siblings`) leads to that root.
+//!
+//! On chain the light client is the [`BridgeLedger`] kept in the asset registry: foreign headers whose
+//! `(chain_id, height, claims_root)` a validator supermajority signed ([`attest_foreign_header`]), so execution is a
+//! pure function of state. Relayers and
+//! indexers can plug in their own client (e.g. one that checks the foreign chain's consensus signatures carried in
+//! `finality`) to check a claim before submitting it.
+//!
+//! A wrapped asset is a token issued by [`BRIDGE_ADDRESS`] for one source chain ([`register_wrapped_asset`]). Only
+//! a verified claim from that chain mints it, and each claim id mints once.
+
+use std::collections::{BTreeMap, BTreeSet};
+use platarium_consensus::wire::{Decode, Encode, Reader, WireError};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::asset::Asset;
+use crate::core::asset_registry::AssetRegistryError;
+use crate::core::events::Event;
+use crate::core::governance::{check_signed_approvals, Approval, GovernanceAction};
+use crate::core::node_registry::NodeRegistry;
+use crate::core::state::{Address, State};
+use crate::core::transaction::Transaction;
+use crate::error::{PlatariumError, Result};
+
+/// Issuer of every wrapped asset. It is not a `Px` key address, so no transaction can be signed for it (see
+/// `State::check_account_keys`) and only bridge claims mint its tokens.
+pub const BRIDGE_ADDRESS: &str = "bridge";
+
+/// Errors produced by inbound claim verification.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BridgeError {
+    #[error("{asset} is not a wrapped asset of {chain}")]
+    NotWrapped { asset: String, chain: String },
+
+    #[error("Claim is from {claim}, proof is for {proof}")]
+    ChainMismatch { claim: String, proof: String },
+
+    #[error("No attested header for {chain} at height {height}")]
+    UnknownHeader { chain: String, height: u64 },
+
+    #[error("Claims root does not match the attested header for {chain} at height {height}")]
+    RootMismatch { chain: String, height: u64 },
+
+    #[error("Header for {chain} at height {height} is already attested with a different root")]
+    ConflictingHeader { chain: String, height: u64 },
+
+    #[error("Claim is not included under the proven claims root")]
+    InvalidProof,
+
+    #[error("Claim {claim_id} from {chain} was already minted")]
+    AlreadyClaimed { chain: String, claim_id: String },
+
+    #[error("Invalid claim: {0}")]
+    InvalidClaim(String),
+}
+
+impl From<BridgeError> for PlatariumError {
+    fn from(err: BridgeError) -> Self {
+        PlatariumError::State(format!("Bridge error: {}", err))
+    }
+}
+
+/// A transfer locked on `source_chain` for `recipient` on Platarium.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeClaim {
+    pub source_chain: String,
+    /// Unique id of the transfer on the source chain (e.g. its lock event id).
+    pub claim_id: String,
+    pub recipient: Address,
+    /// Canonical wrapped asset string.
+    pub asset: String,
+    pub amount: u128,
+}
+
+impl BridgeClaim {
+    /// Claim a `BridgeClaim` transaction makes: source chain from the proof, recipient/asset/amount from the
+    /// transaction. `None` for other kinds.
+    pub fn from_transaction(tx: &Transaction) -> Option<Self> {
+        match tx.kind {
+            crate::core::transaction::TxKind::BridgeClaim { ref claim_id, ref proof } => Some(Self {
+                source_chain: proof.chain_id.clone(),
+                claim_id: claim_id.clone(),
+                recipient: tx.to.clone(),
+                asset: tx.asset.as_canonical(),
+                amount: tx.amount,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Merkle leaf the source chain commits to.
+    pub fn leaf(&self) -> [u8; 32] {
+        platarium_consensus::bridge_claim_leaf(
+            &self.source_chain,
+            &self.claim_id,
+            &self.recipient,
+            &self.asset,
+            self.amount,
+        )
+    }
+}
+
+/// Foreign header commitment plus the claim's inclusion path under it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ForeignHeaderProof {
+    pub chain_id: String,
+    pub height: u64,
+    /// Hex Merkle root of the header's claim leaves.
+    pub claims_root: String,
+    /// Hex sibling hashes from the claim leaf to `claims_root`, leaf level first.
+    pub siblings: Vec<String>,
+    /// Client-specific finality evidence (e.g. foreign validator signatures); unused by [`BridgeLedger`].
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub finality: String,
+}
+
+impl Encode for ForeignHeaderProof {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.chain_id.encode(out);
+        self.height.encode(out);
+        self.claims_root.encode(out);
+        self.siblings.encode(out);
+        self.finality.encode(out);
+    }
+}
+
+impl Decode for ForeignHeaderProof {
+    fn decode(reader: &mut Reader<'_>) -> std::result::Result<Self, WireError> {
+        Ok(Self {
+            chain_id: String::decode(reader)?,
+            height: u64::decode(reader)?,
+            claims_root: String::decode(reader)?,
+            siblings: Vec::decode(reader)?,
+            finality: String::decode(reader)?,
+        })
+    }
+}
+
+/// Decides whether a foreign header commitment is final. Implementations must be deterministic: no network
+/// access, clock or randomness.
+pub trait ForeignLightClient {
+    /// `Ok` if `proof.claims_root` is the final claims root of `proof.chain_id` at `proof.height`.
+    fn verify_header(&self, proof: &ForeignHeaderProof) -> std::result::Result<(), BridgeError>;
+}
+
+/// Verifies that `claim` is final on its source chain: `client` accepts the header and the claim's Merkle path
+/// leads to the header's claims root.
+pub fn verify_inbound(
+    claim: &BridgeClaim,
+    proof: &ForeignHeaderProof,
+    client: &dyn ForeignLightClient,
+) -> std::result::Result<(), BridgeError> {
+    if claim.source_chain != proof.chain_id {
+        return Err(BridgeError::ChainMismatch {
+            claim: claim.source_chain.clone(),
+            proof: proof.chain_id.clone(),
+        });
+    }
+    if claim.amount == 0 || claim.claim_id.is_empty() {
+        return Err(BridgeError::InvalidClaim("claim id and amount must be set".into()));
+    }
+    client.verify_header(proof)?;
+    let root = decode_hash(&proof.claims_root).ok_or(BridgeError::InvalidProof)?;
+    let siblings = proof
+        .siblings
+        .iter()
+        .map(|s| decode_hash(s))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(BridgeError::InvalidProof)?;
+    if !platarium_consensus::verify_merkle_proof(claim.leaf(), &siblings, root) {
+        return Err(BridgeError::InvalidProof);
+    }
+    Ok(())
+}
+
+/// Attested foreign headers and minted claim ids. Part of the asset registry, so it follows snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeLedger {
+    /// chain id -> height -> claims root (lowercase hex).
+    headers: BTreeMap<String, BTreeMap<u64, String>>,
+    /// (chain id, claim id) already minted.
+    claimed: BTreeSet<(String, String)>,
+}
+
+impl BridgeLedger {
+    /// Records `claims_root` as final for `chain_id` at `height`. Re-attesting the same root is a no-op.
+    pub fn attest(&mut self, chain_id: &str, height: u64, claims_root: &str) -> std::result::Result<(), BridgeError> {
+        let root = decode_hash(claims_root)
+            .map(hex::encode)
+            .ok_or_else(|| BridgeError::InvalidClaim(format!("invalid claims root {:?}", claims_root)))?;
+        let headers = self.headers.entry(chain_id.to_string()).or_default();
+        match headers.get(&height) {
+            Some(existing) if *existing != root => Err(BridgeError::ConflictingHeader {
+                chain: chain_id.to_string(),
+                height,
+            }),
+            _ => {
+                headers.insert(height, root);
+                Ok(())
+            }
+        }
+    }
+
+    /// Attested claims root of `chain_id` at `height`.
+    pub fn claims_root(&self, chain_id: &str, height: u64) -> Option<&str> {
+        self.headers.get(chain_id)?.get(&height).map(String::as_str)
+    }
+
+    pub fn is_claimed(&self, chain_id: &str, claim_id: &str) -> bool {
+        self.claimed.contains(&(chain_id.to_string(), claim_id.to_string()))
+    }
+
+    pub(crate) fn mark_claimed(&mut self, chain_id: &str, claim_id: &str) {
+        self.claimed.insert((chain_id.to_string(), claim_id.to_string()));
+    }
+}
+
+impl ForeignLightClient for BridgeLedger {
+    fn verify_header(&self, proof: &ForeignHeaderProof) -> std::result::Result<(), BridgeError> {
+        let attested = self
+            .claims_root(&proof.chain_id, proof.height)
+            .ok_or_else(|| BridgeError::UnknownHeader {
+                chain: proof.chain_id.clone(),
+                height: proof.height,
+            })?;
+        if !attested.eq_ignore_ascii_case(&proof.claims_root) {
+            return Err(BridgeError::RootMismatch {
+                chain: proof.chain_id.clone(),
+                height: proof.height,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Registers `asset` as the wrapped form of `source_chain`'s funds, issued by [`BRIDGE_ADDRESS`], after checking
+/// validator signatures over [`GovernanceAction::RegisterWrappedAsset`].
+pub fn register_wrapped_asset(
+    state: &State,
+    registry: &NodeRegistry,
+    asset: &Asset,
+    source_chain: &str,
+    approvals: &[Approval],
+) -> Result<()> {
+    if source_chain.is_empty() {
+        return Err(BridgeError::InvalidClaim("source chain must be set".into()).into());
+    }
+    let action = GovernanceAction::RegisterWrappedAsset {
+        asset: asset.as_canonical(),
+        source_chain: source_chain.to_string(),
+    };
+    check_signed_approvals(registry, &action, approvals)?;
+    state.update_asset_registry(|r| r.register_wrapped(asset, source_chain))?;
+    Ok(())
+}
+
+/// Records a final header of `chain_id` (its claims root at `height`) after checking validator signatures over
+/// [`GovernanceAction::AttestForeignHeader`]; validators sign the root as lowercase hex.
+pub fn attest_foreign_header(
+    state: &State,
+    registry: &NodeRegistry,
+    chain_id: &str,
+    height: u64,
+    claims_root: &str,
+    approvals: &[Approval],
+) -> Result<()> {
+    let root = decode_hash(claims_root)
+        .map(hex::encode)
+        .ok_or_else(|| BridgeError::InvalidClaim(format!("invalid claims root {:?}", claims_root)))?;
+    let action = GovernanceAction::AttestForeignHeader {
+        chain_id: chain_id.to_string(),
+        height,
+        claims_root: root.clone(),
+    };
+    check_signed_approvals(registry, &action, approvals)?;
+    state.update_asset_registry(|r| r.bridge_mut().attest(chain_id, height, &root))?;
+    Ok(())
+}
+
+/// Effects of a `BridgeClaim` transaction: verifies the claim against the attested headers, charges the relayer's
+/// fee, mints the wrapped asset to `to` and emits `BridgeClaimed`.
+pub(crate) fn apply_bridge_claim(state: &State, tx: &Transaction, proof: &ForeignHeaderProof) -> Result<()> {
+    let claim = BridgeClaim::from_transaction(tx)
+        .ok_or_else(|| BridgeError::InvalidClaim("not a BridgeClaim transaction".into()))?;
+    state.read_asset_registry(|r| -> Result<()> {
+        match r.get(&tx.asset).and_then(|i| i.bridge_chain.as_deref()) {
+            Some(chain) if chain == claim.source_chain => {}
+            _ => {
+                return Err(BridgeError::NotWrapped {
+                    asset: claim.asset.clone(),
+                    chain: claim.source_chain.clone(),
+                }
+                .into())
+            }
+        }
+        if r.is_frozen(&tx.asset) {
+            return Err(AssetRegistryError::Frozen(claim.asset.clone()).into());
+        }
+        if r.bridge().is_claimed(&claim.source_chain, &claim.claim_id) {
+            return Err(BridgeError::AlreadyClaimed {
+                chain: claim.source_chain.clone(),
+                claim_id: claim.claim_id.clone(),
+            }
+            .into());
+        }
+        r.check_supply_add(&tx.asset, tx.amount)?;
+        verify_inbound(&claim, proof, r.bridge())?;
+        Ok(())
+    })?;
+    let balance = state
+        .get_asset_balance(&tx.to, &tx.asset)
+        .checked_add(tx.amount)
+        .ok_or_else(|| PlatariumError::State("wrapped balance overflow".into()))?;
+    state.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+    state.set_asset_balance(&tx.to, &tx.asset, balance);
+    state.update_asset_registry(|r| {
+        r.bridge_mut().mark_claimed(&claim.source_chain, &claim.claim_id);
+        r.add_supply(&tx.asset, tx.amount)
+    })?;
+    state.emit(Event::BridgeClaimed {
+        source_chain: claim.source_chain,
+        claim_id: claim.claim_id,
+        recipient: claim.recipient,
+        asset: claim.asset,
+        amount: claim.amount,
+    });
+    Ok(())
+}
+
+fn decode_hash(s: &str) -> Option<[u8; 32]> {
+    hex::decode(s).ok()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim(id: &str, amount: u128) -> BridgeClaim {
+        BridgeClaim {
+            source_chain: "eth".into(),
+            claim_id: id.into(),
+            recipient: "PxR".into(),
+            asset: "Token:wETH".into(),
+            amount,
+        }
+    }
+
+    fn proof_for(claims: &[BridgeClaim], index: usize) -> ForeignHeaderProof {
+        let leaves: Vec<[u8; 32]> = claims.iter().map(BridgeClaim::leaf).collect();
+        let root = platarium_consensus::merkle_root(leaves.clone()).unwrap();
+        let siblings = platarium_consensus::merkle_proof(leaves, claims[index].leaf()).unwrap();
+        ForeignHeaderProof {
+            chain_id: "eth".into(),
+            height: 100,
+            claims_root: hex::encode(root),
+            siblings: siblings.iter().map(hex::encode).collect(),
+            finality: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_verify_inbound_against_attested_header() {
+        let claims = vec![claim("a", 5), claim("b", 7), claim("c", 9)];
+        let proof = proof_for(&claims, 1);
+        let mut ledger = BridgeLedger::default();
+        assert_eq!(
+            verify_inbound(&claims[1], &proof, &ledger),
+            Err(BridgeError::UnknownHeader { chain: "eth".into(), height: 100 })
+        );
+        ledger.attest("eth", 100, &proof.claims_root.to_uppercase()).unwrap();
+        assert!(ledger.attest("eth", 100, &hex::encode([1u8; 32])).is_err());
+        verify_inbound(&claims[1], &proof, &ledger).unwrap();
+
+        // Changing any claim field breaks inclusion.
+        assert_eq!(verify_inbound(&claim("b", 8), &proof, &ledger), Err(BridgeError::InvalidProof));
+        let mut wrong_height = proof.clone();
+        wrong_height.height = 101;
+        assert!(matches!(
+            verify_inbound(&claims[1], &wrong_height, &ledger),
+            Err(BridgeError::UnknownHeader { .. })
+        ));
+        let mut other_chain = claims[1].clone();
+        other_chain.source_chain = "sol".into();
+        assert!(matches!(
+            verify_inbound(&other_chain, &proof, &ledger),
+            Err(BridgeError::ChainMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_pluggable_client() {
+        struct TrustAll;
+        impl ForeignLightClient for TrustAll {
+            fn verify_header(&self, _: &ForeignHeaderProof) -> std::result::Result<(), BridgeError> {
+                Ok(())
+            }
+        }
+        let claims = vec![claim("a", 5)];
+        let proof = proof_for(&claims, 0);
+        verify_inbound(&claims[0], &proof, &TrustAll).unwrap();
+        assert!(verify_inbound(&claim("z", 5), &proof, &TrustAll).is_err());
+    }
+}
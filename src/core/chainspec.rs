@@ -0,0 +1,114 @@
+//! Chain specification: per-deployment parameters shared by every node of one network.
+//!
+//! Test and staging deployments are often forked from mainnet and reuse key material. `deployment_salt` is mixed into the transaction signing domain (`Transaction::deployment_salt` is part of the hash), and `ChainSpec::check_transaction` rejects transactions whose salt differs from the deployment's, so a transaction signed for one deployment never validates on another. A spec without a salt accepts only unsalted transactions.
+//!
+//! A node installs its spec with `State::set_chain_spec`; `State::apply_transaction` and `Core::submit_transaction` run
+//! `check_transaction` through `State::check_transaction` instead of bare `validate_basic`.
+//!
+//! `network` names the network the deployment belongs to; `check_transaction` also requires its chain ID in `Transaction::chain_id` (see `Transaction::check_chain_id`), so testnet transactions never validate on mainnet.
+
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::core::discovery::{verify_bootnodes, PeerDescriptor};
+use crate::core::protocol_params::{ProtocolParams, ProtocolParamsSchedule};
+use crate::core::transaction::{Transaction, TransactionValidationError, ValidationResult};
+use crate::error::{PlatariumError, Result};
+use crate::utils::NetworkId;
+
+/// Per-deployment chain parameters.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainSpec {
+    /// Human-readable deployment name (e.g. "mainnet", "staging").
+    pub name: String,
+    /// Optional salt mixed into the transaction signing domain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployment_salt: Option<String>,
+    /// Network whose chain ID transactions must carry (mainnet when absent).
+    #[serde(default)]
+    pub network: NetworkId,
+    /// Signed bootnode descriptors for initial peer discovery.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bootnodes: Vec<PeerDescriptor>,
+    /// Genesis protocol parameters; defaults when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_params: Option<ProtocolParams>,
+}
+
+impl ChainSpec {
+    pub fn new(name: &str, deployment_salt: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            deployment_salt: deployment_salt.map(String::from),
+            network: NetworkId::Mainnet,
+            bootnodes: Vec::new(),
+            protocol_params: None,
+        }
+    }
+
+    /// Parses a chainspec, verifies its bootnode signatures and range-checks its protocol parameters.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let spec: Self = serde_json::from_str(json)
+            .map_err(|e| PlatariumError::Validation(format!("invalid chainspec JSON: {}", e)))?;
+        verify_bootnodes(&spec.bootnodes)?;
+        if let Some(params) = &spec.protocol_params {
+            params.validate()?;
+        }
+        Ok(spec)
+    }
+
+    /// Parameter schedule starting from this spec's genesis parameters.
+    pub fn params_schedule(&self) -> ProtocolParamsSchedule {
+        ProtocolParamsSchedule::from_genesis(self.protocol_params.clone().unwrap_or_default())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path).map_err(|e| {
+            PlatariumError::Validation(format!("read chainspec {}: {}", path.display(), e))
+        })?;
+        Self::from_json(&data)
+    }
+
+    /// Checks that `tx` was signed for this deployment (salt match), then runs `validate_basic_for` the spec's network.
+    pub fn check_transaction(&self, tx: &Transaction) -> ValidationResult {
+        if tx.deployment_salt != self.deployment_salt {
+            return Err(TransactionValidationError::DeploymentMismatch {
+                expected: self.deployment_salt.clone(),
+                got: tx.deployment_salt.clone(),
+            });
+        }
+        tx.validate_basic_for(&self.network)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chainspec_json() {
+        let spec = ChainSpec::from_json(r#"{"name":"staging","deployment_salt":"stg-1"}"#).unwrap();
+        assert_eq!(spec, ChainSpec::new("staging", Some("stg-1")));
+        let main = ChainSpec::from_json(r#"{"name":"mainnet"}"#).unwrap();
+        assert_eq!(main.deployment_salt, None);
+        assert_eq!(main.params_schedule().at(0), &ProtocolParams::default());
+
+        let mut bad = ProtocolParams::default();
+        bad.slashing.get_mut(&crate::core::slashing::SlashingReason::NoVote).unwrap().reputation_penalty_pct = 200;
+        let mut spec = ChainSpec::new("dev", None);
+        spec.protocol_params = Some(bad);
+        assert!(ChainSpec::from_json(&serde_json::to_string(&spec).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_chainspec_bootnodes_verified() {
+        let sk = secp256k1::SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let mut spec = ChainSpec::new("testnet", None);
+        spec.bootnodes.push(PeerDescriptor::sign("boot-1".into(), "boot1:30333".into(), &sk).unwrap());
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(ChainSpec::from_json(&json).unwrap(), spec);
+        spec.bootnodes[0].network_address = "evil:30333".into();
+        let json = serde_json::to_string(&spec).unwrap();
+        assert!(ChainSpec::from_json(&json).is_err());
+    }
+}
@@ -0,0 +1,389 @@
+//! Versioned network codec for consensus types.
+//!
+//! Every message on the wire is `CODEC_VERSION (u8) || type tag (u8) || body`, where the body is the canonical
+//! binary encoding of `platarium_consensus::wire` (big-endian integers, u32 length prefixes, one-byte enum tags).
+//! The encoding of a type is fixed for a codec version: a layout change ships as a new version, and a decoder
+//! rejects versions it does not know instead of misreading them. Nodes built from different crate versions that
+//! speak the same codec version therefore agree on every byte.
+//!
+//! Decoding never trusts a length it has not checked: messages are capped at [`MAX_MESSAGE_LEN`], sequence counts
+//! are checked against the remaining input before anything is allocated, trailing bytes are rejected, and each
+//! type's protocol limits (e.g. `BLOCK_MAX_TX_COUNT` hashes per block) are enforced before a value is returned.
+//!
+//! | Tag | Type |
+//! |-----|------|
+//! | 1 | `Transaction` |
+//! | 2 | `Block` |
+//! | 3 | `ConsensusMessage` (votes and proposals) |
+//! | 4 | `SnapshotManifest` |
+//! | 5 | `SnapshotChunk` |
+//! | 6 | `SignedConsensusMessage` (a `ConsensusMessage` and its node signature) |
+
+use platarium_consensus::wire::{Decode, Encode, Reader, WireError, MAX_FRAME_LEN};
+use thiserror::Error;
+use crate::core::block_assembly::Block;
+use crate::core::confirmation_layer::Vote;
+use crate::core::consensus_admission::{ConsensusMessage, SignedConsensusMessage};
+use crate::core::consensus_params::BLOCK_MAX_TX_COUNT;
+use crate::core::state_sync::{ChunkAccount, ChunkToken, SnapshotChunk, SnapshotManifest, MAX_CHUNK_BITS};
+use crate::core::transaction::{Transaction, TxKind, MAX_TRANSFER_OUTPUTS};
+use crate::error::PlatariumError;
+
+/// Codec version written by [`encode_message`] and the only one [`decode_message`] accepts.
+pub const CODEC_VERSION: u8 = 1;
+
+/// Largest encoded message, header included.
+pub const MAX_MESSAGE_LEN: usize = MAX_FRAME_LEN;
+
+/// Errors produced while decoding a network message.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    #[error("Message of {0} bytes exceeds {MAX_MESSAGE_LEN}")]
+    TooLarge(usize),
+
+    #[error("Message too short for its header")]
+    Truncated,
+
+    #[error("Unsupported codec version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Expected message type {expected}, got {got}")]
+    WrongType { expected: u8, got: u8 },
+
+    #[error("Malformed body: {0}")]
+    Wire(WireError),
+
+    #[error("{what} count {count} exceeds {max}")]
+    LimitExceeded { what: &'static str, count: usize, max: usize },
+}
+
+impl From<CodecError> for PlatariumError {
+    fn from(err: CodecError) -> Self {
+        PlatariumError::Validation(format!("Codec error: {}", err))
+    }
+}
+
+/// A type sent between nodes under its own tag.
+pub trait NetworkMessage: Encode + Decode {
+    /// Type tag in the message header.
+    const TAG: u8;
+
+    /// Protocol limits the wire format alone does not bound.
+    fn check_limits(&self) -> Result<(), CodecError> {
+        Ok(())
+    }
+}
+
+/// Encodes `value` with the codec header.
+pub fn encode_message<T: NetworkMessage>(value: &T) -> Vec<u8> {
+    let mut out = vec![CODEC_VERSION, T::TAG];
+    value.encode(&mut out);
+    out
+}
+
+/// Decodes a message produced by [`encode_message`], checking the size, header, body and type limits.
+pub fn decode_message<T: NetworkMessage>(bytes: &[u8]) -> Result<T, CodecError> {
+    if bytes.len() > MAX_MESSAGE_LEN {
+        return Err(CodecError::TooLarge(bytes.len()));
+    }
+    let [version, tag, body @ ..] = bytes else {
+        return Err(CodecError::Truncated);
+    };
+    if *version != CODEC_VERSION {
+        return Err(CodecError::UnsupportedVersion(*version));
+    }
+    if *tag != T::TAG {
+        return Err(CodecError::WrongType { expected: T::TAG, got: *tag });
+    }
+    let value = T::from_wire(body).map_err(CodecError::Wire)?;
+    value.check_limits()?;
+    Ok(value)
+}
+
+fn check_count(what: &'static str, count: usize, max: usize) -> Result<(), CodecError> {
+    if count > max {
+        return Err(CodecError::LimitExceeded { what, count, max });
+    }
+    Ok(())
+}
+
+impl NetworkMessage for Transaction {
+    const TAG: u8 = 1;
+
+    fn check_limits(&self) -> Result<(), CodecError> {
+        match &self.kind {
+            TxKind::MultiTransfer { outputs } => check_count("transfer output", outputs.len(), MAX_TRANSFER_OUTPUTS),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Header fields in declaration order.
+impl Encode for Block {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.block_number.encode(out);
+        self.previous_hash.encode(out);
+        self.timestamp.encode(out);
+        self.transaction_hashes.encode(out);
+        self.merkle_root.encode(out);
+        self.state_root.encode(out);
+        self.block_hash.encode(out);
+        self.producer_id.encode(out);
+        self.producer_sig.encode(out);
+        self.messages_root.encode(out);
+    }
+}
+
+impl Decode for Block {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok(Self {
+            block_number: u64::decode(reader)?,
+            previous_hash: String::decode(reader)?,
+            timestamp: i64::decode(reader)?,
+            transaction_hashes: Vec::decode(reader)?,
+            merkle_root: String::decode(reader)?,
+            state_root: String::decode(reader)?,
+            block_hash: String::decode(reader)?,
+            producer_id: String::decode(reader)?,
+            producer_sig: String::decode(reader)?,
+            messages_root: Option::decode(reader)?,
+        })
+    }
+}
+
+impl NetworkMessage for Block {
+    const TAG: u8 = 2;
+
+    fn check_limits(&self) -> Result<(), CodecError> {
+        check_count("block transaction", self.transaction_hashes.len(), BLOCK_MAX_TX_COUNT)
+    }
+}
+
+impl Encode for Vote {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(match self {
+            Vote::Confirm => 0,
+            Vote::Reject => 1,
+        });
+    }
+}
+
+impl Decode for Vote {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        match reader.u8()? {
+            0 => Ok(Vote::Confirm),
+            1 => Ok(Vote::Reject),
+            tag => Err(WireError::InvalidTag(tag)),
+        }
+    }
+}
+
+/// Tag 0 `Vote`, tag 1 `Proposal`, then the variant's fields in declaration order.
+impl Encode for ConsensusMessage {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            ConsensusMessage::Vote { node_id, slot, subject, vote } => {
+                out.push(0);
+                node_id.encode(out);
+                slot.encode(out);
+                subject.encode(out);
+                vote.encode(out);
+            }
+            ConsensusMessage::Proposal { node_id, slot, block_hash } => {
+                out.push(1);
+                node_id.encode(out);
+                slot.encode(out);
+                block_hash.encode(out);
+            }
+        }
+    }
+}
+
+impl Decode for ConsensusMessage {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok(match reader.u8()? {
+            0 => ConsensusMessage::Vote {
+                node_id: String::decode(reader)?,
+                slot: u64::decode(reader)?,
+                subject: String::decode(reader)?,
+                vote: Vote::decode(reader)?,
+            },
+            1 => ConsensusMessage::Proposal {
+                node_id: String::decode(reader)?,
+                slot: u64::decode(reader)?,
+                block_hash: String::decode(reader)?,
+            },
+            tag => return Err(WireError::InvalidTag(tag)),
+        })
+    }
+}
+
+impl NetworkMessage for ConsensusMessage {
+    const TAG: u8 = 3;
+}
+
+/// The message's own encoding, then the signature.
+impl Encode for SignedConsensusMessage {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.message.encode(out);
+        self.signature.encode(out);
+    }
+}
+
+impl Decode for SignedConsensusMessage {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok(Self {
+            message: ConsensusMessage::decode(reader)?,
+            signature: String::decode(reader)?,
+        })
+    }
+}
+
+impl NetworkMessage for SignedConsensusMessage {
+    const TAG: u8 = 6;
+}
+
+impl Encode for SnapshotManifest {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.state_root.encode(out);
+        self.chunk_bits.encode(out);
+        self.leaf_count.encode(out);
+        self.chunk_digests.encode(out);
+        self.chunk_sizes.encode(out);
+        self.registries.encode(out);
+    }
+}
+
+impl Decode for SnapshotManifest {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok(Self {
+            state_root: String::decode(reader)?,
+            chunk_bits: u8::decode(reader)?,
+            leaf_count: u64::decode(reader)?,
+            chunk_digests: Vec::decode(reader)?,
+            chunk_sizes: Vec::decode(reader)?,
+            registries: String::decode(reader)?,
+        })
+    }
+}
+
+impl NetworkMessage for SnapshotManifest {
+    const TAG: u8 = 4;
+
+    /// At most `2^MAX_CHUNK_BITS` chunks.
+    fn check_limits(&self) -> Result<(), CodecError> {
+        let max = 1usize << MAX_CHUNK_BITS;
+        check_count("snapshot chunk", self.chunk_digests.len(), max)?;
+        check_count("snapshot chunk size", self.chunk_sizes.len(), max)
+    }
+}
+
+impl Encode for ChunkAccount {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.address.encode(out);
+        self.balance.encode(out);
+        self.nonce.encode(out);
+        self.uplp_balance.encode(out);
+    }
+}
+
+impl Decode for ChunkAccount {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok(Self {
+            address: String::decode(reader)?,
+            balance: String::decode(reader)?,
+            nonce: u64::decode(reader)?,
+            uplp_balance: String::decode(reader)?,
+        })
+    }
+}
+
+impl Encode for ChunkToken {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.address.encode(out);
+        self.asset.encode(out);
+        self.balance.encode(out);
+    }
+}
+
+impl Decode for ChunkToken {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok(Self {
+            address: String::decode(reader)?,
+            asset: String::decode(reader)?,
+            balance: String::decode(reader)?,
+        })
+    }
+}
+
+impl Encode for SnapshotChunk {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.index.encode(out);
+        self.accounts.encode(out);
+        self.tokens.encode(out);
+    }
+}
+
+impl Decode for SnapshotChunk {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok(Self { index: u32::decode(reader)?, accounts: Vec::decode(reader)?, tokens: Vec::decode(reader)? })
+    }
+}
+
+impl NetworkMessage for SnapshotChunk {
+    const TAG: u8 = 5;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::State;
+    use crate::core::state_sync::export_snapshot;
+
+    fn block(hashes: usize) -> Block {
+        Block {
+            block_number: 7,
+            previous_hash: "ab".into(),
+            timestamp: -1,
+            transaction_hashes: vec!["cd".into(); hashes],
+            merkle_root: "m".into(),
+            state_root: "s".into(),
+            block_hash: "h".into(),
+            producer_id: "n".into(),
+            producer_sig: String::new(),
+            messages_root: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trips_and_layout() {
+        let bytes = encode_message(&block(1));
+        assert_eq!(&bytes[..2], &[CODEC_VERSION, 2]);
+        assert_eq!(&bytes[2..18], &[0, 0, 0, 0, 0, 0, 0, 7, 0, 0, 0, 2, b'a', b'b', 0xff, 0xff]);
+        assert_eq!(decode_message::<Block>(&bytes).unwrap(), block(1));
+
+        let vote = ConsensusMessage::Vote { node_id: "n1".into(), slot: 3, subject: "tx".into(), vote: Vote::Reject };
+        assert_eq!(decode_message::<ConsensusMessage>(&encode_message(&vote)).unwrap(), vote);
+        let signed = SignedConsensusMessage { message: vote, signature: "ab".into() };
+        assert_eq!(decode_message::<SignedConsensusMessage>(&encode_message(&signed)).unwrap(), signed);
+
+        let state = State::new();
+        state.set_balance(&"alice".to_string(), 5);
+        let (manifest, chunks) = export_snapshot(&state, 1).unwrap();
+        assert_eq!(decode_message::<SnapshotManifest>(&encode_message(&manifest)).unwrap(), manifest);
+        assert_eq!(decode_message::<SnapshotChunk>(&encode_message(&chunks[0])).unwrap(), chunks[0]);
+    }
+
+    #[test]
+    fn test_rejects_bad_headers_and_limits() {
+        let bytes = encode_message(&block(1));
+        assert_eq!(decode_message::<Block>(&bytes[..1]), Err(CodecError::Truncated));
+        assert_eq!(decode_message::<Block>(&[[9].as_slice(), &bytes[1..]].concat()), Err(CodecError::UnsupportedVersion(9)));
+        assert_eq!(decode_message::<SnapshotChunk>(&bytes), Err(CodecError::WrongType { expected: 5, got: 2 }));
+        assert_eq!(decode_message::<Block>(&bytes[..bytes.len() - 1]), Err(CodecError::Wire(WireError::UnexpectedEnd)));
+        assert!(matches!(
+            decode_message::<Block>(&encode_message(&block(BLOCK_MAX_TX_COUNT + 1))),
+            Err(CodecError::LimitExceeded { what: "block transaction", .. })
+        ));
+    }
+}
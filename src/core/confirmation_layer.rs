@@ -6,23 +6,27 @@
 //! - **`process_l1_confirmation(votes)`** → returns **(Confirmed | Rejected, to_penalize)**.
 //!   Confirmed if ≥67% vote Confirm; nodes that voted against the majority are in `to_penalize`.
 //!
-//! Flow: `verify_tx_for_l1(state, tx)` (balance/nonce/sig/fee) → collect votes → `process_l1_confirmation(votes)` → `apply_l1_penalties(registry, to_penalize)`.
+//! Flow: `verify_tx_for_l1(state, tx)` (balance/nonce/sig/fee) → collect signed votes → `ConsensusAdmission::filter_votes` (signatures, per-node rate limits) → `process_l1_confirmation(votes)` → `apply_l1_penalties(registry, state, params, to_penalize)`.
 //!
 //! # Determinism
 //! Same transaction, state, and votes yield the same `ConfirmationResult` and list of nodes to penalize. Verification reuses `ExecutionLogic` (signature, fee, balance, nonce).
 
 use crate::core::execution::ExecutionLogic;
 use crate::core::node_registry::{NodeId, NodeRegistry};
+use crate::core::protocol_params::ProtocolParams;
+use crate::core::slashing::{apply_slash_batch, SlashingReason};
 use crate::core::state::State;
 use crate::core::transaction::Transaction;
 use crate::error::{PlatariumError, Result};
+use serde::Serialize;
 use thiserror::Error;
 
 /// L1 confirmation threshold: at least this percentage of validators must vote Confirm.
 pub const L1_CONFIRM_THRESHOLD_PCT: u64 = 67;
 
 /// A validator’s vote on a transaction.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Vote {
     Confirm,
     Reject,
@@ -50,10 +54,10 @@ impl From<ConfirmationError> for PlatariumError {
     }
 }
 
-/// Performs L1 verification (Step 3): balance, nonce, signature, and fee (μPLP).
+/// Performs L1 verification (Step 3): deployment and chain ID, balance, nonce, signature, and fee (μPLP).
 /// Returns `Ok(true)` if the transaction is valid for L1, `Ok(false)` otherwise (no error, only failed checks).
 pub fn verify_tx_for_l1(state: &State, tx: &Transaction) -> Result<bool> {
-    let valid_sig_and_fee = ExecutionLogic::validate_transaction(tx).is_ok();
+    let valid_sig_and_fee = ExecutionLogic::validate_transaction_on(state, tx).is_ok();
     if !valid_sig_and_fee {
         return Ok(false);
     }
@@ -107,12 +111,19 @@ pub fn confirm_transaction_l1(
     process_l1_confirmation(votes)
 }
 
-/// Applies a rating penalty to each node that voted against the majority by recording a missed vote.
-pub fn apply_l1_penalties(registry: &NodeRegistry, to_penalize: &[NodeId]) -> Result<()> {
+/// Penalizes each node that voted against the majority: records a missed vote, then slashes it for
+/// `SlashingReason::AgainstMajority` with the amounts in `params` (the set active at the block's height), crediting the
+/// removed stake in `state` to the set's slash destination.
+pub fn apply_l1_penalties(
+    registry: &NodeRegistry,
+    state: &State,
+    params: &ProtocolParams,
+    to_penalize: &[NodeId],
+) -> Result<()> {
     for node_id in to_penalize {
         registry.record_vote(node_id, true)?;
     }
-    Ok(())
+    apply_slash_batch(registry, state, params, to_penalize, SlashingReason::AgainstMajority)
 }
 
 #[cfg(test)]
@@ -0,0 +1,510 @@
+//! Per-node rate limiting of consensus messages (votes and block proposals).
+//!
+//! Every vote and proposal arrives as a [`SignedConsensusMessage`] (codec tag 6) and passes through
+//! [`ConsensusAdmission::receive`] (or [`ConsensusAdmission::admit`] / [`ConsensusAdmission::filter_votes`] for
+//! decoded messages) before it reaches vote aggregation (`process_l1_confirmation`) or block confirmation. A message
+//! whose signature does not verify against the node's registered key is dropped without evidence. Per node and
+//! slot (block height) at most `MAX_VOTES_PER_SLOT` votes and `MAX_PROPOSALS_PER_SLOT` proposals are accepted, and
+//! at most `MAX_TRACKED_SLOTS_PER_NODE` slots are tracked per node until `prune_before` releases finalized ones.
+//! Messages over the limit, conflicting votes on the same subject and a second, different proposal in one slot are
+//! dropped and recorded as [`Evidence`], which maps onto a `SlashingReason`. Evidence carries the signed messages
+//! that prove it, so any node holding the registry can check it ([`Evidence::verify`]); [`apply_evidence`] slashes
+//! only evidence that verifies.
+//!
+//! # Determinism
+//! Admission depends only on the sequence of messages seen and the registry; state is kept in ordered maps.
+
+use std::collections::{BTreeMap, BTreeSet};
+use secp256k1::SecretKey;
+use serde::Serialize;
+use crate::core::codec::{decode_message, encode_message};
+use crate::core::confirmation_layer::Vote;
+use crate::core::consensus_params::{MAX_PROPOSALS_PER_SLOT, MAX_TRACKED_SLOTS_PER_NODE, MAX_VOTES_PER_SLOT};
+use crate::core::node_registry::{NodeId, NodeRegistry};
+use crate::core::protocol_params::ProtocolParamsSchedule;
+use crate::core::slashing::{apply_slash_at, SlashingError, SlashingReason};
+use crate::core::state::State;
+use crate::error::Result;
+use crate::signature::{sign_bytes, verify_bytes};
+
+/// A consensus message as seen by the admission path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConsensusMessage {
+    /// Vote on `subject` (transaction or block hash) in `slot`.
+    Vote {
+        node_id: NodeId,
+        slot: u64,
+        subject: String,
+        vote: Vote,
+    },
+    /// Proposal of block `block_hash` for `slot`.
+    Proposal {
+        node_id: NodeId,
+        slot: u64,
+        block_hash: String,
+    },
+}
+
+impl ConsensusMessage {
+    /// Node that sent the message.
+    pub fn node_id(&self) -> &NodeId {
+        match self {
+            ConsensusMessage::Vote { node_id, .. } | ConsensusMessage::Proposal { node_id, .. } => node_id,
+        }
+    }
+
+    pub fn slot(&self) -> u64 {
+        match self {
+            ConsensusMessage::Vote { slot, .. } | ConsensusMessage::Proposal { slot, .. } => *slot,
+        }
+    }
+}
+
+/// A consensus message signed with its node's key over the message's codec encoding (tag 3).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignedConsensusMessage {
+    pub message: ConsensusMessage,
+    pub signature: String,
+}
+
+impl SignedConsensusMessage {
+    /// Signs `message` with the node key.
+    pub fn sign(message: ConsensusMessage, secret_key: &SecretKey) -> Result<Self> {
+        let signature = sign_bytes(secret_key, &encode_message(&message))?.signature_compact;
+        Ok(Self { message, signature })
+    }
+
+    /// True if the signature verifies against the registered public key of the message's node.
+    pub fn verify(&self, registry: &NodeRegistry) -> bool {
+        let Some(node) = registry.get(self.message.node_id()) else {
+            return false;
+        };
+        let key = node.public_key.strip_prefix("Px").unwrap_or(&node.public_key);
+        matches!(verify_bytes(&encode_message(&self.message), &self.signature, key), Ok(true))
+    }
+}
+
+/// Outcome of admitting one message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// Forward to aggregation.
+    Accepted,
+    /// Identical to a message already accepted; drop silently.
+    Duplicate,
+    /// Dropped; evidence was recorded.
+    Rejected,
+    /// Unknown node or a signature that does not verify; dropped without evidence, since it proves nothing.
+    Unauthenticated,
+    /// The node already has `slots_per_node` slots tracked; dropped without evidence.
+    TooManySlots,
+}
+
+/// What a node did wrong.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EvidenceKind {
+    /// Two different votes on the same subject.
+    ConflictingVotes { subject: String },
+    /// Two different block proposals for the same slot.
+    ConflictingProposals { first: String, second: String },
+    /// More votes than `limit` in one slot.
+    VoteFlood { limit: u32 },
+    /// More proposals than `limit` in one slot.
+    ProposalFlood { limit: u32 },
+}
+
+/// Misbehaviour evidence against one node in one slot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Evidence {
+    pub node_id: NodeId,
+    pub slot: u64,
+    #[serde(flatten)]
+    pub kind: EvidenceKind,
+    /// The node's signed messages that show `kind`: the two conflicting messages, or every message of a flood.
+    pub messages: Vec<SignedConsensusMessage>,
+}
+
+impl Evidence {
+    /// Slashing reason for this evidence: conflicting messages are equivocation, floods are spam.
+    pub fn slashing_reason(&self) -> SlashingReason {
+        match self.kind {
+            EvidenceKind::ConflictingVotes { .. } | EvidenceKind::ConflictingProposals { .. } => {
+                SlashingReason::Equivocation
+            }
+            EvidenceKind::VoteFlood { .. } | EvidenceKind::ProposalFlood { .. } => SlashingReason::MessageFlood,
+        }
+    }
+
+    /// True if the evidence proves itself: every message is signed by `node_id` for `slot` (see
+    /// [`SignedConsensusMessage::verify`]) and the messages show `kind` under `limits`: a Confirm and a Reject on
+    /// `subject`, both proposals `first` and `second`, or more distinct votes or proposals than the limit.
+    pub fn verify(&self, registry: &NodeRegistry, limits: &RateLimits) -> bool {
+        let signed = !self.messages.is_empty()
+            && self.messages.iter().all(|m| {
+                m.message.node_id() == &self.node_id && m.message.slot() == self.slot && m.verify(registry)
+            });
+        if !signed {
+            return false;
+        }
+        let votes = || {
+            self.messages.iter().filter_map(|m| match &m.message {
+                ConsensusMessage::Vote { subject, vote, .. } => Some((subject.as_str(), *vote)),
+                ConsensusMessage::Proposal { .. } => None,
+            })
+        };
+        let proposals: BTreeSet<&str> = self
+            .messages
+            .iter()
+            .filter_map(|m| match &m.message {
+                ConsensusMessage::Proposal { block_hash, .. } => Some(block_hash.as_str()),
+                ConsensusMessage::Vote { .. } => None,
+            })
+            .collect();
+        match &self.kind {
+            EvidenceKind::ConflictingVotes { subject } => {
+                let cast = |vote| votes().any(|(s, v)| s == subject && v == vote);
+                cast(Vote::Confirm) && cast(Vote::Reject)
+            }
+            EvidenceKind::ConflictingProposals { first, second } => {
+                first != second && proposals.contains(first.as_str()) && proposals.contains(second.as_str())
+            }
+            EvidenceKind::VoteFlood { limit } => {
+                let subjects: BTreeSet<&str> = votes().map(|(s, _)| s).collect();
+                *limit == limits.votes_per_slot && subjects.len() as u32 > *limit
+            }
+            EvidenceKind::ProposalFlood { limit } => {
+                *limit == limits.proposals_per_slot && proposals.len() as u32 > *limit
+            }
+        }
+    }
+}
+
+/// Slashes the node named by `evidence` for its `slashing_reason`, with the parameters active at the evidence slot
+/// (`apply_slash_at`), once the evidence verifies against `registry` and the protocol default [`RateLimits`].
+/// Returns the stake removed; errors if the evidence does not verify or the node is unknown.
+pub fn apply_evidence(
+    registry: &NodeRegistry,
+    state: &State,
+    schedule: &ProtocolParamsSchedule,
+    evidence: &Evidence,
+) -> Result<u128> {
+    if !evidence.verify(registry, &RateLimits::default()) {
+        return Err(SlashingError::Other(format!("unverified evidence against {}", evidence.node_id)).into());
+    }
+    apply_slash_at(registry, state, schedule, evidence.slot, &evidence.node_id, evidence.slashing_reason())
+}
+
+/// Per-node limits: messages per slot and slots tracked at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimits {
+    pub votes_per_slot: u32,
+    pub proposals_per_slot: u32,
+    pub slots_per_node: u32,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            votes_per_slot: MAX_VOTES_PER_SLOT,
+            proposals_per_slot: MAX_PROPOSALS_PER_SLOT,
+            slots_per_node: MAX_TRACKED_SLOTS_PER_NODE,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SlotActivity {
+    votes: BTreeMap<String, SignedConsensusMessage>,
+    /// Accepted proposals with their block hashes, in arrival order.
+    proposals: Vec<(String, SignedConsensusMessage)>,
+    /// Evidence already raised for a flood in this slot; floods are reported once per slot.
+    flood_reported: bool,
+}
+
+/// Tracks consensus messages per (node, slot) and enforces `RateLimits`.
+#[derive(Debug, Default)]
+pub struct ConsensusAdmission {
+    limits: RateLimits,
+    activity: BTreeMap<(NodeId, u64), SlotActivity>,
+    evidence: Vec<Evidence>,
+}
+
+impl ConsensusAdmission {
+    /// Admission with the protocol default limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limits(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Message intake: decodes a codec-framed [`SignedConsensusMessage`] received from a peer and admits it.
+    /// Errors only if the bytes do not decode.
+    pub fn receive(&mut self, registry: &NodeRegistry, bytes: &[u8]) -> Result<Admission> {
+        let msg = decode_message::<SignedConsensusMessage>(bytes)?;
+        Ok(self.admit(registry, &msg))
+    }
+
+    /// Admits or drops one message: checks its signature against `registry` and the slot cap, then the rate limits,
+    /// recording evidence for conflicts and floods.
+    pub fn admit(&mut self, registry: &NodeRegistry, msg: &SignedConsensusMessage) -> Admission {
+        if !msg.verify(registry) {
+            return Admission::Unauthenticated;
+        }
+        let key = (msg.message.node_id().clone(), msg.message.slot());
+        if !self.activity.contains_key(&key) && self.tracked_slots(&key.0) >= self.limits.slots_per_node {
+            return Admission::TooManySlots;
+        }
+        match &msg.message {
+            ConsensusMessage::Vote { subject, .. } => self.admit_vote(key, subject, msg),
+            ConsensusMessage::Proposal { block_hash, .. } => self.admit_proposal(key, block_hash, msg),
+        }
+    }
+
+    fn tracked_slots(&self, node_id: &NodeId) -> u32 {
+        self.activity.range((node_id.clone(), 0)..=(node_id.clone(), u64::MAX)).count() as u32
+    }
+
+    fn admit_vote(&mut self, (node_id, slot): (NodeId, u64), subject: &str, msg: &SignedConsensusMessage) -> Admission {
+        let limit = self.limits.votes_per_slot;
+        let activity = self.activity.entry((node_id.clone(), slot)).or_default();
+        let (kind, messages) = match activity.votes.get(subject) {
+            Some(prev) if prev.message == msg.message => return Admission::Duplicate,
+            Some(prev) => (
+                EvidenceKind::ConflictingVotes {
+                    subject: subject.to_string(),
+                },
+                vec![prev.clone(), msg.clone()],
+            ),
+            None if activity.votes.len() as u32 >= limit => {
+                if activity.flood_reported {
+                    return Admission::Rejected;
+                }
+                activity.flood_reported = true;
+                let mut messages: Vec<_> = activity.votes.values().cloned().collect();
+                messages.push(msg.clone());
+                (EvidenceKind::VoteFlood { limit }, messages)
+            }
+            None => {
+                activity.votes.insert(subject.to_string(), msg.clone());
+                return Admission::Accepted;
+            }
+        };
+        self.evidence.push(Evidence {
+            node_id,
+            slot,
+            kind,
+            messages,
+        });
+        Admission::Rejected
+    }
+
+    fn admit_proposal(
+        &mut self,
+        (node_id, slot): (NodeId, u64),
+        block_hash: &str,
+        msg: &SignedConsensusMessage,
+    ) -> Admission {
+        let limit = self.limits.proposals_per_slot;
+        let activity = self.activity.entry((node_id.clone(), slot)).or_default();
+        if activity.proposals.iter().any(|(h, _)| h == block_hash) {
+            return Admission::Duplicate;
+        }
+        if activity.proposals.len() as u32 >= limit {
+            // With one proposal per slot, any second block is equivocation; otherwise it is a flood.
+            let evidence = match activity.proposals.first() {
+                Some((first, first_msg)) if limit == 1 => Some((
+                    EvidenceKind::ConflictingProposals {
+                        first: first.clone(),
+                        second: block_hash.to_string(),
+                    },
+                    vec![first_msg.clone(), msg.clone()],
+                )),
+                _ if !activity.flood_reported => {
+                    activity.flood_reported = true;
+                    let mut messages: Vec<_> = activity.proposals.iter().map(|(_, m)| m.clone()).collect();
+                    messages.push(msg.clone());
+                    Some((EvidenceKind::ProposalFlood { limit }, messages))
+                }
+                _ => None,
+            };
+            if let Some((kind, messages)) = evidence {
+                self.evidence.push(Evidence {
+                    node_id,
+                    slot,
+                    kind,
+                    messages,
+                });
+            }
+            return Admission::Rejected;
+        }
+        activity.proposals.push((block_hash.to_string(), msg.clone()));
+        Admission::Accepted
+    }
+
+    /// Admits signed L1 votes on `subject` in `slot` and returns the accepted ones, in input order, for
+    /// `process_l1_confirmation`. Messages for another slot or subject, and proposals, are skipped.
+    pub fn filter_votes(
+        &mut self,
+        registry: &NodeRegistry,
+        slot: u64,
+        subject: &str,
+        votes: &[SignedConsensusMessage],
+    ) -> Vec<(NodeId, Vote)> {
+        votes
+            .iter()
+            .filter_map(|msg| match &msg.message {
+                ConsensusMessage::Vote { node_id, slot: s, subject: sub, vote } if *s == slot && sub == subject => {
+                    (self.admit(registry, msg) == Admission::Accepted).then(|| (node_id.clone(), *vote))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns and clears the evidence collected so far.
+    pub fn take_evidence(&mut self) -> Vec<Evidence> {
+        std::mem::take(&mut self.evidence)
+    }
+
+    /// Forgets activity for slots below `slot` (e.g. once they are finalized).
+    pub fn prune_before(&mut self, slot: u64) {
+        self.activity.retain(|(_, s), _| *s >= slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{DeterministicRng, TestAccount};
+
+    fn registry(accounts: &[(&str, &TestAccount)]) -> NodeRegistry {
+        let registry = NodeRegistry::new();
+        for (node, account) in accounts {
+            registry.register(node.to_string(), account.pub_main(), 1_000, 10).unwrap();
+        }
+        registry
+    }
+
+    fn vote(account: &TestAccount, node: &str, slot: u64, subject: &str, vote: Vote) -> SignedConsensusMessage {
+        let message = ConsensusMessage::Vote {
+            node_id: node.to_string(),
+            slot,
+            subject: subject.to_string(),
+            vote,
+        };
+        SignedConsensusMessage::sign(message, &account.main).unwrap()
+    }
+
+    #[test]
+    fn test_vote_flood_and_conflict_produce_evidence() {
+        let mut rng = DeterministicRng::new(3994);
+        let (n1, n2) = (rng.account(), rng.account());
+        let reg = registry(&[("n1", &n1), ("n2", &n2)]);
+        let limits = RateLimits {
+            votes_per_slot: 2,
+            proposals_per_slot: 1,
+            ..RateLimits::default()
+        };
+        let mut adm = ConsensusAdmission::with_limits(limits);
+        let mut admit = |msg| adm.admit(&reg, &msg);
+        assert_eq!(admit(vote(&n1, "n1", 5, "a", Vote::Confirm)), Admission::Accepted);
+        assert_eq!(admit(vote(&n1, "n1", 5, "a", Vote::Confirm)), Admission::Duplicate);
+        assert_eq!(admit(vote(&n1, "n1", 5, "a", Vote::Reject)), Admission::Rejected);
+        assert_eq!(admit(vote(&n1, "n1", 5, "b", Vote::Confirm)), Admission::Accepted);
+        assert_eq!(admit(vote(&n1, "n1", 5, "c", Vote::Confirm)), Admission::Rejected);
+        assert_eq!(admit(vote(&n1, "n1", 5, "d", Vote::Confirm)), Admission::Rejected);
+        // Other nodes and slots are unaffected.
+        assert_eq!(admit(vote(&n2, "n2", 5, "c", Vote::Confirm)), Admission::Accepted);
+        assert_eq!(admit(vote(&n1, "n1", 6, "c", Vote::Confirm)), Admission::Accepted);
+
+        let evidence = adm.take_evidence();
+        assert_eq!(evidence.len(), 2);
+        assert_eq!(evidence[0].slashing_reason(), SlashingReason::Equivocation);
+        assert_eq!(evidence[1].kind, EvidenceKind::VoteFlood { limit: 2 });
+        assert_eq!(evidence[1].slashing_reason(), SlashingReason::MessageFlood);
+        assert!(evidence.iter().all(|e| e.verify(&reg, &limits)));
+        // A flood only counts against the limit it was raised under.
+        assert!(!evidence[1].verify(&reg, &RateLimits::default()));
+        assert!(adm.take_evidence().is_empty());
+    }
+
+    #[test]
+    fn test_second_proposal_in_slot_is_equivocation() {
+        let n1 = DeterministicRng::new(3995).account();
+        let reg = registry(&[("n1", &n1)]);
+        let mut adm = ConsensusAdmission::new();
+        let propose = |hash: &str| {
+            let message = ConsensusMessage::Proposal {
+                node_id: "n1".to_string(),
+                slot: 9,
+                block_hash: hash.to_string(),
+            };
+            SignedConsensusMessage::sign(message, &n1.main).unwrap()
+        };
+        assert_eq!(adm.admit(&reg, &propose("b1")), Admission::Accepted);
+        assert_eq!(adm.admit(&reg, &propose("b1")), Admission::Duplicate);
+        assert_eq!(adm.admit(&reg, &propose("b2")), Admission::Rejected);
+        let evidence = adm.take_evidence();
+        assert_eq!(
+            evidence,
+            vec![Evidence {
+                node_id: "n1".to_string(),
+                slot: 9,
+                kind: EvidenceKind::ConflictingProposals {
+                    first: "b1".to_string(),
+                    second: "b2".to_string(),
+                },
+                messages: vec![propose("b1"), propose("b2")],
+            }]
+        );
+        assert!(evidence[0].verify(&reg, &RateLimits::default()));
+        adm.prune_before(10);
+        assert_eq!(adm.admit(&reg, &propose("b2")), Admission::Accepted);
+    }
+
+    #[test]
+    fn test_intake_checks_signatures_and_caps_slots() {
+        let mut rng = DeterministicRng::new(3996);
+        let (n1, mallory) = (rng.account(), rng.account());
+        let reg = registry(&[("n1", &n1)]);
+        let mut adm = ConsensusAdmission::with_limits(RateLimits {
+            slots_per_node: 2,
+            ..RateLimits::default()
+        });
+
+        // Wrong key or unknown node: dropped without evidence, so nobody can frame n1.
+        let forged = [vote(&mallory, "n1", 1, "a", Vote::Confirm), vote(&mallory, "n1", 1, "a", Vote::Reject)];
+        assert!(forged.iter().all(|m| adm.admit(&reg, m) == Admission::Unauthenticated));
+        assert_eq!(adm.admit(&reg, &vote(&mallory, "mallory", 1, "a", Vote::Confirm)), Admission::Unauthenticated);
+        assert!(adm.take_evidence().is_empty());
+
+        let received = |adm: &mut ConsensusAdmission, slot| {
+            adm.receive(&reg, &encode_message(&vote(&n1, "n1", slot, "a", Vote::Confirm))).unwrap()
+        };
+        assert_eq!(received(&mut adm, 1), Admission::Accepted);
+        assert_eq!(received(&mut adm, 2), Admission::Accepted);
+        assert_eq!(received(&mut adm, u64::MAX), Admission::TooManySlots);
+        adm.prune_before(2);
+        assert_eq!(received(&mut adm, u64::MAX), Admission::Accepted);
+        assert!(adm.receive(&reg, b"\x01\x06junk").is_err());
+
+        // Accepted votes feed aggregation; evidence with a swapped-in forged message does not verify.
+        let votes = [vote(&n1, "n1", 3, "tx", Vote::Confirm), vote(&n1, "n1", 4, "tx", Vote::Confirm)];
+        let mut adm = ConsensusAdmission::new();
+        assert_eq!(adm.filter_votes(&reg, 3, "tx", &votes), vec![("n1".to_string(), Vote::Confirm)]);
+        assert_eq!(adm.admit(&reg, &vote(&n1, "n1", 3, "tx", Vote::Reject)), Admission::Rejected);
+        let evidence = adm.take_evidence().remove(0);
+        let mut forged = evidence.clone();
+        forged.messages[1] = vote(&mallory, "n1", 3, "tx", Vote::Reject);
+        let (state, schedule) = (State::new(), ProtocolParamsSchedule::default());
+        assert!(apply_evidence(&reg, &state, &schedule, &forged).is_err());
+        assert_eq!(reg.get(&"n1".to_string()).unwrap().stake, 1_000);
+        assert_eq!(apply_evidence(&reg, &state, &schedule, &evidence).unwrap(), 100);
+    }
+}
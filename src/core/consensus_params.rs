@@ -23,3 +23,30 @@ pub const FAUCET_ADDRESS: &str = "faucet";
 /// Allows parallel HTTP submits after Gateway `/api/nonce/allocate` without
 /// requiring in-order arrival. Packing still requires consecutive nonces.
 pub const MEMPOOL_MAX_NONCE_GAP: u64 = 64;
+
+/// Minimum `fee_uplp` admitted at each overload level (index = `OverloadLevel as usize`).
+/// Level 0 is the normal protocol minimum.
+pub const OVERLOAD_MIN_FEE_UPLP: [u128; 4] = [1, 10, 100, 1_000];
+
+/// Max pending mempool transactions per sender at each overload level.
+pub const OVERLOAD_MAX_PENDING_PER_SENDER: [usize; 4] = [
+    MEMPOOL_MAX_NONCE_GAP as usize,
+    16,
+    4,
+    1,
+];
+
+/// Misbehaviour strikes after which the network layer should deprioritize a peer's gossip,
+/// per overload level. `u32::MAX` means never.
+pub const OVERLOAD_PEER_STRIKE_LIMIT: [u32; 4] = [u32::MAX, 10, 3, 1];
+
+/// Max votes one validator may cast per slot (block height). A validator votes at most once per
+/// transaction, so this is the block transaction cap.
+pub const MAX_VOTES_PER_SLOT: u32 = BLOCK_MAX_TX_COUNT as u32;
+
+/// Max block proposals one validator may send per slot.
+pub const MAX_PROPOSALS_PER_SLOT: u32 = 1;
+
+/// Max slots admission tracks per validator at once. Messages for further slots are dropped until finalized slots
+/// are pruned, so a validator cannot grow the admission state by voting on arbitrary heights.
+pub const MAX_TRACKED_SLOTS_PER_NODE: u32 = 64;
@@ -1,32 +1,45 @@
 //! JSON-RPC 2.0 server for Gateway native Core binding.
 //! Newline-delimited JSON over TCP or Unix domain socket.
+//!
+//! Most methods are stateless (`dispatch_rpc`). `mempool_content` pages the node's own mempool, so it is served
+//! through an [`RpcContext`] holding that mempool. It is public-facing, so each connection is rate limited
+//! (token bucket, [`RpcLimits::requests_per_sec`] with [`RpcLimits::burst`]), pages are capped in count and
+//! encoded size, and each page costs `O(log n + page)` from the mempool's sorted index, never a sort of the pool.
 
 use crate::core::asset::Asset;
 use crate::core::block_proposal_cli::{
     block_proposal_status_json, mempool_admit_json, min_fee_from_load_cli, select_block_txs_json,
+    select_block_txs_stored_json,
 };
 use crate::core::consensus_cli::{
     assemble_block_json, l1_process_votes_json, l1_verify_txs_json, l2_process_votes_json,
 };
+use crate::core::invariants::invariant_monitor;
+use crate::core::load::{BlockLoadSample, NetworkLoad};
+use crate::core::mempool::Mempool;
 use crate::core::state_file::{
     init_state_file, state_apply_tx_json, state_credit_json, state_query_json, state_root_json,
     state_validate_tx_json,
 };
-use crate::core::transaction::Transaction;
-use crate::core::validator_selection::{
-    committee_count, select_n_by_weight, selection_percent_from_load_pct,
-};
+use crate::core::state_sync::{state_sync_chunk_json, state_sync_manifest_json, DEFAULT_CHUNK_ACCOUNTS};
+use crate::core::transaction::{SigningPayload, Transaction};
+use crate::core::validator_selection::select_n_by_weight;
 use crate::error::{PlatariumError, Result};
+use crate::storage::recent_load_samples;
 use crate::signature::normalize_signature_hex;
 use crate::signer::sign_with_both_keys;
-use crate::{
-    generate_alphanumeric_part, generate_mnemonic, validate_mnemonic, verify_signature, KeyGenerator,
+use crate::mnemonic::{
+    generate_mnemonic_with, mnemonic_language, parse_language, validate_mnemonic_in, Language, MnemonicConfig,
+    DEFAULT_MNEMONIC_WORDS,
 };
+use crate::{generate_alphanumeric_part, verify_signature, DerivationPath, KeyGenerator};
 use serde_json::{json, Value};
 use std::collections::HashSet;
 use std::io::{BufRead, BufReader, Write};
 use std::net::TcpListener;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 fn param_str(params: &Value, key: &str) -> Result<String> {
     params
@@ -86,6 +99,24 @@ fn param_opt_str(params: &Value, key: &str) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Optional `language` param (BIP39 wordlist name); English when absent.
+/// Load from `pending_count` and the last `LOAD_WINDOW_BLOCKS` headers of the chain stored at `db_path`; without
+/// `db_path`, from the `blocks` samples (JSON array) if given, else the mempool count alone.
+fn param_network_load(params: &Value) -> Result<NetworkLoad> {
+    let pending = param_usize(params, "pending_count")?;
+    let blocks: Vec<BlockLoadSample> = match (param_opt_str(params, "db_path"), param_opt_str(params, "blocks")) {
+        (Some(db_path), _) => recent_load_samples(&crate::storage::RocksStore::open(Path::new(&db_path))?)?,
+        (None, Some(s)) => serde_json::from_str(&s)
+            .map_err(|e| PlatariumError::State(format!("invalid blocks JSON: {}", e)))?,
+        (None, None) => Vec::new(),
+    };
+    Ok(NetworkLoad::estimate(pending, &blocks))
+}
+
+fn param_language(params: &Value) -> Result<Language> {
+    param_opt_str(params, "language").map_or(Ok(Language::English), |name| parse_language(&name))
+}
+
 /// Dispatch one JSON-RPC method to Core logic. Returns JSON result string.
 pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
     match method {
@@ -124,6 +155,18 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
             let path = param_str(params, "state_file")?;
             state_root_json(Path::new(&path))
         }
+        "state_sync_manifest" => {
+            let path = param_str(params, "state_file")?;
+            let chunk_accounts = param_usize(params, "chunk_accounts").unwrap_or(DEFAULT_CHUNK_ACCOUNTS);
+            state_sync_manifest_json(Path::new(&path), chunk_accounts)
+        }
+        "state_sync_chunk" => {
+            let path = param_str(params, "state_file")?;
+            let index = param_u64(params, "index")?;
+            let index = u32::try_from(index).map_err(|_| PlatariumError::State("index out of range".into()))?;
+            let chunk_accounts = param_usize(params, "chunk_accounts").unwrap_or(DEFAULT_CHUNK_ACCOUNTS);
+            state_sync_chunk_json(Path::new(&path), index, chunk_accounts)
+        }
 
         "validate_tx" => {
             let tx = param_str(params, "tx")?;
@@ -173,6 +216,20 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
             let pending = param_usize(params, "pending_count")?;
             min_fee_from_load_cli(pending)
         }
+        "network_load" => {
+            let load = param_network_load(params)?;
+            Ok(json!({
+                "pending_tx_count": load.pending_tx_count,
+                "avg_tps": load.avg_tps,
+                "network_load_pct": load.network_load_pct,
+                "min_fee_uplp": load.min_fee_uplp(),
+                "max_transactions_per_block": load.max_transactions_per_block(),
+                "max_block_size_bytes": load.max_block_size_bytes(),
+                "max_block_time_sec": load.max_block_time_sec(),
+                "l1_selection_percent": load.l1_selection_percent()?,
+            })
+            .to_string())
+        }
         "mempool_admit" => {
             let path = param_str(params, "state_file")?;
             let tx = param_str(params, "tx")?;
@@ -184,10 +241,22 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
             let now_unix = param_i64(params, "now_unix")?;
             block_proposal_status_json(&mempool_txs, now_unix)
         }
+        "invariant_status" => Ok(json!(invariant_monitor().status()).to_string()),
+        "invariant_resume_block_production" => {
+            invariant_monitor().resume_block_production();
+            Ok(json!(invariant_monitor().status()).to_string())
+        }
+        "block_size_target" => {
+            let db_path = param_str(params, "db_path")?;
+            crate::storage::rpc::rocks_block_size_target_json(&db_path)
+        }
         "select_block_txs" => {
             let path = param_str(params, "state_file")?;
             let mempool_txs = param_str(params, "mempool_txs")?;
-            select_block_txs_json(Path::new(&path), &mempool_txs)
+            match param_opt_str(params, "db_path") {
+                Some(db_path) => select_block_txs_stored_json(Path::new(&path), &mempool_txs, Path::new(&db_path)),
+                None => select_block_txs_json(Path::new(&path), &mempool_txs),
+            }
         }
 
         "rocks_get_head" => {
@@ -214,6 +283,41 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
             let address = param_str(params, "address")?;
             crate::storage::rpc::rocks_list_address_txs_json(&db_path, &address)
         }
+        "rocks_list_address_receipts" => {
+            let db_path = param_str(params, "db_path")?;
+            let address = param_str(params, "address")?;
+            let cursor = param_opt_str(params, "cursor");
+            let limit = param_usize(params, "limit").unwrap_or(crate::storage::MAX_RECEIPT_PAGE_SIZE);
+            crate::storage::rpc::rocks_list_address_receipts_json(&db_path, &address, cursor.as_deref(), limit)
+        }
+        "rocks_list_slashing_events" => {
+            let db_path = param_str(params, "db_path")?;
+            let node_id = param_opt_str(params, "node_id");
+            let from = param_u64(params, "from").unwrap_or(0);
+            let to = param_u64(params, "to").unwrap_or(u64::MAX);
+            crate::storage::rpc::rocks_list_slashing_events_json(&db_path, node_id.as_deref(), from, to)
+        }
+        "rocks_fee_bucket_history" => {
+            let db_path = param_str(params, "db_path")?;
+            let from = param_u64(params, "from").unwrap_or(0);
+            let to = param_u64(params, "to").unwrap_or(u64::MAX);
+            crate::storage::rpc::rocks_fee_bucket_history_json(&db_path, from, to)
+        }
+        "rocks_address_bloom_scan" => {
+            let db_path = param_str(params, "db_path")?;
+            let address = param_str(params, "address")?;
+            let from = param_u64(params, "from").unwrap_or(0);
+            let to = param_u64(params, "to").unwrap_or(u64::MAX);
+            crate::storage::rpc::rocks_address_bloom_scan_json(&db_path, &address, from, to)
+        }
+        "rocks_message_proof" => {
+            let db_path = param_str(params, "db_path")?;
+            let height = param_u64(params, "height")?;
+            let index = param_u64(params, "index")?;
+            let index = u32::try_from(index)
+                .map_err(|_| PlatariumError::Validation(format!("index {} out of range", index)))?;
+            crate::storage::rpc::rocks_message_proof_json(&db_path, height, index)
+        }
         "rocks_commit_block" => {
             let db_path = param_str(params, "db_path")?;
             let commit = param_str(params, "commit")?;
@@ -236,16 +340,15 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
         }
 
         "selection_percent_from_load" => {
-            let load_pct = param_u64(params, "load_pct")?;
-            let percent = selection_percent_from_load_pct(load_pct)
-                .map_err(|e| PlatariumError::State(e.to_string()))?;
-            Ok(json!({"percent": percent}).to_string())
+            let load = param_network_load(params)?;
+            let percent = load.l1_selection_percent()?;
+            Ok(json!({"percent": percent, "network_load_pct": load.network_load_pct}).to_string())
         }
         "committee_count" => {
             let candidates = param_usize(params, "candidates")?;
-            let load_pct = param_u64(params, "load_pct")?;
-            let count = committee_count(candidates, load_pct);
-            Ok(json!({"count": count}).to_string())
+            let load = param_network_load(params)?;
+            let count = load.committee_count(candidates);
+            Ok(json!({"count": count, "network_load_pct": load.network_load_pct}).to_string())
         }
         "select_committee" => {
             #[derive(serde::Deserialize)]
@@ -273,21 +376,24 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
         }
 
         "generate_mnemonic" => {
-            let (mnemonic, alphanumeric) = generate_mnemonic()?;
+            let words = param_u64(params, "words").map_or(DEFAULT_MNEMONIC_WORDS, |w| w as usize);
+            let config = MnemonicConfig::new(words)?.with_language(param_language(params)?);
+            let (mnemonic, alphanumeric) = generate_mnemonic_with(&config)?;
             Ok(json!({"mnemonic": mnemonic, "alphanumeric": alphanumeric}).to_string())
         }
 
         "generate_keys" => {
             let mnemonic = param_str(params, "mnemonic")?;
-            if !validate_mnemonic(&mnemonic) {
+            let language = param_language(params)?;
+            if !validate_mnemonic_in(&mnemonic, language) {
                 return Err(PlatariumError::State("Invalid mnemonic phrase".into()));
             }
             let alphanumeric_part = param_opt_str(params, "alphanumeric").unwrap_or_else(|| {
                 generate_alphanumeric_part(12).unwrap_or_default()
             });
             let seed_index = param_u64(params, "seed_index").unwrap_or(0) as u32;
-            let path = param_opt_str(params, "path");
-            let key_gen = KeyGenerator::new(seed_index, None, None, path.clone())?;
+            let path = param_opt_str(params, "path").map(|p| p.parse::<DerivationPath>()).transpose()?;
+            let key_gen = KeyGenerator::new(seed_index, None, None, path)?.with_language(language);
             let keys = key_gen.restore_keys(&mnemonic, &alphanumeric_part, seed_index, path)?;
             Ok(json!({
                 "publicKey": keys.public_key,
@@ -313,24 +419,13 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
             let message_str = param_str(params, "message")?;
             let mnemonic = param_str(params, "mnemonic")?;
             let alphanumeric = param_str(params, "alphanumeric")?;
-            if !validate_mnemonic(&mnemonic) {
+            if mnemonic_language(&mnemonic).is_err() {
                 return Err(PlatariumError::State("Invalid mnemonic phrase".into()));
             }
             let message: Value = serde_json::from_str(&message_str)
                 .map_err(|e| PlatariumError::State(format!("Invalid JSON message: {}", e)))?;
             let signature_result = sign_with_both_keys(&message, &mnemonic, &alphanumeric)?;
-            Ok(json!({
-                "hash": signature_result.hash,
-                "signatures": signature_result.signatures.iter().map(|s| json!({
-                    "sig_type": s.sig_type,
-                    "r": s.r,
-                    "s": s.s,
-                    "pub_key": s.pub_key,
-                    "der": s.der,
-                    "signature_compact": s.signature_compact,
-                })).collect::<Vec<_>>(),
-            })
-            .to_string())
+            Ok(serde_json::to_string(&signature_result).map_err(|e| PlatariumError::State(e.to_string()))?)
         }
 
         "sign_transaction" => {
@@ -352,7 +447,7 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
                 .to_string();
             let mnemonic = param_str(params, "mnemonic")?;
             let alphanumeric = param_str(params, "alphanumeric")?;
-            if !validate_mnemonic(&mnemonic) {
+            if mnemonic_language(&mnemonic).is_err() {
                 return Err(PlatariumError::State("Invalid mnemonic phrase".into()));
             }
             let reads_vec: Vec<String> = serde_json::from_str(&reads)
@@ -368,34 +463,11 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
             } else {
                 Asset::Token(asset.clone())
             };
-            let canonical_asset = asset_enum.as_canonical();
-            let mut reads_sorted: Vec<String> = reads_set.iter().cloned().collect();
-            reads_sorted.sort();
-            let mut writes_sorted: Vec<String> = writes_set.iter().cloned().collect();
-            writes_sorted.sort();
-            #[derive(serde::Serialize)]
-            struct TxHashData {
-                from: String,
-                to: String,
-                asset: String,
-                amount: u128,
-                fee_uplp: u128,
-                nonce: u64,
-                reads: Vec<String>,
-                writes: Vec<String>,
-            }
             let amount_u128 = amount as u128;
             let fee_uplp_u128 = fee_uplp as u128;
-            let message = TxHashData {
-                from: from.clone(),
-                to: to.clone(),
-                asset: canonical_asset,
-                amount: amount_u128,
-                fee_uplp: fee_uplp_u128,
-                nonce,
-                reads: reads_sorted,
-                writes: writes_sorted,
-            };
+            let message = SigningPayload::new(&from, &to, &asset_enum, amount_u128, fee_uplp_u128, nonce)
+                .reads(&reads_set)
+                .writes(&writes_set);
             let sig_result = sign_with_both_keys(&message, &mnemonic, &alphanumeric)?;
             let sig_main = normalize_signature_hex(&sig_result.signatures[0].signature_compact);
             let sig_derived = normalize_signature_hex(&sig_result.signatures[1].signature_compact);
@@ -426,6 +498,11 @@ pub fn dispatch_rpc(method: &str, params: &Value) -> Result<String> {
 }
 
 pub fn handle_rpc_line(line: &str) -> String {
+    respond(line, dispatch_rpc)
+}
+
+/// Parses one request line, runs `dispatch` and wraps its result in a JSON-RPC response.
+fn respond(line: &str, dispatch: impl FnOnce(&str, &Value) -> Result<String>) -> String {
     let req: Value = match serde_json::from_str(line) {
         Ok(v) => v,
         Err(e) => {
@@ -451,7 +528,7 @@ pub fn handle_rpc_line(line: &str) -> String {
         .to_string();
     }
 
-    match dispatch_rpc(method, &params) {
+    match dispatch(method, &params) {
         Ok(result_str) => {
             let result: Value =
                 serde_json::from_str(&result_str).unwrap_or(Value::String(result_str));
@@ -471,7 +548,129 @@ pub fn handle_rpc_line(line: &str) -> String {
     }
 }
 
-fn serve_connection<S: std::io::Read + Write + Send + 'static>(stream: S) {
+/// Default most transactions per `mempool_content` page.
+pub const MEMPOOL_CONTENT_MAX_PAGE: usize = 500;
+
+/// Default cap on the encoded transactions in one `mempool_content` page (1 MiB).
+pub const MEMPOOL_CONTENT_MAX_BYTES: usize = 1024 * 1024;
+
+/// Per-connection limits of the public `mempool_content` method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcLimits {
+    /// Upper bound on the `limit` param.
+    pub max_page_size: usize,
+    /// Upper bound on a page's encoded transactions, in bytes.
+    pub max_response_bytes: usize,
+    /// Sustained `mempool_content` calls per second per connection.
+    pub requests_per_sec: u32,
+    /// Calls a connection may make at once before the sustained rate applies.
+    pub burst: u32,
+}
+
+impl Default for RpcLimits {
+    fn default() -> Self {
+        Self {
+            max_page_size: MEMPOOL_CONTENT_MAX_PAGE,
+            max_response_bytes: MEMPOOL_CONTENT_MAX_BYTES,
+            requests_per_sec: 10,
+            burst: 20,
+        }
+    }
+}
+
+/// Token bucket of one connection. Node-local and time-based; it never affects consensus.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    /// Thousandths of a request.
+    tokens: u64,
+    capacity: u64,
+    /// Thousandths of a request regained per millisecond (= requests per second).
+    refill_per_ms: u64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    /// Full bucket of `burst` requests, refilled at `requests_per_sec`.
+    pub fn new(requests_per_sec: u32, burst: u32) -> Self {
+        let capacity = burst as u64 * 1000;
+        Self {
+            tokens: capacity,
+            capacity,
+            refill_per_ms: requests_per_sec as u64,
+            last: Instant::now(),
+        }
+    }
+
+    /// Takes one request at `now`. Errors with the wait until the next request is allowed.
+    pub fn check(&mut self, now: Instant) -> Result<()> {
+        let elapsed_ms = now.saturating_duration_since(self.last).as_millis() as u64;
+        self.last += Duration::from_millis(elapsed_ms);
+        self.tokens = self
+            .tokens
+            .saturating_add(elapsed_ms.saturating_mul(self.refill_per_ms))
+            .min(self.capacity);
+        if self.tokens >= 1000 {
+            self.tokens -= 1000;
+            return Ok(());
+        }
+        let wait_ms = (1000 - self.tokens).div_ceil(self.refill_per_ms.max(1));
+        Err(PlatariumError::State(format!("rate limit exceeded; retry in {} ms", wait_ms)))
+    }
+}
+
+/// Node-local state of a server: the mempool paged by `mempool_content` and the per-connection limits.
+#[derive(Debug, Clone)]
+pub struct RpcContext {
+    mempool: Arc<Mempool>,
+    limits: RpcLimits,
+}
+
+impl RpcContext {
+    /// Serves `mempool` (e.g. `Core::shared_mempool`) with the default limits.
+    pub fn new(mempool: Arc<Mempool>) -> Self {
+        Self {
+            mempool,
+            limits: RpcLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: RpcLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Fresh rate limiter for a new connection.
+    pub fn connection_limiter(&self) -> RateLimiter {
+        RateLimiter::new(self.limits.requests_per_sec, self.limits.burst)
+    }
+
+    /// Like [`handle_rpc_line`], also serving `mempool_content` under the calling connection's `limiter`.
+    pub fn handle_line(&self, limiter: &mut RateLimiter, line: &str) -> String {
+        respond(line, |method, params| self.dispatch(limiter, method, params))
+    }
+
+    fn dispatch(&self, limiter: &mut RateLimiter, method: &str, params: &Value) -> Result<String> {
+        match method {
+            "mempool_content" => {
+                limiter.check(Instant::now())?;
+                let limit = match params.get("limit") {
+                    Some(_) => param_usize(params, "limit")?,
+                    None => self.limits.max_page_size,
+                };
+                let page = self.mempool.content_page(
+                    param_opt_str(params, "cursor").as_deref(),
+                    limit.clamp(1, self.limits.max_page_size.max(1)),
+                    self.limits.max_response_bytes,
+                )?;
+                Ok(json!(page).to_string())
+            }
+            other => dispatch_rpc(other, params),
+        }
+    }
+}
+
+fn serve_connection<S: std::io::Read + Write + Send + 'static>(stream: S, ctx: Arc<RpcContext>) {
+    let mut limiter = ctx.connection_limiter();
     let mut reader = BufReader::new(stream);
     loop {
         let mut line = String::new();
@@ -481,7 +680,7 @@ fn serve_connection<S: std::io::Read + Write + Send + 'static>(stream: S) {
                 if line.trim().is_empty() {
                     continue;
                 }
-                let response = handle_rpc_line(&line);
+                let response = ctx.handle_line(&mut limiter, &line);
                 if writeln!(reader.get_mut(), "{}", response).is_err() {
                     break;
                 }
@@ -491,8 +690,15 @@ fn serve_connection<S: std::io::Read + Write + Send + 'static>(stream: S) {
     }
 }
 
-/// Run JSON-RPC server on TCP `host:port` or Unix socket `unix:/path` (Unix only).
+/// Run JSON-RPC server on TCP `host:port` or Unix socket `unix:/path` (Unix only). The standalone server has no
+/// node attached, so `mempool_content` pages an empty pool; nodes use [`run_serve_with`].
 pub fn run_serve(listen: &str) -> Result<()> {
+    run_serve_with(listen, RpcContext::new(Arc::new(Mempool::new())))
+}
+
+/// Like [`run_serve`], serving `ctx`'s mempool and limits.
+pub fn run_serve_with(listen: &str, ctx: RpcContext) -> Result<()> {
+    let ctx = Arc::new(ctx);
     if let Some(path) = listen.strip_prefix("unix:") {
         #[cfg(unix)]
         {
@@ -504,7 +710,8 @@ pub fn run_serve(listen: &str) -> Result<()> {
             for stream in listener.incoming() {
                 match stream {
                     Ok(s) => {
-                        std::thread::spawn(move || serve_connection(s));
+                        let ctx = Arc::clone(&ctx);
+                        std::thread::spawn(move || serve_connection(s, ctx));
                     }
                     Err(e) => eprintln!("[core-rpc] accept error: {}", e),
                 }
@@ -522,7 +729,8 @@ pub fn run_serve(listen: &str) -> Result<()> {
         for stream in listener.incoming() {
             match stream {
                 Ok(s) => {
-                    std::thread::spawn(move || serve_connection(s));
+                    let ctx = Arc::clone(&ctx);
+                    std::thread::spawn(move || serve_connection(s, ctx));
                 }
                 Err(e) => eprintln!("[core-rpc] accept error: {}", e),
             }
@@ -548,4 +756,93 @@ mod tests {
         assert!(resp.contains("\"result\""));
         assert!(resp.contains("\"id\":1"));
     }
+
+    #[test]
+    fn test_dispatch_network_load() {
+        let blocks = r#"[{"block_number":2,"timestamp":105,"tx_count":400},{"block_number":1,"timestamp":100,"tx_count":0}]"#;
+        let out = dispatch_rpc("network_load", &json!({"pending_count": 10, "blocks": blocks})).unwrap();
+        let v: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(v["avg_tps"], json!(80));
+        assert_eq!(v["network_load_pct"], json!(80));
+        assert_eq!(v["min_fee_uplp"], json!(3));
+        let idle: Value = serde_json::from_str(&dispatch_rpc("network_load", &json!({"pending_count": 0})).unwrap()).unwrap();
+        assert_eq!(idle["network_load_pct"], json!(0));
+    }
+
+    #[test]
+    fn test_dispatch_block_size_target() {
+        // The history comes from the node's store, not from the caller.
+        assert!(dispatch_rpc("block_size_target", &json!({"block_sizes": "[1048576, 1048576]"})).is_err());
+        let dir = tempfile::TempDir::new().unwrap();
+        let db_path = dir.path().join("db");
+        let out = dispatch_rpc("block_size_target", &json!({"db_path": db_path.to_str().unwrap()})).unwrap();
+        let v: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(v["height"], json!(1));
+        assert_eq!(v["target_bytes"], json!(crate::core::block_assembly::DEFAULT_MAX_BLOCK_SIZE));
+    }
+
+    #[test]
+    fn test_rate_limiter_refills_per_connection() {
+        let mut limiter = RateLimiter::new(2, 3);
+        let start = limiter.last;
+        for _ in 0..3 {
+            limiter.check(start).unwrap();
+        }
+        let err = limiter.check(start).unwrap_err();
+        assert!(err.to_string().contains("retry in 500 ms"), "{}", err);
+        limiter.check(start + Duration::from_millis(500)).unwrap();
+        assert!(limiter.check(start + Duration::from_millis(700)).is_err());
+        // Idle time refills only up to the burst.
+        let later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            limiter.check(later).unwrap();
+        }
+        assert!(limiter.check(later).is_err());
+    }
+
+    #[test]
+    fn test_mempool_content_pages_and_limits() {
+        let mempool = Arc::new(Mempool::new());
+        for i in 0..3u64 {
+            let tx = Transaction::new(
+                format!("s{}", i),
+                "r".into(),
+                Asset::PLP,
+                1,
+                1,
+                i,
+                HashSet::new(),
+                HashSet::new(),
+                String::new(),
+                String::new(),
+            )
+            .unwrap();
+            mempool.add_transaction(tx).unwrap();
+        }
+        let ctx = RpcContext::new(mempool).with_limits(RpcLimits {
+            max_page_size: 2,
+            max_response_bytes: MEMPOOL_CONTENT_MAX_BYTES,
+            requests_per_sec: 1,
+            burst: 2,
+        });
+        let mut limiter = ctx.connection_limiter();
+        let call = |limiter: &mut RateLimiter, params: Value| -> Value {
+            let line = json!({"jsonrpc": "2.0", "id": 1, "method": "mempool_content", "params": params});
+            serde_json::from_str(&ctx.handle_line(limiter, &line.to_string())).unwrap()
+        };
+        let first = call(&mut limiter, json!({"limit": 100}));
+        assert_eq!(first["result"]["transactions"].as_array().unwrap().len(), 2);
+        assert_eq!(first["result"]["total"], json!(3));
+        let cursor = first["result"]["next_cursor"].clone();
+        let second = call(&mut limiter, json!({"cursor": cursor}));
+        assert_eq!(second["result"]["transactions"].as_array().unwrap().len(), 1);
+        assert_eq!(second["result"]["next_cursor"], Value::Null);
+        let limited = call(&mut limiter, json!({}));
+        assert!(limited["error"]["message"].as_str().unwrap().contains("rate limit"));
+
+        // Each connection has its own bucket; stateless methods are not limited.
+        assert!(call(&mut ctx.connection_limiter(), json!({}))["result"].is_object());
+        assert!(ctx.handle_line(&mut limiter, r#"{"id":2,"method":"ping"}"#).contains("\"result\""));
+        assert!(dispatch_rpc("mempool_content", &json!({})).is_err());
+    }
 }
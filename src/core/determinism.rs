@@ -12,6 +12,7 @@
 //! - **No RNG** in execution paths (RNG is allowed only in key generation, outside execution).
 //! - **No system time:** No `SystemTime`, `Instant`, or time-based logic.
 //! - **No unsorted iteration:** HashMap/Set iteration must be sorted (e.g. collect and sort by key) before use in hashing or ordering.
+//! - **Shared tie-breaks:** consensus sorts use the named comparators in `core::ordering` rather than ad-hoc closures.
 //!
 //! # Audited paths
 //! Fee (fee.rs), transaction (transaction.rs), state (state.rs), mempool (mempool.rs), execution (execution.rs), validator_selection, confirmation_layer, block_assembly, slashing, tx_assignment: integer-only, no RNG/time, sorted iteration where applicable. Same inputs yield the same outputs.
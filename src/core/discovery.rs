@@ -0,0 +1,161 @@
+//! Authenticated peer descriptors for bootnode lists.
+//!
+//! **Stability:** experimental. Chainspec loading depends on it, so it is always compiled; the crate root re-exports it only with the `experimental` feature.
+//!
+//! A `PeerDescriptor` binds a node id and network address to the node's secp256k1 key: the node signs `(node_id, public_key, network_address)` with its own key, so anyone holding a bootnode list (from a chainspec or an exported file) can check that each entry was published by the key it names. The network layer is not part of this crate; these types are its authenticated starting point.
+
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::node_registry::NodeId;
+use crate::core::ordering::{self, NodeKey};
+use crate::error::{PlatariumError, Result};
+use crate::signature::{sign_message, verify_signature};
+
+/// Errors produced by peer descriptor verification.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveryError {
+    #[error("Invalid signature for peer {0}")]
+    InvalidSignature(NodeId),
+
+    #[error("Empty network address for peer {0}")]
+    EmptyAddress(NodeId),
+
+    #[error("Duplicate peer in list: {0}")]
+    DuplicatePeer(NodeId),
+
+    #[error("Invalid bootnode list: {0}")]
+    InvalidList(String),
+}
+
+impl From<DiscoveryError> for PlatariumError {
+    fn from(err: DiscoveryError) -> Self {
+        PlatariumError::Validation(format!("Discovery: {}", err))
+    }
+}
+
+/// Signed data of a peer descriptor (everything except the signature).
+#[derive(Serialize)]
+struct PeerDescriptorPayload<'a> {
+    node_id: &'a str,
+    public_key: &'a str,
+    network_address: &'a str,
+}
+
+/// Peer / bootnode descriptor signed by the node key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerDescriptor {
+    pub node_id: NodeId,
+    /// Compressed secp256k1 public key hex (optionally `Px`-prefixed).
+    pub public_key: String,
+    /// Transport address, e.g. "/ip4/10.0.0.1/tcp/30333" or "host:port".
+    pub network_address: String,
+    /// Signature by `public_key` over node_id, public_key and network_address.
+    pub signature: String,
+}
+
+impl NodeKey for PeerDescriptor {
+    fn node_id(&self) -> &str {
+        &self.node_id
+    }
+}
+
+impl PeerDescriptor {
+    /// Creates and signs a descriptor with the node's secret key.
+    pub fn sign(node_id: NodeId, network_address: String, secret_key: &SecretKey) -> Result<Self> {
+        let secp = Secp256k1::new();
+        let public_key = hex::encode(PublicKey::from_secret_key(&secp, secret_key).serialize());
+        let payload = PeerDescriptorPayload {
+            node_id: &node_id,
+            public_key: &public_key,
+            network_address: &network_address,
+        };
+        let sig = sign_message(secret_key, &payload)?;
+        Ok(Self {
+            node_id,
+            public_key,
+            network_address,
+            signature: sig.signature_compact,
+        })
+    }
+
+    /// Verifies the descriptor's signature against its own public key.
+    pub fn verify(&self) -> Result<()> {
+        if self.network_address.trim().is_empty() {
+            return Err(DiscoveryError::EmptyAddress(self.node_id.clone()).into());
+        }
+        let payload = PeerDescriptorPayload {
+            node_id: &self.node_id,
+            public_key: &self.public_key,
+            network_address: &self.network_address,
+        };
+        let key = self.public_key.strip_prefix("Px").unwrap_or(&self.public_key);
+        match verify_signature(&payload, &self.signature, key) {
+            Ok(true) => Ok(()),
+            _ => Err(DiscoveryError::InvalidSignature(self.node_id.clone()).into()),
+        }
+    }
+}
+
+/// Verifies every descriptor and rejects duplicate node ids.
+pub fn verify_bootnodes(peers: &[PeerDescriptor]) -> Result<()> {
+    let mut seen = std::collections::BTreeSet::new();
+    for p in peers {
+        if !seen.insert(p.node_id.as_str()) {
+            return Err(DiscoveryError::DuplicatePeer(p.node_id.clone()).into());
+        }
+        p.verify()?;
+    }
+    Ok(())
+}
+
+/// Exports a bootnode list as pretty JSON, sorted by node id.
+pub fn export_bootnodes(peers: &[PeerDescriptor]) -> Result<String> {
+    let mut sorted = peers.to_vec();
+    sorted.sort_by(ordering::by_node_id);
+    serde_json::to_string_pretty(&sorted)
+        .map_err(|e| DiscoveryError::InvalidList(e.to_string()).into())
+}
+
+/// Imports a bootnode list from JSON, verifying every entry.
+pub fn import_bootnodes(json: &str) -> Result<Vec<PeerDescriptor>> {
+    let peers: Vec<PeerDescriptor> =
+        serde_json::from_str(json).map_err(|e| DiscoveryError::InvalidList(e.to_string()))?;
+    verify_bootnodes(&peers)?;
+    Ok(peers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(b: u8) -> SecretKey {
+        SecretKey::from_slice(&[b; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_sign_verify_and_tamper() {
+        let p = PeerDescriptor::sign("n1".into(), "10.0.0.1:30333".into(), &key(1)).unwrap();
+        p.verify().unwrap();
+        let mut moved = p.clone();
+        moved.network_address = "10.6.6.6:30333".into();
+        assert!(moved.verify().is_err());
+        let mut stolen = p.clone();
+        stolen.public_key = PeerDescriptor::sign("n2".into(), "x".into(), &key(2)).unwrap().public_key;
+        assert!(stolen.verify().is_err());
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let peers = vec![
+            PeerDescriptor::sign("n2".into(), "b:1".into(), &key(2)).unwrap(),
+            PeerDescriptor::sign("n1".into(), "a:1".into(), &key(1)).unwrap(),
+        ];
+        let json = export_bootnodes(&peers).unwrap();
+        let imported = import_bootnodes(&json).unwrap();
+        assert_eq!(imported[0].node_id, "n1");
+        assert_eq!(imported.len(), 2);
+        let dup = vec![peers[0].clone(), peers[0].clone()];
+        assert!(verify_bootnodes(&dup).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! Structured events emitted by state transitions.
+//!
+//! Events are appended to the state's event log in execution order and drained by the embedder (RPC, indexers). They are informational only: they are not part of snapshots or the state root.
+//!
+//! Drained events can be fanned out to in-process subscribers (dashboards, notifiers) through an [`EventBus`]; slashing and suspension events are also persisted per block (`BlockCommit::slashing_events`) for historical queries.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::core::protocol_params::SlashDestination;
+use crate::core::slashing::SlashingReason;
+use crate::core::state::Address;
+
+/// Event emitted by a state transition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Event {
+    /// Transfers of `asset` (canonical string) were paused by its issuer.
+    AssetFrozen { asset: String, issuer: Address },
+    /// Transfers of `asset` (canonical string) were resumed by its issuer.
+    AssetUnfrozen { asset: String, issuer: Address },
+    /// `owner` set the allowance of `spender` for `asset` to `amount` (0 revokes).
+    Approval {
+        owner: Address,
+        spender: Address,
+        asset: String,
+        amount: u128,
+    },
+    /// `spender` moved `amount` of `asset` from `owner` to `to` under an allowance; `remaining` is the allowance left.
+    DelegatedTransfer {
+        owner: Address,
+        spender: Address,
+        to: Address,
+        asset: String,
+        amount: u128,
+        remaining: u128,
+    },
+    /// Governance enacted a freeze order on `address`.
+    AddressFreezeOrdered {
+        order_id: String,
+        address: Address,
+        activation_height: u64,
+        expiry_height: Option<u64>,
+    },
+    /// Governance lifted freeze order `order_id` on `address` from `height` on.
+    AddressFreezeLifted { order_id: String, address: Address, height: u64 },
+    /// `derived_key` (hex) was bound to `address`; later transactions from it must carry this key.
+    DerivedKeyRegistered { address: Address, derived_key: String },
+    /// The derived key of `address` started a rotation to `new_main_key`, effective at `effective_height` unless cancelled.
+    RecoveryInitiated {
+        address: Address,
+        new_main_key: String,
+        effective_height: u64,
+    },
+    /// The pending rotation of `address` was cancelled by its current keys.
+    RecoveryCancelled { address: Address },
+    /// A rotation passed its delay; `new_main_key` now signs for `address`.
+    MainKeyRotated { address: Address, new_main_key: String },
+    /// `address` authorized `session_key` for up to `max_amount_per_block` of `asset` per block until `expiry_height`.
+    SessionKeyRegistered {
+        address: Address,
+        session_key: String,
+        asset: String,
+        max_amount_per_block: u128,
+        expiry_height: u64,
+    },
+    /// `address` revoked `session_key`.
+    SessionKeyRevoked { address: Address, session_key: String },
+    /// Validator `node_id` was slashed for `reason` at `height`: reputation fell by `reputation_penalty` and
+    /// `stake_slashed` stake was credited according to `destination`.
+    NodeSlashed {
+        node_id: String,
+        reason: SlashingReason,
+        height: u64,
+        reputation_penalty: u64,
+        stake_slashed: u128,
+        destination: SlashDestination,
+    },
+    /// Pre-rename form of `NodeSlashed`, emitted right after it with the same slash so consumers matching on the
+    /// old name keep working. Not counted by `is_slashing`, so slashing subscriptions and storage see each slash
+    /// once. Will be dropped in the next major version.
+    StakeSlashed {
+        node_id: String,
+        reason: SlashingReason,
+        amount: u128,
+        destination: SlashDestination,
+    },
+    /// Validator `node_id` was suspended at `height` after a slash for `reason` took its reputation below the threshold.
+    NodeSuspended {
+        node_id: String,
+        reason: SlashingReason,
+        height: u64,
+    },
+    /// `sender` (an account, or `governance`) emitted an outbound message to `destination`; see `core::outbound`.
+    MessageSent {
+        sender: Address,
+        destination: String,
+        /// Payload as hex.
+        payload: String,
+    },
+    /// `address` became a multisig account, or replaced its signer set; see `core::multisig`.
+    MultisigConfigured {
+        address: Address,
+        signers: Vec<String>,
+        threshold: u32,
+    },
+    /// `from` registered standing order `order_id` (its transaction hash); see `core::standing_orders`.
+    StandingOrderCreated {
+        order_id: String,
+        from: Address,
+        to: Address,
+        asset: String,
+        amount: u128,
+        interval_blocks: u64,
+        first_height: u64,
+        end_height: u64,
+    },
+    /// Standing order `order_id` paid `amount` of `asset` from `from` to `to` at block finalization.
+    StandingOrderPaid {
+        order_id: String,
+        from: Address,
+        to: Address,
+        asset: String,
+        amount: u128,
+    },
+    /// `from` cancelled standing order `order_id`.
+    StandingOrderCancelled { order_id: String, from: Address },
+    /// A verified bridge claim minted `amount` of wrapped `asset` to `recipient`; see `core::bridge`.
+    BridgeClaimed {
+        source_chain: String,
+        claim_id: String,
+        recipient: Address,
+        asset: String,
+        amount: u128,
+    },
+    /// Issuer `issuer` minted `amount` of `asset` to `to`.
+    Minted {
+        issuer: Address,
+        to: Address,
+        asset: String,
+        amount: u128,
+    },
+    /// `address` locked `amount` PLP as stake; `total` is its stake afterwards.
+    Staked { address: Address, amount: u128, total: u128 },
+    /// `address` unstaked `amount` PLP; `total` is its stake afterwards. The PLP is paid out by `StakeReleased` once
+    /// the unbonding delay has passed.
+    Unstaked { address: Address, amount: u128, total: u128 },
+    /// `amount` PLP unstaked by `address` finished unbonding and was paid back to it.
+    StakeReleased { address: Address, amount: u128 },
+    /// Governance-approved claim `claim_id` paid `amount` PLP and `fee_refund_uplp` μPLP from the insurance fund to `claimant`.
+    InsurancePayout {
+        claim_id: String,
+        claimant: Address,
+        tx_hash: String,
+        amount: u128,
+        fee_refund_uplp: u128,
+    },
+}
+
+impl Event {
+    /// True for validator slashing and suspension events.
+    pub fn is_slashing(&self) -> bool {
+        matches!(self, Event::NodeSlashed { .. } | Event::NodeSuspended { .. })
+    }
+
+    /// Accounts the event concerns, in field order. Empty for validator events.
+    pub fn addresses(&self) -> Vec<&Address> {
+        match self {
+            Event::AssetFrozen { issuer, .. } | Event::AssetUnfrozen { issuer, .. } => vec![issuer],
+            Event::Approval { owner, spender, .. } => vec![owner, spender],
+            Event::DelegatedTransfer { owner, spender, to, .. } => vec![owner, spender, to],
+            Event::AddressFreezeOrdered { address, .. }
+            | Event::AddressFreezeLifted { address, .. }
+            | Event::DerivedKeyRegistered { address, .. }
+            | Event::RecoveryInitiated { address, .. }
+            | Event::RecoveryCancelled { address }
+            | Event::MainKeyRotated { address, .. }
+            | Event::SessionKeyRegistered { address, .. }
+            | Event::SessionKeyRevoked { address, .. }
+            | Event::Staked { address, .. }
+            | Event::Unstaked { address, .. }
+            | Event::StakeReleased { address, .. } => vec![address],
+            Event::Minted { issuer, to, .. } => vec![issuer, to],
+            Event::InsurancePayout { claimant, .. } => vec![claimant],
+            Event::MessageSent { sender, .. } => vec![sender],
+            Event::MultisigConfigured { address, .. } => vec![address],
+            Event::BridgeClaimed { recipient, .. } => vec![recipient],
+            Event::StandingOrderCreated { from, to, .. } | Event::StandingOrderPaid { from, to, .. } => vec![from, to],
+            Event::StandingOrderCancelled { from, .. } => vec![from],
+            Event::NodeSlashed { .. } | Event::StakeSlashed { .. } | Event::NodeSuspended { .. } => Vec::new(),
+        }
+    }
+
+    /// Validator the event concerns, if any.
+    pub fn node_id(&self) -> Option<&str> {
+        match self {
+            Event::NodeSlashed { node_id, .. }
+            | Event::StakeSlashed { node_id, .. }
+            | Event::NodeSuspended { node_id, .. } => Some(node_id),
+            _ => None,
+        }
+    }
+}
+
+/// Which events a subscriber receives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventFilter {
+    All,
+    /// Slashing and suspension events, optionally for one validator only.
+    Slashing { node_id: Option<String> },
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &Event) -> bool {
+        match self {
+            EventFilter::All => true,
+            EventFilter::Slashing { node_id } => {
+                event.is_slashing() && node_id.as_deref().is_none_or(|id| event.node_id() == Some(id))
+            }
+        }
+    }
+}
+
+/// Fans out published events to channel subscribers. Subscribers whose receiver was dropped are removed on the next publish.
+#[derive(Debug, Default)]
+pub struct EventBus {
+    subscribers: Mutex<Vec<(EventFilter, Sender<Event>)>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a subscriber; matching events are delivered in publish order.
+    pub fn subscribe(&self, filter: EventFilter) -> Receiver<Event> {
+        let (tx, rx) = channel();
+        self.subscribers.lock().unwrap().push((filter, tx));
+        rx
+    }
+
+    /// Delivers `events` (e.g. from `State::take_events`) to every matching subscriber.
+    pub fn publish(&self, events: &[Event]) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|(filter, tx)| {
+            events
+                .iter()
+                .filter(|e| filter.matches(e))
+                .all(|e| tx.send(e.clone()).is_ok())
+        });
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slashing_subscription_filters_by_node() {
+        let bus = EventBus::new();
+        let all = bus.subscribe(EventFilter::All);
+        let n1 = bus.subscribe(EventFilter::Slashing {
+            node_id: Some("n1".into()),
+        });
+        let suspended = |node: &str| Event::NodeSuspended {
+            node_id: node.into(),
+            reason: SlashingReason::Equivocation,
+            height: 3,
+        };
+        let unrelated = Event::RecoveryCancelled { address: "a".into() };
+        bus.publish(&[unrelated.clone(), suspended("n2"), suspended("n1")]);
+        assert_eq!(n1.try_iter().collect::<Vec<_>>(), vec![suspended("n1")]);
+        assert_eq!(all.try_iter().count(), 3);
+
+        drop(all);
+        bus.publish(&[unrelated]);
+        assert_eq!(bus.subscriber_count(), 1);
+    }
+}
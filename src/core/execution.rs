@@ -2,15 +2,22 @@
 //!
 //! Supports production (commit to state) and simulation (dry-run) modes. Execution is deterministic: simulation and production yield the same result for the same inputs. No randomness or system time is used.
 
-use std::sync::Arc;
 use crate::error::{PlatariumError, Result};
-use crate::core::transaction::Transaction;
+use crate::core::transaction::{Transaction, TxKind};
 use crate::core::asset::Asset;
-use crate::core::state::{State, StateSnapshot};
+use crate::core::invariants::{check_invariant, SIMULATION_ISOLATED, SNAPSHOT_LIVE};
+use crate::core::overlay::OverlayState;
+use crate::core::state::{snapshot_live, Address, State, StateError, StateSnapshot, TREASURY_ADDRESS};
+use crate::core::fee::FeeRebatePolicy;
+use crate::core::node_registry::{NodeRegistry, NodeStatus};
+use crate::core::receipt::{BalanceProbe, Receipt};
+use crate::core::standing_orders::{execute_standing_orders, StandingOrderReceipt};
+use crate::core::migrations::{MigrationRecord, MigrationRegistry};
 use thiserror::Error;
 
 /// Execution mode: whether transactions are committed to state or only simulated.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ExecutionContext {
     /// Production: transactions are committed; state changes are permanent.
     Production,
@@ -23,6 +30,9 @@ pub enum ExecutionContext {
 pub enum ExecutionError {
     #[error("Commit not allowed in simulation mode")]
     CommitNotAllowedInSimulation,
+
+    #[error("Transaction wrote {0}, which is not in its declared writes")]
+    UndeclaredWrite(Address),
     
     #[error("Execution error: {0}")]
     Other(String),
@@ -85,6 +95,35 @@ impl ExecutionResult {
     }
 }
 
+/// Fee outcome of an executed transaction, as recorded in its receipt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeeCharge {
+    /// Fee charged to the sender (μPLP), always the full `tx.fee_uplp`.
+    pub fee_uplp: u128,
+    /// Rebate credited back to the sender from the treasury (μPLP); 0 when no policy applies.
+    pub rebate_uplp: u128,
+}
+
+impl FeeCharge {
+    /// Net fee retained by the treasury.
+    pub fn net_fee_uplp(&self) -> u128 {
+        self.fee_uplp - self.rebate_uplp
+    }
+}
+
+/// Outcome of [`ExecutionLogic::execute_block`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockExecution {
+    /// State migrations activated at this height (`MigrationRegistry::apply_due`), applied before anything else.
+    pub migrations: Vec<MigrationRecord>,
+    /// Unbonded stake paid out before the transactions (`State::release_unbonded`), as (address, PLP amount).
+    pub released_stake: Vec<(Address, u128)>,
+    /// One receipt per transaction, in block order.
+    pub receipts: Vec<Receipt>,
+    /// One receipt per standing order attempted after the transactions, in (sender, order id) order.
+    pub standing_orders: Vec<StandingOrderReceipt>,
+}
+
 /// Shared execution logic used by both production and simulation: validation, applicability check, and effect application. All operations are deterministic.
 pub struct ExecutionLogic;
 
@@ -94,9 +133,23 @@ impl ExecutionLogic {
         tx.validate_basic()
             .map_err(|e| PlatariumError::from(e))
     }
+
+    /// [`Self::validate_transaction`] for `state`'s deployment: `State::check_transaction` also rejects another
+    /// deployment's salt or another network's chain ID. Used on every path that has a state.
+    pub fn validate_transaction_on(state: &State, tx: &Transaction) -> Result<()> {
+        state.check_transaction(tx).map_err(PlatariumError::from)
+    }
     
-    /// Checks whether the transaction can be applied: nonce match, sufficient asset balance, sufficient μPLP for fee. Deterministic; errors if the transaction is not applicable.
+    /// Checks whether the transaction can be applied: not expired at the state's block height (`valid_until`), nonce match, sender (and delegating owner) not under a governance freeze order, account keys and recovery preconditions (`State::check_account_keys`; no derived key registered yet for `RegisterDerivedKey`), asset not frozen (transfers, burn, stake) or sender is the issuer (freeze kinds, mint), enough stake (unstake), every output payable in order with the fee (multi-transfers), allowance and owner balance (delegated transfers), sufficient unvested PLP at the state's block height, sufficient asset balance, sufficient μPLP for fee. Deterministic; errors if the transaction is not applicable.
     pub fn check_transaction_applicability(state: &State, tx: &Transaction) -> Result<()> {
+        let height = state.block_height();
+        if tx.is_expired_at(height) {
+            return Err(StateError::Expired {
+                valid_until: tx.valid_until.unwrap_or_default(),
+                height,
+            }
+            .into());
+        }
         let current_nonce = state.get_nonce(&tx.from);
         if current_nonce != tx.nonce {
             return Err(PlatariumError::State(format!(
@@ -104,11 +157,104 @@ impl ExecutionLogic {
                 tx.nonce, current_nonce
             )));
         }
+        state.check_not_frozen(&tx.from)?;
+        if let TxKind::TransferFrom { ref owner } = tx.kind {
+            state.check_not_frozen(owner)?;
+        }
+        state.check_account_keys(tx)?;
+        // Amount the sender itself spends from `tx.asset` (delegated transfers spend the owner's balance).
+        let sender_amount = match tx.kind {
+            TxKind::Transfer | TxKind::SessionTransfer { .. } => {
+                if state.is_asset_frozen(&tx.asset) {
+                    return Err(PlatariumError::State(format!(
+                        "Asset is frozen: {}",
+                        tx.asset.as_canonical()
+                    )));
+                }
+                tx.amount
+            }
+            TxKind::FreezeAsset | TxKind::UnfreezeAsset => {
+                state.asset_registry().check_issuer(&tx.asset, &tx.from)?;
+                0
+            }
+            TxKind::Approve => 0,
+            TxKind::InitiateRecovery { .. }
+            | TxKind::CancelRecovery
+            | TxKind::RegisterSessionKey { .. }
+            | TxKind::RevokeSessionKey { .. }
+            | TxKind::SendMessage { .. }
+            | TxKind::SetMultisig { .. }
+            | TxKind::BridgeClaim { .. } => 0,
+            TxKind::CreateStandingOrder { .. } | TxKind::CancelStandingOrder { .. } => {
+                state.check_standing_order_tx(tx)?;
+                0
+            }
+            TxKind::Mint => {
+                state.asset_registry().check_mint(&tx.asset, &tx.from, tx.amount)?;
+                0
+            }
+            TxKind::Burn | TxKind::Stake => {
+                if matches!(tx.kind, TxKind::Burn) {
+                    state.asset_registry().check_burn(&tx.asset, tx.amount)?;
+                }
+                if state.is_asset_frozen(&tx.asset) {
+                    return Err(PlatariumError::State(format!(
+                        "Asset is frozen: {}",
+                        tx.asset.as_canonical()
+                    )));
+                }
+                tx.amount
+            }
+            TxKind::MultiTransfer { ref outputs } => {
+                state.check_multi_transfer(&tx.from, outputs, tx.fee_uplp)?;
+                0
+            }
+            TxKind::Unstake => {
+                let staked = state.get_stake(&tx.from);
+                if staked < tx.amount {
+                    return Err(StateError::InsufficientStake {
+                        required: tx.amount,
+                        available: staked,
+                    }
+                    .into());
+                }
+                0
+            }
+            TxKind::RegisterDerivedKey => {
+                if state.registered_derived_key(&tx.from).is_some() {
+                    return Err(StateError::DerivedKeyAlreadyRegistered(tx.from.clone()).into());
+                }
+                0
+            }
+            TxKind::TransferFrom { ref owner } => {
+                if state.is_asset_frozen(&tx.asset) {
+                    return Err(PlatariumError::State(format!(
+                        "Asset is frozen: {}",
+                        tx.asset.as_canonical()
+                    )));
+                }
+                let allowance = state.get_allowance(owner, &tx.from, &tx.asset);
+                if allowance < tx.amount {
+                    return Err(PlatariumError::State(format!(
+                        "Insufficient allowance: required {}, available {}",
+                        tx.amount, allowance
+                    )));
+                }
+                let owner_bal = state.get_asset_balance(owner, &tx.asset);
+                if owner_bal < tx.amount {
+                    return Err(PlatariumError::State(format!(
+                        "Insufficient asset balance: required {}, available {}",
+                        tx.amount, owner_bal
+                    )));
+                }
+                0
+            }
+        };
         let asset_bal = state.get_asset_balance(&tx.from, &tx.asset);
-        if asset_bal < tx.amount {
+        if asset_bal < sender_amount {
             return Err(PlatariumError::State(format!(
                 "Insufficient asset balance: required {}, available {}",
-                tx.amount, asset_bal
+                sender_amount, asset_bal
             )));
         }
         let uplp_bal = state.get_uplp_balance(&tx.from);
@@ -119,31 +265,35 @@ impl ExecutionLogic {
                 tx.fee_uplp, fee_available
             )));
         }
+        let fee_from_plp = tx.fee_uplp.saturating_sub(uplp_bal);
         if tx.asset == Asset::PLP {
-            let fee_from_plp = tx.fee_uplp.saturating_sub(uplp_bal);
             let plp_bal = state.get_asset_balance(&tx.from, &Asset::PLP);
-            if plp_bal < tx.amount.saturating_add(fee_from_plp) {
+            if plp_bal < sender_amount.saturating_add(fee_from_plp) {
                 return Err(PlatariumError::State(format!(
                     "Insufficient asset balance: required {}, available {}",
-                    tx.amount.saturating_add(fee_from_plp),
+                    sender_amount.saturating_add(fee_from_plp),
                     plp_bal
                 )));
             }
         }
+        let plp_spent = if tx.asset == Asset::PLP {
+            sender_amount.saturating_add(fee_from_plp)
+        } else {
+            fee_from_plp
+        };
+        let unlocked = state.spendable_balance(&tx.from, state.block_height());
+        if plp_spent > 0 && unlocked < plp_spent {
+            return Err(PlatariumError::State(format!(
+                "Balance is locked by vesting: spending {}, unlocked {}",
+                plp_spent, unlocked
+            )));
+        }
         Ok(())
     }
     
-    /// Applies transaction effects: deducts fee from sender’s μPLP and amount from asset balance; credits amount to receiver and fee to treasury. Deterministic.
+    /// Applies transaction effects by kind (see `State::apply_effects`): for transfers, deducts fee from sender’s μPLP and amount from asset balance; credits amount to receiver and fee to treasury. Deterministic.
     pub fn apply_transaction_effects(state: &State, tx: &Transaction) -> Result<()> {
-        state.apply_transfer(
-            &tx.from,
-            &tx.to,
-            &tx.asset,
-            tx.amount,
-            tx.fee_uplp,
-            Some(tx.nonce),
-        )?;
-        Ok(())
+        state.apply_effects(tx)
     }
     
     /// Executes a transaction (shared logic)
@@ -163,8 +313,8 @@ impl ExecutionLogic {
         tx: &Transaction,
         _context: ExecutionContext,
     ) -> Result<()> {
-        // Step 1: Validate transaction (shared logic)
-        Self::validate_transaction(tx)?;
+        // Step 1: Validate transaction for the state's deployment (shared logic)
+        Self::validate_transaction_on(state, tx)?;
         
         // Step 2: Check applicability (shared logic)
         Self::check_transaction_applicability(state, tx)?;
@@ -181,6 +331,138 @@ impl ExecutionLogic {
         Ok(())
     }
     
+    /// Executes `tx` and checks that every account it wrote (see `State::written_accounts`) is in `tx.writes`; the
+    /// treasury's fee credit is exempt. On an undeclared write the state is restored and `UndeclaredWrite` returned.
+    /// Declared sets from [`Transaction::derive_access_sets`] always pass.
+    pub fn execute_within_access_sets(state: &State, tx: &Transaction, context: ExecutionContext) -> Result<()> {
+        let before = state.snapshot();
+        Self::execute_transaction(state, tx, context)?;
+        let undeclared = state
+            .written_accounts(&before)
+            .into_iter()
+            .find(|a| a != TREASURY_ADDRESS && !tx.writes.contains(a));
+        if let Some(address) = undeclared {
+            state.restore(&before);
+            return Err(ExecutionError::UndeclaredWrite(address).into());
+        }
+        Ok(())
+    }
+
+    /// Executes `tx` in production mode as transaction `index` of block `block_number` and records the outcome: the
+    /// error if it was rejected, otherwise the fee and the balance changes it made.
+    pub fn execute_with_receipt(state: &State, tx: &Transaction, block_number: u64, index: u32) -> Receipt {
+        Self::execute_with_rebate_receipt(state, tx, block_number, index, None)
+    }
+
+    /// [`Self::execute_with_receipt`], then, if `rebates` is given and the transaction applied, settles its fee
+    /// rebate ([`Self::settle_fee_rebate`]) and records it in the receipt. A rebate the treasury cannot cover is
+    /// not paid (recorded as 0); the transaction still stands.
+    fn execute_with_rebate_receipt(
+        state: &State,
+        tx: &Transaction,
+        block_number: u64,
+        index: u32,
+        rebates: Option<(&NodeRegistry, &FeeRebatePolicy)>,
+    ) -> Receipt {
+        let probe = BalanceProbe::new(state, tx);
+        if let Err(e) = Self::execute_transaction(state, tx, ExecutionContext::Production) {
+            return Receipt::failure(tx, &e, block_number, index);
+        }
+        let rebate_uplp = rebates
+            .and_then(|(registry, policy)| Self::settle_fee_rebate(state, tx, registry, policy).ok())
+            .map_or(0, |charge| charge.rebate_uplp);
+        Receipt {
+            rebate_uplp,
+            ..Receipt::success(tx, probe.deltas(state), block_number, index)
+        }
+    }
+
+    /// Executes block `block_number` on `state`: sets the state's block height, applies the state migrations of
+    /// [`MigrationRegistry::standard`] due at that height, releases the unbonding stake due at that height, runs the
+    /// transactions in order with [`Self::execute_with_receipt`], then the standing orders due at that height
+    /// (`execute_standing_orders`). The state root is taken after this returns. Errors only if a migration fails or
+    /// the state's version is unsupported; nothing else has run then.
+    pub fn execute_block(state: &State, txs: &[Transaction], block_number: u64) -> Result<BlockExecution> {
+        Self::execute_block_inner(state, txs, block_number, &MigrationRegistry::standard(), None)
+    }
+
+    /// [`Self::execute_block`] with the state migrations of `migrations` instead of the standard ones.
+    pub fn execute_block_with_migrations(
+        state: &State,
+        txs: &[Transaction],
+        block_number: u64,
+        migrations: &MigrationRegistry,
+    ) -> Result<BlockExecution> {
+        Self::execute_block_inner(state, txs, block_number, migrations, None)
+    }
+
+    /// [`Self::execute_block`] under a fee rebate policy: each applied transaction's rebate is settled right after
+    /// it, against `registry` as of the block, and recorded in its receipt. With [`FeeRebatePolicy::disabled`] the
+    /// result equals `execute_block`'s. Producer and validators must use the same registry and policy.
+    pub fn execute_block_with_rebates(
+        state: &State,
+        txs: &[Transaction],
+        block_number: u64,
+        registry: &NodeRegistry,
+        policy: &FeeRebatePolicy,
+    ) -> Result<BlockExecution> {
+        Self::execute_block_inner(state, txs, block_number, &MigrationRegistry::standard(), Some((registry, policy)))
+    }
+
+    fn execute_block_inner(
+        state: &State,
+        txs: &[Transaction],
+        block_number: u64,
+        migrations: &MigrationRegistry,
+        rebates: Option<(&NodeRegistry, &FeeRebatePolicy)>,
+    ) -> Result<BlockExecution> {
+        state.set_block_height(block_number);
+        let migrations = migrations.apply_due(state, block_number)?;
+        let released_stake = state.release_unbonded();
+        let receipts = txs
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| Self::execute_with_rebate_receipt(state, tx, block_number, index as u32, rebates))
+            .collect();
+        Ok(BlockExecution {
+            migrations,
+            released_stake,
+            receipts,
+            standing_orders: execute_standing_orders(state),
+        })
+    }
+
+    /// Settles the optional fee rebate for an already-applied transaction: if the sender is an active registered validator whose reputation meets `policy`, the rebate moves from the treasury back to the sender. Deterministic.
+    pub fn settle_fee_rebate(
+        state: &State,
+        tx: &Transaction,
+        registry: &NodeRegistry,
+        policy: &FeeRebatePolicy,
+    ) -> Result<FeeCharge> {
+        let reputation = registry
+            .get_by_public_key(tx.pub_main.as_deref().unwrap_or(&tx.from))
+            .filter(|n| n.status == NodeStatus::Active)
+            .map(|n| n.reputation_score);
+        let rebate = policy.rebate_for(tx.fee_uplp, reputation);
+        state.credit_fee_rebate(&tx.from, rebate)?;
+        Ok(FeeCharge {
+            fee_uplp: tx.fee_uplp,
+            rebate_uplp: rebate,
+        })
+    }
+
+    /// Executes a transaction and then settles the fee rebate policy. Returns the fee outcome for the receipt.
+    pub fn execute_transaction_with_rebate(
+        state: &State,
+        tx: &Transaction,
+        context: ExecutionContext,
+        registry: &NodeRegistry,
+        policy: &FeeRebatePolicy,
+    ) -> Result<FeeCharge> {
+        Self::execute_transaction(state, tx, context)?;
+        Self::settle_fee_rebate(state, tx, registry, policy)
+    }
+
     /// Commits transaction execution (context-dependent)
     /// 
     /// In Production mode: commits are allowed (no-op, changes already applied)
@@ -204,11 +486,12 @@ impl ExecutionLogic {
     
     /// Simulates transaction execution on a snapshot
     /// 
-    /// This method executes a transaction on a temporary state created from a snapshot,
+    /// This method executes a transaction in an `OverlayState` over the snapshot,
     /// without modifying the global state. The result shows what would happen if the
     /// transaction were executed.
     /// 
-    /// PERFORMANCE: O(1) snapshot restore + O(1) transaction execution
+    /// PERFORMANCE: O(1) overlay creation + O(touched accounts) execution for transfers;
+    /// only building the final snapshot copies the maps the transaction wrote
     /// 
     /// DETERMINISM GUARANTEE:
     /// - Same transaction + same snapshot → same ExecutionResult (always)
@@ -216,49 +499,31 @@ impl ExecutionLogic {
     /// - Result is a pure function of input data
     /// 
     /// INVARIANTS:
-    /// - Global state is never modified (simulation uses temporary state)
+    /// - Global state is never modified (writes stay in the overlay)
     /// - Snapshot is never modified (read-only)
     /// - Result is deterministic
     /// 
     /// Returns ExecutionResult containing:
     /// - Success status
     /// - Final state snapshot (if successful)
-    /// - Error message (if failed)
+    /// - Error message (if failed; the state rule that rejected the transaction)
     pub fn simulate(tx: &Transaction, snapshot: &StateSnapshot) -> ExecutionResult {
-        assert!(Arc::strong_count(snapshot.asset_balances_arc()) > 0);
-        assert!(Arc::strong_count(snapshot.nonces_arc()) > 0);
+        check_invariant(snapshot_live(snapshot), SNAPSHOT_LIVE, || "simulating on a released snapshot".to_string());
         
-        // Create temporary state from snapshot
-        // This ensures global state is never modified
-        let temp_state = State::new();
-        temp_state.restore(snapshot);
+        // Writes go to the overlay; the snapshot is only read
+        let mut overlay = OverlayState::new(snapshot.clone());
+        let original_balance = snapshot.get_balance(&tx.from);
         
-        // Store original state snapshot for verification
-        let original_snapshot = temp_state.snapshot();
-        
-        // Execute transaction on temporary state
-        // This uses shared execution logic
-        match Self::execute_transaction(&temp_state, tx, ExecutionContext::Simulation) {
+        match Self::validate_transaction(tx).and_then(|()| overlay.apply_effects(tx)) {
             Ok(()) => {
-                // Execution succeeded
-                // Create final state snapshot
-                let final_state = temp_state.snapshot();
-                
-                assert!(
-                    snapshot.get_balance(&tx.from) == original_snapshot.get_balance(&tx.from),
-                    "INVARIANT VIOLATION: Snapshot was modified during simulation"
+                check_invariant(
+                    snapshot.get_balance(&tx.from) == original_balance,
+                    SIMULATION_ISOLATED,
+                    || "snapshot was modified during simulation".to_string(),
                 );
-                
-                // INVARIANT CHECK: Verify global state was not modified
-                // (We can't directly check global state here, but we verify temp_state
-                // was created from snapshot and is independent)
-                
-                ExecutionResult::success(final_state)
-            }
-            Err(e) => {
-                // Execution failed
-                ExecutionResult::failure(e.to_string())
+                ExecutionResult::success(overlay.snapshot())
             }
+            Err(e) => ExecutionResult::failure(e.to_string()),
         }
     }
     
@@ -324,7 +589,7 @@ mod tests {
     #[test]
     fn test_check_transaction_applicability() {
         let state = State::new();
-        let sender = "sender".to_string();
+        let sender = crate::testing::DeterministicRng::new(1).account().address;
         let receiver = "receiver".to_string();
         
         state.set_balance(&sender, 1000);
@@ -355,7 +620,7 @@ mod tests {
         // executing on a temporary state and then rolling back
         
         let state = State::new();
-        let sender = "sender".to_string();
+        let sender = crate::testing::DeterministicRng::new(1).account().address;
         let receiver = "receiver".to_string();
         
         // Set initial state
@@ -439,7 +704,7 @@ mod tests {
     fn test_simulation_does_not_modify_state() {
         // Test: simulation does not mutate state
         let state = State::new();
-        let sender = "sender".to_string();
+        let sender = crate::testing::DeterministicRng::new(1).account().address;
         let receiver = "receiver".to_string();
         
         state.set_balance(&sender, 1000);
@@ -506,7 +771,7 @@ mod tests {
     fn test_simulation_deterministic_output() {
         // Test: deterministic output - same transaction + same snapshot → same result
         let state = State::new();
-        let sender = "sender".to_string();
+        let sender = crate::testing::DeterministicRng::new(1).account().address;
         let receiver = "receiver".to_string();
         
         state.set_balance(&sender, 1000);
@@ -571,6 +836,190 @@ mod tests {
         }
     }
     
+    fn total_uplp_value(state: &State, addrs: &[&str]) -> u128 {
+        addrs
+            .iter()
+            .map(|a| state.get_balance(&a.to_string()) + state.get_uplp_balance(&a.to_string()))
+            .sum()
+    }
+
+    #[test]
+    fn test_fee_rebate_conserves_value() {
+        use crate::core::state::TREASURY_ADDRESS;
+        let registry = NodeRegistry::new();
+        let sender = crate::testing::DeterministicRng::new(1).account().address;
+        registry.register("v1".into(), sender.clone(), 1000, 10).unwrap();
+        let state = State::new();
+        state.set_balance(&sender, 1000);
+        state.set_uplp_balance(&sender, 100);
+        let tx = Transaction::new(
+            sender.clone(),
+            "receiver".to_string(),
+            Asset::PLP,
+            10,
+            50,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            "sig".to_string(),
+            "sig".to_string(),
+        ).unwrap();
+        let addrs = [sender.as_str(), "receiver", TREASURY_ADDRESS];
+        let before = total_uplp_value(&state, &addrs);
+
+        ExecutionLogic::apply_transaction_effects(&state, &tx).unwrap();
+        let policy = FeeRebatePolicy::new(0, 20);
+        let charge = ExecutionLogic::settle_fee_rebate(&state, &tx, &registry, &policy).unwrap();
+
+        assert_eq!(charge, FeeCharge { fee_uplp: 50, rebate_uplp: 10 });
+        assert_eq!(charge.net_fee_uplp(), 40);
+        assert_eq!(state.get_uplp_balance(&TREASURY_ADDRESS.to_string()), 40);
+        assert_eq!(state.get_uplp_balance(&sender), 60);
+        assert_eq!(total_uplp_value(&state, &addrs), before);
+    }
+
+    #[test]
+    fn test_fee_rebate_not_granted_to_non_validator_or_suspended() {
+        let registry = NodeRegistry::new();
+        let accounts = crate::testing::DeterministicRng::new(1).accounts(2);
+        let (validator, stranger) = (accounts[0].address.as_str(), accounts[1].address.as_str());
+        registry.register("v1".into(), validator.to_string(), 1000, 10).unwrap();
+        registry.set_status(&"v1".into(), NodeStatus::Suspended).unwrap();
+        let state = State::new();
+        for sender in [validator, stranger] {
+            state.set_uplp_balance(&sender.to_string(), 100);
+            state.set_balance(&sender.to_string(), 100);
+            let tx = Transaction::new(
+                sender.to_string(),
+                "receiver".to_string(),
+                Asset::PLP,
+                10,
+                50,
+                0,
+                HashSet::new(),
+                HashSet::new(),
+                "sig".to_string(),
+                "sig".to_string(),
+            ).unwrap();
+            ExecutionLogic::apply_transaction_effects(&state, &tx).unwrap();
+            let charge = ExecutionLogic::settle_fee_rebate(&state, &tx, &registry, &FeeRebatePolicy::new(0, 20)).unwrap();
+            assert_eq!(charge.rebate_uplp, 0);
+        }
+    }
+
+    #[test]
+    fn test_expired_transaction_not_applicable() {
+        let state = State::new();
+        let sender = crate::testing::DeterministicRng::new(1).account().address;
+        state.set_balance(&sender, 100);
+        let tx = Transaction::new(
+            sender.clone(),
+            "receiver".to_string(),
+            Asset::PLP,
+            10,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            "sig".to_string(),
+            "sig".to_string(),
+        )
+        .unwrap()
+        .with_valid_until(Some(5))
+        .unwrap();
+        state.set_block_height(5);
+        ExecutionLogic::check_transaction_applicability(&state, &tx).unwrap();
+        state.set_block_height(6);
+        let err = ExecutionLogic::check_transaction_applicability(&state, &tx).unwrap_err();
+        assert!(err.to_string().contains("expired"));
+    }
+
+    #[test]
+    fn test_transfer_from_applicability_and_effects() {
+        let state = State::new();
+        let owner = "owner".to_string();
+        let spender = crate::testing::DeterministicRng::new(1).account().address;
+        state.set_balance(&owner, 100);
+        state.set_balance(&spender, 10);
+        let tx = Transaction::new(
+            spender.clone(),
+            "receiver".to_string(),
+            Asset::PLP,
+            30,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            "sig".to_string(),
+            "sig".to_string(),
+        )
+        .unwrap()
+        .with_kind(TxKind::TransferFrom { owner: owner.clone() })
+        .unwrap();
+        assert!(ExecutionLogic::check_transaction_applicability(&state, &tx).is_err());
+
+        state.apply_approve(&owner, &spender, &Asset::PLP, 50, 1, Some(0)).unwrap();
+        ExecutionLogic::check_transaction_applicability(&state, &tx).unwrap();
+        ExecutionLogic::apply_transaction_effects(&state, &tx).unwrap();
+        assert_eq!(state.get_balance(&owner), 69);
+        assert_eq!(state.get_balance(&"receiver".to_string()), 30);
+        assert_eq!(state.get_allowance(&owner, &spender, &Asset::PLP), 20);
+    }
+
+    #[test]
+    fn test_derived_key_bound_on_first_use() {
+        let state = State::new();
+        let sender = crate::testing::DeterministicRng::new(1).account().address;
+        state.set_balance(&sender, 100);
+        let tx_with_key = |nonce: u64, key: &str, kind: TxKind| {
+            let mut tx = Transaction::new(
+                sender.clone(),
+                "receiver".to_string(),
+                Asset::PLP,
+                if kind == TxKind::RegisterDerivedKey { 0 } else { 5 },
+                1,
+                nonce,
+                HashSet::new(),
+                HashSet::new(),
+                "sig".to_string(),
+                "sig".to_string(),
+            )
+            .unwrap()
+            .with_kind(kind)
+            .unwrap();
+            tx.pub_derived = Some(key.to_string());
+            tx
+        };
+
+        let first = tx_with_key(0, "PxAB01", TxKind::Transfer);
+        ExecutionLogic::check_transaction_applicability(&state, &first).unwrap();
+        ExecutionLogic::apply_transaction_effects(&state, &first).unwrap();
+        assert_eq!(state.registered_derived_key(&sender), Some("ab01".to_string()));
+        assert!(matches!(
+            state.take_events().as_slice(),
+            [crate::core::events::Event::DerivedKeyRegistered { .. }]
+        ));
+
+        // A different derived key is rejected; the registered one (any case or prefix) is accepted.
+        let other = tx_with_key(1, "cd02", TxKind::Transfer);
+        let err = ExecutionLogic::check_transaction_applicability(&state, &other).unwrap_err();
+        assert!(err.to_string().contains("Derived key mismatch"));
+        assert!(ExecutionLogic::apply_transaction_effects(&state, &other).is_err());
+        let same = tx_with_key(1, "AB01", TxKind::Transfer);
+        ExecutionLogic::check_transaction_applicability(&state, &same).unwrap();
+
+        // Explicit registration only works while nothing is bound.
+        let register = tx_with_key(1, "ab01", TxKind::RegisterDerivedKey);
+        assert!(ExecutionLogic::check_transaction_applicability(&state, &register).is_err());
+        let fresh = State::new();
+        fresh.set_balance(&sender, 10);
+        let register = tx_with_key(0, "ef03", TxKind::RegisterDerivedKey);
+        ExecutionLogic::check_transaction_applicability(&fresh, &register).unwrap();
+        ExecutionLogic::apply_transaction_effects(&fresh, &register).unwrap();
+        assert_eq!(fresh.registered_derived_key(&sender), Some("ef03".to_string()));
+        assert_eq!(fresh.get_balance(&sender), 9);
+    }
+
     #[test]
     fn test_execution_result_success() {
         let state = State::new();
@@ -595,4 +1044,163 @@ mod tests {
         assert!(result.get_final_state().is_none());
         assert_eq!(result.get_error(), Some(error_msg.as_str()));
     }
+
+    #[test]
+    fn test_other_key_cannot_sign_for_address() {
+        use crate::core::state::BURN_ADDRESS;
+        let accounts = crate::testing::DeterministicRng::new(5).accounts(2);
+        let (victim, attacker) = (&accounts[0], &accounts[1]);
+        let state = State::new();
+        state.set_balance(&victim.address, 100);
+        state.set_uplp_balance(&victim.address, 10);
+
+        // Valid signatures by the attacker's keys over the victim's transfer.
+        let unsigned = victim.transfer(&attacker.address, 50, 1, 0);
+        let forged = attacker.sign(unsigned);
+        ExecutionLogic::validate_transaction(&forged).unwrap();
+        let err = ExecutionLogic::check_transaction_applicability(&state, &forged).unwrap_err();
+        assert!(err.to_string().contains("does not control"));
+        assert!(ExecutionLogic::apply_transaction_effects(&state, &forged).is_err());
+        assert_eq!(state.registered_derived_key(&victim.address), None);
+        assert_eq!(state.get_balance(&victim.address), 100);
+
+        // System accounts are not keys, so nothing can sign for them.
+        state.set_balance(&BURN_ADDRESS.to_string(), 100);
+        let mut from_burn = attacker.transfer(&attacker.address, 50, 1, 0);
+        from_burn.from = BURN_ADDRESS.to_string();
+        from_burn.hash = from_burn.compute_hash().unwrap();
+        let from_burn = attacker.sign(from_burn);
+        assert!(ExecutionLogic::apply_transaction_effects(&state, &from_burn).is_err());
+
+        let own = victim.transfer(&attacker.address, 50, 1, 0);
+        ExecutionLogic::check_transaction_applicability(&state, &own).unwrap();
+    }
+
+    #[test]
+    fn test_execute_block_receipts() {
+        let mut rng = crate::testing::DeterministicRng::new(3);
+        let accounts = rng.accounts(2);
+        let (alice, bob) = (&accounts[0].address, &accounts[1].address);
+        let state = State::new();
+        state.set_balance(alice, 100);
+        state.set_uplp_balance(alice, 10);
+
+        let txs = [accounts[0].transfer(bob, 40, 1, 0), accounts[0].transfer(bob, 500, 1, 1)];
+        let receipts = ExecutionLogic::execute_block(&state, &txs, 7).unwrap().receipts;
+
+        let ok = &receipts[0];
+        assert!(ok.success && ok.error_code.is_none());
+        assert_eq!((ok.block_number, ok.index, ok.fee_charged), (7, 0, 1));
+        let delta = |address: &str, asset: &str| {
+            ok.balance_deltas.iter().find(|d| d.address == address && d.asset == asset).map(|d| d.delta)
+        };
+        assert_eq!(delta(alice, "PLP"), Some(-40));
+        assert_eq!(delta(bob, "PLP"), Some(40));
+        assert_eq!(delta(alice, "uPLP"), Some(-1));
+        assert_eq!(delta(crate::core::state::TREASURY_ADDRESS, "uPLP"), Some(1));
+        assert_eq!(ok.fee_payer.as_ref(), Some(alice));
+        assert_eq!(ok.to_record().fee_payer.as_ref(), Some(alice));
+
+        let failed = &receipts[1];
+        assert!(!failed.success);
+        assert_eq!((failed.index, failed.fee_charged), (1, 0));
+        assert_eq!(failed.error_code.as_deref(), Some("state"));
+        assert!(failed.balance_deltas.is_empty() && failed.fee_payer.is_none());
+        assert_eq!(state.get_balance(bob), 40);
+        assert_eq!(failed.to_record().status, "failed");
+    }
+
+    #[test]
+    fn test_execute_block_with_rebates_records_the_rebate() {
+        let mut rng = crate::testing::DeterministicRng::new(4);
+        let accounts = rng.accounts(2);
+        let (alice, bob) = (&accounts[0].address, &accounts[1].address);
+        let registry = NodeRegistry::new();
+        registry.register("v1".into(), accounts[0].pub_main(), 1000, 10).unwrap();
+        let txs = [accounts[0].transfer(bob, 40, 50, 0)];
+        let funded = || {
+            let state = State::new();
+            state.set_balance(alice, 100);
+            state.set_uplp_balance(alice, 100);
+            state
+        };
+
+        let state = funded();
+        let policy = FeeRebatePolicy::new(0, 20);
+        let executed = ExecutionLogic::execute_block_with_rebates(&state, &txs, 1, &registry, &policy).unwrap();
+        let receipts = executed.receipts;
+        assert_eq!((receipts[0].fee_charged, receipts[0].rebate_uplp), (50, 10));
+        let delta = |address: &str| {
+            receipts[0].balance_deltas.iter().find(|d| d.address == address && d.asset == "uPLP").map(|d| d.delta)
+        };
+        assert_eq!(delta(alice), Some(-40));
+        assert_eq!(delta(TREASURY_ADDRESS), Some(40));
+        assert_eq!(receipts[0].to_record().rebate_uplp, 10);
+        assert_eq!(state.get_uplp_balance(alice), 60);
+
+        // A disabled policy gives exactly the plain block execution.
+        let (plain, disabled) = (funded(), funded());
+        let expected = ExecutionLogic::execute_block(&plain, &txs, 1).unwrap();
+        let policy = FeeRebatePolicy::disabled();
+        let got = ExecutionLogic::execute_block_with_rebates(&disabled, &txs, 1, &registry, &policy).unwrap();
+        assert_eq!(got, expected);
+        assert_eq!(disabled.snapshot(), plain.snapshot());
+    }
+
+    #[test]
+    fn test_execute_block_applies_due_migrations() {
+        use crate::core::migrations::{MigrationKind, StateMigration, STATE_VERSION};
+
+        fn fund_treasury(state: &State) -> Result<()> {
+            state.set_uplp_balance(&TREASURY_ADDRESS.to_string(), 7);
+            Ok(())
+        }
+        let mut migrations = MigrationRegistry::new(STATE_VERSION);
+        migrations
+            .register(StateMigration {
+                from_version: STATE_VERSION,
+                name: "fund_treasury",
+                activation_height: 5,
+                kind: MigrationKind::State(fund_treasury),
+            })
+            .unwrap();
+        let state = State::new();
+        let treasury = TREASURY_ADDRESS.to_string();
+
+        let before = ExecutionLogic::execute_block_with_migrations(&state, &[], 4, &migrations).unwrap();
+        assert!(before.migrations.is_empty());
+        assert_eq!(state.get_uplp_balance(&treasury), 0);
+
+        let at = ExecutionLogic::execute_block_with_migrations(&state, &[], 5, &migrations).unwrap();
+        assert_eq!(at.migrations.len(), 1);
+        assert_eq!((at.migrations[0].name.as_str(), at.migrations[0].height), ("fund_treasury", 5));
+        assert_eq!(state.get_uplp_balance(&treasury), 7);
+        assert_eq!(state.layout().version, STATE_VERSION + 1);
+
+        // Applied once; the standard registry refuses the now newer state.
+        let after = ExecutionLogic::execute_block_with_migrations(&state, &[], 6, &migrations).unwrap();
+        assert!(after.migrations.is_empty());
+        assert!(ExecutionLogic::execute_block(&state, &[], 7).is_err());
+    }
+
+    #[test]
+    fn test_execute_within_access_sets() {
+        let mut rng = crate::testing::DeterministicRng::new(5);
+        let accounts = rng.accounts(2);
+        let (alice, bob) = (&accounts[0].address, &accounts[1].address);
+        let state = State::new();
+        state.set_balance(alice, 100);
+        state.set_uplp_balance(alice, 10);
+
+        let undeclared = accounts[0].transfer(bob, 40, 1, 0);
+        assert!(undeclared.writes.is_empty());
+        let err = ExecutionLogic::execute_within_access_sets(&state, &undeclared, ExecutionContext::Production).unwrap_err();
+        assert_eq!(err.to_string(), PlatariumError::from(ExecutionError::UndeclaredWrite(alice.clone())).to_string());
+        assert_eq!((state.get_balance(bob), state.get_nonce(alice)), (0, 0));
+
+        let derived = accounts[0].sign(undeclared.derive_access_sets().unwrap());
+        assert_eq!(derived.writes, HashSet::from([alice.clone(), bob.clone()]));
+        ExecutionLogic::execute_within_access_sets(&state, &derived, ExecutionContext::Production).unwrap();
+        assert_eq!(state.get_balance(bob), 40);
+    }
 }
@@ -251,11 +251,17 @@ pub fn calculate_load_multiplier(pending_tx_count: usize) -> u64 {
         // This is deterministic: same pending_tx_count → same load_percentage
         (pending_tx_count * 100) / MAX_BATCH_SIZE
     };
-    
+    load_multiplier_for_pct(load_percentage as u64)
+}
+
+/// Maps a network load percentage (0..=100, higher values count as 100) to its fee multiplier bucket.
+/// `calculate_load_multiplier` uses this after turning a pending count into a percentage;
+/// `core::load::NetworkLoad` uses it with its combined mempool/throughput load.
+pub fn load_multiplier_for_pct(load_pct: u64) -> u64 {
     // BUCKET SYSTEM: Map load percentage to multiplier using integer buckets
     // DETERMINISM: Same percentage → same bucket → same multiplier (always)
     // INTEGER ONLY: Uses integer range matching (0..=30, 31..=60, etc.)
-    match load_percentage {
+    match load_pct {
         0..=30 => MULTIPLIER_1X,      // Bucket 1x: 0-30% (LOW)
         31..=60 => MULTIPLIER_2X,    // Bucket 2x: 31-60% (MEDIUM)
         61..=80 => MULTIPLIER_3X,    // Bucket 3x: 61-80% (HIGH)
@@ -385,6 +391,65 @@ pub fn calculate_fee_from_load_micro_plp(pending_tx_count: usize) -> MicroPLP {
     calculate_fee_micro_plp(base_fee, multiplier)
 }
 
+/// Default minimum reputation (0..=SCORE_SCALE) a validator sender needs for a fee rebate.
+pub const FEE_REBATE_MIN_REPUTATION: u64 = 800_000;
+
+/// Default rebate percentage for high-reputation validator senders.
+pub const FEE_REBATE_PCT: u64 = 20;
+
+/// Optional fee discount policy: senders that are active registered validators with reputation at or above
+/// `min_reputation` get `rebate_pct` percent of their fee credited back. Disabled by default.
+///
+/// The fee is always charged in full first; the rebate is a separate, deterministic credit from the treasury
+/// back to the sender, so total μPLP is conserved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeRebatePolicy {
+    pub enabled: bool,
+    /// Minimum reputation score (0..=SCORE_SCALE) required for a rebate.
+    pub min_reputation: u64,
+    /// Rebate in percent of the fee (capped at 100).
+    pub rebate_pct: u64,
+}
+
+impl FeeRebatePolicy {
+    /// Policy that never grants a rebate.
+    pub const fn disabled() -> Self {
+        Self {
+            enabled: false,
+            min_reputation: FEE_REBATE_MIN_REPUTATION,
+            rebate_pct: 0,
+        }
+    }
+
+    /// Enabled policy with the given threshold and percentage.
+    pub const fn new(min_reputation: u64, rebate_pct: u64) -> Self {
+        Self {
+            enabled: true,
+            min_reputation,
+            rebate_pct,
+        }
+    }
+
+    /// Returns the rebate in μPLP for `fee_uplp` given the sender's validator reputation (`None` if the sender is not an active validator). Integer arithmetic, rounds down.
+    pub fn rebate_for(&self, fee_uplp: u128, sender_reputation: Option<u64>) -> u128 {
+        if !self.enabled {
+            return 0;
+        }
+        match sender_reputation {
+            Some(rep) if rep >= self.min_reputation => {
+                fee_uplp * self.rebate_pct.min(100) as u128 / 100
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl Default for FeeRebatePolicy {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
 /// Converts fee from micro-PLP to PLP (for display purposes)
 /// 
 /// DETERMINISM: This function is deterministic
@@ -418,6 +483,17 @@ pub fn fee_to_plp_string(fee_micro_plp: u64) -> String {
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_fee_rebate_policy() {
+        let policy = FeeRebatePolicy::new(FEE_REBATE_MIN_REPUTATION, 25);
+        assert_eq!(policy.rebate_for(100, Some(FEE_REBATE_MIN_REPUTATION)), 25);
+        assert_eq!(policy.rebate_for(100, Some(FEE_REBATE_MIN_REPUTATION - 1)), 0);
+        assert_eq!(policy.rebate_for(100, None), 0);
+        assert_eq!(policy.rebate_for(3, Some(1_000_000)), 0);
+        assert_eq!(FeeRebatePolicy::disabled().rebate_for(100, Some(1_000_000)), 0);
+        assert_eq!(FeeRebatePolicy::new(0, 150).rebate_for(100, Some(0)), 100);
+    }
+
     #[test]
     fn test_calculate_load_multiplier_bucket_1x() {
         // Bucket 1x: 0-30% load
@@ -0,0 +1,414 @@
+//! Governance-approved freeze orders on addresses (stolen-fund response), and governance-emitted outbound messages.
+//!
+//! A freeze order blocks every outgoing operation of `address` (transfers, approvals, delegated spends, fee payments) from `activation_height` until `expiry_height` (exclusive; `None` = until lifted). Incoming transfers are still accepted, so recovered funds can be returned.
+//!
+//! An order only takes effect when at least `GOVERNANCE_APPROVAL_THRESHOLD_PCT` of the active validators sign a `GovernanceAction::FreezeAddress` for it, which makes the freeze auditable and consensus-approved instead of an out-of-band node patch. Lifting it early takes the same supermajority over `GovernanceAction::LiftFreezeOrder`. Enacted orders live in `State` (part of snapshots and the state file) and emit `Event::AddressFreezeOrdered` and `Event::AddressFreezeLifted`.
+
+use std::collections::BTreeSet;
+use secp256k1::SecretKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use crate::core::events::Event;
+use crate::core::node_registry::{NodeId, NodeRegistry, NodeStatus};
+use crate::core::outbound::{check_message, GOVERNANCE_SENDER};
+use crate::core::state::{Address, State};
+use crate::error::{PlatariumError, Result};
+use crate::signature::{sign_message, verify_signature};
+
+/// Minimum share of active validators (percent) that must approve a freeze order.
+pub const GOVERNANCE_APPROVAL_THRESHOLD_PCT: u64 = 67;
+
+/// Errors produced by governance operations.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum GovernanceError {
+    #[error("No active validators to approve the order")]
+    NoActiveValidators,
+
+    #[error("Insufficient approvals: {approved} of {required} required")]
+    InsufficientApprovals { approved: usize, required: usize },
+
+    #[error("Approver is not an active validator: {0}")]
+    UnknownApprover(NodeId),
+
+    #[error("Approval signature of {0} does not verify")]
+    InvalidApproval(NodeId),
+
+    #[error("Expiry height {expiry} must be greater than activation height {activation}")]
+    InvalidWindow { activation: u64, expiry: u64 },
+
+    #[error("Freeze order already enacted: {0}")]
+    DuplicateOrder(String),
+
+    #[error("Unknown freeze order: {0}")]
+    UnknownOrder(String),
+
+    #[error("Freeze order already lifted or expired: {0}")]
+    OrderNotActive(String),
+}
+
+impl From<GovernanceError> for PlatariumError {
+    fn from(err: GovernanceError) -> Self {
+        PlatariumError::State(format!("Governance error: {}", err))
+    }
+}
+
+/// Freeze order on one address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressFreezeOrder {
+    pub address: Address,
+    /// First height at which the order is enforced.
+    pub activation_height: u64,
+    /// Height at which the order stops being enforced (exclusive). `None` = no expiry.
+    pub expiry_height: Option<u64>,
+    /// Human-readable justification (e.g. incident reference). Part of the order id.
+    pub reason: String,
+    /// Height from which a governance lift ends the order early. Not part of the order id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifted_at: Option<u64>,
+}
+
+impl AddressFreezeOrder {
+    pub fn new(
+        address: Address,
+        activation_height: u64,
+        expiry_height: Option<u64>,
+        reason: String,
+    ) -> std::result::Result<Self, GovernanceError> {
+        if let Some(expiry) = expiry_height {
+            if expiry <= activation_height {
+                return Err(GovernanceError::InvalidWindow {
+                    activation: activation_height,
+                    expiry,
+                });
+            }
+        }
+        Ok(Self {
+            address,
+            activation_height,
+            expiry_height,
+            reason,
+            lifted_at: None,
+        })
+    }
+
+    /// Deterministic order id: SHA256 hex over address, window and reason.
+    pub fn id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(b"PlatariumFreezeOrder:");
+        hasher.update(self.address.as_bytes());
+        hasher.update(self.activation_height.to_le_bytes());
+        hasher.update(self.expiry_height.unwrap_or(u64::MAX).to_le_bytes());
+        hasher.update(self.reason.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// True if the order is enforced at `height`.
+    pub fn is_active_at(&self, height: u64) -> bool {
+        height >= self.activation_height
+            && self.expiry_height.is_none_or(|e| height < e)
+            && self.lifted_at.is_none_or(|l| height < l)
+    }
+}
+
+/// An action validators approve by signing it with their node keys. The variant tag is part of the signed payload,
+/// so an approval of one action cannot be replayed as another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum GovernanceAction {
+    /// `claims_root` (lowercase hex) is final for `chain_id` at `height`.
+    AttestForeignHeader { chain_id: String, height: u64, claims_root: String },
+    /// `asset` (canonical) is the wrapped form of `source_chain`'s funds.
+    RegisterWrappedAsset { asset: String, source_chain: String },
+    /// Enact the freeze order with id `order_id` (`AddressFreezeOrder::id`).
+    FreezeAddress { order_id: String },
+    /// End the freeze order `order_id` early.
+    LiftFreezeOrder { order_id: String },
+    /// `confirming_nodes` (sorted) confirmed the invalid transaction `tx_hash`, included at `block_height`.
+    RecordInvalidTxEvidence { tx_hash: String, block_height: u64, confirming_nodes: Vec<NodeId> },
+    /// Pay the insurance claim `claim_id` (`InsuranceClaim::id`) with exactly these amounts.
+    PayInsuranceClaim { claim_id: String, amount: u128, fee_refund_uplp: u128 },
+}
+
+#[derive(Serialize)]
+struct ApprovalPayload<'a> {
+    approver: &'a str,
+    action: &'a GovernanceAction,
+}
+
+/// A validator's signature over a [`GovernanceAction`], made with its registered node key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Approval {
+    pub approver: NodeId,
+    pub signature: String,
+}
+
+impl Approval {
+    /// Signs `action` as `approver` with the node key.
+    pub fn sign(approver: NodeId, action: &GovernanceAction, secret_key: &SecretKey) -> Result<Self> {
+        let payload = ApprovalPayload { approver: &approver, action };
+        let signature = sign_message(secret_key, &payload)?.signature_compact;
+        Ok(Self { approver, signature })
+    }
+
+    /// True if signed over `action` by an active validator's registered key.
+    pub fn verify(&self, action: &GovernanceAction, registry: &NodeRegistry) -> bool {
+        let Some(node) = registry.get(&self.approver).filter(|n| n.status == NodeStatus::Active) else {
+            return false;
+        };
+        let key = node.public_key.strip_prefix("Px").unwrap_or(&node.public_key);
+        let payload = ApprovalPayload { approver: &self.approver, action };
+        matches!(verify_signature(&payload, &self.signature, key), Ok(true))
+    }
+}
+
+/// Checks that every approval is an active validator's valid signature over `action` and that the distinct
+/// approvers reach the approval threshold.
+pub fn check_signed_approvals(
+    registry: &NodeRegistry,
+    action: &GovernanceAction,
+    approvals: &[Approval],
+) -> Result<()> {
+    for approval in approvals {
+        if registry.get(&approval.approver).is_none_or(|n| n.status != NodeStatus::Active) {
+            return Err(GovernanceError::UnknownApprover(approval.approver.clone()).into());
+        }
+        if !approval.verify(action, registry) {
+            return Err(GovernanceError::InvalidApproval(approval.approver.clone()).into());
+        }
+    }
+    let approvers: Vec<NodeId> = approvals.iter().map(|a| a.approver.clone()).collect();
+    check_approvals(registry, &approvers)
+}
+
+/// Checks that `approvers` (deduplicated) are active validators and reach the approval threshold. The ids are not
+/// authenticated; [`check_signed_approvals`] also checks each validator's signature.
+pub fn check_approvals(registry: &NodeRegistry, approvers: &[NodeId]) -> Result<()> {
+    let active = registry.eligible_summaries().len();
+    if active == 0 {
+        return Err(GovernanceError::NoActiveValidators.into());
+    }
+    let distinct: BTreeSet<&NodeId> = approvers.iter().collect();
+    for id in &distinct {
+        match registry.get(id) {
+            Some(n) if n.status == NodeStatus::Active => {}
+            _ => return Err(GovernanceError::UnknownApprover((*id).clone()).into()),
+        }
+    }
+    let required = (active as u64 * GOVERNANCE_APPROVAL_THRESHOLD_PCT).div_ceil(100) as usize;
+    if distinct.len() < required {
+        return Err(GovernanceError::InsufficientApprovals {
+            approved: distinct.len(),
+            required,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Enacts `order` in `state` after checking validator signatures over `GovernanceAction::FreezeAddress` for its
+/// id. Returns the order id.
+pub fn enact_freeze_order(
+    state: &State,
+    registry: &NodeRegistry,
+    order: AddressFreezeOrder,
+    approvals: &[Approval],
+) -> Result<String> {
+    let id = order.id();
+    check_signed_approvals(registry, &GovernanceAction::FreezeAddress { order_id: id.clone() }, approvals)?;
+    state.add_freeze_order(id.clone(), AddressFreezeOrder { lifted_at: None, ..order.clone() })?;
+    state.emit(Event::AddressFreezeOrdered {
+        order_id: id.clone(),
+        address: order.address,
+        activation_height: order.activation_height,
+        expiry_height: order.expiry_height,
+    });
+    Ok(id)
+}
+
+/// Lifts the freeze order `order_id` from the current block height on, after checking validator signatures over
+/// `GovernanceAction::LiftFreezeOrder`. Errors for an unknown order and for one already lifted or expired.
+pub fn lift_freeze_order(
+    state: &State,
+    registry: &NodeRegistry,
+    order_id: &str,
+    approvals: &[Approval],
+) -> Result<()> {
+    check_signed_approvals(registry, &GovernanceAction::LiftFreezeOrder { order_id: order_id.to_string() }, approvals)?;
+    let height = state.block_height();
+    let order = state.lift_freeze_order(order_id, height)?;
+    state.emit(Event::AddressFreezeLifted { order_id: order_id.to_string(), address: order.address, height });
+    Ok(())
+}
+
+/// Emits an outbound message from [`GOVERNANCE_SENDER`] after checking validator approvals, e.g. to instruct a
+/// bridge contract. It is committed in the current block's messages like a `SendMessage` transaction.
+pub fn emit_governance_message(
+    state: &State,
+    registry: &NodeRegistry,
+    destination: String,
+    payload_hex: &str,
+    approvers: &[NodeId],
+) -> Result<()> {
+    check_approvals(registry, approvers)?;
+    let payload = check_message(&destination, payload_hex)
+        .map_err(|e| PlatariumError::Validation(format!("Invalid message: {}", e)))?;
+    state.emit(Event::MessageSent {
+        sender: GOVERNANCE_SENDER.to_string(),
+        destination,
+        payload: hex::encode(payload),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(n: usize) -> NodeRegistry {
+        let r = NodeRegistry::new();
+        for i in 0..n {
+            r.register(format!("n{}", i), format!("pk{}", i), 100, 10).unwrap();
+        }
+        r
+    }
+
+    /// Registry of `n` validators with real node keys.
+    fn validators(n: usize) -> (NodeRegistry, Vec<(NodeId, SecretKey)>) {
+        let mut rng = crate::testing::DeterministicRng::new(9);
+        let r = NodeRegistry::new();
+        let keys: Vec<_> = (0..n).map(|i| (format!("n{}", i), rng.secret_key())).collect();
+        for (id, key) in &keys {
+            r.register(id.clone(), hex::encode(key.public_key(secp256k1::SECP256K1).serialize()), 100, 10).unwrap();
+        }
+        (r, keys)
+    }
+
+    fn approve(keys: &[(NodeId, SecretKey)], action: &GovernanceAction) -> Vec<Approval> {
+        keys.iter().map(|(id, key)| Approval::sign(id.clone(), action, key).unwrap()).collect()
+    }
+
+    fn freeze(keys: &[(NodeId, SecretKey)], order: &AddressFreezeOrder) -> Vec<Approval> {
+        approve(keys, &GovernanceAction::FreezeAddress { order_id: order.id() })
+    }
+
+    #[test]
+    fn test_order_window() {
+        let o = AddressFreezeOrder::new("thief".into(), 10, Some(20), "incident-1".into()).unwrap();
+        assert!(!o.is_active_at(9));
+        assert!(o.is_active_at(10));
+        assert!(o.is_active_at(19));
+        assert!(!o.is_active_at(20));
+        assert!(AddressFreezeOrder::new("thief".into(), 10, Some(10), String::new()).is_err());
+    }
+
+    #[test]
+    fn test_enact_requires_signed_supermajority() {
+        let (reg, keys) = validators(4);
+        let state = State::new();
+        let order = AddressFreezeOrder::new("thief".into(), 0, None, "incident-1".into()).unwrap();
+        let approvals = freeze(&keys, &order);
+        let twice = vec![approvals[0].clone(), approvals[0].clone()];
+        assert!(enact_freeze_order(&state, &reg, order.clone(), &twice).is_err());
+        assert!(enact_freeze_order(&state, &reg, order.clone(), &approvals[..2]).is_err());
+        // Signatures over another order, or bare ids with forged signatures, do not count.
+        let other = AddressFreezeOrder::new("victim".into(), 0, None, "incident-1".into()).unwrap();
+        assert!(enact_freeze_order(&state, &reg, order.clone(), &freeze(&keys, &other)).is_err());
+        let forged: Vec<Approval> =
+            keys.iter().map(|(id, _)| Approval { approver: id.clone(), signature: "00".repeat(64) }).collect();
+        assert!(enact_freeze_order(&state, &reg, order.clone(), &forged).is_err());
+
+        let id = enact_freeze_order(&state, &reg, order.clone(), &approvals[..3]).unwrap();
+        assert_eq!(id, order.id());
+        assert_eq!(state.active_freeze_order(&"thief".to_string(), 0).map(|(i, _)| i), Some(id));
+        assert!(enact_freeze_order(&state, &reg, order, &approvals).is_err());
+    }
+
+    #[test]
+    fn test_lift_ends_order_from_current_height() {
+        let (reg, keys) = validators(3);
+        let state = State::new();
+        let thief = "thief".to_string();
+        let order = AddressFreezeOrder::new(thief.clone(), 0, None, "incident-3".into()).unwrap();
+        let id = enact_freeze_order(&state, &reg, order.clone(), &freeze(&keys, &order)).unwrap();
+        state.take_events();
+
+        let lift = approve(&keys, &GovernanceAction::LiftFreezeOrder { order_id: id.clone() });
+        assert!(lift_freeze_order(&state, &reg, &id, &lift[..1]).is_err());
+        // Approvals to enact the order cannot lift it.
+        assert!(lift_freeze_order(&state, &reg, &id, &freeze(&keys, &order)).is_err());
+        state.set_block_height(7);
+        lift_freeze_order(&state, &reg, &id, &lift).unwrap();
+        assert!(state.check_not_frozen(&thief).is_ok());
+        assert!(state.active_freeze_order(&thief, 6).is_some());
+        assert_eq!(
+            state.take_events(),
+            vec![Event::AddressFreezeLifted { order_id: id.clone(), address: thief, height: 7 }]
+        );
+        // A lifted order stays on record and cannot be lifted or enacted again.
+        assert!(lift_freeze_order(&state, &reg, &id, &lift).is_err());
+        assert!(enact_freeze_order(&state, &reg, order.clone(), &freeze(&keys, &order)).is_err());
+        let unknown = "00".repeat(32);
+        let lift_unknown = approve(&keys, &GovernanceAction::LiftFreezeOrder { order_id: unknown.clone() });
+        assert!(lift_freeze_order(&state, &reg, &unknown, &lift_unknown).is_err());
+    }
+
+    #[test]
+    fn test_frozen_address_cannot_send_until_expiry() {
+        use crate::core::asset::Asset;
+        use crate::core::transaction::Transaction;
+        use std::collections::HashSet;
+
+        let (reg, keys) = validators(1);
+        let state = State::new();
+        let thief = crate::testing::DeterministicRng::new(1).account().address;
+        state.set_balance(&thief, 100);
+        state.set_balance(&"victim".to_string(), 100);
+        let order = AddressFreezeOrder::new(thief.clone(), 5, Some(10), "incident-2".into()).unwrap();
+        let id = enact_freeze_order(&state, &reg, order.clone(), &freeze(&keys, &order)).unwrap();
+
+        let tx = Transaction::new(
+            thief.clone(),
+            "fence".to_string(),
+            Asset::PLP,
+            10,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            "sig".to_string(),
+            "sig".to_string(),
+        )
+        .unwrap();
+        state.set_block_height(4);
+        assert!(state.check_not_frozen(&thief).is_ok());
+        state.set_block_height(5);
+        let err = state.apply_effects(&tx).unwrap_err().to_string();
+        assert!(err.contains(&id));
+        // Incoming funds are still accepted.
+        state
+            .apply_transfer(&"victim".to_string(), &thief, &Asset::PLP, 1, 1, None)
+            .unwrap();
+        state.set_block_height(10);
+        state.apply_effects(&tx).unwrap();
+        assert_eq!(state.get_balance(&"fence".to_string()), 10);
+    }
+
+    #[test]
+    fn test_governance_message_requires_supermajority() {
+        let reg = registry(3);
+        let state = State::new();
+        assert!(emit_governance_message(&state, &reg, "eth:bridge".into(), "01", &["n0".into(), "n1".into()]).is_err());
+        let approvers = vec!["n0".to_string(), "n1".to_string(), "n2".to_string()];
+        assert!(emit_governance_message(&state, &reg, "no spaces".into(), "01", &approvers).is_err());
+        emit_governance_message(&state, &reg, "eth:bridge".into(), "0A", &approvers).unwrap();
+        assert_eq!(
+            state.take_events(),
+            vec![Event::MessageSent {
+                sender: GOVERNANCE_SENDER.into(),
+                destination: "eth:bridge".into(),
+                payload: "0a".into(),
+            }]
+        );
+    }
+}
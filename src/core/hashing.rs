@@ -0,0 +1,390 @@
+//! Versioned consensus hash functions and golden vectors.
+//!
+//! Each function here is pinned to one preimage format, named by its version: `tx_hash_v1` is the SHA256 of
+//! `PlatariumSignature:` + compact JSON signing payload, `tx_hash_v2` the SHA256 of `PlatariumSignature:` + binary
+//! signing payload, `block_hash_v1` the SHA256 of the block signing payload, `state_root_v1` the SHA256 over sorted
+//! PLP balances then sorted nonces, and `state_commitment_v1` the header state root of `State::current_root`. A new
+//! format gets a new function; an existing one never changes.
+//!
+//! [`golden_vectors`] are fixed inputs with their expected outputs, compiled into the crate. A node calls
+//! [`self_check`] at startup and refuses to run if any output differs, e.g. after a dependency upgrade changed JSON
+//! serialization, instead of forking off the network on its first block.
+
+use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use crate::core::asset::Asset;
+use crate::core::block_assembly::Block;
+use crate::core::state_commitment::AccountCommitment;
+use crate::core::transaction::{SigningPayload, Transaction, TxKind, TxVersion};
+use crate::error::PlatariumError;
+use crate::signature::SignatureScheme;
+
+/// Transaction hash over the V1 (JSON) preimage, whatever `tx.version` says. Equals `tx.hash` for a V1 transaction.
+pub fn tx_hash_v1(tx: &Transaction) -> String {
+    hex::encode(SigningPayload::from_transaction(tx).version(TxVersion::V1).hash())
+}
+
+/// Transaction hash over the V2 (binary) preimage, whatever `tx.version` says. Equals `tx.hash` for a V2 transaction,
+/// the default of `TransactionBuilder`.
+pub fn tx_hash_v2(tx: &Transaction) -> String {
+    hex::encode(SigningPayload::from_transaction(tx).version(TxVersion::V2).hash())
+}
+
+/// Block hash over the header fields; the producer signature and transaction list are not covered (the list is
+/// committed through `merkle_root`).
+pub fn block_hash_v1(block: &Block) -> String {
+    hex::encode(Sha256::digest(block.signing_payload()))
+}
+
+/// State root of PLP `balances` and `nonces`. Both are sorted by address before hashing, so input order does not
+/// matter; zero entries are hashed like any other, as `StateSnapshot::compute_state_root` does.
+pub fn state_root_v1(balances: &[(&str, u128)], nonces: &[(&str, u64)]) -> String {
+    let mut balances = balances.to_vec();
+    let mut nonces = nonces.to_vec();
+    balances.sort_by(|a, b| a.0.cmp(b.0));
+    nonces.sort_by(|a, b| a.0.cmp(b.0));
+    hex::encode(platarium_consensus::snapshot_state_root(balances, nonces))
+}
+
+/// State commitment of `State::current_root`: the account tree over `accounts` `(address, PLP, nonce, μPLP)` and
+/// `tokens` `(address, canonical asset, balance)`, combined with the digest of the encoded `registries` (the JSON of
+/// `StateFileData::registries_of`). Zero entries are left out of the tree, so they do not change the root.
+pub fn state_commitment_v1(
+    accounts: &[(&str, u128, u64, u128)],
+    tokens: &[(&str, &str, u128)],
+    registries: &[u8],
+) -> String {
+    let accounts: Vec<_> = accounts.iter().map(|&(a, b, n, u)| (a.to_string(), b, n, u)).collect();
+    let tokens: Vec<_> = tokens.iter().map(|&(a, asset, b)| (a.to_string(), asset, b)).collect();
+    let mut tree = AccountCommitment::from_entries(
+        accounts.iter().map(|(a, b, n, u)| (a, *b, *n, *u)),
+        tokens.iter().map(|(a, asset, b)| (a, *asset, *b)),
+    );
+    let registries = platarium_consensus::registries_digest(registries);
+    hex::encode(platarium_consensus::state_commitment(&tree.root(), &registries))
+}
+
+/// Input of a golden vector, by hash function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenInput {
+    /// Signing fields of a transaction; `token: None` is PLP.
+    TxV1 {
+        from: &'static str,
+        to: &'static str,
+        token: Option<&'static str>,
+        amount: u128,
+        fee_uplp: u128,
+        nonce: u64,
+        reads: &'static [&'static str],
+        writes: &'static [&'static str],
+        chain_id: Option<&'static str>,
+    },
+    /// The same signing fields hashed over the binary preimage.
+    TxV2 {
+        from: &'static str,
+        to: &'static str,
+        token: Option<&'static str>,
+        amount: u128,
+        fee_uplp: u128,
+        nonce: u64,
+        reads: &'static [&'static str],
+        writes: &'static [&'static str],
+        chain_id: Option<&'static str>,
+    },
+    BlockV1 {
+        block_number: u64,
+        previous_hash: &'static str,
+        timestamp: i64,
+        merkle_root: &'static str,
+        state_root: &'static str,
+        producer_id: &'static str,
+        messages_root: Option<&'static str>,
+    },
+    StateRootV1 {
+        balances: &'static [(&'static str, u128)],
+        nonces: &'static [(&'static str, u64)],
+    },
+    StateCommitmentV1 {
+        accounts: &'static [(&'static str, u128, u64, u128)],
+        tokens: &'static [(&'static str, &'static str, u128)],
+        registries: &'static str,
+    },
+}
+
+/// A fixed input and the hex hash every compatible node computes for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoldenVector {
+    pub name: &'static str,
+    pub input: GoldenInput,
+    pub expected: &'static str,
+}
+
+impl GoldenVector {
+    /// Hash of `input` under this build.
+    pub fn compute(&self) -> String {
+        match self.input {
+            GoldenInput::TxV1 { from, to, token, amount, fee_uplp, nonce, reads, writes, chain_id } => {
+                tx_hash_v1(&golden_tx(from, to, token, amount, fee_uplp, nonce, reads, writes, chain_id))
+            }
+            GoldenInput::TxV2 { from, to, token, amount, fee_uplp, nonce, reads, writes, chain_id } => {
+                tx_hash_v2(&golden_tx(from, to, token, amount, fee_uplp, nonce, reads, writes, chain_id))
+            }
+            GoldenInput::BlockV1 {
+                block_number,
+                previous_hash,
+                timestamp,
+                merkle_root,
+                state_root,
+                producer_id,
+                messages_root,
+            } => block_hash_v1(&Block {
+                block_number,
+                previous_hash: previous_hash.to_string(),
+                timestamp,
+                transaction_hashes: Vec::new(),
+                merkle_root: merkle_root.to_string(),
+                state_root: state_root.to_string(),
+                block_hash: String::new(),
+                producer_id: producer_id.to_string(),
+                producer_sig: String::new(),
+                messages_root: messages_root.map(str::to_string),
+            }),
+            GoldenInput::StateRootV1 { balances, nonces } => state_root_v1(balances, nonces),
+            GoldenInput::StateCommitmentV1 { accounts, tokens, registries } => {
+                state_commitment_v1(accounts, tokens, registries.as_bytes())
+            }
+        }
+    }
+}
+
+/// Unsigned PLP or token transfer with the given signing fields; `version` is left at `V1` and overridden by the
+/// hash function.
+#[allow(clippy::too_many_arguments)]
+fn golden_tx(
+    from: &str,
+    to: &str,
+    token: Option<&str>,
+    amount: u128,
+    fee_uplp: u128,
+    nonce: u64,
+    reads: &[&str],
+    writes: &[&str],
+    chain_id: Option<&str>,
+) -> Transaction {
+    let set = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<HashSet<_>>();
+    Transaction {
+        hash: String::new(),
+        from: from.to_string(),
+        to: to.to_string(),
+        asset: token.map_or(Asset::PLP, |t| Asset::Token(t.to_string())),
+        amount,
+        fee_uplp,
+        nonce,
+        reads: set(reads),
+        writes: set(writes),
+        sig_main: String::new(),
+        sig_derived: String::new(),
+        pub_main: None,
+        pub_derived: None,
+        kind: TxKind::Transfer,
+        deployment_salt: None,
+        valid_until: None,
+        chain_id: chain_id.map(str::to_string),
+        sig_scheme: SignatureScheme::Ecdsa,
+        multisig: None,
+        version: TxVersion::V1,
+    }
+}
+
+const GOLDEN_VECTORS: &[GoldenVector] = &[
+    GoldenVector {
+        name: "tx_v1/plp_transfer",
+        input: GoldenInput::TxV1 {
+            from: "alice",
+            to: "bob",
+            token: None,
+            amount: 1_000_000,
+            fee_uplp: 1,
+            nonce: 0,
+            reads: &[],
+            writes: &[],
+            chain_id: None,
+        },
+        expected: "7339cc395f00beff3f559887d0d80e6169d0ffeae2d4057540ccf34722cffe53",
+    },
+    GoldenVector {
+        name: "tx_v1/token_with_sets_and_chain",
+        input: GoldenInput::TxV1 {
+            from: "alice",
+            to: "bob",
+            token: Some("USDT"),
+            amount: u128::MAX,
+            fee_uplp: 250,
+            nonce: 42,
+            reads: &["bob", "alice"],
+            writes: &["alice", "bob"],
+            chain_id: Some("platarium-testnet"),
+        },
+        expected: "3a6f4cf0e3233ccbf78a4a1e79f4e0e917f22176ed076bf17e39706659ccafde",
+    },
+    GoldenVector {
+        name: "tx_v2/plp_transfer",
+        input: GoldenInput::TxV2 {
+            from: "alice",
+            to: "bob",
+            token: None,
+            amount: 1_000_000,
+            fee_uplp: 1,
+            nonce: 0,
+            reads: &[],
+            writes: &[],
+            chain_id: None,
+        },
+        expected: "9b8d598d026b2d5a2581b774aab63cd278cb9234ef0b266170c185119535dd8d",
+    },
+    GoldenVector {
+        name: "tx_v2/token_with_sets_and_chain",
+        input: GoldenInput::TxV2 {
+            from: "alice",
+            to: "bob",
+            token: Some("USDT"),
+            amount: u128::MAX,
+            fee_uplp: 250,
+            nonce: 42,
+            reads: &["bob", "alice"],
+            writes: &["alice", "bob"],
+            chain_id: Some("platarium-testnet"),
+        },
+        expected: "0bf0f072af6eb6797778103b1d39e7d550f62b7d828510194a9b16d076c95806",
+    },
+    GoldenVector {
+        name: "block_v1/no_messages",
+        input: GoldenInput::BlockV1 {
+            block_number: 1,
+            previous_hash: "0",
+            timestamp: 1_700_000_000,
+            merkle_root: "0",
+            state_root: "0",
+            producer_id: "node-1",
+            messages_root: None,
+        },
+        expected: "826e29b7b639b1986dd50fc15744732a2c35170e10ad7ed435c7d012b6584799",
+    },
+    GoldenVector {
+        name: "block_v1/with_messages",
+        input: GoldenInput::BlockV1 {
+            block_number: u64::MAX,
+            previous_hash: "ab",
+            timestamp: -1,
+            merkle_root: "cd",
+            state_root: "ef",
+            producer_id: "node-2",
+            messages_root: Some("01"),
+        },
+        expected: "e03f0e476631d7bcea8eb8b6776b0c92833d1fc4ef37c3b2d98e9b8e3511b2c4",
+    },
+    GoldenVector {
+        name: "state_root_v1/empty",
+        input: GoldenInput::StateRootV1 { balances: &[], nonces: &[] },
+        expected: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    },
+    GoldenVector {
+        name: "state_root_v1/accounts",
+        input: GoldenInput::StateRootV1 {
+            balances: &[("bob", 5), ("alice", u128::MAX)],
+            nonces: &[("alice", 3)],
+        },
+        expected: "b1351335db75e0659fd0e0c6e9952981c48a318bb49c0b44ecdac5ba8595a705",
+    },
+    GoldenVector {
+        name: "state_commitment_v1/empty",
+        input: GoldenInput::StateCommitmentV1 { accounts: &[], tokens: &[], registries: "{}" },
+        expected: "a81ea6d2b705e6c107007026c5bf30114e123b67e878d48c73e125653cb7d27e",
+    },
+    GoldenVector {
+        name: "state_commitment_v1/accounts_and_tokens",
+        input: GoldenInput::StateCommitmentV1 {
+            accounts: &[("bob", 5, 0, 0), ("alice", u128::MAX, 3, 7), ("carol", 0, 0, 0)],
+            tokens: &[("bob", "Token:USDT", 9), ("alice", "Token:USDT", 0)],
+            registries: "{}",
+        },
+        expected: "6c9c6bf80322fe6360f41634f77709cbb2910ae1367361500934f24a9d2996cb",
+    },
+];
+
+/// Golden vectors compiled into this build, in a fixed order.
+pub fn golden_vectors() -> &'static [GoldenVector] {
+    GOLDEN_VECTORS
+}
+
+/// Errors from [`self_check`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HashingError {
+    #[error("Golden vector {name}: expected {expected}, computed {computed}")]
+    GoldenMismatch {
+        name: &'static str,
+        expected: &'static str,
+        computed: String,
+    },
+}
+
+impl From<HashingError> for PlatariumError {
+    fn from(err: HashingError) -> Self {
+        PlatariumError::State(format!("Hashing error: {}", err))
+    }
+}
+
+/// Recomputes every golden vector; fails on the first output that differs from the compiled-in one.
+pub fn self_check() -> Result<(), HashingError> {
+    for vector in GOLDEN_VECTORS {
+        let computed = vector.compute();
+        if computed != vector.expected {
+            return Err(HashingError::GoldenMismatch { name: vector.name, expected: vector.expected, computed });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::State;
+    use crate::core::state_file::StateFileData;
+    use crate::core::transaction::{TransactionBuilder, MIN_FEE_UPLP};
+    use crate::testing::DeterministicRng;
+
+    #[test]
+    fn test_self_check_passes() {
+        self_check().unwrap();
+        assert_eq!(golden_vectors().len(), 10);
+    }
+
+    #[test]
+    fn test_versioned_functions_match_live_hashes() {
+        let accounts = DeterministicRng::new(7).accounts(2);
+        let tx = accounts[0].transfer(&accounts[1].address, 10, 1, 0);
+        assert_eq!(tx_hash_v1(&tx), tx.hash);
+
+        let state = State::new();
+        state.set_balance(&"bob".to_string(), 5);
+        state.set_balance(&"alice".to_string(), u128::MAX);
+        state.set_nonce(&"alice".to_string(), 3);
+        let root = state.snapshot().compute_state_root();
+        assert_eq!(root, state_root_v1(&[("bob", 5), ("alice", u128::MAX)], &[("alice", 3)]));
+
+        let keys = crate::key_generator::KeyGenerator::new(0, None, None, None).unwrap().generate_keys().unwrap();
+        let v2 = TransactionBuilder::new(keys.public_key.clone(), "receiver", Asset::PLP, 10, MIN_FEE_UPLP, 0)
+            .sign_with_key_pair(&keys)
+            .unwrap();
+        assert_eq!(v2.version, TxVersion::V2);
+        assert_eq!(tx_hash_v2(&v2), v2.hash);
+        assert_ne!(tx_hash_v1(&v2), v2.hash);
+
+        state.set_uplp_balance(&"alice".to_string(), 7);
+        state.set_asset_balance(&"bob".to_string(), &Asset::Token("USDT".into()), 9);
+        let registries = serde_json::to_vec(&StateFileData::registries_of(&state)).unwrap();
+        let accounts = [("bob", 5, 0, 0), ("alice", u128::MAX, 3, 7)];
+        assert_eq!(state.current_root(), state_commitment_v1(&accounts, &[("bob", "Token:USDT", 9)], &registries));
+    }
+}
@@ -0,0 +1,247 @@
+//! Chain head notifications for embedding applications (indexers, bridges, wallets).
+//!
+//! [`HeadPublisher`] holds the latest [`HeadInfo`]; `Core` owns one and updates it from `Core::commit_block` or
+//! `Core::publish_head`. Followers subscribe with `Core::head_updates_sync` ([`HeadReceiver`], std threads only)
+//! or, with the `tokio` feature, `Core::head_updates` (a `tokio::sync::watch::Receiver`). Both behave the same.
+//!
+//! # Backpressure
+//! The channel stores one value, the newest head, not a queue. Publishing never blocks and never allocates per
+//! subscriber, so a slow or stalled follower cannot hold up block production or grow memory. The cost is on
+//! the follower: if several heads are published between two reads, it sees only the last one. A follower that
+//! needs every block compares `height` with the last height it processed and reads the gap from storage
+//! (`storage::get_block`). Dropping a receiver unsubscribes it; dropping the publisher wakes every waiting
+//! receiver with an error.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::error::{PlatariumError, Result};
+use crate::storage::{get_block, get_state_root, BlockCommit, RocksStore};
+
+/// Latest block of the local chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadInfo {
+    pub height: u64,
+    pub block_hash: String,
+    pub state_root: String,
+    /// Highest height that can no longer be reverted; equals `height` for blocks committed to storage.
+    pub finalized_height: u64,
+}
+
+impl HeadInfo {
+    /// Head of a committed block. Committed blocks are final.
+    pub fn from_commit(commit: &BlockCommit) -> Self {
+        Self {
+            height: commit.block.height,
+            block_hash: commit.block.block_hash.clone(),
+            state_root: commit.state_root.clone(),
+            finalized_height: commit.block.height,
+        }
+    }
+
+    /// Head recorded in `store`; the default (height 0) for an empty store.
+    pub fn from_store(store: &RocksStore) -> Result<Self> {
+        let height = store.head_height()?;
+        let Some(block) = get_block(store, height)? else {
+            return Ok(Self::default());
+        };
+        Ok(Self {
+            height,
+            block_hash: block.block_hash,
+            state_root: get_state_root(store, height)?.unwrap_or(block.state_root),
+            finalized_height: height,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Slot {
+    head: HeadInfo,
+    /// Incremented on every publish; receivers compare it with the version they last saw.
+    version: u64,
+    closed: bool,
+}
+
+#[derive(Debug)]
+struct Shared {
+    slot: Mutex<Slot>,
+    changed: Condvar,
+}
+
+/// Sending side of the head channel; see the module docs.
+#[derive(Debug)]
+pub struct HeadPublisher {
+    shared: Arc<Shared>,
+    #[cfg(feature = "tokio")]
+    watch: tokio::sync::watch::Sender<HeadInfo>,
+}
+
+impl HeadPublisher {
+    pub fn new(head: HeadInfo) -> Self {
+        Self {
+            #[cfg(feature = "tokio")]
+            watch: tokio::sync::watch::Sender::new(head.clone()),
+            shared: Arc::new(Shared {
+                slot: Mutex::new(Slot {
+                    head,
+                    version: 0,
+                    closed: false,
+                }),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Replaces the head and wakes all receivers. Never blocks on receivers.
+    pub fn publish(&self, head: HeadInfo) {
+        #[cfg(feature = "tokio")]
+        self.watch.send_replace(head.clone());
+        let mut slot = self.shared.slot.lock().unwrap();
+        slot.head = head;
+        slot.version += 1;
+        self.shared.changed.notify_all();
+    }
+
+    /// Current head.
+    pub fn head(&self) -> HeadInfo {
+        self.shared.slot.lock().unwrap().head.clone()
+    }
+
+    /// New receiver; the current head counts as already seen.
+    pub fn subscribe(&self) -> HeadReceiver {
+        HeadReceiver {
+            shared: Arc::clone(&self.shared),
+            seen: self.shared.slot.lock().unwrap().version,
+        }
+    }
+
+    /// New tokio watch receiver; the current head counts as already seen.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn subscribe_watch(&self) -> tokio::sync::watch::Receiver<HeadInfo> {
+        self.watch.subscribe()
+    }
+}
+
+impl Default for HeadPublisher {
+    fn default() -> Self {
+        Self::new(HeadInfo::default())
+    }
+}
+
+impl Drop for HeadPublisher {
+    fn drop(&mut self) {
+        if let Ok(mut slot) = self.shared.slot.lock() {
+            slot.closed = true;
+        }
+        self.shared.changed.notify_all();
+    }
+}
+
+/// Blocking head receiver for embedders without an async runtime; mirrors `tokio::sync::watch::Receiver`.
+#[derive(Debug, Clone)]
+pub struct HeadReceiver {
+    shared: Arc<Shared>,
+    seen: u64,
+}
+
+impl HeadReceiver {
+    /// Current head, without marking it seen.
+    pub fn borrow(&self) -> HeadInfo {
+        self.shared.slot.lock().unwrap().head.clone()
+    }
+
+    /// Current head, marking it seen.
+    pub fn borrow_and_update(&mut self) -> HeadInfo {
+        let slot = self.shared.slot.lock().unwrap();
+        self.seen = slot.version;
+        slot.head.clone()
+    }
+
+    /// True if a head was published since the last one seen.
+    pub fn has_changed(&self) -> bool {
+        self.shared.slot.lock().unwrap().version != self.seen
+    }
+
+    /// Blocks until a head newer than the last one seen is published and returns it (marked seen). Errors once
+    /// the publisher is dropped.
+    pub fn changed(&mut self) -> Result<HeadInfo> {
+        let seen = self.seen;
+        let shared = Arc::clone(&self.shared);
+        let slot = shared
+            .changed
+            .wait_while(shared.slot.lock().unwrap(), |s| s.version == seen && !s.closed)
+            .unwrap();
+        take(&mut self.seen, &slot)
+    }
+
+    /// Like [`HeadReceiver::changed`], but gives up after `timeout` and returns `Ok(None)`.
+    pub fn changed_timeout(&mut self, timeout: Duration) -> Result<Option<HeadInfo>> {
+        let deadline = Instant::now() + timeout;
+        let shared = Arc::clone(&self.shared);
+        let mut slot = shared.slot.lock().unwrap();
+        while slot.version == self.seen && !slot.closed {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(None);
+            }
+            slot = shared.changed.wait_timeout(slot, left).unwrap().0;
+        }
+        take(&mut self.seen, &slot).map(Some)
+    }
+}
+
+/// The slot's head, marking it seen; errors if nothing new was published (the publisher is gone).
+fn take(seen: &mut u64, slot: &Slot) -> Result<HeadInfo> {
+    if slot.version == *seen {
+        return Err(PlatariumError::State("head channel closed".into()));
+    }
+    *seen = slot.version;
+    Ok(slot.head.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(height: u64) -> HeadInfo {
+        HeadInfo {
+            height,
+            block_hash: format!("bh{}", height),
+            state_root: format!("root{}", height),
+            finalized_height: height.saturating_sub(1),
+        }
+    }
+
+    #[test]
+    fn test_slow_receiver_sees_latest_head_only() {
+        let publisher = HeadPublisher::default();
+        let mut rx = publisher.subscribe();
+        assert!(!rx.has_changed());
+        assert_eq!(rx.changed_timeout(Duration::from_millis(1)).unwrap(), None);
+
+        for height in 1..=5 {
+            publisher.publish(head(height));
+        }
+        assert!(rx.has_changed());
+        assert_eq!(rx.changed().unwrap(), head(5));
+        assert!(!rx.has_changed());
+        assert_eq!(publisher.subscribe().borrow(), head(5));
+
+        let waiter = std::thread::spawn(move || rx.changed());
+        publisher.publish(head(6));
+        assert_eq!(waiter.join().unwrap().unwrap(), head(6));
+    }
+
+    #[test]
+    fn test_dropping_publisher_closes_receivers() {
+        let publisher = HeadPublisher::new(head(1));
+        let mut rx = publisher.subscribe();
+        publisher.publish(head(2));
+        drop(publisher);
+        // The last head is still delivered, then the channel reports closed.
+        assert_eq!(rx.changed().unwrap(), head(2));
+        assert!(rx.changed().is_err());
+        assert!(rx.changed_timeout(Duration::from_secs(5)).is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! Signed uptime heartbeats and countersigned epoch uptime records.
+//!
+//! **Stability:** experimental; only compiled with the `experimental` feature.
+//!
+//! During an epoch each validator signs heartbeats `(node_id, epoch, slot_start..=slot_end)` with its node key. At epoch close the other validators countersign the heartbeats they observed. A heartbeat is accepted as evidence when its own signature verifies against the registered public key and it carries valid countersignatures from at least `HEARTBEAT_QUORUM_PCT` of the other active validators.
+//!
+//! `uptime_scores` turns accepted heartbeats into `uptime_score` values (covered slots / slots per epoch, scaled to `SCORE_SCALE`), and `NodeRegistry::apply_uptime_evidence` writes them for every active node, so uptime comes from verifiable evidence instead of an operator-set value. Nodes without accepted heartbeats get 0.
+
+use std::collections::{BTreeMap, BTreeSet};
+use secp256k1::SecretKey;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::node_registry::{NodeId, NodeRegistry, NodeStatus, SCORE_SCALE};
+use crate::error::{PlatariumError, Result};
+use crate::signature::{sign_message, verify_signature};
+
+/// Minimum share (percent) of the other active validators that must countersign a heartbeat.
+pub const HEARTBEAT_QUORUM_PCT: u64 = 67;
+
+/// Errors produced by heartbeat handling.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HeartbeatError {
+    #[error("Invalid slot range {0}..={1}")]
+    InvalidSlotRange(u64, u64),
+
+    #[error("Slots per epoch must be greater than 0")]
+    ZeroSlotsPerEpoch,
+}
+
+impl From<HeartbeatError> for PlatariumError {
+    fn from(err: HeartbeatError) -> Self {
+        PlatariumError::Validation(format!("Heartbeat: {}", err))
+    }
+}
+
+#[derive(Serialize)]
+struct HeartbeatPayload<'a> {
+    node_id: &'a str,
+    epoch: u64,
+    slot_start: u64,
+    slot_end: u64,
+}
+
+#[derive(Serialize)]
+struct CountersignPayload<'a> {
+    witness: &'a str,
+    heartbeat_signature: &'a str,
+}
+
+/// Heartbeat signed by the validator it reports on. Slots are epoch-relative and inclusive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    pub node_id: NodeId,
+    pub epoch: u64,
+    pub slot_start: u64,
+    pub slot_end: u64,
+    pub signature: String,
+}
+
+/// Another validator's attestation that it observed a heartbeat.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Countersignature {
+    pub witness: NodeId,
+    pub signature: String,
+}
+
+/// Heartbeat plus the countersignatures collected at epoch close.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CountersignedHeartbeat {
+    pub heartbeat: Heartbeat,
+    pub countersignatures: Vec<Countersignature>,
+}
+
+fn registered_key(registry: &NodeRegistry, node_id: &NodeId) -> Option<String> {
+    registry
+        .get(node_id)
+        .filter(|n| n.status == NodeStatus::Active)
+        .map(|n| n.public_key.strip_prefix("Px").unwrap_or(&n.public_key).to_string())
+}
+
+impl Heartbeat {
+    /// Creates and signs a heartbeat with the node key.
+    pub fn sign(
+        node_id: NodeId,
+        epoch: u64,
+        slot_start: u64,
+        slot_end: u64,
+        secret_key: &SecretKey,
+    ) -> Result<Self> {
+        if slot_end < slot_start {
+            return Err(HeartbeatError::InvalidSlotRange(slot_start, slot_end).into());
+        }
+        let payload = HeartbeatPayload {
+            node_id: &node_id,
+            epoch,
+            slot_start,
+            slot_end,
+        };
+        let signature = sign_message(secret_key, &payload)?.signature_compact;
+        Ok(Self {
+            node_id,
+            epoch,
+            slot_start,
+            slot_end,
+            signature,
+        })
+    }
+
+    /// True if the heartbeat signature verifies against the node's registered public key.
+    pub fn verify(&self, registry: &NodeRegistry) -> bool {
+        if self.slot_end < self.slot_start {
+            return false;
+        }
+        let Some(key) = registered_key(registry, &self.node_id) else {
+            return false;
+        };
+        let payload = HeartbeatPayload {
+            node_id: &self.node_id,
+            epoch: self.epoch,
+            slot_start: self.slot_start,
+            slot_end: self.slot_end,
+        };
+        matches!(verify_signature(&payload, &self.signature, &key), Ok(true))
+    }
+
+    /// Countersigns this heartbeat as `witness`.
+    pub fn countersign(&self, witness: NodeId, secret_key: &SecretKey) -> Result<Countersignature> {
+        let payload = CountersignPayload {
+            witness: &witness,
+            heartbeat_signature: &self.signature,
+        };
+        let signature = sign_message(secret_key, &payload)?.signature_compact;
+        Ok(Countersignature { witness, signature })
+    }
+}
+
+impl Countersignature {
+    /// True if signed by an active witness (other than the heartbeat's node) over `heartbeat`.
+    pub fn verify(&self, heartbeat: &Heartbeat, registry: &NodeRegistry) -> bool {
+        if self.witness == heartbeat.node_id {
+            return false;
+        }
+        let Some(key) = registered_key(registry, &self.witness) else {
+            return false;
+        };
+        let payload = CountersignPayload {
+            witness: &self.witness,
+            heartbeat_signature: &heartbeat.signature,
+        };
+        matches!(verify_signature(&payload, &self.signature, &key), Ok(true))
+    }
+}
+
+impl CountersignedHeartbeat {
+    /// True if the heartbeat is for `epoch`, its signature verifies, and distinct valid countersignatures reach the quorum of the other active validators.
+    pub fn is_accepted(&self, epoch: u64, registry: &NodeRegistry) -> bool {
+        if self.heartbeat.epoch != epoch || !self.heartbeat.verify(registry) {
+            return false;
+        }
+        let others = registry
+            .get_all()
+            .iter()
+            .filter(|n| n.status == NodeStatus::Active && n.node_id != self.heartbeat.node_id)
+            .count() as u64;
+        let witnesses: BTreeSet<&NodeId> = self
+            .countersignatures
+            .iter()
+            .filter(|c| c.verify(&self.heartbeat, registry))
+            .map(|c| &c.witness)
+            .collect();
+        witnesses.len() as u64 * 100 >= others * HEARTBEAT_QUORUM_PCT
+    }
+}
+
+/// Computes uptime scores for `epoch` from accepted heartbeats: number of distinct covered slots in `0..slots_per_epoch`, scaled to `SCORE_SCALE`. Only nodes with at least one accepted heartbeat appear.
+pub fn uptime_scores(
+    registry: &NodeRegistry,
+    epoch: u64,
+    slots_per_epoch: u64,
+    heartbeats: &[CountersignedHeartbeat],
+) -> Result<BTreeMap<NodeId, u64>> {
+    if slots_per_epoch == 0 {
+        return Err(HeartbeatError::ZeroSlotsPerEpoch.into());
+    }
+    let mut ranges: BTreeMap<NodeId, Vec<(u64, u64)>> = BTreeMap::new();
+    for hb in heartbeats.iter().filter(|h| h.is_accepted(epoch, registry)) {
+        let h = &hb.heartbeat;
+        if h.slot_start >= slots_per_epoch {
+            continue;
+        }
+        ranges
+            .entry(h.node_id.clone())
+            .or_default()
+            .push((h.slot_start, h.slot_end.min(slots_per_epoch - 1)));
+    }
+    let mut scores = BTreeMap::new();
+    for (node_id, mut rs) in ranges {
+        rs.sort();
+        let mut covered = 0u64;
+        let mut next_uncovered = 0u64;
+        for (start, end) in rs {
+            let start = start.max(next_uncovered);
+            if end >= start {
+                covered += end - start + 1;
+                next_uncovered = end + 1;
+            }
+        }
+        scores.insert(node_id, covered * SCORE_SCALE / slots_per_epoch);
+    }
+    Ok(scores)
+}
+
+impl NodeRegistry {
+    /// Sets `uptime_score` of every active node from the epoch's heartbeat evidence (0 when the node has no accepted heartbeat). Returns the scores applied.
+    pub fn apply_uptime_evidence(
+        &self,
+        epoch: u64,
+        slots_per_epoch: u64,
+        heartbeats: &[CountersignedHeartbeat],
+    ) -> Result<BTreeMap<NodeId, u64>> {
+        let evidence = uptime_scores(self, epoch, slots_per_epoch, heartbeats)?;
+        let mut applied = BTreeMap::new();
+        for node in self.get_all().into_iter().filter(|n| n.status == NodeStatus::Active) {
+            let score = evidence.get(&node.node_id).copied().unwrap_or(0);
+            self.set_uptime_score(&node.node_id, score)?;
+            applied.insert(node.node_id, score);
+        }
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::{PublicKey, Secp256k1};
+
+    fn setup(n: u8) -> (NodeRegistry, Vec<SecretKey>) {
+        let secp = Secp256k1::new();
+        let reg = NodeRegistry::new();
+        let mut keys = Vec::new();
+        for i in 0..n {
+            let sk = SecretKey::from_slice(&[i + 1; 32]).unwrap();
+            let pk = hex::encode(PublicKey::from_secret_key(&secp, &sk).serialize());
+            reg.register(format!("n{}", i), format!("Px{}", pk), 100, 10).unwrap();
+            keys.push(sk);
+        }
+        (reg, keys)
+    }
+
+    fn countersigned(hb: Heartbeat, keys: &[SecretKey], witnesses: &[usize]) -> CountersignedHeartbeat {
+        let countersignatures = witnesses
+            .iter()
+            .map(|&w| hb.countersign(format!("n{}", w), &keys[w]).unwrap())
+            .collect();
+        CountersignedHeartbeat { heartbeat: hb, countersignatures }
+    }
+
+    #[test]
+    fn test_quorum_and_signatures() {
+        let (reg, keys) = setup(4);
+        let hb = Heartbeat::sign("n0".into(), 1, 0, 9, &keys[0]).unwrap();
+        assert!(hb.verify(&reg));
+        // 2 of 3 others is below 67%.
+        assert!(!countersigned(hb.clone(), &keys, &[1, 2]).is_accepted(1, &reg));
+        assert!(countersigned(hb.clone(), &keys, &[1, 2, 3]).is_accepted(1, &reg));
+        assert!(!countersigned(hb.clone(), &keys, &[1, 2, 3]).is_accepted(2, &reg));
+        // Forged heartbeat signed by another key.
+        let forged = Heartbeat::sign("n0".into(), 1, 0, 9, &keys[1]).unwrap();
+        assert!(!countersigned(forged, &keys, &[1, 2, 3]).is_accepted(1, &reg));
+    }
+
+    #[test]
+    fn test_apply_uptime_evidence() {
+        let (reg, keys) = setup(4);
+        let hbs = vec![
+            countersigned(Heartbeat::sign("n0".into(), 1, 0, 49, &keys[0]).unwrap(), &keys, &[1, 2, 3]),
+            countersigned(Heartbeat::sign("n0".into(), 1, 25, 74, &keys[0]).unwrap(), &keys, &[1, 2, 3]),
+            countersigned(Heartbeat::sign("n1".into(), 1, 0, 999, &keys[1]).unwrap(), &keys, &[0, 2, 3]),
+        ];
+        let applied = reg.apply_uptime_evidence(1, 100, &hbs).unwrap();
+        assert_eq!(applied["n0"], 750_000);
+        assert_eq!(applied["n1"], SCORE_SCALE);
+        assert_eq!(applied["n2"], 0);
+        assert_eq!(reg.get(&"n0".to_string()).unwrap().uptime_score, 750_000);
+    }
+}
@@ -0,0 +1,443 @@
+//! Insurance fund compensating accounts damaged by confirmed invalid transactions.
+//!
+//! The fund is the state account `INSURANCE_FUND_ADDRESS`. It receives slashed stake when
+//! `ProtocolParams::slash_destination` is `InsuranceFund` and a share of collected fees via
+//! [`sweep_fee_share`]. When validators are slashed for `SlashingReason::InvalidTx`, the embedder
+//! records [`InvalidTxEvidence`] for the offending transaction with [`record_evidence`], which requires
+//! validator signatures over it. A victim (the account the transaction debited) can then file an
+//! [`InsuranceClaim`]; [`pay_claim`] pays it only with validator signatures over the claim and its amounts
+//! (same threshold as governance freeze orders) and after [`InsuranceFund::verify_claim`] has checked it
+//! against the evidence, the stored transaction and its receipt.
+//!
+//! The records ([`InsuranceFund`]) live in `State` (`State::insurance_fund`): they are part of snapshots, the
+//! state file and the state root, so every node agrees on which evidence exists and which claims were paid.
+//!
+//! # Determinism
+//! Verification and payout depend only on the claim, the recorded evidence, the transaction and
+//! receipt passed in, and state balances. Claim ids are SHA256 hashes; records are kept in ordered maps.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use crate::core::asset::Asset;
+use crate::core::events::Event;
+use crate::core::governance::{check_signed_approvals, Approval, GovernanceAction};
+use crate::core::node_registry::{NodeId, NodeRegistry};
+use crate::core::state::{Address, State, INSURANCE_FUND_ADDRESS, TREASURY_ADDRESS};
+use crate::core::transaction::{Transaction, TxKind};
+use crate::error::{PlatariumError, Result};
+use crate::storage::ReceiptRecord;
+
+/// Share of collected fees (percent) moved from the treasury to the fund by [`sweep_fee_share`].
+pub const INSURANCE_FEE_SHARE_PCT: u128 = 10;
+
+/// Errors produced while verifying or paying a claim.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InsuranceError {
+    #[error("No invalid-transaction evidence recorded for {0}")]
+    NoEvidence(String),
+
+    #[error("Evidence for {0} names no confirming validators")]
+    EmptyEvidence(String),
+
+    #[error("Stored {what} does not match the evidence for {tx_hash}")]
+    RecordMismatch { what: &'static str, tx_hash: String },
+
+    #[error("Claimant {claimant} was not debited by {tx_hash}")]
+    NotDamaged { claimant: Address, tx_hash: String },
+
+    #[error("Claim of {claimed} exceeds the damage of {max} ({unit})")]
+    ExceedsDamage { claimed: u128, max: u128, unit: &'static str },
+
+    #[error("Claim already paid: {0}")]
+    AlreadyPaid(String),
+
+    #[error("Insurance fund holds {available} {unit}, {required} required")]
+    InsufficientFund { required: u128, available: u128, unit: &'static str },
+
+    #[error("Paying the claim would overflow the claimant's {0} balance")]
+    BalanceOverflow(&'static str),
+}
+
+impl From<InsuranceError> for PlatariumError {
+    fn from(err: InsuranceError) -> Self {
+        PlatariumError::State(format!("Insurance: {}", err))
+    }
+}
+
+/// Record that validators confirmed an invalid transaction and were slashed for it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidTxEvidence {
+    pub tx_hash: String,
+    /// Height of the block that included the transaction.
+    pub block_height: u64,
+    /// Validators slashed with `SlashingReason::InvalidTx` for confirming it.
+    pub confirming_nodes: Vec<NodeId>,
+}
+
+impl InvalidTxEvidence {
+    /// Action validators sign to approve recording this evidence (confirming nodes sorted and deduplicated).
+    pub fn action(&self) -> GovernanceAction {
+        let mut confirming_nodes = self.confirming_nodes.clone();
+        confirming_nodes.sort();
+        confirming_nodes.dedup();
+        GovernanceAction::RecordInvalidTxEvidence {
+            tx_hash: self.tx_hash.clone(),
+            block_height: self.block_height,
+            confirming_nodes,
+        }
+    }
+}
+
+/// Compensation requested for one invalid transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InsuranceClaim {
+    pub claimant: Address,
+    pub tx_hash: String,
+    /// PLP to pay; at most the PLP amount the transaction moved out of the claimant's account.
+    pub amount: u128,
+    /// μPLP fee refund; at most the fee recorded in the receipt.
+    pub fee_refund_uplp: u128,
+}
+
+impl InsuranceClaim {
+    /// Deterministic claim id: SHA256 hex over claimant and transaction. One claim per victim and transaction.
+    pub fn id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(b"PlatariumInsuranceClaim:");
+        hasher.update(self.claimant.as_bytes());
+        hasher.update(b":");
+        hasher.update(self.tx_hash.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Action validators sign to approve paying this claim with these amounts.
+    pub fn action(&self) -> GovernanceAction {
+        GovernanceAction::PayInsuranceClaim {
+            claim_id: self.id(),
+            amount: self.amount,
+            fee_refund_uplp: self.fee_refund_uplp,
+        }
+    }
+}
+
+/// Recorded evidence and paid claims, held in `State` (see `State::insurance_fund`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InsuranceFund {
+    evidence: BTreeMap<String, InvalidTxEvidence>,
+    paid: BTreeMap<String, InsuranceClaim>,
+}
+
+impl InsuranceFund {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if no evidence was recorded and no claim paid.
+    pub fn is_empty(&self) -> bool {
+        self.evidence.is_empty() && self.paid.is_empty()
+    }
+
+    /// Records evidence for a transaction, merging confirming nodes if evidence already exists.
+    fn record(&mut self, evidence: InvalidTxEvidence) {
+        let entry = self
+            .evidence
+            .entry(evidence.tx_hash.clone())
+            .or_insert_with(|| InvalidTxEvidence {
+                confirming_nodes: Vec::new(),
+                ..evidence.clone()
+            });
+        entry.confirming_nodes.extend(evidence.confirming_nodes);
+        entry.confirming_nodes.sort();
+        entry.confirming_nodes.dedup();
+    }
+
+    pub fn evidence(&self, tx_hash: &str) -> Option<&InvalidTxEvidence> {
+        self.evidence.get(tx_hash)
+    }
+
+    /// Paid claim by id.
+    pub fn paid_claim(&self, claim_id: &str) -> Option<&InsuranceClaim> {
+        self.paid.get(claim_id)
+    }
+
+    /// Checks `claim` against the recorded evidence, the stored transaction and its receipt.
+    /// The claimant must be the account the transaction debited; amounts are capped by the PLP moved and the fee charged.
+    pub fn verify_claim(
+        &self,
+        claim: &InsuranceClaim,
+        tx: &Transaction,
+        receipt: &ReceiptRecord,
+    ) -> std::result::Result<(), InsuranceError> {
+        let evidence = self
+            .evidence
+            .get(&claim.tx_hash)
+            .ok_or_else(|| InsuranceError::NoEvidence(claim.tx_hash.clone()))?;
+        if evidence.confirming_nodes.is_empty() {
+            return Err(InsuranceError::EmptyEvidence(claim.tx_hash.clone()));
+        }
+        let mismatch = |what| InsuranceError::RecordMismatch {
+            what,
+            tx_hash: claim.tx_hash.clone(),
+        };
+        if tx.hash != claim.tx_hash {
+            return Err(mismatch("transaction"));
+        }
+        if receipt.tx_hash != claim.tx_hash || receipt.block_height != evidence.block_height {
+            return Err(mismatch("receipt"));
+        }
+        let debited = match &tx.kind {
+            TxKind::TransferFrom { owner, .. } => owner,
+            _ => &tx.from,
+        };
+        if *debited != claim.claimant {
+            return Err(InsuranceError::NotDamaged {
+                claimant: claim.claimant.clone(),
+                tx_hash: claim.tx_hash.clone(),
+            });
+        }
+        let max_plp = if tx.asset == Asset::PLP { tx.amount } else { 0 };
+        if claim.amount > max_plp {
+            return Err(InsuranceError::ExceedsDamage {
+                claimed: claim.amount,
+                max: max_plp,
+                unit: "PLP",
+            });
+        }
+        let fee_payer = receipt.fee_payer.as_ref().unwrap_or(&tx.from);
+        let fee_paid = if *fee_payer == claim.claimant {
+            receipt.fee_uplp as u128
+        } else {
+            0
+        };
+        if claim.fee_refund_uplp > fee_paid {
+            return Err(InsuranceError::ExceedsDamage {
+                claimed: claim.fee_refund_uplp,
+                max: fee_paid,
+                unit: "uPLP",
+            });
+        }
+        if self.paid.contains_key(&claim.id()) {
+            return Err(InsuranceError::AlreadyPaid(claim.id()));
+        }
+        Ok(())
+    }
+}
+
+/// Records `evidence` in the state's insurance fund after checking validator signatures over
+/// [`InvalidTxEvidence::action`]; confirming nodes are merged into evidence already recorded for the transaction.
+pub fn record_evidence(
+    state: &State,
+    registry: &NodeRegistry,
+    evidence: InvalidTxEvidence,
+    approvals: &[Approval],
+) -> Result<()> {
+    check_signed_approvals(registry, &evidence.action(), approvals)?;
+    state.update_insurance_fund(|fund| fund.record(evidence));
+    Ok(())
+}
+
+/// Pays a verified claim from the fund after checking validator signatures over [`InsuranceClaim::action`], and
+/// marks it paid in the state's insurance fund. Emits `InsurancePayout`; returns the claim id.
+pub fn pay_claim(
+    state: &State,
+    registry: &NodeRegistry,
+    claim: InsuranceClaim,
+    tx: &Transaction,
+    receipt: &ReceiptRecord,
+    approvals: &[Approval],
+) -> Result<String> {
+    check_signed_approvals(registry, &claim.action(), approvals)?;
+    state.insurance_fund().verify_claim(&claim, tx, receipt)?;
+
+    let fund = INSURANCE_FUND_ADDRESS.to_string();
+    let fund_plp = state.get_asset_balance(&fund, &Asset::PLP);
+    if fund_plp < claim.amount {
+        return Err(InsuranceError::InsufficientFund {
+            required: claim.amount,
+            available: fund_plp,
+            unit: "PLP",
+        }
+        .into());
+    }
+    let fund_uplp = state.get_uplp_balance(&fund);
+    if fund_uplp < claim.fee_refund_uplp {
+        return Err(InsuranceError::InsufficientFund {
+            required: claim.fee_refund_uplp,
+            available: fund_uplp,
+            unit: "uPLP",
+        }
+        .into());
+    }
+    let to = &claim.claimant;
+    let to_plp = state
+        .get_asset_balance(to, &Asset::PLP)
+        .checked_add(claim.amount)
+        .ok_or(InsuranceError::BalanceOverflow("PLP"))?;
+    let to_uplp = state
+        .get_uplp_balance(to)
+        .checked_add(claim.fee_refund_uplp)
+        .ok_or(InsuranceError::BalanceOverflow("uPLP"))?;
+
+    state.set_asset_balance(&fund, &Asset::PLP, fund_plp - claim.amount);
+    state.set_asset_balance(to, &Asset::PLP, to_plp);
+    state.set_uplp_balance(&fund, fund_uplp - claim.fee_refund_uplp);
+    state.set_uplp_balance(to, to_uplp);
+
+    let id = claim.id();
+    state.emit(Event::InsurancePayout {
+        claim_id: id.clone(),
+        claimant: claim.claimant.clone(),
+        tx_hash: claim.tx_hash.clone(),
+        amount: claim.amount,
+        fee_refund_uplp: claim.fee_refund_uplp,
+    });
+    state.update_insurance_fund(|f| f.paid.insert(id.clone(), claim));
+    Ok(id)
+}
+
+/// Moves `INSURANCE_FEE_SHARE_PCT` of `collected_fees_uplp` from the treasury to the fund (capped by the treasury balance). Returns the amount moved.
+pub fn sweep_fee_share(state: &State, collected_fees_uplp: u128) -> u128 {
+    let treasury = TREASURY_ADDRESS.to_string();
+    let fund = INSURANCE_FUND_ADDRESS.to_string();
+    let treasury_bal = state.get_uplp_balance(&treasury);
+    let share = (collected_fees_uplp.saturating_mul(INSURANCE_FEE_SHARE_PCT) / 100).min(treasury_bal);
+    if share > 0 {
+        state.set_uplp_balance(&treasury, treasury_bal - share);
+        let fund_bal = state.get_uplp_balance(&fund);
+        state.set_uplp_balance(&fund, fund_bal + share);
+    }
+    share
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::SecretKey;
+    use std::collections::HashSet;
+
+    /// Validator `n0` with a real node key, a PLP transfer from "victim", its receipt, and a state with the
+    /// transaction's evidence recorded.
+    fn setup() -> (State, NodeRegistry, SecretKey, Transaction, ReceiptRecord) {
+        let key = crate::testing::DeterministicRng::new(3997).secret_key();
+        let registry = NodeRegistry::new();
+        let pubkey = hex::encode(key.public_key(secp256k1::SECP256K1).serialize());
+        registry.register("n0".into(), pubkey, 100, 10).unwrap();
+        let state = State::new();
+        let tx = Transaction::new(
+            "victim".into(),
+            "thief".into(),
+            Asset::PLP,
+            50,
+            2,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap();
+        let receipt = ReceiptRecord {
+            tx_hash: tx.hash.clone(),
+            status: "ok".into(),
+            fee_uplp: 2,
+            block_height: 7,
+            rebate_uplp: 0,
+            events: Vec::new(),
+            fee_payer: None,
+            index: None,
+            error_code: None,
+            balance_deltas: Vec::new(),
+        };
+        let evidence = InvalidTxEvidence {
+            tx_hash: tx.hash.clone(),
+            block_height: 7,
+            confirming_nodes: vec!["n0".into()],
+        };
+        let approval = Approval::sign("n0".into(), &evidence.action(), &key).unwrap();
+        record_evidence(&state, &registry, evidence, &[approval]).unwrap();
+        (state, registry, key, tx, receipt)
+    }
+
+    #[test]
+    fn test_verified_claim_is_paid_once() {
+        let (state, registry, key, tx, receipt) = setup();
+        let fund_addr = INSURANCE_FUND_ADDRESS.to_string();
+        state.set_asset_balance(&fund_addr, &Asset::PLP, 100);
+        state.set_uplp_balance(&TREASURY_ADDRESS.to_string(), 100);
+        assert_eq!(sweep_fee_share(&state, 40), 4);
+
+        let claim = InsuranceClaim {
+            claimant: "victim".into(),
+            tx_hash: tx.hash.clone(),
+            amount: 50,
+            fee_refund_uplp: 2,
+        };
+        // Approvals are bound to the claim's amounts.
+        let smaller = InsuranceClaim { amount: 1, ..claim.clone() };
+        let approvals = vec![Approval::sign("n0".into(), &smaller.action(), &key).unwrap()];
+        assert!(pay_claim(&state, &registry, claim.clone(), &tx, &receipt, &approvals).is_err());
+
+        let approvals = vec![Approval::sign("n0".into(), &claim.action(), &key).unwrap()];
+        let before = state.current_root();
+        let id = pay_claim(&state, &registry, claim.clone(), &tx, &receipt, &approvals).unwrap();
+        assert_eq!(state.get_asset_balance(&"victim".to_string(), &Asset::PLP), 50);
+        assert_eq!(state.get_uplp_balance(&"victim".to_string()), 2);
+        assert_eq!(state.get_uplp_balance(&fund_addr), 2);
+        assert_eq!(state.insurance_fund().paid_claim(&id), Some(&claim));
+        assert!(matches!(state.take_events().last(), Some(Event::InsurancePayout { amount: 50, .. })));
+        assert!(pay_claim(&state, &registry, claim, &tx, &receipt, &approvals).is_err());
+        assert_ne!(state.current_root(), before);
+    }
+
+    #[test]
+    fn test_records_follow_snapshots_and_need_signatures() {
+        let (state, registry, key, tx, _) = setup();
+        let snapshot = state.create_snapshot();
+        let evidence = InvalidTxEvidence {
+            tx_hash: "other".into(),
+            block_height: 8,
+            confirming_nodes: vec!["n0".into()],
+        };
+        let forged = Approval { approver: "n0".into(), signature: "00".repeat(64) };
+        assert!(record_evidence(&state, &registry, evidence.clone(), &[forged]).is_err());
+        let approval = Approval::sign("n0".into(), &evidence.action(), &key).unwrap();
+        record_evidence(&state, &registry, evidence, &[approval]).unwrap();
+        assert!(state.insurance_fund().evidence("other").is_some());
+
+        state.restore(&snapshot);
+        assert!(state.insurance_fund().evidence("other").is_none());
+        assert!(state.insurance_fund().evidence(&tx.hash).is_some());
+    }
+
+    #[test]
+    fn test_claim_verification_rejects_mismatches() {
+        let (state, _, _, tx, receipt) = setup();
+        let fund = state.insurance_fund();
+        let claim = |claimant: &str, amount| InsuranceClaim {
+            claimant: claimant.into(),
+            tx_hash: tx.hash.clone(),
+            amount,
+            fee_refund_uplp: 0,
+        };
+        assert!(fund.verify_claim(&claim("victim", 50), &tx, &receipt).is_ok());
+        assert!(matches!(
+            fund.verify_claim(&claim("thief", 50), &tx, &receipt),
+            Err(InsuranceError::NotDamaged { .. })
+        ));
+        assert!(matches!(
+            fund.verify_claim(&claim("victim", 51), &tx, &receipt),
+            Err(InsuranceError::ExceedsDamage { .. })
+        ));
+        let wrong_height = ReceiptRecord { block_height: 8, ..receipt.clone() };
+        assert!(matches!(
+            fund.verify_claim(&claim("victim", 1), &tx, &wrong_height),
+            Err(InsuranceError::RecordMismatch { what: "receipt", .. })
+        ));
+        assert!(matches!(
+            InsuranceFund::new().verify_claim(&claim("victim", 1), &tx, &receipt),
+            Err(InsuranceError::NoEvidence(_))
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+//! Runtime invariant checks with a configurable failure policy.
+//!
+//! Internal consistency checks (snapshot/restore identity, simulation isolation) go through
+//! [`InvariantMonitor::check`] instead of `assert!`. What a violation does depends on the policy:
+//!
+//! - [`InvariantPolicy::Panic`]: panic with the invariant name, like the former asserts. Default in debug builds.
+//! - [`InvariantPolicy::LogAndCount`]: count it and report it to the violation hook; the node keeps running. Default
+//!   in release builds.
+//! - [`InvariantPolicy::HaltBlockProduction`]: count, report, and latch a halt flag that stops this node from
+//!   proposing blocks (`block_proposal_status` reports `should_propose: false`) until an operator clears it.
+//!   Transaction admission and queries keep working.
+//!
+//! The library does not print. The embedder decides where violations go with
+//! [`InvariantMonitor::set_violation_hook`] (the CLI's `serve` writes them to stderr). The process-wide monitor is
+//! [`invariant_monitor`]; counters are exposed by the `invariant_status` RPC method.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use serde::Serialize;
+
+/// Snapshot read twice under the same locks differs.
+pub const SNAPSHOT_STABLE: &str = "snapshot_stable";
+/// Snapshot contents differ from the state it was taken from.
+pub const SNAPSHOT_MATCHES_STATE: &str = "snapshot_matches_state";
+/// Taking a snapshot changed the state.
+pub const SNAPSHOT_SIDE_EFFECT_FREE: &str = "snapshot_side_effect_free";
+/// A snapshot's shared maps are no longer referenced.
+pub const SNAPSHOT_LIVE: &str = "snapshot_live";
+/// State after `restore` differs from the snapshot.
+pub const RESTORE_IDENTITY: &str = "restore_identity";
+/// Simulation modified the input snapshot.
+pub const SIMULATION_ISOLATED: &str = "simulation_isolated";
+
+/// What to do when an invariant does not hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvariantPolicy {
+    Panic,
+    LogAndCount,
+    HaltBlockProduction,
+}
+
+impl InvariantPolicy {
+    /// `Panic` in debug builds, `LogAndCount` in release builds.
+    pub fn default_for_build() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Panic
+        } else {
+            Self::LogAndCount
+        }
+    }
+
+    /// Parses `panic`, `log` or `halt` (the `--invariant-policy` values).
+    pub fn parse(name: &str) -> std::result::Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "panic" => Ok(Self::Panic),
+            "log" => Ok(Self::LogAndCount),
+            "halt" => Ok(Self::HaltBlockProduction),
+            other => Err(format!("unknown invariant policy '{}' (expected panic, log or halt)", other)),
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Panic => 0,
+            Self::LogAndCount => 1,
+            Self::HaltBlockProduction => 2,
+        }
+    }
+
+    fn from_u8(v: u8) -> Self {
+        match v {
+            0 => Self::Panic,
+            1 => Self::LogAndCount,
+            _ => Self::HaltBlockProduction,
+        }
+    }
+}
+
+/// Counters reported by [`InvariantMonitor::status`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InvariantStatus {
+    pub policy: InvariantPolicy,
+    pub total_violations: u64,
+    /// Violations per invariant name.
+    pub violations: BTreeMap<String, u64>,
+    pub block_production_halted: bool,
+    /// `name: detail` of the most recent violation.
+    pub last_violation: Option<String>,
+}
+
+/// A violation recorded under `LogAndCount` or `HaltBlockProduction`, as passed to the violation hook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InvariantViolation {
+    pub name: &'static str,
+    pub detail: String,
+    /// True when this violation latched the block production halt (the first one after a resume).
+    pub halted_block_production: bool,
+}
+
+type ViolationHook = Box<dyn Fn(&InvariantViolation) + Send + Sync>;
+
+/// Checks invariants and applies the configured [`InvariantPolicy`] on violation.
+pub struct InvariantMonitor {
+    policy: AtomicU8,
+    total: AtomicU64,
+    halted: AtomicBool,
+    by_name: Mutex<BTreeMap<&'static str, u64>>,
+    last: Mutex<Option<String>>,
+    hook: Mutex<Option<ViolationHook>>,
+}
+
+impl std::fmt::Debug for InvariantMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InvariantMonitor").field("status", &self.status()).finish_non_exhaustive()
+    }
+}
+
+impl InvariantMonitor {
+    pub fn new(policy: InvariantPolicy) -> Self {
+        Self {
+            policy: AtomicU8::new(policy.to_u8()),
+            total: AtomicU64::new(0),
+            halted: AtomicBool::new(false),
+            by_name: Mutex::new(BTreeMap::new()),
+            last: Mutex::new(None),
+            hook: Mutex::new(None),
+        }
+    }
+
+    pub fn policy(&self) -> InvariantPolicy {
+        InvariantPolicy::from_u8(self.policy.load(Ordering::SeqCst))
+    }
+
+    pub fn set_policy(&self, policy: InvariantPolicy) {
+        self.policy.store(policy.to_u8(), Ordering::SeqCst);
+    }
+
+    /// Calls `hook` with every violation recorded from now on, replacing any previous hook. Under `Panic` the
+    /// violation is the panic message instead.
+    pub fn set_violation_hook(&self, hook: impl Fn(&InvariantViolation) + Send + Sync + 'static) {
+        *self.hook.lock().unwrap() = Some(Box::new(hook));
+    }
+
+    /// Returns `holds`; when it is false, records a violation of `name` and applies the policy.
+    /// `detail` is only evaluated on violation.
+    pub fn check(&self, holds: bool, name: &'static str, detail: impl FnOnce() -> String) -> bool {
+        if !holds {
+            self.violation(name, detail());
+        }
+        holds
+    }
+
+    /// Records a violation of `name`, applies the policy and passes it to the violation hook, if any.
+    pub fn violation(&self, name: &'static str, detail: String) {
+        let policy = self.policy();
+        if policy == InvariantPolicy::Panic {
+            panic!("INVARIANT {}: {}", name, detail);
+        }
+        self.total.fetch_add(1, Ordering::SeqCst);
+        *self.by_name.lock().unwrap().entry(name).or_insert(0) += 1;
+        *self.last.lock().unwrap() = Some(format!("{}: {}", name, detail));
+        let halted_block_production =
+            policy == InvariantPolicy::HaltBlockProduction && !self.halted.swap(true, Ordering::SeqCst);
+        if let Some(hook) = self.hook.lock().unwrap().as_ref() {
+            hook(&InvariantViolation { name, detail, halted_block_production });
+        }
+    }
+
+    /// True after a violation under `HaltBlockProduction`, until [`InvariantMonitor::resume_block_production`].
+    pub fn block_production_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
+    /// Clears the halt flag (operator action after investigating). Counters are kept.
+    pub fn resume_block_production(&self) {
+        self.halted.store(false, Ordering::SeqCst);
+    }
+
+    pub fn total_violations(&self) -> u64 {
+        self.total.load(Ordering::SeqCst)
+    }
+
+    pub fn status(&self) -> InvariantStatus {
+        InvariantStatus {
+            policy: self.policy(),
+            total_violations: self.total_violations(),
+            violations: self
+                .by_name
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, count)| (name.to_string(), *count))
+                .collect(),
+            block_production_halted: self.block_production_halted(),
+            last_violation: self.last.lock().unwrap().clone(),
+        }
+    }
+}
+
+impl Default for InvariantMonitor {
+    fn default() -> Self {
+        Self::new(InvariantPolicy::default_for_build())
+    }
+}
+
+/// Process-wide monitor used by state, execution and block production.
+pub fn invariant_monitor() -> &'static InvariantMonitor {
+    static MONITOR: OnceLock<InvariantMonitor> = OnceLock::new();
+    MONITOR.get_or_init(InvariantMonitor::default)
+}
+
+/// Checks `holds` against the process-wide monitor; see [`InvariantMonitor::check`].
+pub fn check_invariant(holds: bool, name: &'static str, detail: impl FnOnce() -> String) -> bool {
+    invariant_monitor().check(holds, name, detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "INVARIANT restore_identity")]
+    fn test_panic_policy() {
+        InvariantMonitor::new(InvariantPolicy::Panic).check(false, RESTORE_IDENTITY, || "balances differ".into());
+    }
+
+    #[test]
+    fn test_log_and_halt_policies() {
+        let monitor = InvariantMonitor::new(InvariantPolicy::LogAndCount);
+        assert!(monitor.check(true, SNAPSHOT_STABLE, || unreachable!()));
+        assert!(!monitor.check(false, SNAPSHOT_STABLE, || "nonces".into()));
+        assert_eq!(monitor.total_violations(), 1);
+        assert!(!monitor.block_production_halted());
+
+        let seen = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        monitor.set_violation_hook(move |v| sink.lock().unwrap().push(v.clone()));
+        monitor.set_policy(InvariantPolicy::HaltBlockProduction);
+        monitor.check(false, RESTORE_IDENTITY, || "balances".into());
+        monitor.check(false, RESTORE_IDENTITY, || "nonces".into());
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!((seen[0].name, seen[0].detail.as_str()), (RESTORE_IDENTITY, "balances"));
+        assert!(seen[0].halted_block_production && !seen[1].halted_block_production);
+        let status = monitor.status();
+        assert!(status.block_production_halted);
+        assert_eq!(status.total_violations, 3);
+        assert_eq!(status.violations[SNAPSHOT_STABLE], 1);
+        assert_eq!(status.last_violation.as_deref(), Some("restore_identity: nonces"));
+
+        monitor.resume_block_production();
+        assert!(!monitor.block_production_halted());
+        assert_eq!(monitor.total_violations(), 3);
+    }
+}
@@ -0,0 +1,206 @@
+//! Conversion of legacy `sign-message` payloads into canonical transactions.
+//!
+//! Before the Core execution path existed, wallets signed transfers as free-form JSON with the
+//! `sign-message` command. The supported legacy schema is:
+//!
+//! ```json
+//! {
+//!   "message": {
+//!     "from": "Px…", "to": "Px…",
+//!     "amount": 1000,            // integer or integer string, minimal units
+//!     "asset": "PLP",            // optional, default PLP; "Token:X" or "X" for tokens
+//!     "fee_uplp": 1,             // optional (alias "fee"), default MIN_FEE_UPLP
+//!     "nonce": 0,
+//!     "reads": [], "writes": []  // optional
+//!   },
+//!   "sig_main": "…", "sig_derived": "…",
+//!   "pub_main": "…", "pub_derived": "…"
+//! }
+//! ```
+//!
+//! Legacy signatures cover `hash_message(message)`, i.e. the message as written (object keys sorted),
+//! not the canonical transaction hash data, so they can only be carried over when both hashes happen to
+//! agree. Otherwise the converted transaction is returned unsigned and flagged for re-signing. Conversion
+//! is a pure function of the payload.
+
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::core::asset::Asset;
+use crate::core::transaction::{parse_u128_json, Transaction, MIN_FEE_UPLP};
+use crate::error::{PlatariumError, Result};
+use crate::signature::{hash_message, verify_signature_hash};
+
+/// A signed legacy payload as emitted by `sign-message`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegacySignedMessage {
+    pub message: Value,
+    #[serde(default)]
+    pub sig_main: String,
+    #[serde(default)]
+    pub sig_derived: String,
+    #[serde(default)]
+    pub pub_main: Option<String>,
+    #[serde(default)]
+    pub pub_derived: Option<String>,
+}
+
+/// Whether the legacy signatures could be reused on the canonical transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LegacySignatureStatus {
+    /// The legacy signatures are valid for the canonical transaction and were copied over.
+    Rebound,
+    /// The transaction must be signed again with the account keys.
+    ResignRequired { reason: String },
+}
+
+/// Result of converting a legacy payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LegacyConversion {
+    pub tx: Transaction,
+    /// Hash the legacy signatures were made over.
+    pub legacy_hash: String,
+    /// Whether both legacy signatures verify against `legacy_hash`.
+    pub legacy_signatures_valid: bool,
+    pub signatures: LegacySignatureStatus,
+}
+
+fn field_str(message: &Value, key: &str) -> Result<String> {
+    message
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| PlatariumError::Validation(format!("legacy message: missing `{}`", key)))
+}
+
+fn field_set(message: &Value, key: &str) -> HashSet<String> {
+    message
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|a| a.iter().filter_map(|x| x.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn verifies(hash: &[u8], sig: &str, pub_key: Option<&str>) -> bool {
+    match pub_key {
+        Some(key) if !sig.is_empty() => {
+            verify_signature_hash(hash, sig, key.strip_prefix("Px").unwrap_or(key)).unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// Converts a legacy payload into a canonical transfer, rebinding signatures where possible.
+pub fn convert_legacy_message(legacy: &LegacySignedMessage) -> Result<LegacyConversion> {
+    let m = &legacy.message;
+    let asset = match m.get("asset").and_then(Value::as_str).unwrap_or("PLP") {
+        "PLP" | "" => Asset::PLP,
+        other => Asset::Token(other.strip_prefix("Token:").unwrap_or(other).to_string()),
+    };
+    let amount = parse_u128_json(&m["amount"])
+        .map_err(|e| PlatariumError::Validation(format!("legacy message: invalid amount: {}", e)))?;
+    let fee_value = m.get("fee_uplp").or_else(|| m.get("fee"));
+    let fee_uplp = match fee_value {
+        Some(v) => parse_u128_json(v)
+            .map_err(|e| PlatariumError::Validation(format!("legacy message: invalid fee: {}", e)))?,
+        None => MIN_FEE_UPLP,
+    };
+    let nonce = m
+        .get("nonce")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| PlatariumError::Validation("legacy message: missing `nonce`".to_string()))?;
+
+    let mut tx = Transaction::new(
+        field_str(m, "from")?,
+        field_str(m, "to")?,
+        asset,
+        amount,
+        fee_uplp,
+        nonce,
+        field_set(m, "reads"),
+        field_set(m, "writes"),
+        String::new(),
+        String::new(),
+    )?;
+
+    let legacy_hash = hash_message(m)?;
+    let pub_main = legacy.pub_main.as_deref();
+    let pub_derived = legacy.pub_derived.as_deref();
+    let legacy_signatures_valid = verifies(&legacy_hash, &legacy.sig_main, pub_main)
+        && verifies(&legacy_hash, &legacy.sig_derived, pub_derived);
+    let legacy_hash = hex::encode(legacy_hash);
+
+    let signatures = if !legacy_signatures_valid {
+        LegacySignatureStatus::ResignRequired {
+            reason: "legacy signatures are missing or do not verify".to_string(),
+        }
+    } else if legacy_hash != tx.hash {
+        LegacySignatureStatus::ResignRequired {
+            reason: "legacy message hash differs from the canonical transaction hash".to_string(),
+        }
+    } else {
+        tx.sig_main = legacy.sig_main.clone();
+        tx.sig_derived = legacy.sig_derived.clone();
+        tx.pub_main = legacy.pub_main.clone();
+        tx.pub_derived = legacy.pub_derived.clone();
+        LegacySignatureStatus::Rebound
+    };
+
+    Ok(LegacyConversion {
+        tx,
+        legacy_hash,
+        legacy_signatures_valid,
+        signatures,
+    })
+}
+
+/// Parses a legacy payload from JSON and converts it.
+pub fn convert_legacy_json(json: &str) -> Result<LegacyConversion> {
+    let legacy: LegacySignedMessage = serde_json::from_str(json)
+        .map_err(|e| PlatariumError::Validation(format!("invalid legacy payload: {}", e)))?;
+    convert_legacy_message(&legacy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signer::sign_with_both_keys;
+    use crate::key_generator::KeyGenerator;
+    use serde_json::json;
+
+    fn signed(message: Value) -> LegacySignedMessage {
+        let keys = KeyGenerator::default().generate_keys().unwrap();
+        let sig = sign_with_both_keys(&message, &keys.mnemonic, &keys.alphanumeric_part).unwrap();
+        LegacySignedMessage {
+            message,
+            sig_main: sig.signatures[0].signature_compact.clone(),
+            sig_derived: sig.signatures[1].signature_compact.clone(),
+            pub_main: Some(sig.signatures[0].pub_key.clone()),
+            pub_derived: Some(sig.signatures[1].pub_key.clone()),
+        }
+    }
+
+    #[test]
+    fn test_sorted_legacy_message_requires_resign() {
+        let legacy = signed(json!({"from": "alice", "to": "bob", "amount": "25", "fee": 2, "nonce": 4}));
+        let out = convert_legacy_message(&legacy).unwrap();
+        assert!(out.legacy_signatures_valid);
+        assert!(matches!(out.signatures, LegacySignatureStatus::ResignRequired { .. }));
+        assert_eq!((out.tx.amount, out.tx.fee_uplp, out.tx.nonce), (25, 2, 4));
+        assert!(out.tx.sig_main.is_empty());
+        // Deterministic: same payload, same result.
+        assert_eq!(convert_legacy_message(&legacy).unwrap(), out);
+    }
+
+    #[test]
+    fn test_tampered_or_incomplete_payloads() {
+        let mut legacy = signed(json!({"from": "a", "to": "b", "amount": 1, "nonce": 0}));
+        legacy.message["amount"] = json!(2);
+        let out = convert_legacy_json(&serde_json::to_string(&legacy).unwrap()).unwrap();
+        assert!(!out.legacy_signatures_valid);
+        assert_eq!(out.tx.amount, 2);
+        assert!(convert_legacy_json(r#"{"message": {"from": "a"}}"#).is_err());
+        assert!(convert_legacy_json(r#"{"message": {"from": "a", "to": "b", "amount": 1.5, "nonce": 0}}"#).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! Network load estimate shared by fees, block limits and validator selection.
+//!
+//! `pending_tx_count`, `avg_tps` and `network_load_pct` used to be passed in by each caller. [`NetworkLoad`]
+//! derives all three from the mempool size and the most recent block headers, and exposes the fee,
+//! block-limit and committee-size decisions that depend on them, so every module sees the same load.
+//!
+//! # Definitions (integer arithmetic only)
+//! - `avg_tps`: transactions in the last [`LOAD_WINDOW_BLOCKS`] blocks, excluding the oldest one (it opens the
+//!   interval), divided by the seconds between the oldest and newest timestamps. 0 with fewer than two blocks
+//!   or a non-increasing time span.
+//! - mempool load: `pending_tx_count × 100 / MAX_BATCH_SIZE` (the fee module's definition).
+//! - throughput load: `avg_tps × 100 / NETWORK_CAPACITY_TPS`.
+//! - `network_load_pct`: the larger of the two, capped at 100.
+//!
+//! Without block history the estimate reduces to the mempool load, which is what fees were based on before.
+//! Inputs come from the mempool and committed headers only; no system time is read.
+
+use serde::{Deserialize, Serialize};
+use crate::core::block_assembly::{
+    max_block_size_bytes, max_block_time_sec, max_transactions_per_block, Block, BLOCK_TIME_MAX_SEC,
+    DEFAULT_MAX_TXS_PER_BLOCK,
+};
+use crate::core::fee::{calculate_fee, load_multiplier_for_pct, BASE_TX_FEE_MICRO_PLP, MAX_BATCH_SIZE};
+use crate::core::mempool::Mempool;
+use crate::core::node_registry::{NodeId, NodeRegistry};
+use crate::core::validator_selection::{
+    committee_count, select_l1_l2_validators, selection_percent_from_load_l2, selection_percent_from_load_pct,
+};
+use crate::error::Result;
+
+/// Number of recent blocks averaged for `avg_tps`.
+pub const LOAD_WINDOW_BLOCKS: usize = 16;
+
+/// Sustained throughput treated as 100% load: a full block every `BLOCK_TIME_MAX_SEC` seconds.
+pub const NETWORK_CAPACITY_TPS: u64 = DEFAULT_MAX_TXS_PER_BLOCK as u64 / BLOCK_TIME_MAX_SEC;
+
+/// The parts of a block header the estimate reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockLoadSample {
+    pub block_number: u64,
+    pub timestamp: i64,
+    pub tx_count: u64,
+}
+
+impl From<&Block> for BlockLoadSample {
+    fn from(block: &Block) -> Self {
+        Self {
+            block_number: block.block_number,
+            timestamp: block.timestamp,
+            tx_count: block.transaction_hashes.len() as u64,
+        }
+    }
+}
+
+/// Fee bucket (1x/2x/3x/5x multiplier) in force when a block was assembled, and the load behind it. Committed
+/// alongside the block so bucket thresholds can be evaluated against real history (`Core::fee_bucket_history`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeBucketRecord {
+    pub block_number: u64,
+    pub multiplier: u64,
+    pub network_load_pct: u64,
+    pub avg_tps: u64,
+    pub pending_tx_count: usize,
+    pub mempool_bytes: usize,
+    pub mempool_max_bytes: usize,
+}
+
+/// Load figures for one point in the chain; see the module docs for how each is derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkLoad {
+    pub pending_tx_count: usize,
+    pub avg_tps: u64,
+    pub network_load_pct: u64,
+}
+
+impl NetworkLoad {
+    /// Estimate from a pending count and recent headers (any order; only the newest `LOAD_WINDOW_BLOCKS` by number are used).
+    pub fn estimate(pending_tx_count: usize, recent_blocks: &[BlockLoadSample]) -> Self {
+        let avg_tps = average_tps(recent_blocks);
+        let mempool_pct = (pending_tx_count.min(MAX_BATCH_SIZE) * 100 / MAX_BATCH_SIZE) as u64;
+        let throughput_pct = avg_tps.saturating_mul(100) / NETWORK_CAPACITY_TPS;
+        Self {
+            pending_tx_count,
+            avg_tps,
+            network_load_pct: mempool_pct.max(throughput_pct).min(100),
+        }
+    }
+
+    /// Estimate from the mempool alone (no block history): mempool load only.
+    pub fn from_pending(pending_tx_count: usize) -> Self {
+        Self::estimate(pending_tx_count, &[])
+    }
+
+    /// Estimate from a node's mempool and its most recent blocks.
+    pub fn from_mempool(mempool: &Mempool, recent_blocks: &[Block]) -> Self {
+        let samples: Vec<BlockLoadSample> = recent_blocks.iter().map(BlockLoadSample::from).collect();
+        Self::estimate(mempool.len(), &samples)
+    }
+
+    /// Fee multiplier bucket for this load.
+    pub fn fee_multiplier(&self) -> u64 {
+        load_multiplier_for_pct(self.network_load_pct)
+    }
+
+    /// Minimum fee in μPLP at this load.
+    pub fn min_fee_uplp(&self) -> u64 {
+        calculate_fee(BASE_TX_FEE_MICRO_PLP, self.fee_multiplier())
+    }
+
+    /// Transaction cap for the next block.
+    pub fn max_transactions_per_block(&self) -> usize {
+        max_transactions_per_block(self.pending_tx_count, self.avg_tps, self.network_load_pct)
+    }
+
+    /// Size cap for the next block, in bytes.
+    pub fn max_block_size_bytes(&self) -> u64 {
+        max_block_size_bytes(self.pending_tx_count, self.avg_tps, self.network_load_pct)
+    }
+
+    /// Block time window for the next block, in seconds.
+    pub fn max_block_time_sec(&self) -> u64 {
+        max_block_time_sec(self.network_load_pct)
+    }
+
+    /// Fee bucket record for block `block_number` assembled at this load, with the mempool's byte occupancy.
+    pub fn fee_bucket_record(&self, block_number: u64, mempool_bytes: usize, mempool_max_bytes: usize) -> FeeBucketRecord {
+        FeeBucketRecord {
+            block_number,
+            multiplier: self.fee_multiplier(),
+            network_load_pct: self.network_load_pct,
+            avg_tps: self.avg_tps,
+            pending_tx_count: self.pending_tx_count,
+            mempool_bytes,
+            mempool_max_bytes,
+        }
+    }
+
+    /// Percentage of eligible nodes selected as L1 validators.
+    pub fn l1_selection_percent(&self) -> Result<u64> {
+        selection_percent_from_load_pct(self.network_load_pct)
+    }
+
+    /// Percentage of eligible nodes selected as L2 validators.
+    pub fn l2_selection_percent(&self) -> Result<u64> {
+        selection_percent_from_load_l2(self.network_load_pct, 100)
+    }
+
+    /// Committee size for `candidate_count` candidates.
+    pub fn committee_count(&self, candidate_count: usize) -> usize {
+        committee_count(candidate_count, self.network_load_pct)
+    }
+
+    /// L1 and L2 validator sets for `block_number`, sized by this load.
+    pub fn select_validators(
+        &self,
+        registry: &NodeRegistry,
+        block_number: u64,
+        prev_finalized_hash: &[u8],
+    ) -> Result<(Vec<NodeId>, Vec<NodeId>)> {
+        select_l1_l2_validators(registry, block_number, prev_finalized_hash, self.network_load_pct, 100)
+    }
+}
+
+fn average_tps(recent_blocks: &[BlockLoadSample]) -> u64 {
+    let mut window: Vec<&BlockLoadSample> = recent_blocks.iter().collect();
+    window.sort_by_key(|b| b.block_number);
+    let window = &window[window.len().saturating_sub(LOAD_WINDOW_BLOCKS)..];
+    let (Some(first), Some(last)) = (window.first(), window.last()) else {
+        return 0;
+    };
+    let span = last.timestamp.saturating_sub(first.timestamp);
+    if window.len() < 2 || span <= 0 {
+        return 0;
+    }
+    let txs: u64 = window[1..].iter().map(|b| b.tx_count).sum();
+    txs / span as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks(tx_counts: &[u64], secs_apart: i64) -> Vec<BlockLoadSample> {
+        tx_counts
+            .iter()
+            .enumerate()
+            .map(|(i, &tx_count)| BlockLoadSample {
+                block_number: i as u64 + 1,
+                timestamp: 1_700_000_000 + i as i64 * secs_apart,
+                tx_count,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_without_history_matches_fee_module() {
+        for pending in [0, 300, 310, 650, 900, 5_000] {
+            let load = NetworkLoad::from_pending(pending);
+            assert_eq!(load.avg_tps, 0);
+            assert_eq!(load.min_fee_uplp(), crate::core::fee::calculate_fee_from_load(pending));
+        }
+    }
+
+    #[test]
+    fn test_throughput_raises_load() {
+        // 4 blocks after the first, 400 txs each, 5 s apart: 1600 txs / 20 s = 80 TPS = 80% of capacity.
+        let recent = blocks(&[0, 400, 400, 400, 400], 5);
+        let load = NetworkLoad::estimate(10, &recent);
+        assert_eq!(load.avg_tps, 80);
+        assert_eq!(load.network_load_pct, 80);
+        assert_eq!(load.fee_multiplier(), crate::core::fee::MULTIPLIER_3X);
+        assert_eq!(load.max_block_size_bytes(), max_block_size_bytes(0, 0, 80));
+        assert_eq!(load.max_block_time_sec(), max_block_time_sec(80));
+        assert_eq!(load.committee_count(100), committee_count(100, 80));
+
+        let mut reversed = recent.clone();
+        reversed.reverse();
+        assert_eq!(NetworkLoad::estimate(10, &reversed), load);
+        assert_eq!(NetworkLoad::estimate(10, &recent[..1]).avg_tps, 0);
+    }
+}
@@ -5,6 +5,23 @@
 //! - **`get_transaction_hashes_for_block(max_count)`** returns forced-inclusion hashes first (that are still in the mempool), then regular pending TX up to `max_count`. Guarantees that forced TX are included when building the block.
 //! - Constant: `MAX_FORCED_INCLUSION_QUEUE` = 256.
 //!
+//! # Memory bound
+//! Each entry records the byte size of its encoded (JSON) transaction; `bytes_used()` is their sum. When an
+//! addition would exceed `max_bytes`, the pool evicts its lowest-priority entries, taken from the end of
+//! `ordering::by_fee_then_hash` (lowest fee, then highest hash), until the new transaction fits. Entries in
+//! the forced-inclusion queue are never evicted. If the new transaction itself ranks lowest, it is rejected
+//! instead. Eviction depends only on pool contents, so nodes holding the same pool evict the same entries.
+//!
+//! # Replace-by-fee
+//! The pool holds at most one transaction per `(from, nonce)`. A new transaction for a taken slot replaces the
+//! pending one if its `fee_uplp` is at least `RBF_MIN_FEE_BUMP_PERCENT` percent higher, and is rejected otherwise;
+//! a sender can so unstick an underpriced transaction without waiting for it to expire. The replaced entry is
+//! removed before capacity eviction runs and is reported as evicted.
+//!
+//! # Expiry
+//! A transaction with `valid_until` cannot execute above that height. `evict_expired(height)` drops such
+//! entries once the chain passes it; block production still checks expiry through `ExecutionLogic`.
+//!
 //! # Fairness and determinism
 //!
 //! **Hash-only ordering and starvation:** Ordering solely by `tx.hash` can indefinitely delay
@@ -21,15 +38,24 @@
 //! **Separation of concerns:** The mempool handles fairness and liveness (arrival-order scheduling);
 //! the execution layer receives only the sorted batch of transactions and never `arrival_index`.
 //!
+//! # Paging
+//! An index of `(arrival_index, tx.hash)` is kept sorted as entries are added and removed, so
+//! `get_all_transactions` needs no sort and `content_page` serves one page of it in `O(log n + page)`. Its
+//! cursor is the key of the last entry served: transactions that arrive later land after every cursor, and
+//! removed ones simply drop out, so a client paging through never sees an entry twice.
+//!
 //! # Invariants
 //! - Storage is keyed by transaction hash (deterministic lookup).
 //! - Execution order is derived from the sorted batch produced by this module.
-//! - `arrival_index` is never exposed outside this module.
+//! - `arrival_index` is never exposed outside this module, except encoded in opaque page cursors.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Bound;
 use std::sync::RwLock;
+use serde::Serialize;
 use crate::error::{PlatariumError, Result};
 use crate::core::transaction::Transaction;
+use crate::core::ordering;
 use thiserror::Error;
 
 /// Internal mempool entry: transaction and its logical arrival order.
@@ -39,6 +65,8 @@ use thiserror::Error;
 pub struct MempoolEntry {
     pub tx: Transaction,
     pub arrival_index: u64,
+    /// Encoded transaction size in bytes (see `Transaction::encoded_size`).
+    pub size_bytes: usize,
 }
 
 /// Errors produced by the mempool.
@@ -47,6 +75,23 @@ pub enum MempoolError {
     #[error("Duplicate transaction: transaction with hash {0} already exists")]
     DuplicateTransaction(String),
     
+    #[error("Transaction {hash} is {size} bytes; mempool limit is {max_bytes}")]
+    TooLarge {
+        hash: String,
+        size: usize,
+        max_bytes: usize,
+    },
+
+    #[error("Mempool full: {needed} bytes needed, {max_bytes} max, and the transaction ranks below all evictable entries")]
+    Full { needed: usize, max_bytes: usize },
+
+    #[error("Replacement for {pending} underpriced: fee {fee_uplp} below required {required_fee_uplp}")]
+    ReplacementUnderpriced {
+        pending: String,
+        fee_uplp: u128,
+        required_fee_uplp: u128,
+    },
+
     #[error("Mempool error: {0}")]
     Other(String),
 }
@@ -61,46 +106,216 @@ impl From<MempoolError> for PlatariumError {
 /// Maximum forced-inclusion queue size (Step 5).
 pub const MAX_FORCED_INCLUSION_QUEUE: usize = 256;
 
+/// Default byte cap for pending transactions (64 MiB of encoded transactions).
+pub const DEFAULT_MEMPOOL_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Minimum fee increase, in percent of the pending fee, for a transaction to replace one with the same
+/// `(from, nonce)`.
+pub const RBF_MIN_FEE_BUMP_PERCENT: u128 = 10;
+
+/// Smallest `fee_uplp` that replaces a pending transaction paying `pending_fee_uplp` (rounded up, and always more).
+pub fn replacement_min_fee(pending_fee_uplp: u128) -> u128 {
+    pending_fee_uplp
+        .saturating_mul(100 + RBF_MIN_FEE_BUMP_PERCENT)
+        .div_ceil(100)
+        .max(pending_fee_uplp.saturating_add(1))
+}
+
+/// Point-in-time mempool metrics.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MempoolStats {
+    pub tx_count: usize,
+    pub bytes_used: usize,
+    pub max_bytes: usize,
+    pub forced_inclusion_count: usize,
+}
+
+/// One page of pending transactions from [`Mempool::content_page`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MempoolPage {
+    /// Transactions in `(arrival_index, tx.hash)` order.
+    pub transactions: Vec<Transaction>,
+    /// Cursor for the next page; `None` when this page reaches the end of the pool.
+    pub next_cursor: Option<String>,
+    /// Pool size when the page was read.
+    pub total: usize,
+}
+
 /// Thread-safe transaction pool (mempool) for pending transactions before execution.
 #[derive(Debug)]
 pub struct Mempool {
     /// Pending transactions keyed by hash; each entry includes the transaction and its arrival index.
     transactions: RwLock<HashMap<String, MempoolEntry>>,
+    /// `(arrival_index, tx.hash)` of every entry, kept sorted. Updated while holding the `transactions` lock.
+    order: RwLock<BTreeSet<(u64, String)>>,
+    /// `(from, nonce)` -> hash of the entry holding that slot. Updated while holding the `transactions` lock.
+    slots: RwLock<HashMap<(String, u64), String>>,
     /// Monotonic counter for logical arrival order; incremented on each successful add. Not exposed outside this module.
     next_arrival_index: RwLock<u64>,
     /// Forced-inclusion queue (anti-censorship): these hashes are prioritized when building the next block.
     forced_inclusion: RwLock<Vec<String>>,
+    /// Sum of `size_bytes` over all entries. Updated while holding the `transactions` lock.
+    bytes_used: RwLock<usize>,
+    /// Byte cap enforced by eviction on add.
+    max_bytes: usize,
 }
 
 impl Mempool {
-    /// Creates a new empty mempool.
+    /// Creates a new empty mempool with the default byte cap.
     pub fn new() -> Self {
+        Self::with_max_bytes(DEFAULT_MEMPOOL_MAX_BYTES)
+    }
+
+    /// Creates a new empty mempool holding at most `max_bytes` of encoded transactions.
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
         Self {
             transactions: RwLock::new(HashMap::new()),
+            order: RwLock::new(BTreeSet::new()),
+            slots: RwLock::new(HashMap::new()),
             next_arrival_index: RwLock::new(0),
             forced_inclusion: RwLock::new(Vec::new()),
+            bytes_used: RwLock::new(0),
+            max_bytes,
         }
     }
 
-    /// Adds a transaction to the mempool. Errors if a transaction with the same hash already exists.
+    /// Adds a transaction to the mempool. Errors if a transaction with the same hash already exists, if it takes the
+    /// `(from, nonce)` of a pending transaction without paying enough more to replace it, or if it cannot fit under the
+    /// byte cap (see module docs).
     ///
     /// Assigns a monotonic `arrival_index` (node-local, not from system time), stores the transaction, and does not validate or execute it.
     pub fn add_transaction(&self, tx: Transaction) -> Result<()> {
+        self.insert(tx).map(|_| ())
+    }
+
+    /// Like `add_transaction`, returning the hash of the pending transaction it replaced, if any.
+    pub fn add_transaction_replacing(&self, tx: Transaction) -> Result<Option<String>> {
+        self.insert(tx).map(|(replaced, _)| replaced)
+    }
+
+    /// Like `add_transaction`, returning every hash removed: the replaced transaction first, then those evicted to
+    /// make room, in eviction order.
+    pub fn add_transaction_evicting(&self, tx: Transaction) -> Result<Vec<String>> {
+        self.insert(tx).map(|(replaced, evicted)| replaced.into_iter().chain(evicted).collect())
+    }
+
+    fn insert(&self, tx: Transaction) -> Result<(Option<String>, Vec<String>)> {
         let mut transactions = self.transactions.write().unwrap();
+        let mut order = self.order.write().unwrap();
+        let mut slots = self.slots.write().unwrap();
         let mut next = self.next_arrival_index.write().unwrap();
+        let mut bytes = self.bytes_used.write().unwrap();
 
         if transactions.contains_key(&tx.hash) {
             return Err(MempoolError::DuplicateTransaction(tx.hash.clone()).into());
         }
+        let size = tx.encoded_size();
+        if size > self.max_bytes {
+            return Err(MempoolError::TooLarge {
+                hash: tx.hash.clone(),
+                size,
+                max_bytes: self.max_bytes,
+            }
+            .into());
+        }
+
+        let slot = (tx.from.clone(), tx.nonce);
+        let replaced = slots.get(&slot).map(|hash| &transactions[hash]);
+        if let Some(pending) = replaced {
+            let required_fee_uplp = replacement_min_fee(pending.tx.fee_uplp);
+            if tx.fee_uplp < required_fee_uplp {
+                return Err(MempoolError::ReplacementUnderpriced {
+                    pending: pending.tx.hash.clone(),
+                    fee_uplp: tx.fee_uplp,
+                    required_fee_uplp,
+                }
+                .into());
+            }
+        }
+        let replaced_size = replaced.map_or(0, |e| e.size_bytes);
+        let replaced = replaced.map(|e| e.tx.hash.clone());
+
+        let mut evicted = Vec::new();
+        if *bytes - replaced_size + size > self.max_bytes {
+            let forced = self.forced_inclusion.read().unwrap();
+            let mut candidates: Vec<&MempoolEntry> = transactions
+                .values()
+                .filter(|e| !forced.contains(&e.tx.hash) && Some(&e.tx.hash) != replaced.as_ref())
+                .collect();
+            candidates.sort_by(|a, b| ordering::by_fee_then_hash(&b.tx, &a.tx));
+            let mut freed = replaced_size;
+            for e in candidates {
+                if *bytes - freed + size <= self.max_bytes {
+                    break;
+                }
+                if ordering::by_fee_then_hash(&tx, &e.tx) != std::cmp::Ordering::Less {
+                    break;
+                }
+                freed += e.size_bytes;
+                evicted.push(e.tx.hash.clone());
+            }
+            if *bytes - freed + size > self.max_bytes {
+                return Err(MempoolError::Full {
+                    needed: size,
+                    max_bytes: self.max_bytes,
+                }
+                .into());
+            }
+            for h in &evicted {
+                if let Some(e) = transactions.remove(h) {
+                    *bytes -= e.size_bytes;
+                    slots.remove(&(e.tx.from, e.tx.nonce));
+                    order.remove(&(e.arrival_index, e.tx.hash));
+                }
+            }
+        }
+        if let Some(e) = replaced.as_ref().and_then(|h| transactions.remove(h)) {
+            *bytes -= e.size_bytes;
+            order.remove(&(e.arrival_index, e.tx.hash));
+        }
 
         let idx = *next;
         *next = next.saturating_add(1);
+        *bytes += size;
+        order.insert((idx, tx.hash.clone()));
+        slots.insert(slot, tx.hash.clone());
         transactions.insert(
             tx.hash.clone(),
-            MempoolEntry { tx, arrival_index: idx },
+            MempoolEntry { tx, arrival_index: idx, size_bytes: size },
         );
+        drop((transactions, order, slots, next, bytes));
+        if let Some(hash) = &replaced {
+            self.remove_forced_inclusion(std::slice::from_ref(hash));
+        }
+
+        Ok((replaced, evicted))
+    }
+
+    /// Hash of the pending transaction holding `(from, nonce)`, if any.
+    pub fn pending_hash_for(&self, from: &str, nonce: u64) -> Option<String> {
+        self.slots.read().unwrap().get(&(from.to_string(), nonce)).cloned()
+    }
+
+    /// Total encoded size in bytes of all pending transactions.
+    pub fn bytes_used(&self) -> usize {
+        *self.bytes_used.read().unwrap()
+    }
+
+    /// Byte cap enforced on add.
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
 
-        Ok(())
+    /// Current count, byte usage, byte cap and forced-inclusion queue length.
+    pub fn stats(&self) -> MempoolStats {
+        let transactions = self.transactions.read().unwrap();
+        let bytes_used = *self.bytes_used.read().unwrap();
+        MempoolStats {
+            tx_count: transactions.len(),
+            bytes_used,
+            max_bytes: self.max_bytes,
+            forced_inclusion_count: self.forced_inclusion.read().unwrap().len(),
+        }
     }
 
     /// Returns the transaction for the given hash, if present. The execution layer receives only the transaction; `arrival_index` is not exposed.
@@ -112,25 +327,96 @@ impl Mempool {
     /// Removes a transaction from the mempool by hash. Typically called after the transaction has been executed.
     pub fn remove_transaction(&self, hash: &str) -> bool {
         let mut transactions = self.transactions.write().unwrap();
-        transactions.remove(hash).is_some()
+        match transactions.remove(hash) {
+            Some(e) => {
+                *self.bytes_used.write().unwrap() -= e.size_bytes;
+                self.slots.write().unwrap().remove(&(e.tx.from, e.tx.nonce));
+                self.order.write().unwrap().remove(&(e.arrival_index, e.tx.hash));
+                true
+            }
+            None => false,
+        }
     }
 
     /// Removes the given transactions from the mempool. Typically called after they have been executed in a block.
     pub fn remove_transactions(&self, hashes: &[String]) {
         let mut transactions = self.transactions.write().unwrap();
+        let mut order = self.order.write().unwrap();
+        let mut slots = self.slots.write().unwrap();
+        let mut bytes = self.bytes_used.write().unwrap();
         for hash in hashes {
-            transactions.remove(hash);
+            if let Some(e) = transactions.remove(hash) {
+                *bytes -= e.size_bytes;
+                slots.remove(&(e.tx.from, e.tx.nonce));
+                order.remove(&(e.arrival_index, e.tx.hash));
+            }
+        }
+    }
+
+    /// Removes transactions whose `valid_until` is below `height` (they can no longer be included) and drops them
+    /// from the forced-inclusion queue. Call when the chain reaches `height`. Returns the evicted hashes, sorted.
+    pub fn evict_expired(&self, height: u64) -> Vec<String> {
+        let mut transactions = self.transactions.write().unwrap();
+        let mut expired: Vec<String> = transactions
+            .values()
+            .filter(|e| e.tx.is_expired_at(height))
+            .map(|e| e.tx.hash.clone())
+            .collect();
+        expired.sort();
+        let mut order = self.order.write().unwrap();
+        let mut slots = self.slots.write().unwrap();
+        let mut bytes = self.bytes_used.write().unwrap();
+        for hash in &expired {
+            if let Some(e) = transactions.remove(hash) {
+                *bytes -= e.size_bytes;
+                slots.remove(&(e.tx.from, e.tx.nonce));
+                order.remove(&(e.arrival_index, e.tx.hash));
+            }
         }
+        drop((transactions, order, slots, bytes));
+        self.remove_forced_inclusion(&expired);
+        expired
     }
 
     /// Returns all pending transactions in a fair, deterministic order: sorted by (arrival_index, tx.hash). Same mempool contents yield the same order; the execution layer receives only the transaction list.
     pub fn get_all_transactions(&self) -> Vec<Transaction> {
         let transactions = self.transactions.read().unwrap();
-        let mut entries: Vec<MempoolEntry> = transactions.values().cloned().collect();
-        entries.sort_by(|a, b| {
-            (a.arrival_index, a.tx.hash.as_str()).cmp(&(b.arrival_index, b.tx.hash.as_str()))
-        });
-        entries.into_iter().map(|e| e.tx).collect()
+        let order = self.order.read().unwrap();
+        order.iter().map(|(_, hash)| transactions[hash].tx.clone()).collect()
+    }
+
+    /// Up to `limit` pending transactions after `cursor` (from the previous page; `None` starts at the oldest),
+    /// stopping early before the page's encoded size would exceed `max_bytes`. A page holds at least one
+    /// transaction when any remain, so paging always progresses. Errors on a malformed cursor.
+    pub fn content_page(&self, cursor: Option<&str>, limit: usize, max_bytes: usize) -> Result<MempoolPage> {
+        let start = match cursor {
+            Some(c) => Bound::Excluded(parse_cursor(c)?),
+            None => Bound::Unbounded,
+        };
+        let transactions = self.transactions.read().unwrap();
+        let order = self.order.read().unwrap();
+        let mut page = Vec::new();
+        let mut bytes = 0usize;
+        let mut last = None;
+        let mut remaining = order.range((start, Bound::Unbounded)).peekable();
+        while let Some(key) = remaining.peek() {
+            let entry = &transactions[&key.1];
+            if page.len() >= limit || (!page.is_empty() && bytes + entry.size_bytes > max_bytes) {
+                break;
+            }
+            bytes += entry.size_bytes;
+            page.push(entry.tx.clone());
+            last = remaining.next();
+        }
+        let next_cursor = match (remaining.peek(), last) {
+            (Some(_), Some((arrival, hash))) => Some(format!("{:x}.{}", arrival, hash)),
+            _ => None,
+        };
+        Ok(MempoolPage {
+            transactions: page,
+            next_cursor,
+            total: transactions.len(),
+        })
     }
     
     /// Returns the number of pending transactions.
@@ -145,6 +431,12 @@ impl Mempool {
         transactions.is_empty()
     }
     
+    /// Returns the number of pending transactions sent by `sender`.
+    pub fn pending_count_for(&self, sender: &str) -> usize {
+        let transactions = self.transactions.read().unwrap();
+        transactions.values().filter(|e| e.tx.from == sender).count()
+    }
+
     /// Returns whether a transaction with the given hash is in the mempool.
     pub fn contains(&self, hash: &str) -> bool {
         let transactions = self.transactions.read().unwrap();
@@ -155,6 +447,9 @@ impl Mempool {
     pub fn clear(&self) {
         let mut transactions = self.transactions.write().unwrap();
         transactions.clear();
+        self.order.write().unwrap().clear();
+        self.slots.write().unwrap().clear();
+        *self.bytes_used.write().unwrap() = 0;
     }
 
     /// Adds a transaction hash to the forced-inclusion queue (anti-censorship). No effect if the queue is at capacity or the hash is already enqueued.
@@ -198,6 +493,14 @@ impl Mempool {
     }
 }
 
+/// Parses a `content_page` cursor (`{arrival_index:x}.{hash}`).
+fn parse_cursor(cursor: &str) -> Result<(u64, String)> {
+    cursor
+        .split_once('.')
+        .and_then(|(arrival, hash)| Some((u64::from_str_radix(arrival, 16).ok()?, hash.to_string())))
+        .ok_or_else(|| MempoolError::Other(format!("invalid cursor {:?}", cursor)).into())
+}
+
 impl Default for Mempool {
     fn default() -> Self {
         Self::new()
@@ -562,4 +865,142 @@ mod tests {
             assert_eq!(xa.hash, xb.hash, "index {} differs", i);
         }
     }
+
+    fn fee_tx(sender: &str, fee: u128) -> Transaction {
+        Transaction::new(
+            sender.to_string(),
+            "r".to_string(),
+            Asset::PLP,
+            1,
+            fee,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            "sig".to_string(),
+            "sig".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_byte_accounting_tracks_adds_and_removes() {
+        let mempool = Mempool::new();
+        let a = fee_tx("a", 1);
+        let b = fee_tx("bb", 1);
+        mempool.add_transaction(a.clone()).unwrap();
+        mempool.add_transaction(b.clone()).unwrap();
+        assert_eq!(mempool.bytes_used(), a.encoded_size() + b.encoded_size());
+        mempool.remove_transaction(&a.hash);
+        assert_eq!(mempool.bytes_used(), b.encoded_size());
+        let stats = mempool.stats();
+        assert_eq!((stats.tx_count, stats.bytes_used), (1, b.encoded_size()));
+        mempool.clear();
+        assert_eq!(mempool.bytes_used(), 0);
+    }
+
+    #[test]
+    fn test_byte_cap_evicts_lowest_fee_deterministically() {
+        let low = fee_tx("s1", 1);
+        let mid = fee_tx("s2", 5);
+        let high = fee_tx("s3", 9);
+        let cap = low.encoded_size() + mid.encoded_size() + 4;
+        let mempool = Mempool::with_max_bytes(cap);
+        mempool.add_transaction(low.clone()).unwrap();
+        mempool.add_transaction(mid.clone()).unwrap();
+
+        let evicted = mempool.add_transaction_evicting(high.clone()).unwrap();
+        assert_eq!(evicted, vec![low.hash.clone()]);
+        assert!(mempool.contains(&high.hash) && mempool.contains(&mid.hash));
+        assert!(mempool.bytes_used() <= cap);
+
+        // A transaction ranking below everything evictable is rejected instead.
+        let err = mempool.add_transaction(fee_tx("s4", 1)).unwrap_err();
+        assert!(err.to_string().contains("Mempool full"));
+
+        // Forced-inclusion entries are never evicted.
+        mempool.add_forced_inclusion(mid.hash.clone());
+        let top = fee_tx("s5", 20);
+        assert_eq!(mempool.add_transaction_evicting(top).unwrap(), vec![high.hash.clone()]);
+        assert!(mempool.contains(&mid.hash));
+    }
+
+    #[test]
+    fn test_evict_expired() {
+        let mempool = Mempool::new();
+        let open = fee_tx("open", 1);
+        let short = fee_tx("short", 1).with_valid_until(Some(10)).unwrap();
+        let long = fee_tx("long", 1).with_valid_until(Some(20)).unwrap();
+        for tx in [&open, &short, &long] {
+            mempool.add_transaction(tx.clone()).unwrap();
+        }
+        mempool.add_forced_inclusion(short.hash.clone());
+
+        assert!(mempool.evict_expired(10).is_empty());
+        assert_eq!(mempool.evict_expired(11), vec![short.hash.clone()]);
+        assert!(mempool.get_forced_inclusion().is_empty());
+        assert_eq!(mempool.bytes_used(), open.encoded_size() + long.encoded_size());
+        assert_eq!(mempool.evict_expired(u64::MAX), vec![long.hash.clone()]);
+        assert_eq!(mempool.get_all_transactions(), vec![open]);
+    }
+
+    #[test]
+    fn test_oversized_transaction_rejected() {
+        let mempool = Mempool::with_max_bytes(10);
+        let err = mempool.add_transaction(fee_tx("a", 1)).unwrap_err();
+        assert!(err.to_string().contains("mempool limit"));
+        assert!(mempool.is_empty());
+    }
+
+    #[test]
+    fn test_content_pages_follow_arrival_order() {
+        let mempool = Mempool::new();
+        let txs: Vec<Transaction> = (0..5).map(|i| fee_tx(&format!("p{}", i), 1)).collect();
+        for tx in &txs {
+            mempool.add_transaction(tx.clone()).unwrap();
+        }
+        let first = mempool.content_page(None, 2, usize::MAX).unwrap();
+        assert_eq!(first.transactions, txs[..2].to_vec());
+        assert_eq!(first.total, 5);
+
+        // Removals and later arrivals do not shift the remaining pages.
+        mempool.remove_transaction(&txs[2].hash);
+        let late = fee_tx("late", 1);
+        mempool.add_transaction(late.clone()).unwrap();
+        let second = mempool.content_page(first.next_cursor.as_deref(), 2, usize::MAX).unwrap();
+        assert_eq!(second.transactions, vec![txs[3].clone(), txs[4].clone()]);
+        let third = mempool.content_page(second.next_cursor.as_deref(), 2, usize::MAX).unwrap();
+        assert_eq!(third.transactions, vec![late]);
+        assert_eq!(third.next_cursor, None);
+
+        // The byte cap shortens a page but never empties it.
+        let capped = mempool.content_page(None, 10, 1).unwrap();
+        assert_eq!(capped.transactions.len(), 1);
+        assert!(capped.next_cursor.is_some());
+        assert!(mempool.content_page(Some("zz"), 1, 1).is_err());
+        assert_eq!(mempool.get_all_transactions().len(), 5);
+    }
+
+    #[test]
+    fn test_replace_by_fee() {
+        let mempool = Mempool::new();
+        let stuck = fee_tx("s", 10);
+        mempool.add_transaction(stuck.clone()).unwrap();
+        mempool.add_forced_inclusion(stuck.hash.clone());
+        assert_eq!(replacement_min_fee(10), 11);
+        assert_eq!(replacement_min_fee(0), 1);
+
+        let err = mempool.add_transaction(fee_tx("s", 10).with_valid_until(Some(5)).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("underpriced"));
+        assert_eq!(mempool.pending_hash_for("s", 0), Some(stuck.hash.clone()));
+
+        let bumped = fee_tx("s", 11);
+        assert_eq!(mempool.add_transaction_replacing(bumped.clone()).unwrap(), Some(stuck.hash.clone()));
+        assert_eq!(mempool.get_all_transactions(), vec![bumped.clone()]);
+        assert_eq!(mempool.bytes_used(), bumped.encoded_size());
+        assert!(mempool.get_forced_inclusion().is_empty());
+        assert_eq!(mempool.add_transaction_evicting(fee_tx("s", 20)).unwrap(), vec![bumped.hash]);
+
+        mempool.clear();
+        assert_eq!(mempool.add_transaction_replacing(stuck).unwrap(), None);
+    }
 }
@@ -0,0 +1,332 @@
+//! Versioned state migrations.
+//!
+//! The state layout carries a version ([`STATE_VERSION`] for a fresh state). When a release changes the layout
+//! it registers a forward migration from the previous version in [`MigrationRegistry::standard`]. Two kinds exist:
+//!
+//! - [`MigrationKind::Layout`] rewrites the serialized state document (the state file JSON) before it is parsed.
+//!   It runs at startup, must only change representation, and must not change the state root.
+//! - [`MigrationKind::State`] transforms the live state. It runs once the chain reaches its activation height,
+//!   so every node changes consensus state at the same block; nodes starting past the height apply it on load.
+//!
+//! Migrations run strictly in version order, and activation heights never decrease along that order, so a
+//! pending height-gated migration holds back every later one. Each applied migration is appended to the
+//! state's migration log (persisted in the state file). A state whose version is newer than the latest
+//! registered version is refused rather than guessed at.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::core::state::State;
+use crate::error::{PlatariumError, Result};
+
+/// Layout version of a freshly created state; the latest version in [`MigrationRegistry::standard`].
+pub const STATE_VERSION: u32 = 1;
+
+/// How a migration changes the state.
+#[derive(Debug, Clone, Copy)]
+pub enum MigrationKind {
+    /// Rewrites the serialized state document in place. Applied at startup.
+    Layout(fn(&mut Value) -> Result<()>),
+    /// Transforms the loaded state. Applied at the activation height.
+    State(fn(&State) -> Result<()>),
+}
+
+/// Forward migration from `from_version` to `from_version + 1`.
+#[derive(Debug, Clone, Copy)]
+pub struct StateMigration {
+    pub from_version: u32,
+    pub name: &'static str,
+    /// First height at which the migration applies. Must be 0 for layout migrations.
+    pub activation_height: u64,
+    pub kind: MigrationKind,
+}
+
+/// One applied migration, as recorded in the migration log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub name: String,
+    /// Block height the migration ran at (0 for layout migrations run at startup).
+    pub height: u64,
+}
+
+/// Layout version of a state and the migrations that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateLayout {
+    pub version: u32,
+    pub migration_log: Vec<MigrationRecord>,
+}
+
+impl Default for StateLayout {
+    fn default() -> Self {
+        Self {
+            version: STATE_VERSION,
+            migration_log: Vec::new(),
+        }
+    }
+}
+
+/// Ordered forward migrations starting at `base_version`.
+#[derive(Debug, Clone)]
+pub struct MigrationRegistry {
+    base_version: u32,
+    migrations: Vec<StateMigration>,
+}
+
+impl MigrationRegistry {
+    /// Empty registry whose oldest supported version is `base_version`.
+    pub fn new(base_version: u32) -> Self {
+        Self {
+            base_version,
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Migrations shipped with this binary. v1 is the first layout, so there are none yet.
+    pub fn standard() -> Self {
+        Self::new(1)
+    }
+
+    /// Appends `migration`. It must start at the current latest version, a layout migration must activate at
+    /// height 0, and activation heights must not decrease.
+    pub fn register(&mut self, migration: StateMigration) -> Result<()> {
+        let latest = self.latest_version();
+        if migration.from_version != latest {
+            return Err(migration_error(format!(
+                "{} migrates from v{}, expected v{}",
+                migration.name, migration.from_version, latest
+            )));
+        }
+        if matches!(migration.kind, MigrationKind::Layout(_)) && migration.activation_height != 0 {
+            return Err(migration_error(format!(
+                "layout migration {} cannot have an activation height",
+                migration.name
+            )));
+        }
+        if let Some(prev) = self.migrations.last() {
+            if migration.activation_height < prev.activation_height {
+                return Err(migration_error(format!(
+                    "{} activates at {} before {} at {}",
+                    migration.name, migration.activation_height, prev.name, prev.activation_height
+                )));
+            }
+        }
+        self.migrations.push(migration);
+        Ok(())
+    }
+
+    /// Newest version this registry can migrate to.
+    pub fn latest_version(&self) -> u32 {
+        self.base_version + self.migrations.len() as u32
+    }
+
+    /// Ok if `version` can be loaded: not older than the base version and not newer than the latest.
+    pub fn check_supported(&self, version: u32) -> Result<()> {
+        if version > self.latest_version() {
+            return Err(migration_error(format!(
+                "state version {} is newer than this binary supports ({}); upgrade the node",
+                version,
+                self.latest_version()
+            )));
+        }
+        if version < self.base_version {
+            return Err(migration_error(format!(
+                "state version {} predates the oldest supported version {}",
+                version, self.base_version
+            )));
+        }
+        Ok(())
+    }
+
+    /// Version the chain is at by `height` when starting from `version`.
+    pub fn version_at(&self, version: u32, height: u64) -> u32 {
+        self.pending(version)
+            .take_while(|m| m.activation_height <= height)
+            .last()
+            .map_or(version, |m| m.from_version + 1)
+    }
+
+    fn pending(&self, version: u32) -> impl Iterator<Item = &StateMigration> {
+        let skip = version.saturating_sub(self.base_version) as usize;
+        self.migrations.iter().skip(skip)
+    }
+
+    /// Applies the leading layout migrations to a serialized state document, updating its `version` and
+    /// `migration_log` fields. Stops at the first state migration. Returns the applied records.
+    pub fn migrate_document(&self, doc: &mut Value) -> Result<Vec<MigrationRecord>> {
+        let version = doc
+            .get("version")
+            .and_then(Value::as_u64)
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| migration_error("state document has no valid version".to_string()))?;
+        self.check_supported(version)?;
+        let mut applied = Vec::new();
+        for migration in self.pending(version) {
+            let MigrationKind::Layout(migrate) = migration.kind else {
+                break;
+            };
+            migrate(doc)?;
+            applied.push(record(migration, 0));
+        }
+        if let Some(last) = applied.last() {
+            let log: Vec<MigrationRecord> = match doc.get("migration_log") {
+                Some(v) if !v.is_null() => serde_json::from_value(v.clone())
+                    .map_err(|e| migration_error(format!("invalid migration log: {}", e)))?,
+                _ => Vec::new(),
+            };
+            let log: Vec<MigrationRecord> = log.into_iter().chain(applied.iter().cloned()).collect();
+            let object = doc
+                .as_object_mut()
+                .ok_or_else(|| migration_error("state document is not an object".to_string()))?;
+            object.insert("version".to_string(), Value::from(last.to_version));
+            object.insert("migration_log".to_string(), serde_json::to_value(log).unwrap());
+        }
+        Ok(applied)
+    }
+
+    /// Applies every state migration due at `height` to `state`, records it in the state's layout and returns the
+    /// applied ones. Runs at load (`load_state_file_with`) and at the start of every block
+    /// (`ExecutionLogic::execute_block`). Fails on an unknown future version or on
+    /// a layout migration that was not applied before the state was loaded.
+    pub fn apply_due(&self, state: &State, height: u64) -> Result<Vec<MigrationRecord>> {
+        let mut layout = state.layout();
+        self.check_supported(layout.version)?;
+        let mut applied = Vec::new();
+        for migration in self.pending(layout.version) {
+            if migration.activation_height > height {
+                break;
+            }
+            let MigrationKind::State(migrate) = migration.kind else {
+                return Err(migration_error(format!(
+                    "layout migration {} must be applied before the state is loaded",
+                    migration.name
+                )));
+            };
+            migrate(state)?;
+            let entry = record(migration, height);
+            layout.version = entry.to_version;
+            layout.migration_log.push(entry.clone());
+            state.set_layout(layout.clone());
+            applied.push(entry);
+        }
+        Ok(applied)
+    }
+}
+
+fn record(migration: &StateMigration, height: u64) -> MigrationRecord {
+    MigrationRecord {
+        from_version: migration.from_version,
+        to_version: migration.from_version + 1,
+        name: migration.name.to_string(),
+        height,
+    }
+}
+
+fn migration_error(msg: String) -> PlatariumError {
+    PlatariumError::State(format!("state migration: {}", msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_nonces(doc: &mut Value) -> Result<()> {
+        let obj = doc.as_object_mut().unwrap();
+        if let Some(v) = obj.remove("account_nonces") {
+            obj.insert("nonces".to_string(), v);
+        }
+        Ok(())
+    }
+
+    fn credit_treasury(state: &State) -> Result<()> {
+        state.set_uplp_balance(&"treasury".to_string(), 7);
+        Ok(())
+    }
+
+    fn registry() -> MigrationRegistry {
+        let mut registry = MigrationRegistry::new(1);
+        registry
+            .register(StateMigration {
+                from_version: 1,
+                name: "rename_nonces",
+                activation_height: 0,
+                kind: MigrationKind::Layout(rename_nonces),
+            })
+            .unwrap();
+        registry
+            .register(StateMigration {
+                from_version: 2,
+                name: "credit_treasury",
+                activation_height: 100,
+                kind: MigrationKind::State(credit_treasury),
+            })
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_standard_registry_matches_state_version() {
+        assert_eq!(MigrationRegistry::standard().latest_version(), STATE_VERSION);
+        assert_eq!(State::new().layout(), StateLayout::default());
+    }
+
+    #[test]
+    fn test_register_rejects_gaps_and_out_of_order_heights() {
+        let mut registry = registry();
+        let mut m = StateMigration {
+            from_version: 2,
+            name: "gap",
+            activation_height: 200,
+            kind: MigrationKind::State(credit_treasury),
+        };
+        assert!(registry.register(m).is_err());
+        m.from_version = 3;
+        m.activation_height = 50;
+        assert!(registry.register(m).is_err());
+        m.activation_height = 200;
+        m.kind = MigrationKind::Layout(rename_nonces);
+        assert!(registry.register(m).is_err());
+    }
+
+    #[test]
+    fn test_document_then_activation_height() {
+        let registry = registry();
+        let mut doc = json!({"version": 1, "account_nonces": [["a", 3]]});
+        let applied = registry.migrate_document(&mut doc).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(doc["version"], 2);
+        assert_eq!(doc["nonces"], json!([["a", 3]]));
+        assert_eq!(doc["migration_log"][0]["name"], "rename_nonces");
+        assert!(registry.migrate_document(&mut doc).unwrap().is_empty());
+
+        let state = State::new();
+        state.set_layout(StateLayout {
+            version: 2,
+            migration_log: applied,
+        });
+        assert!(registry.apply_due(&state, 99).unwrap().is_empty());
+        assert_eq!(registry.version_at(2, 99), 2);
+        let applied = registry.apply_due(&state, 100).unwrap();
+        assert_eq!(applied[0].height, 100);
+        assert_eq!(state.get_uplp_balance(&"treasury".to_string()), 7);
+        let layout = state.layout();
+        assert_eq!(layout.version, 3);
+        assert_eq!(layout.migration_log.len(), 2);
+        assert!(registry.apply_due(&state, 101).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_refuses_unknown_future_version() {
+        let registry = registry();
+        let mut doc = json!({"version": 4});
+        let err = registry.migrate_document(&mut doc).unwrap_err();
+        assert!(err.to_string().contains("newer than this binary"));
+        let state = State::new();
+        state.set_layout(StateLayout {
+            version: 9,
+            migration_log: Vec::new(),
+        });
+        assert!(registry.apply_due(&state, 0).is_err());
+    }
+}
@@ -9,29 +9,75 @@
 //! - No external sources of non-determinism (time, RNG, etc.) are used in the core path.
 
 pub mod asset;
+pub mod asset_registry;
+pub mod events;
+pub mod address_bloom;
 pub mod transaction;
 pub mod state;
+pub mod overlay;
+pub mod outbound;
+pub mod bridge;
 pub mod mempool;
 pub mod execution;
 pub mod fee;
+pub mod head;
+pub mod invariants;
+pub mod load;
+pub mod migrations;
 pub mod determinism;
 pub mod node_registry;
 pub mod validator_selection;
 pub mod confirmation_layer;
+pub mod consensus_admission;
 pub mod block_assembly;
+pub mod codec;
+pub mod hashing;
 pub mod slashing;
 pub mod tx_assignment;
 pub mod state_file;
 pub mod consensus_params;
 pub mod block_proposal;
+pub mod chainspec;
+pub mod ordering;
+pub mod partial_tx;
+pub mod presigned;
+pub mod protocol_params;
+pub mod receipt;
+pub mod overload;
+pub mod recovery;
+pub mod session_keys;
+pub mod standing_orders;
+pub mod multisig;
+pub mod state_commitment;
+pub mod state_sync;
 pub mod block_proposal_cli;
 pub mod consensus_cli;
 pub mod core_rpc;
+pub mod discovery;
+pub mod governance;
+pub mod insurance;
+pub mod legacy;
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+pub mod heartbeat;
+pub mod vesting;
 
 use crate::error::{PlatariumError, Result};
 use crate::core::transaction::Transaction;
 use crate::core::state::State;
+use crate::core::load::{FeeBucketRecord, NetworkLoad};
 use crate::core::mempool::Mempool;
+use crate::core::node_registry::{NodeId, NodeRegistry};
+use crate::core::overlay::OverlayState;
+use crate::core::block_assembly::Block;
+use crate::core::head::{HeadInfo, HeadPublisher, HeadReceiver};
+use crate::core::overload::{AdmissionPolicy, OverloadLevel};
+use crate::storage::{
+    commit_block, list_fee_buckets, list_receipts_for_address, recent_load_samples, BlockCommit, ReceiptPage,
+    RocksStore,
+};
+use std::ops::RangeInclusive;
+use std::sync::{Arc, RwLock};
 
 /// Transaction hash type (alias for String).
 pub type TxHash = String;
@@ -42,7 +88,13 @@ pub struct Core {
     /// Blockchain state; updates are deterministic and order-dependent.
     state: State,
     /// Transaction pool; execution order is determined by the mempool’s sorted batch, not storage order.
-    mempool: Mempool,
+    mempool: Arc<Mempool>,
+    /// Node-local load-shedding level; gates admission only, never execution.
+    overload: RwLock<OverloadLevel>,
+    /// Committed chain storage for historical queries; `None` for an in-memory core.
+    store: Option<RocksStore>,
+    /// Latest head, pushed to `head_updates` subscribers.
+    head: HeadPublisher,
 }
 
 impl Core {
@@ -50,23 +102,51 @@ impl Core {
     pub fn new() -> Self {
         Self {
             state: State::new(),
-            mempool: Mempool::new(),
+            mempool: Arc::new(Mempool::new()),
+            overload: RwLock::new(OverloadLevel::Normal),
+            store: None,
+            head: HeadPublisher::default(),
+        }
+    }
+
+    /// Creates a Core backed by `store` for receipt and history queries.
+    pub fn with_store(store: RocksStore) -> Self {
+        Self {
+            head: HeadPublisher::new(HeadInfo::from_store(&store).unwrap_or_default()),
+            store: Some(store),
+            ..Self::new()
         }
     }
     
-    /// Submits a transaction: validates (validate_basic), adds to mempool, then applies to state. Returns the transaction hash on success. Errors if validation fails, the transaction is a duplicate, or state application fails. Same transaction order yields the same state; no randomness or system time is used.
+    /// Submits a transaction to the mempool: validates it for the state's deployment (`State::check_transaction`: salt, chain ID, validate_basic), checks the overload admission policy, checks that it applies on top of the sender's pending transactions, then adds it to the mempool. State is not touched; the transaction is applied when a block containing it is executed. A transaction with the `(from, nonce)` of a pending one replaces it if it pays enough more (see `mempool::replacement_min_fee`). Returns the transaction hash on success. Errors if validation fails, the transaction is refused under the current overload level, it would not apply, or the mempool rejects it (duplicate, underpriced replacement, full).
     pub fn submit_transaction(&self, tx: Transaction) -> Result<TxHash> {
-        tx.validate_basic()
+        self.state.check_transaction(&tx)
             .map_err(PlatariumError::from)?;
+        self.admission_policy()
+            .check(&tx, self.mempool.pending_count_for(&tx.from))
+            .map_err(PlatariumError::from)?;
+        self.check_applies(&tx)?;
         self.mempool.add_transaction(tx.clone())
             .map_err(PlatariumError::from)?;
-        self.state.apply_transaction(&tx)
-            .map_err(|e| {
-                let _ = self.mempool.remove_transaction(&tx.hash);
-                e
-            })?;
         Ok(tx.hash)
     }
+
+    /// Applies the sender's pending transactions with lower nonces, then `tx`, to an overlay over the current
+    /// state. The state itself is left unchanged.
+    fn check_applies(&self, tx: &Transaction) -> Result<()> {
+        let mut ahead: Vec<Transaction> = self
+            .mempool
+            .get_all_transactions()
+            .into_iter()
+            .filter(|p| p.from == tx.from && p.nonce < tx.nonce)
+            .collect();
+        ahead.sort_by_key(|p| p.nonce);
+        let mut overlay = OverlayState::new(self.state.snapshot());
+        for pending in &ahead {
+            overlay.apply_effects(pending)?;
+        }
+        overlay.apply_effects(tx)
+    }
     
     /// Returns a reference to the state manager.
     pub fn state(&self) -> &State {
@@ -77,6 +157,140 @@ impl Core {
     pub fn mempool(&self) -> &Mempool {
         &self.mempool
     }
+
+    /// Shared handle to the mempool, e.g. for `core_rpc::RpcContext` so the RPC server pages the node's pool.
+    pub fn shared_mempool(&self) -> Arc<Mempool> {
+        Arc::clone(&self.mempool)
+    }
+
+    /// Load estimate from the current mempool and `recent_blocks` (the node's latest committed blocks).
+    pub fn network_load(&self, recent_blocks: &[Block]) -> NetworkLoad {
+        NetworkLoad::from_mempool(&self.mempool, recent_blocks)
+    }
+
+    /// Load estimate from the current mempool and the last `LOAD_WINDOW_BLOCKS` headers in the attached store; the
+    /// mempool alone without a store.
+    pub fn stored_network_load(&self) -> Result<NetworkLoad> {
+        let samples = match &self.store {
+            Some(store) => recent_load_samples(store)?,
+            None => Vec::new(),
+        };
+        Ok(NetworkLoad::estimate(self.mempool.len(), &samples))
+    }
+
+    /// L1 and L2 validator sets for `block_number`, sized by [`Self::stored_network_load`].
+    pub fn select_validators(
+        &self,
+        registry: &NodeRegistry,
+        block_number: u64,
+        prev_finalized_hash: &[u8],
+    ) -> Result<(Vec<NodeId>, Vec<NodeId>)> {
+        self.stored_network_load()?.select_validators(registry, block_number, prev_finalized_hash)
+    }
+
+    /// Returns the attached chain storage, if any.
+    pub fn store(&self) -> Option<&RocksStore> {
+        self.store.as_ref()
+    }
+
+    /// Pages through committed receipts where `address` was sender, receiver or fee payer, using the storage address index. Pass `None` for the first page and the returned `next_cursor` afterwards; `limit` is clamped to `MAX_RECEIPT_PAGE_SIZE`. Errors if no store is attached or the cursor is malformed.
+    pub fn get_receipts_by_address(
+        &self,
+        address: &str,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<ReceiptPage> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| PlatariumError::State("no chain storage attached".to_string()))?;
+        list_receipts_for_address(store, address, cursor, limit)
+    }
+
+    /// Fee bucket record for the block being assembled at `block_number`: the current load's multiplier plus
+    /// mempool occupancy. `commit_block` fills `BlockCommit::fee_bucket` from the stored headers when it is unset.
+    pub fn fee_bucket_record(&self, block_number: u64, recent_blocks: &[Block]) -> FeeBucketRecord {
+        self.network_load(recent_blocks)
+            .fee_bucket_record(block_number, self.mempool.bytes_used(), self.mempool.max_bytes())
+    }
+
+    /// Fee buckets recorded for committed blocks in `range`, by height.
+    pub fn fee_bucket_history(&self, range: RangeInclusive<u64>) -> Result<Vec<FeeBucketRecord>> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| PlatariumError::State("no chain storage attached".to_string()))?;
+        list_fee_buckets(store, *range.start(), *range.end())
+    }
+
+    /// Commits a finalized block to the attached storage, then publishes it as the new head. A commit without a
+    /// `fee_bucket` gets one from [`Self::stored_network_load`] and the current mempool occupancy.
+    pub fn commit_block(&self, commit: &BlockCommit) -> Result<HeadInfo> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| PlatariumError::State("no chain storage attached".to_string()))?;
+        let filled;
+        let commit = match commit.fee_bucket {
+            Some(_) => commit,
+            None => {
+                let bucket = self.stored_network_load()?.fee_bucket_record(
+                    commit.block.height,
+                    self.mempool.bytes_used(),
+                    self.mempool.max_bytes(),
+                );
+                filled = BlockCommit {
+                    fee_bucket: Some(bucket),
+                    ..commit.clone()
+                };
+                &filled
+            }
+        };
+        commit_block(store, commit)?;
+        let head = HeadInfo::from_commit(commit);
+        self.head.publish(head.clone());
+        Ok(head)
+    }
+
+    /// Publishes a head that did not go through `commit_block` (e.g. an in-memory core, or a proposed block
+    /// whose `finalized_height` lags its `height`).
+    pub fn publish_head(&self, head: HeadInfo) {
+        self.head.publish(head);
+    }
+
+    /// Latest published head.
+    pub fn head(&self) -> HeadInfo {
+        self.head.head()
+    }
+
+    /// Blocking receiver of head updates, for embedders without an async runtime. Holds only the latest head;
+    /// see `core::head` for the backpressure semantics.
+    pub fn head_updates_sync(&self) -> HeadReceiver {
+        self.head.subscribe()
+    }
+
+    /// Receiver of head updates. Holds only the latest head; see `core::head` for the backpressure semantics.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn head_updates(&self) -> tokio::sync::watch::Receiver<HeadInfo> {
+        self.head.subscribe_watch()
+    }
+
+    /// Sets the load-shedding level and returns the resulting policy, which the network layer uses to deprioritize gossip from peers over `peer_strike_limit`. Already-pending transactions are kept; only new admissions are affected.
+    pub fn set_overload_mode(&self, level: OverloadLevel) -> AdmissionPolicy {
+        *self.overload.write().unwrap() = level;
+        level.policy()
+    }
+
+    /// Current load-shedding level.
+    pub fn overload_mode(&self) -> OverloadLevel {
+        *self.overload.read().unwrap()
+    }
+
+    /// Admission policy for the current load-shedding level.
+    pub fn admission_policy(&self) -> AdmissionPolicy {
+        self.overload_mode().policy()
+    }
 }
 
 impl Default for Core {
@@ -126,6 +340,40 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[test]
+    fn test_overload_mode_tightens_admission() {
+        use crate::core::overload::OverloadLevel;
+        let core = Core::new();
+        let tx = |fee: u128, nonce: u64| {
+            Transaction::new(
+                "sender".to_string(),
+                "receiver".to_string(),
+                Asset::PLP,
+                1,
+                fee,
+                nonce,
+                HashSet::new(),
+                HashSet::new(),
+                String::new(),
+                String::new(),
+            )
+            .unwrap()
+        };
+        let policy = core.set_overload_mode(OverloadLevel::Critical);
+        assert_eq!(core.overload_mode(), OverloadLevel::Critical);
+        assert_eq!(core.admission_policy(), policy);
+        let pending = || core.mempool().pending_count_for("sender");
+        assert!(policy.check(&tx(1, 0), pending()).is_err());
+        assert!(policy.check(&tx(policy.min_fee_uplp, 0), pending()).is_ok());
+
+        core.mempool().add_transaction(tx(policy.min_fee_uplp, 0)).unwrap();
+        let err = core.admission_policy().check(&tx(policy.min_fee_uplp, 1), pending()).unwrap_err();
+        assert!(err.to_string().contains("pending transactions"), "{}", err);
+
+        core.set_overload_mode(OverloadLevel::Normal);
+        assert!(core.admission_policy().check(&tx(1, 1), pending()).is_ok());
+    }
+
     #[test]
     fn test_submit_transaction_duplicate() {
         let core = Core::new();
@@ -157,6 +405,36 @@ mod tests {
         assert!(result2.is_err());
     }
     
+    #[test]
+    fn test_submit_transaction_replaces_by_fee() {
+        use crate::core::execution::ExecutionLogic;
+        use crate::testing::DeterministicRng;
+        let mut rng = DeterministicRng::new(4054);
+        let (alice, bob, carol) = (rng.account(), rng.account(), rng.account());
+        let core = Core::new();
+        core.state().set_balance(&alice.address, 1_000);
+        core.state().set_uplp_balance(&alice.address, 100);
+
+        let first = core.submit_transaction(alice.transfer(&bob.address, 100, 1, 0)).unwrap();
+        let next = core.submit_transaction(alice.transfer(&bob.address, 50, 1, 1)).unwrap();
+        assert_eq!(core.state().get_nonce(&alice.address), 0, "submission leaves the state alone");
+        assert!(core.submit_transaction(alice.transfer(&carol.address, 100, 1, 0)).is_err());
+        assert!(core.submit_transaction(alice.transfer(&carol.address, 2_000, 5, 0)).is_err());
+
+        let replacement = core.submit_transaction(alice.transfer(&carol.address, 100, 5, 0)).unwrap();
+        assert!(!core.mempool().contains(&first));
+        assert_eq!(core.mempool().len(), 2);
+
+        let mut txs: Vec<_> = core.mempool().get_all_transactions();
+        txs.sort_by_key(|tx| tx.nonce);
+        assert_eq!((txs[0].hash.as_str(), txs[1].hash.as_str()), (replacement.as_str(), next.as_str()));
+        let executed = ExecutionLogic::execute_block(core.state(), &txs, 1).unwrap();
+        assert!(executed.receipts.iter().all(|r| r.success), "{:?}", executed.receipts);
+        assert_eq!(core.state().get_balance(&carol.address), 100);
+        assert_eq!(core.state().get_balance(&bob.address), 50);
+        assert_eq!(core.state().get_nonce(&alice.address), 2);
+    }
+
     #[test]
     fn test_determinism_same_transactions_same_state() {
         use std::collections::HashSet;
@@ -212,4 +490,27 @@ mod tests {
         let hash1_2 = tx1.compute_hash().unwrap();
         assert_eq!(hash1_1, hash1_2); // Deterministic hash computation
     }
+
+    #[test]
+    fn test_publish_head_reaches_subscribers() {
+        let core = Core::new();
+        let mut updates = core.head_updates_sync();
+        let head = HeadInfo {
+            height: 3,
+            block_hash: "bh3".into(),
+            state_root: "root3".into(),
+            finalized_height: 1,
+        };
+        core.publish_head(head.clone());
+        assert_eq!(updates.borrow_and_update(), head);
+        assert!(!updates.has_changed());
+
+        #[cfg(feature = "tokio")]
+        {
+            let mut watch = core.head_updates();
+            assert_eq!(*watch.borrow_and_update(), head);
+            core.publish_head(HeadInfo::default());
+            assert!(watch.has_changed().unwrap());
+        }
+    }
 }
@@ -0,0 +1,242 @@
+//! m-of-n multisig accounts.
+//!
+//! A dual-signed `SetMultisig { signers, threshold }` transaction turns its sender into a multisig account
+//! (`AccountKind::Multisig` in `State`). The main signature must come from the key the address is made of (or the
+//! key installed by recovery), so no other key pair can hand the account to its own signers. From then on the account's transactions carry a [`MultisigWitness`] in
+//! `Transaction::multisig` instead of the main/derived signature pair: the signer set and threshold, plus at least
+//! `threshold` signatures over the transaction hash by distinct signers. `Transaction::verify_signatures` checks the
+//! signatures without state; execution checks that the witness names exactly the signer set and threshold
+//! registered for the account. A later `SetMultisig`, authorized by the current signers, replaces the set.
+//!
+//! The address does not change. Derived keys and recovery (`RegisterDerivedKey`, `InitiateRecovery`,
+//! `CancelRecovery`) are not available to multisig accounts; session keys are, registered and revoked with a witness.
+
+use platarium_consensus::wire::{Decode, Encode, Reader, WireError};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::state::Address;
+use crate::error::{PlatariumError, Result};
+use crate::signature::{normalize_public_key_hex, verify_signature_hash_with, SignatureScheme};
+use crate::signer::{sign_digest_checked, Signer};
+
+/// Largest signer set.
+pub const MAX_MULTISIG_SIGNERS: usize = 16;
+
+/// Errors produced by multisig configuration and authorization.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MultisigError {
+    #[error("Invalid multisig configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("{0} is not a multisig account")]
+    NotMultisig(Address),
+
+    #[error("{0} is a multisig account; the transaction needs a multisig witness")]
+    WitnessRequired(Address),
+
+    #[error("Witness signer set or threshold does not match the one registered for {0}")]
+    SignerSetMismatch(Address),
+
+    #[error("Signer {0} is not in the witness signer set")]
+    UnknownSigner(String),
+
+    #[error("Invalid witness signature entry: {0}")]
+    InvalidSignature(String),
+
+    #[error("Derived keys and recovery are not available to multisig account {0}")]
+    RecoveryNotSupported(Address),
+}
+
+impl From<MultisigError> for PlatariumError {
+    fn from(err: MultisigError) -> Self {
+        PlatariumError::State(format!("Multisig error: {}", err))
+    }
+}
+
+/// Checks a signer set and threshold: 1 to [`MAX_MULTISIG_SIGNERS`] distinct valid keys (hex, optional `Px`) and
+/// `1 <= threshold <= signers`. Returns the keys as lowercase hex without prefix, in the given order.
+pub fn check_multisig_config(signers: &[String], threshold: u32) -> std::result::Result<Vec<String>, MultisigError> {
+    if signers.is_empty() || signers.len() > MAX_MULTISIG_SIGNERS {
+        return Err(MultisigError::InvalidConfig(format!(
+            "need 1 to {} signers, got {}",
+            MAX_MULTISIG_SIGNERS,
+            signers.len()
+        )));
+    }
+    if threshold == 0 || threshold as usize > signers.len() {
+        return Err(MultisigError::InvalidConfig(format!(
+            "threshold {} out of range 1..={}",
+            threshold,
+            signers.len()
+        )));
+    }
+    let mut keys = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let key = normalize_public_key_hex(signer).map_err(|e| MultisigError::InvalidConfig(e.to_string()))?;
+        if keys.contains(&key) {
+            return Err(MultisigError::InvalidConfig(format!("duplicate signer {}", key)));
+        }
+        keys.push(key);
+    }
+    Ok(keys)
+}
+
+/// Signature by signer `signer` (index into the witness signer set).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigSignature {
+    pub signer: u32,
+    /// Compact (r || s) hex signature of the transaction hash, under the transaction's `sig_scheme`.
+    pub signature: String,
+}
+
+/// Authorization of a multisig account's transaction; see the module docs. Not part of the transaction hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigWitness {
+    pub signers: Vec<String>,
+    pub threshold: u32,
+    /// At most one entry per signer, ordered by signer index.
+    #[serde(default)]
+    pub signatures: Vec<MultisigSignature>,
+}
+
+impl Encode for MultisigSignature {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.signer.encode(out);
+        self.signature.encode(out);
+    }
+}
+
+impl Decode for MultisigSignature {
+    fn decode(reader: &mut Reader<'_>) -> std::result::Result<Self, WireError> {
+        Ok(Self { signer: u32::decode(reader)?, signature: String::decode(reader)? })
+    }
+}
+
+impl Encode for MultisigWitness {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.signers.encode(out);
+        self.threshold.encode(out);
+        self.signatures.encode(out);
+    }
+}
+
+impl Decode for MultisigWitness {
+    fn decode(reader: &mut Reader<'_>) -> std::result::Result<Self, WireError> {
+        Ok(Self {
+            signers: Vec::decode(reader)?,
+            threshold: u32::decode(reader)?,
+            signatures: Vec::decode(reader)?,
+        })
+    }
+}
+
+impl MultisigWitness {
+    /// Empty witness for a checked signer set.
+    pub fn new(signers: Vec<String>, threshold: u32) -> Result<Self> {
+        Ok(Self {
+            signers: check_multisig_config(&signers, threshold)?,
+            threshold,
+            signatures: Vec::new(),
+        })
+    }
+
+    /// Adds (or replaces) `signer`'s signature over `hash`. The signer's key must be in the set.
+    pub fn sign(&mut self, signer: &dyn Signer, hash: &[u8; 32]) -> Result<()> {
+        let key = hex::encode(signer.public_key()?.serialize());
+        let index = self
+            .signers
+            .iter()
+            .position(|s| normalize_public_key_hex(s).is_ok_and(|s| s == key))
+            .ok_or(MultisigError::UnknownSigner(key))? as u32;
+        let (signature, _) = sign_digest_checked(signer, hash)?;
+        self.signatures.retain(|s| s.signer != index);
+        self.signatures.push(MultisigSignature {
+            signer: index,
+            signature: hex::encode(signature.serialize_compact()),
+        });
+        self.signatures.sort_by_key(|s| s.signer);
+        Ok(())
+    }
+
+    /// True if the witness names `signers` (in order, any key format) and `threshold`.
+    pub fn matches(&self, signers: &[String], threshold: u32) -> bool {
+        self.threshold == threshold
+            && self.signers.len() == signers.len()
+            && self
+                .signers
+                .iter()
+                .zip(signers)
+                .all(|(a, b)| crate::core::transaction::same_public_key(a, b))
+    }
+
+    /// Checks the configuration and every signature over `hash`. `Ok(true)` if all signatures verify and at least
+    /// `threshold` distinct signers signed; `Ok(false)` if a signature is wrong or too few signed; `Err` for a
+    /// malformed witness.
+    pub fn verify(&self, scheme: SignatureScheme, hash: &[u8]) -> Result<bool> {
+        let signers = check_multisig_config(&self.signers, self.threshold)?;
+        let mut seen = vec![false; signers.len()];
+        for entry in &self.signatures {
+            let index = entry.signer as usize;
+            match seen.get_mut(index) {
+                Some(true) => {
+                    return Err(MultisigError::InvalidSignature(format!("signer {} signed twice", index)).into())
+                }
+                Some(s) => *s = true,
+                None => return Err(MultisigError::InvalidSignature(format!("no signer {}", index)).into()),
+            }
+            if !verify_signature_hash_with(scheme, hash, &entry.signature, &signers[index])? {
+                return Ok(false);
+            }
+        }
+        Ok(self.signatures.len() >= self.threshold as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signer::SoftwareSigner;
+    use secp256k1::SecretKey;
+
+    fn signer(seed: u8) -> SoftwareSigner {
+        SoftwareSigner::new(SecretKey::from_slice(&[seed; 32]).unwrap())
+    }
+
+    fn key(seed: u8) -> String {
+        format!("Px{}", hex::encode(signer(seed).public_key().unwrap().serialize()))
+    }
+
+    #[test]
+    fn test_config_rules() {
+        assert!(check_multisig_config(&[], 1).is_err());
+        assert!(check_multisig_config(&[key(1)], 0).is_err());
+        assert!(check_multisig_config(&[key(1), key(2)], 3).is_err());
+        assert!(check_multisig_config(&[key(1), key(1).to_uppercase().replace("PX", "")], 1).is_err());
+        assert!(check_multisig_config(&["Pxzz".into()], 1).is_err());
+        let keys = check_multisig_config(&[key(1), key(2)], 2).unwrap();
+        assert!(!keys[0].starts_with("Px"));
+    }
+
+    #[test]
+    fn test_threshold_signatures() {
+        let hash = [9u8; 32];
+        let mut witness = MultisigWitness::new(vec![key(1), key(2), key(3)], 2).unwrap();
+        witness.sign(&signer(3), &hash).unwrap();
+        assert!(!witness.verify(SignatureScheme::Ecdsa, &hash).unwrap());
+        witness.sign(&signer(3), &hash).unwrap();
+        assert_eq!(witness.signatures.len(), 1);
+        witness.sign(&signer(1), &hash).unwrap();
+        assert!(witness.verify(SignatureScheme::Ecdsa, &hash).unwrap());
+        assert!(!witness.verify(SignatureScheme::Ecdsa, &[8u8; 32]).unwrap());
+        assert!(witness.sign(&signer(4), &hash).is_err());
+        assert!(witness.matches(&[key(1), key(2), key(3)], 2));
+        assert!(!witness.matches(&[key(1), key(2), key(3)], 1));
+
+        let mut doubled = witness.clone();
+        doubled.signatures.push(doubled.signatures[0].clone());
+        assert!(doubled.verify(SignatureScheme::Ecdsa, &hash).is_err());
+        let mut forged = witness.clone();
+        forged.signatures[1].signer = 1;
+        assert!(!forged.verify(SignatureScheme::Ecdsa, &hash).unwrap());
+    }
+}
@@ -23,9 +23,44 @@
 //! # Load
 //! `LoadScore = current_tasks × SCORE_SCALE / max_capacity` (capped at SCORE_SCALE).
 //! Higher load reduces effective selection weight.
-
-use std::collections::HashMap;
-use std::sync::RwLock;
+//!
+//! # Capacity auto-tuning
+//! `max_capacity` starts as the operator-supplied value and is then tuned once per epoch from the
+//! processed-task count recorded on-chain (`record_processed_tasks`). `close_capacity_epoch` moves each
+//! capacity towards the observed count by at most `CAPACITY_MAX_STEP_PCT` percent, so a node cannot jump
+//! its capacity (and thus its load-adjusted weight) in a single epoch. Nodes with no recorded work keep
+//! their capacity: an idle epoch is not evidence of low throughput.
+//!
+//! # Large registries
+//! Nodes are kept in a map ordered by `node_id`, so [`NodeRegistry::get_page`] and [`NodeRegistry::pages`]
+//! walk the registry in canonical order without cloning it whole. Selection rounds use
+//! [`NodeRegistry::eligible_summaries`]: a [`NodeSummary`] (id, weight, status) per Active node, built once
+//! and shared until the next mutation clears it.
+//!
+//! # On-chain stake
+//! A node's staking account is the key address of its `public_key` ([`Node::stake_account`]). Its PLP is
+//! locked with `Stake` transactions and tracked in `State::get_stake`; [`NodeRegistry::sync_stakes`] copies
+//! those amounts into `Node::stake`, which selection weights, verifier groups and slashing read. Call it after
+//! each executed block. `slashing::apply_slash_at` removes the slashed amount from the account's on-chain stake
+//! as well as from the registry. Nodes whose key does not parse keep the stake they were registered with.
+//!
+//! # Backups
+//! Until the registry is part of consensus state, operators back it up with [`NodeRegistry::export`] and
+//! restore it with [`NodeRegistry::import`]. The backup is a compact JSON document: `version`,
+//! `node_count`, `integrity` and `nodes` in `node_id` order, every field in a fixed order and stakes as
+//! decimal strings, so the same registry always exports to the same bytes. `integrity` is
+//! SHA256(`"PlatariumNodeRegistry:"` || compact JSON of `nodes`); import rejects a backup whose hash,
+//! count, version or ordering does not check out.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::ops::Bound;
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::address::address_from_public_key;
+use crate::core::ordering;
+use crate::core::state::{Address, State};
 use crate::error::{PlatariumError, Result};
 use thiserror::Error;
 
@@ -38,8 +73,17 @@ pub const WEIGHT_LATENCY: u64 = 200;
 pub const WEIGHT_VOTE_ACCURACY: u64 = 300;
 pub const WEIGHT_STAKE: u64 = 200;
 
+/// Maximum per-epoch change of `max_capacity` during auto-tuning, in percent of the current capacity.
+pub const CAPACITY_MAX_STEP_PCT: u64 = 10;
+
+/// Version of the backup format written by [`NodeRegistry::export`].
+pub const REGISTRY_EXPORT_VERSION: u32 = 1;
+
+const REGISTRY_EXPORT_DOMAIN: &str = "PlatariumNodeRegistry:";
+
 /// Validator node status.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NodeStatus {
     Active,
     Suspended,
@@ -49,10 +93,11 @@ pub enum NodeStatus {
 pub type NodeId = String;
 
 /// A validator node with stake, reputation and load metrics.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Node {
     pub node_id: NodeId,
     pub public_key: String,
+    #[serde(with = "decimal_u128")]
     pub stake: u128,
     /// Cached reputation in 0..=SCORE_SCALE. Updated by `compute_reputation`.
     pub reputation_score: u64,
@@ -68,6 +113,8 @@ pub struct Node {
     pub current_tasks: u64,
     /// Maximum task capacity; `load_score = current_tasks * SCORE_SCALE / max_capacity`.
     pub max_capacity: u64,
+    /// Tasks processed in the current epoch (recorded on-chain). Consumed by `close_capacity_epoch`.
+    pub processed_tasks: u64,
     pub status: NodeStatus,
 }
 
@@ -91,6 +138,7 @@ impl Node {
             total_votes: 0,
             current_tasks: 0,
             max_capacity: if max_capacity == 0 { 1 } else { max_capacity },
+            processed_tasks: 0,
             status: NodeStatus::Active,
         }
     }
@@ -114,6 +162,16 @@ impl Node {
         };
     }
 
+    /// Tunes `max_capacity` towards this epoch's `processed_tasks` (bounded by `max_step_pct`), resets the
+    /// epoch counter, and recomputes `load_score`. No-op on capacity when nothing was processed.
+    pub fn autotune_capacity(&mut self, max_step_pct: u64) {
+        if self.processed_tasks > 0 {
+            self.max_capacity = autotune_capacity(self.max_capacity, self.processed_tasks, max_step_pct);
+        }
+        self.processed_tasks = 0;
+        self.recompute_load_score();
+    }
+
     /// Recomputes reputation from component scores. Requires the maximum stake across all nodes for StakeWeight.
     /// Formula: (Uptime×300 + Latency×200 + VoteAccuracy×300 + StakeWeight×200) / 1000.
     /// StakeWeight = min(SCORE_SCALE, stake × SCORE_SCALE / max_stake).
@@ -132,6 +190,13 @@ impl Node {
         self.reputation_score = sum / 1000;
     }
 
+    /// Account whose on-chain stake backs this node: the key address of `public_key` (hex, optionally `Px`-prefixed).
+    /// `None` if the key does not parse.
+    pub fn stake_account(&self) -> Option<Address> {
+        let key = self.public_key.strip_prefix("Px").unwrap_or(&self.public_key);
+        key.parse::<secp256k1::PublicKey>().ok().map(|pk| address_from_public_key(&pk))
+    }
+
     /// Selection weight for legacy path: reputation × (1 - load). Higher load reduces weight. Value in 0..=SCORE_SCALE.
     pub fn selection_weight(&self) -> u64 {
         let load_penalty = SCORE_SCALE.saturating_sub(self.load_score);
@@ -146,6 +211,46 @@ impl Node {
     }
 }
 
+/// Returns the next capacity given the current capacity and the observed per-epoch task count.
+/// The result moves towards `observed` by at most `current × max_step_pct / 100` (at least 1) and is never 0.
+pub fn autotune_capacity(current: u64, observed: u64, max_step_pct: u64) -> u64 {
+    let current = current.max(1);
+    let step = (current.saturating_mul(max_step_pct) / 100).max(1);
+    let next = if observed > current {
+        current.saturating_add(step.min(observed - current))
+    } else {
+        current - step.min(current - observed)
+    };
+    next.max(1)
+}
+
+/// Compact projection of a node for selection rounds: id, `selection_weight_ratio` (the weight validator
+/// sampling uses) and status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeSummary {
+    pub node_id: NodeId,
+    pub weight: u64,
+    pub status: NodeStatus,
+}
+
+impl From<&Node> for NodeSummary {
+    fn from(n: &Node) -> Self {
+        Self {
+            node_id: n.node_id.clone(),
+            weight: n.selection_weight_ratio(),
+            status: n.status,
+        }
+    }
+}
+
+/// One page of a registry listing in `node_id` order. `next_cursor` is the last id on the page, to be passed
+/// as `after` for the following page; `None` when the listing is exhausted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodePage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<NodeId>,
+}
+
 /// Errors produced by the node registry.
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum NodeRegistryError {
@@ -158,6 +263,15 @@ pub enum NodeRegistryError {
     #[error("Invalid score: must be 0..={0}, got {1}")]
     InvalidScore(u64, u64),
 
+    #[error("Unsupported registry backup version {0} (expected {REGISTRY_EXPORT_VERSION})")]
+    UnsupportedExportVersion(u32),
+
+    #[error("Registry backup integrity hash mismatch")]
+    IntegrityMismatch,
+
+    #[error("Malformed registry backup: {0}")]
+    MalformedExport(String),
+
     #[error("Registry error: {0}")]
     Other(String),
 }
@@ -171,16 +285,26 @@ impl From<NodeRegistryError> for PlatariumError {
 /// Thread-safe node registry and rating engine.
 #[derive(Debug)]
 pub struct NodeRegistry {
-    nodes: RwLock<HashMap<NodeId, Node>>,
+    nodes: RwLock<BTreeMap<NodeId, Node>>,
+    /// Summaries of Active nodes in `node_id` order; `None` after any mutation.
+    eligible_cache: RwLock<Option<Arc<Vec<NodeSummary>>>>,
 }
 
 impl NodeRegistry {
     pub fn new() -> Self {
         Self {
-            nodes: RwLock::new(HashMap::new()),
+            nodes: RwLock::new(BTreeMap::new()),
+            eligible_cache: RwLock::new(None),
         }
     }
 
+    /// Write access to the node map. Every mutation goes through here, which drops the eligible-set cache.
+    fn nodes_mut(&self) -> RwLockWriteGuard<'_, BTreeMap<NodeId, Node>> {
+        let guard = self.nodes.write().unwrap();
+        *self.eligible_cache.write().unwrap() = None;
+        guard
+    }
+
     /// Registers a new node. Errors if `node_id` is already registered.
     pub fn register(
         &self,
@@ -189,7 +313,7 @@ impl NodeRegistry {
         stake: u128,
         max_capacity: u64,
     ) -> Result<()> {
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         if nodes.contains_key(&node_id) {
             return Err(NodeRegistryError::DuplicateNode(node_id).into());
         }
@@ -202,7 +326,7 @@ impl NodeRegistry {
 
     /// Removes a node from the registry.
     pub fn unregister(&self, node_id: &NodeId) -> Result<()> {
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         nodes.remove(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         Ok(())
     }
@@ -213,12 +337,69 @@ impl NodeRegistry {
         nodes.get(node_id).cloned()
     }
 
+    /// Returns the node registered with `public_key`, if any. The `Px` address prefix is ignored on both sides.
+    pub fn get_by_public_key(&self, public_key: &str) -> Option<Node> {
+        let key = public_key.strip_prefix("Px").unwrap_or(public_key);
+        let nodes = self.nodes.read().unwrap();
+        let mut matches: Vec<&Node> = nodes
+            .values()
+            .filter(|n| n.public_key.strip_prefix("Px").unwrap_or(&n.public_key).eq_ignore_ascii_case(key))
+            .collect();
+        matches.sort_by(ordering::by_node_id);
+        matches.first().map(|n| (*n).clone())
+    }
+
     /// Returns all registered nodes, sorted by `node_id` for deterministic ordering.
     pub fn get_all(&self) -> Vec<Node> {
         let nodes = self.nodes.read().unwrap();
-        let mut v: Vec<Node> = nodes.values().cloned().collect();
-        v.sort_by(|a, b| a.node_id.cmp(&b.node_id));
-        v
+        nodes.values().cloned().collect()
+    }
+
+    /// Up to `limit` nodes with `node_id` greater than `after` (from the start when `None`), in `node_id` order.
+    /// Clones only the nodes on the page.
+    pub fn get_page(&self, after: Option<&NodeId>, limit: usize) -> NodePage<Node> {
+        let nodes = self.nodes.read().unwrap();
+        let lower = after.map_or(Bound::Unbounded, Bound::Excluded);
+        let mut range = nodes.range::<NodeId, _>((lower, Bound::Unbounded));
+        let items: Vec<Node> = range.by_ref().take(limit).map(|(_, n)| n.clone()).collect();
+        let next_cursor = match (items.last(), range.next()) {
+            (Some(last), Some(_)) => Some(last.node_id.clone()),
+            _ => None,
+        };
+        NodePage { items, next_cursor }
+    }
+
+    /// Streams the registry in pages of `page_size` nodes. Each page takes the read lock only while it is
+    /// copied, so concurrent mutations between pages are visible to later pages.
+    pub fn pages(&self, page_size: usize) -> NodePages<'_> {
+        NodePages {
+            registry: self,
+            page_size: page_size.max(1),
+            cursor: None,
+            done: false,
+        }
+    }
+
+    /// Summaries of all Active nodes in `node_id` order. Built on first use and shared until the registry
+    /// changes; selection rounds can call this repeatedly without cloning `Node`s.
+    pub fn eligible_summaries(&self) -> Arc<Vec<NodeSummary>> {
+        if let Some(cached) = self.eligible_cache.read().unwrap().as_ref() {
+            return Arc::clone(cached);
+        }
+        let nodes = self.nodes.read().unwrap();
+        let mut cache = self.eligible_cache.write().unwrap();
+        if let Some(cached) = cache.as_ref() {
+            return Arc::clone(cached);
+        }
+        let summaries: Arc<Vec<NodeSummary>> = Arc::new(
+            nodes
+                .values()
+                .filter(|n| n.status == NodeStatus::Active)
+                .map(NodeSummary::from)
+                .collect(),
+        );
+        *cache = Some(Arc::clone(&summaries));
+        summaries
     }
 
     /// Returns the maximum stake among all nodes, or 0 if the registry is empty.
@@ -230,7 +411,7 @@ impl NodeRegistry {
     /// Recomputes reputation for every node (e.g. after stake or score updates).
     pub fn recompute_all_reputations(&self) {
         let max_stake = self.max_stake();
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         for node in nodes.values_mut() {
             node.compute_reputation(max_stake);
         }
@@ -242,7 +423,7 @@ impl NodeRegistry {
             return Err(NodeRegistryError::InvalidScore(SCORE_SCALE, score).into());
         }
         let max_stake = self.max_stake();
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.uptime_score = score;
         node.compute_reputation(max_stake);
@@ -255,7 +436,7 @@ impl NodeRegistry {
             return Err(NodeRegistryError::InvalidScore(SCORE_SCALE, score).into());
         }
         let max_stake = self.max_stake();
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.latency_score = score;
         node.compute_reputation(max_stake);
@@ -278,7 +459,7 @@ impl NodeRegistry {
             return Err(NodeRegistryError::InvalidScore(SCORE_SCALE, latency_score).into());
         }
         let max_stake = self.max_stake();
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.uptime_score = uptime_score;
         node.latency_score = latency_score;
@@ -302,7 +483,7 @@ impl NodeRegistry {
             .into());
         }
         let max_stake = self.max_stake();
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.missed_votes = missed_votes;
         node.total_votes = total_votes;
@@ -313,7 +494,7 @@ impl NodeRegistry {
     /// Records one vote for a node. Set `missed` to true if the node did not participate.
     pub fn record_vote(&self, node_id: &NodeId, missed: bool) -> Result<()> {
         let max_stake = self.max_stake();
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.total_votes = node.total_votes.saturating_add(1);
         if missed {
@@ -326,7 +507,7 @@ impl NodeRegistry {
     /// Updates current task count and max capacity for a node, then recomputes load score and reputation.
     pub fn set_load(&self, node_id: &NodeId, current_tasks: u64, max_capacity: u64) -> Result<()> {
         let max_stake = self.max_stake();
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.current_tasks = current_tasks;
         node.max_capacity = max_capacity.max(1);
@@ -335,9 +516,28 @@ impl NodeRegistry {
         Ok(())
     }
 
+    /// Adds `count` to the node's processed-task counter for the current epoch (from on-chain records).
+    pub fn record_processed_tasks(&self, node_id: &NodeId, count: u64) -> Result<()> {
+        let mut nodes = self.nodes_mut();
+        let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
+        node.processed_tasks = node.processed_tasks.saturating_add(count);
+        Ok(())
+    }
+
+    /// Epoch close: auto-tunes every node's `max_capacity` from its processed-task count (step bounded by
+    /// `CAPACITY_MAX_STEP_PCT`), resets the counters, and recomputes load and reputation. Deterministic.
+    pub fn close_capacity_epoch(&self) {
+        let max_stake = self.max_stake();
+        let mut nodes = self.nodes_mut();
+        for node in nodes.values_mut() {
+            node.autotune_capacity(CAPACITY_MAX_STEP_PCT);
+            node.compute_reputation(max_stake);
+        }
+    }
+
     /// Updates a node’s stake and recomputes reputation for all nodes (StakeWeight depends on global max stake).
     pub fn set_stake(&self, node_id: &NodeId, stake: u128) -> Result<()> {
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.stake = stake;
         let max_stake = nodes.values().map(|n| n.stake).max().unwrap_or(0);
@@ -347,9 +547,24 @@ impl NodeRegistry {
         Ok(())
     }
 
+    /// Sets every node's stake to its staking account's bonded stake in `state` (see the module docs) and recomputes
+    /// reputations. Unbonding stake does not count. Nodes without a parseable key are left unchanged.
+    pub fn sync_stakes(&self, state: &State) {
+        let mut nodes = self.nodes_mut();
+        for node in nodes.values_mut() {
+            if let Some(account) = node.stake_account() {
+                node.stake = state.get_stake(&account);
+            }
+        }
+        let max_stake = nodes.values().map(|n| n.stake).max().unwrap_or(0);
+        for n in nodes.values_mut() {
+            n.compute_reputation(max_stake);
+        }
+    }
+
     /// Sets the node’s status to Active or Suspended.
     pub fn set_status(&self, node_id: &NodeId, status: NodeStatus) -> Result<()> {
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.status = status;
         Ok(())
@@ -357,7 +572,7 @@ impl NodeRegistry {
 
     /// Applies a reputation penalty by subtracting `amount` from the node’s score. Sets status to Suspended if score falls below `suspension_threshold`.
     pub fn apply_reputation_penalty(&self, node_id: &NodeId, amount: u64, suspension_threshold: u64) -> Result<()> {
-        let mut nodes = self.nodes.write().unwrap();
+        let mut nodes = self.nodes_mut();
         let node = nodes.get_mut(node_id).ok_or_else(|| NodeRegistryError::NodeNotFound(node_id.clone()))?;
         node.reputation_score = node.reputation_score.saturating_sub(amount);
         if node.reputation_score < suspension_threshold {
@@ -369,13 +584,11 @@ impl NodeRegistry {
     /// Returns all nodes with status Active, sorted by `node_id` for deterministic ordering.
     pub fn get_eligible(&self) -> Vec<Node> {
         let nodes = self.nodes.read().unwrap();
-        let mut v: Vec<Node> = nodes
+        nodes
             .values()
             .filter(|n| n.status == NodeStatus::Active)
             .cloned()
-            .collect();
-        v.sort_by(|a, b| a.node_id.cmp(&b.node_id));
-        v
+            .collect()
     }
 
     /// Returns eligible nodes together with their selection weight (reputation reduced by load). Sorted by `node_id`.
@@ -388,7 +601,7 @@ impl NodeRegistry {
                 (n, w)
             })
             .collect();
-        v.sort_by(|a, b| a.0.node_id.cmp(&b.0.node_id));
+        v.sort_by(ordering::by_node_id);
         v
     }
 
@@ -403,12 +616,126 @@ impl NodeRegistry {
     }
 }
 
+/// On-disk form of a registry backup; field order is part of the format.
+#[derive(Serialize, Deserialize)]
+struct RegistryExport {
+    version: u32,
+    node_count: usize,
+    integrity: String,
+    nodes: Vec<Node>,
+}
+
+fn export_integrity(nodes: &[Node]) -> Result<String> {
+    let json = serde_json::to_vec(nodes)
+        .map_err(|e| NodeRegistryError::MalformedExport(e.to_string()))?;
+    let mut hasher = Sha256::new();
+    hasher.update(REGISTRY_EXPORT_DOMAIN.as_bytes());
+    hasher.update(&json);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+impl NodeRegistry {
+    /// Writes a backup of every node (see the module docs for the format) and returns its integrity hash.
+    pub fn export<W: Write>(&self, mut writer: W) -> Result<String> {
+        let nodes = self.get_all();
+        let integrity = export_integrity(&nodes)?;
+        let doc = RegistryExport {
+            version: REGISTRY_EXPORT_VERSION,
+            node_count: nodes.len(),
+            integrity: integrity.clone(),
+            nodes,
+        };
+        serde_json::to_writer(&mut writer, &doc)
+            .map_err(|e| PlatariumError::State(format!("NodeRegistry: backup write failed: {}", e)))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| PlatariumError::State(format!("NodeRegistry: backup write failed: {}", e)))?;
+        Ok(integrity)
+    }
+
+    /// Rebuilds a registry from a backup written by [`NodeRegistry::export`]. Nodes are restored exactly,
+    /// including cached reputation and load scores; nothing is recomputed.
+    pub fn import<R: Read>(reader: R) -> Result<Self> {
+        let doc: RegistryExport = serde_json::from_reader(reader)
+            .map_err(|e| NodeRegistryError::MalformedExport(e.to_string()))?;
+        if doc.version != REGISTRY_EXPORT_VERSION {
+            return Err(NodeRegistryError::UnsupportedExportVersion(doc.version).into());
+        }
+        if doc.node_count != doc.nodes.len() {
+            return Err(NodeRegistryError::MalformedExport(format!(
+                "node_count {} but {} nodes",
+                doc.node_count,
+                doc.nodes.len()
+            ))
+            .into());
+        }
+        if export_integrity(&doc.nodes)? != doc.integrity.to_ascii_lowercase() {
+            return Err(NodeRegistryError::IntegrityMismatch.into());
+        }
+        if let Some(pair) = doc.nodes.windows(2).find(|w| w[0].node_id >= w[1].node_id) {
+            return Err(NodeRegistryError::MalformedExport(format!(
+                "nodes not in strict node_id order at {}",
+                pair[1].node_id
+            ))
+            .into());
+        }
+        for n in &doc.nodes {
+            for score in [n.reputation_score, n.uptime_score, n.latency_score, n.load_score] {
+                if score > SCORE_SCALE {
+                    return Err(NodeRegistryError::InvalidScore(SCORE_SCALE, score).into());
+                }
+            }
+            if n.max_capacity == 0 {
+                return Err(NodeRegistryError::MalformedExport(format!("zero max_capacity for {}", n.node_id)).into());
+            }
+        }
+        let registry = Self::new();
+        *registry.nodes_mut() = doc.nodes.into_iter().map(|n| (n.node_id.clone(), n)).collect();
+        Ok(registry)
+    }
+}
+
+/// Stake as a decimal string, so backups survive JSON tooling limited to 64-bit numbers.
+mod decimal_u128 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<u128, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl Default for NodeRegistry {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Iterator over registry pages returned by [`NodeRegistry::pages`].
+pub struct NodePages<'a> {
+    registry: &'a NodeRegistry,
+    page_size: usize,
+    cursor: Option<NodeId>,
+    done: bool,
+}
+
+impl Iterator for NodePages<'_> {
+    type Item = Vec<Node>;
+
+    fn next(&mut self) -> Option<Vec<Node>> {
+        if self.done {
+            return None;
+        }
+        let page = self.registry.get_page(self.cursor.as_ref(), self.page_size);
+        self.done = page.next_cursor.is_none();
+        self.cursor = page.next_cursor;
+        (!page.items.is_empty()).then_some(page.items)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -459,6 +786,33 @@ mod tests {
         assert_eq!(n.selection_weight(), SCORE_SCALE / 2);
     }
 
+    #[test]
+    fn test_autotune_capacity_bounded_step() {
+        assert_eq!(autotune_capacity(100, 500, CAPACITY_MAX_STEP_PCT), 110);
+        assert_eq!(autotune_capacity(100, 105, CAPACITY_MAX_STEP_PCT), 105);
+        assert_eq!(autotune_capacity(100, 1, CAPACITY_MAX_STEP_PCT), 90);
+        assert_eq!(autotune_capacity(5, 50, CAPACITY_MAX_STEP_PCT), 6);
+        assert_eq!(autotune_capacity(1, 0, CAPACITY_MAX_STEP_PCT), 1);
+    }
+
+    #[test]
+    fn test_close_capacity_epoch() {
+        let reg = NodeRegistry::new();
+        reg.register("busy".into(), "pk1".into(), 1000, 100).unwrap();
+        reg.register("idle".into(), "pk2".into(), 1000, 100).unwrap();
+        reg.register("slow".into(), "pk3".into(), 1000, 100).unwrap();
+        reg.record_processed_tasks(&"busy".into(), 400).unwrap();
+        reg.record_processed_tasks(&"slow".into(), 20).unwrap();
+        reg.set_load(&"slow".into(), 45, 100).unwrap();
+        reg.close_capacity_epoch();
+        assert_eq!(reg.get(&"busy".into()).unwrap().max_capacity, 110);
+        assert_eq!(reg.get(&"idle".into()).unwrap().max_capacity, 100);
+        let slow = reg.get(&"slow".into()).unwrap();
+        assert_eq!(slow.max_capacity, 90);
+        assert_eq!(slow.processed_tasks, 0);
+        assert_eq!(slow.load_score, SCORE_SCALE / 2);
+    }
+
     #[test]
     fn test_registry_register_get() {
         let reg = NodeRegistry::new();
@@ -551,4 +905,64 @@ mod tests {
         let r = reg.set_vote_stats(&"n1".into(), 11, 10);
         assert!(r.is_err());
     }
+
+    #[test]
+    fn test_pages_cover_registry_in_order() {
+        let reg = NodeRegistry::new();
+        for i in (0..25).rev() {
+            reg.register(format!("n{:02}", i), format!("pk{}", i), 100, 10).unwrap();
+        }
+        let first = reg.get_page(None, 10);
+        assert_eq!(first.items.len(), 10);
+        assert_eq!(first.next_cursor.as_deref(), Some("n09"));
+        let last = reg.get_page(Some(&"n19".to_string()), 10);
+        assert_eq!(last.items.len(), 5);
+        assert_eq!(last.next_cursor, None);
+
+        let streamed: Vec<NodeId> = reg.pages(7).flatten().map(|n| n.node_id).collect();
+        let all: Vec<NodeId> = reg.get_all().into_iter().map(|n| n.node_id).collect();
+        assert_eq!(streamed, all);
+        assert_eq!(reg.pages(25).count(), 1);
+        assert_eq!(NodeRegistry::new().pages(5).count(), 0);
+    }
+
+    #[test]
+    fn test_eligible_summaries_cached_until_mutation() {
+        let reg = NodeRegistry::new();
+        reg.register("a".to_string(), "pka".to_string(), 100, 10).unwrap();
+        reg.register("b".to_string(), "pkb".to_string(), 100, 10).unwrap();
+        let first = reg.eligible_summaries();
+        assert!(Arc::ptr_eq(&first, &reg.eligible_summaries()));
+        assert_eq!(first[0].weight, reg.get(&"a".to_string()).unwrap().selection_weight_ratio());
+
+        reg.set_status(&"a".to_string(), NodeStatus::Suspended).unwrap();
+        let after = reg.eligible_summaries();
+        assert!(!Arc::ptr_eq(&first, &after));
+        let ids: Vec<&str> = after.iter().map(|n| n.node_id.as_str()).collect();
+        assert_eq!(ids, vec!["b"]);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let registry = NodeRegistry::new();
+        registry.register("n2".into(), "pk2".into(), 10u128.pow(30), 10).unwrap();
+        registry.register("n1".into(), "pk1".into(), 500, 4).unwrap();
+        registry.set_load(&"n1".into(), 2, 4).unwrap();
+        registry.set_status(&"n2".into(), NodeStatus::Suspended).unwrap();
+
+        let mut backup = Vec::new();
+        let integrity = registry.export(&mut backup).unwrap();
+        let restored = NodeRegistry::import(backup.as_slice()).unwrap();
+        assert_eq!(restored.get_all(), registry.get_all());
+        let mut again = Vec::new();
+        assert_eq!(restored.export(&mut again).unwrap(), integrity);
+        assert_eq!(again, backup);
+
+        let text = String::from_utf8(backup).unwrap();
+        let tampered = text.replace("\"stake\":\"500\"", "\"stake\":\"501\"");
+        assert_ne!(tampered, text);
+        assert!(NodeRegistry::import(tampered.as_bytes()).is_err());
+        let future = text.replacen("\"version\":1", "\"version\":2", 1);
+        assert!(NodeRegistry::import(future.as_bytes()).is_err());
+    }
 }
@@ -0,0 +1,210 @@
+//! Deterministic tie-break comparators shared by every consensus-relevant sort.
+//!
+//! Any ordering that feeds a block (mempool batches, proposal snapshots), a hash (registry
+//! listings), or a selection (validator sampling) must be a **total order** over the items being
+//! sorted, so two honest nodes holding the same set produce the same sequence. Each comparator here
+//! ends on a unique key (transaction hash or node id), which makes it total as long as that key is
+//! unique within the set — true for mempools (keyed by hash) and registries (keyed by node id).
+//!
+//! | Comparator | Primary key | Tie-break | Used by |
+//! |---|---|---|---|
+//! | [`by_arrival_then_hash`] | `arrival_index` ascending | hash ascending | mempool batch, proposal snapshots |
+//! | [`by_node_id`] | `node_id` ascending | — (unique) | node registry, validator selection |
+//! | [`by_fee_then_hash`] | fee descending | hash ascending | fee-priority listings |
+//!
+//! Changing any of these is a consensus change: nodes running different comparators build
+//! different blocks from the same mempool.
+
+use std::cmp::Ordering;
+use crate::core::block_proposal::MempoolSnapshotEntry;
+use crate::core::mempool::MempoolEntry;
+use crate::core::node_registry::Node;
+use crate::core::transaction::Transaction;
+
+/// Items ordered by arrival in the mempool, with the transaction hash as tie-break.
+pub trait ArrivalKey {
+    fn arrival_index(&self) -> u64;
+    fn tx_hash(&self) -> &str;
+}
+
+/// Items ordered by node id.
+pub trait NodeKey {
+    fn node_id(&self) -> &str;
+}
+
+/// Items ordered by fee, with the transaction hash as tie-break.
+pub trait FeeKey {
+    fn fee_uplp(&self) -> u128;
+    fn tx_hash(&self) -> &str;
+}
+
+/// `(arrival_index, hash)` ascending. Earlier arrivals first; equal indices fall back to the hash.
+pub fn by_arrival_then_hash<T: ArrivalKey>(a: &T, b: &T) -> Ordering {
+    a.arrival_index()
+        .cmp(&b.arrival_index())
+        .then_with(|| a.tx_hash().cmp(b.tx_hash()))
+}
+
+/// `node_id` ascending (byte-wise string order).
+pub fn by_node_id<T: NodeKey>(a: &T, b: &T) -> Ordering {
+    a.node_id().cmp(b.node_id())
+}
+
+/// Fee descending, then hash ascending. Higher-paying transactions first.
+pub fn by_fee_then_hash<T: FeeKey>(a: &T, b: &T) -> Ordering {
+    b.fee_uplp()
+        .cmp(&a.fee_uplp())
+        .then_with(|| a.tx_hash().cmp(b.tx_hash()))
+}
+
+impl ArrivalKey for MempoolEntry {
+    fn arrival_index(&self) -> u64 {
+        self.arrival_index
+    }
+    fn tx_hash(&self) -> &str {
+        &self.tx.hash
+    }
+}
+
+impl ArrivalKey for MempoolSnapshotEntry {
+    fn arrival_index(&self) -> u64 {
+        self.arrival_index
+    }
+    fn tx_hash(&self) -> &str {
+        &self.tx.hash
+    }
+}
+
+impl NodeKey for Node {
+    fn node_id(&self) -> &str {
+        &self.node_id
+    }
+}
+
+impl NodeKey for str {
+    fn node_id(&self) -> &str {
+        self
+    }
+}
+
+impl NodeKey for String {
+    fn node_id(&self) -> &str {
+        self
+    }
+}
+
+impl<T: NodeKey + ?Sized> NodeKey for &T {
+    fn node_id(&self) -> &str {
+        (**self).node_id()
+    }
+}
+
+impl<T: NodeKey, U> NodeKey for (T, U) {
+    fn node_id(&self) -> &str {
+        self.0.node_id()
+    }
+}
+
+impl FeeKey for Transaction {
+    fn fee_uplp(&self) -> u128 {
+        self.fee_uplp
+    }
+    fn tx_hash(&self) -> &str {
+        &self.hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Arr(u64, String);
+    impl ArrivalKey for Arr {
+        fn arrival_index(&self) -> u64 {
+            self.0
+        }
+        fn tx_hash(&self) -> &str {
+            &self.1
+        }
+    }
+
+    struct Fee(u128, String);
+    impl FeeKey for Fee {
+        fn fee_uplp(&self) -> u128 {
+            self.0
+        }
+        fn tx_hash(&self) -> &str {
+            &self.1
+        }
+    }
+
+    /// Small deterministic generator so the property checks need no extra dependency.
+    fn lcg(seed: &mut u64) -> u64 {
+        *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        *seed >> 33
+    }
+
+    /// Checks antisymmetry, transitivity and that `Equal` only holds for identical keys.
+    fn assert_total<T, K: PartialEq>(items: &[T], cmp: fn(&T, &T) -> Ordering, key: impl Fn(&T) -> K) {
+        for a in items {
+            for b in items {
+                let ab = cmp(a, b);
+                assert_eq!(ab, cmp(b, a).reverse());
+                assert_eq!(ab == Ordering::Equal, key(a) == key(b));
+                for c in items {
+                    if ab != Ordering::Greater && cmp(b, c) != Ordering::Greater {
+                        assert_ne!(cmp(a, c), Ordering::Greater);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn arrival_then_hash_is_total() {
+        let mut seed = 7;
+        let items: Vec<Arr> = (0..40)
+            .map(|_| Arr(lcg(&mut seed) % 5, format!("h{}", lcg(&mut seed) % 7)))
+            .collect();
+        assert_total(&items, by_arrival_then_hash, |x| (x.0, x.1.clone()));
+    }
+
+    #[test]
+    fn fee_then_hash_is_total() {
+        let mut seed = 11;
+        let items: Vec<Fee> = (0..40)
+            .map(|_| Fee((lcg(&mut seed) % 4) as u128, format!("h{}", lcg(&mut seed) % 7)))
+            .collect();
+        assert_total(&items, by_fee_then_hash, |x| (x.0, x.1.clone()));
+    }
+
+    #[test]
+    fn node_id_is_total() {
+        let mut seed = 3;
+        let items: Vec<String> = (0..40).map(|_| format!("n{}", lcg(&mut seed) % 9)).collect();
+        assert_total(&items, by_node_id, |x| x.clone());
+    }
+
+    #[test]
+    fn sort_result_independent_of_input_order() {
+        let mut seed = 42;
+        let base: Vec<(u64, String)> = (0..30)
+            .map(|i| (lcg(&mut seed) % 3, format!("h{:02}", i)))
+            .collect();
+        let mut forward: Vec<Arr> = base.iter().map(|(a, h)| Arr(*a, h.clone())).collect();
+        let mut backward: Vec<Arr> = base.iter().rev().map(|(a, h)| Arr(*a, h.clone())).collect();
+        forward.sort_by(by_arrival_then_hash);
+        backward.sort_by(by_arrival_then_hash);
+        let f: Vec<&str> = forward.iter().map(|x| x.1.as_str()).collect();
+        let b: Vec<&str> = backward.iter().map(|x| x.1.as_str()).collect();
+        assert_eq!(f, b);
+    }
+
+    #[test]
+    fn fee_orders_highest_first() {
+        let mut v = [Fee(1, "b".into()), Fee(5, "z".into()), Fee(5, "a".into())];
+        v.sort_by(by_fee_then_hash);
+        let got: Vec<(u128, &str)> = v.iter().map(|f| (f.0, f.1.as_str())).collect();
+        assert_eq!(got, vec![(5, "a"), (5, "z"), (1, "b")]);
+    }
+}
@@ -0,0 +1,217 @@
+//! Outbound message queue for external bridges.
+//!
+//! A `SendMessage` transaction, or a governance decision approved by the validators
+//! (`governance::emit_governance_message`), emits `Event::MessageSent` with an opaque hex payload for a named
+//! destination (e.g. a bridge contract on another chain). The block producer feeds the block's events into an
+//! [`OutboundQueue`] and seals it at the block height, which numbers the messages in emission order. The block
+//! header then commits to them with `Block::with_messages`: `messages_root` is the Merkle root of their leaves
+//! (`platarium_consensus::message_leaf`), and it is part of the block hash. Blocks without messages have no root,
+//! and their hash is unchanged.
+//!
+//! Storage keeps each block's messages. A bridge fetches a [`MessageProof`] (RPC `rocks_message_proof`) and
+//! checks it against the `messages_root` of a block header it has already verified, so it does not have to
+//! trust the node that served the proof.
+
+use serde::{Deserialize, Serialize};
+use crate::core::events::Event;
+use crate::core::state::Address;
+use crate::error::{PlatariumError, Result};
+
+/// Largest message payload, in bytes (the hex string is twice as long).
+pub const MAX_MESSAGE_PAYLOAD_BYTES: usize = 4096;
+
+/// Longest destination name, in bytes.
+pub const MAX_DESTINATION_LEN: usize = 64;
+
+/// Sender recorded for messages emitted by governance rather than by an account.
+pub const GOVERNANCE_SENDER: &str = "governance";
+
+/// Checks a destination and hex payload and returns the payload bytes. Destinations are 1 to
+/// [`MAX_DESTINATION_LEN`] ASCII letters, digits, `-`, `_`, `.` or `:`.
+pub fn check_message(destination: &str, payload_hex: &str) -> std::result::Result<Vec<u8>, String> {
+    if destination.is_empty() || destination.len() > MAX_DESTINATION_LEN {
+        return Err(format!("destination must be 1 to {} bytes", MAX_DESTINATION_LEN));
+    }
+    if !destination.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_.:".contains(&b)) {
+        return Err(format!("invalid destination {:?}", destination));
+    }
+    let payload = hex::decode(payload_hex).map_err(|e| format!("payload is not hex: {}", e))?;
+    if payload.len() > MAX_MESSAGE_PAYLOAD_BYTES {
+        return Err(format!(
+            "payload is {} bytes, limit is {}",
+            payload.len(),
+            MAX_MESSAGE_PAYLOAD_BYTES
+        ));
+    }
+    Ok(payload)
+}
+
+/// Message `index` of block `block_height`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboundMessage {
+    pub block_height: u64,
+    pub index: u32,
+    pub sender: Address,
+    pub destination: String,
+    /// Payload as lowercase hex.
+    pub payload: String,
+}
+
+impl OutboundMessage {
+    /// Merkle leaf committed in the block's `messages_root`.
+    pub fn leaf(&self) -> [u8; 32] {
+        let payload = hex::decode(&self.payload).unwrap_or_default();
+        platarium_consensus::message_leaf(self.block_height, self.index, &self.sender, &self.destination, &payload)
+    }
+}
+
+/// A message with the sibling hashes linking its leaf to the block's `messages_root`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageProof {
+    pub message: OutboundMessage,
+    /// Hex sibling hashes, leaf level first.
+    pub siblings: Vec<String>,
+}
+
+impl MessageProof {
+    /// True if the message is committed under `messages_root` (hex, from a verified block header).
+    pub fn verify(&self, messages_root: &str) -> bool {
+        let Some(root) = decode_hash(messages_root) else {
+            return false;
+        };
+        let Some(siblings) = self.siblings.iter().map(|s| decode_hash(s)).collect::<Option<Vec<_>>>() else {
+            return false;
+        };
+        platarium_consensus::verify_merkle_proof(self.message.leaf(), &siblings, root)
+    }
+}
+
+/// Messages emitted while a block is being executed, in emission order.
+#[derive(Debug, Clone, Default)]
+pub struct OutboundQueue {
+    pending: Vec<(Address, String, String)>,
+}
+
+impl OutboundQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a message after checking it with [`check_message`].
+    pub fn push(&mut self, sender: Address, destination: String, payload_hex: &str) -> Result<()> {
+        let payload = check_message(&destination, payload_hex)
+            .map_err(|e| PlatariumError::Validation(format!("Invalid message: {}", e)))?;
+        self.pending.push((sender, destination, hex::encode(payload)));
+        Ok(())
+    }
+
+    /// Queues every `MessageSent` in `events` (e.g. `State::take_events` after executing the block's transactions).
+    pub fn push_events(&mut self, events: &[Event]) -> Result<()> {
+        for event in events {
+            if let Event::MessageSent { sender, destination, payload } = event {
+                self.push(sender.clone(), destination.clone(), payload)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Drains the queue into the messages of block `block_height`, numbered from 0.
+    pub fn seal(&mut self, block_height: u64) -> Vec<OutboundMessage> {
+        self.pending
+            .drain(..)
+            .enumerate()
+            .map(|(index, (sender, destination, payload))| OutboundMessage {
+                block_height,
+                index: index as u32,
+                sender,
+                destination,
+                payload,
+            })
+            .collect()
+    }
+}
+
+/// Hex Merkle root of `messages`, or `None` for a block without messages.
+pub fn messages_root(messages: &[OutboundMessage]) -> Option<String> {
+    platarium_consensus::merkle_root(messages.iter().map(OutboundMessage::leaf).collect()).map(hex::encode)
+}
+
+/// Proof for message `index` among a block's `messages`.
+pub fn message_proof(messages: &[OutboundMessage], index: u32) -> Option<MessageProof> {
+    let message = messages.iter().find(|m| m.index == index)?;
+    let leaves = messages.iter().map(OutboundMessage::leaf).collect();
+    let siblings = platarium_consensus::merkle_proof(leaves, message.leaf())?;
+    Some(MessageProof {
+        message: message.clone(),
+        siblings: siblings.iter().map(hex::encode).collect(),
+    })
+}
+
+/// Checks that `messages` are numbered `0..n` for `block_height` and carry valid destinations and payloads.
+pub fn check_block_messages(block_height: u64, messages: &[OutboundMessage]) -> Result<()> {
+    for (i, m) in messages.iter().enumerate() {
+        if m.block_height != block_height || m.index as usize != i {
+            return Err(PlatariumError::State(format!(
+                "outbound message {} is numbered ({}, {}), expected ({}, {})",
+                i, m.block_height, m.index, block_height, i
+            )));
+        }
+        check_message(&m.destination, &m.payload)
+            .map_err(|e| PlatariumError::Validation(format!("Invalid message: {}", e)))?;
+    }
+    Ok(())
+}
+
+fn decode_hash(s: &str) -> Option<[u8; 32]> {
+    hex::decode(s).ok()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_prove() {
+        let mut queue = OutboundQueue::new();
+        queue
+            .push_events(&[
+                Event::MessageSent {
+                    sender: "PxA".into(),
+                    destination: "eth:bridge".into(),
+                    payload: "00ff".into(),
+                },
+                Event::RecoveryCancelled { address: "PxA".into() },
+            ])
+            .unwrap();
+        queue.push(GOVERNANCE_SENDER.into(), "eth:bridge".into(), "").unwrap();
+        queue.push("PxB".into(), "eth:bridge".into(), "AB").unwrap();
+        assert!(queue.push("PxB".into(), "bad dest".into(), "").is_err());
+        assert!(queue.push("PxB".into(), "eth".into(), &"00".repeat(MAX_MESSAGE_PAYLOAD_BYTES + 1)).is_err());
+        assert_eq!(queue.len(), 3);
+
+        let messages = queue.seal(12);
+        assert!(queue.is_empty());
+        assert_eq!(messages[2].payload, "ab");
+        check_block_messages(12, &messages).unwrap();
+        assert!(check_block_messages(13, &messages).is_err());
+
+        let root = messages_root(&messages).unwrap();
+        for m in &messages {
+            let proof = message_proof(&messages, m.index).unwrap();
+            assert!(proof.verify(&root));
+            let mut forged = proof.clone();
+            forged.message.payload = "01".into();
+            assert!(!forged.verify(&root));
+        }
+        assert!(message_proof(&messages, 3).is_none());
+        assert_eq!(messages_root(&[]), None);
+    }
+}
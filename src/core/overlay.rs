@@ -0,0 +1,428 @@
+//! Copy-on-write overlay over a state snapshot, for speculative execution.
+//!
+//! An [`OverlayState`] keeps the accounts written by the transactions applied to it in small write layers over an
+//! immutable [`StateSnapshot`]; reads fall through to the snapshot. Applying a transaction costs memory in the
+//! accounts it touches, not in the size of the state, so a block builder can try many candidate orderings of the
+//! same mempool over one snapshot and keep the best. The result is either committed to the live [`State`]
+//! ([`OverlayState::commit_into`]) or dropped ([`OverlayState::discard`]).
+//!
+//! Plain transfers run on the layers through the same balance rules as `State::apply_transfer`. Any other kind,
+//! a multisig sender or witness, or a sender with a pending key rotation, is applied to a temporary `State` built from the overlay's current view,
+//! which then becomes the new base: correct for every kind, but O(state) for that transaction.
+//!
+//! Applying is all-or-nothing per transaction: a rejected transaction leaves the overlay unchanged.
+
+use std::collections::HashMap;
+use std::thread;
+use crate::core::asset::Asset;
+use crate::core::events::Event;
+use crate::core::state::{
+    check_addresses, check_installed_main_key, check_registered_derived_key, transfer_effects, Address, Ledger, State,
+    StateError, StateSnapshot,
+};
+use crate::core::transaction::{Transaction, TxKind};
+use crate::error::{PlatariumError, Result};
+
+/// Uncommitted writes layered over a snapshot; see the module docs.
+#[derive(Debug, Clone)]
+pub struct OverlayState {
+    /// Snapshot the overlay was created on. `commit_into` requires the target state to still be at it.
+    origin: StateSnapshot,
+    /// Snapshot reads fall through to. Equal to `origin` until a transaction outside the fast path is folded in.
+    base: StateSnapshot,
+    asset_balances: HashMap<(Address, String), u128>,
+    uplp_balances: HashMap<Address, u128>,
+    nonces: HashMap<Address, u64>,
+    derived_keys: HashMap<Address, String>,
+    events: Vec<Event>,
+    rebased: bool,
+}
+
+impl OverlayState {
+    /// Empty overlay over `base`. O(1).
+    pub fn new(base: StateSnapshot) -> Self {
+        Self {
+            origin: base.clone(),
+            base,
+            asset_balances: HashMap::new(),
+            uplp_balances: HashMap::new(),
+            nonces: HashMap::new(),
+            derived_keys: HashMap::new(),
+            events: Vec::new(),
+            rebased: false,
+        }
+    }
+
+    /// Block height of the underlying snapshot.
+    pub fn block_height(&self) -> u64 {
+        self.base.block_height()
+    }
+
+    pub fn get_asset_balance(&self, address: &Address, asset: &Asset) -> u128 {
+        self.asset_balance(address, &asset.as_canonical())
+    }
+
+    pub fn get_balance(&self, address: &Address) -> u128 {
+        self.get_asset_balance(address, &Asset::PLP)
+    }
+
+    pub fn get_uplp_balance(&self, address: &Address) -> u128 {
+        self.uplp_balance(address)
+    }
+
+    pub fn get_nonce(&self, address: &Address) -> u64 {
+        self.nonce(address)
+    }
+
+    pub fn registered_derived_key(&self, address: &Address) -> Option<String> {
+        self.derived_keys
+            .get(address)
+            .cloned()
+            .or_else(|| self.base.registered_derived_key(address))
+    }
+
+    /// Number of entries in the write layers (balances, fee balances, nonces, derived keys).
+    pub fn write_count(&self) -> usize {
+        self.asset_balances.len() + self.uplp_balances.len() + self.nonces.len() + self.derived_keys.len()
+    }
+
+    /// True if a transaction outside the transfer fast path has been applied, so the base is no longer the
+    /// original snapshot.
+    pub fn is_rebased(&self) -> bool {
+        self.rebased
+    }
+
+    /// Events emitted by the applied transactions, in order. Not drained; `commit_into` emits them on the state.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Applies a transaction like `State::apply_transaction`: `validate_basic`, then [`OverlayState::apply_effects`].
+    pub fn apply_transaction(&mut self, tx: &Transaction) -> Result<()> {
+        tx.validate_basic().map_err(PlatariumError::from)?;
+        self.apply_effects(tx)
+    }
+
+    /// Applies the state effects of `tx` with the rules of `State::apply_effects`. Leaves the overlay unchanged on
+    /// error.
+    pub fn apply_effects(&mut self, tx: &Transaction) -> Result<()> {
+        let fast = tx.kind == TxKind::Transfer
+            && tx.multisig.is_none()
+            && !self.base.pending_rotations_arc().contains_key(&tx.from)
+            && !self.base.account_kinds_arc().contains_key(&tx.from);
+        if fast {
+            self.apply_transfer_tx(tx)
+        } else {
+            self.apply_on_state(tx)
+        }
+    }
+
+    /// Transfer on the write layers, in the order of `State::apply_effects`: addresses, freeze orders, keys, balance
+    /// rules, then first-use binding of the derived key.
+    fn apply_transfer_tx(&mut self, tx: &Transaction) -> Result<()> {
+        let height = self.base.block_height();
+        check_addresses(tx)?;
+        let frozen_by = self
+            .base
+            .freeze_orders_arc()
+            .iter()
+            .find(|(_, o)| o.address == tx.from && o.is_active_at(height));
+        if let Some((order_id, _)) = frozen_by {
+            return Err(StateError::AddressFrozen {
+                address: tx.from.clone(),
+                order_id: order_id.clone(),
+            }
+            .into());
+        }
+        let registered = self.registered_derived_key(&tx.from);
+        let first_use = registered.is_none();
+        check_registered_derived_key(&tx.from, registered, tx.pub_derived.as_deref())?;
+        let main_key = self.base.main_keys_arc().get(&tx.from).cloned();
+        check_installed_main_key(&tx.from, main_key, tx.pub_main.as_deref().unwrap_or(&tx.from))?;
+        if self.base.asset_registry_arc().is_frozen(&tx.asset) {
+            return Err(StateError::AssetFrozen(tx.asset.as_canonical()).into());
+        }
+        let locked = self
+            .base
+            .vesting_arc()
+            .get(&tx.from)
+            .map(|s| s.locked_at(height))
+            .unwrap_or(0);
+        transfer_effects(self, &tx.from, &tx.to, &tx.asset, tx.amount, tx.fee_uplp, Some(tx.nonce), locked)?;
+        if let (true, Some(key)) = (first_use, tx.pub_derived.as_deref()) {
+            let key = key.strip_prefix("Px").unwrap_or(key).to_ascii_lowercase();
+            self.derived_keys.insert(tx.from.clone(), key.clone());
+            self.events.push(Event::DerivedKeyRegistered {
+                address: tx.from.clone(),
+                derived_key: key,
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies `tx` to a temporary `State` holding the overlay's current view and makes the result the new base.
+    fn apply_on_state(&mut self, tx: &Transaction) -> Result<()> {
+        let state = State::new();
+        state.restore(&self.snapshot());
+        state.apply_effects(tx)?;
+        self.events.extend(state.take_events());
+        self.base = state.snapshot();
+        self.asset_balances.clear();
+        self.uplp_balances.clear();
+        self.nonces.clear();
+        self.derived_keys.clear();
+        self.rebased = true;
+        Ok(())
+    }
+
+    /// The overlay's view as a snapshot. Copies only the maps that have writes.
+    pub fn snapshot(&self) -> StateSnapshot {
+        self.base
+            .with_writes(&self.asset_balances, &self.uplp_balances, &self.nonces, &self.derived_keys)
+    }
+
+    /// Writes the overlay into `state` and emits its events there. Errors, leaving `state` unchanged, if `state`
+    /// has been written or restored since the overlay's snapshot was taken from it.
+    pub fn commit_into(self, state: &State) -> Result<()> {
+        if !state.is_at(&self.origin) {
+            return Err(StateError::Other("overlay base is no longer the current state".to_string()).into());
+        }
+        let rebased = self.is_rebased();
+        let view = rebased.then(|| self.snapshot());
+        let Self {
+            origin,
+            base,
+            asset_balances,
+            uplp_balances,
+            nonces,
+            derived_keys,
+            events,
+            ..
+        } = self;
+        // Release the overlay's hold on the shared maps first, so writing them does not copy them.
+        drop((origin, base));
+        match view {
+            Some(view) => state.restore(&view),
+            None => state.apply_writes(asset_balances, uplp_balances, nonces, derived_keys),
+        }
+        for event in events {
+            state.emit(event);
+        }
+        Ok(())
+    }
+
+    /// Drops the overlay's writes. Equivalent to dropping it; spelled out where a speculative branch is abandoned.
+    pub fn discard(self) {}
+}
+
+impl Ledger for OverlayState {
+    fn asset_balance(&self, address: &Address, asset: &str) -> u128 {
+        let k = (address.clone(), asset.to_string());
+        match self.asset_balances.get(&k) {
+            Some(b) => *b,
+            None => self.base.asset_balances_arc().get(&k).copied().unwrap_or(0),
+        }
+    }
+    fn set_asset_balance(&mut self, address: &Address, asset: &str, balance: u128) {
+        self.asset_balances.insert((address.clone(), asset.to_string()), balance);
+    }
+    fn uplp_balance(&self, address: &Address) -> u128 {
+        match self.uplp_balances.get(address) {
+            Some(b) => *b,
+            None => self.base.get_uplp_balance(address),
+        }
+    }
+    fn set_uplp_balance(&mut self, address: &Address, balance: u128) {
+        self.uplp_balances.insert(address.clone(), balance);
+    }
+    fn nonce(&self, address: &Address) -> u64 {
+        match self.nonces.get(address) {
+            Some(n) => *n,
+            None => self.base.get_nonce(address),
+        }
+    }
+    fn set_nonce(&mut self, address: &Address, nonce: u64) {
+        self.nonces.insert(address.clone(), nonce);
+    }
+}
+
+/// Outcome of applying one candidate ordering; see [`speculate_orderings`].
+#[derive(Debug, Clone)]
+pub struct SpeculativeRun {
+    /// Post-state of the ordering; commit it with [`OverlayState::commit_into`] if it wins.
+    pub overlay: OverlayState,
+    /// Hashes of the transactions that applied, in order.
+    pub applied: Vec<String>,
+    /// Hash and error of each rejected transaction, in order. Rejected transactions have no effect.
+    pub rejected: Vec<(String, String)>,
+}
+
+/// Applies each candidate ordering to its own overlay over `base`, one thread per ordering, skipping transactions
+/// that fail. Results are in the order of `orderings` and do not depend on thread scheduling.
+pub fn speculate_orderings(base: &StateSnapshot, orderings: &[Vec<Transaction>]) -> Vec<SpeculativeRun> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = orderings
+            .iter()
+            .map(|txs| scope.spawn(move || speculate(base.clone(), txs)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("speculative execution thread panicked"))
+            .collect()
+    })
+}
+
+fn speculate(base: StateSnapshot, txs: &[Transaction]) -> SpeculativeRun {
+    let mut overlay = OverlayState::new(base);
+    let mut applied = Vec::new();
+    let mut rejected = Vec::new();
+    for tx in txs {
+        match overlay.apply_transaction(tx) {
+            Ok(()) => applied.push(tx.hash.clone()),
+            Err(e) => rejected.push((tx.hash.clone(), e.to_string())),
+        }
+    }
+    SpeculativeRun {
+        overlay,
+        applied,
+        rejected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use crate::core::state::TREASURY_ADDRESS;
+    use crate::testing::DeterministicRng;
+
+    /// Key address standing for `label` (`"A"`, `"B"`, ...), the same on every call.
+    fn key(label: &str) -> String {
+        DeterministicRng::new(label.bytes().fold(0, |seed, b| seed * 256 + u64::from(b))).account().address
+    }
+
+    fn tx(from: &str, to: &str, amount: u128, nonce: u64) -> Transaction {
+        Transaction::new(
+            key(from),
+            key(to),
+            Asset::PLP,
+            amount,
+            1,
+            nonce,
+            HashSet::new(),
+            HashSet::new(),
+            "sig".to_string(),
+            "sig".to_string(),
+        )
+        .unwrap()
+    }
+
+    fn funded() -> State {
+        let state = State::new();
+        state.set_balance(&key("A"), 100);
+        state.set_balance(&key("B"), 50);
+        state.set_uplp_balance(&key("B"), 10);
+        for i in 0..100 {
+            state.set_balance(&format!("other{}", i), 1);
+        }
+        state
+    }
+
+    #[test]
+    fn test_transfer_matches_state_and_touches_only_its_accounts() {
+        let state = funded();
+        let base = state.snapshot();
+        let mut overlay = OverlayState::new(base.clone());
+        let txs = [tx("A", "B", 30, 0), tx("B", "C", 70, 0), tx("A", "C", 5, 1)];
+        for t in &txs {
+            overlay.apply_effects(t).unwrap();
+        }
+        // PLP: A, B, C; μPLP: A, B, treasury; nonces: A, B.
+        assert_eq!(overlay.write_count(), 8);
+        assert!(!overlay.is_rebased());
+        assert_eq!(overlay.get_uplp_balance(&TREASURY_ADDRESS.to_string()), 3);
+        assert_eq!(state.snapshot(), base);
+
+        let reference = State::new();
+        reference.restore(&base);
+        for t in &txs {
+            reference.apply_effects(t).unwrap();
+        }
+        assert_eq!(overlay.snapshot(), reference.snapshot());
+        overlay.commit_into(&state).unwrap();
+        assert_eq!(state.snapshot(), reference.snapshot());
+        assert_eq!(state.current_root(), reference.recompute_root());
+    }
+
+    #[test]
+    fn test_rejected_tx_leaves_overlay_unchanged() {
+        let state = funded();
+        let mut overlay = OverlayState::new(state.snapshot());
+        overlay.apply_effects(&tx("A", "B", 10, 0)).unwrap();
+        let before = overlay.snapshot();
+        assert!(overlay.apply_effects(&tx("A", "B", 500, 1)).is_err());
+        assert!(overlay.apply_effects(&tx("A", "B", 1, 0)).is_err());
+        assert_eq!(overlay.snapshot(), before);
+        overlay.clone().discard();
+        assert_eq!(overlay.get_nonce(&key("A")), 1);
+    }
+
+    #[test]
+    fn test_other_kinds_rebase_and_commit() {
+        let state = funded();
+        let base = state.snapshot();
+        let approve = tx("A", "B", 20, 0).with_kind(TxKind::Approve).unwrap();
+        let mut overlay = OverlayState::new(base.clone());
+        overlay.apply_effects(&approve).unwrap();
+        assert!(overlay.is_rebased());
+        overlay.apply_effects(&tx("A", "C", 5, 1)).unwrap();
+        assert_eq!(overlay.events().len(), 1);
+
+        let reference = State::new();
+        reference.restore(&base);
+        reference.apply_effects(&approve).unwrap();
+        reference.apply_effects(&tx("A", "C", 5, 1)).unwrap();
+        assert_eq!(overlay.snapshot(), reference.snapshot());
+        overlay.commit_into(&state).unwrap();
+        assert_eq!(state.get_allowance(&key("A"), &key("B"), &Asset::PLP), 20);
+        assert_eq!(state.take_events(), reference.take_events());
+    }
+
+    #[test]
+    fn test_commit_refuses_moved_state() {
+        let state = funded();
+        let mut overlay = OverlayState::new(state.snapshot());
+        overlay.apply_effects(&tx("A", "B", 10, 0)).unwrap();
+        state.set_nonce(&key("Z"), 4);
+        let before = state.snapshot();
+        assert!(overlay.commit_into(&state).is_err());
+        assert_eq!(state.snapshot(), before);
+    }
+
+    #[test]
+    fn test_speculate_orderings_in_parallel() {
+        let mut rng = DeterministicRng::new(23);
+        let accounts = rng.accounts(2);
+        let state = State::new();
+        state.set_balance(&accounts[0].address, 100);
+        let to = &accounts[1].address;
+        let big = accounts[0].transfer(to, 90, 1, 0);
+        let small = accounts[0].transfer(to, 20, 1, 0);
+        let orderings = vec![vec![big.clone(), small.clone()], vec![small.clone(), big.clone()]];
+
+        let runs = speculate_orderings(&state.snapshot(), &orderings);
+        assert_eq!(runs[0].applied, vec![big.hash.clone()]);
+        assert_eq!(runs[1].applied, vec![small.hash.clone()]);
+        assert_eq!(runs[1].rejected.len(), 1);
+        assert_eq!(runs[0].overlay.get_balance(to), 90);
+        // First transfer binds the derived key.
+        assert_eq!(runs[0].overlay.registered_derived_key(&accounts[0].address), Some(accounts[0].pub_derived()));
+        assert!(state.get_balance(to) == 0 && state.registered_derived_key(&accounts[0].address).is_none());
+
+        let mut runs = runs;
+        runs.swap_remove(0).overlay.commit_into(&state).unwrap();
+        assert_eq!(state.get_balance(to), 90);
+        assert_eq!(state.registered_derived_key(&accounts[0].address), Some(accounts[0].pub_derived()));
+        assert_eq!(state.take_events().len(), 1);
+    }
+}
@@ -0,0 +1,135 @@
+//! Load shedding for validators under DoS.
+//!
+//! An operator (or the node's own monitoring) raises the overload level with `Core::set_overload_mode`.
+//! Each level maps to a fixed [`AdmissionPolicy`] taken from the protocol params in `consensus_params`:
+//! a higher minimum fee, a smaller per-sender pending cap, and a strike limit after which the network
+//! layer should deprioritize gossip from a misbehaving peer.
+//!
+//! The level only gates admission into the local mempool; it never changes how an admitted transaction
+//! executes, so state stays a deterministic function of the transactions applied.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::consensus_params::{
+    OVERLOAD_MAX_PENDING_PER_SENDER, OVERLOAD_MIN_FEE_UPLP, OVERLOAD_PEER_STRIKE_LIMIT,
+};
+use crate::core::transaction::Transaction;
+use crate::error::PlatariumError;
+
+/// Load-shedding level, from normal operation to the strictest admission.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverloadLevel {
+    #[default]
+    Normal = 0,
+    Elevated = 1,
+    Severe = 2,
+    Critical = 3,
+}
+
+impl OverloadLevel {
+    /// Level for a numeric value; values above 3 clamp to `Critical`.
+    pub fn from_u8(level: u8) -> Self {
+        match level {
+            0 => Self::Normal,
+            1 => Self::Elevated,
+            2 => Self::Severe,
+            _ => Self::Critical,
+        }
+    }
+
+    /// Admission thresholds for this level.
+    pub fn policy(self) -> AdmissionPolicy {
+        let i = self as usize;
+        AdmissionPolicy {
+            level: self,
+            min_fee_uplp: OVERLOAD_MIN_FEE_UPLP[i],
+            max_pending_per_sender: OVERLOAD_MAX_PENDING_PER_SENDER[i],
+            peer_strike_limit: OVERLOAD_PEER_STRIKE_LIMIT[i],
+        }
+    }
+}
+
+/// Errors produced by overload admission checks.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OverloadError {
+    #[error("Fee {fee} is below the {level:?} overload minimum of {min}")]
+    FeeTooLow { level: OverloadLevel, fee: u128, min: u128 },
+
+    #[error("Sender {sender} already has {pending} pending transactions ({level:?} overload cap {max})")]
+    SenderCapReached {
+        level: OverloadLevel,
+        sender: String,
+        pending: usize,
+        max: usize,
+    },
+}
+
+impl From<OverloadError> for PlatariumError {
+    fn from(err: OverloadError) -> Self {
+        PlatariumError::State(format!("Overload: {}", err))
+    }
+}
+
+/// Admission thresholds in force at one overload level. Also the signal handed to the network layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AdmissionPolicy {
+    pub level: OverloadLevel,
+    /// Transactions paying less than this are not admitted.
+    pub min_fee_uplp: u128,
+    /// A sender with this many pending transactions cannot add another.
+    pub max_pending_per_sender: usize,
+    /// Gossip from peers with at least this many strikes should be deprioritized.
+    pub peer_strike_limit: u32,
+}
+
+impl AdmissionPolicy {
+    /// Checks `tx` against the policy given the sender's current pending count.
+    pub fn check(&self, tx: &Transaction, sender_pending: usize) -> Result<(), OverloadError> {
+        if tx.fee_uplp < self.min_fee_uplp {
+            return Err(OverloadError::FeeTooLow {
+                level: self.level,
+                fee: tx.fee_uplp,
+                min: self.min_fee_uplp,
+            });
+        }
+        if sender_pending >= self.max_pending_per_sender {
+            return Err(OverloadError::SenderCapReached {
+                level: self.level,
+                sender: tx.from.clone(),
+                pending: sender_pending,
+                max: self.max_pending_per_sender,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether the network layer should deprioritize gossip from a peer with `strikes` misbehaviour strikes.
+    pub fn deprioritize_peer(&self, strikes: u32) -> bool {
+        strikes >= self.peer_strike_limit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_tighten_monotonically() {
+        let levels = [
+            OverloadLevel::Normal,
+            OverloadLevel::Elevated,
+            OverloadLevel::Severe,
+            OverloadLevel::Critical,
+        ];
+        for pair in levels.windows(2) {
+            let (a, b) = (pair[0].policy(), pair[1].policy());
+            assert!(b.min_fee_uplp > a.min_fee_uplp);
+            assert!(b.max_pending_per_sender < a.max_pending_per_sender);
+            assert!(b.peer_strike_limit < a.peer_strike_limit);
+        }
+        assert_eq!(OverloadLevel::from_u8(9), OverloadLevel::Critical);
+        assert!(!OverloadLevel::Normal.policy().deprioritize_peer(1_000));
+        assert!(OverloadLevel::Critical.policy().deprioritize_peer(1));
+    }
+}
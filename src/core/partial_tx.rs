@@ -0,0 +1,437 @@
+//! Partially signed transactions: a portable container for offline and multi-party signing.
+//!
+//! A [`PartialTransaction`] carries the unsigned transaction, free-form metadata and the signatures
+//! collected so far, keyed by [`SignerRole`]. Each signer adds its signature on its own machine; the
+//! containers are then merged and finalized into an ordinary signed [`Transaction`]. Every signature is
+//! verified against the transaction hash when it is added, including those merged in from another container.
+//! For a multisig sender (a transaction carrying a `MultisigWitness`) each member of the signer set signs as
+//! [`SignerRole::Multisig`], and `finalize` fills the witness once `threshold` members have signed.
+//!
+//! The canonical encoding is `pptx1` followed by the hex of the container's JSON. Maps are ordered and the
+//! transaction's `reads`/`writes` sets serialize sorted, so equal containers encode to identical strings
+//! regardless of which tool produced them.
+//!
+//! A single signature can also travel on its own as a [`RoleSignature`] (`psig1` + hex JSON): produced with
+//! `Transaction::sign_role` where the key lives and attached with `Transaction::attach_signature` or
+//! [`PartialTransaction::add_role_signature`].
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::multisig::MultisigSignature;
+use crate::core::transaction::{same_public_key, Transaction, TxKind};
+use crate::error::PlatariumError;
+use crate::signature::{normalize_public_key_hex, normalize_signature_hex, verify_signature_hash};
+
+/// Prefix of the canonical string encoding; the digit is the container version.
+pub const PARTIAL_TX_PREFIX: &str = "pptx1";
+
+/// Prefix of the string encoding of a [`RoleSignature`].
+pub const ROLE_SIGNATURE_PREFIX: &str = "psig1";
+
+/// Errors produced while building, merging or finalizing a partial transaction.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PartialTxError {
+    #[error("Cannot merge partial transactions for different hashes ({ours} vs {theirs})")]
+    HashMismatch { ours: String, theirs: String },
+
+    #[error("Conflicting {0} for the same partial transaction")]
+    Conflict(String),
+
+    #[error("Invalid {role:?} signature for transaction {hash}")]
+    InvalidSignature { role: SignerRole, hash: String },
+
+    #[error("Missing {0:?} signature")]
+    MissingSignature(SignerRole),
+
+    #[error("Key {0} is not in the transaction's multisig signer set")]
+    UnknownSigner(String),
+
+    #[error("Invalid partial transaction encoding: {0}")]
+    Encoding(String),
+}
+
+impl From<PartialTxError> for PlatariumError {
+    fn from(err: PartialTxError) -> Self {
+        PlatariumError::Signature(format!("Partial transaction: {}", err))
+    }
+}
+
+/// Which transaction signature a partial signature fills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerRole {
+    /// `sig_main`, by the account's main key (or the session key for `SessionTransfer`).
+    Main,
+    /// `sig_derived`, by the account's derived key.
+    Derived,
+    /// One signature in `Transaction::multisig`, by a key in the witness signer set.
+    Multisig,
+}
+
+/// One collected signature with the public key that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialSignature {
+    pub pub_key: String,
+    pub signature: String,
+}
+
+/// One transaction signature made apart from the transaction, with the hash it is over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleSignature {
+    pub hash: String,
+    pub role: SignerRole,
+    #[serde(flatten)]
+    pub signature: PartialSignature,
+}
+
+impl RoleSignature {
+    /// String encoding (`psig1` + hex of the JSON).
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("role signature serializes");
+        format!("{}{}", ROLE_SIGNATURE_PREFIX, hex::encode(json))
+    }
+
+    /// Parses [`Self::encode`] output. The signature is checked when it is attached.
+    pub fn decode(encoded: &str) -> Result<Self, PartialTxError> {
+        let body = encoded
+            .trim()
+            .strip_prefix(ROLE_SIGNATURE_PREFIX)
+            .ok_or_else(|| PartialTxError::Encoding(format!("missing {} prefix", ROLE_SIGNATURE_PREFIX)))?;
+        let bytes = hex::decode(body).map_err(|e| PartialTxError::Encoding(e.to_string()))?;
+        serde_json::from_slice(&bytes).map_err(|e| PartialTxError::Encoding(e.to_string()))
+    }
+}
+
+/// Unsigned transaction plus metadata and the signatures collected so far.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialTransaction {
+    pub tx: Transaction,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default)]
+    pub signatures: BTreeMap<SignerRole, PartialSignature>,
+    /// Multisig witness signatures by signer key (lowercase hex without prefix).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub multisig_signatures: BTreeMap<String, PartialSignature>,
+}
+
+impl PartialTransaction {
+    /// Wraps `tx`, dropping any signatures and public keys it carries. A multisig witness keeps its signer set and
+    /// threshold.
+    pub fn new(mut tx: Transaction) -> Self {
+        tx.sig_main.clear();
+        tx.sig_derived.clear();
+        tx.pub_main = None;
+        tx.pub_derived = None;
+        if let Some(witness) = tx.multisig.as_mut() {
+            witness.signatures.clear();
+        }
+        Self {
+            tx,
+            metadata: BTreeMap::new(),
+            signatures: BTreeMap::new(),
+            multisig_signatures: BTreeMap::new(),
+        }
+    }
+
+    /// Roles whose signatures `finalize` requires for this transaction kind: `Multisig` alone for a transaction
+    /// carrying a witness.
+    pub fn required_roles(&self) -> &'static [SignerRole] {
+        if self.tx.multisig.is_some() {
+            return &[SignerRole::Multisig];
+        }
+        match self.tx.kind {
+            TxKind::SessionTransfer { .. } => &[SignerRole::Main],
+            TxKind::InitiateRecovery { .. } => &[SignerRole::Derived],
+            _ => &[SignerRole::Main, SignerRole::Derived],
+        }
+    }
+
+    /// Verifies `signature` by `pub_key` over the transaction hash and records it for `role`. A `Multisig` signature
+    /// must come from a key in the witness signer set.
+    pub fn add_signature(&mut self, role: SignerRole, pub_key: &str, signature: &str) -> Result<(), PartialTxError> {
+        let invalid = || PartialTxError::InvalidSignature {
+            role,
+            hash: self.tx.hash.clone(),
+        };
+        let hash = hex::decode(&self.tx.hash).map_err(|_| invalid())?;
+        let key = pub_key.strip_prefix("Px").unwrap_or(pub_key);
+        if !verify_signature_hash(&hash, signature, key).unwrap_or(false) {
+            return Err(invalid());
+        }
+        let sig = PartialSignature {
+            pub_key: pub_key.to_string(),
+            signature: normalize_signature_hex(signature),
+        };
+        if role == SignerRole::Multisig {
+            let signer =
+                self.multisig_signer(pub_key).ok_or_else(|| PartialTxError::UnknownSigner(pub_key.to_string()))?;
+            return record(&mut self.multisig_signatures, signer, sig, role);
+        }
+        record(&mut self.signatures, role, sig, role)
+    }
+
+    /// `pub_key` as lowercase hex if it is in the witness signer set.
+    fn multisig_signer(&self, pub_key: &str) -> Option<String> {
+        let witness = self.tx.multisig.as_ref()?;
+        let key = normalize_public_key_hex(pub_key).ok()?;
+        witness.signers.iter().any(|s| same_public_key(s, &key)).then_some(key)
+    }
+
+    /// Records a [`RoleSignature`]; it must be over this transaction's hash.
+    pub fn add_role_signature(&mut self, signature: &RoleSignature) -> Result<(), PartialTxError> {
+        if !signature.hash.eq_ignore_ascii_case(&self.tx.hash) {
+            return Err(PartialTxError::HashMismatch {
+                ours: self.tx.hash.clone(),
+                theirs: signature.hash.clone(),
+            });
+        }
+        self.add_signature(signature.role, &signature.signature.pub_key, &signature.signature.signature)
+    }
+
+    /// Combines the signatures and metadata of `other` into `self`, verifying each incoming signature like
+    /// [`Self::add_signature`]. Both must wrap the same transaction (and multisig signer set); on error `self` is
+    /// unchanged.
+    pub fn merge(&mut self, other: &PartialTransaction) -> Result<(), PartialTxError> {
+        if self.tx.hash != other.tx.hash {
+            return Err(PartialTxError::HashMismatch {
+                ours: self.tx.hash.clone(),
+                theirs: other.tx.hash.clone(),
+            });
+        }
+        let same_signers = match (&self.tx.multisig, &other.tx.multisig) {
+            (Some(ours), Some(theirs)) => ours.matches(&theirs.signers, theirs.threshold),
+            (ours, theirs) => ours.is_none() && theirs.is_none(),
+        };
+        if !same_signers {
+            return Err(PartialTxError::Conflict("multisig signer set".to_string()));
+        }
+        for (key, value) in &other.metadata {
+            if self.metadata.get(key).is_some_and(|v| v != value) {
+                return Err(PartialTxError::Conflict(format!("metadata `{}`", key)));
+            }
+        }
+        let mut merged = self.clone();
+        let incoming = other.signatures.iter().map(|(role, sig)| (*role, sig));
+        for (role, sig) in incoming.chain(other.multisig_signatures.values().map(|sig| (SignerRole::Multisig, sig))) {
+            merged.add_signature(role, &sig.pub_key, &sig.signature)?;
+        }
+        merged.metadata.extend(other.metadata.clone());
+        *self = merged;
+        Ok(())
+    }
+
+    /// First required role still missing: an absent signature, or fewer multisig signatures than the threshold.
+    fn missing_role(&self) -> Option<SignerRole> {
+        let threshold = self.tx.multisig.as_ref().map_or(0, |w| w.threshold as usize);
+        self.required_roles().iter().copied().find(|role| match role {
+            SignerRole::Multisig => self.multisig_signatures.len() < threshold,
+            _ => !self.signatures.contains_key(role),
+        })
+    }
+
+    /// Whether every required signature is present.
+    pub fn is_complete(&self) -> bool {
+        self.missing_role().is_none()
+    }
+
+    /// Produces the signed transaction. Errors if a required signature is missing.
+    pub fn finalize(&self) -> Result<Transaction, PartialTxError> {
+        if let Some(missing) = self.missing_role() {
+            return Err(PartialTxError::MissingSignature(missing));
+        }
+        let mut tx = self.tx.clone();
+        if let Some(main) = self.signatures.get(&SignerRole::Main) {
+            tx.sig_main = main.signature.clone();
+            if !matches!(tx.kind, TxKind::SessionTransfer { .. }) {
+                tx.pub_main = Some(main.pub_key.clone());
+            }
+        }
+        if let Some(derived) = self.signatures.get(&SignerRole::Derived) {
+            tx.sig_derived = derived.signature.clone();
+            tx.pub_derived = Some(derived.pub_key.clone());
+        }
+        if let Some(witness) = tx.multisig.as_mut() {
+            let signed =
+                |key: &String| normalize_public_key_hex(key).ok().and_then(|k| self.multisig_signatures.get(&k));
+            witness.signatures = (0u32..)
+                .zip(&witness.signers)
+                .filter_map(|(signer, key)| {
+                    signed(key).map(|s| MultisigSignature { signer, signature: s.signature.clone() })
+                })
+                .collect();
+        }
+        Ok(tx)
+    }
+
+    /// Canonical string encoding (`pptx1` + hex of the JSON container).
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("partial transaction serializes");
+        format!("{}{}", PARTIAL_TX_PREFIX, hex::encode(json))
+    }
+
+    /// Parses the canonical encoding and checks that the embedded hash matches the transaction.
+    pub fn decode(encoded: &str) -> Result<Self, PartialTxError> {
+        let body = encoded
+            .trim()
+            .strip_prefix(PARTIAL_TX_PREFIX)
+            .ok_or_else(|| PartialTxError::Encoding(format!("missing {} prefix", PARTIAL_TX_PREFIX)))?;
+        let bytes = hex::decode(body).map_err(|e| PartialTxError::Encoding(e.to_string()))?;
+        let ptx: Self = serde_json::from_slice(&bytes).map_err(|e| PartialTxError::Encoding(e.to_string()))?;
+        if !ptx.tx.validate_hash().unwrap_or(false) {
+            return Err(PartialTxError::Encoding("transaction hash does not match its data".to_string()));
+        }
+        Ok(ptx)
+    }
+}
+
+/// Records `sig` under `key`; an existing different signature for the same key is a conflict.
+fn record<K: Ord>(
+    signatures: &mut BTreeMap<K, PartialSignature>,
+    key: K,
+    sig: PartialSignature,
+    role: SignerRole,
+) -> Result<(), PartialTxError> {
+    match signatures.get(&key) {
+        Some(existing) if *existing != sig => Err(PartialTxError::Conflict(format!("{:?} signature", role))),
+        _ => {
+            signatures.insert(key, sig);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::asset::Asset;
+    use crate::core::multisig::MultisigWitness;
+    use crate::signature::sign_message;
+    use secp256k1::{PublicKey, Secp256k1, SecretKey};
+    use std::collections::HashSet;
+
+    fn keypair(byte: u8) -> (SecretKey, String) {
+        let sk = SecretKey::from_slice(&[byte; 32]).unwrap();
+        let pk = hex::encode(PublicKey::from_secret_key(&Secp256k1::new(), &sk).serialize());
+        (sk, pk)
+    }
+
+    fn sign_hash(sk: &SecretKey, hash: &str) -> String {
+        let secp = Secp256k1::new();
+        let msg = secp256k1::Message::from_digest_slice(&hex::decode(hash).unwrap()).unwrap();
+        hex::encode(secp.sign_ecdsa(&msg, sk).serialize_compact())
+    }
+
+    fn unsigned() -> PartialTransaction {
+        let tx = Transaction::new(
+            "alice".into(),
+            "bob".into(),
+            Asset::PLP,
+            10,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap();
+        PartialTransaction::new(tx)
+    }
+
+    #[test]
+    fn test_merge_and_finalize_across_signers() {
+        let (main_sk, main_pk) = keypair(1);
+        let (derived_sk, derived_pk) = keypair(2);
+        let mut a = unsigned();
+        a.metadata.insert("memo".into(), "invoice 7".into());
+        let mut b = PartialTransaction::decode(&a.encode()).unwrap();
+
+        let hash = a.tx.hash.clone();
+        a.add_signature(SignerRole::Main, &main_pk, &sign_hash(&main_sk, &hash)).unwrap();
+        b.add_signature(SignerRole::Derived, &derived_pk, &sign_hash(&derived_sk, &hash)).unwrap();
+        assert!(matches!(a.finalize(), Err(PartialTxError::MissingSignature(SignerRole::Derived))));
+
+        a.merge(&b).unwrap();
+        assert!(a.is_complete());
+        let tx = a.finalize().unwrap();
+        assert!(tx.verify_signatures().unwrap());
+        assert_eq!(PartialTransaction::decode(&a.encode()).unwrap(), a);
+    }
+
+    #[test]
+    fn test_rejects_bad_signature_and_mismatched_merge() {
+        let (sk, pk) = keypair(3);
+        let mut a = unsigned();
+        let wrong = sign_message(&sk, &"not the tx").unwrap().signature_compact;
+        assert!(matches!(
+            a.add_signature(SignerRole::Main, &pk, &wrong),
+            Err(PartialTxError::InvalidSignature { .. })
+        ));
+
+        let mut other = unsigned();
+        other.tx.nonce = 1;
+        other.tx.hash = other.tx.compute_hash().unwrap();
+        assert!(matches!(a.merge(&other), Err(PartialTxError::HashMismatch { .. })));
+        assert!(PartialTransaction::decode(&other.encode().replacen("pptx1", "pptx9", 1)).is_err());
+    }
+
+    #[test]
+    fn test_encoding_is_canonical_and_merge_verifies() {
+        let addresses: Vec<String> = (0..32).map(|i| format!("addr{:02}", i)).collect();
+        let with_sets = |order: &[String]| {
+            let mut ptx = unsigned();
+            ptx.tx.reads = order.iter().cloned().collect();
+            ptx.tx.writes = order.iter().rev().cloned().collect();
+            ptx
+        };
+        let reversed: Vec<String> = addresses.iter().rev().cloned().collect();
+        assert_eq!(with_sets(&addresses).encode(), with_sets(&reversed).encode());
+
+        // A forged signature in the other container is rejected and leaves ours untouched.
+        let (main_sk, main_pk) = keypair(4);
+        let mut ours = unsigned();
+        let mut theirs = unsigned();
+        theirs.signatures.insert(
+            SignerRole::Derived,
+            PartialSignature { pub_key: main_pk.clone(), signature: "11".repeat(64) },
+        );
+        theirs.metadata.insert("memo".into(), "x".into());
+        assert!(matches!(ours.merge(&theirs), Err(PartialTxError::InvalidSignature { .. })));
+        assert_eq!(ours, unsigned());
+        let hash = ours.tx.hash.clone();
+        theirs.signatures.clear();
+        theirs.add_signature(SignerRole::Main, &main_pk, &sign_hash(&main_sk, &hash)).unwrap();
+        ours.merge(&theirs).unwrap();
+        assert_eq!(ours.signatures, theirs.signatures);
+    }
+
+    #[test]
+    fn test_multisig_role_fills_witness() {
+        let keys: Vec<(SecretKey, String)> = (5..8).map(keypair).collect();
+        let mut tx = unsigned().tx;
+        let signers = keys.iter().map(|(_, pk)| pk.clone()).collect();
+        tx.multisig = Some(MultisigWitness::new(signers, 2).unwrap());
+        let mut a = PartialTransaction::new(tx);
+        assert_eq!(a.required_roles(), &[SignerRole::Multisig]);
+        let mut b = a.clone();
+        let hash = a.tx.hash.clone();
+
+        let (outsider_sk, outsider_pk) = keypair(9);
+        assert!(matches!(
+            a.add_signature(SignerRole::Multisig, &outsider_pk, &sign_hash(&outsider_sk, &hash)),
+            Err(PartialTxError::UnknownSigner(_))
+        ));
+        a.add_signature(SignerRole::Multisig, &keys[2].1, &sign_hash(&keys[2].0, &hash)).unwrap();
+        assert!(matches!(a.finalize(), Err(PartialTxError::MissingSignature(SignerRole::Multisig))));
+        b.add_signature(SignerRole::Multisig, &keys[0].1, &sign_hash(&keys[0].0, &hash)).unwrap();
+        a.merge(&b).unwrap();
+        assert_eq!(PartialTransaction::decode(&a.encode()).unwrap(), a);
+
+        let tx = a.finalize().unwrap();
+        let witness = tx.multisig.as_ref().unwrap();
+        assert_eq!(witness.signatures.iter().map(|s| s.signer).collect::<Vec<_>>(), vec![0, 2]);
+        assert!(tx.verify_signatures().unwrap());
+    }
+}
@@ -0,0 +1,340 @@
+//! Pre-signed transactions for air-gapped signing.
+//!
+//! The online host builds a [`PresignedTransaction`] from an unsigned [`Transaction`] and hands it to the offline
+//! machine, which recomputes the hash from the payload, signs it with [`PresignedTransaction::sign_detached`] and
+//! returns only the [`DetachedSignatures`]. The online host attaches them and gets a submittable transaction from
+//! [`PresignedTransaction::into_transaction`]; the keys never leave the offline machine.
+//!
+//! Unlike [`crate::core::partial_tx`], which collects signatures from several parties, this is the single-signer
+//! round trip: one payload out, one pair of signatures back.
+//!
+//! [`UnsignedTransaction`] serves signers that do not run this crate (hardware wallets, HSM scripts): it exports the
+//! exact preimage bytes from [`UnsignedTransaction::signing_payload`], the signer returns two compact signatures over
+//! their SHA256, and [`UnsignedTransaction::with_signatures`] turns them into a checked [`Transaction`].
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::transaction::Transaction;
+use crate::error::PlatariumError;
+use crate::signer::{sign_digest_checked, Signer};
+
+/// Errors produced while attaching signatures to or finalizing a pre-signed transaction.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PresignedTxError {
+    #[error("Transaction hash does not match its data")]
+    HashMismatch,
+
+    #[error("Signatures are for transaction {signed}, not {expected}")]
+    WrongTransaction { expected: String, signed: String },
+
+    #[error("Signatures do not verify: {0}")]
+    InvalidSignatures(String),
+
+    #[error("No signatures attached")]
+    Unsigned,
+}
+
+impl From<PresignedTxError> for PlatariumError {
+    fn from(err: PresignedTxError) -> Self {
+        PlatariumError::Signature(format!("Pre-signed transaction: {}", err))
+    }
+}
+
+/// Signatures over a transaction hash, made apart from the transaction itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetachedSignatures {
+    /// Hash the signatures are over, so they cannot be attached to a different payload by mistake.
+    pub hash: String,
+    pub sig_main: String,
+    pub sig_derived: String,
+    pub pub_main: String,
+    pub pub_derived: String,
+}
+
+/// Unsigned transaction payload plus the detached signatures, once attached.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresignedTransaction {
+    payload: Transaction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signatures: Option<DetachedSignatures>,
+}
+
+impl PresignedTransaction {
+    /// Wraps `tx`, dropping any signatures and public keys it carries. Errors if its hash does not match its data.
+    pub fn new(mut tx: Transaction) -> Result<Self, PresignedTxError> {
+        if !tx.validate_hash().unwrap_or(false) {
+            return Err(PresignedTxError::HashMismatch);
+        }
+        tx.sig_main.clear();
+        tx.sig_derived.clear();
+        tx.pub_main = None;
+        tx.pub_derived = None;
+        Ok(Self { payload: tx, signatures: None })
+    }
+
+    /// The unsigned transaction.
+    pub fn payload(&self) -> &Transaction {
+        &self.payload
+    }
+
+    pub fn signatures(&self) -> Option<&DetachedSignatures> {
+        self.signatures.as_ref()
+    }
+
+    /// Hash to be signed, recomputed from the payload rather than taken from its `hash` field.
+    pub fn signing_hash(&self) -> Result<String, PresignedTxError> {
+        self.payload.compute_hash().map_err(|_| PresignedTxError::HashMismatch)
+    }
+
+    /// Exact bytes that are hashed into [`Self::signing_hash`]; see [`UnsignedTransaction::signing_payload`].
+    pub fn signing_payload(&self) -> Vec<u8> {
+        self.payload.signing_payload()
+    }
+
+    /// Signs the payload on the offline machine with the main and derived signers.
+    pub fn sign_detached(&self, main: &dyn Signer, derived: &dyn Signer) -> crate::error::Result<DetachedSignatures> {
+        let hash = self.signing_hash()?;
+        let digest: [u8; 32] = hex::decode(&hash)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(PresignedTxError::HashMismatch)?;
+        let (sig_main, pub_main) = sign_digest_checked(main, &digest)?;
+        let (sig_derived, pub_derived) = sign_digest_checked(derived, &digest)?;
+        Ok(DetachedSignatures {
+            hash,
+            sig_main: hex::encode(sig_main.serialize_compact()),
+            sig_derived: hex::encode(sig_derived.serialize_compact()),
+            pub_main: hex::encode(pub_main.serialize()),
+            pub_derived: hex::encode(pub_derived.serialize()),
+        })
+    }
+
+    /// Attaches signatures made by [`Self::sign_detached`] (or any signer of the same hash). They must be over
+    /// this payload's hash and verify as the transaction's signatures; otherwise nothing is attached.
+    pub fn attach_signatures(&mut self, signatures: DetachedSignatures) -> Result<(), PresignedTxError> {
+        let expected = self.signing_hash()?;
+        if !signatures.hash.eq_ignore_ascii_case(&expected) {
+            return Err(PresignedTxError::WrongTransaction { expected, signed: signatures.hash });
+        }
+        let signed = Self::signed(&self.payload, &signatures);
+        match signed.verify_signatures() {
+            Ok(true) => {
+                self.signatures = Some(signatures);
+                Ok(())
+            }
+            Ok(false) => Err(PresignedTxError::InvalidSignatures("one or both signatures are invalid".to_string())),
+            Err(e) => Err(PresignedTxError::InvalidSignatures(e.to_string())),
+        }
+    }
+
+    /// The signed transaction, ready to submit. Checks the hash and signatures again, so a container edited
+    /// after [`Self::attach_signatures`] (e.g. deserialized from a file) is still rejected.
+    pub fn into_transaction(self) -> Result<Transaction, PresignedTxError> {
+        if !self.payload.validate_hash().unwrap_or(false) {
+            return Err(PresignedTxError::HashMismatch);
+        }
+        let signatures = self.signatures.ok_or(PresignedTxError::Unsigned)?;
+        let mut presigned = Self { payload: self.payload, signatures: None };
+        presigned.attach_signatures(signatures.clone())?;
+        Ok(Self::signed(&presigned.payload, &signatures))
+    }
+
+    fn signed(payload: &Transaction, signatures: &DetachedSignatures) -> Transaction {
+        let mut tx = payload.clone();
+        tx.sig_main = signatures.sig_main.clone();
+        tx.sig_derived = signatures.sig_derived.clone();
+        tx.pub_main = Some(signatures.pub_main.clone());
+        tx.pub_derived = Some(signatures.pub_derived.clone());
+        tx
+    }
+}
+
+/// A transaction without signatures, exported for a signer outside this crate.
+///
+/// Serializes as the transaction JSON with empty signatures; deserializing checks the hash against the data.
+/// Public keys are not covered by the hash and are kept, since `pub_derived` is needed to check the signatures.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Transaction", into = "Transaction")]
+pub struct UnsignedTransaction {
+    tx: Transaction,
+}
+
+impl UnsignedTransaction {
+    /// Wraps `tx`, dropping its signatures. Errors if its hash does not match its data.
+    pub fn new(mut tx: Transaction) -> Result<Self, PresignedTxError> {
+        if !tx.validate_hash().unwrap_or(false) {
+            return Err(PresignedTxError::HashMismatch);
+        }
+        tx.sig_main.clear();
+        tx.sig_derived.clear();
+        Ok(Self { tx })
+    }
+
+    /// Sets the public keys the signatures will be checked against; `pub_main` defaults to `from`.
+    pub fn with_public_keys(mut self, pub_main: Option<String>, pub_derived: String) -> Self {
+        self.tx.pub_main = pub_main;
+        self.tx.pub_derived = Some(pub_derived);
+        self
+    }
+
+    pub fn transaction(&self) -> &Transaction {
+        &self.tx
+    }
+
+    /// Hash both signatures must be over (hex): the SHA256 of [`Self::signing_payload`].
+    pub fn hash(&self) -> &str {
+        &self.tx.hash
+    }
+
+    /// Exact bytes that are hashed: `PlatariumSignature:` and the payload JSON for a V1 transaction,
+    /// `PlatariumTx/v2:` and its binary encoding for V2.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        self.tx.signing_payload()
+    }
+
+    /// The signed transaction. `sig_main` and `sig_derived` are compact ECDSA signatures (hex) over [`Self::hash`];
+    /// both must verify against the transaction's public keys.
+    pub fn with_signatures(self, sig_main: &str, sig_derived: &str) -> Result<Transaction, PresignedTxError> {
+        let mut tx = self.tx;
+        tx.sig_main = sig_main.to_string();
+        tx.sig_derived = sig_derived.to_string();
+        match tx.verify_signatures() {
+            Ok(true) => Ok(tx),
+            Ok(false) => Err(PresignedTxError::InvalidSignatures("one or both signatures are invalid".to_string())),
+            Err(e) => Err(PresignedTxError::InvalidSignatures(e.to_string())),
+        }
+    }
+}
+
+impl TryFrom<Transaction> for UnsignedTransaction {
+    type Error = PresignedTxError;
+
+    fn try_from(tx: Transaction) -> Result<Self, Self::Error> {
+        Self::new(tx)
+    }
+}
+
+impl From<UnsignedTransaction> for Transaction {
+    fn from(unsigned: UnsignedTransaction) -> Self {
+        unsigned.tx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::asset::Asset;
+    use crate::signer::SoftwareSigner;
+    use sha2::{Digest, Sha256};
+    use std::collections::HashSet;
+
+    fn unsigned(from: &str) -> Transaction {
+        Transaction::new(
+            from.to_string(),
+            "receiver".to_string(),
+            Asset::PLP,
+            100,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_offline_round_trip() {
+        let main = SoftwareSigner::from_slice(&[1; 32]).unwrap();
+        let derived = SoftwareSigner::from_slice(&[2; 32]).unwrap();
+        let from = format!("Px{}", hex::encode(main.public_key().unwrap().serialize()));
+
+        let online = PresignedTransaction::new(unsigned(&from)).unwrap();
+        let offline: PresignedTransaction = serde_json::from_str(&serde_json::to_string(&online).unwrap()).unwrap();
+        let signatures = offline.sign_detached(&main, &derived).unwrap();
+
+        let mut online = online;
+        online.attach_signatures(signatures).unwrap();
+        let tx = online.into_transaction().unwrap();
+        assert!(tx.verify_signatures().unwrap());
+        assert!(tx.validate_basic().is_ok());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_signatures() {
+        let main = SoftwareSigner::from_slice(&[1; 32]).unwrap();
+        let derived = SoftwareSigner::from_slice(&[2; 32]).unwrap();
+        let from = format!("Px{}", hex::encode(main.public_key().unwrap().serialize()));
+        let mut presigned = PresignedTransaction::new(unsigned(&from)).unwrap();
+
+        let mut other = unsigned(&from);
+        other.nonce = 1;
+        other.hash = other.compute_hash().unwrap();
+        let foreign = PresignedTransaction::new(other).unwrap().sign_detached(&main, &derived).unwrap();
+        assert!(matches!(presigned.attach_signatures(foreign.clone()), Err(PresignedTxError::WrongTransaction { .. })));
+
+        let forged = DetachedSignatures { hash: presigned.signing_hash().unwrap(), ..foreign };
+        assert!(matches!(presigned.attach_signatures(forged), Err(PresignedTxError::InvalidSignatures(_))));
+        assert_eq!(presigned.clone().into_transaction(), Err(PresignedTxError::Unsigned));
+
+        let mut tampered = unsigned(&from);
+        tampered.amount = 5;
+        assert_eq!(PresignedTransaction::new(tampered), Err(PresignedTxError::HashMismatch));
+    }
+
+    #[test]
+    fn test_unsigned_export_signed_externally() {
+        let main = SoftwareSigner::from_slice(&[1; 32]).unwrap();
+        let derived = SoftwareSigner::from_slice(&[2; 32]).unwrap();
+        let from = format!("Px{}", hex::encode(main.public_key().unwrap().serialize()));
+        let pub_main = hex::encode(main.public_key().unwrap().serialize());
+        let pub_derived = hex::encode(derived.public_key().unwrap().serialize());
+        let exported =
+            UnsignedTransaction::new(unsigned(&from)).unwrap().with_public_keys(Some(pub_main), pub_derived);
+        let json = serde_json::to_string(&exported).unwrap();
+
+        // The cold signer only sees the preimage bytes.
+        let digest: [u8; 32] = Sha256::digest(exported.signing_payload()).into();
+        assert_eq!(hex::encode(digest), exported.hash());
+        let sig = |signer: &SoftwareSigner| {
+            let mut signature = signer.sign_digest(&digest).unwrap();
+            signature.normalize_s();
+            hex::encode(signature.serialize_compact())
+        };
+
+        let imported: UnsignedTransaction = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            imported.clone().with_signatures(&sig(&derived), &sig(&main)),
+            Err(PresignedTxError::InvalidSignatures(_))
+        ));
+        let tx = imported.with_signatures(&sig(&main), &sig(&derived)).unwrap();
+        assert!(tx.validate_basic().is_ok());
+
+        let tampered = json.replace("\"amount\":100", "\"amount\":101");
+        assert!(serde_json::from_str::<UnsignedTransaction>(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_unsigned_round_trip() {
+        let main = SoftwareSigner::from_slice(&[1; 32]).unwrap();
+        let derived = SoftwareSigner::from_slice(&[2; 32]).unwrap();
+        let from = format!("Px{}", hex::encode(main.public_key().unwrap().serialize()));
+        let signed = PresignedTransaction::new(unsigned(&from)).unwrap();
+        let signatures = signed.sign_detached(&main, &derived).unwrap();
+        let mut tx = unsigned(&from);
+        tx.sig_main = signatures.sig_main.clone();
+        tx.sig_derived = signatures.sig_derived.clone();
+        tx.pub_main = Some(signatures.pub_main.clone());
+        tx.pub_derived = Some(signatures.pub_derived.clone());
+
+        // Transaction -> UnsignedTransaction -> JSON -> UnsignedTransaction -> signed Transaction.
+        let exported = UnsignedTransaction::try_from(tx.clone()).unwrap();
+        assert!(exported.transaction().sig_main.is_empty() && exported.transaction().sig_derived.is_empty());
+        assert_eq!(exported.signing_payload(), signed.signing_payload());
+        let imported: UnsignedTransaction = serde_json::from_str(&serde_json::to_string(&exported).unwrap()).unwrap();
+        assert_eq!(imported, exported);
+        assert_eq!(Transaction::from(imported.clone()), *exported.transaction());
+        assert_eq!(imported.with_signatures(&signatures.sig_main, &signatures.sig_derived).unwrap(), tx);
+    }
+}
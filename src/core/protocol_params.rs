@@ -0,0 +1,192 @@
+//! Governance-tunable protocol parameters, versioned by activation height.
+//!
+//! Genesis parameters come from the chainspec (`ChainSpec::protocol_params`, defaults when absent) and
+//! are range-checked when the chainspec is loaded. Governance schedules replacements with
+//! [`ProtocolParamsSchedule::schedule`]; consumers such as `slashing::apply_slash_at` read the set
+//! active at the height they act on, so every node applies the same penalties for the same block.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::core::slashing::SlashingReason;
+use crate::core::state::{BURN_ADDRESS, INSURANCE_FUND_ADDRESS, TREASURY_ADDRESS};
+use crate::error::{PlatariumError, Result};
+
+/// Penalty for one slashing reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlashingEntry {
+    /// Reputation penalty as a percentage of `SCORE_SCALE` (0..=100).
+    pub reputation_penalty_pct: u64,
+    /// Stake removed from the node, in stake units.
+    pub stake_slash: u128,
+}
+
+/// Upper bound on a single stake slash accepted by validation.
+pub const MAX_STAKE_SLASH: u128 = 1_000_000_000;
+
+/// Every slashing reason; a parameter set must define all of them.
+pub const ALL_SLASHING_REASONS: [SlashingReason; 5] = [
+    SlashingReason::NoVote,
+    SlashingReason::AgainstMajority,
+    SlashingReason::Equivocation,
+    SlashingReason::InvalidTx,
+    SlashingReason::MessageFlood,
+];
+
+/// Where stake removed by slashing is credited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlashDestination {
+    /// Credited to `BURN_ADDRESS`, which no transaction can spend from (see its docs): the amount leaves circulation
+    /// but stays accounted for.
+    #[default]
+    Burn,
+    /// Credited to `TREASURY_ADDRESS`.
+    Treasury,
+    /// Credited to `INSURANCE_FUND_ADDRESS`.
+    InsuranceFund,
+}
+
+impl SlashDestination {
+    /// State address that receives the slashed stake.
+    pub fn address(self) -> &'static str {
+        match self {
+            SlashDestination::Burn => BURN_ADDRESS,
+            SlashDestination::Treasury => TREASURY_ADDRESS,
+            SlashDestination::InsuranceFund => INSURANCE_FUND_ADDRESS,
+        }
+    }
+}
+
+/// One complete set of protocol parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolParams {
+    /// Per-reason slashing penalties.
+    pub slashing: BTreeMap<SlashingReason, SlashingEntry>,
+    /// Destination of slashed stake; burn when absent from the chainspec.
+    #[serde(default)]
+    pub slash_destination: SlashDestination,
+}
+
+impl Default for ProtocolParams {
+    fn default() -> Self {
+        let entry = |reputation_penalty_pct, stake_slash| SlashingEntry {
+            reputation_penalty_pct,
+            stake_slash,
+        };
+        Self {
+            slashing: BTreeMap::from([
+                (SlashingReason::NoVote, entry(2, 1)),
+                (SlashingReason::AgainstMajority, entry(3, 2)),
+                (SlashingReason::Equivocation, entry(15, 100)),
+                (SlashingReason::InvalidTx, entry(10, 50)),
+                (SlashingReason::MessageFlood, entry(5, 10)),
+            ]),
+            slash_destination: SlashDestination::Burn,
+        }
+    }
+}
+
+impl ProtocolParams {
+    /// Checks that every reason has an entry and all values are in range.
+    pub fn validate(&self) -> Result<()> {
+        for reason in ALL_SLASHING_REASONS {
+            let entry = self.slashing.get(&reason).ok_or_else(|| {
+                PlatariumError::Validation(format!("protocol params: missing slashing entry for {:?}", reason))
+            })?;
+            if entry.reputation_penalty_pct > 100 {
+                return Err(PlatariumError::Validation(format!(
+                    "protocol params: {:?} reputation penalty {}% exceeds 100%",
+                    reason, entry.reputation_penalty_pct
+                )));
+            }
+            if entry.stake_slash > MAX_STAKE_SLASH {
+                return Err(PlatariumError::Validation(format!(
+                    "protocol params: {:?} stake slash {} exceeds {}",
+                    reason, entry.stake_slash, MAX_STAKE_SLASH
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Penalty for `reason`. Validated sets always contain every reason.
+    pub fn slashing_entry(&self, reason: SlashingReason) -> SlashingEntry {
+        self.slashing
+            .get(&reason)
+            .copied()
+            .unwrap_or_else(|| ProtocolParams::default().slashing[&reason])
+    }
+}
+
+/// Parameter sets keyed by activation height; the set at height `h` is the latest one activated at or before `h`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolParamsSchedule {
+    activations: BTreeMap<u64, ProtocolParams>,
+}
+
+impl Default for ProtocolParamsSchedule {
+    fn default() -> Self {
+        Self::from_genesis(ProtocolParams::default())
+    }
+}
+
+impl ProtocolParamsSchedule {
+    /// Schedule with `genesis` active from height 0.
+    pub fn from_genesis(genesis: ProtocolParams) -> Self {
+        Self {
+            activations: BTreeMap::from([(0, genesis)]),
+        }
+    }
+
+    /// Parameters active at `height`.
+    pub fn at(&self, height: u64) -> &ProtocolParams {
+        self.activations
+            .range(..=height)
+            .next_back()
+            .map(|(_, p)| p)
+            .expect("schedule always has a genesis entry")
+    }
+
+    /// Schedules `params` to take effect at `activation_height`. Must validate and must not precede the latest activation.
+    pub fn schedule(&mut self, activation_height: u64, params: ProtocolParams) -> Result<()> {
+        params.validate()?;
+        let latest = self.activations.keys().next_back().copied().unwrap_or(0);
+        if activation_height <= latest {
+            return Err(PlatariumError::Validation(format!(
+                "protocol params: activation height {} must be after {}",
+                activation_height, latest
+            )));
+        }
+        self.activations.insert(activation_height, params);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_params_validate_and_schedule_by_height() {
+        let defaults = ProtocolParams::default();
+        defaults.validate().unwrap();
+        let mut tuned = defaults.clone();
+        tuned.slashing.get_mut(&SlashingReason::NoVote).unwrap().stake_slash = 7;
+
+        let mut schedule = ProtocolParamsSchedule::default();
+        schedule.schedule(100, tuned.clone()).unwrap();
+        assert_eq!(schedule.at(99), &defaults);
+        assert_eq!(schedule.at(100).slashing_entry(SlashingReason::NoVote).stake_slash, 7);
+        assert!(schedule.schedule(50, tuned).is_err());
+    }
+
+    #[test]
+    fn test_validation_rejects_out_of_range_and_missing() {
+        let mut p = ProtocolParams::default();
+        p.slashing.get_mut(&SlashingReason::Equivocation).unwrap().reputation_penalty_pct = 101;
+        assert!(p.validate().is_err());
+        let mut p = ProtocolParams::default();
+        p.slashing.remove(&SlashingReason::InvalidTx);
+        assert!(p.validate().is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Execution receipts.
+//!
+//! `ExecutionLogic::execute_with_receipt` runs one transaction and returns a [`Receipt`]: whether it applied, why
+//! not, the fee charged and the net balance change of every account it moved funds of, at its position in the block.
+//! Wallets and indexers read outcomes from receipts instead of re-simulating. A block's receipts are committed as
+//! `storage::ReceiptRecord`s ([`Receipt::to_record`]) and read back with `storage::list_block_receipts`.
+//!
+//! A rejected transaction is not applied: its receipt records the error, charges no fee and has no balance deltas.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::core::asset::Asset;
+use crate::core::bridge::BRIDGE_ADDRESS;
+use crate::core::state::{Address, State, BURN_ADDRESS, STAKING_ADDRESS, TREASURY_ADDRESS};
+use crate::core::transaction::{Transaction, TxKind};
+use crate::error::PlatariumError;
+use crate::storage::{ReceiptRecord, FEE_ASSET};
+
+/// Net change of one account's balance of one asset. Fee balances use the asset code `storage::FEE_ASSET`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceDelta {
+    pub address: Address,
+    pub asset: String,
+    pub delta: i128,
+}
+
+/// Outcome of one transaction in a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub tx_hash: String,
+    pub success: bool,
+    /// `PlatariumError::code` of the rejection; `None` on success.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// Rejection message; `None` on success.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Fee paid to the treasury (μPLP).
+    pub fee_charged: u128,
+    /// Part of `fee_charged` credited back to the sender under a `FeeRebatePolicy` (μPLP); included in
+    /// `balance_deltas`.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub rebate_uplp: u128,
+    /// Account charged `fee_charged`: the sender for every kind (the spender of a `TransferFrom`, the relayer of a
+    /// `BridgeClaim`). `None` when rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<Address>,
+    /// Nonzero balance changes, sorted by address then asset.
+    pub balance_deltas: Vec<BalanceDelta>,
+    pub block_number: u64,
+    /// Position of the transaction in the block.
+    pub index: u32,
+}
+
+impl Receipt {
+    pub(crate) fn success(tx: &Transaction, balance_deltas: Vec<BalanceDelta>, block_number: u64, index: u32) -> Self {
+        Self {
+            tx_hash: tx.hash.clone(),
+            success: true,
+            error_code: None,
+            error: None,
+            fee_charged: tx.fee_uplp,
+            rebate_uplp: 0,
+            fee_payer: Some(tx.from.clone()),
+            balance_deltas,
+            block_number,
+            index,
+        }
+    }
+
+    pub(crate) fn failure(tx: &Transaction, error: &PlatariumError, block_number: u64, index: u32) -> Self {
+        Self {
+            tx_hash: tx.hash.clone(),
+            success: false,
+            error_code: Some(error.code().to_string()),
+            error: Some(error.to_string()),
+            fee_charged: 0,
+            rebate_uplp: 0,
+            fee_payer: None,
+            balance_deltas: Vec::new(),
+            block_number,
+            index,
+        }
+    }
+
+    /// Storage form, with status `ok` or `failed`.
+    pub fn to_record(&self) -> ReceiptRecord {
+        ReceiptRecord {
+            tx_hash: self.tx_hash.clone(),
+            status: if self.success { "ok" } else { "failed" }.to_string(),
+            fee_uplp: u64::try_from(self.fee_charged).unwrap_or(u64::MAX),
+            block_height: self.block_number,
+            rebate_uplp: u64::try_from(self.rebate_uplp).unwrap_or(u64::MAX),
+            events: Vec::new(),
+            fee_payer: self.fee_payer.clone(),
+            index: Some(self.index),
+            error_code: self.error_code.clone(),
+            balance_deltas: self.balance_deltas.clone(),
+        }
+    }
+}
+
+fn is_zero(n: &u128) -> bool {
+    *n == 0
+}
+
+/// Balances `tx` can change, read before execution: sender, receiver, delegating owner and multi-transfer
+/// recipients, plus the treasury, burn, staking and bridge accounts, in the transaction's assets, PLP and fee μPLP.
+pub(crate) struct BalanceProbe {
+    /// (address, asset code) -> asset (`None` for fee μPLP) and balance.
+    before: BTreeMap<(Address, String), (Option<Asset>, u128)>,
+}
+
+impl BalanceProbe {
+    pub(crate) fn new(state: &State, tx: &Transaction) -> Self {
+        let mut addresses = vec![tx.from.clone(), tx.to.clone()];
+        let mut assets = vec![tx.asset.clone(), Asset::PLP];
+        match &tx.kind {
+            TxKind::TransferFrom { owner } => addresses.push(owner.clone()),
+            TxKind::MultiTransfer { outputs } => {
+                for output in outputs {
+                    addresses.push(output.to.clone());
+                    assets.push(output.asset.clone());
+                }
+            }
+            _ => {}
+        }
+        addresses.extend([TREASURY_ADDRESS, BURN_ADDRESS, STAKING_ADDRESS, BRIDGE_ADDRESS].map(String::from));
+        let mut before = BTreeMap::new();
+        for address in &addresses {
+            for asset in &assets {
+                let balance = state.get_asset_balance(address, asset);
+                before.insert((address.clone(), asset.as_canonical()), (Some(asset.clone()), balance));
+            }
+            before.insert((address.clone(), FEE_ASSET.to_string()), (None, state.get_uplp_balance(address)));
+        }
+        Self { before }
+    }
+
+    /// Nonzero changes since [`Self::new`].
+    pub(crate) fn deltas(&self, state: &State) -> Vec<BalanceDelta> {
+        self.before
+            .iter()
+            .filter_map(|((address, code), (asset, before))| {
+                let before = *before;
+                let after = match asset {
+                    Some(asset) => state.get_asset_balance(address, asset),
+                    None => state.get_uplp_balance(address),
+                };
+                let delta = if after >= before {
+                    i128::try_from(after - before).unwrap_or(i128::MAX)
+                } else {
+                    -i128::try_from(before - after).unwrap_or(i128::MAX)
+                };
+                (delta != 0).then(|| BalanceDelta {
+                    address: address.clone(),
+                    asset: code.clone(),
+                    delta,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,96 @@
+//! One-shot account recovery through the derived key.
+//!
+//! **Stability:** experimental (crate-root re-exports behind the `experimental` feature). The transaction kinds are wired into execution and are consensus-relevant once enabled on a network.
+//!
+//! If an account's main key is compromised, the holder of its registered derived key can submit `InitiateRecovery { new_main_key }`, signed by the derived key alone. The rotation is held as a `PendingRotation` for `RECOVERY_DELAY_BLOCKS`; until then the current key pair can cancel it with a normal dual-signed `CancelRecovery`. Once the delay has passed, the new main key is in effect and transactions from the account must carry it as `pub_main`.
+//!
+//! The delay is what makes recovery safe against a stolen derived key: the legitimate owner still holds both keys and has the whole window to cancel. A stolen main key alone can neither cancel (it lacks the derived signature) nor start a recovery.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::state::Address;
+use crate::error::PlatariumError;
+use crate::signature::normalize_public_key_hex;
+
+/// Blocks between `InitiateRecovery` and the new main key taking effect.
+pub const RECOVERY_DELAY_BLOCKS: u64 = 1_000;
+
+/// Errors produced by recovery transactions.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryError {
+    #[error("Recovery already pending for {0}")]
+    AlreadyPending(Address),
+
+    #[error("No pending recovery for {0}")]
+    NonePending(Address),
+
+    #[error("Recovery window for {0} has closed")]
+    WindowClosed(Address),
+
+    #[error("Invalid new main key: {0}")]
+    InvalidKey(String),
+
+    #[error("Main key mismatch for {address}: expected {expected}, got {got}")]
+    MainKeyMismatch {
+        address: Address,
+        expected: String,
+        got: String,
+    },
+}
+
+impl From<RecoveryError> for PlatariumError {
+    fn from(err: RecoveryError) -> Self {
+        PlatariumError::State(format!("Recovery error: {}", err))
+    }
+}
+
+/// A main key rotation waiting out its delay window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingRotation {
+    /// New main public key (hex, no `Px` prefix).
+    pub new_main_key: String,
+    /// Height at which the recovery was initiated.
+    pub initiated_height: u64,
+    /// First height at which `new_main_key` is in effect.
+    pub effective_height: u64,
+}
+
+impl PendingRotation {
+    pub fn new(new_main_key: String, initiated_height: u64) -> Self {
+        Self {
+            new_main_key,
+            initiated_height,
+            effective_height: initiated_height.saturating_add(RECOVERY_DELAY_BLOCKS),
+        }
+    }
+
+    /// True once the delay window has passed at `height`.
+    pub fn is_effective_at(&self, height: u64) -> bool {
+        height >= self.effective_height
+    }
+}
+
+/// Normalizes the new main key of a recovery to lowercase hex without `Px`. Errors if it is not a valid public key.
+pub fn normalize_main_key(key: &str) -> Result<String, RecoveryError> {
+    normalize_public_key_hex(key).map_err(|e| RecoveryError::InvalidKey(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_rotation_window() {
+        let r = PendingRotation::new("02aa".to_string(), 10);
+        assert_eq!(r.effective_height, 10 + RECOVERY_DELAY_BLOCKS);
+        assert!(!r.is_effective_at(10 + RECOVERY_DELAY_BLOCKS - 1));
+        assert!(r.is_effective_at(10 + RECOVERY_DELAY_BLOCKS));
+    }
+
+    #[test]
+    fn test_normalize_main_key() {
+        let g = "0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798";
+        assert_eq!(normalize_main_key(&format!("Px{}", g)).unwrap(), g.to_ascii_lowercase());
+        assert!(normalize_main_key("02aa").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Scoped, expiring session keys.
+//!
+//! **Stability:** experimental; scope fields may still change. Crate-root re-exports require the `experimental` feature.
+//!
+//! An account registers a session key with a dual-signed `RegisterSessionKey` transaction, fixing the one asset the key may move, a per-block amount cap and an expiry height. An app holding only the session key can then submit `SessionTransfer` transactions signed by that key; execution checks the scope against state. The account pays the fees and its nonce advances as usual. `RevokeSessionKey` removes a key before it expires.
+//!
+//! The cap applies to transferred amounts only; fees are charged on top.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::state::Address;
+use crate::error::PlatariumError;
+
+/// Errors produced by session key registration and use.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SessionKeyError {
+    #[error("Session key {key} is not registered for {address}")]
+    NotRegistered { address: Address, key: String },
+
+    #[error("Session key {0} is already registered")]
+    AlreadyRegistered(String),
+
+    #[error("Session key expired at height {expiry_height} (current {height})")]
+    Expired { expiry_height: u64, height: u64 },
+
+    #[error("Session key is scoped to {allowed}, not {got}")]
+    AssetNotAllowed { allowed: String, got: String },
+
+    #[error("Session key cap exceeded in this block: spending {required}, remaining {remaining}")]
+    CapExceeded { required: u128, remaining: u128 },
+
+    #[error("Invalid session key scope: {0}")]
+    InvalidScope(String),
+}
+
+impl From<SessionKeyError> for PlatariumError {
+    fn from(err: SessionKeyError) -> Self {
+        PlatariumError::State(format!("Session key error: {}", err))
+    }
+}
+
+/// Scope and per-block usage of one registered session key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionKey {
+    /// Canonical asset string the key may transfer.
+    pub asset: String,
+    /// Maximum total amount the key may transfer within one block.
+    pub max_amount_per_block: u128,
+    /// First height at which the key is no longer accepted.
+    pub expiry_height: u64,
+    /// Height of the block `spent_in_block` refers to.
+    #[serde(default)]
+    pub spent_height: u64,
+    /// Amount already transferred in block `spent_height`.
+    #[serde(default)]
+    pub spent_in_block: u128,
+}
+
+impl SessionKey {
+    pub fn new(asset: String, max_amount_per_block: u128, expiry_height: u64) -> Self {
+        Self {
+            asset,
+            max_amount_per_block,
+            expiry_height,
+            spent_height: 0,
+            spent_in_block: 0,
+        }
+    }
+
+    /// Validates a new scope at registration height `height`: non-zero cap and an expiry in the future.
+    pub fn validate(&self, height: u64) -> Result<(), SessionKeyError> {
+        if self.max_amount_per_block == 0 {
+            return Err(SessionKeyError::InvalidScope("max_amount_per_block must be > 0".to_string()));
+        }
+        if self.expiry_height <= height {
+            return Err(SessionKeyError::InvalidScope(format!(
+                "expiry height {} is not after current height {}",
+                self.expiry_height, height
+            )));
+        }
+        Ok(())
+    }
+
+    /// Amount the key may still transfer in the block at `height`.
+    pub fn remaining_at(&self, height: u64) -> u128 {
+        let spent = if self.spent_height == height { self.spent_in_block } else { 0 };
+        self.max_amount_per_block.saturating_sub(spent)
+    }
+
+    /// Checks that the key may transfer `amount` of `asset` at `height`.
+    pub fn check_spend(&self, asset: &str, amount: u128, height: u64) -> Result<(), SessionKeyError> {
+        if height >= self.expiry_height {
+            return Err(SessionKeyError::Expired {
+                expiry_height: self.expiry_height,
+                height,
+            });
+        }
+        if asset != self.asset {
+            return Err(SessionKeyError::AssetNotAllowed {
+                allowed: self.asset.clone(),
+                got: asset.to_string(),
+            });
+        }
+        let remaining = self.remaining_at(height);
+        if amount > remaining {
+            return Err(SessionKeyError::CapExceeded {
+                required: amount,
+                remaining,
+            });
+        }
+        Ok(())
+    }
+
+    /// Records `amount` as spent in the block at `height`. Call after `check_spend`.
+    pub fn record_spend(&mut self, amount: u128, height: u64) {
+        if self.spent_height != height {
+            self.spent_height = height;
+            self.spent_in_block = 0;
+        }
+        self.spent_in_block = self.spent_in_block.saturating_add(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap_resets_each_block() {
+        let mut key = SessionKey::new("PLP".to_string(), 10, 100);
+        key.check_spend("PLP", 7, 5).unwrap();
+        key.record_spend(7, 5);
+        assert_eq!(
+            key.check_spend("PLP", 4, 5),
+            Err(SessionKeyError::CapExceeded { required: 4, remaining: 3 })
+        );
+        key.check_spend("PLP", 10, 6).unwrap();
+        key.record_spend(10, 6);
+        assert_eq!(key.remaining_at(6), 0);
+    }
+
+    #[test]
+    fn test_scope_and_expiry() {
+        let key = SessionKey::new("Token:GOLD".to_string(), 10, 100);
+        assert!(matches!(key.check_spend("PLP", 1, 5), Err(SessionKeyError::AssetNotAllowed { .. })));
+        assert!(matches!(key.check_spend("Token:GOLD", 1, 100), Err(SessionKeyError::Expired { .. })));
+        assert!(key.validate(99).is_ok());
+        assert!(key.validate(100).is_err());
+        assert!(SessionKey::new("PLP".to_string(), 0, 100).validate(0).is_err());
+    }
+}
@@ -1,22 +1,26 @@
 //! Slashing & Stability Engine (Module 5).
 //!
 //! Nodes are penalized for: failing to vote, voting against the majority, equivocation (signing two different blocks at the same height), or confirming an invalid transaction.
-//! Penalties: reputation is reduced by a reason-dependent amount; stake is reduced by a minor slash. If reputation falls below the threshold, the node is suspended.
+//! Penalties: reputation is reduced by a reason-dependent amount; stake is reduced by a minor slash. If reputation falls below the threshold, the node is suspended. Amounts come from `ProtocolParams::slashing`: every entry point takes the parameter set active at the current height (`apply_slash_at` looks it up in a `ProtocolParamsSchedule`). Every entry point also moves the removed stake from the node's on-chain stake to the set's `slash_destination` in state.
 //!
-//! **Step 6:** SlashingReason: NoVote, AgainstMajority, Equivocation, InvalidTx. apply_slash / apply_slash_batch; penalty_amounts(reason); SUSPENSION_THRESHOLD → status = Suspended. **Integration:** For “vote against majority” (L1/L2), call `apply_slash(registry, node_id, SlashingReason::AgainstMajority)` or `apply_slash_batch` on the list of nodes to penalize for full penalty (reputation, stake, and suspension check).
+//! **Step 6:** SlashingReason: NoVote, AgainstMajority, Equivocation, InvalidTx, MessageFlood. apply_slash / apply_slash_batch; penalty_amounts(reason); SUSPENSION_THRESHOLD → status = Suspended. **Integration:** For “vote against majority” (L1/L2), call `apply_slash(registry, state, params, node_id, SlashingReason::AgainstMajority)` with the parameter set active at the block's height or `apply_slash_batch` on the list of nodes to penalize for full penalty (reputation, stake, and suspension check).
 //!
 //! # Determinism
 //! Same (node_id, reason) yields the same penalty amounts; all arithmetic is integer-only.
 
-use crate::core::node_registry::{NodeId, NodeRegistry, SCORE_SCALE};
+use crate::core::events::Event;
+use crate::core::node_registry::{NodeId, NodeRegistry, NodeStatus, SCORE_SCALE};
+use crate::core::protocol_params::{ProtocolParams, ProtocolParamsSchedule};
+use crate::core::state::State;
 use crate::error::{PlatariumError, Result};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Reputation score below this value results in node suspension (approximately 10% of SCORE_SCALE).
 pub const SUSPENSION_THRESHOLD: u64 = 100_000;
 
 /// Reason for slashing; determines the reputation penalty and stake slash amounts.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum SlashingReason {
     /// Node did not submit a vote when selected.
     NoVote,
@@ -26,26 +30,14 @@ pub enum SlashingReason {
     Equivocation,
     /// Node confirmed an invalid transaction.
     InvalidTx,
+    /// Node exceeded the per-slot vote or proposal rate limit.
+    MessageFlood,
 }
 
-/// Returns the reputation penalty (subtracted from ReputationScore) for the given reason.
-fn reputation_penalty_for(reason: SlashingReason) -> u64 {
-    match reason {
-        SlashingReason::NoVote => (SCORE_SCALE * 2) / 100,
-        SlashingReason::AgainstMajority => (SCORE_SCALE * 3) / 100,
-        SlashingReason::Equivocation => (SCORE_SCALE * 15) / 100,
-        SlashingReason::InvalidTx => (SCORE_SCALE * 10) / 100,
-    }
-}
-
-/// Returns the stake slash (subtracted from stake) for the given reason.
-fn stake_slash_for(reason: SlashingReason) -> u128 {
-    match reason {
-        SlashingReason::NoVote => 1,
-        SlashingReason::AgainstMajority => 2,
-        SlashingReason::Equivocation => 100,
-        SlashingReason::InvalidTx => 50,
-    }
+/// Returns (reputation penalty, stake slash) for `reason` under `params`.
+fn penalties_for(params: &ProtocolParams, reason: SlashingReason) -> (u64, u128) {
+    let entry = params.slashing_entry(reason);
+    ((SCORE_SCALE * entry.reputation_penalty_pct) / 100, entry.stake_slash)
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -63,57 +55,131 @@ impl From<SlashingError> for PlatariumError {
     }
 }
 
-/// Applies slashing for a single node and reason: reduces reputation and stake by the configured amounts. Sets status to Suspended if reputation falls below the threshold.
+/// Applies slashing for a single node and reason: reduces reputation and stake by the amounts in `params` (the set active at the current height, e.g. `ProtocolParamsSchedule::at`) and credits the removed stake in `state` to the set's `slash_destination`. Sets status to Suspended if reputation falls below the threshold. Emits the slash events at the state's block height (see `apply_slash_with_threshold`). Returns the stake removed.
 pub fn apply_slash(
     registry: &NodeRegistry,
+    state: &State,
+    params: &ProtocolParams,
     node_id: &NodeId,
     reason: SlashingReason,
-) -> Result<()> {
-    apply_slash_with_threshold(registry, node_id, reason, SUSPENSION_THRESHOLD)
+) -> Result<u128> {
+    apply_slash_with_threshold(registry, state, params, node_id, reason, SUSPENSION_THRESHOLD)
+}
+
+/// Applies slashing with the parameter set active at `height` in `schedule`, and reports the events at `height`. Use this on the consensus path so governance changes take effect at their activation height.
+/// Otherwise identical to `apply_slash`.
+pub fn apply_slash_at(
+    registry: &NodeRegistry,
+    state: &State,
+    schedule: &ProtocolParamsSchedule,
+    height: u64,
+    node_id: &NodeId,
+    reason: SlashingReason,
+) -> Result<u128> {
+    slash(registry, state, schedule.at(height), height, node_id, reason, SUSPENSION_THRESHOLD)
 }
 
 /// Applies slashing with a custom suspension threshold (otherwise identical to `apply_slash`).
 pub fn apply_slash_with_threshold(
     registry: &NodeRegistry,
+    state: &State,
+    params: &ProtocolParams,
     node_id: &NodeId,
     reason: SlashingReason,
     suspension_threshold: u64,
-) -> Result<()> {
+) -> Result<u128> {
+    slash(registry, state, params, state.block_height(), node_id, reason, suspension_threshold)
+}
+
+/// Shared slash path. The stake removed is taken from the node's on-chain stake (`State::slash_stake` on `Node::stake_account`, bonded then unbonding) and moved to the set's `slash_destination`. A part the account does not hold on chain (a registry-only stake) only leaves the registry: no PLP backs it, so none is credited and total supply is unchanged.
+/// Emits `NodeSlashed` (followed by the legacy `StakeSlashed`), and `NodeSuspended` when this slash suspends the node, on the state's event log.
+fn slash(
+    registry: &NodeRegistry,
+    state: &State,
+    params: &ProtocolParams,
+    height: u64,
+    node_id: &NodeId,
+    reason: SlashingReason,
+    suspension_threshold: u64,
+) -> Result<u128> {
     let node = registry
         .get(node_id)
         .ok_or_else(|| SlashingError::NodeNotFound(node_id.clone()))?;
 
-    let rep_penalty = reputation_penalty_for(reason);
-    let stake_slash = stake_slash_for(reason);
+    let (rep_penalty, stake_slash) = penalties_for(params, reason);
 
-    let new_stake = node.stake.saturating_sub(stake_slash);
-    registry.set_stake(node_id, new_stake)?;
+    let slashed = stake_slash.min(node.stake);
+    registry.set_stake(node_id, node.stake - slashed)?;
     registry.apply_reputation_penalty(node_id, rep_penalty, suspension_threshold)?;
 
-    Ok(())
+    let destination = params.slash_destination;
+    if let Some(account) = node.stake_account() {
+        state.slash_stake(&account, slashed, destination);
+    }
+
+    state.emit(Event::NodeSlashed {
+        node_id: node_id.clone(),
+        reason,
+        height,
+        reputation_penalty: rep_penalty,
+        stake_slashed: slashed,
+        destination,
+    });
+    state.emit(Event::StakeSlashed {
+        node_id: node_id.clone(),
+        reason,
+        amount: slashed,
+        destination,
+    });
+    let suspended = registry.get(node_id).is_some_and(|n| n.status == NodeStatus::Suspended);
+    if suspended && node.status != NodeStatus::Suspended {
+        state.emit(Event::NodeSuspended {
+            node_id: node_id.clone(),
+            reason,
+            height,
+        });
+    }
+    Ok(slashed)
 }
 
-/// Applies slashing to multiple nodes (e.g. all that voted against the majority). Uses the default suspension threshold.
+/// Applies slashing to multiple nodes (e.g. all that voted against the majority) with the amounts in `params`, crediting the removed stake in `state`. Uses the default suspension threshold.
 pub fn apply_slash_batch(
     registry: &NodeRegistry,
+    state: &State,
+    params: &ProtocolParams,
     node_ids: &[NodeId],
     reason: SlashingReason,
 ) -> Result<()> {
     for node_id in node_ids {
-        let _ = apply_slash(registry, node_id, reason);
+        let _ = apply_slash(registry, state, params, node_id, reason);
     }
     Ok(())
 }
 
-/// Returns the (reputation_penalty, stake_slash) for the given reason (for display or off-chain logic).
+/// Returns the (reputation_penalty, stake_slash) for the given reason under the default parameters (for display or off-chain logic).
 pub fn penalty_amounts(reason: SlashingReason) -> (u64, u128) {
-    (reputation_penalty_for(reason), stake_slash_for(reason))
+    penalties_for(&ProtocolParams::default(), reason)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::node_registry::NodeStatus;
+
+    /// Registers each of `ids` under a fresh key whose account has `stake` bonded on chain.
+    fn stake_on_chain(reg: &NodeRegistry, state: &State, ids: &[&str], stake: u128) {
+        let accounts = crate::testing::DeterministicRng::new(11).accounts(ids.len());
+        let pool = crate::core::state::STAKING_ADDRESS.to_string();
+        for (id, account) in ids.iter().zip(&accounts) {
+            reg.register((*id).into(), account.pub_main(), stake, 10).unwrap();
+            state.set_stake(&account.address, stake);
+            state.set_balance(&pool, state.get_balance(&pool) + stake);
+        }
+    }
+
+    /// Sum of every PLP balance, including the staking pool and the slash destinations.
+    fn plp_supply(state: &State) -> u128 {
+        state.committed_accounts().iter().map(|(_, balance, _, _)| balance).sum()
+    }
 
     #[test]
     fn test_penalty_amounts() {
@@ -131,19 +197,153 @@ mod tests {
         let reg = NodeRegistry::new();
         reg.register("n1".into(), "pk1".into(), 1000, 10).unwrap();
         let before = reg.get(&"n1".into()).unwrap();
-        apply_slash(&reg, &"n1".into(), SlashingReason::AgainstMajority).unwrap();
+        let (state, params) = (State::new(), ProtocolParams::default());
+        apply_slash(&reg, &state, &params, &"n1".into(), SlashingReason::AgainstMajority).unwrap();
         let after = reg.get(&"n1".into()).unwrap();
         assert!(after.reputation_score < before.reputation_score);
         assert_eq!(after.stake, before.stake.saturating_sub(2));
     }
 
+    #[test]
+    fn test_apply_slash_at_uses_active_params() {
+        let reg = NodeRegistry::new();
+        reg.register("n1".into(), "pk1".into(), 1000, 10).unwrap();
+        let state = State::new();
+        let mut tuned = ProtocolParams::default();
+        tuned.slashing.get_mut(&SlashingReason::NoVote).unwrap().stake_slash = 40;
+        let mut schedule = ProtocolParamsSchedule::default();
+        schedule.schedule(10, tuned).unwrap();
+
+        apply_slash_at(&reg, &state, &schedule, 9, &"n1".into(), SlashingReason::NoVote).unwrap();
+        assert_eq!(reg.get(&"n1".into()).unwrap().stake, 999);
+        apply_slash_at(&reg, &state, &schedule, 10, &"n1".into(), SlashingReason::NoVote).unwrap();
+        assert_eq!(reg.get(&"n1".into()).unwrap().stake, 959);
+    }
+
+    #[test]
+    fn test_slash_paths_use_the_given_params() {
+        use crate::core::confirmation_layer::apply_l1_penalties;
+        use crate::core::protocol_params::SlashingEntry;
+        use crate::core::state::BURN_ADDRESS;
+
+        let mut params = ProtocolParams::default();
+        let harsh = SlashingEntry {
+            reputation_penalty_pct: 50,
+            stake_slash: 300,
+        };
+        params.slashing.insert(SlashingReason::AgainstMajority, harsh);
+        let reg = NodeRegistry::new();
+        let state = State::new();
+        stake_on_chain(&reg, &state, &["n1", "n2", "n3"], 1000);
+        let burned = || state.get_balance(&BURN_ADDRESS.to_string());
+        assert_eq!(apply_slash(&reg, &state, &params, &"n1".into(), SlashingReason::AgainstMajority).unwrap(), 300);
+        assert_eq!((reg.get(&"n1".into()).unwrap().stake, burned()), (700, 300));
+        apply_l1_penalties(&reg, &state, &params, &["n2".into(), "n3".into()]).unwrap();
+        for id in ["n2", "n3"] {
+            let node = reg.get(&id.into()).unwrap();
+            assert_eq!((node.stake, node.missed_votes), (700, 1));
+        }
+        assert_eq!(burned(), 900);
+    }
+
+    #[test]
+    fn test_slashed_stake_is_credited_to_destination() {
+        use crate::core::asset::Asset;
+        use crate::core::protocol_params::SlashDestination;
+        use crate::core::state::{BURN_ADDRESS, INSURANCE_FUND_ADDRESS};
+
+        let reg = NodeRegistry::new();
+        let state = State::new();
+        stake_on_chain(&reg, &state, &["n1"], 120);
+        let insured = ProtocolParams {
+            slash_destination: SlashDestination::InsuranceFund,
+            ..ProtocolParams::default()
+        };
+        let mut schedule = ProtocolParamsSchedule::default();
+        schedule.schedule(5, insured).unwrap();
+
+        let plp = |addr: &str| state.get_asset_balance(&addr.to_string(), &Asset::PLP);
+        assert_eq!(apply_slash_at(&reg, &state, &schedule, 1, &"n1".into(), SlashingReason::Equivocation).unwrap(), 100);
+        // Only the remaining 20 can be slashed.
+        assert_eq!(apply_slash_at(&reg, &state, &schedule, 5, &"n1".into(), SlashingReason::Equivocation).unwrap(), 20);
+        assert_eq!(reg.get(&"n1".into()).unwrap().stake, 0);
+        assert_eq!((plp(BURN_ADDRESS), plp(INSURANCE_FUND_ADDRESS)), (100, 20));
+        assert_eq!(
+            state.take_events()[2..],
+            [
+                Event::NodeSlashed {
+                    node_id: "n1".into(),
+                    reason: SlashingReason::Equivocation,
+                    height: 5,
+                    reputation_penalty: SCORE_SCALE * 15 / 100,
+                    stake_slashed: 20,
+                    destination: SlashDestination::InsuranceFund,
+                },
+                Event::StakeSlashed {
+                    node_id: "n1".into(),
+                    reason: SlashingReason::Equivocation,
+                    amount: 20,
+                    destination: SlashDestination::InsuranceFund,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slash_keeps_total_supply() {
+        use crate::core::state::BURN_ADDRESS;
+
+        let reg = NodeRegistry::new();
+        let state = State::new();
+        stake_on_chain(&reg, &state, &["staked"], 60);
+        reg.register("registry_only".into(), "pk_registry_only".into(), 500, 10).unwrap();
+        let params = ProtocolParams::default();
+        let supply = plp_supply(&state);
+
+        // The node's whole 60 is held on chain and moves to the burn address.
+        assert_eq!(apply_slash(&reg, &state, &params, &"staked".into(), SlashingReason::Equivocation).unwrap(), 60);
+        assert_eq!(state.get_balance(&BURN_ADDRESS.to_string()), 60);
+        assert_eq!(plp_supply(&state), supply);
+
+        // A registry-only stake leaves the registry without crediting anything.
+        apply_slash(&reg, &state, &params, &"registry_only".into(), SlashingReason::Equivocation).unwrap();
+        assert_eq!(reg.get(&"registry_only".into()).unwrap().stake, 400);
+        assert_eq!(state.get_balance(&BURN_ADDRESS.to_string()), 60);
+        assert_eq!(plp_supply(&state), supply);
+    }
+
+    #[test]
+    fn test_every_slash_path_emits_events() {
+        use crate::core::confirmation_layer::apply_l1_penalties;
+
+        let reg = NodeRegistry::new();
+        reg.register("n1".into(), "pk1".into(), 1000, 10).unwrap();
+        reg.register("n2".into(), "pk2".into(), 1000, 10).unwrap();
+        let (state, params) = (State::new(), ProtocolParams::default());
+        state.set_block_height(42);
+        apply_slash(&reg, &state, &params, &"n1".into(), SlashingReason::NoVote).unwrap();
+        apply_l1_penalties(&reg, &state, &params, &["n2".into()]).unwrap();
+        let events = state.take_events();
+        let slashed: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                Event::NodeSlashed { node_id, height, .. } => Some((node_id.as_str(), *height)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(slashed, [("n1", 42), ("n2", 42)]);
+        assert_eq!(events.iter().filter(|e| matches!(e, Event::StakeSlashed { .. })).count(), 2);
+        assert_eq!(events.iter().filter(|e| e.is_slashing()).count(), 2);
+    }
+
     #[test]
     fn test_suspension_below_threshold() {
         let reg = NodeRegistry::new();
         reg.register("n1".into(), "pk1".into(), 1000, 10).unwrap();
+        let (state, params) = (State::new(), ProtocolParams::default());
         // Equivocation = 15% each; 7 * 150_000 = 1_050_000 > 1_000_000 → reputation 0, below 100_000
         for _ in 0..7 {
-            apply_slash(&reg, &"n1".into(), SlashingReason::Equivocation).unwrap();
+            apply_slash(&reg, &state, &params, &"n1".into(), SlashingReason::Equivocation).unwrap();
         }
         let node = reg.get(&"n1".into()).unwrap();
         assert_eq!(node.status, NodeStatus::Suspended);
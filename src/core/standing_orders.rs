@@ -0,0 +1,195 @@
+//! Recurring payments (standing orders) executed by the chain.
+//!
+//! **Stability:** experimental. Crate-root re-exports require the `experimental` feature.
+//!
+//! An account registers an order with a `CreateStandingOrder` transaction: `to`, `asset` and `amount` are the
+//! payment, `interval_blocks` its period and `end_height` the last height it may run at. The order is keyed by
+//! the sender and the registering transaction's hash, pays first at registration height plus one interval, and is
+//! removed after its last payment or by a `CancelStandingOrder` from the sender.
+//!
+//! Payments are not transactions. `ExecutionLogic::execute_block`, which the producer and every validator run,
+//! calls [`execute_standing_orders`] after the block's transactions, before the state root is computed; it runs each due order
+//! once, in (sender, order id) order, from the sender's balance with no fee and no nonce change. A payment that
+//! cannot be made (balance, vesting lock, frozen asset or account) is skipped and the order moves on to its next
+//! period. Every attempt yields a [`StandingOrderReceipt`] and, when paid, an `Event::StandingOrderPaid`.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::core::asset::Asset;
+use crate::core::events::Event;
+use crate::core::state::{Address, State};
+use crate::error::PlatariumError;
+
+/// Most active orders one account may hold; executions are free, so the per-block work is bounded per account.
+pub const MAX_STANDING_ORDERS_PER_ACCOUNT: usize = 16;
+
+/// (sender, order id).
+pub type StandingOrderId = (Address, String);
+
+/// Errors produced by standing order registration and cancellation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum StandingOrderError {
+    #[error("Standing order {order_id} is not registered for {address}")]
+    NotFound { address: Address, order_id: String },
+
+    #[error("Account {0} already has {MAX_STANDING_ORDERS_PER_ACCOUNT} standing orders")]
+    TooMany(Address),
+
+    #[error("Invalid standing order schedule: {0}")]
+    InvalidSchedule(String),
+}
+
+impl From<StandingOrderError> for PlatariumError {
+    fn from(err: StandingOrderError) -> Self {
+        PlatariumError::State(format!("Standing order error: {}", err))
+    }
+}
+
+/// One registered recurring payment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandingOrder {
+    pub to: Address,
+    pub asset: Asset,
+    /// Amount of `asset` paid each period.
+    pub amount: u128,
+    pub interval_blocks: u64,
+    /// Height of the next payment.
+    pub next_height: u64,
+    /// Last height a payment may run at.
+    pub end_height: u64,
+}
+
+impl StandingOrder {
+    /// Order registered at `height`, validated: a positive interval and at least one payment by `end_height`.
+    pub fn new(
+        to: Address,
+        asset: Asset,
+        amount: u128,
+        interval_blocks: u64,
+        end_height: u64,
+        height: u64,
+    ) -> Result<Self, StandingOrderError> {
+        if interval_blocks == 0 {
+            return Err(StandingOrderError::InvalidSchedule("interval_blocks must be > 0".to_string()));
+        }
+        let next_height = height.saturating_add(interval_blocks);
+        if end_height < next_height {
+            return Err(StandingOrderError::InvalidSchedule(format!(
+                "end height {} is before the first payment at {}",
+                end_height, next_height
+            )));
+        }
+        Ok(Self { to, asset, amount, interval_blocks, next_height, end_height })
+    }
+}
+
+/// Outcome of one payment attempt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum StandingOrderStatus {
+    Paid,
+    Skipped { reason: String },
+}
+
+/// Record of one payment attempt, for the block's receipts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandingOrderReceipt {
+    pub order_id: String,
+    pub from: Address,
+    pub to: Address,
+    pub asset: String,
+    pub amount: u128,
+    pub height: u64,
+    #[serde(flatten)]
+    pub status: StandingOrderStatus,
+    /// Whether this was the order's last payment (it has been removed).
+    pub completed: bool,
+}
+
+/// Runs every order due at the state's block height; see the module docs. Deterministic for a given state.
+pub fn execute_standing_orders(state: &State) -> Vec<StandingOrderReceipt> {
+    let height = state.block_height();
+    let mut receipts = Vec::new();
+    for ((from, order_id), mut order) in state.standing_orders() {
+        if order.next_height > height {
+            continue;
+        }
+        let status = match state
+            .check_not_frozen(&from)
+            .and_then(|_| state.apply_transfer(&from, &order.to, &order.asset, order.amount, 0, None))
+        {
+            Ok(()) => {
+                state.emit(Event::StandingOrderPaid {
+                    order_id: order_id.clone(),
+                    from: from.clone(),
+                    to: order.to.clone(),
+                    asset: order.asset.as_canonical(),
+                    amount: order.amount,
+                });
+                StandingOrderStatus::Paid
+            }
+            Err(e) => StandingOrderStatus::Skipped { reason: e.to_string() },
+        };
+        order.next_height = height.saturating_add(order.interval_blocks);
+        let completed = order.next_height > order.end_height;
+        receipts.push(StandingOrderReceipt {
+            order_id: order_id.clone(),
+            from: from.clone(),
+            to: order.to.clone(),
+            asset: order.asset.as_canonical(),
+            amount: order.amount,
+            height,
+            status,
+            completed,
+        });
+        let id = (from, order_id);
+        if completed {
+            state.remove_standing_order(&id);
+        } else {
+            state.set_standing_order(id, order);
+        }
+    }
+    receipts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_validation() {
+        assert!(StandingOrder::new("b".into(), Asset::PLP, 5, 0, 100, 10).is_err());
+        assert!(StandingOrder::new("b".into(), Asset::PLP, 5, 10, 19, 10).is_err());
+        let order = StandingOrder::new("b".into(), Asset::PLP, 5, 10, 20, 10).unwrap();
+        assert_eq!(order.next_height, 20);
+    }
+
+    #[test]
+    fn test_payments_run_each_interval_until_end() {
+        let state = State::new();
+        let from = "alice".to_string();
+        state.set_balance(&from, 12);
+        let order = StandingOrder::new("bob".into(), Asset::PLP, 5, 10, 40, 0).unwrap();
+        state.set_standing_order((from.clone(), "o1".into()), order);
+
+        state.set_block_height(9);
+        assert!(execute_standing_orders(&state).is_empty());
+        for h in [10, 20] {
+            state.set_block_height(h);
+            let r = execute_standing_orders(&state);
+            assert_eq!(r[0].status, StandingOrderStatus::Paid);
+        }
+        assert_eq!(state.get_balance(&"bob".to_string()), 10);
+
+        // Not enough left: skipped, and the order still ends on schedule.
+        state.set_block_height(30);
+        assert!(matches!(execute_standing_orders(&state)[0].status, StandingOrderStatus::Skipped { .. }));
+        state.set_balance(&from, 7);
+        state.set_block_height(40);
+        let r = execute_standing_orders(&state);
+        assert_eq!(r[0].status, StandingOrderStatus::Paid);
+        assert!(r[0].completed);
+        assert!(state.standing_orders().is_empty());
+        assert_eq!(state.get_nonce(&from), 0);
+    }
+}
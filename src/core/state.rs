@@ -7,20 +7,73 @@
 //! - State transitions are deterministic functions of the transaction sequence.
 //! - Balance and nonce updates follow fixed rules. Same sequence of transactions always produces the same state.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::{Arc, RwLock};
-use sha2::{Sha256, Digest};
+use crate::address::{self, AddressError};
 use crate::error::{PlatariumError, Result};
 use crate::core::asset::Asset;
-use crate::core::transaction::{Transaction, TransactionValidationError};
+use crate::core::asset_registry::{AssetRegistry, AssetRegistryError};
+use crate::core::events::Event;
+use crate::core::governance::{AddressFreezeOrder, GovernanceError};
+use crate::core::insurance::InsuranceFund;
+use crate::core::invariants::{
+    check_invariant, RESTORE_IDENTITY, SNAPSHOT_LIVE, SNAPSHOT_MATCHES_STATE, SNAPSHOT_SIDE_EFFECT_FREE, SNAPSHOT_STABLE,
+};
+use crate::core::migrations::StateLayout;
+use crate::core::multisig::{check_multisig_config, MultisigError};
+use crate::core::protocol_params::SlashDestination;
+use crate::core::recovery::{normalize_main_key, PendingRotation, RecoveryError};
+use crate::core::session_keys::{SessionKey, SessionKeyError};
+use crate::core::standing_orders::{StandingOrder, StandingOrderError, StandingOrderId, MAX_STANDING_ORDERS_PER_ACCOUNT};
+use crate::core::state_commitment::AccountCommitment;
+use crate::core::state_file::StateFileData;
+use crate::signature::normalize_public_key_hex;
+use crate::core::vesting::VestingSchedule;
+use crate::core::transaction::{
+    same_public_key, Transaction, TransactionValidationError, TransferOutput, TxKind, ValidationResult,
+};
+use crate::core::chainspec::ChainSpec;
+use platarium_consensus::Word;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Fee recipient address. Fee is always in μPLP.
 pub const TREASURY_ADDRESS: &str = "treasury";
 
+/// Receives slashed stake when governance routes it to the insurance fund (PLP).
+pub const INSURANCE_FUND_ADDRESS: &str = "insurance_fund";
+
+/// Sink for burned tokens and slashed stake (PLP). It is not a `Px` key address and no main key can be installed for it
+/// (recovery and multisig setup both need a transaction it cannot sign), so `check_installed_main_key` rejects every
+/// transaction from it and its balance is out of circulation.
+pub const BURN_ADDRESS: &str = "burn";
+
+/// Holds the PLP locked by `Stake` transactions, and unstaked PLP until it is released; per-account stakes are
+/// tracked in `State::get_stake`, unbonding amounts in `State::unbonding`.
+pub const STAKING_ADDRESS: &str = "staking";
+
+/// Blocks between an `Unstake` and the release of its PLP. Unbonding stake can still be slashed.
+pub const UNBONDING_DELAY_BLOCKS: u64 = 1_000;
+
 /// Address type (alias for String).
 pub type Address = String;
 
+/// Allowance key: (owner, spender, asset canonical string).
+pub type AllowanceKey = (Address, Address, String);
+
+/// Session key id: (account, session key hex).
+pub type SessionKeyId = (Address, String);
+
+/// How an account authorizes its transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountKind {
+    /// Main key (`pub_main`, or the address itself) plus the registered derived key.
+    #[default]
+    Single,
+    /// Any `threshold` of `signers` (key hex, no `Px`), through `Transaction::multisig`; see `core::multisig`.
+    Multisig { signers: Vec<String>, threshold: u32 },
+}
+
 /// Trait for types that can produce immutable state snapshots. Same state yields the same snapshot; no randomness or system time. Snapshots are immutable.
 pub trait SnapshotableState {
     /// Produces an immutable snapshot of the current state. Deterministic: same state yields the same snapshot.
@@ -38,20 +91,30 @@ pub struct StateSnapshot {
     asset_balances: Arc<HashMap<(Address, String), u128>>,
     uplp_balances: Arc<HashMap<Address, u128>>,
     nonces: Arc<HashMap<Address, u64>>,
+    asset_registry: Arc<AssetRegistry>,
+    allowances: Arc<HashMap<AllowanceKey, u128>>,
+    vesting: Arc<HashMap<Address, VestingSchedule>>,
+    freeze_orders: Arc<BTreeMap<String, AddressFreezeOrder>>,
+    derived_keys: Arc<HashMap<Address, String>>,
+    main_keys: Arc<HashMap<Address, String>>,
+    pending_rotations: Arc<BTreeMap<Address, PendingRotation>>,
+    session_keys: Arc<BTreeMap<SessionKeyId, SessionKey>>,
+    standing_orders: Arc<BTreeMap<StandingOrderId, StandingOrder>>,
+    account_kinds: Arc<HashMap<Address, AccountKind>>,
+    stakes: Arc<HashMap<Address, u128>>,
+    unbonding: Arc<BTreeMap<(Address, u64), u128>>,
+    insurance: Arc<InsuranceFund>,
+    block_height: u64,
+}
+
+/// The snapshot's shared balance and nonce maps are still referenced (checked around restore and simulation).
+pub(crate) fn snapshot_live(snapshot: &StateSnapshot) -> bool {
+    Arc::strong_count(snapshot.asset_balances_arc()) > 0
+        && Arc::strong_count(snapshot.uplp_balances_arc()) > 0
+        && Arc::strong_count(snapshot.nonces_arc()) > 0
 }
 
 impl StateSnapshot {
-    fn new(
-        asset_balances: Arc<HashMap<(Address, String), u128>>,
-        uplp_balances: Arc<HashMap<Address, u128>>,
-        nonces: Arc<HashMap<Address, u64>>,
-    ) -> Self {
-        Self {
-            asset_balances,
-            uplp_balances,
-            nonces,
-        }
-    }
 
     pub(crate) fn asset_balances_arc(&self) -> &Arc<HashMap<(Address, String), u128>> {
         &self.asset_balances
@@ -62,6 +125,50 @@ impl StateSnapshot {
     pub(crate) fn nonces_arc(&self) -> &Arc<HashMap<Address, u64>> {
         &self.nonces
     }
+    pub(crate) fn asset_registry_arc(&self) -> &Arc<AssetRegistry> {
+        &self.asset_registry
+    }
+    pub(crate) fn allowances_arc(&self) -> &Arc<HashMap<AllowanceKey, u128>> {
+        &self.allowances
+    }
+    pub(crate) fn vesting_arc(&self) -> &Arc<HashMap<Address, VestingSchedule>> {
+        &self.vesting
+    }
+    pub(crate) fn freeze_orders_arc(&self) -> &Arc<BTreeMap<String, AddressFreezeOrder>> {
+        &self.freeze_orders
+    }
+    pub(crate) fn derived_keys_arc(&self) -> &Arc<HashMap<Address, String>> {
+        &self.derived_keys
+    }
+    pub(crate) fn main_keys_arc(&self) -> &Arc<HashMap<Address, String>> {
+        &self.main_keys
+    }
+    pub(crate) fn pending_rotations_arc(&self) -> &Arc<BTreeMap<Address, PendingRotation>> {
+        &self.pending_rotations
+    }
+    pub(crate) fn session_keys_arc(&self) -> &Arc<BTreeMap<SessionKeyId, SessionKey>> {
+        &self.session_keys
+    }
+    pub(crate) fn standing_orders_arc(&self) -> &Arc<BTreeMap<StandingOrderId, StandingOrder>> {
+        &self.standing_orders
+    }
+    pub(crate) fn account_kinds_arc(&self) -> &Arc<HashMap<Address, AccountKind>> {
+        &self.account_kinds
+    }
+    pub(crate) fn stakes_arc(&self) -> &Arc<HashMap<Address, u128>> {
+        &self.stakes
+    }
+    pub(crate) fn unbonding_arc(&self) -> &Arc<BTreeMap<(Address, u64), u128>> {
+        &self.unbonding
+    }
+    pub(crate) fn insurance_arc(&self) -> &Arc<InsuranceFund> {
+        &self.insurance
+    }
+
+    /// Block height the snapshot was taken at.
+    pub fn block_height(&self) -> u64 {
+        self.block_height
+    }
 
     /// Returns the PLP balance for the address, or 0 if absent.
     pub fn get_balance(&self, address: &Address) -> u128 {
@@ -73,6 +180,51 @@ impl StateSnapshot {
         self.nonces.get(address).copied().unwrap_or(0)
     }
 
+    /// Balance of `asset` for the address, or 0 if absent.
+    pub fn get_asset_balance(&self, address: &Address, asset: &Asset) -> u128 {
+        let k = (address.clone(), asset.as_canonical());
+        self.asset_balances.get(&k).copied().unwrap_or(0)
+    }
+
+    /// μPLP fee balance for the address, or 0 if absent.
+    pub fn get_uplp_balance(&self, address: &Address) -> u128 {
+        self.uplp_balances.get(address).copied().unwrap_or(0)
+    }
+
+    /// Derived key bound to the address (hex, no `Px` prefix).
+    pub fn registered_derived_key(&self, address: &Address) -> Option<String> {
+        self.derived_keys.get(address).cloned()
+    }
+
+    /// This snapshot with the given entries written over its balance, nonce and derived key maps. Only the maps
+    /// with entries are copied; the rest stay shared.
+    pub(crate) fn with_writes(
+        &self,
+        asset_balances: &HashMap<(Address, String), u128>,
+        uplp_balances: &HashMap<Address, u128>,
+        nonces: &HashMap<Address, u64>,
+        derived_keys: &HashMap<Address, String>,
+    ) -> Self {
+        fn merge<K: Clone + Eq + std::hash::Hash, V: Clone>(
+            base: &Arc<HashMap<K, V>>,
+            writes: &HashMap<K, V>,
+        ) -> Arc<HashMap<K, V>> {
+            if writes.is_empty() {
+                return base.clone();
+            }
+            let mut merged = base.as_ref().clone();
+            merged.extend(writes.iter().map(|(k, v)| (k.clone(), v.clone())));
+            Arc::new(merged)
+        }
+        Self {
+            asset_balances: merge(&self.asset_balances, asset_balances),
+            uplp_balances: merge(&self.uplp_balances, uplp_balances),
+            nonces: merge(&self.nonces, nonces),
+            derived_keys: merge(&self.derived_keys, derived_keys),
+            ..self.clone()
+        }
+    }
+
     /// Returns all PLP balances, sorted by address for deterministic ordering.
     pub fn get_all_balances(&self) -> Vec<(Address, u128)> {
         let plp = Asset::PLP.as_canonical();
@@ -96,18 +248,23 @@ impl StateSnapshot {
         v
     }
 
-    /// Computes the deterministic state root for the block header from sorted balances and nonces.
+    /// Legacy snapshot root (`state_root_v1`) over sorted PLP balances and nonces. Block headers commit
+    /// [`commitment_root`](Self::commitment_root) instead.
     pub fn compute_state_root(&self) -> String {
-        let mut hasher = Sha256::new();
-        for (addr, bal) in self.get_all_balances() {
-            hasher.update(addr.as_bytes());
-            hasher.update(bal.to_le_bytes());
-        }
-        for (addr, nonce) in self.get_all_nonces() {
-            hasher.update(addr.as_bytes());
-            hasher.update(nonce.to_le_bytes());
-        }
-        hex::encode(hasher.finalize())
+        let balances = self.get_all_balances();
+        let nonces = self.get_all_nonces();
+        hex::encode(platarium_consensus::snapshot_state_root(
+            balances.iter().map(|(addr, bal)| (addr.as_str(), *bal)),
+            nonces.iter().map(|(addr, nonce)| (addr.as_str(), *nonce)),
+        ))
+    }
+
+    /// State commitment of this snapshot, as `State::current_root` computes it: the block header state root.
+    /// O(accounts + tokens).
+    pub fn commitment_root(&self) -> String {
+        let state = State::new();
+        state.restore(self);
+        state.recompute_root()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -127,6 +284,20 @@ impl PartialEq for StateSnapshot {
         *self.asset_balances == *other.asset_balances
             && *self.uplp_balances == *other.uplp_balances
             && *self.nonces == *other.nonces
+            && *self.asset_registry == *other.asset_registry
+            && *self.allowances == *other.allowances
+            && *self.vesting == *other.vesting
+            && *self.freeze_orders == *other.freeze_orders
+            && *self.derived_keys == *other.derived_keys
+            && *self.main_keys == *other.main_keys
+            && *self.pending_rotations == *other.pending_rotations
+            && *self.session_keys == *other.session_keys
+            && *self.standing_orders == *other.standing_orders
+            && *self.account_kinds == *other.account_kinds
+            && *self.stakes == *other.stakes
+            && *self.unbonding == *other.unbonding
+            && *self.insurance == *other.insurance
+            && self.block_height == other.block_height
     }
 }
 
@@ -147,10 +318,321 @@ pub enum StateError {
         got: u64,
     },
     
+    #[error("Asset is frozen: {0}")]
+    AssetFrozen(String),
+
+    #[error("Insufficient allowance: required {required}, available {available}")]
+    InsufficientAllowance {
+        required: u128,
+        available: u128,
+    },
+
+    #[error("Owner cannot spend its own allowance; use a plain transfer")]
+    SelfAllowance,
+
+    #[error("Address {address} is frozen by governance order {order_id}")]
+    AddressFrozen {
+        address: Address,
+        order_id: String,
+    },
+
+    #[error("Balance is locked by vesting: spending {required}, unlocked {unlocked}")]
+    VestingLocked {
+        required: u128,
+        unlocked: u128,
+    },
+
+    #[error("Missing derived key for {0}")]
+    MissingDerivedKey(Address),
+
+    #[error("Derived key mismatch for {address}: registered {registered}, got {got}")]
+    DerivedKeyMismatch {
+        address: Address,
+        registered: String,
+        got: String,
+    },
+
+    #[error("Derived key already registered for {0}")]
+    DerivedKeyAlreadyRegistered(Address),
+
+    #[error("Main key {got} does not control {address}")]
+    MainKeyMismatch { address: Address, got: String },
+
+    #[error("Invalid address {address}: {reason}")]
+    InvalidAddress { address: Address, reason: AddressError },
+
+    #[error("Transaction expired: valid until block {valid_until}, current height {height}")]
+    Expired { valid_until: u64, height: u64 },
+
+    #[error("Insufficient stake: required {required}, available {available}")]
+    InsufficientStake {
+        required: u128,
+        available: u128,
+    },
+
+    #[error("Supply mismatch for {asset}: tracked {tracked}, held {held}")]
+    SupplyMismatch {
+        asset: String,
+        tracked: u128,
+        held: u128,
+    },
+
     #[error("State error: {0}")]
     Other(String),
 }
 
+/// Rejects a transaction whose sender, recipient, `TransferFrom` owner or `MultiTransfer` output is a key address
+/// (see [`address::is_key_address`]) that is invalid or not canonical.
+pub(crate) fn check_addresses(tx: &Transaction) -> std::result::Result<(), StateError> {
+    let owner = match &tx.kind {
+        TxKind::TransferFrom { owner } => Some(owner),
+        _ => None,
+    };
+    let outputs = match &tx.kind {
+        TxKind::MultiTransfer { outputs } => outputs.as_slice(),
+        _ => &[],
+    };
+    for addr in [Some(&tx.from), Some(&tx.to), owner].into_iter().flatten().chain(outputs.iter().map(|o| &o.to)) {
+        address::check(addr).map_err(|reason| StateError::InvalidAddress {
+            address: addr.clone(),
+            reason,
+        })?;
+    }
+    Ok(())
+}
+
+/// Checks `pub_derived` against the derived key registered for `address`; any key passes while none is registered.
+pub(crate) fn check_registered_derived_key(
+    address: &Address,
+    registered: Option<String>,
+    pub_derived: Option<&str>,
+) -> Result<()> {
+    let Some(registered) = registered else {
+        return Ok(());
+    };
+    match pub_derived {
+        Some(k) if same_public_key(&registered, k) => Ok(()),
+        got => Err(StateError::DerivedKeyMismatch {
+            address: address.clone(),
+            registered,
+            got: got.unwrap_or_default().to_string(),
+        }
+        .into()),
+    }
+}
+
+/// Checks `pub_main` against the main key in effect for `address`: the key installed by recovery if there is one,
+/// otherwise the key the address is made of. An address that is not a key (`burn`, `treasury`, ...) has no main key
+/// and cannot send.
+pub(crate) fn check_installed_main_key(address: &Address, main_key: Option<String>, pub_main: &str) -> Result<()> {
+    match main_key {
+        Some(expected) if !same_public_key(&expected, pub_main) => Err(RecoveryError::MainKeyMismatch {
+            address: address.clone(),
+            expected,
+            got: pub_main.to_string(),
+        }
+        .into()),
+        Some(_) => Ok(()),
+        None if address.starts_with("Px") && same_public_key(address, pub_main) => Ok(()),
+        None => Err(StateError::MainKeyMismatch { address: address.clone(), got: pub_main.to_string() }.into()),
+    }
+}
+
+/// Balance and nonce access used by [`transfer_effects`]: the locked maps of a `State`, or the write layers of an `OverlayState`.
+pub(crate) trait Ledger {
+    fn asset_balance(&self, address: &Address, asset: &str) -> u128;
+    fn set_asset_balance(&mut self, address: &Address, asset: &str, balance: u128);
+    fn uplp_balance(&self, address: &Address) -> u128;
+    fn set_uplp_balance(&mut self, address: &Address, balance: u128);
+    fn nonce(&self, address: &Address) -> u64;
+    fn set_nonce(&mut self, address: &Address, nonce: u64);
+}
+
+/// `State`'s balance and nonce maps, borrowed under their write locks.
+struct MapLedger<'a> {
+    asset_balances: &'a mut HashMap<(Address, String), u128>,
+    uplp_balances: &'a mut HashMap<Address, u128>,
+    nonces: &'a mut HashMap<Address, u64>,
+}
+
+impl Ledger for MapLedger<'_> {
+    fn asset_balance(&self, address: &Address, asset: &str) -> u128 {
+        self.asset_balances.get(&(address.clone(), asset.to_string())).copied().unwrap_or(0)
+    }
+    fn set_asset_balance(&mut self, address: &Address, asset: &str, balance: u128) {
+        self.asset_balances.insert((address.clone(), asset.to_string()), balance);
+    }
+    fn uplp_balance(&self, address: &Address) -> u128 {
+        self.uplp_balances.get(address).copied().unwrap_or(0)
+    }
+    fn set_uplp_balance(&mut self, address: &Address, balance: u128) {
+        self.uplp_balances.insert(address.clone(), balance);
+    }
+    fn nonce(&self, address: &Address) -> u64 {
+        self.nonces.get(address).copied().unwrap_or(0)
+    }
+    fn set_nonce(&mut self, address: &Address, nonce: u64) {
+        self.nonces.insert(address.clone(), nonce);
+    }
+}
+
+/// Asset balance, μPLP balance and nonce writes of a [`StagedLedger`].
+type StagedWrites = (HashMap<(Address, String), u128>, HashMap<Address, u128>, HashMap<Address, u64>);
+
+/// Writes over a `State` held back until [`StagedLedger::into_writes`]; dropping it discards them. Lets a sequence of
+/// [`transfer_effects`] apply all or nothing.
+struct StagedLedger<'a> {
+    state: &'a State,
+    asset_balances: HashMap<(Address, String), u128>,
+    uplp_balances: HashMap<Address, u128>,
+    nonces: HashMap<Address, u64>,
+}
+
+impl<'a> StagedLedger<'a> {
+    fn new(state: &'a State) -> Self {
+        Self {
+            state,
+            asset_balances: HashMap::new(),
+            uplp_balances: HashMap::new(),
+            nonces: HashMap::new(),
+        }
+    }
+
+    fn into_writes(self) -> StagedWrites {
+        (self.asset_balances, self.uplp_balances, self.nonces)
+    }
+}
+
+impl Ledger for StagedLedger<'_> {
+    fn asset_balance(&self, address: &Address, asset: &str) -> u128 {
+        let k = (address.clone(), asset.to_string());
+        match self.asset_balances.get(&k) {
+            Some(b) => *b,
+            None => self.state.asset_balances.read().unwrap().get(&k).copied().unwrap_or(0),
+        }
+    }
+    fn set_asset_balance(&mut self, address: &Address, asset: &str, balance: u128) {
+        self.asset_balances.insert((address.clone(), asset.to_string()), balance);
+    }
+    fn uplp_balance(&self, address: &Address) -> u128 {
+        match self.uplp_balances.get(address) {
+            Some(b) => *b,
+            None => self.state.get_uplp_balance(address),
+        }
+    }
+    fn set_uplp_balance(&mut self, address: &Address, balance: u128) {
+        self.uplp_balances.insert(address.clone(), balance);
+    }
+    fn nonce(&self, address: &Address) -> u64 {
+        match self.nonces.get(address) {
+            Some(n) => *n,
+            None => self.state.get_nonce(address),
+        }
+    }
+    fn set_nonce(&mut self, address: &Address, nonce: u64) {
+        self.nonces.insert(address.clone(), nonce);
+    }
+}
+
+/// Balance rules of `State::apply_transfer` after the asset freeze check, with `locked` the sender's PLP still
+/// vesting. Every check runs before the first write, so an error leaves `ledger` unchanged.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_effects(
+    ledger: &mut impl Ledger,
+    from: &Address,
+    to: &Address,
+    asset: &Asset,
+    amount: u128,
+    fee_uplp: u128,
+    expected_nonce: Option<u64>,
+    locked: u128,
+) -> Result<()> {
+    let treasury = TREASURY_ADDRESS.to_string();
+    let asset_id = asset.as_canonical();
+    let plp = Asset::PLP.as_canonical();
+
+    if let Some(expected) = expected_nonce {
+        let cur = ledger.nonce(from);
+        if cur != expected {
+            return Err(StateError::InvalidNonce { expected, got: cur }.into());
+        }
+    }
+    let asset_bal = ledger.asset_balance(from, &asset_id);
+    let uplp_bal = ledger.uplp_balance(from);
+    let plp_asset_bal = ledger.asset_balance(from, &plp);
+    let fee_from_plp = fee_uplp.saturating_sub(uplp_bal);
+
+    if *asset == Asset::PLP {
+        if asset_bal < amount.saturating_add(fee_from_plp) {
+            return Err(StateError::InsufficientBalance {
+                required: amount.saturating_add(fee_from_plp),
+                available: asset_bal,
+            }
+            .into());
+        }
+    } else if asset_bal < amount {
+        return Err(StateError::InsufficientBalance {
+            required: amount,
+            available: asset_bal,
+        }
+        .into());
+    }
+
+    let fee_pool = plp_asset_bal.saturating_add(uplp_bal);
+    if fee_pool < fee_uplp {
+        return Err(StateError::InsufficientBalance {
+            required: fee_uplp,
+            available: fee_pool,
+        }
+        .into());
+    }
+
+    let plp_spent = if *asset == Asset::PLP {
+        amount.saturating_add(fee_from_plp)
+    } else {
+        fee_from_plp
+    };
+    if plp_spent > 0 {
+        let unlocked = plp_asset_bal.saturating_sub(locked);
+        if unlocked < plp_spent {
+            return Err(StateError::VestingLocked {
+                required: plp_spent,
+                unlocked,
+            }
+            .into());
+        }
+    }
+
+    // Fee: legacy uplp pool first, remainder from PLP asset balance.
+    let fee_uplp_used = fee_uplp.min(uplp_bal);
+    let fee_plp_used = fee_uplp - fee_uplp_used;
+    ledger.set_uplp_balance(from, uplp_bal - fee_uplp_used);
+    let sender_plp_after_fee = if fee_plp_used > 0 {
+        plp_asset_bal - fee_plp_used
+    } else {
+        plp_asset_bal
+    };
+    if *asset != Asset::PLP && fee_plp_used > 0 {
+        ledger.set_asset_balance(from, &plp, sender_plp_after_fee);
+    }
+
+    let treasury_bal = ledger.uplp_balance(&treasury);
+    ledger.set_uplp_balance(&treasury, treasury_bal + fee_uplp);
+
+    if *asset == Asset::PLP {
+        ledger.set_asset_balance(from, &asset_id, sender_plp_after_fee - amount);
+    } else {
+        ledger.set_asset_balance(from, &asset_id, asset_bal - amount);
+    }
+    let to_bal = ledger.asset_balance(to, &asset_id);
+    ledger.set_asset_balance(to, &asset_id, to_bal + amount);
+
+    if let Some(expected) = expected_nonce {
+        ledger.set_nonce(from, expected + 1);
+    }
+    Ok(())
+}
+
 impl From<StateError> for PlatariumError {
     fn from(err: StateError) -> Self {
         PlatariumError::State(err.to_string())
@@ -164,6 +646,8 @@ impl From<TransactionValidationError> for PlatariumError {
 }
 
 /// Blockchain state: asset balances, μPLP (fee) balances, and nonces. Fee is always in μPLP and is separate from asset balances.
+///
+/// **Stability:** the accessors re-exported through `compat::v1` (balances, nonces, snapshot/restore, and the snapshot state root) are stable; the account-key and session-key helpers are experimental.
 #[derive(Debug)]
 pub struct State {
     /// Asset balances: (address, asset_canonical) -> balance in minimal units
@@ -171,6 +655,46 @@ pub struct State {
     /// μPLP balances for fees only. Fee is always paid from this.
     uplp_balances: RwLock<Arc<HashMap<Address, u128>>>,
     nonces: RwLock<Arc<HashMap<Address, u64>>>,
+    /// Token issuers and freeze flags. Part of snapshots.
+    asset_registry: RwLock<Arc<AssetRegistry>>,
+    /// Delegated spending limits: (owner, spender, asset) -> remaining amount. Part of snapshots.
+    allowances: RwLock<Arc<HashMap<AllowanceKey, u128>>>,
+    /// PLP vesting schedules per address. Part of snapshots.
+    vesting: RwLock<Arc<HashMap<Address, VestingSchedule>>>,
+    /// Enacted governance freeze orders keyed by order id. Part of snapshots.
+    freeze_orders: RwLock<Arc<BTreeMap<String, AddressFreezeOrder>>>,
+    /// HKDF-derived public key bound to each account on first use. Part of snapshots.
+    derived_keys: RwLock<Arc<HashMap<Address, String>>>,
+    /// Main keys installed by completed recoveries; accounts without an entry sign with `pub_main` (or `from`). Part of snapshots.
+    main_keys: RwLock<Arc<HashMap<Address, String>>>,
+    /// Main key rotations waiting out the recovery delay. Part of snapshots.
+    pending_rotations: RwLock<Arc<BTreeMap<Address, PendingRotation>>>,
+    /// Session keys by (account, key hex) with their scope and per-block usage. Part of snapshots.
+    session_keys: RwLock<Arc<BTreeMap<SessionKeyId, SessionKey>>>,
+    /// Recurring payments by (sender, order id); see `standing_orders`. Part of snapshots.
+    standing_orders: RwLock<Arc<BTreeMap<StandingOrderId, StandingOrder>>>,
+    /// Multisig accounts; accounts without an entry are `AccountKind::Single`. Part of snapshots.
+    account_kinds: RwLock<Arc<HashMap<Address, AccountKind>>>,
+    /// PLP staked per address; the coins are held by `STAKING_ADDRESS`. Part of snapshots.
+    stakes: RwLock<Arc<HashMap<Address, u128>>>,
+    /// Unstaked PLP by (address, release height), still held by `STAKING_ADDRESS`. Part of snapshots.
+    unbonding: RwLock<Arc<BTreeMap<(Address, u64), u128>>>,
+    /// Invalid-transaction evidence and paid insurance claims; see `insurance`. Part of snapshots.
+    insurance: RwLock<Arc<InsuranceFund>>,
+    /// Height of the block being executed; used for height-dependent rules (vesting, freeze orders). Part of snapshots.
+    block_height: RwLock<u64>,
+    /// Events emitted since the last `take_events`. Not part of snapshots.
+    events: RwLock<Vec<Event>>,
+    /// Incremental account tree behind `current_root`. Not part of snapshots.
+    commitment: RwLock<AccountCommitment>,
+    /// (address, asset) balances written since the last `current_root`, PLP standing for the account leaf (PLP,
+    /// nonce, μPLP); `None` forces a full rebuild (after `restore`).
+    touched: RwLock<Option<BTreeSet<(Address, String)>>>,
+    /// Layout version and migration log. Not part of snapshots: migrations are not rolled back.
+    layout: RwLock<StateLayout>,
+    /// Deployment whose transactions `check_transaction` accepts (salt and chain ID). Node configuration, not part
+    /// of snapshots; mainnet without a salt by default.
+    chain_spec: RwLock<ChainSpec>,
 }
 
 impl State {
@@ -179,6 +703,25 @@ impl State {
             asset_balances: RwLock::new(Arc::new(HashMap::new())),
             uplp_balances: RwLock::new(Arc::new(HashMap::new())),
             nonces: RwLock::new(Arc::new(HashMap::new())),
+            asset_registry: RwLock::new(Arc::new(AssetRegistry::new())),
+            allowances: RwLock::new(Arc::new(HashMap::new())),
+            vesting: RwLock::new(Arc::new(HashMap::new())),
+            freeze_orders: RwLock::new(Arc::new(BTreeMap::new())),
+            derived_keys: RwLock::new(Arc::new(HashMap::new())),
+            main_keys: RwLock::new(Arc::new(HashMap::new())),
+            pending_rotations: RwLock::new(Arc::new(BTreeMap::new())),
+            session_keys: RwLock::new(Arc::new(BTreeMap::new())),
+            standing_orders: RwLock::new(Arc::new(BTreeMap::new())),
+            account_kinds: RwLock::new(Arc::new(HashMap::new())),
+            stakes: RwLock::new(Arc::new(HashMap::new())),
+            unbonding: RwLock::new(Arc::new(BTreeMap::new())),
+            insurance: RwLock::new(Arc::new(InsuranceFund::new())),
+            block_height: RwLock::new(0),
+            events: RwLock::new(Vec::new()),
+            commitment: RwLock::new(AccountCommitment::new()),
+            touched: RwLock::new(Some(BTreeSet::new())),
+            layout: RwLock::new(StateLayout::default()),
+            chain_spec: RwLock::new(ChainSpec::default()),
         }
     }
 
@@ -199,30 +742,860 @@ impl State {
         ub.get(address).copied().unwrap_or(0)
     }
 
-    /// Legacy: PLP asset balance (for compatibility).
-    pub fn get_balance(&self, address: &Address) -> u128 {
-        self.get_asset_balance(address, &Asset::PLP)
+    /// Legacy: PLP asset balance (for compatibility).
+    pub fn get_balance(&self, address: &Address) -> u128 {
+        self.get_asset_balance(address, &Asset::PLP)
+    }
+
+    pub fn get_nonce(&self, address: &Address) -> u64 {
+        let nonces = self.nonces.read().unwrap();
+        nonces.get(address).copied().unwrap_or(0)
+    }
+
+    pub fn set_asset_balance(&self, address: &Address, asset: &Asset, balance: u128) {
+        let mut ab = self.asset_balances.write().unwrap();
+        let ab_mut = Arc::make_mut(&mut ab);
+        ab_mut.insert(Self::asset_key(address, asset), balance);
+        drop(ab);
+        self.touch_asset(address, &asset.as_canonical());
+    }
+
+    /// Records accounts whose PLP balance, nonce or μPLP balance changed, for `current_root`.
+    fn touch(&self, addresses: &[&Address]) {
+        if let Some(set) = self.touched.write().unwrap().as_mut() {
+            for a in addresses {
+                set.insert(((*a).clone(), Asset::PLP.as_canonical()));
+            }
+        }
+    }
+
+    /// Records a changed balance of `asset` (canonical), for `current_root`.
+    fn touch_asset(&self, address: &Address, asset: &str) {
+        if let Some(set) = self.touched.write().unwrap().as_mut() {
+            set.insert((address.clone(), asset.to_string()));
+        }
+    }
+
+    /// Every address with a PLP balance, nonce or μPLP entry, with the three values, sorted by address.
+    pub(crate) fn committed_accounts(&self) -> Vec<(Address, u128, u64, u128)> {
+        let plp = Asset::PLP.as_canonical();
+        let ab = self.asset_balances.read().unwrap();
+        let ub = self.uplp_balances.read().unwrap();
+        let nc = self.nonces.read().unwrap();
+        let mut addrs: BTreeSet<&Address> = ab.keys().filter(|(_, a)| *a == plp).map(|(addr, _)| addr).collect();
+        addrs.extend(nc.keys());
+        addrs.extend(ub.keys());
+        addrs
+            .into_iter()
+            .map(|a| {
+                let bal = ab.get(&(a.clone(), plp.clone())).copied().unwrap_or(0);
+                (a.clone(), bal, nc.get(a).copied().unwrap_or(0), ub.get(a).copied().unwrap_or(0))
+            })
+            .collect()
+    }
+
+    /// Every token (non-PLP) balance entry as (address, asset canonical, balance), sorted.
+    pub(crate) fn committed_tokens(&self) -> Vec<(Address, String, u128)> {
+        let plp = Asset::PLP.as_canonical();
+        let ab = self.asset_balances.read().unwrap();
+        let mut out: Vec<_> = ab
+            .iter()
+            .filter(|((_, asset), _)| *asset != plp)
+            .map(|((addr, asset), bal)| (addr.clone(), asset.clone(), *bal))
+            .collect();
+        out.sort();
+        out
+    }
+
+    /// Digest of everything the state file persists besides balances and nonces (registries, keys, vesting, stakes,
+    /// ...), as `StateFileData::registries_of` encodes it. O(registry size).
+    pub fn registries_digest(&self) -> String {
+        hex::encode(self.registries_word())
+    }
+
+    fn registries_word(&self) -> Word {
+        let encoded = serde_json::to_vec(&StateFileData::registries_of(self)).expect("state file data serializes");
+        platarium_consensus::registries_digest(&encoded)
+    }
+
+    fn account_tree(&self) -> AccountCommitment {
+        let accounts = self.committed_accounts();
+        let tokens = self.committed_tokens();
+        AccountCommitment::from_entries(
+            accounts.iter().map(|(a, b, n, u)| (a, *b, *n, *u)),
+            tokens.iter().map(|(a, asset, b)| (a, asset.as_str(), *b)),
+        )
+    }
+
+    /// State commitment (the block header state root): the account tree (see `state_commitment`), updated from the
+    /// balances touched since the previous call in O(touched), combined with `registries_digest`. After `restore`
+    /// the next call rebuilds the tree in full.
+    pub fn current_root(&self) -> String {
+        let pending = self.touched.write().unwrap().replace(BTreeSet::new());
+        let mut c = self.commitment.write().unwrap();
+        match pending {
+            Some(entries) => {
+                let plp = Asset::PLP.as_canonical();
+                for (a, asset) in entries {
+                    if asset == plp {
+                        c.update(&a, self.get_balance(&a), self.get_nonce(&a), self.get_uplp_balance(&a));
+                    } else {
+                        let bal = self.asset_balances.read().unwrap().get(&(a.clone(), asset.clone())).copied();
+                        c.update_token(&a, &asset, bal.unwrap_or(0));
+                    }
+                }
+            }
+            None => *c = self.account_tree(),
+        }
+        hex::encode(platarium_consensus::state_commitment(&c.root(), &self.registries_word()))
+    }
+
+    /// The state commitment rebuilt from all accounts, without using or updating the incremental state.
+    /// O(accounts + tokens).
+    pub fn recompute_root(&self) -> String {
+        hex::encode(platarium_consensus::state_commitment(&self.account_tree().root(), &self.registries_word()))
+    }
+
+    pub fn set_uplp_balance(&self, address: &Address, balance: u128) {
+        let mut ub = self.uplp_balances.write().unwrap();
+        Arc::make_mut(&mut ub).insert(address.clone(), balance);
+        drop(ub);
+        self.touch(&[address]);
+    }
+
+    /// Sets PLP asset balance (for initialization/testing). Legacy compat.
+    pub fn set_balance(&self, address: &Address, balance: u128) {
+        self.set_asset_balance(address, &Asset::PLP, balance);
+    }
+
+    /// Registers a token and its issuer (genesis or admin setup). Errors for PLP or an already registered token.
+    pub fn register_token(&self, asset: &Asset, issuer: &Address) -> Result<()> {
+        let mut reg = self.asset_registry.write().unwrap();
+        Arc::make_mut(&mut reg).register(asset, issuer.clone())?;
+        Ok(())
+    }
+
+    /// Returns a copy of the token registry.
+    pub fn asset_registry(&self) -> AssetRegistry {
+        self.asset_registry.read().unwrap().as_ref().clone()
+    }
+
+    /// Runs `f` on the token registry without copying it.
+    pub(crate) fn read_asset_registry<R>(&self, f: impl FnOnce(&AssetRegistry) -> R) -> R {
+        f(&self.asset_registry.read().unwrap())
+    }
+
+    /// Runs `f` on a writable token registry (copy-on-write if a snapshot shares it).
+    pub(crate) fn update_asset_registry<R>(&self, f: impl FnOnce(&mut AssetRegistry) -> R) -> R {
+        let mut reg = self.asset_registry.write().unwrap();
+        f(Arc::make_mut(&mut reg))
+    }
+
+    /// Total supply of a registered token (minted minus burned); `None` for PLP and unregistered tokens.
+    pub fn total_supply(&self, asset: &Asset) -> Option<u128> {
+        self.asset_registry.read().unwrap().supply(asset)
+    }
+
+    /// Checks a registered token's tracked supply against the balances held outside `BURN_ADDRESS`; returns the
+    /// supply. Errors for unregistered assets and on a mismatch (e.g. balances set directly rather than minted).
+    pub fn check_supply(&self, asset: &Asset) -> Result<u128> {
+        let tracked = self
+            .total_supply(asset)
+            .ok_or_else(|| AssetRegistryError::UnknownAsset(asset.as_canonical()))?;
+        let code = asset.as_canonical();
+        let held = self
+            .asset_balances
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|((addr, a), _)| *a == code && addr != BURN_ADDRESS)
+            .fold(0u128, |sum, (_, bal)| sum.saturating_add(*bal));
+        if held != tracked {
+            return Err(StateError::SupplyMismatch { asset: code, tracked, held }.into());
+        }
+        Ok(tracked)
+    }
+
+    /// True if transfers of `asset` are currently frozen by its issuer.
+    pub fn is_asset_frozen(&self, asset: &Asset) -> bool {
+        self.asset_registry.read().unwrap().is_frozen(asset)
+    }
+
+    /// Drains and returns events emitted since the last call, in emission order.
+    pub fn take_events(&self) -> Vec<Event> {
+        std::mem::take(&mut *self.events.write().unwrap())
+    }
+
+    pub(crate) fn emit(&self, event: Event) {
+        self.events.write().unwrap().push(event);
+    }
+
+    /// Applies an issuer freeze/unfreeze: checks issuer authority, charges the fee and bumps the nonce (as a zero-amount self transfer in PLP), sets the flag and emits `AssetFrozen`/`AssetUnfrozen`.
+    pub fn apply_asset_freeze(
+        &self,
+        issuer: &Address,
+        asset: &Asset,
+        frozen: bool,
+        fee_uplp: u128,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        self.asset_registry.read().unwrap().check_issuer(asset, issuer)?;
+        self.apply_transfer(issuer, issuer, &Asset::PLP, 0, fee_uplp, expected_nonce)?;
+        {
+            let mut reg = self.asset_registry.write().unwrap();
+            Arc::make_mut(&mut reg).set_frozen(asset, issuer, frozen)?;
+        }
+        let asset = asset.as_canonical();
+        let issuer = issuer.clone();
+        self.emit(if frozen {
+            Event::AssetFrozen { asset, issuer }
+        } else {
+            Event::AssetUnfrozen { asset, issuer }
+        });
+        Ok(())
+    }
+
+    /// Current block height used by height-dependent rules.
+    pub fn block_height(&self) -> u64 {
+        *self.block_height.read().unwrap()
+    }
+
+    /// Sets the height of the block being executed (call before applying its transactions).
+    pub fn set_block_height(&self, height: u64) {
+        *self.block_height.write().unwrap() = height;
+    }
+
+    /// Layout version and the migrations applied to reach it.
+    pub fn layout(&self) -> StateLayout {
+        self.layout.read().unwrap().clone()
+    }
+
+    /// Replaces the layout metadata (state file loading and `MigrationRegistry::apply_due`).
+    pub fn set_layout(&self, layout: StateLayout) {
+        *self.layout.write().unwrap() = layout;
+    }
+
+    /// Deployment this state accepts transactions for.
+    pub fn chain_spec(&self) -> ChainSpec {
+        self.chain_spec.read().unwrap().clone()
+    }
+
+    /// Sets the deployment this state accepts transactions for (node startup, from the chainspec file).
+    pub fn set_chain_spec(&self, spec: ChainSpec) {
+        *self.chain_spec.write().unwrap() = spec;
+    }
+
+    /// Stateless validation for this state's deployment: `ChainSpec::check_transaction` (deployment salt, chain ID,
+    /// then `validate_basic`).
+    pub fn check_transaction(&self, tx: &Transaction) -> ValidationResult {
+        self.chain_spec.read().unwrap().check_transaction(tx)
+    }
+
+    /// Sets the PLP vesting schedule for `address` (genesis). Replaces any existing schedule.
+    pub fn set_vesting_schedule(&self, address: &Address, schedule: VestingSchedule) -> Result<()> {
+        schedule.validate()?;
+        let mut v = self.vesting.write().unwrap();
+        Arc::make_mut(&mut v).insert(address.clone(), schedule);
+        Ok(())
+    }
+
+    pub fn vesting_schedule(&self, address: &Address) -> Option<VestingSchedule> {
+        self.vesting.read().unwrap().get(address).copied()
+    }
+
+    /// All vesting schedules, sorted by address.
+    pub fn vesting_schedules(&self) -> Vec<(Address, VestingSchedule)> {
+        let v = self.vesting.read().unwrap();
+        let mut out: Vec<_> = v.iter().map(|(a, s)| (a.clone(), *s)).collect();
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        out
+    }
+
+    /// Amount of `address`'s vesting allocation vested at `height` (0 without a schedule).
+    pub fn vested_balance(&self, address: &Address, height: u64) -> u128 {
+        self.vesting_schedule(address)
+            .map(|s| s.vested_at(height))
+            .unwrap_or(0)
+    }
+
+    /// PLP locked by vesting for `address` at `height` (0 without a schedule).
+    pub fn locked_balance(&self, address: &Address, height: u64) -> u128 {
+        self.vesting_schedule(address)
+            .map(|s| s.locked_at(height))
+            .unwrap_or(0)
+    }
+
+    /// PLP balance minus the amount still locked at `height`.
+    pub fn spendable_balance(&self, address: &Address, height: u64) -> u128 {
+        self.get_balance(address)
+            .saturating_sub(self.locked_balance(address, height))
+    }
+
+    /// Stores an enacted freeze order. Use `governance::enact_freeze_order`, which checks validator approvals first.
+    pub(crate) fn add_freeze_order(&self, id: String, order: AddressFreezeOrder) -> Result<()> {
+        let mut fo = self.freeze_orders.write().unwrap();
+        let fo = Arc::make_mut(&mut fo);
+        if fo.contains_key(&id) {
+            return Err(GovernanceError::DuplicateOrder(id).into());
+        }
+        fo.insert(id, order);
+        Ok(())
+    }
+
+    /// Ends the freeze order `id` from `height` on and returns it. Use `governance::lift_freeze_order`, which checks
+    /// validator approvals first.
+    pub(crate) fn lift_freeze_order(&self, id: &str, height: u64) -> Result<AddressFreezeOrder> {
+        let mut fo = self.freeze_orders.write().unwrap();
+        let fo = Arc::make_mut(&mut fo);
+        let order = fo.get_mut(id).ok_or_else(|| GovernanceError::UnknownOrder(id.to_string()))?;
+        if order.lifted_at.is_some() || order.expiry_height.is_some_and(|e| e <= height) {
+            return Err(GovernanceError::OrderNotActive(id.to_string()).into());
+        }
+        order.lifted_at = Some(height);
+        Ok(order.clone())
+    }
+
+    /// The first (by order id) freeze order enforced on `address` at `height`, if any.
+    pub fn active_freeze_order(&self, address: &Address, height: u64) -> Option<(String, AddressFreezeOrder)> {
+        let fo = self.freeze_orders.read().unwrap();
+        fo.iter()
+            .find(|(_, o)| o.address == *address && o.is_active_at(height))
+            .map(|(id, o)| (id.clone(), o.clone()))
+    }
+
+    /// All enacted freeze orders (active or not), sorted by order id.
+    pub fn freeze_orders(&self) -> Vec<(String, AddressFreezeOrder)> {
+        let fo = self.freeze_orders.read().unwrap();
+        fo.iter().map(|(id, o)| (id.clone(), o.clone())).collect()
+    }
+
+    /// Errors with `AddressFrozen` if a freeze order is enforced on `address` at the current block height.
+    pub fn check_not_frozen(&self, address: &Address) -> Result<()> {
+        match self.active_freeze_order(address, self.block_height()) {
+            Some((order_id, _)) => Err(StateError::AddressFrozen {
+                address: address.clone(),
+                order_id,
+            }
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Derived public key registered for `address`, as stored (hex, no `Px` prefix).
+    pub fn registered_derived_key(&self, address: &Address) -> Option<String> {
+        self.derived_keys.read().unwrap().get(address).cloned()
+    }
+
+    /// All registered derived keys, sorted by address.
+    pub fn derived_keys(&self) -> Vec<(Address, String)> {
+        let dk = self.derived_keys.read().unwrap();
+        let mut out: Vec<(Address, String)> = dk.iter().map(|(a, k)| (a.clone(), k.clone())).collect();
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        out
+    }
+
+    /// Binds `key` as the derived key of `address` (genesis, state file load, or first use). Errors if one is already bound.
+    pub fn register_derived_key(&self, address: &Address, key: &str) -> Result<()> {
+        let mut dk = self.derived_keys.write().unwrap();
+        let dk = Arc::make_mut(&mut dk);
+        if dk.contains_key(address) {
+            return Err(StateError::DerivedKeyAlreadyRegistered(address.clone()).into());
+        }
+        let key = key.strip_prefix("Px").unwrap_or(key).to_ascii_lowercase();
+        dk.insert(address.clone(), key);
+        Ok(())
+    }
+
+    /// Checks the transaction's derived key against the one registered for `address`. Accounts without a registered key accept any key (it is bound on first use).
+    pub fn check_derived_key(&self, address: &Address, pub_derived: Option<&str>) -> Result<()> {
+        check_registered_derived_key(address, self.registered_derived_key(address), pub_derived)
+    }
+
+    /// Main key in effect for `address` at the current height: a rotation past its delay, else the key installed by an earlier recovery. `None` if the account never recovered.
+    pub fn main_key(&self, address: &Address) -> Option<String> {
+        if let Some(p) = self.pending_rotation(address) {
+            if p.is_effective_at(self.block_height()) {
+                return Some(p.new_main_key);
+            }
+        }
+        self.main_keys.read().unwrap().get(address).cloned()
+    }
+
+    /// Rotation recorded for `address` and not yet settled into its main key.
+    pub fn pending_rotation(&self, address: &Address) -> Option<PendingRotation> {
+        self.pending_rotations.read().unwrap().get(address).cloned()
+    }
+
+    /// All recorded rotations, sorted by address.
+    pub fn pending_rotations(&self) -> Vec<(Address, PendingRotation)> {
+        let pr = self.pending_rotations.read().unwrap();
+        pr.iter().map(|(a, r)| (a.clone(), r.clone())).collect()
+    }
+
+    /// All main keys installed by recoveries, sorted by address.
+    pub fn main_keys(&self) -> Vec<(Address, String)> {
+        let mk = self.main_keys.read().unwrap();
+        let mut out: Vec<(Address, String)> = mk.iter().map(|(a, k)| (a.clone(), k.clone())).collect();
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        out
+    }
+
+    /// Restores recovery state (state file load).
+    pub(crate) fn set_main_key(&self, address: &Address, key: String) {
+        let mut mk = self.main_keys.write().unwrap();
+        Arc::make_mut(&mut mk).insert(address.clone(), key);
+    }
+
+    /// Restores a pending rotation (state file load).
+    pub(crate) fn set_pending_rotation(&self, address: &Address, rotation: PendingRotation) {
+        let mut pr = self.pending_rotations.write().unwrap();
+        Arc::make_mut(&mut pr).insert(address.clone(), rotation);
+    }
+
+    /// Moves a rotation whose delay has passed into `main_keys` and emits `MainKeyRotated`.
+    fn settle_rotation(&self, address: &Address) {
+        let height = self.block_height();
+        let matured = {
+            let mut pr = self.pending_rotations.write().unwrap();
+            match pr.get(address) {
+                Some(r) if r.is_effective_at(height) => Arc::make_mut(&mut pr).remove(address),
+                _ => None,
+            }
+        };
+        if let Some(r) = matured {
+            self.set_main_key(address, r.new_main_key.clone());
+            self.emit(Event::MainKeyRotated {
+                address: address.clone(),
+                new_main_key: r.new_main_key,
+            });
+        }
+    }
+
+    /// Scope and usage of `session_key` (hex, optional `Px`) registered for `address`.
+    pub fn session_key(&self, address: &Address, session_key: &str) -> Option<SessionKey> {
+        let key = session_key.strip_prefix("Px").unwrap_or(session_key).to_ascii_lowercase();
+        self.session_keys.read().unwrap().get(&(address.clone(), key)).cloned()
+    }
+
+    /// All registered session keys, sorted by (account, key).
+    pub fn session_keys(&self) -> Vec<(SessionKeyId, SessionKey)> {
+        let sk = self.session_keys.read().unwrap();
+        sk.iter().map(|(id, k)| (id.clone(), k.clone())).collect()
+    }
+
+    /// Stores a session key as-is (state file load).
+    pub(crate) fn set_session_key(&self, id: SessionKeyId, key: SessionKey) {
+        let mut sk = self.session_keys.write().unwrap();
+        Arc::make_mut(&mut sk).insert(id, key);
+    }
+
+    /// Standing order `order_id` of `address`.
+    pub fn standing_order(&self, address: &Address, order_id: &str) -> Option<StandingOrder> {
+        self.standing_orders.read().unwrap().get(&(address.clone(), order_id.to_string())).cloned()
+    }
+
+    /// All standing orders, sorted by (sender, order id).
+    pub fn standing_orders(&self) -> Vec<(StandingOrderId, StandingOrder)> {
+        let so = self.standing_orders.read().unwrap();
+        so.iter().map(|(id, o)| (id.clone(), o.clone())).collect()
+    }
+
+    /// Stores a standing order as-is (execution, state file load).
+    pub(crate) fn set_standing_order(&self, id: StandingOrderId, order: StandingOrder) {
+        let mut so = self.standing_orders.write().unwrap();
+        Arc::make_mut(&mut so).insert(id, order);
+    }
+
+    pub(crate) fn remove_standing_order(&self, id: &StandingOrderId) {
+        let mut so = self.standing_orders.write().unwrap();
+        if so.contains_key(id) {
+            Arc::make_mut(&mut so).remove(id);
+        }
+    }
+
+    /// A `CreateStandingOrder` must have a valid schedule and room under the per-account cap; a
+    /// `CancelStandingOrder` must name one of the sender's orders.
+    pub(crate) fn check_standing_order_tx(&self, tx: &Transaction) -> Result<()> {
+        match tx.kind {
+            TxKind::CreateStandingOrder { interval_blocks, end_height } => {
+                StandingOrder::new(
+                    tx.to.clone(),
+                    tx.asset.clone(),
+                    tx.amount,
+                    interval_blocks,
+                    end_height,
+                    self.block_height(),
+                )?;
+                let so = self.standing_orders.read().unwrap();
+                if so.keys().filter(|(from, _)| *from == tx.from).count() >= MAX_STANDING_ORDERS_PER_ACCOUNT {
+                    return Err(StandingOrderError::TooMany(tx.from.clone()).into());
+                }
+                Ok(())
+            }
+            TxKind::CancelStandingOrder { ref order_id } => match self.standing_order(&tx.from, order_id) {
+                Some(_) => Ok(()),
+                None => Err(StandingOrderError::NotFound {
+                    address: tx.from.clone(),
+                    order_id: order_id.clone(),
+                }
+                .into()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// How `address` authorizes its transactions.
+    pub fn account_kind(&self, address: &Address) -> AccountKind {
+        self.account_kinds.read().unwrap().get(address).cloned().unwrap_or_default()
+    }
+
+    /// All multisig accounts, sorted by address.
+    pub fn multisig_accounts(&self) -> Vec<(Address, AccountKind)> {
+        let ak = self.account_kinds.read().unwrap();
+        let mut v: Vec<_> = ak.iter().map(|(a, k)| (a.clone(), k.clone())).collect();
+        v.sort_by(|a, b| a.0.cmp(&b.0));
+        v
+    }
+
+    /// Stores an account kind as-is (state file load); `Single` removes the entry.
+    pub(crate) fn set_account_kind(&self, address: &Address, kind: AccountKind) {
+        let mut ak = self.account_kinds.write().unwrap();
+        let ak = Arc::make_mut(&mut ak);
+        match kind {
+            AccountKind::Single => ak.remove(address),
+            kind => ak.insert(address.clone(), kind),
+        };
+    }
+
+    /// PLP staked by `address`. Returns 0 if none.
+    pub fn get_stake(&self, address: &Address) -> u128 {
+        self.stakes.read().unwrap().get(address).copied().unwrap_or(0)
+    }
+
+    /// All non-zero stakes, sorted by address.
+    pub fn stakes(&self) -> Vec<(Address, u128)> {
+        let st = self.stakes.read().unwrap();
+        let mut v: Vec<_> = st.iter().map(|(a, s)| (a.clone(), *s)).collect();
+        v.sort_by(|a, b| a.0.cmp(&b.0));
+        v
+    }
+
+    /// Stores a stake as-is (state file load); 0 removes the entry.
+    pub(crate) fn set_stake(&self, address: &Address, amount: u128) {
+        let mut st = self.stakes.write().unwrap();
+        let st = Arc::make_mut(&mut st);
+        if amount == 0 {
+            st.remove(address);
+        } else {
+            st.insert(address.clone(), amount);
+        }
+    }
+
+    /// Recorded invalid-transaction evidence and paid claims. Modified only by `insurance::record_evidence` and
+    /// `insurance::pay_claim`.
+    pub fn insurance_fund(&self) -> InsuranceFund {
+        self.insurance.read().unwrap().as_ref().clone()
+    }
+
+    /// Replaces the insurance records as-is (state file load).
+    pub(crate) fn set_insurance_fund(&self, fund: InsuranceFund) {
+        *self.insurance.write().unwrap() = Arc::new(fund);
+    }
+
+    /// Runs `f` on the insurance records in place.
+    pub(crate) fn update_insurance_fund<R>(&self, f: impl FnOnce(&mut InsuranceFund) -> R) -> R {
+        let mut fund = self.insurance.write().unwrap();
+        f(Arc::make_mut(&mut fund))
+    }
+
+    /// Unstaked PLP waiting for release, as (address, release height, amount) sorted by address then height.
+    pub fn unbonding(&self) -> Vec<(Address, u64, u128)> {
+        let ub = self.unbonding.read().unwrap();
+        ub.iter().map(|((a, h), amount)| (a.clone(), *h, *amount)).collect()
+    }
+
+    /// Total PLP `address` has unbonding. Returns 0 if none.
+    pub fn get_unbonding(&self, address: &Address) -> u128 {
+        let ub = self.unbonding.read().unwrap();
+        ub.range((address.clone(), 0)..=(address.clone(), u64::MAX)).map(|(_, a)| a).sum()
+    }
+
+    /// Stores an unbonding entry as-is (state file load); 0 removes the entry.
+    pub(crate) fn set_unbonding(&self, address: &Address, release_height: u64, amount: u128) {
+        let mut ub = self.unbonding.write().unwrap();
+        let ub = Arc::make_mut(&mut ub);
+        if amount == 0 {
+            ub.remove(&(address.clone(), release_height));
+        } else {
+            ub.insert((address.clone(), release_height), amount);
+        }
+    }
+
+    /// Pays out every unbonding entry whose release height has been reached: moves the PLP from `STAKING_ADDRESS`
+    /// to its owner and emits `StakeReleased`. Run by `ExecutionLogic::execute_block` before the block's
+    /// transactions. Returns the released (address, amount) pairs in address order.
+    pub fn release_unbonded(&self) -> Vec<(Address, u128)> {
+        let height = self.block_height();
+        let due: Vec<((Address, u64), u128)> = self
+            .unbonding
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|((_, release), _)| *release <= height)
+            .map(|(k, a)| (k.clone(), *a))
+            .collect();
+        let pool = STAKING_ADDRESS.to_string();
+        let mut released = Vec::with_capacity(due.len());
+        for ((address, release_height), amount) in due {
+            let pool_balance = self.get_asset_balance(&pool, &Asset::PLP);
+            let balance = self.get_asset_balance(&address, &Asset::PLP);
+            self.set_asset_balance(&pool, &Asset::PLP, pool_balance.saturating_sub(amount));
+            self.set_asset_balance(&address, &Asset::PLP, balance.saturating_add(amount));
+            self.set_unbonding(&address, release_height, 0);
+            self.emit(Event::StakeReleased {
+                address: address.clone(),
+                amount,
+            });
+            released.push((address, amount));
+        }
+        released
+    }
+
+    /// Removes up to `amount` of `address`'s stake, bonded first, then unbonding entries from the latest release,
+    /// and moves the PLP from `STAKING_ADDRESS` to `destination`'s address. Returns the amount removed, which is
+    /// less than `amount` when the account holds less.
+    pub fn slash_stake(&self, address: &Address, amount: u128, destination: SlashDestination) -> u128 {
+        let bonded = self.get_stake(address).min(amount);
+        self.set_stake(address, self.get_stake(address) - bonded);
+        let mut taken = bonded;
+        let entries: Vec<((Address, u64), u128)> = {
+            let ub = self.unbonding.read().unwrap();
+            ub.range((address.clone(), 0)..=(address.clone(), u64::MAX))
+                .rev()
+                .map(|(k, a)| (k.clone(), *a))
+                .collect()
+        };
+        for ((_, release_height), entry) in entries {
+            if taken == amount {
+                break;
+            }
+            let cut = entry.min(amount - taken);
+            self.set_unbonding(address, release_height, entry - cut);
+            taken += cut;
+        }
+        if taken > 0 {
+            let pool = STAKING_ADDRESS.to_string();
+            let to = destination.address().to_string();
+            let pool_balance = self.get_asset_balance(&pool, &Asset::PLP);
+            self.set_asset_balance(&pool, &Asset::PLP, pool_balance.saturating_sub(taken));
+            let balance = self.get_asset_balance(&to, &Asset::PLP);
+            self.set_asset_balance(&to, &Asset::PLP, balance.saturating_add(taken));
+        }
+        taken
+    }
+
+    /// Multisig accounts must carry a witness naming their registered signer set and threshold, and cannot use
+    /// recovery; single-key accounts must not carry one. A `SetMultisig` must name a valid configuration, and a
+    /// single-key account converts only with a proven main key: the key its address is made of, or the one installed
+    /// by recovery.
+    fn check_multisig_tx(&self, tx: &Transaction) -> Result<()> {
+        if let TxKind::SetMultisig { ref signers, threshold } = tx.kind {
+            check_multisig_config(signers, threshold)?;
+        }
+        match (self.account_kind(&tx.from), &tx.multisig) {
+            (AccountKind::Multisig { signers, threshold }, Some(witness)) => {
+                if !witness.matches(&signers, threshold) {
+                    return Err(MultisigError::SignerSetMismatch(tx.from.clone()).into());
+                }
+                if let TxKind::InitiateRecovery { .. } | TxKind::CancelRecovery | TxKind::RegisterDerivedKey = tx.kind {
+                    return Err(MultisigError::RecoveryNotSupported(tx.from.clone()).into());
+                }
+                Ok(())
+            }
+            (AccountKind::Multisig { .. }, None) => Err(MultisigError::WitnessRequired(tx.from.clone()).into()),
+            (AccountKind::Single, Some(_)) => Err(MultisigError::NotMultisig(tx.from.clone()).into()),
+            (AccountKind::Single, None) => {
+                if let TxKind::SetMultisig { .. } = tx.kind {
+                    self.check_main_key(&tx.from, tx.pub_main.as_deref().unwrap_or(&tx.from))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn check_session_key_tx(&self, tx: &Transaction) -> Result<()> {
+        let height = self.block_height();
+        match tx.kind {
+            TxKind::RegisterSessionKey {
+                ref session_key,
+                max_amount_per_block,
+                expiry_height,
+            } => {
+                let key = normalize_public_key_hex(session_key)?;
+                SessionKey::new(tx.asset.as_canonical(), max_amount_per_block, expiry_height).validate(height)?;
+                match self.session_key(&tx.from, &key) {
+                    Some(existing) if existing.expiry_height > height => {
+                        Err(SessionKeyError::AlreadyRegistered(key).into())
+                    }
+                    _ => Ok(()),
+                }
+            }
+            TxKind::RevokeSessionKey { ref session_key } | TxKind::SessionTransfer { ref session_key } => {
+                let key = self.session_key(&tx.from, session_key).ok_or_else(|| SessionKeyError::NotRegistered {
+                    address: tx.from.clone(),
+                    key: session_key.clone(),
+                })?;
+                if let TxKind::SessionTransfer { .. } = tx.kind {
+                    key.check_spend(&tx.asset.as_canonical(), tx.amount, height)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_main_key(&self, address: &Address, pub_main: &str) -> Result<()> {
+        check_installed_main_key(address, self.main_key(address), pub_main)
+    }
+
+    /// Key checks shared by applicability and `apply_effects`: the derived key must match the registered one; the main key must be the key the sender address is made of, or the key installed by recovery (except for `InitiateRecovery`, which only the derived key signs); recovery kinds must find the pending rotation in the right state; session key kinds must respect the registered scope. `SessionTransfer` is checked against its session key only; a multisig account's transaction against its registered signer set only (`check_multisig_tx`).
+    pub fn check_account_keys(&self, tx: &Transaction) -> Result<()> {
+        if let TxKind::SessionTransfer { .. } = tx.kind {
+            return self.check_session_key_tx(tx);
+        }
+        self.check_multisig_tx(tx)?;
+        if tx.multisig.is_some() {
+            return self.check_session_key_tx(tx);
+        }
+        self.check_derived_key(&tx.from, tx.pub_derived.as_deref())?;
+        self.check_session_key_tx(tx)?;
+        let pub_main = tx.pub_main.as_deref().unwrap_or(&tx.from);
+        let pending = self
+            .pending_rotation(&tx.from)
+            .filter(|p| !p.is_effective_at(self.block_height()));
+        match tx.kind {
+            TxKind::InitiateRecovery { ref new_main_key } => {
+                if self.registered_derived_key(&tx.from).is_none() {
+                    return Err(StateError::MissingDerivedKey(tx.from.clone()).into());
+                }
+                normalize_main_key(new_main_key)?;
+                if pending.is_some() {
+                    return Err(RecoveryError::AlreadyPending(tx.from.clone()).into());
+                }
+                Ok(())
+            }
+            TxKind::CancelRecovery => {
+                self.check_main_key(&tx.from, pub_main)?;
+                match (pending, self.pending_rotation(&tx.from)) {
+                    (Some(_), _) => Ok(()),
+                    (None, Some(_)) => Err(RecoveryError::WindowClosed(tx.from.clone()).into()),
+                    (None, None) => Err(RecoveryError::NonePending(tx.from.clone()).into()),
+                }
+            }
+            _ => self.check_main_key(&tx.from, pub_main),
+        }
+    }
+
+    /// Remaining amount `spender` may move of `owner`'s `asset`. Returns 0 if none.
+    pub fn get_allowance(&self, owner: &Address, spender: &Address, asset: &Asset) -> u128 {
+        let al = self.allowances.read().unwrap();
+        al.get(&(owner.clone(), spender.clone(), asset.as_canonical()))
+            .copied()
+            .unwrap_or(0)
     }
 
-    pub fn get_nonce(&self, address: &Address) -> u64 {
-        let nonces = self.nonces.read().unwrap();
-        nonces.get(address).copied().unwrap_or(0)
+    /// All allowances as ((owner, spender, asset canonical), remaining amount), sorted.
+    pub fn allowances(&self) -> Vec<(AllowanceKey, u128)> {
+        let al = self.allowances.read().unwrap();
+        let mut v: Vec<_> = al.iter().map(|(k, a)| (k.clone(), *a)).collect();
+        v.sort();
+        v
     }
 
-    pub fn set_asset_balance(&self, address: &Address, asset: &Asset, balance: u128) {
-        let mut ab = self.asset_balances.write().unwrap();
-        let ab_mut = Arc::make_mut(&mut ab);
-        ab_mut.insert(Self::asset_key(address, asset), balance);
+    /// Stores an allowance as-is (approvals and state file load); 0 removes the entry.
+    pub(crate) fn set_allowance(&self, owner: &Address, spender: &Address, asset: &Asset, amount: u128) {
+        let mut al = self.allowances.write().unwrap();
+        let al = Arc::make_mut(&mut al);
+        let key = (owner.clone(), spender.clone(), asset.as_canonical());
+        if amount == 0 {
+            al.remove(&key);
+        } else {
+            al.insert(key, amount);
+        }
     }
 
-    pub fn set_uplp_balance(&self, address: &Address, balance: u128) {
-        let mut ub = self.uplp_balances.write().unwrap();
-        Arc::make_mut(&mut ub).insert(address.clone(), balance);
+    /// Applies an `Approve`: charges the fee and bumps the owner's nonce, then sets the (owner, spender, asset) allowance to `amount` (0 revokes) and emits `Approval`.
+    pub fn apply_approve(
+        &self,
+        owner: &Address,
+        spender: &Address,
+        asset: &Asset,
+        amount: u128,
+        fee_uplp: u128,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        if owner == spender {
+            return Err(StateError::SelfAllowance.into());
+        }
+        self.apply_transfer(owner, owner, &Asset::PLP, 0, fee_uplp, expected_nonce)?;
+        self.set_allowance(owner, spender, asset, amount);
+        self.emit(Event::Approval {
+            owner: owner.clone(),
+            spender: spender.clone(),
+            asset: asset.as_canonical(),
+            amount,
+        });
+        Ok(())
     }
 
-    /// Sets PLP asset balance (for initialization/testing). Legacy compat.
-    pub fn set_balance(&self, address: &Address, balance: u128) {
-        self.set_asset_balance(address, &Asset::PLP, balance);
+    /// Applies a `TransferFrom`: `spender` moves `amount` of `owner`'s `asset` to `to`, paying the fee and using its own nonce. Allowance, freeze flag and owner balance are checked before any write, so the operation is all-or-nothing. Emits `DelegatedTransfer`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_transfer_from(
+        &self,
+        spender: &Address,
+        owner: &Address,
+        to: &Address,
+        asset: &Asset,
+        amount: u128,
+        fee_uplp: u128,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        if owner == spender {
+            return Err(StateError::SelfAllowance.into());
+        }
+        let allowance = self.get_allowance(owner, spender, asset);
+        if allowance < amount {
+            return Err(StateError::InsufficientAllowance {
+                required: amount,
+                available: allowance,
+            }
+            .into());
+        }
+        if self.is_asset_frozen(asset) {
+            return Err(StateError::AssetFrozen(asset.as_canonical()).into());
+        }
+        let owner_bal = self.get_asset_balance(owner, asset);
+        if owner_bal < amount {
+            return Err(StateError::InsufficientBalance {
+                required: amount,
+                available: owner_bal,
+            }
+            .into());
+        }
+        // Fee and nonce on the spender; the owner's balances are untouched by this step.
+        self.apply_transfer(spender, spender, &Asset::PLP, 0, fee_uplp, expected_nonce)?;
+        self.apply_transfer(owner, to, asset, amount, 0, None)?;
+        let remaining = allowance - amount;
+        self.set_allowance(owner, spender, asset, remaining);
+        self.emit(Event::DelegatedTransfer {
+            owner: owner.clone(),
+            spender: spender.clone(),
+            to: to.clone(),
+            asset: asset.as_canonical(),
+            amount,
+            remaining,
+        });
+        Ok(())
     }
 
     /// μPLP available for fees: PLP asset balance (μPLP units) plus legacy uplp pool.
@@ -232,7 +1605,7 @@ impl State {
             .saturating_add(self.get_uplp_balance(address))
     }
 
-    /// Applies a transfer: deduct fee from uplp then PLP asset; amount from asset; credit receiver and treasury.
+    /// Applies a transfer: rejected if the token is frozen or the PLP spent exceeds the unvested remainder; deduct fee from uplp then PLP asset; amount from asset; credit receiver and treasury.
     /// Fee is always μPLP (fraction of PLP). Order: fee, then asset transfer, then nonce (deterministic).
     pub fn apply_transfer(
         &self,
@@ -243,84 +1616,190 @@ impl State {
         fee_uplp: u128,
         expected_nonce: Option<u64>,
     ) -> Result<()> {
-        let treasury = TREASURY_ADDRESS.to_string();
-        let k = Self::asset_key(from, asset);
-
+        if self.is_asset_frozen(asset) {
+            return Err(StateError::AssetFrozen(asset.as_canonical()).into());
+        }
+        let locked = self.locked_balance(from, self.block_height());
         let mut ab_arc = self.asset_balances.write().unwrap();
         let mut ub_arc = self.uplp_balances.write().unwrap();
         let mut nonces_arc = self.nonces.write().unwrap();
-        let ab = Arc::make_mut(&mut ab_arc);
-        let ub = Arc::make_mut(&mut ub_arc);
-        let nonces = Arc::make_mut(&mut nonces_arc);
-
-        if let Some(expected) = expected_nonce {
-            let cur = nonces.get(from).copied().unwrap_or(0);
-            if cur != expected {
-                return Err(StateError::InvalidNonce { expected, got: cur }.into());
-            }
+        let mut ledger = MapLedger {
+            asset_balances: Arc::make_mut(&mut ab_arc),
+            uplp_balances: Arc::make_mut(&mut ub_arc),
+            nonces: Arc::make_mut(&mut nonces_arc),
+        };
+        transfer_effects(&mut ledger, from, to, asset, amount, fee_uplp, expected_nonce, locked)?;
+        drop(ab_arc);
+        drop(ub_arc);
+        drop(nonces_arc);
+        self.touch(&[from, to, &TREASURY_ADDRESS.to_string()]);
+        if *asset != Asset::PLP {
+            self.touch_asset(from, &asset.as_canonical());
+            self.touch_asset(to, &asset.as_canonical());
         }
-        let asset_bal = ab.get(&k).copied().unwrap_or(0);
-        let uplp_bal = ub.get(from).copied().unwrap_or(0);
-        let plp_key = Self::asset_key(from, &Asset::PLP);
-        let plp_asset_bal = ab.get(&plp_key).copied().unwrap_or(0);
-        let fee_from_plp = fee_uplp.saturating_sub(uplp_bal);
-
-        if *asset == Asset::PLP {
-            if asset_bal < amount.saturating_add(fee_from_plp) {
-                return Err(StateError::InsufficientBalance {
-                    required: amount.saturating_add(fee_from_plp),
-                    available: asset_bal,
-                }
-                .into());
-            }
-        } else if asset_bal < amount {
+        Ok(())
+    }
+    
+    /// Credits a fee rebate: moves `amount` μPLP from the treasury fee pool back to `to`'s μPLP balance.
+    /// Errors if the treasury holds less than `amount`. Total μPLP is unchanged.
+    pub fn credit_fee_rebate(&self, to: &Address, amount: u128) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let treasury = TREASURY_ADDRESS.to_string();
+        let mut ub_arc = self.uplp_balances.write().unwrap();
+        let ub = Arc::make_mut(&mut ub_arc);
+        let treasury_bal = ub.get(&treasury).copied().unwrap_or(0);
+        if treasury_bal < amount {
             return Err(StateError::InsufficientBalance {
                 required: amount,
-                available: asset_bal,
+                available: treasury_bal,
             }
             .into());
         }
+        ub.insert(treasury.clone(), treasury_bal - amount);
+        let to_bal = ub.get(to).copied().unwrap_or(0);
+        ub.insert(to.clone(), to_bal + amount);
+        drop(ub_arc);
+        self.touch(&[&treasury, to]);
+        Ok(())
+    }
 
-        let fee_pool = plp_asset_bal.saturating_add(uplp_bal);
-        if fee_pool < fee_uplp {
-            return Err(StateError::InsufficientBalance {
-                required: fee_uplp,
-                available: fee_pool,
-            }
-            .into());
-        }
+    /// Applies a `MultiTransfer`: charges the fee and bumps the nonce, then pays each output from `from` with the
+    /// rules of `apply_transfer`, in order. All writes are staged and only land if every output succeeds, so a frozen
+    /// asset, a vesting lock or a short balance on any output leaves the state unchanged.
+    pub fn apply_multi_transfer(
+        &self,
+        from: &Address,
+        outputs: &[TransferOutput],
+        fee_uplp: u128,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        let (asset_balances, uplp_balances, nonces) = self.stage_multi_transfer(from, outputs, fee_uplp, expected_nonce)?;
+        self.apply_writes(asset_balances, uplp_balances, nonces, HashMap::new());
+        Ok(())
+    }
 
-        // Fee: legacy uplp pool first, remainder from PLP asset balance.
-        let fee_uplp_used = fee_uplp.min(uplp_bal);
-        let fee_plp_used = fee_uplp - fee_uplp_used;
-        ub.insert(from.clone(), uplp_bal - fee_uplp_used);
-        let sender_plp_after_fee = if fee_plp_used > 0 {
-            plp_asset_bal - fee_plp_used
-        } else {
-            plp_asset_bal
-        };
-        if *asset != Asset::PLP && fee_plp_used > 0 {
-            ab.insert(plp_key.clone(), sender_plp_after_fee);
+    /// Checks that [`Self::apply_multi_transfer`] would succeed, without writing.
+    pub fn check_multi_transfer(&self, from: &Address, outputs: &[TransferOutput], fee_uplp: u128) -> Result<()> {
+        self.stage_multi_transfer(from, outputs, fee_uplp, None).map(|_| ())
+    }
+
+    fn stage_multi_transfer(
+        &self,
+        from: &Address,
+        outputs: &[TransferOutput],
+        fee_uplp: u128,
+        expected_nonce: Option<u64>,
+    ) -> Result<StagedWrites> {
+        if let Some(output) = outputs.iter().find(|o| self.is_asset_frozen(&o.asset)) {
+            return Err(StateError::AssetFrozen(output.asset.as_canonical()).into());
+        }
+        let locked = self.locked_balance(from, self.block_height());
+        let mut staged = StagedLedger::new(self);
+        transfer_effects(&mut staged, from, from, &Asset::PLP, 0, fee_uplp, expected_nonce, locked)?;
+        for output in outputs {
+            transfer_effects(&mut staged, from, &output.to, &output.asset, output.amount, 0, None, locked)?;
         }
+        Ok(staged.into_writes())
+    }
 
-        let treasury_bal = ub.get(&treasury).copied().unwrap_or(0);
-        ub.insert(treasury, treasury_bal + fee_uplp);
+    /// Applies a `Mint`: the issuer pays the fee and bumps its nonce, then `amount` of `asset` is credited to `to` and
+    /// added to the token's supply. The issuer check and the overflow checks run before any write. Emits `Minted`.
+    pub fn apply_mint(
+        &self,
+        issuer: &Address,
+        to: &Address,
+        asset: &Asset,
+        amount: u128,
+        fee_uplp: u128,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        self.read_asset_registry(|r| r.check_mint(asset, issuer, amount))?;
+        let balance = self
+            .get_asset_balance(to, asset)
+            .checked_add(amount)
+            .ok_or_else(|| StateError::Other("minted balance overflow".to_string()))?;
+        self.apply_transfer(issuer, issuer, &Asset::PLP, 0, fee_uplp, expected_nonce)?;
+        self.set_asset_balance(to, asset, balance);
+        self.update_asset_registry(|r| r.add_supply(asset, amount))?;
+        self.emit(Event::Minted {
+            issuer: issuer.clone(),
+            to: to.clone(),
+            asset: asset.as_canonical(),
+            amount,
+        });
+        Ok(())
+    }
 
-        if *asset == Asset::PLP {
-            ab.insert(k.clone(), sender_plp_after_fee - amount);
-        } else {
-            ab.insert(k.clone(), asset_bal - amount);
-        }
-        let to_k = Self::asset_key(to, asset);
-        let to_bal = ab.get(&to_k).copied().unwrap_or(0);
-        ab.insert(to_k, to_bal + amount);
+    /// Applies a `Burn`: moves `amount` of `asset` from `from` to `BURN_ADDRESS` and, for a registered token, removes it
+    /// from the supply. A burn above the supply is rejected before any write.
+    pub fn apply_burn(
+        &self,
+        from: &Address,
+        asset: &Asset,
+        amount: u128,
+        fee_uplp: u128,
+        expected_nonce: Option<u64>,
+    ) -> Result<()> {
+        self.read_asset_registry(|r| r.check_burn(asset, amount))?;
+        self.apply_transfer(from, &BURN_ADDRESS.to_string(), asset, amount, fee_uplp, expected_nonce)?;
+        self.update_asset_registry(|r| r.remove_supply(asset, amount))?;
+        Ok(())
+    }
+
+    /// Applies a `Stake`: moves `amount` PLP from `address` to `STAKING_ADDRESS` (vesting-locked PLP cannot be
+    /// staked) and adds it to the address's stake. Emits `Staked`.
+    pub fn apply_stake(&self, address: &Address, amount: u128, fee_uplp: u128, expected_nonce: Option<u64>) -> Result<()> {
+        let total = self
+            .get_stake(address)
+            .checked_add(amount)
+            .ok_or_else(|| StateError::Other("stake overflow".to_string()))?;
+        let pool = STAKING_ADDRESS.to_string();
+        self.apply_transfer(address, &pool, &Asset::PLP, amount, fee_uplp, expected_nonce)?;
+        self.set_stake(address, total);
+        self.emit(Event::Staked {
+            address: address.clone(),
+            amount,
+            total,
+        });
+        Ok(())
+    }
 
-        if let Some(expected) = expected_nonce {
-            nonces.insert(from.clone(), expected + 1);
+    /// Applies an `Unstake`: charges the fee and bumps the nonce, then subtracts `amount` from the address's stake and
+    /// queues it for release `UNBONDING_DELAY_BLOCKS` after the current height (`release_unbonded`). The PLP stays in
+    /// `STAKING_ADDRESS`, and slashable, until then. Errors before any write if the stake is smaller. Emits `Unstaked`.
+    pub fn apply_unstake(&self, address: &Address, amount: u128, fee_uplp: u128, expected_nonce: Option<u64>) -> Result<()> {
+        let staked = self.get_stake(address);
+        if staked < amount {
+            return Err(StateError::InsufficientStake {
+                required: amount,
+                available: staked,
+            }
+            .into());
+        }
+        let pool = STAKING_ADDRESS.to_string();
+        let pool_balance = self.get_asset_balance(&pool, &Asset::PLP);
+        if pool_balance < amount {
+            return Err(StateError::InsufficientBalance {
+                required: amount,
+                available: pool_balance,
+            }
+            .into());
         }
+        self.apply_transfer(address, address, &Asset::PLP, 0, fee_uplp, expected_nonce)?;
+        let release_height = self.block_height().saturating_add(UNBONDING_DELAY_BLOCKS);
+        let unbonding = self.unbonding.read().unwrap().get(&(address.clone(), release_height)).copied().unwrap_or(0);
+        self.set_unbonding(address, release_height, unbonding + amount);
+        self.set_stake(address, staked - amount);
+        self.emit(Event::Unstaked {
+            address: address.clone(),
+            amount,
+            total: staked - amount,
+        });
         Ok(())
     }
-    
+
     /// Sets nonce for an address (for initialization/testing)
     /// 
     /// PERFORMANCE: Creates new Arc if HashMap is shared (copy-on-write)
@@ -328,6 +1807,8 @@ impl State {
         let mut nonces_arc = self.nonces.write().unwrap();
         // Use Arc::make_mut for copy-on-write: clones only if shared
         Arc::make_mut(&mut nonces_arc).insert(address.clone(), nonce);
+        drop(nonces_arc);
+        self.touch(&[address]);
     }
     
     /// Gets all balances (for debugging/testing)
@@ -393,19 +1874,58 @@ impl State {
         let ab_arc = self.asset_balances.read().unwrap();
         let ub_arc = self.uplp_balances.read().unwrap();
         let nc_arc = self.nonces.read().unwrap();
-        assert!(**ab_arc == ab_snap, "INVARIANT: state changed during snapshot");
-        assert!(**ub_arc == ub_snap, "INVARIANT: state changed during snapshot");
-        assert!(**nc_arc == nc_snap, "INVARIANT: state changed during snapshot");
-        let snapshot = StateSnapshot::new(ab_arc.clone(), ub_arc.clone(), nc_arc.clone());
-        assert!(**snapshot.asset_balances_arc() == ab_snap, "INVARIANT: snapshot != state");
-        assert!(**snapshot.uplp_balances_arc() == ub_snap, "INVARIANT: snapshot != state");
-        assert!(**snapshot.nonces_arc() == nc_snap, "INVARIANT: snapshot != state");
+        check_invariant(
+            **ab_arc == ab_snap && **ub_arc == ub_snap && **nc_arc == nc_snap,
+            SNAPSHOT_STABLE,
+            || "state changed during snapshot".to_string(),
+        );
+        let reg_arc = self.asset_registry.read().unwrap();
+        let al_arc = self.allowances.read().unwrap();
+        let vs_arc = self.vesting.read().unwrap();
+        let fo_arc = self.freeze_orders.read().unwrap();
+        let dk_arc = self.derived_keys.read().unwrap();
+        let mk_arc = self.main_keys.read().unwrap();
+        let pr_arc = self.pending_rotations.read().unwrap();
+        let sk_arc = self.session_keys.read().unwrap();
+        let so_arc = self.standing_orders.read().unwrap();
+        let ak_arc = self.account_kinds.read().unwrap();
+        let st_arc = self.stakes.read().unwrap();
+        let ub_arc2 = self.unbonding.read().unwrap();
+        let in_arc = self.insurance.read().unwrap();
+        let snapshot = StateSnapshot {
+            asset_balances: ab_arc.clone(),
+            uplp_balances: ub_arc.clone(),
+            nonces: nc_arc.clone(),
+            asset_registry: reg_arc.clone(),
+            allowances: al_arc.clone(),
+            vesting: vs_arc.clone(),
+            freeze_orders: fo_arc.clone(),
+            derived_keys: dk_arc.clone(),
+            main_keys: mk_arc.clone(),
+            pending_rotations: pr_arc.clone(),
+            session_keys: sk_arc.clone(),
+            standing_orders: so_arc.clone(),
+            account_kinds: ak_arc.clone(),
+            stakes: st_arc.clone(),
+            unbonding: ub_arc2.clone(),
+            insurance: in_arc.clone(),
+            block_height: self.block_height(),
+        };
+        check_invariant(
+            **snapshot.asset_balances_arc() == ab_snap
+                && **snapshot.uplp_balances_arc() == ub_snap
+                && **snapshot.nonces_arc() == nc_snap,
+            SNAPSHOT_MATCHES_STATE,
+            || "snapshot != state".to_string(),
+        );
         let ab2 = self.asset_balances.read().unwrap();
         let ub2 = self.uplp_balances.read().unwrap();
         let nc2 = self.nonces.read().unwrap();
-        assert!(**ab2 == ab_snap, "INVARIANT: snapshot creation modified state");
-        assert!(**ub2 == ub_snap, "INVARIANT: snapshot creation modified state");
-        assert!(**nc2 == nc_snap, "INVARIANT: snapshot creation modified state");
+        check_invariant(
+            **ab2 == ab_snap && **ub2 == ub_snap && **nc2 == nc_snap,
+            SNAPSHOT_SIDE_EFFECT_FREE,
+            || "snapshot creation modified state".to_string(),
+        );
         snapshot
     }
     
@@ -457,9 +1977,7 @@ impl State {
     /// - Restore is atomic (all or nothing)
     /// - Restore order is deterministic
     pub fn restore(&self, snapshot: &StateSnapshot) {
-        assert!(Arc::strong_count(snapshot.asset_balances_arc()) > 0);
-        assert!(Arc::strong_count(snapshot.uplp_balances_arc()) > 0);
-        assert!(Arc::strong_count(snapshot.nonces_arc()) > 0);
+        check_invariant(snapshot_live(snapshot), SNAPSHOT_LIVE, || "restore from a released snapshot".to_string());
         let ab_snap = snapshot.asset_balances_arc().as_ref().clone();
         let ub_snap = snapshot.uplp_balances_arc().as_ref().clone();
         let nc_snap = snapshot.nonces_arc().as_ref().clone();
@@ -469,26 +1987,329 @@ impl State {
         *ab = snapshot.asset_balances_arc().clone();
         *ub = snapshot.uplp_balances_arc().clone();
         *nc = snapshot.nonces_arc().clone();
-        assert!(**ab == ab_snap, "INVARIANT: restore failed");
-        assert!(**ub == ub_snap, "INVARIANT: restore failed");
-        assert!(**nc == nc_snap, "INVARIANT: restore failed");
-        assert!(Arc::strong_count(snapshot.asset_balances_arc()) > 0);
-        assert!(Arc::strong_count(snapshot.uplp_balances_arc()) > 0);
-        assert!(Arc::strong_count(snapshot.nonces_arc()) > 0);
+        *self.asset_registry.write().unwrap() = snapshot.asset_registry_arc().clone();
+        *self.allowances.write().unwrap() = snapshot.allowances_arc().clone();
+        *self.vesting.write().unwrap() = snapshot.vesting_arc().clone();
+        *self.freeze_orders.write().unwrap() = snapshot.freeze_orders_arc().clone();
+        *self.derived_keys.write().unwrap() = snapshot.derived_keys_arc().clone();
+        *self.main_keys.write().unwrap() = snapshot.main_keys_arc().clone();
+        *self.pending_rotations.write().unwrap() = snapshot.pending_rotations_arc().clone();
+        *self.session_keys.write().unwrap() = snapshot.session_keys_arc().clone();
+        *self.standing_orders.write().unwrap() = snapshot.standing_orders_arc().clone();
+        *self.account_kinds.write().unwrap() = snapshot.account_kinds_arc().clone();
+        *self.stakes.write().unwrap() = snapshot.stakes_arc().clone();
+        *self.unbonding.write().unwrap() = snapshot.unbonding_arc().clone();
+        *self.insurance.write().unwrap() = snapshot.insurance_arc().clone();
+        self.set_block_height(snapshot.block_height());
+        *self.touched.write().unwrap() = None;
+        check_invariant(
+            **ab == ab_snap && **ub == ub_snap && **nc == nc_snap,
+            RESTORE_IDENTITY,
+            || "restore failed".to_string(),
+        );
+        check_invariant(snapshot_live(snapshot), SNAPSHOT_LIVE, || "snapshot released during restore".to_string());
     }
     
-    /// Applies a transaction: validate_basic, then apply_transfer(from, to, asset, amount, fee_uplp, nonce).
+    /// True if the state has not been written or restored elsewhere since `snapshot` was taken from it: every map
+    /// is still the snapshot's shared one. O(1).
+    pub(crate) fn is_at(&self, snapshot: &StateSnapshot) -> bool {
+        Arc::ptr_eq(&self.asset_balances.read().unwrap(), snapshot.asset_balances_arc())
+            && Arc::ptr_eq(&self.uplp_balances.read().unwrap(), snapshot.uplp_balances_arc())
+            && Arc::ptr_eq(&self.nonces.read().unwrap(), snapshot.nonces_arc())
+            && Arc::ptr_eq(&self.asset_registry.read().unwrap(), snapshot.asset_registry_arc())
+            && Arc::ptr_eq(&self.allowances.read().unwrap(), snapshot.allowances_arc())
+            && Arc::ptr_eq(&self.vesting.read().unwrap(), snapshot.vesting_arc())
+            && Arc::ptr_eq(&self.freeze_orders.read().unwrap(), snapshot.freeze_orders_arc())
+            && Arc::ptr_eq(&self.derived_keys.read().unwrap(), snapshot.derived_keys_arc())
+            && Arc::ptr_eq(&self.main_keys.read().unwrap(), snapshot.main_keys_arc())
+            && Arc::ptr_eq(&self.pending_rotations.read().unwrap(), snapshot.pending_rotations_arc())
+            && Arc::ptr_eq(&self.session_keys.read().unwrap(), snapshot.session_keys_arc())
+            && Arc::ptr_eq(&self.standing_orders.read().unwrap(), snapshot.standing_orders_arc())
+            && Arc::ptr_eq(&self.account_kinds.read().unwrap(), snapshot.account_kinds_arc())
+            && Arc::ptr_eq(&self.stakes.read().unwrap(), snapshot.stakes_arc())
+            && Arc::ptr_eq(&self.unbonding.read().unwrap(), snapshot.unbonding_arc())
+            && Arc::ptr_eq(&self.insurance.read().unwrap(), snapshot.insurance_arc())
+            && self.block_height() == snapshot.block_height()
+    }
+
+    /// Writes balance, nonce and derived key entries as-is and marks the accounts touched (`OverlayState::commit_into`).
+    pub(crate) fn apply_writes(
+        &self,
+        asset_balances: HashMap<(Address, String), u128>,
+        uplp_balances: HashMap<Address, u128>,
+        nonces: HashMap<Address, u64>,
+        derived_keys: HashMap<Address, String>,
+    ) {
+        for (address, asset) in asset_balances.keys() {
+            self.touch_asset(address, asset);
+        }
+        let mut touched: Vec<Address> = uplp_balances.keys().cloned().collect();
+        touched.extend(nonces.keys().cloned());
+        if !asset_balances.is_empty() {
+            Arc::make_mut(&mut self.asset_balances.write().unwrap()).extend(asset_balances);
+        }
+        if !uplp_balances.is_empty() {
+            Arc::make_mut(&mut self.uplp_balances.write().unwrap()).extend(uplp_balances);
+        }
+        if !nonces.is_empty() {
+            Arc::make_mut(&mut self.nonces.write().unwrap()).extend(nonces);
+        }
+        if !derived_keys.is_empty() {
+            Arc::make_mut(&mut self.derived_keys.write().unwrap()).extend(derived_keys);
+        }
+        self.touch(&touched.iter().collect::<Vec<_>>());
+    }
+
+    /// Accounts whose balance, fee balance, nonce, stake (bonded or unbonding), derived key or allowance (as owner) differs from `before`.
+    /// Maps still shared with `before` are skipped; a changed map is compared in full, so this is meant for checks
+    /// and audits rather than the block path.
+    pub fn written_accounts(&self, before: &StateSnapshot) -> BTreeSet<Address> {
+        fn diff<K: Eq + std::hash::Hash, V: PartialEq>(
+            before: &Arc<HashMap<K, V>>,
+            after: &Arc<HashMap<K, V>>,
+            address: impl Fn(&K) -> &Address,
+            out: &mut BTreeSet<Address>,
+        ) {
+            if Arc::ptr_eq(before, after) {
+                return;
+            }
+            for (k, v) in after.iter() {
+                if before.get(k) != Some(v) {
+                    out.insert(address(k).clone());
+                }
+            }
+            for k in before.keys().filter(|k| !after.contains_key(*k)) {
+                out.insert(address(k).clone());
+            }
+        }
+        let mut out = BTreeSet::new();
+        diff(before.asset_balances_arc(), &self.asset_balances.read().unwrap(), |(a, _)| a, &mut out);
+        diff(before.uplp_balances_arc(), &self.uplp_balances.read().unwrap(), |a| a, &mut out);
+        diff(before.nonces_arc(), &self.nonces.read().unwrap(), |a| a, &mut out);
+        diff(before.stakes_arc(), &self.stakes.read().unwrap(), |a| a, &mut out);
+        let (unbonding_before, unbonding) = (before.unbonding_arc(), self.unbonding.read().unwrap());
+        if !Arc::ptr_eq(unbonding_before, &unbonding) {
+            let changed = unbonding_before
+                .iter()
+                .chain(unbonding.iter())
+                .filter(|(k, _)| unbonding_before.get(*k) != unbonding.get(*k));
+            out.extend(changed.map(|((address, _), _)| address.clone()));
+        }
+        diff(before.derived_keys_arc(), &self.derived_keys.read().unwrap(), |a| a, &mut out);
+        diff(before.allowances_arc(), &self.allowances.read().unwrap(), |(owner, _, _)| owner, &mut out);
+        out
+    }
+
+    /// Applies a transaction: `check_transaction` (deployment and `validate_basic`), then `apply_effects`.
     /// Fee is always μPLP; asset balance and uplp balance are checked separately.
     pub fn apply_transaction(&self, tx: &Transaction) -> Result<()> {
-        tx.validate_basic().map_err(PlatariumError::from)?;
-        self.apply_transfer(
-            &tx.from,
-            &tx.to,
-            &tx.asset,
-            tx.amount,
-            tx.fee_uplp,
-            Some(tx.nonce),
-        )
+        self.check_transaction(tx).map_err(PlatariumError::from)?;
+        self.apply_effects(tx)
+    }
+
+    /// Applies the state effects of `tx` by kind, without signature validation. Rejected if it names an invalid or non-canonical key address, if the sender (or delegating owner) is under an active governance freeze order, or if it fails `check_account_keys`. A rotation whose delay has passed is settled first. `Transfer` goes through `apply_transfer(from, to, asset, amount, fee_uplp, nonce)`, freeze kinds through `apply_asset_freeze`, `Approve`/`TransferFrom` through `apply_approve`/`apply_transfer_from`, `Mint`/`Burn`/`Stake`/`Unstake` through `apply_mint`/`apply_burn`/`apply_stake`/`apply_unstake`, `MultiTransfer` through `apply_multi_transfer`; recovery kinds charge the fee and record or drop the pending rotation. The first successful transaction carrying a `pub_derived` binds it to the sender and emits `DerivedKeyRegistered`.
+    pub fn apply_effects(&self, tx: &Transaction) -> Result<()> {
+        check_addresses(tx)?;
+        self.check_not_frozen(&tx.from)?;
+        if let TxKind::TransferFrom { ref owner } = tx.kind {
+            self.check_not_frozen(owner)?;
+        }
+        self.check_account_keys(tx)?;
+        let first_use = self.registered_derived_key(&tx.from).is_none();
+        self.settle_rotation(&tx.from);
+        self.apply_kind_effects(tx, first_use)?;
+        let session_signed = matches!(tx.kind, TxKind::SessionTransfer { .. }) || tx.multisig.is_some();
+        if let (true, false, Some(key)) = (first_use, session_signed, tx.pub_derived.as_deref()) {
+            self.register_derived_key(&tx.from, key)?;
+            self.emit(Event::DerivedKeyRegistered {
+                address: tx.from.clone(),
+                derived_key: self.registered_derived_key(&tx.from).unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    fn apply_kind_effects(&self, tx: &Transaction, first_use: bool) -> Result<()> {
+        match tx.kind {
+            TxKind::Transfer => self.apply_transfer(
+                &tx.from,
+                &tx.to,
+                &tx.asset,
+                tx.amount,
+                tx.fee_uplp,
+                Some(tx.nonce),
+            ),
+            TxKind::FreezeAsset | TxKind::UnfreezeAsset => self.apply_asset_freeze(
+                &tx.from,
+                &tx.asset,
+                tx.kind == TxKind::FreezeAsset,
+                tx.fee_uplp,
+                Some(tx.nonce),
+            ),
+            TxKind::Approve => self.apply_approve(
+                &tx.from,
+                &tx.to,
+                &tx.asset,
+                tx.amount,
+                tx.fee_uplp,
+                Some(tx.nonce),
+            ),
+            TxKind::TransferFrom { ref owner } => self.apply_transfer_from(
+                &tx.from,
+                owner,
+                &tx.to,
+                &tx.asset,
+                tx.amount,
+                tx.fee_uplp,
+                Some(tx.nonce),
+            ),
+            TxKind::RegisterDerivedKey => {
+                if !first_use {
+                    return Err(StateError::DerivedKeyAlreadyRegistered(tx.from.clone()).into());
+                }
+                if tx.pub_derived.is_none() {
+                    return Err(StateError::MissingDerivedKey(tx.from.clone()).into());
+                }
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))
+            }
+            TxKind::InitiateRecovery { ref new_main_key } => {
+                let new_main_key = normalize_main_key(new_main_key)?;
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+                let rotation = PendingRotation::new(new_main_key.clone(), self.block_height());
+                self.emit(Event::RecoveryInitiated {
+                    address: tx.from.clone(),
+                    new_main_key,
+                    effective_height: rotation.effective_height,
+                });
+                self.set_pending_rotation(&tx.from, rotation);
+                Ok(())
+            }
+            TxKind::CancelRecovery => {
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+                let mut pr = self.pending_rotations.write().unwrap();
+                Arc::make_mut(&mut pr).remove(&tx.from);
+                drop(pr);
+                self.emit(Event::RecoveryCancelled { address: tx.from.clone() });
+                Ok(())
+            }
+            TxKind::RegisterSessionKey {
+                ref session_key,
+                max_amount_per_block,
+                expiry_height,
+            } => {
+                let key = normalize_public_key_hex(session_key)?;
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+                let scope = SessionKey::new(tx.asset.as_canonical(), max_amount_per_block, expiry_height);
+                self.set_session_key((tx.from.clone(), key.clone()), scope);
+                self.emit(Event::SessionKeyRegistered {
+                    address: tx.from.clone(),
+                    session_key: key,
+                    asset: tx.asset.as_canonical(),
+                    max_amount_per_block,
+                    expiry_height,
+                });
+                Ok(())
+            }
+            TxKind::RevokeSessionKey { ref session_key } => {
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+                let key = session_key.strip_prefix("Px").unwrap_or(session_key).to_ascii_lowercase();
+                let mut sk = self.session_keys.write().unwrap();
+                Arc::make_mut(&mut sk).remove(&(tx.from.clone(), key.clone()));
+                drop(sk);
+                self.emit(Event::SessionKeyRevoked {
+                    address: tx.from.clone(),
+                    session_key: key,
+                });
+                Ok(())
+            }
+            TxKind::SendMessage {
+                ref destination,
+                ref payload,
+            } => {
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+                self.emit(Event::MessageSent {
+                    sender: tx.from.clone(),
+                    destination: destination.clone(),
+                    payload: payload.to_ascii_lowercase(),
+                });
+                Ok(())
+            }
+            TxKind::BridgeClaim { ref proof, .. } => crate::core::bridge::apply_bridge_claim(self, tx, proof),
+            TxKind::CreateStandingOrder { interval_blocks, end_height } => {
+                self.check_standing_order_tx(tx)?;
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+                let order = StandingOrder::new(
+                    tx.to.clone(),
+                    tx.asset.clone(),
+                    tx.amount,
+                    interval_blocks,
+                    end_height,
+                    self.block_height(),
+                )?;
+                self.emit(Event::StandingOrderCreated {
+                    order_id: tx.hash.clone(),
+                    from: tx.from.clone(),
+                    to: tx.to.clone(),
+                    asset: tx.asset.as_canonical(),
+                    amount: tx.amount,
+                    interval_blocks,
+                    first_height: order.next_height,
+                    end_height,
+                });
+                self.set_standing_order((tx.from.clone(), tx.hash.clone()), order);
+                Ok(())
+            }
+            TxKind::CancelStandingOrder { ref order_id } => {
+                self.check_standing_order_tx(tx)?;
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+                self.remove_standing_order(&(tx.from.clone(), order_id.clone()));
+                self.emit(Event::StandingOrderCancelled {
+                    order_id: order_id.clone(),
+                    from: tx.from.clone(),
+                });
+                Ok(())
+            }
+            TxKind::SetMultisig { ref signers, threshold } => {
+                let signers = check_multisig_config(signers, threshold)?;
+                self.apply_transfer(&tx.from, &tx.from, &Asset::PLP, 0, tx.fee_uplp, Some(tx.nonce))?;
+                self.set_account_kind(
+                    &tx.from,
+                    AccountKind::Multisig {
+                        signers: signers.clone(),
+                        threshold,
+                    },
+                );
+                self.emit(Event::MultisigConfigured {
+                    address: tx.from.clone(),
+                    signers,
+                    threshold,
+                });
+                Ok(())
+            }
+            TxKind::Mint => self.apply_mint(&tx.from, &tx.to, &tx.asset, tx.amount, tx.fee_uplp, Some(tx.nonce)),
+            TxKind::Burn => self.apply_burn(&tx.from, &tx.asset, tx.amount, tx.fee_uplp, Some(tx.nonce)),
+            TxKind::Stake => self.apply_stake(&tx.from, tx.amount, tx.fee_uplp, Some(tx.nonce)),
+            TxKind::Unstake => self.apply_unstake(&tx.from, tx.amount, tx.fee_uplp, Some(tx.nonce)),
+            TxKind::MultiTransfer { ref outputs } => {
+                self.apply_multi_transfer(&tx.from, outputs, tx.fee_uplp, Some(tx.nonce))
+            }
+            TxKind::SessionTransfer { ref session_key } => {
+                let id = (
+                    tx.from.clone(),
+                    session_key.strip_prefix("Px").unwrap_or(session_key).to_ascii_lowercase(),
+                );
+                let height = self.block_height();
+                let mut scope = self.session_key(&tx.from, session_key).ok_or_else(|| SessionKeyError::NotRegistered {
+                    address: tx.from.clone(),
+                    key: session_key.clone(),
+                })?;
+                scope.check_spend(&tx.asset.as_canonical(), tx.amount, height)?;
+                self.apply_transfer(&tx.from, &tx.to, &tx.asset, tx.amount, tx.fee_uplp, Some(tx.nonce))?;
+                scope.record_spend(tx.amount, height);
+                self.set_session_key(id, scope);
+                Ok(())
+            }
+        }
     }
 }
 
@@ -573,6 +2394,240 @@ mod tests {
         assert_eq!(state.fee_spendable_uplp(&sender), 4899);
     }
 
+    #[test]
+    fn test_asset_freeze_blocks_transfers_and_emits_events() {
+        let state = State::new();
+        let usdt = Asset::Token("USDT".to_string());
+        let issuer = "issuer".to_string();
+        let holder = "holder".to_string();
+        state.register_token(&usdt, &issuer).unwrap();
+        state.set_balance(&issuer, 10);
+        state.set_asset_balance(&holder, &usdt, 100);
+        state.set_balance(&holder, 10);
+
+        // Only the issuer can freeze.
+        assert!(state.apply_asset_freeze(&holder, &usdt, true, 1, Some(0)).is_err());
+        assert_eq!(state.get_nonce(&holder), 0);
+
+        state.apply_asset_freeze(&issuer, &usdt, true, 1, Some(0)).unwrap();
+        assert!(state.is_asset_frozen(&usdt));
+        assert_eq!(state.get_nonce(&issuer), 1);
+        assert_eq!(state.get_uplp_balance(&TREASURY_ADDRESS.to_string()), 1);
+        let err = state.apply_transfer(&holder, &issuer, &usdt, 5, 1, Some(0));
+        assert!(err.unwrap_err().to_string().contains("Asset is frozen"));
+        assert_eq!(state.get_asset_balance(&holder, &usdt), 100);
+        // PLP is unaffected.
+        state.apply_transfer(&holder, &issuer, &Asset::PLP, 1, 1, Some(0)).unwrap();
+
+        state.apply_asset_freeze(&issuer, &usdt, false, 1, Some(1)).unwrap();
+        state.apply_transfer(&holder, &issuer, &usdt, 5, 1, Some(1)).unwrap();
+        assert_eq!(
+            state.take_events(),
+            vec![
+                Event::AssetFrozen { asset: "Token:USDT".to_string(), issuer: issuer.clone() },
+                Event::AssetUnfrozen { asset: "Token:USDT".to_string(), issuer: issuer.clone() },
+            ]
+        );
+        assert!(state.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_mint_burn_stake_unstake() {
+        let state = State::new();
+        let usdt = Asset::Token("USDT".to_string());
+        let issuer = "issuer".to_string();
+        let holder = "holder".to_string();
+        state.register_token(&usdt, &issuer).unwrap();
+        state.set_balance(&issuer, 10);
+        state.set_balance(&holder, 100);
+
+        assert!(state.apply_mint(&holder, &holder, &usdt, 50, 1, Some(0)).is_err());
+        assert!(state.apply_mint(&issuer, &holder, &Asset::PLP, 50, 1, Some(0)).is_err());
+        state.apply_mint(&issuer, &holder, &usdt, 50, 1, Some(0)).unwrap();
+        assert_eq!(state.get_asset_balance(&holder, &usdt), 50);
+        assert_eq!(state.get_nonce(&issuer), 1);
+
+        let burn = Transaction::new(
+            holder.clone(),
+            holder.clone(),
+            usdt.clone(),
+            20,
+            1,
+            0,
+            Default::default(),
+            Default::default(),
+            String::new(),
+            String::new(),
+        )
+        .and_then(|tx| tx.with_kind(TxKind::Burn))
+        .unwrap();
+        state.apply_kind_effects(&burn, false).unwrap();
+        assert_eq!(state.get_asset_balance(&holder, &usdt), 30);
+        assert_eq!(state.get_asset_balance(&BURN_ADDRESS.to_string(), &usdt), 20);
+        assert_eq!(state.check_supply(&usdt).unwrap(), 30);
+        assert!(state.apply_burn(&holder, &usdt, 31, 1, Some(1)).is_err());
+        assert_eq!(state.get_nonce(&holder), 1);
+        state.set_asset_balance(&holder, &usdt, 31);
+        assert!(state.check_supply(&usdt).is_err());
+        state.set_asset_balance(&holder, &usdt, 30);
+
+        state.apply_stake(&holder, 60, 1, Some(1)).unwrap();
+        assert_eq!(state.get_stake(&holder), 60);
+        assert_eq!(state.get_balance(&holder), 38);
+        assert_eq!(state.get_balance(&STAKING_ADDRESS.to_string()), 60);
+        let snapshot = state.snapshot();
+
+        assert!(state.apply_unstake(&holder, 61, 1, Some(2)).is_err());
+        assert_eq!(state.get_nonce(&holder), 2);
+        state.apply_unstake(&holder, 60, 1, Some(2)).unwrap();
+        assert_eq!(state.get_stake(&holder), 0);
+        assert_eq!(state.get_balance(&holder), 37);
+        assert!(state.stakes().is_empty());
+        assert_eq!(state.unbonding(), vec![(holder.clone(), UNBONDING_DELAY_BLOCKS, 60)]);
+
+        state.set_block_height(UNBONDING_DELAY_BLOCKS - 1);
+        assert!(state.release_unbonded().is_empty());
+        state.set_block_height(UNBONDING_DELAY_BLOCKS);
+        assert_eq!(state.release_unbonded(), vec![(holder.clone(), 60)]);
+        assert_eq!(state.get_balance(&holder), 97);
+        assert_eq!(state.get_balance(&STAKING_ADDRESS.to_string()), 0);
+        assert_eq!(state.get_unbonding(&holder), 0);
+
+        state.restore(&snapshot);
+        assert_eq!(state.stakes(), vec![(holder.clone(), 60)]);
+        assert!(state.unbonding().is_empty());
+        assert!(matches!(
+            state.take_events().as_slice(),
+            [
+                Event::Minted { amount: 50, .. },
+                Event::Staked { total: 60, .. },
+                Event::Unstaked { total: 0, .. },
+                Event::StakeReleased { amount: 60, .. }
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_multi_transfer_is_atomic() {
+        let state = State::new();
+        let usdt = Asset::Token("USDT".to_string());
+        let payer = "payer".to_string();
+        state.set_balance(&payer, 100);
+        state.set_asset_balance(&payer, &usdt, 5);
+        let output = |to: &str, asset: &Asset, amount| TransferOutput { to: to.to_string(), asset: asset.clone(), amount };
+
+        let short = [output("a", &Asset::PLP, 40), output("b", &usdt, 6)];
+        let before = state.snapshot();
+        assert!(state.check_multi_transfer(&payer, &short, 1).is_err());
+        assert!(state.apply_multi_transfer(&payer, &short, 1, Some(0)).is_err());
+        assert_eq!(state.snapshot(), before);
+
+        let payroll = [output("a", &Asset::PLP, 40), output("b", &Asset::PLP, 50), output("b", &usdt, 5)];
+        state.check_multi_transfer(&payer, &payroll, 1).unwrap();
+        state.apply_multi_transfer(&payer, &payroll, 1, Some(0)).unwrap();
+        assert_eq!(state.get_balance(&payer), 9);
+        assert_eq!(state.get_balance(&"a".to_string()), 40);
+        assert_eq!(state.get_balance(&"b".to_string()), 50);
+        assert_eq!(state.get_asset_balance(&"b".to_string(), &usdt), 5);
+        assert_eq!(state.get_nonce(&payer), 1);
+    }
+
+    #[test]
+    fn test_asset_freeze_rolled_back_by_restore() {
+        let state = State::new();
+        let usdt = Asset::Token("USDT".to_string());
+        let issuer = "issuer".to_string();
+        state.register_token(&usdt, &issuer).unwrap();
+        state.set_balance(&issuer, 10);
+        let snap = state.snapshot();
+        state.apply_asset_freeze(&issuer, &usdt, true, 1, Some(0)).unwrap();
+        assert_ne!(state.snapshot(), snap);
+        state.restore(&snap);
+        assert!(!state.is_asset_frozen(&usdt));
+    }
+
+    #[test]
+    fn test_allowance_approve_and_transfer_from() {
+        let state = State::new();
+        let usdt = Asset::Token("USDT".to_string());
+        let owner = "owner".to_string();
+        let exchange = "exchange".to_string();
+        let buyer = "buyer".to_string();
+        state.set_asset_balance(&owner, &usdt, 100);
+        state.set_balance(&owner, 10);
+        state.set_balance(&exchange, 10);
+
+        state.apply_approve(&owner, &exchange, &usdt, 60, 1, Some(0)).unwrap();
+        assert_eq!(state.get_allowance(&owner, &exchange, &usdt), 60);
+        assert_eq!(state.get_nonce(&owner), 1);
+
+        state.apply_transfer_from(&exchange, &owner, &buyer, &usdt, 40, 1, Some(0)).unwrap();
+        assert_eq!(state.get_asset_balance(&owner, &usdt), 60);
+        assert_eq!(state.get_asset_balance(&buyer, &usdt), 40);
+        assert_eq!(state.get_allowance(&owner, &exchange, &usdt), 20);
+        // Spender pays the fee and uses its own nonce; owner's nonce is untouched.
+        assert_eq!(state.get_balance(&exchange), 9);
+        assert_eq!(state.get_nonce(&exchange), 1);
+        assert_eq!(state.get_nonce(&owner), 1);
+
+        // Over the remaining allowance: rejected without side effects.
+        let before = state.snapshot();
+        let err = state.apply_transfer_from(&exchange, &owner, &buyer, &usdt, 21, 1, Some(1));
+        assert!(err.unwrap_err().to_string().contains("Insufficient allowance"));
+        assert_eq!(state.snapshot(), before);
+
+        // Revoke.
+        state.apply_approve(&owner, &exchange, &usdt, 0, 1, Some(1)).unwrap();
+        assert_eq!(state.get_allowance(&owner, &exchange, &usdt), 0);
+
+        let events = state.take_events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[1],
+            Event::DelegatedTransfer {
+                owner: owner.clone(),
+                spender: exchange.clone(),
+                to: buyer.clone(),
+                asset: "Token:USDT".to_string(),
+                amount: 40,
+                remaining: 20,
+            }
+        );
+    }
+
+    #[test]
+    fn test_vesting_locks_unvested_plp() {
+        use crate::core::vesting::VestingSchedule;
+        let state = State::new();
+        let founder = "founder".to_string();
+        let other = "other".to_string();
+        state.set_balance(&founder, 1_100);
+        state
+            .set_vesting_schedule(
+                &founder,
+                VestingSchedule { total: 1_000, start_height: 0, cliff_blocks: 10, duration_blocks: 100 },
+            )
+            .unwrap();
+
+        // Before the cliff only the unvested-free 100 is spendable (amount + fee).
+        state.set_block_height(5);
+        assert_eq!(state.spendable_balance(&founder, 5), 100);
+        assert!(state.apply_transfer(&founder, &other, &Asset::PLP, 100, 1, Some(0)).is_err());
+        state.apply_transfer(&founder, &other, &Asset::PLP, 99, 1, Some(0)).unwrap();
+        assert_eq!(state.get_balance(&founder), 1_000);
+
+        // Halfway: 500 vested.
+        state.set_block_height(50);
+        assert_eq!(state.vested_balance(&founder, 50), 500);
+        assert!(state.apply_transfer(&founder, &other, &Asset::PLP, 501, 0, None).is_err());
+        state.apply_transfer(&founder, &other, &Asset::PLP, 500, 0, None).unwrap();
+
+        // Fully vested.
+        state.set_block_height(100);
+        state.apply_transfer(&founder, &other, &Asset::PLP, 500, 0, None).unwrap();
+        assert_eq!(state.get_balance(&founder), 0);
+    }
+
     #[test]
     fn test_apply_transfer_success() {
         let state = State::new();
@@ -1679,4 +3734,159 @@ mod tests {
             "INVARIANT VIOLATION: Restore modified snapshot data"
         );
     }
+
+    #[test]
+    fn test_system_sinks_cannot_send() {
+        use std::collections::HashSet;
+        let state = State::new();
+        let mut rng = crate::testing::DeterministicRng::new(3998);
+        let attacker = rng.account();
+        for sink in [BURN_ADDRESS, STAKING_ADDRESS, INSURANCE_FUND_ADDRESS, TREASURY_ADDRESS] {
+            let sink = sink.to_string();
+            state.set_balance(&sink, 1_000);
+            state.set_uplp_balance(&sink, 10);
+            for kind in [TxKind::Transfer, TxKind::InitiateRecovery { new_main_key: attacker.pub_main() }] {
+                let amount = if kind == TxKind::Transfer { 1_000 } else { 0 };
+                let tx = Transaction::new(
+                    sink.clone(),
+                    attacker.address.clone(),
+                    Asset::PLP,
+                    amount,
+                    1,
+                    0,
+                    HashSet::new(),
+                    HashSet::new(),
+                    String::new(),
+                    String::new(),
+                )
+                .and_then(|tx| tx.with_kind(kind))
+                .unwrap();
+                assert!(state.apply_transaction(&attacker.sign(tx)).is_err(), "{} spent", sink);
+            }
+            assert_eq!(state.get_balance(&sink), 1_000);
+        }
+    }
+
+    #[test]
+    fn test_recovery_rotation_and_cancellation() {
+        use crate::core::recovery::RECOVERY_DELAY_BLOCKS;
+        use std::collections::HashSet;
+        const NEW_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let state = State::new();
+        let account = crate::testing::DeterministicRng::new(1).account();
+        let addr = account.address.clone();
+        let old = account.pub_main();
+        state.set_balance(&addr, 100);
+        state.register_derived_key(&addr, "dd01").unwrap();
+        let tx = |nonce: u64, kind: TxKind, pub_main: &str| {
+            let mut tx = Transaction::new(
+                addr.clone(),
+                addr.clone(),
+                Asset::PLP,
+                0,
+                1,
+                nonce,
+                HashSet::new(),
+                HashSet::new(),
+                "sig".to_string(),
+                "sig".to_string(),
+            )
+            .unwrap()
+            .with_kind(kind)
+            .unwrap();
+            tx.pub_main = Some(pub_main.to_string());
+            tx.pub_derived = Some("dd01".to_string());
+            tx
+        };
+        let initiate = TxKind::InitiateRecovery { new_main_key: NEW_KEY.to_string() };
+
+        // Cancelled within the window: old key keeps signing.
+        state.set_block_height(10);
+        state.apply_effects(&tx(0, initiate.clone(), &old)).unwrap();
+        assert!(state.apply_effects(&tx(1, initiate.clone(), &old)).is_err());
+        state.apply_effects(&tx(1, TxKind::CancelRecovery, &old)).unwrap();
+        assert!(state.pending_rotation(&addr).is_none());
+        assert!(state.apply_effects(&tx(2, TxKind::CancelRecovery, &old)).is_err());
+
+        // Not cancelled: after the delay only the new key is accepted.
+        state.apply_effects(&tx(2, initiate, &old)).unwrap();
+        state.set_block_height(10 + RECOVERY_DELAY_BLOCKS);
+        assert_eq!(state.main_key(&addr), Some(NEW_KEY.to_string()));
+        let err = state.apply_effects(&tx(3, TxKind::CancelRecovery, NEW_KEY)).unwrap_err();
+        assert!(err.to_string().contains("window"));
+        assert!(state.apply_effects(&tx(3, TxKind::Transfer, &old)).is_err());
+        let mut transfer = tx(3, TxKind::Transfer, NEW_KEY);
+        transfer.amount = 1;
+        state.apply_effects(&transfer).unwrap();
+        assert!(state.pending_rotation(&addr).is_none());
+        assert_eq!(state.main_keys(), vec![(addr.clone(), NEW_KEY.to_string())]);
+        let events = state.take_events();
+        assert!(matches!(events.last(), Some(Event::MainKeyRotated { .. })));
+    }
+
+    #[test]
+    fn test_current_root_tracks_batches_incrementally() {
+        let state = State::new();
+        for i in 0..20 {
+            state.set_balance(&format!("acct{}", i), 1_000);
+        }
+        let genesis_root = state.current_root();
+        assert_eq!(genesis_root, state.recompute_root());
+        let snap = state.snapshot();
+
+        // One "block": a few transfers touching a handful of accounts.
+        for (i, to) in ["acct1", "acct2", "new"].iter().enumerate() {
+            state
+                .apply_transfer(&"acct0".to_string(), &to.to_string(), &Asset::PLP, 10, 1, Some(i as u64))
+                .unwrap();
+        }
+        let block_root = state.current_root();
+        assert_ne!(block_root, genesis_root);
+        assert_eq!(block_root, state.recompute_root());
+        assert_eq!(state.current_root(), block_root);
+
+        // Restore forces a rebuild back to the genesis commitment.
+        state.restore(&snap);
+        assert_eq!(state.current_root(), genesis_root);
+    }
+
+    #[test]
+    fn test_apply_effects_rejects_mistyped_key_address() {
+        let state = State::new();
+        let from = "alice".to_string();
+        state.set_balance(&from, 100);
+        state.set_uplp_balance(&from, 10);
+        let mut tx = Transaction::new(
+            from.clone(),
+            "bob".to_string(),
+            Asset::PLP,
+            5,
+            1,
+            0,
+            std::collections::HashSet::new(),
+            std::collections::HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap();
+        // A compressed key with its last character dropped.
+        tx.to = format!("Px02{}", "ab".repeat(32).split_at(63).0);
+        let err = state.apply_effects(&tx).unwrap_err();
+        assert!(err.to_string().contains("expected 66 hex characters"), "{}", err);
+        assert_eq!(state.get_balance(&from), 100);
+
+        // The same goes for a `MultiTransfer` output, including a short one with a stray `-`.
+        tx.to = "bob".to_string();
+        tx.amount = 0;
+        tx.kind = TxKind::MultiTransfer {
+            outputs: vec![crate::core::transaction::TransferOutput {
+                to: "Px02ab-cd".to_string(),
+                asset: Asset::PLP,
+                amount: 5,
+            }],
+        };
+        let err = state.apply_effects(&tx).unwrap_err();
+        assert!(err.to_string().contains("Px02ab-cd"), "{}", err);
+        assert_eq!(state.get_balance(&from), 100);
+    }
 }
@@ -0,0 +1,197 @@
+//! Incrementally maintained account tree of the state commitment.
+//!
+//! Each account with a non-zero PLP balance, nonce or μPLP balance contributes a leaf at `account_key(address)` of
+//! a sparse Merkle tree, and each non-zero token balance a leaf at `token_key(address, asset)` (see
+//! `platarium_consensus::state_root`). Zero entries are not in the tree, so an explicit zero and a missing entry
+//! commit identically, and the root does not depend on the order of updates.
+//!
+//! Inner nodes are cached. Updating a leaf only drops the cached nodes on its path, so the next [`root`] rehashes
+//! O(k · log n) nodes after a block that touched `k` of `n` leaves rather than the whole tree.
+//!
+//! `State::current_root` combines this root with a digest of the registries; the result is the state root in
+//! block headers. It is a different function from `StateSnapshot::compute_state_root`, which hashes PLP balances
+//! and nonces only; the two roots are not interchangeable.
+//!
+//! [`root`]: AccountCommitment::root
+
+use std::collections::{BTreeMap, HashMap};
+use crate::core::state::Address;
+
+use platarium_consensus::{account_key, token_key, TreeNode, Word};
+
+/// Leaf for one account, or `None` for an all-zero account.
+pub fn account_leaf(address: &Address, plp_balance: u128, nonce: u64, uplp_balance: u128) -> Option<Word> {
+    platarium_consensus::account_leaf(address, plp_balance, nonce, uplp_balance)
+}
+
+/// Leaf for one token balance (`asset` canonical), or `None` for a zero balance.
+pub fn token_leaf(address: &Address, asset: &str, balance: u128) -> Option<Word> {
+    platarium_consensus::token_leaf(address, asset, balance)
+}
+
+/// Account tree with the current value of every committed leaf and the inner nodes computed so far.
+#[derive(Debug, Clone, Default)]
+pub struct AccountCommitment {
+    /// account or token key -> leaf.
+    leaves: BTreeMap<Word, Word>,
+    /// (depth, key prefix with the bits from `depth` on cleared) -> subtree with at least two leaves.
+    nodes: HashMap<(u16, Word), TreeNode>,
+}
+
+impl PartialEq for AccountCommitment {
+    fn eq(&self, other: &Self) -> bool {
+        self.leaves == other.leaves
+    }
+}
+
+impl Eq for AccountCommitment {}
+
+impl AccountCommitment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the commitment from a full listing of accounts `(address, PLP, nonce, μPLP)` and token balances
+    /// `(address, asset, balance)`. O(leaves).
+    pub fn from_entries<'a>(
+        accounts: impl IntoIterator<Item = (&'a Address, u128, u64, u128)>,
+        tokens: impl IntoIterator<Item = (&'a Address, &'a str, u128)>,
+    ) -> Self {
+        let mut c = Self::new();
+        for (address, balance, nonce, uplp) in accounts {
+            c.update(address, balance, nonce, uplp);
+        }
+        for (address, asset, balance) in tokens {
+            c.update_token(address, asset, balance);
+        }
+        c
+    }
+
+    /// Replaces the account leaf of `address`. O(log n); hashing is deferred to `root`.
+    pub fn update(&mut self, address: &Address, plp_balance: u128, nonce: u64, uplp_balance: u128) {
+        self.set(account_key(address), account_leaf(address, plp_balance, nonce, uplp_balance));
+    }
+
+    /// Replaces the leaf of `address`'s balance of `asset` (canonical, not PLP).
+    pub fn update_token(&mut self, address: &Address, asset: &str, balance: u128) {
+        self.set(token_key(address, asset), token_leaf(address, asset, balance));
+    }
+
+    fn set(&mut self, key: Word, leaf: Option<Word>) {
+        match leaf {
+            Some(leaf) => self.leaves.insert(key, leaf),
+            None => self.leaves.remove(&key),
+        };
+        for depth in 0..=256 {
+            self.nodes.remove(&(depth as u16, prefix(&key, depth)));
+        }
+    }
+
+    /// Root hash of the account tree.
+    pub fn root(&mut self) -> Word {
+        self.node(0, [0u8; 32]).hash
+    }
+
+    /// Subtree at `depth` whose keys start with the first `depth` bits of `prefix` (the rest must be zero).
+    fn node(&mut self, depth: usize, prefix: Word) -> TreeNode {
+        let mut below = self.leaves.range(prefix..=fill(&prefix, depth));
+        let (first, second) = (below.next(), below.next());
+        match (first, second) {
+            (None, _) => TreeNode::EMPTY,
+            (Some((key, leaf)), None) => TreeNode::leaf(key, leaf),
+            _ => {
+                if let Some(node) = self.nodes.get(&(depth as u16, prefix)) {
+                    return *node;
+                }
+                let mut right = prefix;
+                right[depth / 8] |= 0x80 >> (depth % 8);
+                let node = TreeNode::parent(&self.node(depth + 1, prefix), &self.node(depth + 1, right));
+                self.nodes.insert((depth as u16, prefix), node);
+                node
+            }
+        }
+    }
+
+    /// Number of leaves.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+}
+
+/// First `depth` bits of `key`, the rest cleared.
+fn prefix(key: &Word, depth: usize) -> Word {
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        let keep = depth.saturating_sub(i * 8).min(8);
+        *byte = key[i] & !(0xffu8.checked_shr(keep as u32).unwrap_or(0));
+    }
+    out
+}
+
+/// `prefix` with every bit from `depth` on set: the largest key in its subtree.
+fn fill(prefix: &Word, depth: usize) -> Word {
+    let mut out = *prefix;
+    for (i, byte) in out.iter_mut().enumerate() {
+        let keep = depth.saturating_sub(i * 8).min(8);
+        *byte |= 0xffu8.checked_shr(keep as u32).unwrap_or(0);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_order_independent() {
+        let (a, b) = ("alice".to_string(), "bob".to_string());
+        let mut x = AccountCommitment::new();
+        x.update(&a, 10, 1, 0);
+        x.update_token(&a, "Token:USDT", 4);
+        x.update(&b, 5, 0, 3);
+        x.update(&a, 7, 2, 0);
+        let y = AccountCommitment::from_entries(vec![(&b, 5, 0, 3), (&a, 7, 2, 0)], vec![(&a, "Token:USDT", 4)]);
+        assert_eq!(x.root(), y.clone().root());
+        x.update(&b, 0, 0, 0);
+        x.update_token(&a, "Token:USDT", 0);
+        assert_eq!(x.len(), 1);
+        assert_eq!(x.root(), AccountCommitment::from_entries(vec![(&a, 7, 2, 0)], vec![]).root());
+    }
+
+    #[test]
+    fn test_cached_root_matches_full_tree() {
+        let addresses: Vec<Address> = (0..50).map(|i| format!("acct{}", i)).collect();
+        let mut incremental = AccountCommitment::from_entries(addresses.iter().map(|a| (a, 1, 0, 0)), vec![]);
+        incremental.root();
+        for (i, a) in addresses.iter().enumerate().step_by(7) {
+            incremental.update(a, 100 + i as u128, 3, 0);
+        }
+        let mut leaves: Vec<(Word, Word)> = addresses
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let (balance, nonce) = if i % 7 == 0 { (100 + i as u128, 3) } else { (1, 0) };
+                (account_key(a), account_leaf(a, balance, nonce, 0).unwrap())
+            })
+            .collect();
+        leaves.sort();
+        assert_eq!(incremental.root(), platarium_consensus::subtree(0, &leaves).hash);
+
+        // Every account and token change shows in the root.
+        let before = incremental.root();
+        incremental.update(&addresses[1], 2, 0, 0);
+        incremental.update(&addresses[2], 0, 0, 0);
+        assert_ne!(incremental.root(), before);
+        let before = incremental.root();
+        incremental.update(&addresses[3], 1, 0, 9);
+        assert_ne!(incremental.root(), before);
+        let before = incremental.root();
+        incremental.update_token(&addresses[3], "Token:USDT", 1);
+        assert_ne!(incremental.root(), before);
+        assert_eq!(AccountCommitment::new().root(), [0u8; 32]);
+    }
+}
@@ -8,12 +8,20 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 
 use crate::core::asset::Asset;
+use crate::core::asset_registry::AssetRegistry;
 use crate::core::execution::{ExecutionContext, ExecutionLogic};
-use crate::core::state::{State, TREASURY_ADDRESS};
+use crate::core::governance::AddressFreezeOrder;
+use crate::core::insurance::InsuranceFund;
+use crate::core::migrations::{MigrationRecord, MigrationRegistry, StateLayout, STATE_VERSION};
+use crate::core::recovery::PendingRotation;
+use crate::core::session_keys::SessionKey;
+use crate::core::standing_orders::StandingOrder;
+use crate::core::state::{AccountKind, State, TREASURY_ADDRESS};
 use crate::core::transaction::Transaction;
+use crate::core::vesting::GenesisVesting;
 use crate::error::{PlatariumError, Result};
 
-pub const STATE_FILE_VERSION: u32 = 1;
+pub const STATE_FILE_VERSION: u32 = STATE_VERSION;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct StateFileData {
@@ -22,6 +30,60 @@ pub struct StateFileData {
     pub asset_balances: Vec<(String, String, String)>,
     pub uplp_balances: Vec<(String, String)>,
     pub nonces: Vec<(String, u64)>,
+    /// Genesis vesting schedules (sorted by address).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vesting: Vec<GenesisVesting>,
+    /// Registered derived keys as (address, key hex), sorted by address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived_keys: Vec<(String, String)>,
+    /// Main keys installed by completed recoveries, sorted by address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub main_keys: Vec<(String, String)>,
+    /// Pending main key rotations, sorted by address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_rotations: Vec<(String, PendingRotation)>,
+    /// Session keys as ((address, key hex), scope), sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub session_keys: Vec<((String, String), SessionKey)>,
+    /// Standing orders as ((sender, order id), order), sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub standing_orders: Vec<((String, String), StandingOrder)>,
+    /// Multisig accounts with their signer sets, sorted by address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub multisig_accounts: Vec<(String, AccountKind)>,
+    /// Stakes as (address, PLP amount as decimal string), sorted by address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stakes: Vec<(String, String)>,
+    /// Unbonding stake as (address, release height, PLP amount as decimal string), sorted by address then height.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unbonding: Vec<(String, u64, String)>,
+    /// Allowances as ((owner, spender, asset canonical), remaining amount as decimal string), sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowances: Vec<((String, String, String), String)>,
+    /// Enacted freeze orders (active, expired or lifted), sorted by order id.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub freeze_orders: Vec<(String, AddressFreezeOrder)>,
+    /// Registered tokens (issuer, freeze flag, supply, wrapped chain) and the bridge ledger of attested foreign
+    /// headers and minted claim ids.
+    #[serde(default, skip_serializing_if = "is_empty_registry")]
+    pub asset_registry: AssetRegistry,
+    /// Recorded invalid-transaction evidence and paid insurance claims.
+    #[serde(default, skip_serializing_if = "InsuranceFund::is_empty")]
+    pub insurance: InsuranceFund,
+    /// Height of the last executed block; selects which height-gated migrations are due on load.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub block_height: u64,
+    /// Migrations applied to this state, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migration_log: Vec<MigrationRecord>,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+fn is_empty_registry(registry: &AssetRegistry) -> bool {
+    *registry == AssetRegistry::default()
 }
 
 impl StateFileData {
@@ -31,6 +93,51 @@ impl StateFileData {
             asset_balances: Vec::new(),
             uplp_balances: Vec::new(),
             nonces: Vec::new(),
+            vesting: Vec::new(),
+            derived_keys: Vec::new(),
+            main_keys: Vec::new(),
+            pending_rotations: Vec::new(),
+            session_keys: Vec::new(),
+            standing_orders: Vec::new(),
+            multisig_accounts: Vec::new(),
+            stakes: Vec::new(),
+            unbonding: Vec::new(),
+            allowances: Vec::new(),
+            freeze_orders: Vec::new(),
+            asset_registry: AssetRegistry::default(),
+            insurance: InsuranceFund::new(),
+            block_height: 0,
+            migration_log: Vec::new(),
+        }
+    }
+
+    /// Everything but balances, nonces, height and migration log: the non-account part of the state, whose
+    /// encoding the state commitment covers through `State::registries_digest` and state sync ships whole.
+    pub fn registries_of(state: &State) -> Self {
+        let vesting = state
+            .vesting_schedules()
+            .into_iter()
+            .map(|(address, schedule)| GenesisVesting { address, schedule })
+            .collect();
+        Self {
+            vesting,
+            derived_keys: state.derived_keys(),
+            main_keys: state.main_keys(),
+            pending_rotations: state.pending_rotations(),
+            session_keys: state.session_keys(),
+            standing_orders: state.standing_orders(),
+            multisig_accounts: state.multisig_accounts(),
+            stakes: state.stakes().into_iter().map(|(addr, amount)| (addr, amount.to_string())).collect(),
+            unbonding: state
+                .unbonding()
+                .into_iter()
+                .map(|(addr, release_height, amount)| (addr, release_height, amount.to_string()))
+                .collect(),
+            allowances: state.allowances().into_iter().map(|(key, amount)| (key, amount.to_string())).collect(),
+            freeze_orders: state.freeze_orders(),
+            asset_registry: state.asset_registry(),
+            insurance: state.insurance_fund(),
+            ..Self::empty()
         }
     }
 
@@ -57,34 +164,36 @@ impl StateFileData {
             .collect();
         nonces.sort_by(|a, b| a.0.cmp(&b.0));
 
+        let layout = state.layout();
         Self {
-            version: STATE_FILE_VERSION,
+            version: layout.version,
             asset_balances,
             uplp_balances,
             nonces,
+            block_height: state.block_height(),
+            migration_log: layout.migration_log,
+            ..Self::registries_of(state)
         }
     }
 
     pub fn into_state(self) -> Result<State> {
-        if self.version != STATE_FILE_VERSION {
-            return Err(PlatariumError::State(format!(
-                "unsupported state file version {}",
-                self.version
-            )));
-        }
+        self.into_state_with(&MigrationRegistry::standard())
+    }
+
+    /// Builds the state, refusing versions `registry` does not know. Does not run migrations.
+    pub fn into_state_with(self, registry: &MigrationRegistry) -> Result<State> {
+        registry.check_supported(self.version)?;
         let state = State::new();
+        state.set_layout(StateLayout {
+            version: self.version,
+            migration_log: self.migration_log,
+        });
+        state.set_block_height(self.block_height);
         for (addr, asset, bal_str) in self.asset_balances {
             let bal: u128 = bal_str
                 .parse()
                 .map_err(|e| PlatariumError::State(format!("invalid balance for {}: {}", addr, e)))?;
-            let asset_enum = if asset == Asset::PLP.as_canonical() {
-                Asset::PLP
-            } else if asset.starts_with("Token:") {
-                Asset::Token(asset["Token:".len()..].to_string())
-            } else {
-                Asset::Token(asset)
-            };
-            state.set_asset_balance(&addr, &asset_enum, bal);
+            state.set_asset_balance(&addr, &Asset::from_canonical(&asset), bal);
         }
         for (addr, bal_str) in self.uplp_balances {
             let bal: u128 = bal_str.parse().map_err(|e| {
@@ -95,6 +204,50 @@ impl StateFileData {
         for (addr, nonce) in self.nonces {
             state.set_nonce(&addr, nonce);
         }
+        for v in self.vesting {
+            state.set_vesting_schedule(&v.address, v.schedule)?;
+        }
+        for (addr, key) in self.derived_keys {
+            state.register_derived_key(&addr, &key)?;
+        }
+        for (addr, key) in self.main_keys {
+            state.set_main_key(&addr, key);
+        }
+        for (addr, rotation) in self.pending_rotations {
+            state.set_pending_rotation(&addr, rotation);
+        }
+        for (id, key) in self.session_keys {
+            state.set_session_key(id, key);
+        }
+        for (id, order) in self.standing_orders {
+            state.set_standing_order(id, order);
+        }
+        for (addr, kind) in self.multisig_accounts {
+            state.set_account_kind(&addr, kind);
+        }
+        for (addr, amount_str) in self.stakes {
+            let amount: u128 = amount_str
+                .parse()
+                .map_err(|e| PlatariumError::State(format!("invalid stake for {}: {}", addr, e)))?;
+            state.set_stake(&addr, amount);
+        }
+        for (addr, release_height, amount_str) in self.unbonding {
+            let amount: u128 = amount_str
+                .parse()
+                .map_err(|e| PlatariumError::State(format!("invalid unbonding stake for {}: {}", addr, e)))?;
+            state.set_unbonding(&addr, release_height, amount);
+        }
+        for ((owner, spender, asset), amount_str) in self.allowances {
+            let amount: u128 = amount_str.parse().map_err(|e| {
+                PlatariumError::State(format!("invalid allowance of {} for {}: {}", owner, spender, e))
+            })?;
+            state.set_allowance(&owner, &spender, &Asset::from_canonical(&asset), amount);
+        }
+        for (id, order) in self.freeze_orders {
+            state.add_freeze_order(id, order)?;
+        }
+        state.update_asset_registry(|r| *r = self.asset_registry);
+        state.set_insurance_fund(self.insurance);
         Ok(state)
     }
 }
@@ -110,13 +263,25 @@ pub fn init_state_file(path: &Path) -> Result<()> {
 }
 
 pub fn load_state_file(path: &Path) -> Result<State> {
+    load_state_file_with(path, &MigrationRegistry::standard())
+}
+
+/// Loads a state file, migrating it forward with `registry`: layout migrations before parsing, then the state
+/// migrations due at the file's block height. The migrated state is returned, not written back.
+pub fn load_state_file_with(path: &Path, registry: &MigrationRegistry) -> Result<State> {
     let data = fs::read_to_string(path).map_err(|e| {
         PlatariumError::State(format!("read state file {}: {}", path.display(), e))
     })?;
-    let file: StateFileData = serde_json::from_str(&data).map_err(|e| {
+    let mut doc: serde_json::Value = serde_json::from_str(&data).map_err(|e| {
+        PlatariumError::State(format!("parse state file {}: {}", path.display(), e))
+    })?;
+    registry.migrate_document(&mut doc)?;
+    let file: StateFileData = serde_json::from_value(doc).map_err(|e| {
         PlatariumError::State(format!("parse state file {}: {}", path.display(), e))
     })?;
-    file.into_state()
+    let state = file.into_state_with(registry)?;
+    registry.apply_due(&state, state.block_height())?;
+    Ok(state)
 }
 
 pub fn save_state_file(path: &Path, state: &State) -> Result<()> {
@@ -186,6 +351,7 @@ where
 }
 
 pub fn state_query_json(path: &Path, address: &str, asset: &str) -> Result<String> {
+    let address = &crate::address::normalize(address)?;
     let state = load_state_file(path)?;
     let asset_enum = parse_asset(asset)?;
     let balance = if asset_enum == Asset::PLP {
@@ -210,7 +376,7 @@ pub fn state_query_json(path: &Path, address: &str, asset: &str) -> Result<Strin
 pub fn state_validate_tx_json(path: &Path, tx_json: &str) -> Result<String> {
     let state = load_state_file(path)?;
     let tx = Transaction::from_gateway_json(tx_json)?;
-    match ExecutionLogic::validate_transaction(&tx)
+    match ExecutionLogic::validate_transaction_on(&state, &tx)
         .and_then(|_| ExecutionLogic::check_transaction_applicability(&state, &tx))
     {
         Ok(()) => Ok(r#"{"valid":true}"#.to_string()),
@@ -226,7 +392,7 @@ pub fn state_apply_tx_json(path: &Path, tx_json: &str) -> Result<String> {
     with_state_file_mut(path, |state| {
         let tx = Transaction::from_gateway_json(tx_json)?;
         ExecutionLogic::execute_transaction(state, &tx, ExecutionContext::Production)?;
-        let root = state.create_snapshot().compute_state_root();
+        let root = state.current_root();
         Ok(serde_json::json!({
             "ok": true,
             "hash": tx.hash,
@@ -242,8 +408,8 @@ pub fn state_credit_json(path: &Path, address: &str, plp: u128, uplp: u128, test
             "state-credit requires --testnet flag".into(),
         ));
     }
+    let addr = crate::address::normalize(address)?;
     with_state_file_mut(path, |state| {
-        let addr = address.to_string();
         if plp > 0 {
             let current = state.get_balance(&addr);
             state.set_balance(&addr, current.saturating_add(plp));
@@ -252,7 +418,7 @@ pub fn state_credit_json(path: &Path, address: &str, plp: u128, uplp: u128, test
             let current = state.get_uplp_balance(&addr);
             state.set_uplp_balance(&addr, current.saturating_add(uplp));
         }
-        let root = state.create_snapshot().compute_state_root();
+        let root = state.current_root();
         Ok(serde_json::json!({
             "ok": true,
             "state_root": root,
@@ -264,7 +430,7 @@ pub fn state_credit_json(path: &Path, address: &str, plp: u128, uplp: u128, test
 
 pub fn state_root_json(path: &Path) -> Result<String> {
     let state = load_state_file(path)?;
-    let root = state.create_snapshot().compute_state_root();
+    let root = state.current_root();
     Ok(serde_json::to_string(&serde_json::json!({ "state_root": root }))
         .map_err(|e| PlatariumError::State(e.to_string()))?)
 }
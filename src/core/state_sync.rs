@@ -0,0 +1,454 @@
+//! Chunked state snapshots for state sync, verifiable chunk by chunk.
+//!
+//! [`export_snapshot`] splits the leaves of the account tree (`state_commitment`) by the first `chunk_bits` bits
+//! of their key: chunk `i` holds every account (PLP balance, nonce, μPLP balance) and every token balance whose
+//! key starts with `i`. Each chunk is therefore one subtree of the account tree, and its digest is that subtree's
+//! node. The [`SnapshotManifest`] lists every chunk's digest and leaf count, plus the encoded registries (token
+//! registry, keys, vesting, stakes, ...; see `StateFileData::registries_of`). The digests hashed up pairwise give
+//! the account tree root, which combined with the registries digest is the state commitment in block headers
+//! (`State::current_root`). `chunk_bits` is picked so that chunks average at most the requested number of leaves.
+//!
+//! A downloader takes the state root from a block it already trusts and opens a [`SnapshotDownload`], which
+//! rejects a manifest whose digests and registries do not hash up to it. Each chunk is then checked against its
+//! digest as it arrives, from any peer and in any order, so a bad chunk costs one request rather than the whole
+//! transfer. The download serializes: saved to disk, it resumes with [`SnapshotDownload::missing`] after a
+//! restart. [`SnapshotDownload::finish`] builds the [`State`] and checks its commitment once more.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use platarium_consensus::{account_key, token_key, TreeNode, Word};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::core::asset::Asset;
+use crate::core::state::State;
+use crate::core::state_commitment::{account_leaf, token_leaf};
+use crate::core::state_file::{load_state_file, StateFileData};
+use crate::error::{PlatariumError, Result};
+
+/// Leaves (accounts and token balances) per chunk when the caller does not choose.
+pub const DEFAULT_CHUNK_ACCOUNTS: usize = 1_000;
+
+/// Largest `chunk_bits`: at most 65536 chunks per snapshot.
+pub const MAX_CHUNK_BITS: u8 = 16;
+
+/// Errors produced while verifying a snapshot download.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum StateSyncError {
+    #[error("Manifest root {got} does not match the trusted state root {expected}")]
+    RootMismatch { expected: String, got: String },
+
+    #[error("Chunk {0} is not in the manifest")]
+    UnknownChunk(u32),
+
+    #[error("Chunk {0} does not match its manifest digest")]
+    DigestMismatch(u32),
+
+    #[error("Malformed snapshot: {0}")]
+    Malformed(String),
+
+    #[error("Download incomplete: {0} chunks missing")]
+    Incomplete(usize),
+}
+
+impl From<StateSyncError> for PlatariumError {
+    fn from(e: StateSyncError) -> Self {
+        PlatariumError::State(format!("State sync: {}", e))
+    }
+}
+
+/// One committed account. Balances are decimal strings, as in storage account records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkAccount {
+    pub address: String,
+    /// PLP balance.
+    pub balance: String,
+    pub nonce: u64,
+    pub uplp_balance: String,
+}
+
+/// One committed token balance; `asset` is the canonical code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkToken {
+    pub address: String,
+    pub asset: String,
+    pub balance: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotChunk {
+    pub index: u32,
+    pub accounts: Vec<ChunkAccount>,
+    pub tokens: Vec<ChunkToken>,
+}
+
+impl SnapshotChunk {
+    /// Number of leaves in the chunk.
+    pub fn len(&self) -> usize {
+        self.accounts.len() + self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Subtree node of the chunk's leaves at depth `chunk_bits`. Entries must be non-zero and under the chunk's
+    /// key prefix; accounts and tokens must each be in strictly increasing key order.
+    pub fn node(&self, chunk_bits: u8) -> std::result::Result<TreeNode, StateSyncError> {
+        let mut accounts: Vec<(Word, Word)> = Vec::with_capacity(self.accounts.len());
+        for a in &self.accounts {
+            let leaf = account_leaf(&a.address, parse_balance(&a.balance)?, a.nonce, parse_balance(&a.uplp_balance)?)
+                .ok_or_else(|| StateSyncError::Malformed(format!("zero account {} in chunk", a.address)))?;
+            self.push_leaf(&mut accounts, account_key(&a.address), leaf, chunk_bits, &a.address)?;
+        }
+        let mut tokens: Vec<(Word, Word)> = Vec::with_capacity(self.tokens.len());
+        for t in &self.tokens {
+            if t.asset == Asset::PLP.as_canonical() {
+                return Err(StateSyncError::Malformed(format!("PLP token entry for {}", t.address)));
+            }
+            let leaf = token_leaf(&t.address, &t.asset, parse_balance(&t.balance)?).ok_or_else(|| {
+                StateSyncError::Malformed(format!("zero {} balance of {} in chunk", t.asset, t.address))
+            })?;
+            self.push_leaf(&mut tokens, token_key(&t.address, &t.asset), leaf, chunk_bits, &t.address)?;
+        }
+        let mut leaves = accounts;
+        leaves.extend(tokens);
+        leaves.sort_unstable();
+        if leaves.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(StateSyncError::Malformed(format!("duplicate key in chunk {}", self.index)));
+        }
+        Ok(platarium_consensus::subtree(chunk_bits as usize, &leaves))
+    }
+
+    fn push_leaf(
+        &self,
+        leaves: &mut Vec<(Word, Word)>,
+        key: Word,
+        leaf: Word,
+        chunk_bits: u8,
+        address: &str,
+    ) -> std::result::Result<(), StateSyncError> {
+        if chunk_index(&key, chunk_bits) != self.index {
+            return Err(StateSyncError::Malformed(format!("{} does not belong in chunk {}", address, self.index)));
+        }
+        if leaves.last().is_some_and(|(prev, _)| *prev >= key) {
+            return Err(StateSyncError::Malformed(format!("chunk {} is not in key order", self.index)));
+        }
+        leaves.push((key, leaf));
+        Ok(())
+    }
+}
+
+/// Chunk digests and registries of one snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// State commitment the digests and registries hash up to (hex).
+    pub state_root: String,
+    /// Chunks split the key space by this many leading bits; there are `2^chunk_bits` of them.
+    pub chunk_bits: u8,
+    pub leaf_count: u64,
+    /// Subtree hash of each chunk (hex), in chunk order; all zero for an empty chunk.
+    pub chunk_digests: Vec<String>,
+    /// Number of leaves in each chunk.
+    pub chunk_sizes: Vec<u64>,
+    /// `StateFileData::registries_of` the state, as the JSON its digest is taken over.
+    pub registries: String,
+}
+
+impl SnapshotManifest {
+    /// State commitment the chunk digests and registries hash up to (hex). Errors unless there is one digest and
+    /// size per chunk and the sizes add up to `leaf_count`.
+    pub fn root(&self) -> std::result::Result<String, StateSyncError> {
+        let chunks = 1usize << self.chunk_bits.min(MAX_CHUNK_BITS);
+        if self.chunk_bits > MAX_CHUNK_BITS || self.chunk_digests.len() != chunks || self.chunk_sizes.len() != chunks {
+            return Err(StateSyncError::Malformed(format!("manifest must list {} chunks", chunks)));
+        }
+        let mut level = self
+            .chunk_digests
+            .iter()
+            .zip(&self.chunk_sizes)
+            .map(|(d, &leaves)| {
+                let hash = parse_word(d)?;
+                if (leaves == 0) != (hash == TreeNode::EMPTY.hash) {
+                    return Err(StateSyncError::Malformed(format!("digest {} does not match its size", d)));
+                }
+                Ok(TreeNode { hash, leaves })
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let total = level.iter().try_fold(0u64, |sum, n| sum.checked_add(n.leaves));
+        if total != Some(self.leaf_count) {
+            return Err(StateSyncError::Malformed("chunk sizes do not add up to the leaf count".into()));
+        }
+        while level.len() > 1 {
+            level = level.chunks(2).map(|pair| TreeNode::parent(&pair[0], &pair[1])).collect();
+        }
+        let registries = platarium_consensus::registries_digest(self.registries.as_bytes());
+        Ok(hex::encode(platarium_consensus::state_commitment(&level[0].hash, &registries)))
+    }
+
+    /// Checks `chunk` against its digest and size.
+    pub fn verify_chunk(&self, chunk: &SnapshotChunk) -> std::result::Result<(), StateSyncError> {
+        let index = chunk.index as usize;
+        let (Some(expected), Some(&size)) = (self.chunk_digests.get(index), self.chunk_sizes.get(index)) else {
+            return Err(StateSyncError::UnknownChunk(chunk.index));
+        };
+        if chunk.len() as u64 != size {
+            return Err(StateSyncError::DigestMismatch(chunk.index));
+        }
+        if hex::encode(chunk.node(self.chunk_bits)?.hash) != *expected {
+            return Err(StateSyncError::DigestMismatch(chunk.index));
+        }
+        Ok(())
+    }
+}
+
+/// Splits the account tree of `state` into key-prefix chunks of about `chunk_leaves` leaves each, with their
+/// manifest. Every chunk is returned, empty ones included, so `chunks[i].index == i`.
+pub fn export_snapshot(state: &State, chunk_leaves: usize) -> Result<(SnapshotManifest, Vec<SnapshotChunk>)> {
+    if chunk_leaves == 0 {
+        return Err(StateSyncError::Malformed("chunk_leaves must be positive".into()).into());
+    }
+    let mut accounts: Vec<(Word, ChunkAccount)> = state
+        .committed_accounts()
+        .into_iter()
+        .filter(|(_, balance, nonce, uplp)| *balance != 0 || *nonce != 0 || *uplp != 0)
+        .map(|(address, balance, nonce, uplp)| {
+            let account = ChunkAccount { address, balance: balance.to_string(), nonce, uplp_balance: uplp.to_string() };
+            (account_key(&account.address), account)
+        })
+        .collect();
+    accounts.sort_by_key(|a| a.0);
+    let mut tokens: Vec<(Word, ChunkToken)> = state
+        .committed_tokens()
+        .into_iter()
+        .filter(|(_, _, balance)| *balance != 0)
+        .map(|(address, asset, balance)| {
+            (token_key(&address, &asset), ChunkToken { address, asset, balance: balance.to_string() })
+        })
+        .collect();
+    tokens.sort_by_key(|t| t.0);
+    let leaf_count = (accounts.len() + tokens.len()) as u64;
+    let mut chunk_bits = 0u8;
+    while chunk_bits < MAX_CHUNK_BITS && (chunk_leaves as u64) << chunk_bits < leaf_count {
+        chunk_bits += 1;
+    }
+    let mut chunks: Vec<SnapshotChunk> = (0..1u32 << chunk_bits)
+        .map(|index| SnapshotChunk { index, accounts: Vec::new(), tokens: Vec::new() })
+        .collect();
+    for (key, account) in accounts {
+        chunks[chunk_index(&key, chunk_bits) as usize].accounts.push(account);
+    }
+    for (key, token) in tokens {
+        chunks[chunk_index(&key, chunk_bits) as usize].tokens.push(token);
+    }
+    let nodes = chunks
+        .iter()
+        .map(|c| c.node(chunk_bits))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let registries = serde_json::to_string(&StateFileData::registries_of(state))
+        .map_err(|e| PlatariumError::State(e.to_string()))?;
+    let manifest = SnapshotManifest {
+        state_root: state.current_root(),
+        chunk_bits,
+        leaf_count,
+        chunk_digests: nodes.iter().map(|n| hex::encode(n.hash)).collect(),
+        chunk_sizes: nodes.iter().map(|n| n.leaves).collect(),
+        registries,
+    };
+    Ok((manifest, chunks))
+}
+
+/// A snapshot being downloaded: the verified manifest and the chunks received so far.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDownload {
+    manifest: SnapshotManifest,
+    chunks: BTreeMap<u32, SnapshotChunk>,
+}
+
+impl SnapshotDownload {
+    /// Starts a download of `manifest`, which must hash up to `trusted_root`.
+    pub fn new(manifest: SnapshotManifest, trusted_root: &str) -> Result<Self> {
+        let got = manifest.root()?;
+        if got != trusted_root || manifest.state_root != trusted_root {
+            return Err(StateSyncError::RootMismatch { expected: trusted_root.to_string(), got }.into());
+        }
+        Ok(Self { manifest, chunks: BTreeMap::new() })
+    }
+
+    pub fn manifest(&self) -> &SnapshotManifest {
+        &self.manifest
+    }
+
+    /// Verifies and keeps `chunk`. Returns `false` if it was already held.
+    pub fn accept(&mut self, chunk: SnapshotChunk) -> Result<bool> {
+        self.manifest.verify_chunk(&chunk)?;
+        if self.chunks.contains_key(&chunk.index) {
+            return Ok(false);
+        }
+        self.chunks.insert(chunk.index, chunk);
+        Ok(true)
+    }
+
+    /// Indices of the non-empty chunks still to fetch, ascending.
+    pub fn missing(&self) -> Vec<u32> {
+        (0..self.manifest.chunk_sizes.len() as u32)
+            .filter(|i| self.manifest.chunk_sizes[*i as usize] > 0 && !self.chunks.contains_key(i))
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.missing().is_empty()
+    }
+
+    /// Builds the state from the registries and all chunks and checks its commitment.
+    pub fn finish(self) -> Result<State> {
+        let missing = self.missing().len();
+        if missing > 0 {
+            return Err(StateSyncError::Incomplete(missing).into());
+        }
+        let registries: StateFileData = serde_json::from_str(&self.manifest.registries)
+            .map_err(|e| StateSyncError::Malformed(format!("registries: {}", e)))?;
+        let state = registries.into_state()?;
+        let mut count = 0u64;
+        for c in self.chunks.values() {
+            for a in &c.accounts {
+                state.set_balance(&a.address, parse_balance(&a.balance)?);
+                state.set_nonce(&a.address, a.nonce);
+                state.set_uplp_balance(&a.address, parse_balance(&a.uplp_balance)?);
+            }
+            for t in &c.tokens {
+                state.set_asset_balance(&t.address, &Asset::from_canonical(&t.asset), parse_balance(&t.balance)?);
+            }
+            count += c.len() as u64;
+        }
+        let root = state.recompute_root();
+        if root != self.manifest.state_root || count != self.manifest.leaf_count {
+            return Err(StateSyncError::RootMismatch { expected: self.manifest.state_root, got: root }.into());
+        }
+        Ok(state)
+    }
+}
+
+/// JSON manifest of the state file's snapshot (RPC `state_sync_manifest`).
+pub fn state_sync_manifest_json(path: &Path, chunk_leaves: usize) -> Result<String> {
+    let (manifest, _) = export_snapshot(&load_state_file(path)?, chunk_leaves)?;
+    serde_json::to_string(&manifest).map_err(|e| PlatariumError::State(e.to_string()))
+}
+
+/// JSON chunk `index` of the state file's snapshot (RPC `state_sync_chunk`).
+pub fn state_sync_chunk_json(path: &Path, index: u32, chunk_leaves: usize) -> Result<String> {
+    let (_, chunks) = export_snapshot(&load_state_file(path)?, chunk_leaves)?;
+    let chunk = chunks.get(index as usize).ok_or(StateSyncError::UnknownChunk(index))?;
+    serde_json::to_string(chunk).map_err(|e| PlatariumError::State(e.to_string()))
+}
+
+/// First `chunk_bits` bits of `key` as a chunk index.
+fn chunk_index(key: &Word, chunk_bits: u8) -> u32 {
+    if chunk_bits == 0 {
+        return 0;
+    }
+    (u32::from_be_bytes([key[0], key[1], key[2], key[3]])) >> (32 - chunk_bits as u32)
+}
+
+fn parse_balance(s: &str) -> std::result::Result<u128, StateSyncError> {
+    s.parse()
+        .map_err(|_| StateSyncError::Malformed(format!("balance {:?}", s)))
+}
+
+fn parse_word(s: &str) -> std::result::Result<Word, StateSyncError> {
+    hex::decode(s)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| StateSyncError::Malformed(format!("digest {:?}", s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_state() -> State {
+        let state = State::new();
+        for i in 0..5u64 {
+            let a = format!("acct{}", i);
+            state.set_balance(&a, 100 + i as u128);
+            state.set_nonce(&a, i);
+        }
+        let usdt = Asset::Token("USDT".into());
+        state.register_token(&usdt, &"acct0".to_string()).unwrap();
+        state.set_asset_balance(&"acct1".to_string(), &usdt, 40);
+        state.set_uplp_balance(&"fees-only".to_string(), 9);
+        // Explicit zero entries commit like missing ones and are not exported.
+        state.set_balance(&"empty".to_string(), 0);
+        state.set_asset_balance(&"acct2".to_string(), &usdt, 0);
+        state
+    }
+
+    #[test]
+    fn test_chunk_digests_hash_up_to_commitment() {
+        let state = sample_state();
+        let (manifest, chunks) = export_snapshot(&state, 3).unwrap();
+        assert_eq!(manifest.chunk_bits, 2);
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(manifest.leaf_count, 7);
+        assert_eq!(manifest.root().unwrap(), state.current_root());
+        for c in &chunks {
+            manifest.verify_chunk(c).unwrap();
+        }
+        // Any chunking of the same state hashes up to the same root.
+        for per_chunk in [1, 2, 100] {
+            assert_eq!(export_snapshot(&state, per_chunk).unwrap().0.root().unwrap(), state.current_root());
+        }
+        assert!(export_snapshot(&state, 0).is_err());
+
+        // A manifest that misstates a chunk's size cannot get the real chunk accepted.
+        let mut lying = manifest.clone();
+        let from = lying.chunk_sizes.iter().position(|&n| n > 0).unwrap();
+        lying.chunk_sizes[from] += 1;
+        assert!(lying.root().is_err());
+        lying.leaf_count += 1;
+        assert!(lying.verify_chunk(&chunks[from]).is_err());
+
+        // So does one whose registries differ from the committed ones.
+        let mut registries = manifest.clone();
+        registries.registries = serde_json::to_string(&StateFileData::empty()).unwrap();
+        assert_ne!(registries.root().unwrap(), state.current_root());
+    }
+
+    #[test]
+    fn test_download_rejects_tampered_chunks() {
+        let state = sample_state();
+        let (manifest, chunks) = export_snapshot(&state, 3).unwrap();
+        assert!(SnapshotDownload::new(manifest.clone(), &hex::encode([1u8; 32])).is_err());
+
+        let mut dl = SnapshotDownload::new(manifest, &state.current_root()).unwrap();
+        let full = chunks.iter().position(|c| c.len() > 1 && !c.accounts.is_empty()).unwrap();
+        let mut bad = chunks[full].clone();
+        bad.accounts[0].balance = "1000000".into();
+        assert!(dl.accept(bad).is_err());
+        let mut reordered = chunks[full].clone();
+        reordered.accounts.reverse();
+        assert!(dl.accept(reordered).is_err());
+        let mut moved = chunks[full].clone();
+        moved.index = (full as u32 + 1) % 4;
+        assert!(dl.accept(moved).is_err());
+        let mut dropped = chunks[full].clone();
+        dropped.accounts.pop();
+        assert!(dl.accept(dropped).is_err());
+
+        assert!(dl.accept(chunks[full].clone()).unwrap());
+        assert!(!dl.accept(chunks[full].clone()).unwrap());
+        let expected: Vec<u32> = (0..4).filter(|&i| i as usize != full && !chunks[i as usize].is_empty()).collect();
+        assert_eq!(dl.missing(), expected);
+        assert!(dl.clone().finish().is_err());
+        for i in expected {
+            dl.accept(chunks[i as usize].clone()).unwrap();
+        }
+        let synced = dl.finish().unwrap();
+        assert_eq!(synced.current_root(), state.current_root());
+        let usdt = Asset::Token("USDT".into());
+        assert_eq!(synced.get_asset_balance(&"acct1".to_string(), &usdt), 40);
+        assert_eq!(synced.get_uplp_balance(&"fees-only".to_string()), 9);
+        assert_eq!(synced.asset_registry(), state.asset_registry());
+    }
+}
@@ -3,20 +3,37 @@
 //! **Multi-asset:** Amount is in minimal units of the chosen `asset` (PLP or token); the asset does not affect fee. Fee is always in μPLP (1 PLP = 1_000_000 μPLP). Transactions with non-μPLP fee or zero fee are rejected.
 //!
 //! **Determinism:** Hash is computed deterministically (e.g. set elements sorted before hashing); no randomness or system time. Same transaction data yields the same hash.
+//!
+//! **Encoding:** Transactions travel between nodes in the binary wire format of `platarium_consensus::wire` ([`Transaction::to_wire_bytes`], framed with `platarium_consensus::frame` on streams). `TxVersion::V2` transactions also hash that encoding; `V1` transactions keep the original JSON hash preimage. JSON is for display and the RPC/CLI only.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+use crate::address::{self, AddressError};
 use crate::error::Result;
 use crate::core::asset::Asset;
-use crate::signature::{hash_message, verify_signature};
+use crate::core::bridge::ForeignHeaderProof;
+use crate::core::multisig::{MultisigSignature, MultisigWitness};
+use crate::core::outbound::check_message;
+use crate::core::state::{BURN_ADDRESS, STAKING_ADDRESS};
+use crate::signature::{normalize_signature_hex, verify_signature_hash_with, SignatureScheme};
+use crate::key_generator::KeyPair;
+use crate::core::partial_tx::{PartialSignature, PartialTxError, RoleSignature, SignerRole};
+use crate::signer::{sign_digest_checked, software_signers, Signer, SoftwareSigner};
+use crate::utils::NetworkId;
+use platarium_consensus::wire::{Decode, Encode, Reader, WireError};
+use platarium_consensus::{check_amount_and_fee, AmountRule, BasicTxError};
 use thiserror::Error;
 use crate::PlatariumError;
 
 /// Minimum transaction fee in μPLP. Fee currency is fixed to μPLP and is not configurable.
-pub const MIN_FEE_UPLP: u128 = 1;
+pub const MIN_FEE_UPLP: u128 = platarium_consensus::MIN_FEE_UPLP;
+
+/// Length prefix of a framed transaction (`platarium_consensus::frame`), included in [`Transaction::weight`].
+pub const TX_FRAME_PREFIX_BYTES: u64 = 4;
 
 /// Errors produced by transaction validation.
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TransactionValidationError {
     #[error("Invalid signature: {0}")]
     InvalidSignature(String),
@@ -29,13 +46,288 @@ pub enum TransactionValidationError {
 
     #[error("Hash mismatch: expected {0}, got {1}")]
     HashMismatch(String, String),
+
+    #[error("Invalid amount: {0:?} transactions must have amount 0")]
+    UnexpectedAmount(TxKind),
+
+    #[error("Invalid address {address}: {reason}")]
+    InvalidAddress { address: String, reason: AddressError },
+
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
+
+    #[error("Invalid asset: {0:?} transactions must use PLP")]
+    InvalidAsset(TxKind),
+
+    #[error("Invalid outputs: {0}")]
+    InvalidOutputs(String),
+
+    #[error("Chain ID mismatch: network expects {expected}, transaction has {got:?}")]
+    ChainIdMismatch { expected: String, got: Option<String> },
+
+    #[error("Deployment mismatch: chain expects salt {expected:?}, transaction has {got:?}")]
+    DeploymentMismatch {
+        expected: Option<String>,
+        got: Option<String>,
+    },
+
+    #[error("Malformed transaction JSON: {0}")]
+    MalformedJson(String),
+
+    #[error("Missing field: {0}")]
+    MissingField(String),
+
+    #[error("Unknown field: {0}")]
+    UnknownField(String),
+
+    #[error("Invalid field {field}: {reason}")]
+    InvalidField { field: String, reason: String },
 }
 
+/// Fields [`Transaction::from_json`] requires.
+const TX_JSON_REQUIRED_FIELDS: [&str; 11] = [
+    "hash", "from", "to", "asset", "amount", "fee_uplp", "nonce", "reads", "writes", "sig_main", "sig_derived",
+];
+
+/// Fields [`Transaction::from_json`] accepts besides the required ones.
+const TX_JSON_OPTIONAL_FIELDS: [&str; 9] = [
+    "pub_main", "pub_derived", "kind", "deployment_salt", "valid_until", "chain_id", "sig_scheme", "multisig", "version",
+];
+
 /// Result type for transaction validation.
 pub type ValidationResult = std::result::Result<(), TransactionValidationError>;
 
+/// Transaction kind. `Transfer` is the default and is omitted from the hash and JSON, so plain transfers keep their original hash.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum TxKind {
+    /// Move `amount` of `asset` from `from` to `to`.
+    #[default]
+    Transfer,
+    /// Issuer pauses all transfers of `asset` (token only). `to` is ignored; `amount` must be 0.
+    FreezeAsset,
+    /// Issuer resumes transfers of `asset`. `to` is ignored; `amount` must be 0.
+    UnfreezeAsset,
+    /// `from` (owner) allows `to` (spender) to spend up to `amount` of `asset`. Replaces any previous allowance; 0 revokes.
+    Approve,
+    /// `from` (spender) moves `amount` of `asset` from `owner` to `to` within the owner's allowance. The spender pays the fee.
+    TransferFrom { owner: String },
+    /// Binds `pub_derived` as the sender's derived key without moving funds. `to` is ignored; `amount` must be 0. Only valid while no derived key is registered.
+    RegisterDerivedKey,
+    /// Signed by the derived key only: schedules rotation of the sender's main key to `new_main_key` after `RECOVERY_DELAY_BLOCKS`. `amount` must be 0.
+    InitiateRecovery { new_main_key: String },
+    /// Signed by the current key pair: cancels a pending rotation during its delay window. `amount` must be 0.
+    CancelRecovery,
+    /// Authorizes `session_key` to transfer up to `max_amount_per_block` of `asset` per block until `expiry_height`. `to` is ignored; `amount` must be 0.
+    RegisterSessionKey {
+        session_key: String,
+        max_amount_per_block: u128,
+        expiry_height: u64,
+    },
+    /// Removes a registered session key. `amount` must be 0.
+    RevokeSessionKey { session_key: String },
+    /// Transfer from `from` signed by `session_key` alone (`sig_main`), within that key's registered scope.
+    SessionTransfer { session_key: String },
+    /// Emits an outbound message (hex `payload`) to `destination` for external bridges; see `core::outbound`. `to` is ignored; `amount` must be 0.
+    SendMessage { destination: String, payload: String },
+    /// Makes the sender a multisig account with `signers` (key hex) and `threshold`, or replaces the signer set of a multisig sender; see `core::multisig`. `to` is ignored; `amount` must be 0.
+    SetMultisig { signers: Vec<String>, threshold: u32 },
+    /// Relayer `from` mints `amount` of wrapped `asset` to `to` for transfer `claim_id` locked on `proof.chain_id`, after the claim is verified against an attested foreign header; see `core::bridge`. The relayer pays the fee.
+    BridgeClaim { claim_id: String, proof: Box<ForeignHeaderProof> },
+    /// Registers a recurring payment of `amount` of `asset` to `to` every `interval_blocks` until `end_height`, executed at block finalization; see `core::standing_orders`. The order id is this transaction's hash. No funds move at registration.
+    CreateStandingOrder { interval_blocks: u64, end_height: u64 },
+    /// Cancels the sender's standing order `order_id`. `to` is ignored; `amount` must be 0.
+    CancelStandingOrder { order_id: String },
+    /// Issuer `from` creates `amount` of its registered token `asset` and credits it to `to`. Wrapped assets are minted only by `BridgeClaim`.
+    Mint,
+    /// Destroys `amount` of the sender's `asset` by moving it to `state::BURN_ADDRESS`. `to` is ignored.
+    Burn,
+    /// Locks `amount` PLP of the sender as stake in `state::STAKING_ADDRESS`. `asset` must be PLP; `to` is ignored.
+    Stake,
+    /// Releases `amount` of the sender's stake back to its PLP balance. `asset` must be PLP; `to` is ignored.
+    Unstake,
+    /// Pays every output from `from` atomically: if any output fails, none is applied and the transaction is rejected.
+    /// One fee and one nonce for the batch. `to`, `asset` are ignored; `amount` must be 0.
+    MultiTransfer { outputs: Vec<TransferOutput> },
+}
+
+/// Largest number of outputs in a `MultiTransfer`.
+pub const MAX_TRANSFER_OUTPUTS: usize = 256;
+
+/// One payment of a `MultiTransfer`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TransferOutput {
+    pub to: String,
+    pub asset: Asset,
+    pub amount: u128,
+}
+
+impl Encode for TransferOutput {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.to.encode(out);
+        self.asset.encode(out);
+        self.amount.encode(out);
+    }
+}
+
+impl Decode for TransferOutput {
+    fn decode(reader: &mut Reader<'_>) -> std::result::Result<Self, WireError> {
+        Ok(Self { to: String::decode(reader)?, asset: Asset::decode(reader)?, amount: u128::decode(reader)? })
+    }
+}
+
+impl TxKind {
+    pub fn is_transfer(&self) -> bool {
+        *self == TxKind::Transfer
+    }
+}
+
+/// One-byte tag in declaration order (`Transfer` = 0 ... `MultiTransfer` = 20), then the variant's fields.
+impl Encode for TxKind {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            TxKind::Transfer => out.push(0),
+            TxKind::FreezeAsset => out.push(1),
+            TxKind::UnfreezeAsset => out.push(2),
+            TxKind::Approve => out.push(3),
+            TxKind::TransferFrom { owner } => {
+                out.push(4);
+                owner.encode(out);
+            }
+            TxKind::RegisterDerivedKey => out.push(5),
+            TxKind::InitiateRecovery { new_main_key } => {
+                out.push(6);
+                new_main_key.encode(out);
+            }
+            TxKind::CancelRecovery => out.push(7),
+            TxKind::RegisterSessionKey { session_key, max_amount_per_block, expiry_height } => {
+                out.push(8);
+                session_key.encode(out);
+                max_amount_per_block.encode(out);
+                expiry_height.encode(out);
+            }
+            TxKind::RevokeSessionKey { session_key } => {
+                out.push(9);
+                session_key.encode(out);
+            }
+            TxKind::SessionTransfer { session_key } => {
+                out.push(10);
+                session_key.encode(out);
+            }
+            TxKind::SendMessage { destination, payload } => {
+                out.push(11);
+                destination.encode(out);
+                payload.encode(out);
+            }
+            TxKind::SetMultisig { signers, threshold } => {
+                out.push(12);
+                signers.encode(out);
+                threshold.encode(out);
+            }
+            TxKind::BridgeClaim { claim_id, proof } => {
+                out.push(13);
+                claim_id.encode(out);
+                proof.encode(out);
+            }
+            TxKind::CreateStandingOrder { interval_blocks, end_height } => {
+                out.push(14);
+                interval_blocks.encode(out);
+                end_height.encode(out);
+            }
+            TxKind::CancelStandingOrder { order_id } => {
+                out.push(15);
+                order_id.encode(out);
+            }
+            TxKind::Mint => out.push(16),
+            TxKind::Burn => out.push(17),
+            TxKind::Stake => out.push(18),
+            TxKind::Unstake => out.push(19),
+            TxKind::MultiTransfer { outputs } => {
+                out.push(20);
+                outputs.encode(out);
+            }
+        }
+    }
+}
+
+impl Decode for TxKind {
+    fn decode(reader: &mut Reader<'_>) -> std::result::Result<Self, WireError> {
+        Ok(match reader.u8()? {
+            0 => TxKind::Transfer,
+            1 => TxKind::FreezeAsset,
+            2 => TxKind::UnfreezeAsset,
+            3 => TxKind::Approve,
+            4 => TxKind::TransferFrom { owner: String::decode(reader)? },
+            5 => TxKind::RegisterDerivedKey,
+            6 => TxKind::InitiateRecovery { new_main_key: String::decode(reader)? },
+            7 => TxKind::CancelRecovery,
+            8 => TxKind::RegisterSessionKey {
+                session_key: String::decode(reader)?,
+                max_amount_per_block: u128::decode(reader)?,
+                expiry_height: u64::decode(reader)?,
+            },
+            9 => TxKind::RevokeSessionKey { session_key: String::decode(reader)? },
+            10 => TxKind::SessionTransfer { session_key: String::decode(reader)? },
+            11 => TxKind::SendMessage { destination: String::decode(reader)?, payload: String::decode(reader)? },
+            12 => TxKind::SetMultisig { signers: Vec::decode(reader)?, threshold: u32::decode(reader)? },
+            13 => TxKind::BridgeClaim {
+                claim_id: String::decode(reader)?,
+                proof: Box::new(ForeignHeaderProof::decode(reader)?),
+            },
+            14 => TxKind::CreateStandingOrder { interval_blocks: u64::decode(reader)?, end_height: u64::decode(reader)? },
+            15 => TxKind::CancelStandingOrder { order_id: String::decode(reader)? },
+            16 => TxKind::Mint,
+            17 => TxKind::Burn,
+            18 => TxKind::Stake,
+            19 => TxKind::Unstake,
+            20 => TxKind::MultiTransfer { outputs: Vec::decode(reader)? },
+            tag => return Err(WireError::InvalidTag(tag)),
+        })
+    }
+}
+
+/// Hash preimage format of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxVersion {
+    /// Compact JSON of the signing payload after `PlatariumSignature:`. Default, so existing hashes stay valid.
+    #[default]
+    V1,
+    /// Binary encoding of the same fields after `PlatariumTx/v2:` (`platarium_consensus::TX_BINARY_DOMAIN_SEPARATOR`),
+    /// independent of JSON serializer details.
+    V2,
+}
+
+impl TxVersion {
+    pub fn is_v1(&self) -> bool {
+        *self == TxVersion::V1
+    }
+}
+
+impl Encode for TxVersion {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(match self {
+            TxVersion::V1 => 1,
+            TxVersion::V2 => 2,
+        });
+    }
+}
+
+impl Decode for TxVersion {
+    fn decode(reader: &mut Reader<'_>) -> std::result::Result<Self, WireError> {
+        match reader.u8()? {
+            1 => Ok(TxVersion::V1),
+            2 => Ok(TxVersion::V2),
+            tag => Err(WireError::InvalidTag(tag)),
+        }
+    }
+}
+
 /// Canonical transaction structure (single source of truth for the network format).
+///
+/// **Stability:** consensus-critical, stable (`compat::v1`).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct Transaction {
     /// Transaction hash (computed from transaction data)
     pub hash: String,
@@ -58,10 +350,13 @@ pub struct Transaction {
     /// Nonce to prevent replay attacks
     pub nonce: u64,
 
-    /// Set of addresses that this transaction reads from
+    /// Set of addresses that this transaction reads from (see [`Transaction::access_set`]). Serialized sorted.
+    #[serde(serialize_with = "serialize_sorted")]
     pub reads: HashSet<String>,
 
-    /// Set of addresses that this transaction writes to
+    /// Set of addresses that this transaction writes to; `ExecutionLogic::execute_within_access_sets` enforces it.
+    /// Serialized sorted.
+    #[serde(serialize_with = "serialize_sorted")]
     pub writes: HashSet<String>,
 
     /// Main signature (from main private key)
@@ -77,10 +372,189 @@ pub struct Transaction {
     /// HKDF-derived public key hex (required for dual-signature verification when present)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pub_derived: Option<String>,
+
+    /// Transaction kind (defaults to `Transfer`). Part of the hash for non-transfer kinds.
+    #[serde(default, skip_serializing_if = "TxKind::is_transfer")]
+    pub kind: TxKind,
+
+    /// Deployment salt from the chainspec the transaction was signed for. Part of the hash when present, so a
+    /// transaction signed for one deployment never validates on another that shares key material.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployment_salt: Option<String>,
+
+    /// Last block height the transaction may be executed at; `None` never expires. Part of the hash when present, so
+    /// extending it requires new signatures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+
+    /// Chain ID of the network the transaction is for (see `NetworkId::chain_id`). Part of the hash when present, so a
+    /// transaction signed for one network never validates on another. Absent only on mainnet transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+
+    /// Scheme of `sig_main` and `sig_derived` (defaults to ECDSA). Not part of the hash: the signatures are over it.
+    #[serde(default, skip_serializing_if = "SignatureScheme::is_ecdsa")]
+    pub sig_scheme: SignatureScheme,
+
+    /// Signer set and signatures of a multisig sender; replaces `sig_main`/`sig_derived`. Not part of the hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<MultisigWitness>,
+
+    /// Hash preimage format (defaults to `V1`). Selects the hash, so it is covered by it indirectly: the V1 and V2
+    /// preimages have different domain separators.
+    #[serde(default, skip_serializing_if = "TxVersion::is_v1")]
+    pub version: TxVersion,
+}
+
+/// Serializes an address set in sorted order, so equal transactions have identical JSON.
+fn serialize_sorted<S>(set: &HashSet<String>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_seq(set.iter().collect::<BTreeSet<_>>())
+}
+
+/// Wire format: `version`, then every field in declaration order from `hash` to `multisig`, with `reads` and
+/// `writes` sorted.
+impl Encode for Transaction {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.version.encode(out);
+        self.hash.encode(out);
+        self.from.encode(out);
+        self.to.encode(out);
+        self.asset.encode(out);
+        self.amount.encode(out);
+        self.fee_uplp.encode(out);
+        self.nonce.encode(out);
+        sorted_set(&self.reads).encode(out);
+        sorted_set(&self.writes).encode(out);
+        self.sig_main.encode(out);
+        self.sig_derived.encode(out);
+        self.pub_main.encode(out);
+        self.pub_derived.encode(out);
+        self.kind.encode(out);
+        self.deployment_salt.encode(out);
+        self.valid_until.encode(out);
+        self.chain_id.encode(out);
+        self.sig_scheme.encode(out);
+        self.multisig.encode(out);
+    }
+}
+
+impl Decode for Transaction {
+    fn decode(reader: &mut Reader<'_>) -> std::result::Result<Self, WireError> {
+        Ok(Self {
+            version: TxVersion::decode(reader)?,
+            hash: String::decode(reader)?,
+            from: String::decode(reader)?,
+            to: String::decode(reader)?,
+            asset: Asset::decode(reader)?,
+            amount: u128::decode(reader)?,
+            fee_uplp: u128::decode(reader)?,
+            nonce: u64::decode(reader)?,
+            reads: Vec::<String>::decode(reader)?.into_iter().collect(),
+            writes: Vec::<String>::decode(reader)?.into_iter().collect(),
+            sig_main: String::decode(reader)?,
+            sig_derived: String::decode(reader)?,
+            pub_main: Option::decode(reader)?,
+            pub_derived: Option::decode(reader)?,
+            kind: TxKind::decode(reader)?,
+            deployment_salt: Option::decode(reader)?,
+            valid_until: Option::decode(reader)?,
+            chain_id: Option::decode(reader)?,
+            sig_scheme: SignatureScheme::decode(reader)?,
+            multisig: Option::decode(reader)?,
+        })
+    }
+}
+
+fn sorted_set(set: &HashSet<String>) -> Vec<&str> {
+    let mut items: Vec<&str> = set.iter().map(String::as_str).collect();
+    items.sort_unstable();
+    items
+}
+
+/// Builder for the data a transaction hash and its signatures cover.
+///
+/// Serializes (compact JSON, field order as declared) to the body of the hash preimage; [`SigningPayload::bytes`]
+/// prefixes the signature domain separator. `Transaction::compute_hash`, `verify_signatures` and the CLI/RPC signers
+/// all go through this type, so an external signer building the same payload gets the same hash.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct SigningPayload<'a>(platarium_consensus::SigningPayload<'a, TxKind>);
+
+impl<'a> SigningPayload<'a> {
+    /// Plain transfer with no declared reads or writes.
+    pub fn new(from: &'a str, to: &'a str, asset: &Asset, amount: u128, fee_uplp: u128, nonce: u64) -> Self {
+        Self(platarium_consensus::SigningPayload::new(
+            from,
+            to,
+            asset.as_canonical(),
+            amount,
+            fee_uplp,
+            nonce,
+        ))
+    }
+
+    /// Payload of an existing transaction (ignores its declared `hash` and signatures).
+    pub fn from_transaction(tx: &'a Transaction) -> Self {
+        Self::new(&tx.from, &tx.to, &tx.asset, tx.amount, tx.fee_uplp, tx.nonce)
+            .reads(&tx.reads)
+            .writes(&tx.writes)
+            .kind(&tx.kind)
+            .deployment_salt(tx.deployment_salt.as_deref())
+            .valid_until(tx.valid_until)
+            .chain_id(tx.chain_id.as_deref())
+            .version(tx.version)
+    }
+
+    /// Declared read set; order and duplicates do not matter.
+    pub fn reads(self, reads: impl IntoIterator<Item = &'a String>) -> Self {
+        Self(self.0.reads(reads.into_iter().map(String::as_str)))
+    }
+
+    /// Declared write set; order and duplicates do not matter.
+    pub fn writes(self, writes: impl IntoIterator<Item = &'a String>) -> Self {
+        Self(self.0.writes(writes.into_iter().map(String::as_str)))
+    }
+
+    /// Transaction kind; `Transfer` is omitted from the payload.
+    pub fn kind(self, kind: &'a TxKind) -> Self {
+        Self(self.0.kind((!kind.is_transfer()).then_some(kind)))
+    }
+
+    pub fn deployment_salt(self, salt: Option<&'a str>) -> Self {
+        Self(self.0.deployment_salt(salt))
+    }
+
+    pub fn valid_until(self, height: Option<u64>) -> Self {
+        Self(self.0.valid_until(height))
+    }
+
+    pub fn chain_id(self, chain_id: Option<&'a str>) -> Self {
+        Self(self.0.chain_id(chain_id))
+    }
+
+    /// Preimage format; `V2` hashes the binary encoding instead of the JSON.
+    pub fn version(self, version: TxVersion) -> Self {
+        Self(self.0.binary(version == TxVersion::V2))
+    }
+
+    /// Exact bytes that are hashed: `PlatariumSignature:` followed by the compact JSON of this payload, or for `V2`
+    /// `PlatariumTx/v2:` followed by its binary encoding.
+    pub fn bytes(&self) -> Vec<u8> {
+        self.0.bytes()
+    }
+
+    /// SHA256 of [`Self::bytes`]; the digest both signatures are over.
+    pub fn hash(&self) -> [u8; 32] {
+        self.0.hash()
+    }
 }
 
 impl Transaction {
-    /// Constructs a new transaction and computes its hash.
+    /// Constructs a new transaction and computes its hash. Checksummed `from`/`to` key addresses are stored in
+    /// canonical lowercase form; a checksum mismatch is an error.
     pub fn new(
         from: String,
         to: String,
@@ -95,8 +569,8 @@ impl Transaction {
     ) -> Result<Self> {
         let mut tx = Self {
             hash: String::new(),
-            from,
-            to,
+            from: address::normalize(&from)?,
+            to: address::normalize(&to)?,
             asset,
             amount,
             fee_uplp,
@@ -107,102 +581,269 @@ impl Transaction {
             sig_derived,
             pub_main: None,
             pub_derived: None,
+            kind: TxKind::Transfer,
+            deployment_salt: None,
+            valid_until: None,
+            chain_id: None,
+            sig_scheme: SignatureScheme::Ecdsa,
+            multisig: None,
+            version: TxVersion::V1,
         };
         tx.hash = tx.compute_hash()?;
         Ok(tx)
     }
 
-    fn hash_data(&self) -> (Vec<String>, Vec<String>) {
-        let mut reads_vec: Vec<String> = self.reads.iter().cloned().collect();
-        reads_vec.sort();
-        let mut writes_vec: Vec<String> = self.writes.iter().cloned().collect();
-        writes_vec.sort();
-        (reads_vec, writes_vec)
+    /// Accounts the transaction's effects can touch: `from` (which pays the fee), `to`, plus the delegating owner of a
+    /// `TransferFrom`, the recipients of a `MultiTransfer`, `BURN_ADDRESS` for `Burn` and `STAKING_ADDRESS` for
+    /// `Stake`/`Unstake`. The treasury's fee credit is commutative across transactions and is left out.
+    pub fn access_set(&self) -> HashSet<String> {
+        let mut set: HashSet<String> = [self.from.clone(), self.to.clone()].into_iter().collect();
+        match &self.kind {
+            TxKind::TransferFrom { owner } => {
+                set.insert(owner.clone());
+            }
+            TxKind::MultiTransfer { outputs } => set.extend(outputs.iter().map(|o| o.to.clone())),
+            TxKind::Burn => {
+                set.insert(BURN_ADDRESS.to_string());
+            }
+            TxKind::Stake | TxKind::Unstake => {
+                set.insert(STAKING_ADDRESS.to_string());
+            }
+            _ => {}
+        }
+        set
+    }
+
+    /// Replaces `reads` and `writes` with [`Self::access_set`] and recomputes the hash. Signatures must be produced
+    /// over the new hash data.
+    pub fn derive_access_sets(mut self) -> Result<Self> {
+        let access = self.access_set();
+        self.reads = access.clone();
+        self.writes = access;
+        self.hash = self.compute_hash()?;
+        Ok(self)
+    }
+
+    /// Sets the transaction kind and recomputes the hash. Signatures must be produced over the new hash data.
+    pub fn with_kind(mut self, kind: TxKind) -> Result<Self> {
+        self.kind = kind;
+        self.hash = self.compute_hash()?;
+        Ok(self)
+    }
+
+    /// Exact bytes whose SHA256 is `hash` and which both signatures sign (see [`SigningPayload`]): `PlatariumSignature:`
+    /// followed by the compact JSON of `from, to, asset, amount, fee_uplp, nonce, reads, writes` (sets sorted), then
+    /// `kind` unless it is a plain transfer, then `deployment_salt`, `valid_until` and `chain_id` if present. Lets auditors and external signers reproduce the hash.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        SigningPayload::from_transaction(self).bytes()
     }
     
     /// Computes the transaction hash. Same transaction data -> same hash. No randomness or system time.
     pub fn compute_hash(&self) -> Result<String> {
-        #[derive(Serialize)]
-        struct TransactionHashData {
-            from: String,
-            to: String,
-            asset: String,
-            amount: u128,
-            fee_uplp: u128,
-            nonce: u64,
-            reads: Vec<String>,
-            writes: Vec<String>,
-        }
-        let (reads_vec, writes_vec) = self.hash_data();
-        let hash_data = TransactionHashData {
-            from: self.from.clone(),
-            to: self.to.clone(),
-            asset: self.asset.as_canonical(),
-            amount: self.amount,
-            fee_uplp: self.fee_uplp,
-            nonce: self.nonce,
-            reads: reads_vec,
-            writes: writes_vec,
-        };
-        let hash_bytes = hash_message(&hash_data)?;
-        Ok(hex::encode(hash_bytes))
+        Ok(hex::encode(SigningPayload::from_transaction(self).hash()))
     }
     
-    /// Verifies both signatures (main and derived)
+    /// Verifies both signatures: `sig_main` against `pub_main` (or `from`) and `sig_derived` against `pub_derived`. The derived key is required and must differ from the main key; whether it is the key registered for the account is checked against state during execution. `InitiateRecovery` is checked against the derived signature only, `SessionTransfer` against `sig_main` under its session key only. A transaction carrying a multisig witness is checked against the witness alone (threshold signatures from its signer set); whether that set is the account's is checked against state during execution. All signatures are checked under `sig_scheme`.
     pub fn verify_signatures(&self) -> Result<bool> {
-        #[derive(Serialize)]
-        struct TransactionHashData {
-            from: String,
-            to: String,
-            asset: String,
-            amount: u128,
-            fee_uplp: u128,
-            nonce: u64,
-            reads: Vec<String>,
-            writes: Vec<String>,
+        let hash = SigningPayload::from_transaction(self).hash();
+        let verify_signature_hash =
+            |hash: &[u8], sig: &str, key: &str| verify_signature_hash_with(self.sig_scheme, hash, sig, key);
+        if let TxKind::SessionTransfer { ref session_key } = self.kind {
+            let key = session_key.strip_prefix("Px").unwrap_or(session_key);
+            return verify_signature_hash(&hash, &self.sig_main, key);
+        }
+        if let Some(ref witness) = self.multisig {
+            return witness.verify(self.sig_scheme, &hash);
         }
-        let (reads_vec, writes_vec) = self.hash_data();
-        let message = TransactionHashData {
-            from: self.from.clone(),
-            to: self.to.clone(),
-            asset: self.asset.as_canonical(),
-            amount: self.amount,
-            fee_uplp: self.fee_uplp,
-            nonce: self.nonce,
-            reads: reads_vec,
-            writes: writes_vec,
-        };
         let pub_main = self.pub_main.as_deref().unwrap_or(self.from.as_str());
-        let pub_derived = self
-            .pub_derived
-            .as_deref()
-            .unwrap_or(pub_main);
-        let main_verified = verify_signature(&message, &self.sig_main, pub_main)?;
+        let pub_derived = self.pub_derived.as_deref().ok_or_else(|| {
+            PlatariumError::Signature("missing derived public key".to_string())
+        })?;
+        if same_public_key(pub_main, pub_derived) {
+            return Err(PlatariumError::Signature(
+                "derived key must differ from the main key".to_string(),
+            ));
+        }
+        if let TxKind::InitiateRecovery { .. } = self.kind {
+            return verify_signature_hash(&hash, &self.sig_derived, pub_derived);
+        }
+        let main_verified = verify_signature_hash(&hash, &self.sig_main, pub_main)?;
         if !main_verified {
             return Ok(false);
         }
-        let derived_verified = verify_signature(&message, &self.sig_derived, pub_derived)?;
+        let derived_verified = verify_signature_hash(&hash, &self.sig_derived, pub_derived)?;
         Ok(main_verified && derived_verified)
     }
     
+    /// Signs the hash as `role` without changing the transaction, for carrying to wherever the other key lives.
+    /// Pair with [`Self::attach_signature`].
+    pub fn sign_role(&self, role: SignerRole, signer: &dyn Signer) -> Result<RoleSignature> {
+        let hash = SigningPayload::from_transaction(self).hash();
+        let (signature, public_key) = sign_digest_checked(signer, &hash)?;
+        Ok(RoleSignature {
+            hash: hex::encode(hash),
+            role,
+            signature: PartialSignature {
+                pub_key: hex::encode(public_key.serialize()),
+                signature: hex::encode(signature.serialize_compact()),
+            },
+        })
+    }
+
+    /// Signs with the main key and sets `sig_main`/`pub_main`, leaving the derived signature as it is.
+    pub fn sign_main(&mut self, signer: &dyn Signer) -> Result<()> {
+        let signature = self.sign_role(SignerRole::Main, signer)?;
+        self.attach_signature(&signature)
+    }
+
+    /// Signs with the HKDF-derived key and sets `sig_derived`/`pub_derived`, leaving the main signature as it is.
+    pub fn sign_derived(&mut self, signer: &dyn Signer) -> Result<()> {
+        let signature = self.sign_role(SignerRole::Derived, signer)?;
+        self.attach_signature(&signature)
+    }
+
+    /// Attaches a signature from [`Self::sign_role`]: it must be over this transaction's hash (recomputed from its
+    /// data) and verify under `sig_scheme`. `Main` sets `sig_main` and `pub_main` (only `sig_main` for a
+    /// `SessionTransfer`, whose main signature is by the session key); `Derived` sets `sig_derived` and `pub_derived`;
+    /// `Multisig` adds (or replaces) the signer's entry in the multisig witness, whose signer set must hold the key.
+    pub fn attach_signature(&mut self, signature: &RoleSignature) -> Result<()> {
+        let hash = SigningPayload::from_transaction(self).hash();
+        if !signature.hash.eq_ignore_ascii_case(&hex::encode(hash)) {
+            return Err(PartialTxError::HashMismatch {
+                ours: hex::encode(hash),
+                theirs: signature.hash.clone(),
+            }
+            .into());
+        }
+        let PartialSignature { pub_key, signature: sig } = &signature.signature;
+        let key = pub_key.strip_prefix("Px").unwrap_or(pub_key);
+        if !verify_signature_hash_with(self.sig_scheme, &hash, sig, key).unwrap_or(false) {
+            return Err(PartialTxError::InvalidSignature {
+                role: signature.role,
+                hash: signature.hash.clone(),
+            }
+            .into());
+        }
+        match signature.role {
+            SignerRole::Main => {
+                self.sig_main = sig.clone();
+                if !matches!(self.kind, TxKind::SessionTransfer { .. }) {
+                    self.pub_main = Some(pub_key.clone());
+                }
+            }
+            SignerRole::Derived => {
+                self.sig_derived = sig.clone();
+                self.pub_derived = Some(pub_key.clone());
+            }
+            SignerRole::Multisig => {
+                let unknown = || PartialTxError::UnknownSigner(pub_key.clone());
+                let witness = self.multisig.as_mut().ok_or_else(unknown)?;
+                let signer = witness.signers.iter().position(|s| same_public_key(s, key)).ok_or_else(unknown)? as u32;
+                witness.signatures.retain(|s| s.signer != signer);
+                witness.signatures.push(MultisigSignature { signer, signature: normalize_signature_hex(sig) });
+                witness.signatures.sort_by_key(|s| s.signer);
+            }
+        }
+        Ok(())
+    }
+
+    /// True once the signatures this transaction needs are present and verify (see [`Self::verify_signatures`]).
+    pub fn is_fully_signed(&self) -> bool {
+        self.verify_signatures().unwrap_or(false)
+    }
+
+    /// Adds `signer`'s signature over the hash to the multisig witness, which must already name the signer set.
+    pub fn sign_multisig(&mut self, signer: &dyn Signer) -> Result<()> {
+        let hash = SigningPayload::from_transaction(self).hash();
+        self.multisig
+            .as_mut()
+            .ok_or_else(|| PlatariumError::Signature("transaction has no multisig witness".to_string()))?
+            .sign(signer, &hash)
+    }
+
+    /// Sets the deployment salt and recomputes the hash. Signatures must be produced over the new hash data.
+    pub fn with_deployment_salt(mut self, salt: Option<String>) -> Result<Self> {
+        self.deployment_salt = salt;
+        self.hash = self.compute_hash()?;
+        Ok(self)
+    }
+
+    /// Sets the expiry height and recomputes the hash. Signatures must be produced over the new hash data.
+    pub fn with_valid_until(mut self, height: Option<u64>) -> Result<Self> {
+        self.valid_until = height;
+        self.hash = self.compute_hash()?;
+        Ok(self)
+    }
+
+    /// Sets the chain ID and recomputes the hash. Signatures must be produced over the new hash data.
+    pub fn with_chain_id(mut self, chain_id: Option<String>) -> Result<Self> {
+        self.chain_id = chain_id;
+        self.hash = self.compute_hash()?;
+        Ok(self)
+    }
+
+    /// Checks that the transaction is for `network`: `chain_id` must be the network's chain ID. Mainnet also accepts
+    /// transactions without one, which were signed before the chain ID was part of the hash.
+    pub fn check_chain_id(&self, network: &NetworkId) -> ValidationResult {
+        match self.chain_id.as_deref() {
+            Some(id) if id == network.chain_id() => Ok(()),
+            None if *network == NetworkId::Mainnet => Ok(()),
+            _ => Err(TransactionValidationError::ChainIdMismatch {
+                expected: network.chain_id().to_string(),
+                got: self.chain_id.clone(),
+            }),
+        }
+    }
+
+    /// [`Self::check_chain_id`] for `network`, then [`Self::validate_basic`].
+    pub fn validate_basic_for(&self, network: &NetworkId) -> ValidationResult {
+        self.check_chain_id(network)?;
+        self.validate_basic()
+    }
+
+    /// True if the transaction can no longer be executed in a block at `height`.
+    pub fn is_expired_at(&self, height: u64) -> bool {
+        self.valid_until.is_some_and(|until| height > until)
+    }
+
+    /// Sets the hash preimage format and recomputes the hash. Signatures must be produced over the new hash data.
+    pub fn with_version(mut self, version: TxVersion) -> Result<Self> {
+        self.version = version;
+        self.hash = self.compute_hash()?;
+        Ok(self)
+    }
+
+    /// Binary wire encoding (see `platarium_consensus::wire`), used for network transport.
+    pub fn to_wire_bytes(&self) -> Vec<u8> {
+        self.to_wire()
+    }
+
+    /// Decodes [`Self::to_wire_bytes`] output. The hash and signatures are not checked.
+    pub fn from_wire_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(Self::from_wire(bytes)?)
+    }
+
+    /// Size in bytes of the transaction in its wire encoding. Used for mempool memory accounting.
+    pub fn encoded_size(&self) -> usize {
+        self.to_wire_bytes().len()
+    }
+
+    /// Block space the transaction takes, in bytes: its wire encoding plus the frame length prefix it is stored
+    /// with. Counted against `block_assembly::max_block_size_bytes` (see `block_assembly::fits_block`).
+    pub fn weight(&self) -> u64 {
+        self.encoded_size() as u64 + TX_FRAME_PREFIX_BYTES
+    }
+
     /// Validates the transaction hash matches computed hash
     pub fn validate_hash(&self) -> Result<bool> {
         Ok(self.hash == self.compute_hash()?)
     }
 
     /// Validates basic transaction properties (no state access).
-    /// Amount > 0; fee in μPLP, fee >= MIN_FEE_UPLP (fee = 0 forbidden); signatures.
+    /// Key addresses in `from`/`to` must be valid and canonical (lowercase); amount > 0 for transfers, mint, burn and staking kinds (0 for asset-control kinds and `MultiTransfer`, any value for `Approve`); staking kinds in PLP only; `MultiTransfer` outputs checked by count, address and nonzero amount; fee in μPLP, fee >= MIN_FEE_UPLP (fee = 0 forbidden); signatures.
     /// Fee currency is fixed to μPLP and is not configurable.
     pub fn validate_basic(&self) -> ValidationResult {
-        if self.amount == 0 {
-            return Err(TransactionValidationError::InvalidAmount);
-        }
-        if self.fee_uplp < MIN_FEE_UPLP {
-            return Err(TransactionValidationError::InvalidFee(
-                MIN_FEE_UPLP,
-                self.fee_uplp,
-            ));
-        }
+        self.validate_fields()?;
         match self.verify_signatures() {
             Ok(true) => {}
             Ok(false) => {
@@ -219,6 +860,90 @@ impl Transaction {
         Ok(())
     }
 
+    /// `validate_basic` without the signature check.
+    fn validate_fields(&self) -> ValidationResult {
+        for addr in [&self.from, &self.to] {
+            address::check(addr).map_err(|reason| TransactionValidationError::InvalidAddress {
+                address: addr.clone(),
+                reason,
+            })?;
+        }
+        let rule = match self.kind {
+            TxKind::Transfer
+            | TxKind::TransferFrom { .. }
+            | TxKind::SessionTransfer { .. }
+            | TxKind::BridgeClaim { .. }
+            | TxKind::CreateStandingOrder { .. }
+            | TxKind::Mint
+            | TxKind::Burn
+            | TxKind::Stake
+            | TxKind::Unstake => AmountRule::NonZero,
+            TxKind::FreezeAsset
+            | TxKind::UnfreezeAsset
+            | TxKind::RegisterDerivedKey
+            | TxKind::InitiateRecovery { .. }
+            | TxKind::CancelRecovery
+            | TxKind::RegisterSessionKey { .. }
+            | TxKind::RevokeSessionKey { .. }
+            | TxKind::SendMessage { .. }
+            | TxKind::SetMultisig { .. }
+            | TxKind::CancelStandingOrder { .. }
+            | TxKind::MultiTransfer { .. } => AmountRule::Zero,
+            TxKind::Approve => AmountRule::Any,
+        };
+        check_amount_and_fee(rule, self.amount, self.fee_uplp).map_err(|e| match e {
+            BasicTxError::ZeroAmount => TransactionValidationError::InvalidAmount,
+            BasicTxError::UnexpectedAmount => TransactionValidationError::UnexpectedAmount(self.kind.clone()),
+            BasicTxError::FeeTooLow { min, got } => TransactionValidationError::InvalidFee(min, got),
+        })?;
+        if matches!(self.kind, TxKind::Stake | TxKind::Unstake) && self.asset != Asset::PLP {
+            return Err(TransactionValidationError::InvalidAsset(self.kind.clone()));
+        }
+        if let TxKind::SendMessage { ref destination, ref payload } = self.kind {
+            check_message(destination, payload).map_err(TransactionValidationError::InvalidMessage)?;
+        }
+        if let TxKind::MultiTransfer { ref outputs } = self.kind {
+            check_outputs(outputs)?;
+        }
+        Ok(())
+    }
+
+    /// Strict parse of the canonical JSON form: every required field present, no unknown fields, each field of the
+    /// right type and range, then the `validate_basic` rules except signatures (addresses, amount and fee for the
+    /// kind, outputs, messages) and finally the hash, which must match the data. Signatures are not required, so a
+    /// transaction still being signed parses; `validate_basic` checks them. Use this rather than plain `serde_json`
+    /// for untrusted input, which accepts any `hash`.
+    pub fn from_json(json: &str) -> std::result::Result<Self, TransactionValidationError> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| TransactionValidationError::MalformedJson(e.to_string()))?;
+        let fields = value
+            .as_object()
+            .ok_or_else(|| TransactionValidationError::MalformedJson("expected a JSON object".to_string()))?;
+        if let Some(unknown) = fields
+            .keys()
+            .find(|k| !TX_JSON_REQUIRED_FIELDS.contains(&k.as_str()) && !TX_JSON_OPTIONAL_FIELDS.contains(&k.as_str()))
+        {
+            return Err(TransactionValidationError::UnknownField(unknown.clone()));
+        }
+        if let Some(missing) = TX_JSON_REQUIRED_FIELDS.iter().find(|f| !fields.contains_key(**f)) {
+            return Err(TransactionValidationError::MissingField(missing.to_string()));
+        }
+        for (name, field) in fields {
+            check_json_field(name, field)?;
+        }
+        // Decoded from the text: `Value` holds integers above u64::MAX only as f64.
+        let tx: Transaction =
+            serde_json::from_str(json).map_err(|e| TransactionValidationError::MalformedJson(e.to_string()))?;
+        tx.validate_fields()?;
+        let expected = tx
+            .compute_hash()
+            .map_err(|e| TransactionValidationError::MalformedJson(e.to_string()))?;
+        if tx.hash != expected {
+            return Err(TransactionValidationError::HashMismatch(expected, tx.hash));
+        }
+        Ok(tx)
+    }
+
     /// Parse transaction from Gateway JSON. Asset may be string "PLP" or "Token:XXX".
     /// Amount and fee_uplp may be number or string (u64/u128).
     pub fn from_gateway_json(json_str: &str) -> std::result::Result<Self, PlatariumError> {
@@ -260,6 +985,42 @@ impl Transaction {
             .get("pub_derived")
             .and_then(|x| x.as_str())
             .map(String::from);
+        let deployment_salt = v
+            .get("deployment_salt")
+            .and_then(|x| x.as_str())
+            .map(String::from);
+        let valid_until = match v.get("valid_until") {
+            Some(h) if !h.is_null() => Some(
+                h.as_u64()
+                    .ok_or_else(|| PlatariumError::Signature("invalid valid_until: expected block height".into()))?,
+            ),
+            _ => None,
+        };
+        let chain_id = v
+            .get("chain_id")
+            .and_then(|x| x.as_str())
+            .map(String::from);
+        let kind = match v.get("kind") {
+            Some(k) if !k.is_null() => serde_json::from_value(k.clone())
+                .map_err(|e| PlatariumError::Signature(format!("invalid kind: {}", e)))?,
+            _ => TxKind::Transfer,
+        };
+        let sig_scheme = match v.get("sig_scheme").and_then(|x| x.as_str()) {
+            Some(name) => SignatureScheme::parse(name)?,
+            None => SignatureScheme::Ecdsa,
+        };
+        let multisig = match v.get("multisig") {
+            Some(w) if !w.is_null() => Some(
+                serde_json::from_value(w.clone())
+                    .map_err(|e| PlatariumError::Signature(format!("invalid multisig witness: {}", e)))?,
+            ),
+            _ => None,
+        };
+        let version = match v.get("version") {
+            Some(x) if !x.is_null() => serde_json::from_value(x.clone())
+                .map_err(|e| PlatariumError::Signature(format!("invalid version: {}", e)))?,
+            _ => TxVersion::V1,
+        };
         Ok(Self {
             hash,
             from,
@@ -274,11 +1035,197 @@ impl Transaction {
             sig_derived,
             pub_main,
             pub_derived,
+            kind,
+            deployment_salt,
+            valid_until,
+            chain_id,
+            sig_scheme,
+            multisig,
+            version,
         })
     }
 }
 
-fn parse_u128_json(v: &serde_json::Value) -> std::result::Result<u128, String> {
+/// Builds and signs a [`Transaction`] without reproducing its hash layout by hand. `reads`/`writes` default to
+/// [`Transaction::access_set`]; the hash is computed over the final kind, deployment salt, expiry and chain ID before signing. New
+/// transactions hash their binary encoding (`TxVersion::V2`) unless [`version`](Self::version) says otherwise.
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    from: String,
+    to: String,
+    asset: Asset,
+    amount: u128,
+    fee_uplp: u128,
+    nonce: u64,
+    reads: Option<HashSet<String>>,
+    writes: Option<HashSet<String>>,
+    kind: TxKind,
+    deployment_salt: Option<String>,
+    valid_until: Option<u64>,
+    chain_id: Option<String>,
+    version: TxVersion,
+}
+
+impl TransactionBuilder {
+    pub fn new(
+        from: impl Into<String>,
+        to: impl Into<String>,
+        asset: Asset,
+        amount: u128,
+        fee_uplp: u128,
+        nonce: u64,
+    ) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            asset,
+            amount,
+            fee_uplp,
+            nonce,
+            reads: None,
+            writes: None,
+            kind: TxKind::Transfer,
+            deployment_salt: None,
+            valid_until: None,
+            chain_id: None,
+            version: TxVersion::V2,
+        }
+    }
+
+    /// Overrides the declared read set.
+    pub fn reads(mut self, reads: HashSet<String>) -> Self {
+        self.reads = Some(reads);
+        self
+    }
+
+    /// Overrides the declared write set.
+    pub fn writes(mut self, writes: HashSet<String>) -> Self {
+        self.writes = Some(writes);
+        self
+    }
+
+    pub fn kind(mut self, kind: TxKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn deployment_salt(mut self, salt: impl Into<String>) -> Self {
+        self.deployment_salt = Some(salt.into());
+        self
+    }
+
+    /// Last block height the transaction may be executed at.
+    pub fn valid_until(mut self, height: u64) -> Self {
+        self.valid_until = Some(height);
+        self
+    }
+
+    /// Binds the transaction to `network` by its chain ID. Without it, the transaction is valid on mainnet only.
+    pub fn network(mut self, network: &NetworkId) -> Self {
+        self.chain_id = Some(network.chain_id().to_string());
+        self
+    }
+
+    pub fn version(mut self, version: TxVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// The transaction with its hash computed and no signatures.
+    pub fn build_unsigned(self) -> Result<Transaction> {
+        let from = address::normalize(&self.from)?;
+        let to = address::normalize(&self.to)?;
+        let mut tx = Transaction::new(from, to, self.asset, self.amount, self.fee_uplp, self.nonce, HashSet::new(), HashSet::new(), String::new(), String::new())?
+            .with_kind(self.kind)?
+            .with_deployment_salt(self.deployment_salt)?
+            .with_valid_until(self.valid_until)?
+            .with_chain_id(self.chain_id)?
+            .with_version(self.version)?;
+        tx.reads = self.reads.unwrap_or_else(|| tx.access_set());
+        tx.writes = self.writes.unwrap_or_else(|| tx.access_set());
+        tx.hash = tx.compute_hash()?;
+        Ok(tx)
+    }
+
+    /// Builds the transaction and signs it with the main and derived signers.
+    pub fn sign_with(self, main: &dyn Signer, derived: &dyn Signer) -> Result<Transaction> {
+        let mut tx = self.build_unsigned()?;
+        crate::signer::sign_transaction(&mut tx, main, derived)?;
+        Ok(tx)
+    }
+
+    /// Signs with a generated key pair: `private_key` as the main key and `signature_key` as the derived key.
+    pub fn sign_with_key_pair(self, keys: &KeyPair) -> Result<Transaction> {
+        let main = SoftwareSigner::from_private_key_str(&keys.private_key)?;
+        let derived = SoftwareSigner::from_private_key_str(&keys.signature_key)?;
+        self.sign_with(&main, &derived)
+    }
+
+    /// Signs with the main and HKDF keys derived from a mnemonic and alphanumeric part (see [`software_signers`]).
+    pub fn sign_with_mnemonic(self, mnemonic: &str, alphanumeric_part: &str) -> Result<Transaction> {
+        let (main, derived) = software_signers(mnemonic, alphanumeric_part)?;
+        self.sign_with(&main, &derived)
+    }
+}
+
+/// Checks the outputs of a `MultiTransfer`: 1 to [`MAX_TRANSFER_OUTPUTS`] of them, each with a canonical key
+/// address and a nonzero amount.
+fn check_outputs(outputs: &[TransferOutput]) -> ValidationResult {
+    if outputs.is_empty() || outputs.len() > MAX_TRANSFER_OUTPUTS {
+        return Err(TransactionValidationError::InvalidOutputs(format!(
+            "expected 1 to {} outputs, got {}",
+            MAX_TRANSFER_OUTPUTS,
+            outputs.len()
+        )));
+    }
+    for output in outputs {
+        address::check(&output.to).map_err(|reason| TransactionValidationError::InvalidAddress {
+            address: output.to.clone(),
+            reason,
+        })?;
+        if output.amount == 0 {
+            return Err(TransactionValidationError::InvalidOutputs(format!("zero amount to {}", output.to)));
+        }
+    }
+    Ok(())
+}
+
+/// Compares two hex public keys, ignoring a `Px` prefix and hex case.
+pub fn same_public_key(a: &str, b: &str) -> bool {
+    let strip = |k: &str| k.strip_prefix("Px").unwrap_or(k).to_ascii_lowercase();
+    strip(a) == strip(b)
+}
+
+/// Type and range check of one `from_json` field, so errors name the field.
+fn check_json_field(name: &str, value: &serde_json::Value) -> ValidationResult {
+    fn decode<T: serde::de::DeserializeOwned>(value: &serde_json::Value) -> std::result::Result<(), String> {
+        T::deserialize(value).map(|_| ()).map_err(|e| e.to_string())
+    }
+    let checked = match name {
+        "amount" | "fee_uplp" => match value.as_number() {
+            Some(n) if n.is_u64() => Ok(()),
+            // Integers above u64::MAX parse as f64 here; the typed decode checks them exactly.
+            Some(n) if n.is_f64() && n.as_f64().is_some_and(|f| f.fract() == 0.0 && f > 0.0) => Ok(()),
+            _ => Err("expected a non-negative integer".to_string()),
+        },
+        "nonce" => decode::<u64>(value),
+        "valid_until" => decode::<Option<u64>>(value),
+        "reads" | "writes" => decode::<HashSet<String>>(value),
+        "asset" => decode::<Asset>(value),
+        "kind" => decode::<TxKind>(value),
+        "sig_scheme" => decode::<SignatureScheme>(value),
+        "multisig" => decode::<Option<MultisigWitness>>(value),
+        "version" => decode::<TxVersion>(value),
+        "pub_main" | "pub_derived" | "deployment_salt" | "chain_id" => decode::<Option<String>>(value),
+        _ => decode::<String>(value),
+    };
+    checked.map_err(|reason| TransactionValidationError::InvalidField {
+        field: name.to_string(),
+        reason,
+    })
+}
+
+pub(crate) fn parse_u128_json(v: &serde_json::Value) -> std::result::Result<u128, String> {
     if v.is_null() {
         return Err("missing value".into());
     }
@@ -312,6 +1259,31 @@ fn parse_u128_json(v: &serde_json::Value) -> std::result::Result<u128, String> {
 mod tests {
     use super::*;
     use crate::core::asset::Asset;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn test_derived_key_required_and_distinct() {
+        let mut tx = Transaction::new(
+            "sender".to_string(),
+            "receiver".to_string(),
+            Asset::PLP,
+            100,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            "sig".to_string(),
+            "sig".to_string(),
+        )
+        .unwrap();
+        tx.pub_main = Some("Px02aa".to_string());
+        let err = tx.verify_signatures().unwrap_err();
+        assert!(err.to_string().contains("missing derived public key"));
+        tx.pub_derived = Some("02AA".to_string());
+        let err = tx.verify_signatures().unwrap_err();
+        assert!(err.to_string().contains("must differ"));
+        assert!(matches!(tx.validate_basic(), Err(TransactionValidationError::InvalidSignature(_))));
+    }
 
     #[test]
     fn test_transaction_creation() {
@@ -388,6 +1360,98 @@ mod tests {
         assert_eq!(tx1.hash, tx2.hash);
     }
 
+    #[test]
+    fn test_signing_payload_is_hash_preimage() {
+        let tx = Transaction::new(
+            "sender".to_string(),
+            "receiver".to_string(),
+            Asset::PLP,
+            100,
+            1,
+            0,
+            HashSet::from(["b".to_string(), "a".to_string()]),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap();
+        let payload = tx.signing_payload();
+        assert_eq!(
+            String::from_utf8(payload.clone()).unwrap(),
+            r#"PlatariumSignature:{"from":"sender","to":"receiver","asset":"PLP","amount":100,"fee_uplp":1,"nonce":0,"reads":["a","b"],"writes":[]}"#
+        );
+        assert_eq!(hex::encode(Sha256::digest(&payload)), tx.hash);
+    }
+
+    #[test]
+    fn test_signing_payload_shared_by_hash_and_signatures() {
+        let (mnemonic, alphanumeric) = crate::mnemonic::generate_mnemonic().unwrap();
+        let reads = HashSet::from(["k2".to_string(), "k1".to_string()]);
+        let payload = SigningPayload::new("sender", "receiver", &Asset::Token("USD".into()), 9, 2, 4).reads(&reads);
+        let signed = crate::signer::sign_with_both_keys(&payload, &mnemonic, &alphanumeric).unwrap();
+
+        let mut tx = Transaction::new(
+            "sender".to_string(),
+            "receiver".to_string(),
+            Asset::Token("USD".into()),
+            9,
+            2,
+            4,
+            reads.clone(),
+            HashSet::new(),
+            signed.signatures[0].signature_compact.clone(),
+            signed.signatures[1].signature_compact.clone(),
+        )
+        .unwrap();
+        tx.pub_main = Some(signed.signatures[0].pub_key.clone());
+        tx.pub_derived = Some(signed.signatures[1].pub_key.clone());
+
+        assert_eq!(SigningPayload::from_transaction(&tx).bytes(), payload.bytes());
+        assert_eq!(tx.signing_payload(), payload.bytes());
+        assert_eq!(tx.hash, signed.hash);
+        assert_eq!(tx.hash, hex::encode(payload.hash()));
+        assert!(tx.verify_signatures().unwrap());
+
+        let approve = tx.clone().with_kind(TxKind::Approve).unwrap();
+        assert_ne!(approve.hash, tx.hash);
+        assert_eq!(approve.signing_payload(), payload.clone().kind(&TxKind::Approve).bytes());
+    }
+
+    #[test]
+    fn test_schnorr_signed_transaction() {
+        let main = secp256k1::SecretKey::from_slice(&[3; 32]).unwrap();
+        let derived = secp256k1::SecretKey::from_slice(&[4; 32]).unwrap();
+        let mut tx = Transaction::new(
+            "sender".to_string(),
+            "receiver".to_string(),
+            Asset::PLP,
+            10,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap();
+        let payload = SigningPayload::from_transaction(&tx);
+        let sig_main = crate::signature::sign_message_schnorr(&main, &payload).unwrap();
+        let sig_derived = crate::signature::sign_message_schnorr(&derived, &payload).unwrap();
+        tx.sig_main = sig_main.signature_compact;
+        tx.sig_derived = sig_derived.signature_compact;
+        tx.pub_main = Some(sig_main.pub_key);
+        tx.pub_derived = Some(sig_derived.pub_key);
+
+        assert!(!matches!(tx.verify_signatures(), Ok(true)));
+        tx.sig_scheme = SignatureScheme::Schnorr;
+        assert!(tx.verify_signatures().unwrap());
+        assert_eq!(tx.compute_hash().unwrap(), tx.hash);
+
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(json["sig_scheme"], "schnorr");
+        assert!(Transaction::from_gateway_json(&json.to_string()).unwrap().verify_signatures().unwrap());
+    }
+
     #[test]
     fn test_validate_basic_valid() {
         let tx = Transaction::new(
@@ -434,6 +1498,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_freeze_kind_hash_and_amount() {
+        let token = Asset::Token("USDT".to_string());
+        let transfer = Transaction::new(
+            "issuer".to_string(),
+            "issuer".to_string(),
+            token,
+            0,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            "sig_main".to_string(),
+            "sig_derived".to_string(),
+        )
+        .unwrap();
+        let freeze = transfer.clone().with_kind(TxKind::FreezeAsset).unwrap();
+        assert_ne!(freeze.hash, transfer.hash);
+        assert!(freeze.validate_hash().unwrap());
+        // Zero amount is fine for freeze kinds (fails later on the dummy signatures).
+        assert!(matches!(
+            freeze.validate_basic(),
+            Err(TransactionValidationError::InvalidSignature(_))
+        ));
+        let mut with_amount = freeze.clone();
+        with_amount.amount = 5;
+        assert_eq!(
+            with_amount.validate_basic(),
+            Err(TransactionValidationError::UnexpectedAmount(TxKind::FreezeAsset))
+        );
+        let json = serde_json::to_string(&transfer).unwrap();
+        assert!(!json.contains("kind"));
+        let round: Transaction = serde_json::from_str(&serde_json::to_string(&freeze).unwrap()).unwrap();
+        assert_eq!(round.kind, TxKind::FreezeAsset);
+    }
+
     #[test]
     fn test_validate_basic_invalid_fee_too_low() {
         let tx = Transaction::new(
@@ -499,4 +1599,173 @@ mod tests {
         assert_eq!(tx.amount, 1_000_000);
         assert_eq!(tx.fee_uplp, 1);
     }
+
+    #[test]
+    fn test_checksummed_addresses_are_canonicalized() {
+        use secp256k1::{PublicKey, Secp256k1, SecretKey};
+        let key = PublicKey::from_secret_key(&Secp256k1::new(), &SecretKey::from_slice(&[3u8; 32]).unwrap());
+        let canonical = address::address_from_public_key(&key);
+        let checksummed = address::encode(&canonical).unwrap();
+        let new_tx = |to: &str| {
+            Transaction::new(
+                "sender".to_string(),
+                to.to_string(),
+                Asset::PLP,
+                5,
+                1,
+                0,
+                HashSet::new(),
+                HashSet::new(),
+                String::new(),
+                String::new(),
+            )
+        };
+        let tx = new_tx(&checksummed).unwrap();
+        assert_eq!(tx.to, canonical);
+        assert_eq!(tx.hash, new_tx(&canonical).unwrap().hash);
+
+        let pos = checksummed[2..].find(|c: char| c.is_ascii_uppercase()).unwrap() + 2;
+        let typo = format!("{}{}{}", &checksummed[..pos], checksummed[pos..=pos].to_ascii_lowercase(), &checksummed[pos + 1..]);
+        assert!(new_tx(&typo).is_err());
+
+        let mut tx = tx;
+        tx.to = checksummed;
+        assert!(matches!(
+            tx.validate_basic(),
+            Err(TransactionValidationError::InvalidAddress { reason: AddressError::NotCanonical(_), .. })
+        ));
+    }
+
+    #[test]
+    fn test_builder_signs_valid_transaction() {
+        let keys = crate::key_generator::KeyGenerator::new(0, None, None, None).unwrap().generate_keys().unwrap();
+        let tx = TransactionBuilder::new(keys.public_key.clone(), "receiver", Asset::PLP, 100, MIN_FEE_UPLP, 3)
+            .sign_with_key_pair(&keys)
+            .unwrap();
+        assert!(tx.validate_hash().unwrap());
+        assert!(tx.validate_basic().is_ok());
+        let touched: HashSet<String> = [tx.from.clone(), "receiver".to_string()].into_iter().collect();
+        assert_eq!(tx.reads, touched);
+        assert_eq!(tx.writes, touched);
+
+        let salted = TransactionBuilder::new(keys.public_key.clone(), "receiver", Asset::PLP, 100, MIN_FEE_UPLP, 3)
+            .deployment_salt("salt")
+            .sign_with_key_pair(&keys)
+            .unwrap();
+        assert_ne!(salted.hash, tx.hash);
+        assert!(salted.validate_basic().is_ok());
+    }
+
+    #[test]
+    fn test_from_json_is_strict() {
+        let keys = crate::key_generator::KeyGenerator::new(0, None, None, None).unwrap().generate_keys().unwrap();
+        let builder = |amount| TransactionBuilder::new(keys.public_key.clone(), "receiver", Asset::PLP, amount, MIN_FEE_UPLP, 0);
+        let large = builder(u64::MAX as u128 + 1).sign_with_key_pair(&keys).unwrap();
+        assert_eq!(Transaction::from_json(&serde_json::to_string(&large).unwrap()).unwrap(), large);
+
+        let tx = builder(100).build_unsigned().unwrap();
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(Transaction::from_json(&json.to_string()).unwrap(), tx);
+
+        let with = |field: &str, value: serde_json::Value| {
+            let mut v = json.clone();
+            v[field] = value;
+            Transaction::from_json(&v.to_string()).unwrap_err()
+        };
+        assert_eq!(with("memo", "hi".into()), TransactionValidationError::UnknownField("memo".into()));
+        assert!(matches!(with("nonce", (-1).into()), TransactionValidationError::InvalidField { field, .. } if field == "nonce"));
+        assert!(matches!(with("fee_uplp", 1.5.into()), TransactionValidationError::InvalidField { field, .. } if field == "fee_uplp"));
+        assert!(matches!(with("fee_uplp", 0.into()), TransactionValidationError::InvalidFee(..)));
+        assert!(matches!(with("to", format!("Px{}", tx.from[2..].to_ascii_uppercase()).into()), TransactionValidationError::InvalidAddress { .. }));
+        assert!(matches!(with("hash", "00".into()), TransactionValidationError::HashMismatch(expected, got) if expected == tx.hash && got == "00"));
+
+        let mut v = json.clone();
+        v.as_object_mut().unwrap().remove("sig_main");
+        assert_eq!(Transaction::from_json(&v.to_string()).unwrap_err(), TransactionValidationError::MissingField("sig_main".into()));
+        assert!(matches!(Transaction::from_json("[]"), Err(TransactionValidationError::MalformedJson(_))));
+    }
+
+    #[test]
+    fn test_two_phase_signing() {
+        let keys = crate::key_generator::KeyGenerator::new(0, None, None, None).unwrap().generate_keys().unwrap();
+        let main = SoftwareSigner::from_private_key_str(&keys.private_key).unwrap();
+        let derived = SoftwareSigner::from_private_key_str(&keys.signature_key).unwrap();
+        let mut tx = TransactionBuilder::new(keys.public_key.clone(), "receiver", Asset::PLP, 100, MIN_FEE_UPLP, 0)
+            .build_unsigned()
+            .unwrap();
+
+        // The derived key signs elsewhere; its signature travels as a string.
+        let carried = tx.sign_role(SignerRole::Derived, &derived).unwrap().encode();
+        tx.sign_main(&main).unwrap();
+        assert!(!tx.is_fully_signed());
+        let derived_sig = RoleSignature::decode(&carried).unwrap();
+        tx.attach_signature(&derived_sig).unwrap();
+        assert!(tx.is_fully_signed());
+
+        let mut other = tx.clone().with_valid_until(Some(9)).unwrap();
+        assert!(other.attach_signature(&derived_sig).is_err());
+        let mut forged = derived_sig;
+        forged.role = SignerRole::Main;
+        forged.signature.pub_key = tx.pub_main.clone().unwrap();
+        assert!(tx.attach_signature(&forged).is_err());
+    }
+
+    #[test]
+    fn test_chain_id_binds_network() {
+        let keys = crate::key_generator::KeyGenerator::new(0, None, None, None).unwrap().generate_keys().unwrap();
+        let builder = TransactionBuilder::new(keys.public_key.clone(), "receiver", Asset::PLP, 100, MIN_FEE_UPLP, 0);
+        let legacy = builder.clone().sign_with_key_pair(&keys).unwrap();
+        let testnet = builder.network(&NetworkId::Testnet).sign_with_key_pair(&keys).unwrap();
+        assert_ne!(legacy.hash, testnet.hash);
+
+        assert!(legacy.validate_basic_for(&NetworkId::Mainnet).is_ok());
+        assert!(testnet.validate_basic_for(&NetworkId::Testnet).is_ok());
+        assert!(matches!(
+            testnet.validate_basic_for(&NetworkId::Mainnet),
+            Err(TransactionValidationError::ChainIdMismatch { .. })
+        ));
+        assert!(legacy.check_chain_id(&NetworkId::Testnet).is_err());
+
+        let mut relabeled = testnet.clone();
+        relabeled.chain_id = Some(NetworkId::Mainnet.chain_id().to_string());
+        assert!(relabeled.validate_basic_for(&NetworkId::Mainnet).is_err());
+    }
+
+    #[test]
+    fn test_binary_hash_and_wire_round_trip() {
+        let keys = crate::key_generator::KeyGenerator::new(0, None, None, None).unwrap().generate_keys().unwrap();
+        let builder = TransactionBuilder::new(keys.public_key.clone(), "receiver", Asset::Token("USD".into()), 100, MIN_FEE_UPLP, 1)
+            .kind(TxKind::BridgeClaim {
+                claim_id: "c1".into(),
+                proof: Box::new(ForeignHeaderProof {
+                    chain_id: "eth".into(),
+                    height: 7,
+                    claims_root: "ab".into(),
+                    siblings: vec!["cd".into()],
+                    finality: String::new(),
+                }),
+            })
+            .valid_until(50);
+        let tx = builder.clone().sign_with_key_pair(&keys).unwrap();
+        assert_eq!(tx.version, TxVersion::V2);
+        assert!(tx.signing_payload().starts_with(b"PlatariumTx/v2:"));
+        assert!(tx.validate_basic().is_ok());
+        let v1 = builder.version(TxVersion::V1).sign_with_key_pair(&keys).unwrap();
+        assert_ne!(v1.hash, tx.hash);
+        assert!(v1.validate_basic().is_ok());
+
+        for tx in [&tx, &v1] {
+            let bytes = tx.to_wire_bytes();
+            assert_eq!(tx.encoded_size(), bytes.len());
+            assert_eq!(&Transaction::from_wire_bytes(&bytes).unwrap(), tx);
+            let mut stream = platarium_consensus::frame(&bytes);
+            stream.push(0);
+            let (payload, rest) = platarium_consensus::unframe(&stream).unwrap();
+            assert_eq!((payload, rest), (&bytes[..], &[0u8][..]));
+            assert!(Transaction::from_wire_bytes(&stream[4..]).is_err());
+        }
+        // JSON keeps the version, so a V2 transaction survives a round trip through the RPC format.
+        let json: Transaction = serde_json::from_value(serde_json::to_value(&tx).unwrap()).unwrap();
+        assert!(json.validate_hash().unwrap());
+    }
 }
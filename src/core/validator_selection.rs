@@ -33,7 +33,8 @@
 
 use std::collections::HashSet;
 use sha2::{Sha256, Digest};
-use crate::core::node_registry::{Node, NodeId, NodeRegistry};
+use crate::core::node_registry::{NodeId, NodeRegistry, NodeSummary};
+use crate::core::ordering::{self, NodeKey};
 use crate::error::{PlatariumError, Result};
 use thiserror::Error;
 
@@ -181,9 +182,9 @@ pub fn select_validators_l2(
 ) -> Result<Vec<NodeId>> {
     let percent = selection_percent_from_load_l2(current_tps, system_capacity)?;
     let exclude_set: HashSet<_> = exclude.iter().cloned().collect();
-    let eligible: Vec<Node> = registry
-        .get_eligible()
-        .into_iter()
+    let summaries = registry.eligible_summaries();
+    let eligible: Vec<&NodeSummary> = summaries
+        .iter()
         .filter(|n| !exclude_set.contains(&n.node_id))
         .collect();
     let count = select_count(eligible.len(), percent);
@@ -194,15 +195,12 @@ pub fn select_validators_l2(
 
     let mut weighted: Vec<WeightedNode> = eligible
         .into_iter()
-        .map(|n| {
-            let w = n.selection_weight_ratio().max(1);
-            WeightedNode {
-                node_id: n.node_id,
-                weight: w,
-            }
+        .map(|n| WeightedNode {
+            node_id: n.node_id.clone(),
+            weight: n.weight.max(1),
         })
         .collect();
-    weighted.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+    weighted.sort_by(ordering::by_node_id);
 
     let total_weight: u64 = weighted.iter().map(|w| w.weight).sum();
     if total_weight == 0 {
@@ -245,6 +243,12 @@ struct WeightedNode {
     weight: u64,
 }
 
+impl NodeKey for WeightedNode {
+    fn node_id(&self) -> &str {
+        &self.node_id
+    }
+}
+
 /// Gateway API: select n node ids from (node_id, weight) pairs using Core's deterministic weighted selection.
 /// Seed is 32 bytes. Used by Go Gateway so committee selection lives in Core.
 pub fn select_n_by_weight(
@@ -319,7 +323,7 @@ pub fn select_validators_with_percent(
     seed: &[u8; 32],
     percent: u64,
 ) -> Result<Vec<NodeId>> {
-    let eligible = registry.get_eligible();
+    let eligible = registry.eligible_summaries();
     let count = select_count(eligible.len(), percent);
 
     if count == 0 || eligible.is_empty() {
@@ -327,16 +331,13 @@ pub fn select_validators_with_percent(
     }
 
     let mut weighted: Vec<WeightedNode> = eligible
-        .into_iter()
-        .map(|n| {
-            let w = n.selection_weight_ratio().max(1);
-            WeightedNode {
-                node_id: n.node_id,
-                weight: w,
-            }
+        .iter()
+        .map(|n| WeightedNode {
+            node_id: n.node_id.clone(),
+            weight: n.weight.max(1),
         })
         .collect();
-    weighted.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+    weighted.sort_by(ordering::by_node_id);
 
     let total_weight: u64 = weighted.iter().map(|w| w.weight).sum();
     if total_weight == 0 {
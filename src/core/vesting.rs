@@ -0,0 +1,117 @@
+//! Cliff + linear vesting schedules for genesis allocations.
+//!
+//! A schedule locks part of an address's PLP balance: nothing vests before `start_height + cliff_blocks`, then the allocation vests linearly until `start_height + duration_blocks`, after which it is fully vested. Unvested PLP stays visible in the balance but cannot be spent (transfers or fees); this is enforced by `ExecutionLogic::check_transaction_applicability` and `State::apply_transfer` at the state's current block height.
+//!
+//! Integer arithmetic only; vested amounts round down.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::error::PlatariumError;
+
+/// Errors produced by vesting schedule validation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VestingError {
+    #[error("Vesting duration must be greater than 0")]
+    ZeroDuration,
+
+    #[error("Cliff ({cliff}) exceeds duration ({duration})")]
+    CliffExceedsDuration { cliff: u64, duration: u64 },
+}
+
+impl From<VestingError> for PlatariumError {
+    fn from(err: VestingError) -> Self {
+        PlatariumError::State(format!("Vesting error: {}", err))
+    }
+}
+
+/// Vesting schedule for one address, in blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VestingSchedule {
+    /// Total PLP (minimal units) subject to vesting.
+    pub total: u128,
+    /// Height at which vesting starts.
+    pub start_height: u64,
+    /// Blocks after `start_height` before anything vests.
+    pub cliff_blocks: u64,
+    /// Blocks after `start_height` until the allocation is fully vested.
+    pub duration_blocks: u64,
+}
+
+impl VestingSchedule {
+    /// Validates the schedule: duration > 0 and cliff <= duration.
+    pub fn validate(&self) -> Result<(), VestingError> {
+        if self.duration_blocks == 0 {
+            return Err(VestingError::ZeroDuration);
+        }
+        if self.cliff_blocks > self.duration_blocks {
+            return Err(VestingError::CliffExceedsDuration {
+                cliff: self.cliff_blocks,
+                duration: self.duration_blocks,
+            });
+        }
+        Ok(())
+    }
+
+    /// Amount vested at `height`.
+    pub fn vested_at(&self, height: u64) -> u128 {
+        let elapsed = height.saturating_sub(self.start_height);
+        if elapsed < self.cliff_blocks {
+            return 0;
+        }
+        if elapsed >= self.duration_blocks {
+            return self.total;
+        }
+        self.total * elapsed as u128 / self.duration_blocks as u128
+    }
+
+    /// Amount still locked at `height`.
+    pub fn locked_at(&self, height: u64) -> u128 {
+        self.total - self.vested_at(height)
+    }
+}
+
+/// Genesis entry assigning a vesting schedule to an address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisVesting {
+    pub address: String,
+    pub schedule: VestingSchedule,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> VestingSchedule {
+        VestingSchedule {
+            total: 1_000,
+            start_height: 100,
+            cliff_blocks: 10,
+            duration_blocks: 40,
+        }
+    }
+
+    #[test]
+    fn test_cliff_then_linear() {
+        let s = schedule();
+        assert_eq!(s.vested_at(0), 0);
+        assert_eq!(s.vested_at(109), 0);
+        assert_eq!(s.vested_at(110), 250);
+        assert_eq!(s.vested_at(120), 500);
+        assert_eq!(s.vested_at(139), 975);
+        assert_eq!(s.vested_at(140), 1_000);
+        assert_eq!(s.vested_at(u64::MAX), 1_000);
+        assert_eq!(s.locked_at(120), 500);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(schedule().validate().is_ok());
+        let mut s = schedule();
+        s.duration_blocks = 0;
+        s.cliff_blocks = 0;
+        assert_eq!(s.validate(), Err(VestingError::ZeroDuration));
+        let mut s = schedule();
+        s.cliff_blocks = 41;
+        assert!(matches!(s.validate(), Err(VestingError::CliffExceedsDuration { .. })));
+    }
+}
@@ -1,6 +1,7 @@
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum PlatariumError {
     #[error("Validation error: {0}")]
     Validation(String),
@@ -26,6 +27,21 @@ pub enum PlatariumError {
 
 pub type Result<T> = std::result::Result<T, PlatariumError>;
 
+impl PlatariumError {
+    /// Stable snake_case name of the error category, e.g. for receipts.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PlatariumError::Validation(_) => "validation",
+            PlatariumError::Crypto(_) => "crypto",
+            PlatariumError::Bip39(_) => "bip39",
+            PlatariumError::Bip32(_) => "bip32",
+            PlatariumError::Signature(_) => "signature",
+            PlatariumError::KeyDerivation(_) => "key_derivation",
+            PlatariumError::State(_) => "state",
+        }
+    }
+}
+
 impl From<bip39::Error> for PlatariumError {
     fn from(err: bip39::Error) -> Self {
         PlatariumError::Bip39(err.to_string())
@@ -38,6 +54,12 @@ impl From<secp256k1::Error> for PlatariumError {
     }
 }
 
+impl From<platarium_consensus::WireError> for PlatariumError {
+    fn from(err: platarium_consensus::WireError) -> Self {
+        PlatariumError::Validation(format!("malformed encoding: {}", err))
+    }
+}
+
 impl From<bip32::Error> for PlatariumError {
     fn from(err: bip32::Error) -> Self {
         PlatariumError::Bip32(err.to_string())
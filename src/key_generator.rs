@@ -1,36 +1,38 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use bip39::{Language, Mnemonic};
-use bip32::{DerivationPath, XPrv};
+use std::fmt;
+use std::str::FromStr;
+use bip32::{ChildNumber, XPrv};
 use secp256k1::{Secp256k1, SecretKey, PublicKey};
-use rand::Rng;
+use serde::{Deserialize, Serialize};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+use zeroize::{Zeroize, Zeroizing};
+use crate::address::{address_from_public_key, ADDRESS_HEX_LEN, ADDRESS_PREFIX};
 use crate::error::{PlatariumError, Result};
-use crate::mnemonic::{generate_mnemonic, validate_mnemonic, CHARACTER_SET};
-use crate::utils::{bn_to_hex32, verify_correlation, HKDF_SALT, HKDF_INFO};
+use crate::signer::SoftwareSigner;
+use crate::mnemonic::{generate_mnemonic_in, validate_mnemonic_in, CharsetPolicy};
+use crate::utils::{bn_to_hex32, verify_correlation, NetworkId, HKDF_SALT, HKDF_INFO};
+use crate::watch_only::xpub_from_seed;
 
-/// Generates a random alphanumeric string of given length
+/// Generates a random alphanumeric string of given length from [`CHARACTER_SET`](crate::mnemonic::CHARACTER_SET), with no
+/// entropy minimum
 pub fn generate_alphanumeric_part(length: usize) -> Result<String> {
-    if length == 0 {
-        return Err(PlatariumError::Validation("length must be a positive integer".to_string()));
-    }
+    generate_alphanumeric_part_with(&CharsetPolicy { length, ..CharsetPolicy::default() }.with_min_entropy_bits(0.0))
+}
 
-    let mut rng = rand::thread_rng();
-    let chars: Vec<char> = CHARACTER_SET.chars().collect();
-    
-    let result: String = (0..length)
-        .map(|_| {
-            let idx = rng.gen_range(0..chars.len());
-            chars[idx]
-        })
-        .collect();
-    
-    Ok(result)
+/// Generates a random alphanumeric string under `policy` (length, charset, minimum entropy)
+pub fn generate_alphanumeric_part_with(policy: &CharsetPolicy) -> Result<String> {
+    policy.generate()
 }
 
 /// Re-export derive_signature_seed_from_master_seed from utils
 pub use crate::utils::derive_signature_seed_from_master_seed;
 
 
-/// Key generation result
-#[derive(Debug, Clone)]
+/// Key generation result. The mnemonic, alphanumeric part and private keys are wiped when it is dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct KeyPair {
     pub mnemonic: String,
     pub alphanumeric_part: String,
@@ -40,18 +42,220 @@ pub struct KeyPair {
     pub signature_key: String,
 }
 
-#[derive(Debug, Clone)]
+impl KeyPair {
+    /// Software signer for the main private key (`PSx...`).
+    pub fn main_signer(&self) -> Result<SoftwareSigner> {
+        let hex_key = self
+            .private_key
+            .strip_prefix("PSx")
+            .ok_or_else(|| PlatariumError::Validation("private key must start with PSx".to_string()))?;
+        let bytes = hex::decode(hex_key).map_err(|e| PlatariumError::Crypto(format!("Invalid private key: {}", e)))?;
+        let bytes = Zeroizing::new(bytes);
+        SoftwareSigner::from_slice(&bytes)
+    }
+}
+
+impl Drop for KeyPair {
+    fn drop(&mut self) {
+        self.mnemonic.zeroize();
+        self.alphanumeric_part.zeroize();
+        self.private_key.zeroize();
+        self.signature_key.zeroize();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DerivationPaths {
     pub main_path: String,
     pub signature_path: String,
 }
 
+/// One level of a [`DerivationPath`]. `index` must be below 2^31; hardening is a separate flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChildIndex {
+    pub index: u32,
+    pub hardened: bool,
+}
+
+impl ChildIndex {
+    pub const fn hardened(index: u32) -> Self {
+        ChildIndex { index, hardened: true }
+    }
+
+    pub const fn normal(index: u32) -> Self {
+        ChildIndex { index, hardened: false }
+    }
+
+    fn to_child_number(self) -> Result<ChildNumber> {
+        ChildNumber::new(self.index, self.hardened)
+            .map_err(|_| PlatariumError::Bip32(format!("Invalid derivation path: child index {} out of range", self.index)))
+    }
+}
+
+impl fmt::Display for ChildIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.index, if self.hardened { "'" } else { "" })
+    }
+}
+
+impl FromStr for ChildIndex {
+    type Err = PlatariumError;
+
+    /// `5` or `5'` (`5h` is accepted as well).
+    fn from_str(s: &str) -> Result<Self> {
+        let (digits, hardened) = match s.strip_suffix('\'').or_else(|| s.strip_suffix('h')) {
+            Some(digits) => (digits, true),
+            None => (s, false),
+        };
+        let index = digits
+            .parse::<u32>()
+            .ok()
+            .filter(|i| *i < ChildNumber::HARDENED_FLAG && !digits.starts_with('+'))
+            .ok_or_else(|| PlatariumError::Bip32(format!("Invalid derivation path: bad child index {:?}", s)))?;
+        Ok(ChildIndex { index, hardened })
+    }
+}
+
+/// BIP44 path `m/purpose/coin_type/account/change/address_index`. The default is `m/44'/60'/0'/0/0`, the first
+/// key below [`ACCOUNT_PATH`](crate::watch_only::ACCOUNT_PATH); the `with_*` methods replace one index and keep
+/// its hardening. Parsing accepts exactly five levels and `Display` prints the canonical form, so the two
+/// round-trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DerivationPath {
+    pub purpose: ChildIndex,
+    pub coin_type: ChildIndex,
+    pub account: ChildIndex,
+    pub change: ChildIndex,
+    pub address_index: ChildIndex,
+}
+
+impl Default for DerivationPath {
+    fn default() -> Self {
+        DerivationPath {
+            purpose: ChildIndex::hardened(44),
+            coin_type: ChildIndex::hardened(60),
+            account: ChildIndex::hardened(0),
+            change: ChildIndex::normal(0),
+            address_index: ChildIndex::normal(0),
+        }
+    }
+}
+
+impl DerivationPath {
+    pub fn with_purpose(mut self, index: u32) -> Self {
+        self.purpose.index = index;
+        self
+    }
+
+    pub fn with_coin_type(mut self, index: u32) -> Self {
+        self.coin_type.index = index;
+        self
+    }
+
+    pub fn with_account(mut self, index: u32) -> Self {
+        self.account.index = index;
+        self
+    }
+
+    pub fn with_change(mut self, index: u32) -> Self {
+        self.change.index = index;
+        self
+    }
+
+    pub fn with_address_index(mut self, index: u32) -> Self {
+        self.address_index.index = index;
+        self
+    }
+
+    fn levels(&self) -> [ChildIndex; 5] {
+        [self.purpose, self.coin_type, self.account, self.change, self.address_index]
+    }
+
+    /// Checks every index is below 2^31.
+    pub fn validate(&self) -> Result<()> {
+        self.levels().iter().try_for_each(|c| c.to_child_number().map(|_| ()))
+    }
+
+    /// The path as a `bip32` derivation path.
+    pub fn to_bip32(&self) -> Result<bip32::DerivationPath> {
+        let mut path = bip32::DerivationPath::default();
+        for level in self.levels() {
+            path.push(level.to_child_number()?);
+        }
+        Ok(path)
+    }
+
+    /// The same path with every level hardened.
+    pub fn all_hardened(mut self) -> Self {
+        for level in [
+            &mut self.purpose,
+            &mut self.coin_type,
+            &mut self.account,
+            &mut self.change,
+            &mut self.address_index,
+        ] {
+            level.hardened = true;
+        }
+        self
+    }
+
+    pub fn is_fully_hardened(&self) -> bool {
+        self.levels().iter().all(|c| c.hardened)
+    }
+
+    /// Parent of `address_index`, as a path string.
+    fn parent_path(&self) -> String {
+        format!("m/{}/{}/{}/{}", self.purpose, self.coin_type, self.account, self.change)
+    }
+
+    /// Parent of `address_index` (the node an xpub is exported from), as a path string. Requires a
+    /// non-hardened `address_index`, since its siblings could not be derived from the xpub otherwise.
+    fn chain_path(&self) -> Result<String> {
+        self.validate()?;
+        if self.address_index.hardened {
+            return Err(PlatariumError::Bip32(format!("Path {} does not end in a non-hardened child", self)));
+        }
+        Ok(self.parent_path())
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "m/{}/{}/{}/{}/{}",
+            self.purpose, self.coin_type, self.account, self.change, self.address_index
+        )
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = PlatariumError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || PlatariumError::Bip32(format!("Invalid derivation path: {:?} is not m/a/b/c/d/e", s));
+        let mut parts = s.trim().split('/');
+        if parts.next() != Some("m") {
+            return Err(invalid());
+        }
+        let levels = parts.map(ChildIndex::from_str).collect::<Result<Vec<_>>>()?;
+        match levels[..] {
+            [purpose, coin_type, account, change, address_index] => {
+                Ok(DerivationPath { purpose, coin_type, account, change, address_index })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
 /// Key generator using BIP32 + HKDF
 pub struct KeyGenerator {
     seed_index: u32,
     hkdf_salt: Vec<u8>,
     hkdf_info: Vec<u8>,
-    custom_path: Option<String>,
+    custom_path: Option<DerivationPath>,
+    language: Language,
+    hardened_only: bool,
 }
 
 impl KeyGenerator {
@@ -66,7 +270,7 @@ impl KeyGenerator {
         seed_index: u32,
         hkdf_salt: Option<&[u8]>,
         hkdf_info: Option<&[u8]>,
-        custom_path: Option<String>,
+        custom_path: Option<DerivationPath>,
     ) -> Result<Self> {
         const MAX_INDEX: u32 = 2u32.pow(31) - 1;
         
@@ -75,98 +279,284 @@ impl KeyGenerator {
                 format!("seedIndex must be in range [0, {}]", MAX_INDEX - 1),
             ));
         }
+        if let Some(path) = &custom_path {
+            path.validate()?;
+        }
 
         Ok(KeyGenerator {
             seed_index,
             hkdf_salt: hkdf_salt.unwrap_or(HKDF_SALT).to_vec(),
             hkdf_info: hkdf_info.unwrap_or(HKDF_INFO).to_vec(),
             custom_path,
+            language: Language::English,
+            hardened_only: false,
         })
     }
 
+    /// Sets the BIP39 wordlist used to generate, validate and restore mnemonics (default English)
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Uses the HKDF salt and info of `network` for the signature key, replacing any given to
+    /// [`KeyGenerator::new`]. Mainnet is the default domain; see [`NetworkId`].
+    pub fn with_network(mut self, network: &NetworkId) -> Self {
+        self.hkdf_salt = network.hkdf_salt();
+        self.hkdf_info = network.hkdf_info();
+        self
+    }
+
+    /// Hardened-only mode: the default path becomes `m/44'/60'/0'/0'/i'`, custom paths with a non-hardened
+    /// level are rejected and [`KeyGenerator::export_xpub`] is refused. With non-hardened children, an xpub
+    /// together with any one child private key yields the parent private key and so every sibling; hardened
+    /// children cannot be derived from public data, so one leaked key stays one leaked key. Keys differ from
+    /// the normal mode for the same mnemonic, so a wallet must be restored in the mode it was created in.
+    pub fn hardened_only(mut self) -> Self {
+        self.hardened_only = true;
+        self
+    }
+
+    pub fn is_hardened_only(&self) -> bool {
+        self.hardened_only
+    }
+
+    /// Exports the xpub of the account node for watch-only use: [`ACCOUNT_PATH`](crate::watch_only::ACCOUNT_PATH),
+    /// or the parent of the custom path, whose last step must then be non-hardened. `derive_public_keys(xpub, i)` gives the main public key
+    /// for child `i`. The mnemonic must be in the generator's language.
+    pub fn export_xpub(&self, mnemonic: &str, alphanumeric_part: &str) -> Result<String> {
+        if self.hardened_only {
+            return Err(PlatariumError::Bip32("xpub export is disabled in hardened-only mode".to_string()));
+        }
+        let seed = self.master_seed(mnemonic, alphanumeric_part)?;
+        xpub_from_seed(seed.as_slice(), &self.account_path()?)
+    }
+
     /// Generates new keys from a random mnemonic
     pub fn generate_keys(&self) -> Result<KeyPair> {
-        let (mnemonic, alphanumeric_part) = generate_mnemonic()?;
+        let (mnemonic, alphanumeric_part) = generate_mnemonic_in(self.language)?;
         
-        if !validate_mnemonic(&mnemonic) {
+        if !validate_mnemonic_in(&mnemonic, self.language) {
             return Err(PlatariumError::Validation(
                 "Generated mnemonic is not valid according to BIP39".to_string(),
             ));
         }
 
-        self.restore_keys(&mnemonic, &alphanumeric_part, self.seed_index, self.custom_path.clone())
+        self.restore_keys(&mnemonic, &alphanumeric_part, self.seed_index, self.custom_path)
     }
 
-    /// Restores keys from mnemonic and alphanumeric part
+    /// Restores keys from mnemonic and alphanumeric part; the mnemonic must be in the generator's language
     pub fn restore_keys(
         &self,
         mnemonic: &str,
         alphanumeric_part: &str,
         seed_index: u32,
-        custom_path: Option<String>,
+        custom_path: Option<DerivationPath>,
     ) -> Result<KeyPair> {
-        if !validate_mnemonic(mnemonic) {
+        let master_seed = self.master_seed(mnemonic, alphanumeric_part)?;
+        let main_path = match custom_path {
+            Some(path) => self.check_path(path)?,
+            None => self.default_path().with_address_index(seed_index),
+        };
+        let main_node = main_path
+            .to_bip32()?
+            .iter()
+            .try_fold(XPrv::new(master_seed.as_slice())?, |acc, child| acc.derive_child(child))?;
+        let signature_key_hex = self.signature_key_hex(&master_seed)?;
+        self.build_key_pair(
+            mnemonic,
+            alphanumeric_part,
+            main_path.to_string(),
+            &main_node,
+            &signature_key_hex,
+            &master_seed,
+        )
+    }
+
+    /// Restores the key pairs for seed indices `start_index..start_index + count` (children of the account
+    /// node, as in [`KeyGenerator::export_xpub`]). The mnemonic is parsed and stretched into a seed once, and
+    /// the account node and HKDF signature key are derived once, so each extra index costs one child derivation.
+    pub fn derive_range(
+        &self,
+        mnemonic: &str,
+        alphanumeric_part: &str,
+        start_index: u32,
+        count: u32,
+    ) -> Result<Vec<KeyPair>> {
+        let end = start_index
+            .checked_add(count)
+            .filter(|end| *end <= ChildNumber::HARDENED_FLAG)
+            .ok_or_else(|| {
+                PlatariumError::Validation(format!(
+                    "seed index range {}+{} exceeds {}",
+                    start_index,
+                    count,
+                    ChildNumber::HARDENED_FLAG - 1
+                ))
+            })?;
+        let master_seed = self.master_seed(mnemonic, alphanumeric_part)?;
+        let account_node = derive_path(XPrv::new(master_seed.as_slice())?, &self.account_path()?)?;
+        let base_path = self.base_path()?;
+        let signature_key_hex = self.signature_key_hex(&master_seed)?;
+        (start_index..end)
+            .map(|index| {
+                let node = account_node.derive_child(ChildNumber::new(index, self.hardened_only)?)?;
+                self.build_key_pair(
+                    mnemonic,
+                    alphanumeric_part,
+                    base_path.with_address_index(index).to_string(),
+                    &node,
+                    &signature_key_hex,
+                    &master_seed,
+                )
+            })
+            .collect()
+    }
+
+    /// BIP-85 child mnemonic of `word_count` words (12, 15, 18, 21 or 24) in the generator's language, at
+    /// `m/83696968'/39'/{language}'/{words}'/{index}'` below the master seed. Each index is an independent wallet
+    /// that can be restored from the parent mnemonic and alphanumeric part alone.
+    pub fn derive_child_mnemonic(
+        &self,
+        mnemonic: &str,
+        alphanumeric_part: &str,
+        word_count: usize,
+        index: u32,
+    ) -> Result<String> {
+        if ![12, 15, 18, 21, 24].contains(&word_count) {
+            return Err(PlatariumError::Validation(format!(
+                "word count must be 12, 15, 18, 21 or 24, got {}",
+                word_count
+            )));
+        }
+        let master_seed = self.master_seed(mnemonic, alphanumeric_part)?;
+        let path = format!(
+            "m/{}'/39'/{}'/{}'/{}'",
+            BIP85_PURPOSE,
+            bip85_language_code(self.language),
+            word_count,
+            index
+        );
+        let entropy = bip85_entropy(&XPrv::new(master_seed.as_slice())?, &path)?;
+        let child = Mnemonic::from_entropy_in(self.language, &entropy[..word_count * 4 / 3])?;
+        Ok(child.to_string())
+    }
+
+    /// BIP-85 hex entropy: `num_bytes` (16 to 64) bytes at `m/83696968'/128169'/{num_bytes}'/{index}'`.
+    pub fn derive_child_entropy(
+        &self,
+        mnemonic: &str,
+        alphanumeric_part: &str,
+        num_bytes: usize,
+        index: u32,
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        if !(16..=64).contains(&num_bytes) {
+            return Err(PlatariumError::Validation(format!(
+                "entropy length must be 16 to 64 bytes, got {}",
+                num_bytes
+            )));
+        }
+        let master_seed = self.master_seed(mnemonic, alphanumeric_part)?;
+        let path = format!("m/{}'/128169'/{}'/{}'", BIP85_PURPOSE, num_bytes, index);
+        let entropy = bip85_entropy(&XPrv::new(master_seed.as_slice())?, &path)?;
+        Ok(Zeroizing::new(entropy[..num_bytes].to_vec()))
+    }
+
+    /// BIP39 seed for a mnemonic in the generator's language.
+    fn master_seed(&self, mnemonic: &str, alphanumeric_part: &str) -> Result<Zeroizing<[u8; 64]>> {
+        if !validate_mnemonic_in(mnemonic, self.language) {
             return Err(PlatariumError::Validation(
                 "Provided mnemonic is not valid according to BIP39".to_string(),
             ));
         }
+        let mnemonic_obj = Mnemonic::parse_in(self.language, mnemonic)?;
+        Ok(Zeroizing::new(mnemonic_obj.to_seed(alphanumeric_part)))
+    }
 
-        self.build_keys_from_seed(mnemonic, alphanumeric_part, seed_index, custom_path)
+    /// [`ACCOUNT_PATH`](crate::watch_only::ACCOUNT_PATH), or the parent of the custom path if it ends in a
+    /// non-hardened child.
+    fn account_path(&self) -> Result<String> {
+        let base_path = self.base_path()?;
+        if self.hardened_only {
+            Ok(base_path.parent_path())
+        } else {
+            base_path.chain_path()
+        }
     }
 
-    fn build_keys_from_seed(
-        &self,
-        mnemonic: &str,
-        alphanumeric_part: &str,
-        seed_index: u32,
-        custom_path: Option<String>,
-    ) -> Result<KeyPair> {
-        let secp = Secp256k1::new();
-        
-        // Generate master seed from mnemonic
-        let mnemonic_obj = Mnemonic::parse_in_normalized(Language::English, mnemonic)?;
-        let master_seed = mnemonic_obj.to_seed(alphanumeric_part);
+    /// [`DerivationPath::default`], fully hardened in hardened-only mode.
+    fn default_path(&self) -> DerivationPath {
+        if self.hardened_only {
+            DerivationPath::default().all_hardened()
+        } else {
+            DerivationPath::default()
+        }
+    }
 
-        // Derive main key using BIP32
-        let root_xprv = XPrv::new(&master_seed)?;
-        
-        let main_path = custom_path.clone().unwrap_or_else(|| {
-            format!("m/44'/60'/0'/0/{}", seed_index)
-        });
-        
-        let derivation_path: DerivationPath = main_path.parse()
-            .map_err(|e| PlatariumError::Bip32(format!("Invalid derivation path: {}", e)))?;
-        
-        // Derive path by iterating through components
-        let main_node = derivation_path.iter().fold(Ok(root_xprv), |acc, child_num| {
-            acc?.derive_child(child_num)
-        })?;
-        let main_private_key = SecretKey::from_slice(&main_node.private_key().to_bytes())
-            .map_err(|e| PlatariumError::Crypto(format!("Invalid private key: {}", e)))?;
+    /// `path`, if valid and allowed by the hardened-only setting.
+    fn check_path(&self, path: DerivationPath) -> Result<DerivationPath> {
+        path.validate()?;
+        if self.hardened_only && !path.is_fully_hardened() {
+            return Err(PlatariumError::Bip32(format!(
+                "Path {} has non-hardened levels, which hardened-only mode rejects",
+                path
+            )));
+        }
+        Ok(path)
+    }
+
+    /// The custom path, or the default one.
+    fn base_path(&self) -> Result<DerivationPath> {
+        match self.custom_path {
+            Some(path) => self.check_path(path),
+            None => Ok(self.default_path()),
+        }
+    }
 
-        // Derive signature key using HKDF
+    /// HKDF signature key for the master seed, as 64 hex characters.
+    fn signature_key_hex(&self, master_seed: &[u8; 64]) -> Result<Zeroizing<String>> {
         let signature_seed = crate::utils::derive_signature_seed_from_master_seed(
-            &master_seed,
+            master_seed.as_slice(),
             Some(&self.hkdf_salt),
             Some(&self.hkdf_info),
         )?;
-        
-        let signature_private_key = SecretKey::from_slice(&signature_seed)
+        let mut signature_private_key = SecretKey::from_slice(signature_seed.as_slice())
             .map_err(|e| PlatariumError::Crypto(format!("Invalid signature key: {}", e)))?;
+        let signature_key_hex = Zeroizing::new(bn_to_hex32(Zeroizing::new(signature_private_key.secret_bytes()).as_slice())?);
+        signature_private_key.non_secure_erase();
+        Ok(signature_key_hex)
+    }
+
+    fn build_key_pair(
+        &self,
+        mnemonic: &str,
+        alphanumeric_part: &str,
+        main_path: String,
+        main_node: &XPrv,
+        signature_key_hex: &str,
+        master_seed: &[u8; 64],
+    ) -> Result<KeyPair> {
+        let secp = Secp256k1::new();
+        let main_key_bytes = Zeroizing::new(main_node.private_key().to_bytes());
+        let mut main_private_key = SecretKey::from_slice(main_key_bytes.as_slice())
+            .map_err(|e| PlatariumError::Crypto(format!("Invalid private key: {}", e)))?;
 
         // Get public keys
         let main_public_key = PublicKey::from_secret_key(&secp, &main_private_key);
-        let main_public_key_hex = hex::encode(main_public_key.serialize());
 
         // Format keys
-        let private_key_hex = bn_to_hex32(&main_private_key.secret_bytes())?;
-        let signature_key_hex = bn_to_hex32(&signature_private_key.secret_bytes())?;
+        let private_key_hex = Zeroizing::new(bn_to_hex32(Zeroizing::new(main_private_key.secret_bytes()).as_slice())?);
+        main_private_key.non_secure_erase();
 
         // Verify correlation
         let is_valid = verify_correlation(
             &private_key_hex,
-            &signature_key_hex,
-            &master_seed,
+            signature_key_hex,
+            master_seed.as_slice(),
             Some(&self.hkdf_salt),
             Some(&self.hkdf_info),
         )?;
@@ -181,16 +571,279 @@ impl KeyGenerator {
             mnemonic: mnemonic.to_string(),
             alphanumeric_part: alphanumeric_part.to_string(),
             derivation_paths: DerivationPaths {
-                main_path: main_path.clone(),
+                main_path,
                 signature_path: "HKDF-derived".to_string(),
             },
-            public_key: format!("Px{}", main_public_key_hex),
-            private_key: format!("PSx{}", private_key_hex),
+            public_key: address_from_public_key(&main_public_key),
+            private_key: format!("PSx{}", *private_key_hex),
             signature_key: format!("Sx{}", signature_key_hex),
         })
     }
 }
 
+/// Stops a running [`VanitySearch`] from another thread. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct VanityCancel(Arc<AtomicBool>);
+
+impl VanityCancel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Key whose address matched a vanity prefix. The private key is wiped when it is dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VanityMatch {
+    /// Seed index (derivation search) or key counter (ephemeral search) of the match.
+    pub index: u64,
+    /// Candidates checked across all threads before the search stopped.
+    pub attempts: u64,
+    /// `Px` address, as in `KeyPair::public_key`.
+    pub public_key: String,
+    /// `PSx`-prefixed main private key, as in `KeyPair::private_key`.
+    pub private_key: String,
+}
+
+impl Drop for VanityMatch {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+/// Searches for a key whose canonical address starts with a given prefix.
+///
+/// Candidates are numbered `0, 1, 2, ...` and split round-robin across threads; the search returns the
+/// lowest-numbered match, so the result for the same inputs does not depend on the thread count or on
+/// scheduling. Two candidate sources are supported:
+/// - [`VanitySearch::search_indices`]: seed indices of a mnemonic's account node, so the match is restored
+///   later with `restore_keys(.., index, ..)`;
+/// - [`VanitySearch::search_ephemeral`]: standalone keys `SHA256(seed || counter_be)`, reproducible from the
+///   32-byte seed.
+///
+/// Compressed public keys start with `02` or `03`, so every address starts with `Px02` or `Px03`; each further
+/// hex character multiplies the expected work by 16 (see [`VanitySearch::expected_attempts`]).
+#[derive(Debug, Clone)]
+pub struct VanitySearch {
+    prefix: String,
+    threads: usize,
+    max_attempts: u64,
+}
+
+impl VanitySearch {
+    /// Search for addresses starting with `prefix` (hex, with or without `Px`, any case). Uses all available
+    /// cores and no attempt limit by default.
+    pub fn new(prefix: &str) -> Result<Self> {
+        let body = prefix.strip_prefix(ADDRESS_PREFIX).unwrap_or(prefix).to_ascii_lowercase();
+        if body.is_empty() || body.len() > ADDRESS_HEX_LEN || !body.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(PlatariumError::Validation(format!(
+                "vanity prefix must be 1 to {} hex characters after {}",
+                ADDRESS_HEX_LEN, ADDRESS_PREFIX
+            )));
+        }
+        if !"02".starts_with(&body[..body.len().min(2)]) && !"03".starts_with(&body[..body.len().min(2)]) {
+            return Err(PlatariumError::Validation(format!(
+                "vanity prefix {} can never match: addresses start with {}02 or {}03",
+                prefix, ADDRESS_PREFIX, ADDRESS_PREFIX
+            )));
+        }
+        Ok(Self {
+            prefix: format!("{}{}", ADDRESS_PREFIX, body),
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            max_attempts: u64::MAX,
+        })
+    }
+
+    /// Worker threads (at least 1).
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Gives up after this many candidates.
+    pub fn max_attempts(mut self, max_attempts: u64) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Canonical prefix searched for, including `Px`.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// True if canonical `address` starts with the prefix.
+    pub fn matches(&self, address: &str) -> bool {
+        address.starts_with(&self.prefix)
+    }
+
+    /// Mean number of candidates per match: 2 for the `02`/`03` character, 16 for each hex character after it.
+    pub fn expected_attempts(&self) -> u64 {
+        let body = self.prefix.len() - ADDRESS_PREFIX.len();
+        if body < 2 {
+            return 1;
+        }
+        16u64.saturating_pow((body - 2) as u32).saturating_mul(2)
+    }
+
+    /// Searches seed indices `start_index..` of the account node for the mnemonic (as [`KeyGenerator::derive_range`]).
+    /// `Ok(None)` if cancelled, out of attempts, or out of non-hardened indices.
+    pub fn search_indices(
+        &self,
+        key_gen: &KeyGenerator,
+        mnemonic: &str,
+        alphanumeric_part: &str,
+        start_index: u32,
+        cancel: &VanityCancel,
+    ) -> Result<Option<VanityMatch>> {
+        let master_seed = key_gen.master_seed(mnemonic, alphanumeric_part)?;
+        let account_node = derive_path(XPrv::new(master_seed.as_slice())?, &key_gen.account_path()?)?;
+        let limit = u64::from(ChildNumber::HARDENED_FLAG.saturating_sub(start_index));
+        let secret_at = |counter: u64| -> Result<Option<SecretKey>> {
+            let index = ChildNumber::new(start_index + counter as u32, key_gen.hardened_only)?;
+            let key_bytes = Zeroizing::new(account_node.derive_child(index)?.private_key().to_bytes());
+            Ok(SecretKey::from_slice(key_bytes.as_slice()).ok())
+        };
+        self.run(limit, cancel, &secret_at)?
+            .map(|(counter, attempts, secret)| self.build_match(u64::from(start_index) + counter, attempts, secret))
+            .transpose()
+    }
+
+    /// Searches ephemeral keys `SHA256(seed || counter_be)` for `counter` in `0..`. `Ok(None)` if cancelled or
+    /// out of attempts.
+    pub fn search_ephemeral(&self, seed: &[u8; 32], cancel: &VanityCancel) -> Result<Option<VanityMatch>> {
+        let secret_at = |counter: u64| -> Result<Option<SecretKey>> {
+            let digest = Sha256::new().chain_update(seed).chain_update(counter.to_be_bytes()).finalize();
+            let digest = Zeroizing::new(<[u8; 32]>::from(digest));
+            Ok(SecretKey::from_slice(digest.as_slice()).ok())
+        };
+        self.run(u64::MAX, cancel, &secret_at)?
+            .map(|(counter, attempts, secret)| self.build_match(counter, attempts, secret))
+            .transpose()
+    }
+
+    /// Lowest counter below `limit` (and `max_attempts`) whose key matches, with the total attempts and its key.
+    /// Thread `t` checks `t, t + threads, ...` and stops once past the best match so far, so every counter below
+    /// the returned one has been checked.
+    fn run(
+        &self,
+        limit: u64,
+        cancel: &VanityCancel,
+        secret_at: &(dyn Fn(u64) -> Result<Option<SecretKey>> + Sync),
+    ) -> Result<Option<(u64, u64, SecretKey)>> {
+        let limit = limit.min(self.max_attempts);
+        let best = AtomicU64::new(u64::MAX);
+        let attempts = AtomicU64::new(0);
+        let failure: Mutex<Option<PlatariumError>> = Mutex::new(None);
+        let failed = AtomicBool::new(false);
+        let secp = Secp256k1::signing_only();
+        std::thread::scope(|scope| {
+            for first in 0..self.threads as u64 {
+                let (best, attempts, failure, failed, secp) = (&best, &attempts, &failure, &failed, &secp);
+                scope.spawn(move || {
+                    let mut counter = first;
+                    while counter < limit && counter < best.load(Ordering::Relaxed) && !cancel.is_cancelled()
+                        && !failed.load(Ordering::Relaxed)
+                    {
+                        match secret_at(counter) {
+                            Ok(Some(mut secret)) => {
+                                let address = address_from_public_key(&PublicKey::from_secret_key(secp, &secret));
+                                if self.matches(&address) {
+                                    best.fetch_min(counter, Ordering::Relaxed);
+                                }
+                                secret.non_secure_erase();
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                failure.lock().unwrap().get_or_insert(e);
+                                failed.store(true, Ordering::Relaxed);
+                            }
+                        }
+                        attempts.fetch_add(1, Ordering::Relaxed);
+                        counter = match counter.checked_add(self.threads as u64) {
+                            Some(next) => next,
+                            None => break,
+                        };
+                    }
+                });
+            }
+        });
+        if let Some(e) = failure.into_inner().unwrap() {
+            return Err(e);
+        }
+        let best = best.into_inner();
+        if best == u64::MAX {
+            return Ok(None);
+        }
+        let secret = secret_at(best)?.expect("matched key is valid");
+        Ok(Some((best, attempts.into_inner(), secret)))
+    }
+
+    fn build_match(&self, index: u64, attempts: u64, mut secret: SecretKey) -> Result<VanityMatch> {
+        let public_key = address_from_public_key(&PublicKey::from_secret_key(&Secp256k1::signing_only(), &secret));
+        let private_key_hex = Zeroizing::new(bn_to_hex32(Zeroizing::new(secret.secret_bytes()).as_slice())?);
+        secret.non_secure_erase();
+        Ok(VanityMatch {
+            index,
+            attempts,
+            public_key,
+            private_key: format!("PSx{}", *private_key_hex),
+        })
+    }
+}
+
+/// Purpose index of BIP-85 derivation paths.
+pub const BIP85_PURPOSE: u32 = 83696968;
+
+/// 64 bytes of BIP-85 entropy: `HMAC-SHA512("bip-entropy-from-k", k)` for the private key `k` at `path` below
+/// `root`. Every step of `path` must be hardened.
+pub fn bip85_entropy(root: &XPrv, path: &str) -> Result<Zeroizing<[u8; 64]>> {
+    let derivation_path: bip32::DerivationPath = path
+        .parse()
+        .map_err(|e| PlatariumError::Bip32(format!("Invalid derivation path: {}", e)))?;
+    if derivation_path.iter().any(|c| !c.is_hardened()) {
+        return Err(PlatariumError::Bip32(format!("BIP-85 path {} must be fully hardened", path)));
+    }
+    let node = derive_path(root.clone(), path)?;
+    let key = Zeroizing::new(node.private_key().to_bytes());
+    let mut mac = Hmac::<Sha512>::new_from_slice(b"bip-entropy-from-k").expect("HMAC accepts any key length");
+    mac.update(key.as_slice());
+    let mut entropy = Zeroizing::new([0u8; 64]);
+    entropy.copy_from_slice(&mac.finalize().into_bytes());
+    Ok(entropy)
+}
+
+/// BIP-85 code of a BIP39 wordlist.
+fn bip85_language_code(language: Language) -> u32 {
+    match language {
+        Language::English => 0,
+        Language::Japanese => 1,
+        Language::Korean => 2,
+        Language::Spanish => 3,
+        Language::SimplifiedChinese => 4,
+        Language::TraditionalChinese => 5,
+        Language::French => 6,
+        Language::Italian => 7,
+        Language::Czech => 8,
+        Language::Portuguese => 9,
+    }
+}
+
+/// Derives `path` below `root`.
+fn derive_path(root: XPrv, path: &str) -> Result<XPrv> {
+    let derivation_path: bip32::DerivationPath = path
+        .parse()
+        .map_err(|e| PlatariumError::Bip32(format!("Invalid derivation path: {}", e)))?;
+    let node = derivation_path.iter().try_fold(root, |acc, child_num| acc.derive_child(child_num))?;
+    Ok(node)
+}
+
 impl Default for KeyGenerator {
     fn default() -> Self {
         KeyGenerator::new(0, None, None, None).unwrap()
@@ -229,5 +882,172 @@ mod tests {
         assert_eq!(restored.private_key, generated.private_key);
         assert_eq!(restored.signature_key, generated.signature_key);
     }
-}
 
+    #[test]
+    fn test_restore_keys_in_other_language() {
+        let key_gen = KeyGenerator::default().with_language(Language::Japanese);
+        let generated = key_gen.generate_keys().unwrap();
+        let restored = key_gen
+            .restore_keys(&generated.mnemonic, &generated.alphanumeric_part, 0, None)
+            .unwrap();
+        assert_eq!(restored.public_key, generated.public_key);
+        assert!(KeyGenerator::default()
+            .restore_keys(&generated.mnemonic, &generated.alphanumeric_part, 0, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_derivation_path_round_trip_and_validation() {
+        let path = DerivationPath::default().with_account(1).with_address_index(7);
+        assert_eq!(path.to_string(), "m/44'/60'/1'/0/7");
+        assert_eq!("m/44h/60h/1h/0/7".parse::<DerivationPath>().unwrap(), path);
+        assert_eq!(path.to_bip32().unwrap().to_string(), "m/44'/60'/1'/0/7");
+        for bad in ["m/44'/60'/0'/0", "44'/60'/0'/0/0", "m/44'/60'/0'/0/0/1", "m/44'/60'/x'/0/0", "m/44'/60'/0'/0/2147483648"] {
+            assert!(bad.parse::<DerivationPath>().is_err(), "{}", bad);
+        }
+        let out_of_range = DerivationPath::default().with_address_index(1 << 31);
+        assert!(out_of_range.validate().is_err());
+        assert!(KeyGenerator::new(0, None, None, Some(out_of_range)).is_err());
+        let key_gen = KeyGenerator::default();
+        let generated = key_gen.generate_keys().unwrap();
+        assert!(key_gen
+            .restore_keys(&generated.mnemonic, &generated.alphanumeric_part, 1 << 31, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_hardened_only_mode() {
+        let key_gen = KeyGenerator::default().hardened_only();
+        let generated = key_gen.generate_keys().unwrap();
+        assert_eq!(generated.derivation_paths.main_path, "m/44'/60'/0'/0'/0'");
+        let range = key_gen
+            .derive_range(&generated.mnemonic, &generated.alphanumeric_part, 0, 2)
+            .unwrap();
+        assert_eq!(range[0], generated);
+        assert_eq!(range[1].derivation_paths.main_path, "m/44'/60'/0'/0'/1'");
+
+        let normal = KeyGenerator::default()
+            .restore_keys(&generated.mnemonic, &generated.alphanumeric_part, 0, None)
+            .unwrap();
+        assert_ne!(normal.public_key, generated.public_key);
+        assert!(key_gen.export_xpub(&generated.mnemonic, &generated.alphanumeric_part).is_err());
+        let soft: DerivationPath = "m/44'/60'/1'/0/3".parse().unwrap();
+        assert!(key_gen
+            .restore_keys(&generated.mnemonic, &generated.alphanumeric_part, 0, Some(soft))
+            .is_err());
+        assert!(key_gen
+            .restore_keys(&generated.mnemonic, &generated.alphanumeric_part, 0, Some(soft.all_hardened()))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_derive_range_matches_restore_keys() {
+        let key_gen = KeyGenerator::default();
+        let generated = key_gen.generate_keys().unwrap();
+        let range = key_gen
+            .derive_range(&generated.mnemonic, &generated.alphanumeric_part, 5, 3)
+            .unwrap();
+        assert_eq!(range.len(), 3);
+        for (offset, keys) in range.iter().enumerate() {
+            let restored = key_gen
+                .restore_keys(&generated.mnemonic, &generated.alphanumeric_part, 5 + offset as u32, None)
+                .unwrap();
+            assert_eq!(keys, &restored);
+        }
+        assert!(key_gen
+            .derive_range(&generated.mnemonic, &generated.alphanumeric_part, (1 << 31) - 1, 2)
+            .is_err());
+    }
+
+    #[test]
+    fn test_bip85_vectors() {
+        // Test case 1 and the 12-word BIP39 case from the BIP-85 specification.
+        let root: XPrv = "xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            hex::encode(bip85_entropy(&root, "m/83696968'/0'/0'").unwrap().as_slice()),
+            "efecfbccffea313214232d29e71563d941229afb4338c21f9517c41aaa0d16f00b83d2a09ef747e7a64e8e2bd5a14869e693da66ce94ac2da570ab7ee48618f7"
+        );
+        let entropy = bip85_entropy(&root, "m/83696968'/39'/0'/12'/0'").unwrap();
+        assert_eq!(hex::encode(&entropy[..16]), "6250b68daf746d12a24d58b4787a714b");
+        assert!(bip85_entropy(&root, "m/83696968'/0'/0").is_err());
+    }
+
+    #[test]
+    fn test_child_mnemonics_are_independent_wallets() {
+        let key_gen = KeyGenerator::default();
+        let parent = key_gen.generate_keys().unwrap();
+        let child = |words, index| {
+            key_gen
+                .derive_child_mnemonic(&parent.mnemonic, &parent.alphanumeric_part, words, index)
+                .unwrap()
+        };
+        assert_eq!(child(24, 0), child(24, 0));
+        assert_ne!(child(24, 0), child(24, 1));
+        assert_eq!(child(12, 3).split_whitespace().count(), 12);
+        assert!(validate_mnemonic_in(&child(18, 0), Language::English));
+        assert!(key_gen.derive_child_mnemonic(&parent.mnemonic, &parent.alphanumeric_part, 13, 0).is_err());
+
+        // The alphanumeric part is the BIP39 passphrase, so it changes every child.
+        let other = key_gen
+            .derive_child_mnemonic(&parent.mnemonic, "OTHER", 24, 0)
+            .unwrap();
+        assert_ne!(other, child(24, 0));
+
+        let entropy = key_gen
+            .derive_child_entropy(&parent.mnemonic, &parent.alphanumeric_part, 32, 0)
+            .unwrap();
+        assert_eq!(entropy.len(), 32);
+        assert!(key_gen.derive_child_entropy(&parent.mnemonic, &parent.alphanumeric_part, 65, 0).is_err());
+    }
+
+    #[test]
+    fn test_vanity_indices_find_lowest_match() {
+        let key_gen = KeyGenerator::default();
+        let generated = key_gen.generate_keys().unwrap();
+        let (mnemonic, alphanumeric) = (&generated.mnemonic, &generated.alphanumeric_part);
+        let search = VanitySearch::new("Px02A").unwrap();
+        assert_eq!(search.prefix(), "Px02a");
+        assert_eq!(search.expected_attempts(), 32);
+
+        let cancel = VanityCancel::new();
+        let run = |threads| {
+            search
+                .clone()
+                .threads(threads)
+                .search_indices(&key_gen, mnemonic, alphanumeric, 3, &cancel)
+                .unwrap()
+                .unwrap()
+        };
+        let (found, single) = (run(4), run(1));
+        assert_eq!(found.index, single.index);
+        assert_eq!(found.public_key, single.public_key);
+
+        let restored = key_gen.restore_keys(mnemonic, alphanumeric, found.index as u32, None).unwrap();
+        assert!(restored.public_key.starts_with("Px02a"));
+        assert_eq!(restored.public_key, found.public_key);
+        assert_eq!(restored.private_key, found.private_key);
+        let skipped = key_gen.derive_range(mnemonic, alphanumeric, 3, found.index as u32 - 3).unwrap();
+        assert!(skipped.iter().all(|k| !search.matches(&k.public_key)));
+    }
+
+    #[test]
+    fn test_vanity_ephemeral_is_deterministic_and_cancellable() {
+        let search = VanitySearch::new("03f").unwrap().threads(3);
+        let cancel = VanityCancel::new();
+        let a = search.search_ephemeral(&[9; 32], &cancel).unwrap().unwrap();
+        let b = search.clone().threads(1).search_ephemeral(&[9; 32], &cancel).unwrap().unwrap();
+        assert_eq!((a.index, &a.public_key, &a.private_key), (b.index, &b.public_key, &b.private_key));
+        assert!(a.public_key.starts_with("Px03f"));
+
+        assert!(search.clone().max_attempts(a.index).search_ephemeral(&[9; 32], &cancel).unwrap().is_none());
+        cancel.cancel();
+        assert!(search.search_ephemeral(&[9; 32], &cancel).unwrap().is_none());
+
+        assert!(VanitySearch::new("").is_err());
+        assert!(VanitySearch::new("Px04").is_err());
+        assert!(VanitySearch::new("Px1").is_err());
+        assert!(VanitySearch::new("Px02xyz").is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Key rotation certificates: a signed link from an account's old main key to its replacement.
+//!
+//! A [`RotationCertificate`] names the old and new public keys, a sequence number and the height from which the
+//! new key is meant to be used, and carries two signatures over the same digest: one by the old key (handing the
+//! identity over) and one by the new key (proving possession, so nobody can rotate an account onto a key they do
+//! not hold). The digest is
+//!
+//! `SHA256("PlatariumKeyRotation:" || version (u32 BE) || old key (33 bytes) || new key (33 bytes) || sequence (u64 BE) || effective_height (u64 BE))`
+//!
+//! Certificates chain: certificate `i` must start from the key certificate `i - 1` ended at, with sequence `i`.
+//! [`verify_rotation_chain`] walks a chain from an account's original address and returns its current key, so a
+//! wallet or indexer can keep treating the original address as the account's identity after any number of
+//! rotations. On-chain, the key that `State` accepts for an account still changes only through the recovery
+//! transactions (`core::recovery`).
+
+use secp256k1::ecdsa::Signature;
+use secp256k1::{Message, PublicKey, Secp256k1};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use crate::address::Address;
+use crate::error::PlatariumError;
+use crate::key_generator::{KeyGenerator, KeyPair};
+use crate::signer::{sign_digest_checked, Signer};
+
+/// Domain separator of the rotation digest.
+pub const ROTATION_DOMAIN: &str = "PlatariumKeyRotation:";
+
+/// Certificate format version.
+pub const ROTATION_VERSION: u32 = 1;
+
+/// Why a rotation certificate or chain was rejected.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RotationError {
+    #[error("unsupported rotation certificate version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("invalid {which} key: {reason}")]
+    InvalidKey { which: &'static str, reason: String },
+
+    #[error("old and new keys are the same")]
+    SameKey,
+
+    #[error("{0} key signature does not verify")]
+    BadSignature(&'static str),
+
+    #[error("certificate {index} starts from {got}, expected {expected}")]
+    BrokenChain { index: usize, expected: String, got: String },
+
+    #[error("certificate {index} has sequence {got}, expected {expected}")]
+    SequenceMismatch { index: usize, expected: u64, got: u64 },
+
+    #[error("certificate {index} takes effect at {got}, not after the previous rotation at {previous}")]
+    HeightNotIncreasing { index: usize, previous: u64, got: u64 },
+}
+
+impl From<RotationError> for PlatariumError {
+    fn from(err: RotationError) -> Self {
+        PlatariumError::Signature(format!("Key rotation error: {}", err))
+    }
+}
+
+/// Old key → new key handover, signed by both keys.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationCertificate {
+    pub version: u32,
+    /// Address (`Px` + compressed key) being retired.
+    pub old_public_key: String,
+    /// Address (`Px` + compressed key) taking over.
+    pub new_public_key: String,
+    /// Position in the account's rotation chain, starting at 0.
+    pub sequence: u64,
+    /// Height from which the new key is meant to be used.
+    pub effective_height: u64,
+    /// Compact (r || s) hex signature of the digest by the old key.
+    pub sig_old: String,
+    /// Compact (r || s) hex signature of the digest by the new key.
+    pub sig_new: String,
+}
+
+impl RotationCertificate {
+    /// Signs a rotation from `old`'s key to `new`'s key. Either signer may be a hardware device.
+    pub fn issue(
+        old: &dyn Signer,
+        new: &dyn Signer,
+        sequence: u64,
+        effective_height: u64,
+    ) -> crate::error::Result<Self> {
+        let (old_key, new_key) = (old.public_key()?, new.public_key()?);
+        if old_key == new_key {
+            return Err(RotationError::SameKey.into());
+        }
+        let digest = rotation_digest(ROTATION_VERSION, &old_key, &new_key, sequence, effective_height);
+        let (sig_old, _) = sign_digest_checked(old, &digest)?;
+        let (sig_new, _) = sign_digest_checked(new, &digest)?;
+        Ok(Self {
+            version: ROTATION_VERSION,
+            old_public_key: Address::from_public_key(&old_key).into_string(),
+            new_public_key: Address::from_public_key(&new_key).into_string(),
+            sequence,
+            effective_height,
+            sig_old: hex::encode(sig_old.serialize_compact()),
+            sig_new: hex::encode(sig_new.serialize_compact()),
+        })
+    }
+
+    /// Certificate handing `old`'s main key over to `new`'s main key.
+    pub fn from_key_pairs(
+        old: &KeyPair,
+        new: &KeyPair,
+        sequence: u64,
+        effective_height: u64,
+    ) -> crate::error::Result<Self> {
+        Self::issue(&old.main_signer()?, &new.main_signer()?, sequence, effective_height)
+    }
+
+    /// Digest both keys signed.
+    pub fn digest(&self) -> Result<[u8; 32], RotationError> {
+        let (old_key, new_key) = (parse_key("old", &self.old_public_key)?, parse_key("new", &self.new_public_key)?);
+        Ok(rotation_digest(self.version, &old_key, &new_key, self.sequence, self.effective_height))
+    }
+
+    /// Checks the version, both keys and both signatures.
+    pub fn verify(&self) -> Result<(), RotationError> {
+        if self.version != ROTATION_VERSION {
+            return Err(RotationError::UnsupportedVersion(self.version));
+        }
+        let (old_key, new_key) = (parse_key("old", &self.old_public_key)?, parse_key("new", &self.new_public_key)?);
+        if old_key == new_key {
+            return Err(RotationError::SameKey);
+        }
+        let message = Message::from_digest(self.digest()?);
+        let secp = Secp256k1::verification_only();
+        for (which, sig, key) in [("old", &self.sig_old, &old_key), ("new", &self.sig_new, &new_key)] {
+            let signature = hex::decode(sig)
+                .ok()
+                .and_then(|bytes| Signature::from_compact(&bytes).ok())
+                .ok_or(RotationError::BadSignature(which))?;
+            secp.verify_ecdsa(&message, &signature, key)
+                .map_err(|_| RotationError::BadSignature(which))?;
+        }
+        Ok(())
+    }
+}
+
+impl KeyGenerator {
+    /// Generates a fresh key pair (new mnemonic) and a certificate rotating `old`'s main key to it.
+    pub fn rotate_keys(
+        &self,
+        old: &KeyPair,
+        sequence: u64,
+        effective_height: u64,
+    ) -> crate::error::Result<(KeyPair, RotationCertificate)> {
+        let new = self.generate_keys()?;
+        let certificate = RotationCertificate::from_key_pairs(old, &new, sequence, effective_height)?;
+        Ok((new, certificate))
+    }
+}
+
+/// Verifies `chain` as the rotations of the account first known as `original` and returns its current address
+/// (`original` itself for an empty chain). Each certificate must verify, start from the previous one's new key,
+/// carry the next sequence number and take effect after the previous one.
+pub fn verify_rotation_chain(original: &str, chain: &[RotationCertificate]) -> Result<Address, RotationError> {
+    let mut current = parse_address("original", original)?;
+    let mut previous_height = None;
+    for (index, certificate) in chain.iter().enumerate() {
+        certificate.verify()?;
+        let old = parse_address("old", &certificate.old_public_key)?;
+        if old != current {
+            return Err(RotationError::BrokenChain {
+                index,
+                expected: current.into_string(),
+                got: old.into_string(),
+            });
+        }
+        if certificate.sequence != index as u64 {
+            return Err(RotationError::SequenceMismatch {
+                index,
+                expected: index as u64,
+                got: certificate.sequence,
+            });
+        }
+        if let Some(previous) = previous_height.filter(|p| certificate.effective_height <= *p) {
+            return Err(RotationError::HeightNotIncreasing {
+                index,
+                previous,
+                got: certificate.effective_height,
+            });
+        }
+        previous_height = Some(certificate.effective_height);
+        current = parse_address("new", &certificate.new_public_key)?;
+    }
+    Ok(current)
+}
+
+fn rotation_digest(version: u32, old: &PublicKey, new: &PublicKey, sequence: u64, effective_height: u64) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(ROTATION_DOMAIN.as_bytes())
+        .chain_update(version.to_be_bytes())
+        .chain_update(old.serialize())
+        .chain_update(new.serialize())
+        .chain_update(sequence.to_be_bytes())
+        .chain_update(effective_height.to_be_bytes())
+        .finalize()
+        .into()
+}
+
+fn parse_address(which: &'static str, address: &str) -> Result<Address, RotationError> {
+    Address::parse(address).map_err(|e| RotationError::InvalidKey {
+        which,
+        reason: e.to_string(),
+    })
+}
+
+fn parse_key(which: &'static str, address: &str) -> Result<PublicKey, RotationError> {
+    Ok(parse_address(which, address)?.public_key())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_chain_links_keys() {
+        let key_gen = KeyGenerator::default();
+        let original = key_gen.generate_keys().unwrap();
+        let (second, first_cert) = key_gen.rotate_keys(&original, 0, 100).unwrap();
+        let (third, second_cert) = key_gen.rotate_keys(&second, 1, 250).unwrap();
+        first_cert.verify().unwrap();
+        assert_eq!(first_cert.new_public_key, second.public_key);
+
+        let chain = vec![first_cert.clone(), second_cert.clone()];
+        let current = verify_rotation_chain(&original.public_key, &chain).unwrap();
+        assert_eq!(current.as_str(), third.public_key);
+        assert_eq!(verify_rotation_chain(&original.public_key, &[]).unwrap().as_str(), original.public_key);
+
+        let json = serde_json::to_string(&first_cert).unwrap();
+        assert_eq!(serde_json::from_str::<RotationCertificate>(&json).unwrap(), first_cert);
+
+        assert!(matches!(
+            verify_rotation_chain(&original.public_key, std::slice::from_ref(&second_cert)),
+            Err(RotationError::BrokenChain { index: 0, .. })
+        ));
+        assert!(matches!(
+            verify_rotation_chain(&original.public_key, &[first_cert.clone(), first_cert.clone()]),
+            Err(RotationError::BrokenChain { index: 1, .. })
+        ));
+        let (_, late) = key_gen.rotate_keys(&second, 1, 100).unwrap();
+        assert!(matches!(
+            verify_rotation_chain(&original.public_key, &[first_cert.clone(), late]),
+            Err(RotationError::HeightNotIncreasing { index: 1, .. })
+        ));
+        let (_, skipped) = key_gen.rotate_keys(&second, 5, 300).unwrap();
+        assert!(matches!(
+            verify_rotation_chain(&original.public_key, &[first_cert, skipped]),
+            Err(RotationError::SequenceMismatch { index: 1, expected: 1, got: 5 })
+        ));
+    }
+
+    #[test]
+    fn test_tampered_certificate_is_rejected() {
+        let key_gen = KeyGenerator::default();
+        let old = key_gen.generate_keys().unwrap();
+        let (new, cert) = key_gen.rotate_keys(&old, 0, 10).unwrap();
+
+        let mut moved = cert.clone();
+        moved.effective_height = 11;
+        assert_eq!(moved.verify(), Err(RotationError::BadSignature("old")));
+
+        // Swapping in a key the signer does not hold breaks the possession proof.
+        let intruder = key_gen.generate_keys().unwrap();
+        let mut hijacked = cert.clone();
+        hijacked.new_public_key = intruder.public_key.clone();
+        assert!(hijacked.verify().is_err());
+        let mut forged = RotationCertificate::from_key_pairs(&old, &intruder, 0, 10).unwrap();
+        forged.sig_new = cert.sig_new.clone();
+        assert_eq!(forged.verify(), Err(RotationError::BadSignature("new")));
+
+        let mut future = cert.clone();
+        future.version = 2;
+        assert_eq!(future.verify(), Err(RotationError::UnsupportedVersion(2)));
+        assert!(RotationCertificate::from_key_pairs(&new, &new, 0, 10).is_err());
+    }
+}
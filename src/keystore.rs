@@ -0,0 +1,395 @@
+//! Password-encrypted keystore files for key material produced by `KeyGenerator`.
+//!
+//! A keystore is a versioned JSON document. The secret (a whole [`KeyPair`] or a raw seed) is
+//! serialized to JSON and encrypted with AES-256-GCM under a key stretched from the password with
+//! scrypt. The cleartext header (`version`, `kind`, `address`) is bound as associated data, so editing
+//! it makes decryption fail just like a wrong password does.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "kind": "key_pair",
+//!   "address": "Px02…",
+//!   "crypto": {
+//!     "kdf": "scrypt",
+//!     "kdfparams": { "log_n": 15, "r": 8, "p": 1, "dklen": 32, "salt": "<hex>" },
+//!     "cipher": "aes-256-gcm",
+//!     "nonce": "<hex>",
+//!     "ciphertext": "<hex, includes the 16-byte tag>"
+//!   }
+//! }
+//! ```
+
+use std::path::Path;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::Zeroizing;
+use crate::error::{PlatariumError, Result};
+use crate::key_generator::KeyPair;
+
+/// Current keystore schema version.
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const KDF_SCRYPT: &str = "scrypt";
+const CIPHER_AES_256_GCM: &str = "aes-256-gcm";
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Highest accepted scrypt `log_n`. Parameters come from the keystore file, so without a bound a crafted file could
+/// make an unlock take unbounded memory and time.
+pub const MAX_KDF_LOG_N: u8 = 20;
+/// Highest accepted scrypt memory use, `128 * r * 2^log_n` bytes: 1 GiB, i.e. `log_n = 20` at `r = 8`.
+pub const MAX_KDF_MEMORY_BYTES: u64 = 1 << 30;
+/// Highest accepted scrypt parallelism `p`; the work grows linearly with it.
+pub const MAX_KDF_P: u32 = 16;
+
+/// Errors produced while encrypting, decrypting or parsing a keystore.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KeystoreError {
+    #[error("Unsupported keystore version {0} (expected {KEYSTORE_VERSION})")]
+    UnsupportedVersion(u32),
+
+    #[error("Unsupported keystore {field}: {value}")]
+    Unsupported { field: &'static str, value: String },
+
+    #[error("Invalid scrypt parameters: {0}")]
+    InvalidKdfParams(String),
+
+    #[error("Malformed keystore: {0}")]
+    Malformed(String),
+
+    #[error("Wrong password or corrupted keystore")]
+    DecryptionFailed,
+
+    #[error("Keystore holds a {found:?}, not a {expected:?}")]
+    WrongKind { expected: KeystoreKind, found: KeystoreKind },
+}
+
+impl From<KeystoreError> for PlatariumError {
+    fn from(e: KeystoreError) -> Self {
+        PlatariumError::Crypto(format!("Keystore: {}", e))
+    }
+}
+
+/// What the encrypted payload contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeystoreKind {
+    KeyPair,
+    Seed,
+}
+
+/// Decrypted keystore contents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum KeystoreSecret {
+    KeyPair(KeyPair),
+    Seed { seed: String },
+}
+
+impl KeystoreSecret {
+    /// Wraps raw seed bytes (stored hex-encoded inside the ciphertext).
+    pub fn seed(bytes: &[u8]) -> Self {
+        KeystoreSecret::Seed {
+            seed: hex::encode(bytes),
+        }
+    }
+
+    pub fn kind(&self) -> KeystoreKind {
+        match self {
+            KeystoreSecret::KeyPair(_) => KeystoreKind::KeyPair,
+            KeystoreSecret::Seed { .. } => KeystoreKind::Seed,
+        }
+    }
+}
+
+/// scrypt cost parameters. `log_n` is log2 of the CPU/memory cost; memory use is about `128 * r * 2^log_n` bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for KdfParams {
+    /// 2^15 × 8 blocks: about 32 MiB and well under a second per unlock on current hardware.
+    fn default() -> Self {
+        Self { log_n: 15, r: 8, p: 1 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScryptParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    pub dklen: usize,
+    pub salt: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    pub kdf: String,
+    pub kdfparams: ScryptParams,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Encrypted keystore document. Only `address` is readable without the password.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub kind: KeystoreKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub crypto: KeystoreCrypto,
+}
+
+impl Keystore {
+    /// Encrypts `secret` under `password` with a fresh random salt and nonce.
+    pub fn encrypt(secret: &KeystoreSecret, password: &str, params: KdfParams) -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let kdfparams = ScryptParams {
+            log_n: params.log_n,
+            r: params.r,
+            p: params.p,
+            dklen: KEY_LEN,
+            salt: hex::encode(salt),
+        };
+        let key = derive_key(password, &kdfparams, &salt)?;
+        let address = match secret {
+            KeystoreSecret::KeyPair(kp) => Some(kp.public_key.clone()),
+            KeystoreSecret::Seed { .. } => None,
+        };
+        let mut keystore = Keystore {
+            version: KEYSTORE_VERSION,
+            kind: secret.kind(),
+            address,
+            crypto: KeystoreCrypto {
+                kdf: KDF_SCRYPT.to_string(),
+                kdfparams,
+                cipher: CIPHER_AES_256_GCM.to_string(),
+                nonce: hex::encode(nonce),
+                ciphertext: String::new(),
+            },
+        };
+        let plaintext = Zeroizing::new(serde_json::to_vec(secret)
+            .map_err(|e| KeystoreError::Malformed(e.to_string()))?);
+        let ciphertext = cipher(&key)?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &keystore.associated_data(),
+                },
+            )
+            .map_err(|_| PlatariumError::Crypto("AES-GCM encryption failed".to_string()))?;
+        keystore.crypto.ciphertext = hex::encode(ciphertext);
+        Ok(keystore)
+    }
+
+    /// Encrypts a key pair; `address` is set to its public key.
+    pub fn encrypt_key_pair(key_pair: &KeyPair, password: &str, params: KdfParams) -> Result<Self> {
+        Self::encrypt(&KeystoreSecret::KeyPair(key_pair.clone()), password, params)
+    }
+
+    /// Encrypts raw seed bytes (e.g. a BIP39 master seed).
+    pub fn encrypt_seed(seed: &[u8], password: &str, params: KdfParams) -> Result<Self> {
+        Self::encrypt(&KeystoreSecret::seed(seed), password, params)
+    }
+
+    /// Decrypts the payload. A wrong password and a tampered file both give `DecryptionFailed`.
+    pub fn decrypt(&self, password: &str) -> Result<KeystoreSecret> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(self.version).into());
+        }
+        if self.crypto.kdf != KDF_SCRYPT {
+            return Err(KeystoreError::Unsupported { field: "kdf", value: self.crypto.kdf.clone() }.into());
+        }
+        if self.crypto.cipher != CIPHER_AES_256_GCM {
+            return Err(KeystoreError::Unsupported { field: "cipher", value: self.crypto.cipher.clone() }.into());
+        }
+        let salt = decode_hex("salt", &self.crypto.kdfparams.salt)?;
+        let nonce = decode_hex("nonce", &self.crypto.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(KeystoreError::Malformed(format!("nonce must be {} bytes", NONCE_LEN)).into());
+        }
+        let ciphertext = decode_hex("ciphertext", &self.crypto.ciphertext)?;
+
+        let key = derive_key(password, &self.crypto.kdfparams, &salt)?;
+        let plaintext = Zeroizing::new(cipher(&key)?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &self.associated_data(),
+                },
+            )
+            .map_err(|_| KeystoreError::DecryptionFailed)?);
+        let secret: KeystoreSecret = serde_json::from_slice(&plaintext)
+            .map_err(|e| KeystoreError::Malformed(e.to_string()))?;
+        if secret.kind() != self.kind {
+            return Err(KeystoreError::WrongKind { expected: self.kind, found: secret.kind() }.into());
+        }
+        Ok(secret)
+    }
+
+    /// Decrypts a `key_pair` keystore.
+    pub fn decrypt_key_pair(&self, password: &str) -> Result<KeyPair> {
+        match self.decrypt(password)? {
+            KeystoreSecret::KeyPair(kp) => Ok(kp),
+            other => Err(KeystoreError::WrongKind { expected: KeystoreKind::KeyPair, found: other.kind() }.into()),
+        }
+    }
+
+    /// Decrypts a `seed` keystore into the raw seed bytes.
+    pub fn decrypt_seed(&self, password: &str) -> Result<Vec<u8>> {
+        match self.decrypt(password)? {
+            KeystoreSecret::Seed { seed } => decode_hex("seed", &seed),
+            other => Err(KeystoreError::WrongKind { expected: KeystoreKind::Seed, found: other.kind() }.into()),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| KeystoreError::Malformed(e.to_string()).into())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| KeystoreError::Malformed(e.to_string()).into())
+    }
+
+    /// Writes the keystore to `path`; on Unix the file is created with mode 0600.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        use std::io::Write;
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(path)
+            .map_err(|e| PlatariumError::State(format!("Cannot write keystore {}: {}", path.display(), e)))?;
+        file.write_all(self.to_json()?.as_bytes())
+            .map_err(|e| PlatariumError::State(format!("Cannot write keystore {}: {}", path.display(), e)))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| PlatariumError::State(format!("Cannot read keystore {}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+    }
+
+    /// Header bytes authenticated alongside the ciphertext.
+    fn associated_data(&self) -> Vec<u8> {
+        let kind = match self.kind {
+            KeystoreKind::KeyPair => "key_pair",
+            KeystoreKind::Seed => "seed",
+        };
+        format!(
+            "platarium-keystore:{}:{}:{}",
+            self.version,
+            kind,
+            self.address.as_deref().unwrap_or("")
+        )
+        .into_bytes()
+    }
+}
+
+/// The AES key is wiped on drop, as are the plaintexts in [`Keystore::encrypt`] and [`Keystore::decrypt`].
+fn derive_key(password: &str, params: &ScryptParams, salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LEN]>> {
+    if params.dklen != KEY_LEN {
+        return Err(KeystoreError::InvalidKdfParams(format!("dklen must be {}", KEY_LEN)).into());
+    }
+    if params.log_n > MAX_KDF_LOG_N {
+        return Err(KeystoreError::InvalidKdfParams(format!("log_n {} exceeds {}", params.log_n, MAX_KDF_LOG_N)).into());
+    }
+    if (128 * u64::from(params.r)) << params.log_n > MAX_KDF_MEMORY_BYTES {
+        return Err(KeystoreError::InvalidKdfParams(format!(
+            "r {} with log_n {} needs more than {} bytes",
+            params.r, params.log_n, MAX_KDF_MEMORY_BYTES
+        ))
+        .into());
+    }
+    if params.p > MAX_KDF_P {
+        return Err(KeystoreError::InvalidKdfParams(format!("p {} exceeds {}", params.p, MAX_KDF_P)).into());
+    }
+    let scrypt_params = scrypt::Params::new(params.log_n, params.r, params.p, KEY_LEN)
+        .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    scrypt::scrypt(password.as_bytes(), salt, &scrypt_params, key.as_mut_slice())
+        .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+    Ok(key)
+}
+
+fn cipher(key: &[u8; KEY_LEN]) -> Result<Aes256Gcm> {
+    Aes256Gcm::new_from_slice(key).map_err(|e| PlatariumError::Crypto(e.to_string()))
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>> {
+    hex::decode(value).map_err(|e| KeystoreError::Malformed(format!("{}: {}", field, e)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_generator::KeyGenerator;
+
+    /// Cheap parameters so tests stay fast.
+    const TEST_PARAMS: KdfParams = KdfParams { log_n: 4, r: 8, p: 1 };
+
+    #[test]
+    fn key_pair_round_trip_through_json() {
+        let kp = KeyGenerator::default().generate_keys().unwrap();
+        let keystore = Keystore::encrypt_key_pair(&kp, "correct horse", TEST_PARAMS).unwrap();
+        let json = keystore.to_json().unwrap();
+        assert!(!json.contains(&kp.mnemonic) && !json.contains(&kp.private_key));
+
+        let restored = Keystore::from_json(&json).unwrap();
+        assert_eq!(restored.address.as_deref(), Some(kp.public_key.as_str()));
+        assert_eq!(restored.decrypt_key_pair("correct horse").unwrap(), kp);
+        assert!(restored.decrypt_seed("correct horse").is_err());
+    }
+
+    #[test]
+    fn wrong_password_and_tampering_are_rejected() {
+        let keystore = Keystore::encrypt_seed(&[7u8; 64], "pw", TEST_PARAMS).unwrap();
+        assert_eq!(keystore.decrypt_seed("pw").unwrap(), vec![7u8; 64]);
+        assert!(keystore.decrypt("other").is_err());
+
+        let mut relabeled = keystore.clone();
+        relabeled.address = Some("Px00".to_string());
+        assert!(relabeled.decrypt("pw").is_err());
+
+        // Crafted cost parameters are refused before any scrypt work.
+        let crafted = [(MAX_KDF_LOG_N + 1, 1, 1), (MAX_KDF_LOG_N, 9, 1), (4, u32::MAX, 1), (4, 8, MAX_KDF_P + 1)];
+        for (log_n, r, p) in crafted {
+            let mut costly = keystore.clone();
+            costly.crypto.kdfparams.log_n = log_n;
+            costly.crypto.kdfparams.r = r;
+            costly.crypto.kdfparams.p = p;
+            assert!(matches!(
+                costly.decrypt("pw"),
+                Err(PlatariumError::Crypto(msg)) if msg.contains("Invalid scrypt parameters")
+            ));
+        }
+        assert!(Keystore::encrypt_seed(&[7u8; 64], "pw", KdfParams { log_n: 21, r: 8, p: 1 }).is_err());
+
+        let mut future = keystore;
+        future.version = 2;
+        assert!(matches!(
+            future.decrypt("pw"),
+            Err(PlatariumError::Crypto(msg)) if msg.contains("version 2")
+        ));
+    }
+}
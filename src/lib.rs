@@ -1,27 +1,157 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
 pub mod mnemonic;
 pub mod key_generator;
+pub mod keystore;
+pub mod seed_store;
 pub mod signer;
+pub mod key_rotation;
 pub mod signature;
+pub mod address;
+pub mod wif;
+pub mod qr;
 pub mod utils;
 pub mod error;
 pub mod core;
 pub mod storage;
+pub mod compat;
+pub mod watch_only;
+pub mod shamir;
+#[cfg(feature = "experimental")]
+pub mod threshold;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
-pub use mnemonic::{generate_mnemonic, validate_mnemonic, CHARACTER_SET};
-pub use key_generator::{KeyGenerator, KeyPair, DerivationPaths, generate_alphanumeric_part};
-pub use utils::{derive_signature_seed_from_master_seed, bn_to_hex32};
-pub use signer::{sign_with_both_keys, DualSignature, SignatureWithType};
-pub use signature::{verify_signature, hash_message, sign_message, normalize_signature_hex, SignatureComponents};
+pub use mnemonic::{
+    generate_mnemonic, generate_mnemonic_in, generate_mnemonic_with, language_name, mnemonic_language,
+    parse_language, validate_mnemonic, validate_mnemonic_in, validate_mnemonic_with, estimate_alphanumeric_entropy,
+    CharsetPolicy, Language, MnemonicConfig, CHARACTER_SET, DEFAULT_ALPHANUMERIC_LEN, DEFAULT_MNEMONIC_WORDS,
+    MIN_ALPHANUMERIC_ENTROPY_BITS, MNEMONIC_WORD_COUNTS,
+};
+pub use key_generator::{
+    KeyGenerator, KeyPair, ChildIndex, DerivationPath, DerivationPaths, VanityCancel, VanityMatch, VanitySearch,
+    generate_alphanumeric_part, generate_alphanumeric_part_with, bip85_entropy, BIP85_PURPOSE,
+};
+pub use keystore::{
+    KdfParams, Keystore, KeystoreError, KeystoreKind, KeystoreSecret, KEYSTORE_VERSION, MAX_KDF_LOG_N,
+    MAX_KDF_MEMORY_BYTES, MAX_KDF_P,
+};
+pub use wif::{decode_wif, encode_wif, WifError, WifKey, WifKeys, WifKind, WifNetwork, WIF_IMPORTED_PATH};
+pub use qr::{
+    address_uri, parse_signed_transaction_uri, signed_transaction_uri, PaymentRequest, QrError, MAX_QR_PAYLOAD_BYTES,
+    PAYMENT_URI_SCHEME, SIGNED_TX_URI_SCHEME,
+};
+#[cfg(feature = "qr")]
+pub use qr::{render_qr_svg, render_qr_terminal};
+pub use seed_store::{
+    default_seed_store, validate_seed_name, FileSeedStore, SeedStore, SeedStoreError, KEYRING_SERVICE,
+    MAX_SEED_NAME_LEN,
+};
+#[cfg(feature = "keyring")]
+pub use seed_store::KeyringSeedStore;
+pub use utils::{derive_signature_seed_for_network, derive_signature_seed_from_master_seed, bn_to_hex32, NetworkId};
+pub use signer::{
+    sign_digest_checked, sign_message_with, sign_messages_with_both_keys, sign_transaction, sign_with_both_keys,
+    sign_with_both_keys_for_network, sign_with_private_key_str, sign_with_signers, software_signers,
+    software_signers_for_network, verify_dual_signature, verify_with_public_key_str, DualSignature, SignatureWithType,
+    Signer, SoftwareSigner,
+};
+pub use key_rotation::{verify_rotation_chain, RotationCertificate, RotationError, ROTATION_VERSION};
+pub use signature::{verify_bytes, verify_signature, verify_signature_in, verify_signature_strict, verify_signature_hash, verify_signature_hash_strict, verify_signature_hash_with, verify_schnorr_hash, hash_message, hash_message_in, update_json, message_preimage, message_preimage_in, sign_bytes, sign_hash, sign_message, sign_message_in, sign_message_recoverable, sign_message_schnorr, recover_pubkey, recover_pubkey_hash, normalize_signature_hex, normalize_public_key_hex, parse_ecdsa_signature, parse_ecdsa_signature_strict, SignatureComponents, SignatureFormat, SignatureScheme, SigningDomain, MessageHasher};
 pub use utils::verify_correlation;
+pub use address::{address_from_public_key, AddressError, ADDRESS_HEX_LEN, ADDRESS_PREFIX};
+pub use watch_only::{
+    derive_public_keys, discover_accounts, export_xpub, rpc_call, AccountActivity, AccountDiscovery, BalanceLookup,
+    DiscoveredAccount, RpcBalanceLookup, WatchOnlyKeys, WatchOnlyWallet, ACCOUNT_PATH, DEFAULT_GAP_LIMIT,
+};
+#[cfg(feature = "experimental")]
+pub use threshold::{
+    aggregate_signature, keygen_dealing, Dealing, KeyShare, PresignRound1, PresignRound2, PresignRound3,
+    PresignSession, Presignature, PresignatureInfo, SealedShare, SignatureShare, ThresholdError, Transport,
+};
+pub use shamir::{combine_mnemonic, combine_shares, split_mnemonic, split_secret, SecretShare, ShareError, MAX_SHARE_COUNT, MIN_SECRET_LEN};
 pub use error::{PlatariumError, Result};
 
 // Core API exports
 pub use core::{Core, TxHash};
 pub use core::asset::Asset;
-pub use core::transaction::Transaction;
-pub use core::state::{State, Address, StateSnapshot, SnapshotableState, TREASURY_ADDRESS};
-pub use core::mempool::{Mempool, MAX_FORCED_INCLUSION_QUEUE};
-pub use core::execution::{ExecutionContext, ExecutionLogic, ExecutionError, ExecutionResult};
+pub use core::asset_registry::{AssetInfo, AssetRegistry, AssetRegistryError};
+pub use core::address_bloom::AddressBloom;
+pub use core::events::{Event, EventBus, EventFilter};
+pub use core::transaction::{SigningPayload, Transaction, TransactionBuilder, TransferOutput, TxKind, TxVersion, MAX_TRANSFER_OUTPUTS};
+pub use core::chainspec::ChainSpec;
+#[cfg(feature = "experimental")]
+pub use core::discovery::{
+    PeerDescriptor,
+    DiscoveryError,
+    verify_bootnodes,
+    export_bootnodes,
+    import_bootnodes,
+};
+#[cfg(feature = "experimental")]
+pub use core::heartbeat::{
+    Heartbeat,
+    Countersignature,
+    CountersignedHeartbeat,
+    HeartbeatError,
+    HEARTBEAT_QUORUM_PCT,
+    uptime_scores,
+};
+pub use core::governance::{
+    AddressFreezeOrder,
+    Approval,
+    GovernanceAction,
+    GovernanceError,
+    GOVERNANCE_APPROVAL_THRESHOLD_PCT,
+    check_approvals,
+    check_signed_approvals,
+    enact_freeze_order,
+    lift_freeze_order,
+};
+pub use core::insurance::{
+    InsuranceClaim, InsuranceError, InsuranceFund, InvalidTxEvidence, INSURANCE_FEE_SHARE_PCT, pay_claim, record_evidence,
+    sweep_fee_share,
+};
+pub use core::vesting::{GenesisVesting, VestingError, VestingSchedule};
+#[cfg(feature = "experimental")]
+pub use core::recovery::{PendingRotation, RecoveryError, RECOVERY_DELAY_BLOCKS};
+#[cfg(feature = "experimental")]
+pub use core::session_keys::{SessionKey, SessionKeyError};
+#[cfg(feature = "experimental")]
+pub use core::standing_orders::{
+    execute_standing_orders, StandingOrder, StandingOrderError, StandingOrderId, StandingOrderReceipt,
+    StandingOrderStatus, MAX_STANDING_ORDERS_PER_ACCOUNT,
+};
+pub use core::state::{State, AccountKind, Address, AllowanceKey, SessionKeyId, StateSnapshot, SnapshotableState, TREASURY_ADDRESS, INSURANCE_FUND_ADDRESS, BURN_ADDRESS, STAKING_ADDRESS, UNBONDING_DELAY_BLOCKS};
+pub use core::overlay::{speculate_orderings, OverlayState, SpeculativeRun};
+pub use core::state_commitment::{account_leaf, token_leaf, AccountCommitment};
+pub use core::state_sync::{
+    export_snapshot, ChunkAccount, ChunkToken, SnapshotChunk, SnapshotDownload, SnapshotManifest, StateSyncError,
+    DEFAULT_CHUNK_ACCOUNTS, MAX_CHUNK_BITS,
+};
+pub use core::mempool::{
+    replacement_min_fee, Mempool, MempoolError, MempoolStats, DEFAULT_MEMPOOL_MAX_BYTES, MAX_FORCED_INCLUSION_QUEUE,
+    RBF_MIN_FEE_BUMP_PERCENT,
+};
+pub use core::invariants::{
+    check_invariant, invariant_monitor, InvariantMonitor, InvariantPolicy, InvariantStatus, InvariantViolation,
+};
+pub use core::head::{HeadInfo, HeadPublisher, HeadReceiver};
+pub use core::multisig::{
+    check_multisig_config, MultisigError, MultisigSignature, MultisigWitness, MAX_MULTISIG_SIGNERS,
+};
+pub use core::bridge::{
+    attest_foreign_header, register_wrapped_asset, verify_inbound, BridgeClaim, BridgeError, BridgeLedger,
+    ForeignHeaderProof, ForeignLightClient, BRIDGE_ADDRESS,
+};
+pub use core::outbound::{
+    messages_root, message_proof, MessageProof, OutboundMessage, OutboundQueue, GOVERNANCE_SENDER,
+    MAX_DESTINATION_LEN, MAX_MESSAGE_PAYLOAD_BYTES,
+};
+pub use core::load::{BlockLoadSample, FeeBucketRecord, NetworkLoad, LOAD_WINDOW_BLOCKS, NETWORK_CAPACITY_TPS};
+pub use core::migrations::{MigrationKind, MigrationRecord, MigrationRegistry, StateLayout, StateMigration, STATE_VERSION};
+pub use core::execution::{BlockExecution, ExecutionContext, ExecutionLogic, ExecutionError, ExecutionResult, FeeCharge};
+pub use core::receipt::{BalanceDelta, Receipt};
 pub use core::fee::{
     MicroPLP,
     BASE_TX_FEE_MICRO_PLP,
@@ -32,11 +162,15 @@ pub use core::fee::{
     MULTIPLIER_3X,
     MULTIPLIER_5X,
     calculate_load_multiplier,
+    load_multiplier_for_pct,
     calculate_fee,
     calculate_fee_micro_plp,
     calculate_fee_from_load,
     calculate_fee_from_load_micro_plp,
     fee_to_plp_string,
+    FeeRebatePolicy,
+    FEE_REBATE_MIN_REPUTATION,
+    FEE_REBATE_PCT,
 };
 pub use core::node_registry::{
     Node,
@@ -44,11 +178,17 @@ pub use core::node_registry::{
     NodeRegistry,
     NodeStatus,
     NodeRegistryError,
+    NodePage,
+    NodePages,
+    NodeSummary,
     SCORE_SCALE,
     WEIGHT_UPTIME,
     WEIGHT_LATENCY,
     WEIGHT_VOTE_ACCURACY,
     WEIGHT_STAKE,
+    CAPACITY_MAX_STEP_PCT,
+    REGISTRY_EXPORT_VERSION,
+    autotune_capacity,
 };
 pub use core::validator_selection::{
     select_validators,
@@ -89,6 +229,13 @@ pub use core::confirmation_layer::{
     apply_l1_penalties,
     ConfirmationError,
 };
+pub use core::codec::{
+    decode_message, encode_message, CodecError, NetworkMessage, CODEC_VERSION, MAX_MESSAGE_LEN,
+};
+pub use core::hashing::{
+    block_hash_v1, golden_vectors, self_check, state_commitment_v1, state_root_v1, tx_hash_v1, tx_hash_v2, GoldenInput,
+    GoldenVector, HashingError,
+};
 pub use core::block_assembly::{
     Block,
     block_finalized,
@@ -101,17 +248,32 @@ pub use core::block_assembly::{
     max_transactions_per_block,
     max_block_size_bytes,
     max_block_time_sec,
+    BlockSizeController,
+    BLOCK_TARGET_MIN_BYTES,
+    BLOCK_TARGET_MAX_BYTES,
+    BLOCK_FULLNESS_TARGET_PCT,
+    BLOCK_TARGET_MAX_STEP_DENOM,
+    BLOCK_FULLNESS_WINDOW,
+    BLOCK_TARGET_REPLAY_BLOCKS,
     assemble_block,
     process_l2_block_votes,
     apply_l2_block_penalties,
     BlockConfirmationResult,
     BlockAssemblyError,
 };
+pub use core::consensus_admission::{
+    apply_evidence, Admission, ConsensusAdmission, ConsensusMessage, Evidence, EvidenceKind, RateLimits,
+    SignedConsensusMessage,
+};
+pub use core::protocol_params::{
+    ProtocolParams, ProtocolParamsSchedule, SlashDestination, SlashingEntry, ALL_SLASHING_REASONS, MAX_STAKE_SLASH,
+};
 pub use core::slashing::{
     SlashingReason,
     SUSPENSION_THRESHOLD,
     apply_slash,
     apply_slash_with_threshold,
+    apply_slash_at,
     apply_slash_batch,
     penalty_amounts,
     SlashingError,
@@ -133,6 +295,7 @@ pub use core::state_file::{
     StateFileData,
     init_state_file,
     load_state_file,
+    load_state_file_with,
     save_state_file,
     state_apply_tx_json,
     state_credit_json,
@@ -151,22 +314,35 @@ pub use core::block_proposal_cli::{
     mempool_admit_json,
     min_fee_from_load_cli,
     select_block_txs_json,
+    select_block_txs_stored_json,
 };
 pub use core::block_proposal::{
-    block_proposal_status, mempool_admit, parse_mempool_snapshot, select_block_txs,
+    block_proposal_status, mempool_admit, parse_mempool_snapshot, select_block_txs, select_block_txs_with,
+    select_executable_block_txs, select_executable_block_txs_with,
     MempoolSnapshotEntry,
 };
+pub use core::overload::{AdmissionPolicy, OverloadError, OverloadLevel};
+pub use core::partial_tx::{
+    PartialSignature, PartialTransaction, PartialTxError, RoleSignature, SignerRole, PARTIAL_TX_PREFIX,
+    ROLE_SIGNATURE_PREFIX,
+};
+pub use core::presigned::{DetachedSignatures, PresignedTransaction, PresignedTxError, UnsignedTransaction};
+pub use core::legacy::{convert_legacy_json, convert_legacy_message, LegacyConversion, LegacySignatureStatus, LegacySignedMessage};
+pub use core::ordering::{by_arrival_then_hash, by_fee_then_hash, by_node_id, ArrivalKey, FeeKey, NodeKey};
 pub use core::consensus_params::{
     BLOCK_GAS_CAP_UPLP, BLOCK_MAX_TX_COUNT, BLOCK_MAX_WAIT_SEC, BLOCK_MIN_GAS_UPLP,
-    BLOCK_MIN_TX_COUNT, FAUCET_ADDRESS,
+    BLOCK_MIN_TX_COUNT, FAUCET_ADDRESS, OVERLOAD_MAX_PENDING_PER_SENDER, OVERLOAD_MIN_FEE_UPLP,
+    OVERLOAD_PEER_STRIKE_LIMIT, MAX_VOTES_PER_SLOT, MAX_PROPOSALS_PER_SLOT, MAX_TRACKED_SLOTS_PER_NODE,
 };
 pub use storage::{
-    AccountRecord, BlockCommit, BlockRecordStored, ReceiptRecord, RocksStore, SNAPSHOT_INTERVAL,
-    SnapshotMeta, SCHEMA_VERSION, bootstrap_from_snapshot, build_commit_batch, commit_block,
-    create_snapshot_if_due, get_account, get_block, get_head, get_receipt, get_state_root, get_tx,
-    list_snapshots, list_tx_hashes_for_address, migrate_json_to_rocks, open_store,
-    rocks_bootstrap_snapshot_json, rocks_commit_block_json, rocks_get_account_json,
+    AccountRecord, BlockCommit, BlockRecordStored, FEE_ASSET, LedgerEntry, MAX_RECEIPT_PAGE_SIZE,
+    ReceiptPage, export_ledger, ledger_entries_for_tx, ledger_to_csv, rocks_export_ledger,
+    ReceiptRecord, RocksStore, SNAPSHOT_INTERVAL, SnapshotMeta, SCHEMA_VERSION,
+    block_size_target, bootstrap_from_snapshot, build_commit_batch, commit_block, create_snapshot_if_due, get_account,
+    get_block, get_head, get_receipt, get_state_root, get_tx, list_block_receipts, list_receipts_for_address,
+    list_snapshots, list_tx_hashes_for_address, migrate_json_to_rocks, open_store, recent_load_samples,
+    rocks_block_size_target_json, rocks_bootstrap_snapshot_json, rocks_commit_block_json, rocks_get_account_json,
     rocks_get_block_json, rocks_get_head_json, rocks_get_receipt_json, rocks_get_snapshot_json,
-    rocks_get_state_root_json, rocks_get_tx_json, rocks_list_address_txs_json,
-    rocks_list_snapshots_json,
+    rocks_get_state_root_json, rocks_get_tx_json, rocks_list_address_receipts_json,
+    rocks_list_address_txs_json, rocks_list_snapshots_json,
 };
\ No newline at end of file
@@ -15,18 +15,24 @@ struct Cli {
 enum Commands {
     /// Consensus: selection percent from load (Gateway uses this instead of duplicating logic). Output: JSON {"percent": 10|15|20|25|30}
     SelectionPercentFromLoad {
-        /// Load percentage 0..100 (e.g. LoadScore×100/SCORE_SCALE)
+        /// Pending transactions in the mempool
         #[arg(long)]
-        load_pct: u64,
+        pending_count: usize,
+        /// RocksDB directory whose latest block headers give the throughput part of the load
+        #[arg(long)]
+        db_path: Option<String>,
     },
     /// Consensus: committee size from candidate count and load (all logic in Core). Output: JSON {"count": N}
     CommitteeCount {
         /// Number of candidates (e.g. 1 + peer count)
         #[arg(long)]
         candidates: usize,
-        /// Load percentage 0..100
+        /// Pending transactions in the mempool
+        #[arg(long)]
+        pending_count: usize,
+        /// RocksDB directory whose latest block headers give the throughput part of the load
         #[arg(long)]
-        load_pct: u64,
+        db_path: Option<String>,
     },
     /// Consensus: select committee by weight (Gateway uses Core for deterministic selection). Input: JSON [{"id":"...","weight":N},...]. Output: JSON ["id1","id2",...]
     SelectCommittee {
@@ -41,13 +47,25 @@ enum Commands {
         count: usize,
     },
     /// Generate a new BIP39 mnemonic phrase and alphanumeric code
-    GenerateMnemonic,
+    GenerateMnemonic {
+        /// Wordlist: english, japanese, spanish, french, italian, czech, korean, portuguese, chinese-simplified, chinese-traditional
+        #[arg(short, long, default_value = "english", value_parser = parse_language)]
+        language: Language,
+
+        /// Number of words: 12, 15, 18, 21 or 24 (128–256 bits of entropy)
+        #[arg(short, long, default_value_t = DEFAULT_MNEMONIC_WORDS)]
+        words: usize,
+    },
     
     /// Generate cryptographic keys from a mnemonic phrase
     GenerateKeys {
         /// BIP39 mnemonic phrase
         #[arg(short, long)]
         mnemonic: String,
+
+        /// Wordlist of the mnemonic (see generate-mnemonic)
+        #[arg(short, long, default_value = "english", value_parser = parse_language)]
+        language: Language,
         
         /// Alphanumeric code (optional, will be generated if not provided)
         #[arg(short, long)]
@@ -57,9 +75,17 @@ enum Commands {
         #[arg(short, long, default_value = "0")]
         seed_index: u32,
         
-        /// Custom derivation path (optional)
+        /// Custom derivation path, e.g. m/44'/60'/1'/0/0 (optional)
         #[arg(short, long)]
-        path: Option<String>,
+        path: Option<DerivationPath>,
+
+        /// Derive with every path level hardened (no xpub export; custom paths must be fully hardened)
+        #[arg(long)]
+        hardened_only: bool,
+
+        /// Network whose signature key domain to use: mainnet, testnet or a chain id
+        #[arg(long, default_value = "mainnet")]
+        network: NetworkId,
     },
     
     /// Sign a message with both keys (main + HKDF)
@@ -259,6 +285,17 @@ enum Commands {
         #[arg(long)]
         address: String,
     },
+    /// RocksDB: export a double-entry ledger for a height range (csv or json)
+    RocksExportLedger {
+        #[arg(long)]
+        db_path: String,
+        #[arg(long)]
+        from: u64,
+        #[arg(long)]
+        to: u64,
+        #[arg(long, default_value = "csv")]
+        format: String,
+    },
     /// RocksDB: atomic commit block (BlockCommit JSON)
     RocksCommitBlock {
         #[arg(long)]
@@ -293,6 +330,9 @@ enum Commands {
         /// Listen address, e.g. 127.0.0.1:19500 or unix:/tmp/platarium-core.sock
         #[arg(long)]
         listen: String,
+        /// Invariant violation policy: panic, log or halt (stop proposing blocks). Default: panic in debug builds, log in release builds
+        #[arg(long, value_parser = InvariantPolicy::parse)]
+        invariant_policy: Option<InvariantPolicy>,
     },
 
     /// Sign a transaction with both keys; outputs full signed tx JSON (Gateway adds to mempool).
@@ -316,10 +356,324 @@ enum Commands {
         /// JSON array of write addresses, e.g. []
         #[arg(long, default_value = "[]")]
         writes: String,
+        #[arg(short, long, required_unless_present = "seed", conflicts_with = "seed")]
+        mnemonic: Option<String>,
+        #[arg(short, long, required_unless_present = "seed", conflicts_with = "seed")]
+        alphanumeric: Option<String>,
+        /// Sign with a seed saved by `seed-save` instead of --mnemonic/--alphanumeric
+        #[arg(long)]
+        seed: Option<String>,
+        /// Seed directory for the file fallback (default: ~/.platarium/seeds)
+        #[arg(long)]
+        seed_dir: Option<String>,
+        /// Environment variable holding the seed's keystore password
+        #[arg(long, default_value = "PLATARIUM_KEYSTORE_PASSWORD")]
+        password_env: String,
+        /// Network whose signing keys to use: mainnet, testnet or a chain id; off mainnet its chain id is also part of the transaction hash
+        #[arg(long, default_value = "mainnet")]
+        network: NetworkId,
+    },
+
+    /// Convert a legacy sign-message payload into a canonical transaction (flags re-sign if needed)
+    ConvertLegacyMessage {
+        /// Legacy payload JSON (or @file)
+        #[arg(long)]
+        payload: String,
+    },
+
+    /// Export the account xpub for watch-only use (run on the signing device)
+    ExportXpub {
+        #[arg(short, long)]
+        mnemonic: String,
+        #[arg(short, long)]
+        alphanumeric: String,
+    },
+
+    /// Print a QR code for a payment request (address, optional amount) or a prebuilt payload URI
+    #[cfg(feature = "qr")]
+    Qr {
+        /// Address to be paid
+        #[arg(long, required_unless_present = "uri", conflicts_with = "uri")]
+        address: Option<String>,
+        /// Amount in the asset's minimal units
+        #[arg(long)]
+        amount: Option<u128>,
+        /// Asset: "PLP" or "Token:XXX"
+        #[arg(long, default_value = "PLP")]
+        asset: String,
+        #[arg(long)]
+        label: Option<String>,
+        #[arg(long)]
+        message: Option<String>,
+        /// Encode this payload as is (e.g. a platarium-tx: URI)
+        #[arg(long)]
+        uri: Option<String>,
+        /// Write an SVG file instead of printing to the terminal
+        #[arg(long)]
+        svg: Option<String>,
+    },
+
+    /// Print both private keys of a mnemonic as checksummed WIF strings
+    ExportWif {
+        #[arg(short, long)]
+        mnemonic: String,
+        #[arg(short, long)]
+        alphanumeric: String,
+        #[arg(short, long, default_value = "0")]
+        seed_index: u32,
+        /// Mark the keys as testnet keys
+        #[arg(long)]
+        testnet: bool,
+    },
+
+    /// Check a pair of WIF keys and print the key pair they hold as JSON
+    ImportWif {
+        /// Main key (WIF of the PSx key)
+        #[arg(long)]
+        main: String,
+        /// Signature key (WIF of the Sx key)
+        #[arg(long)]
+        signature: String,
+    },
+
+    /// Encrypt the keys derived from a mnemonic into a password-protected keystore file
+    KeystoreExport {
+        #[arg(short, long)]
+        mnemonic: String,
+        #[arg(short, long)]
+        alphanumeric: String,
+        #[arg(short, long, default_value = "0")]
+        seed_index: u32,
+        /// Output keystore path
+        #[arg(long)]
+        out: String,
+        /// Environment variable holding the keystore password
+        #[arg(long, default_value = "PLATARIUM_KEYSTORE_PASSWORD")]
+        password_env: String,
+    },
+
+    /// Decrypt a keystore file and print its keys as JSON
+    KeystoreImport {
+        /// Keystore path
+        #[arg(long)]
+        file: String,
+        /// Environment variable holding the keystore password
+        #[arg(long, default_value = "PLATARIUM_KEYSTORE_PASSWORD")]
+        password_env: String,
+    },
+
+    /// Encrypt a seed under a name in the OS credential store (or the seed directory). Reads the mnemonic and
+    /// then the alphanumeric part as two lines on stdin, so neither appears on the command line.
+    SeedSave {
+        #[arg(long)]
+        name: String,
+        #[arg(short, long, default_value = "0")]
+        seed_index: u32,
+        /// Seed directory for the file fallback (default: ~/.platarium/seeds)
+        #[arg(long)]
+        seed_dir: Option<String>,
+        /// Environment variable holding the keystore password
+        #[arg(long, default_value = "PLATARIUM_KEYSTORE_PASSWORD")]
+        password_env: String,
+    },
+
+    /// Decrypt a saved seed and print its keys as JSON
+    SeedShow {
+        #[arg(long)]
+        name: String,
+        /// Seed directory for the file fallback (default: ~/.platarium/seeds)
+        #[arg(long)]
+        seed_dir: Option<String>,
+        /// Environment variable holding the keystore password
+        #[arg(long, default_value = "PLATARIUM_KEYSTORE_PASSWORD")]
+        password_env: String,
+    },
+
+    /// Remove a saved seed
+    SeedDelete {
+        #[arg(long)]
+        name: String,
+        /// Seed directory for the file fallback (default: ~/.platarium/seeds)
+        #[arg(long)]
+        seed_dir: Option<String>,
+    },
+
+    /// Check a validator registry backup (version, integrity hash, ordering) and print its summary as JSON
+    RegistryVerify {
+        /// Backup written by NodeRegistry::export
+        #[arg(long)]
+        file: String,
+        /// Also rewrite the verified registry here in canonical form
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Split a mnemonic into share phrases, any `threshold` of which restore it
+    SplitMnemonic {
+        #[arg(short, long)]
+        mnemonic: String,
+        /// Wordlist of the mnemonic (see generate-mnemonic)
+        #[arg(short, long, default_value = "english", value_parser = parse_language)]
+        language: Language,
+        /// Shares required to restore
+        #[arg(short, long)]
+        threshold: u8,
+        /// Shares to create (at most 16)
+        #[arg(short, long)]
+        shares: u8,
+    },
+
+    /// Rebuild a mnemonic from share phrases made by split-mnemonic
+    CombineShares {
+        /// Share phrase (repeat once per share)
+        #[arg(long = "share", required = true)]
+        shares: Vec<String>,
+        /// Wordlist of the restored mnemonic
+        #[arg(short, long, default_value = "english", value_parser = parse_language)]
+        language: Language,
+    },
+
+    /// Derive an independent child mnemonic (BIP-85) from a mnemonic and alphanumeric part
+    DeriveChildMnemonic {
         #[arg(short, long)]
         mnemonic: String,
         #[arg(short, long)]
         alphanumeric: String,
+        /// Wordlist of both mnemonics (see generate-mnemonic)
+        #[arg(short, long, default_value = "english", value_parser = parse_language)]
+        language: Language,
+        /// Words in the child mnemonic (12, 15, 18, 21 or 24)
+        #[arg(short, long, default_value_t = 24)]
+        words: usize,
+        /// Child index; each index is a separate wallet
+        #[arg(short, long, default_value_t = 0)]
+        index: u32,
+    },
+
+    /// Validate a Px address and print its canonical and checksummed forms
+    ValidateAddress {
+        #[arg(long)]
+        address: String,
+    },
+
+    /// Search for a key whose address starts with a hex prefix (e.g. Px02beef)
+    VanityAddress {
+        #[arg(long)]
+        prefix: String,
+        /// Search seed indices of this mnemonic; without it, search ephemeral keys from --seed-hex
+        #[arg(short, long)]
+        mnemonic: Option<String>,
+        #[arg(short, long)]
+        alphanumeric: Option<String>,
+        /// Wordlist of the mnemonic (see generate-mnemonic)
+        #[arg(short, long, default_value = "english", value_parser = parse_language)]
+        language: Language,
+        /// First seed index to try (mnemonic search)
+        #[arg(long, default_value = "0")]
+        start_index: u32,
+        /// 32-byte hex seed for ephemeral keys (random if omitted)
+        #[arg(long)]
+        seed_hex: Option<String>,
+        /// Worker threads (default: all cores)
+        #[arg(long)]
+        threads: Option<usize>,
+        #[arg(long)]
+        max_attempts: Option<u64>,
+        /// Give up after this many seconds
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+    },
+
+    /// Restore a wallet: find the used seed indices of a mnemonic by gap-limit scanning over the Core RPC
+    DiscoverAccounts {
+        #[arg(short, long)]
+        mnemonic: String,
+        #[arg(short, long)]
+        alphanumeric: String,
+        /// Core RPC endpoint (host:port)
+        #[arg(long)]
+        rpc: String,
+        /// State file path on the RPC server
+        #[arg(long)]
+        state_file: String,
+        /// Stop after this many unused indices in a row
+        #[arg(long, default_value_t = DEFAULT_GAP_LIMIT)]
+        gap_limit: u32,
+    },
+
+    /// Watch-only: derive receive addresses from an xpub
+    WatchAddresses {
+        #[arg(long)]
+        xpub: String,
+        #[arg(long, default_value = "0")]
+        start: u32,
+        #[arg(long, default_value = "10")]
+        count: u32,
+    },
+
+    /// Watch-only: query balances and nonces of derived addresses via Core RPC
+    WatchBalances {
+        #[arg(long)]
+        xpub: String,
+        /// Core RPC endpoint (host:port)
+        #[arg(long)]
+        rpc: String,
+        /// State file path on the RPC server
+        #[arg(long)]
+        state_file: String,
+        #[arg(long, default_value = "PLP")]
+        asset: String,
+        #[arg(long, default_value = "0")]
+        start: u32,
+        #[arg(long, default_value = "10")]
+        count: u32,
+        /// RocksDB path on the RPC server; when set, committed tx hashes are listed too
+        #[arg(long)]
+        db_path: Option<String>,
+    },
+
+    /// Watch-only: build an unsigned transfer from a derived address for offline signing
+    WatchUnsignedTx {
+        #[arg(long)]
+        xpub: String,
+        #[arg(long, default_value = "0")]
+        index: u32,
+        #[arg(long)]
+        to: String,
+        #[arg(long, default_value = "PLP")]
+        asset: String,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        fee_uplp: u64,
+        #[arg(long)]
+        nonce: u64,
+    },
+
+    /// Export an unsigned transaction for an external signer. Output: JSON {"unsigned", "hash", "signing_payload"} (payload hex); sign SHA256(payload) with both keys
+    ExportUnsignedTx {
+        /// Transaction JSON (or @file), e.g. from watch-unsigned-tx
+        #[arg(long)]
+        tx: String,
+        /// Main public key (hex); defaults to the one in the transaction
+        #[arg(long)]
+        pub_main: Option<String>,
+        /// Derived public key (hex) the derived signature is checked against
+        #[arg(long)]
+        pub_derived: Option<String>,
+    },
+
+    /// Attach external signatures to an exported unsigned transaction; outputs the signed tx JSON
+    AttachSignatures {
+        /// Output of export-unsigned-tx, or its "unsigned" transaction (or @file)
+        #[arg(long)]
+        unsigned: String,
+        /// Compact main signature (hex)
+        #[arg(long)]
+        sig_main: String,
+        /// Compact derived signature (hex)
+        #[arg(long)]
+        sig_derived: String,
     },
 }
 
@@ -327,20 +681,27 @@ fn main() {
     let cli = Cli::parse();
 
     let result = match cli.command {
-        Commands::SelectionPercentFromLoad { load_pct } => handle_selection_percent_from_load(load_pct),
-        Commands::CommitteeCount { candidates, load_pct } => handle_committee_count(candidates, load_pct),
+        Commands::SelectionPercentFromLoad { pending_count, db_path } => {
+            handle_selection_percent_from_load(pending_count, db_path)
+        }
+        Commands::CommitteeCount { candidates, pending_count, db_path } => {
+            handle_committee_count(candidates, pending_count, db_path)
+        }
         Commands::SelectCommittee {
             candidates,
             seed_hex,
             count,
         } => handle_select_committee(candidates, seed_hex, count),
-        Commands::GenerateMnemonic => handle_generate_mnemonic(),
+        Commands::GenerateMnemonic { language, words } => handle_generate_mnemonic(language, words),
         Commands::GenerateKeys {
             mnemonic,
+            language,
             alphanumeric,
             seed_index,
             path,
-        } => handle_generate_keys(mnemonic, alphanumeric, seed_index, path),
+            hardened_only,
+            network,
+        } => handle_generate_keys(mnemonic, language, alphanumeric, seed_index, path, hardened_only, network),
         Commands::SignMessage {
             message,
             mnemonic,
@@ -400,6 +761,9 @@ fn main() {
         Commands::RocksListAddressTxs { db_path, address } => {
             handle_rocks_list_address_txs(db_path, address)
         }
+        Commands::RocksExportLedger { db_path, from, to, format } => {
+            handle_rocks_export_ledger(db_path, from, to, format)
+        }
         Commands::RocksCommitBlock { db_path, commit } => handle_rocks_commit_block(db_path, commit),
         Commands::RocksListSnapshots { db_path } => handle_rocks_list_snapshots(db_path),
         Commands::RocksBootstrapSnapshot { db_path, snapshot } => {
@@ -421,8 +785,109 @@ fn main() {
             writes,
             mnemonic,
             alphanumeric,
-        } => handle_sign_transaction(from, to, asset, amount, fee_uplp, nonce, reads, writes, mnemonic, alphanumeric),
-        Commands::Serve { listen } => handle_serve(listen),
+            seed,
+            seed_dir,
+            password_env,
+            network,
+        } => {
+            let keys = match seed {
+                Some(name) => load_seed(&name, seed_dir, &password_env),
+                None => Ok((mnemonic.unwrap_or_default(), alphanumeric.unwrap_or_default())),
+            };
+            keys.and_then(|(mnemonic, alphanumeric)| {
+                handle_sign_transaction(
+                    from,
+                    to,
+                    asset,
+                    amount,
+                    fee_uplp,
+                    nonce,
+                    reads,
+                    writes,
+                    mnemonic,
+                    alphanumeric,
+                    network,
+                )
+            })
+        }
+        Commands::Serve { listen, invariant_policy } => handle_serve(listen, invariant_policy),
+        Commands::ConvertLegacyMessage { payload } => handle_convert_legacy_message(payload),
+        Commands::ExportXpub { mnemonic, alphanumeric } => handle_export_xpub(mnemonic, alphanumeric),
+        #[cfg(feature = "qr")]
+        Commands::Qr { address, amount, asset, label, message, uri, svg } => {
+            handle_qr(address, amount, asset, label, message, uri, svg)
+        }
+        Commands::ExportWif { mnemonic, alphanumeric, seed_index, testnet } => {
+            handle_export_wif(mnemonic, alphanumeric, seed_index, testnet)
+        }
+        Commands::ImportWif { main, signature } => handle_import_wif(main, signature),
+        Commands::KeystoreExport { mnemonic, alphanumeric, seed_index, out, password_env } => {
+            handle_keystore_export(mnemonic, alphanumeric, seed_index, out, password_env)
+        }
+        Commands::KeystoreImport { file, password_env } => handle_keystore_import(file, password_env),
+        Commands::SeedSave { name, seed_index, seed_dir, password_env } => {
+            handle_seed_save(name, seed_index, seed_dir, password_env)
+        }
+        Commands::SeedShow { name, seed_dir, password_env } => handle_seed_show(name, seed_dir, password_env),
+        Commands::SeedDelete { name, seed_dir } => handle_seed_delete(name, seed_dir),
+        Commands::RegistryVerify { file, out } => handle_registry_verify(file, out),
+        Commands::SplitMnemonic { mnemonic, language, threshold, shares } => {
+            handle_split_mnemonic(mnemonic, language, threshold, shares)
+        }
+        Commands::CombineShares { shares, language } => handle_combine_shares(shares, language),
+        Commands::DeriveChildMnemonic { mnemonic, alphanumeric, language, words, index } => {
+            handle_derive_child_mnemonic(mnemonic, alphanumeric, language, words, index)
+        }
+        Commands::ValidateAddress { address } => handle_validate_address(address),
+        Commands::VanityAddress {
+            prefix,
+            mnemonic,
+            alphanumeric,
+            language,
+            start_index,
+            seed_hex,
+            threads,
+            max_attempts,
+            timeout_secs,
+        } => handle_vanity_address(
+            prefix,
+            mnemonic,
+            alphanumeric,
+            language,
+            start_index,
+            seed_hex,
+            threads,
+            max_attempts,
+            timeout_secs,
+        ),
+        Commands::DiscoverAccounts { mnemonic, alphanumeric, rpc, state_file, gap_limit } => {
+            handle_discover_accounts(mnemonic, alphanumeric, rpc, state_file, gap_limit)
+        }
+        Commands::WatchAddresses { xpub, start, count } => handle_watch_addresses(xpub, start, count),
+        Commands::WatchBalances {
+            xpub,
+            rpc,
+            state_file,
+            asset,
+            start,
+            count,
+            db_path,
+        } => handle_watch_balances(xpub, rpc, state_file, asset, start, count, db_path),
+        Commands::WatchUnsignedTx {
+            xpub,
+            index,
+            to,
+            asset,
+            amount,
+            fee_uplp,
+            nonce,
+        } => handle_watch_unsigned_tx(xpub, index, to, asset, amount, fee_uplp, nonce),
+        Commands::ExportUnsignedTx { tx, pub_main, pub_derived } => handle_export_unsigned_tx(tx, pub_main, pub_derived),
+        Commands::AttachSignatures {
+            unsigned,
+            sig_main,
+            sig_derived,
+        } => handle_attach_signatures(unsigned, sig_main, sig_derived),
     };
 
     if let Err(e) = result {
@@ -431,21 +896,54 @@ fn main() {
     }
 }
 
-fn handle_serve(listen: String) -> std::result::Result<(), Box<dyn std::error::Error>> {
+fn handle_serve(
+    listen: String,
+    invariant_policy: Option<InvariantPolicy>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    if let Some(policy) = invariant_policy {
+        invariant_monitor().set_policy(policy);
+    }
+    invariant_monitor().set_violation_hook(|v| {
+        eprintln!("[invariant] violated {}: {}", v.name, v.detail);
+        if v.halted_block_production {
+            eprintln!("[invariant] block production halted");
+        }
+    });
     platarium_core::core::core_rpc::run_serve(&listen)?;
     Ok(())
 }
 
-fn handle_selection_percent_from_load(load_pct: u64) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let percent = selection_percent_from_load_pct(load_pct).map_err(|e| e.to_string())?;
-    let out = serde_json::json!({ "percent": percent });
+/// Network load from `pending_count` and, with `db_path`, the latest stored block headers.
+fn network_load(
+    pending_count: usize,
+    db_path: Option<String>,
+) -> std::result::Result<NetworkLoad, Box<dyn std::error::Error>> {
+    let samples = match db_path {
+        Some(path) => recent_load_samples(&RocksStore::open(std::path::Path::new(&path))?)?,
+        None => Vec::new(),
+    };
+    Ok(NetworkLoad::estimate(pending_count, &samples))
+}
+
+fn handle_selection_percent_from_load(
+    pending_count: usize,
+    db_path: Option<String>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let load = network_load(pending_count, db_path)?;
+    let percent = load.l1_selection_percent()?;
+    let out = serde_json::json!({ "percent": percent, "network_load_pct": load.network_load_pct });
     println!("{}", serde_json::to_string(&out)?);
     Ok(())
 }
 
-fn handle_committee_count(candidates: usize, load_pct: u64) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let count = committee_count(candidates, load_pct);
-    let out = serde_json::json!({ "count": count });
+fn handle_committee_count(
+    candidates: usize,
+    pending_count: usize,
+    db_path: Option<String>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let load = network_load(pending_count, db_path)?;
+    let count = load.committee_count(candidates);
+    let out = serde_json::json!({ "count": count, "network_load_pct": load.network_load_pct });
     println!("{}", serde_json::to_string(&out)?);
     Ok(())
 }
@@ -474,8 +972,12 @@ fn handle_select_committee(
     Ok(())
 }
 
-fn handle_generate_mnemonic() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let (mnemonic, alphanumeric) = generate_mnemonic()?;
+fn handle_generate_mnemonic(
+    language: Language,
+    words: usize,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let config = MnemonicConfig::new(words)?.with_language(language);
+    let (mnemonic, alphanumeric) = generate_mnemonic_with(&config)?;
     
     println!("Mnemonic: {}", mnemonic);
     println!("Alphanumeric: {}", alphanumeric);
@@ -483,32 +985,103 @@ fn handle_generate_mnemonic() -> std::result::Result<(), Box<dyn std::error::Err
     Ok(())
 }
 
+fn handle_registry_verify(file: String, out: Option<String>) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let registry = NodeRegistry::import(std::io::BufReader::new(std::fs::File::open(&file)?))?;
+    let integrity = match out {
+        Some(out) => {
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(out)?);
+            let integrity = registry.export(&mut writer)?;
+            std::io::Write::flush(&mut writer)?;
+            integrity
+        }
+        None => registry.export(std::io::sink())?,
+    };
+    let out = serde_json::json!({
+        "version": REGISTRY_EXPORT_VERSION,
+        "node_count": registry.len(),
+        "active": registry.eligible_summaries().len(),
+        "integrity": integrity,
+    });
+    println!("{}", serde_json::to_string(&out)?);
+    Ok(())
+}
+
+fn handle_split_mnemonic(
+    mnemonic: String,
+    language: Language,
+    threshold: u8,
+    shares: u8,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    for (i, phrase) in split_mnemonic(&mnemonic, language, threshold, shares)?.iter().enumerate() {
+        println!("Share {}: {}", i + 1, phrase);
+    }
+    Ok(())
+}
+
+fn handle_combine_shares(shares: Vec<String>, language: Language) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    println!("Mnemonic: {}", combine_mnemonic(&shares, language)?);
+    Ok(())
+}
+
+fn handle_derive_child_mnemonic(
+    mnemonic: String,
+    alphanumeric: String,
+    language: Language,
+    words: usize,
+    index: u32,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let child = KeyGenerator::default()
+        .with_language(language)
+        .derive_child_mnemonic(&mnemonic, &alphanumeric, words, index)?;
+    println!("Child mnemonic {}: {}", index, child);
+    Ok(())
+}
+
+/// Prints a warning to stderr when a user-supplied alphanumeric part looks weak.
+fn warn_weak_alphanumeric(code: &str) {
+    if let Some(warning) = CharsetPolicy::default().strength_warning(code) {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
 fn handle_generate_keys(
     mnemonic: String,
+    language: Language,
     alphanumeric: Option<String>,
     seed_index: u32,
-    path: Option<String>,
+    path: Option<DerivationPath>,
+    hardened_only: bool,
+    network: NetworkId,
 ) -> std::result::Result<(), Box<dyn std::error::Error>> {
     // Validate mnemonic
-    if !validate_mnemonic(&mnemonic) {
+    if !validate_mnemonic_in(&mnemonic, language) {
         return Err("Invalid mnemonic phrase".into());
     }
 
     // Use provided alphanumeric or generate new one
+    if let Some(code) = &alphanumeric {
+        warn_weak_alphanumeric(code);
+    }
     let alphanumeric_part = alphanumeric.unwrap_or_else(|| {
-        generate_alphanumeric_part(12).unwrap_or_else(|_| {
+        CharsetPolicy::default().generate().unwrap_or_else(|_| {
             eprintln!("Warning: Failed to generate alphanumeric, using empty string");
             String::new()
         })
     });
 
     // Create key generator
-    let key_gen = KeyGenerator::new(seed_index, None, None, path.clone())?;
+    let mut key_gen = KeyGenerator::new(seed_index, None, None, path)?
+        .with_language(language)
+        .with_network(&network);
+    if hardened_only {
+        key_gen = key_gen.hardened_only();
+    }
     
     // Restore keys from mnemonic
     let keys = key_gen.restore_keys(&mnemonic, &alphanumeric_part, seed_index, path)?;
     
     println!("Public Key: {}", keys.public_key);
+    println!("Address (checksummed): {}", address::encode(&keys.public_key)?);
     println!("Private Key: {}", keys.private_key);
     println!("Signature Key: {}", keys.signature_key);
     println!("Derivation Path: {}", keys.derivation_paths.main_path);
@@ -523,7 +1096,7 @@ fn handle_sign_message(
     alphanumeric: String,
 ) -> std::result::Result<(), Box<dyn std::error::Error>> {
     // Validate mnemonic
-    if !validate_mnemonic(&mnemonic) {
+    if mnemonic_language(&mnemonic).is_err() {
         return Err("Invalid mnemonic phrase".into());
     }
 
@@ -752,6 +1325,16 @@ fn handle_rocks_list_address_txs(
     Ok(())
 }
 
+fn handle_rocks_export_ledger(
+    db_path: String,
+    from: u64,
+    to: u64,
+    format: String,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    print!("{}", rocks_export_ledger(&db_path, from, to, &format)?);
+    Ok(())
+}
+
 fn handle_rocks_commit_block(
     db_path: String,
     commit: String,
@@ -824,6 +1407,7 @@ fn handle_select_block_txs(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_sign_transaction(
     from: String,
     to: String,
@@ -835,9 +1419,10 @@ fn handle_sign_transaction(
     writes: String,
     mnemonic: String,
     alphanumeric: String,
+    network: NetworkId,
 ) -> std::result::Result<(), Box<dyn std::error::Error>> {
     use std::collections::HashSet;
-    if !validate_mnemonic(&mnemonic) {
+    if mnemonic_language(&mnemonic).is_err() {
         return Err("Invalid mnemonic phrase".into());
     }
     let reads_vec: Vec<String> = serde_json::from_str(&reads).map_err(|e| format!("invalid reads JSON: {}", e))?;
@@ -851,35 +1436,16 @@ fn handle_sign_transaction(
     } else {
         Asset::Token(asset.clone())
     };
-    let canonical_asset = asset_enum.as_canonical();
-    let mut reads_sorted: Vec<String> = reads_set.iter().cloned().collect();
-    reads_sorted.sort();
-    let mut writes_sorted: Vec<String> = writes_set.iter().cloned().collect();
-    writes_sorted.sort();
-    #[derive(serde::Serialize)]
-    struct TxHashData {
-        from: String,
-        to: String,
-        asset: String,
-        amount: u128,
-        fee_uplp: u128,
-        nonce: u64,
-        reads: Vec<String>,
-        writes: Vec<String>,
-    }
+    let from = address::normalize(&from)?;
+    let to = address::normalize(&to)?;
     let amount_u128 = amount as u128;
     let fee_uplp_u128 = fee_uplp as u128;
-    let message = TxHashData {
-        from: from.clone(),
-        to: to.clone(),
-        asset: canonical_asset,
-        amount: amount_u128,
-        fee_uplp: fee_uplp_u128,
-        nonce,
-        reads: reads_sorted,
-        writes: writes_sorted,
-    };
-    let sig_result = sign_with_both_keys(&message, &mnemonic, &alphanumeric)?;
+    let chain_id = (network != NetworkId::Mainnet).then(|| network.chain_id().to_string());
+    let message = SigningPayload::new(&from, &to, &asset_enum, amount_u128, fee_uplp_u128, nonce)
+        .reads(&reads_set)
+        .writes(&writes_set)
+        .chain_id(chain_id.as_deref());
+    let sig_result = sign_with_both_keys_for_network(&message, &mnemonic, &alphanumeric, &network)?;
     let sig_main = normalize_signature_hex(&sig_result.signatures[0].signature_compact);
     let sig_derived = normalize_signature_hex(&sig_result.signatures[1].signature_compact);
     let pub_main = sig_result.signatures[0].pub_key.clone();
@@ -887,7 +1453,7 @@ fn handle_sign_transaction(
     // Output Gateway-compatible JSON (asset as string "PLP" or "Token:X")
     let reads_out: Vec<String> = reads_set.iter().cloned().collect();
     let writes_out: Vec<String> = writes_set.iter().cloned().collect();
-    let out = serde_json::json!({
+    let mut out = serde_json::json!({
         "hash": sig_result.hash,
         "from": from,
         "to": to,
@@ -902,6 +1468,404 @@ fn handle_sign_transaction(
         "pub_main": pub_main,
         "pub_derived": pub_derived,
     });
+    if let Some(chain_id) = chain_id {
+        out["chain_id"] = serde_json::Value::String(chain_id);
+    }
+    println!("{}", serde_json::to_string(&out)?);
+    Ok(())
+}
+fn handle_convert_legacy_message(payload: String) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let payload = resolve_cli_json_arg(&payload)?;
+    let out = convert_legacy_json(&payload)?;
+    println!("{}", serde_json::to_string(&out)?);
+    Ok(())
+}
+
+fn keystore_password(password_env: &str) -> std::result::Result<String, Box<dyn std::error::Error>> {
+    match std::env::var(password_env) {
+        Ok(pw) if !pw.is_empty() => Ok(pw),
+        _ => Err(format!("set the keystore password in ${}", password_env).into()),
+    }
+}
+
+fn handle_keystore_export(
+    mnemonic: String,
+    alphanumeric: String,
+    seed_index: u32,
+    out: String,
+    password_env: String,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let password = keystore_password(&password_env)?;
+    warn_weak_alphanumeric(&alphanumeric);
+    let language = mnemonic_language(&mnemonic).map_err(|_| "Invalid mnemonic phrase")?;
+    let keys = KeyGenerator::new(seed_index, None, None, None)?
+        .with_language(language)
+        .restore_keys(&mnemonic, &alphanumeric, seed_index, None)?;
+    let keystore = Keystore::encrypt_key_pair(&keys, &password, KdfParams::default())?;
+    keystore.save(std::path::Path::new(&out))?;
+    println!("{}", serde_json::json!({"address": keys.public_key, "keystore": out}));
+    Ok(())
+}
+
+fn handle_keystore_import(file: String, password_env: String) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let password = keystore_password(&password_env)?;
+    let keystore = Keystore::load(std::path::Path::new(&file))?;
+    println!("{}", serde_json::to_string_pretty(&keystore.decrypt(&password)?)?);
+    Ok(())
+}
+
+fn seed_store(seed_dir: Option<String>) -> Box<dyn SeedStore> {
+    let dir = seed_dir.map(std::path::PathBuf::from).unwrap_or_else(|| {
+        std::env::var_os("HOME")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_default()
+            .join(".platarium")
+            .join("seeds")
+    });
+    default_seed_store(&dir)
+}
+
+/// Mnemonic and alphanumeric part of a saved seed.
+fn load_seed(
+    name: &str,
+    seed_dir: Option<String>,
+    password_env: &str,
+) -> std::result::Result<(String, String), Box<dyn std::error::Error>> {
+    let password = keystore_password(password_env)?;
+    let keys = seed_store(seed_dir).load(name)?.decrypt_key_pair(&password)?;
+    Ok((keys.mnemonic.clone(), keys.alphanumeric_part.clone()))
+}
+
+fn handle_seed_save(
+    name: String,
+    seed_index: u32,
+    seed_dir: Option<String>,
+    password_env: String,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    validate_seed_name(&name)?;
+    let password = keystore_password(&password_env)?;
+    let mut lines = std::io::stdin().lines();
+    let mut next_line = |what: &str| -> std::result::Result<String, Box<dyn std::error::Error>> {
+        Ok(lines.next().ok_or(format!("expected the {} on stdin", what))??.trim().to_string())
+    };
+    let mnemonic = next_line("mnemonic")?;
+    let alphanumeric = next_line("alphanumeric part")?;
+    warn_weak_alphanumeric(&alphanumeric);
+    let language = mnemonic_language(&mnemonic).map_err(|_| "Invalid mnemonic phrase")?;
+    let keys = KeyGenerator::new(seed_index, None, None, None)?
+        .with_language(language)
+        .restore_keys(&mnemonic, &alphanumeric, seed_index, None)?;
+    let store = seed_store(seed_dir);
+    store.save(&name, &Keystore::encrypt_key_pair(&keys, &password, KdfParams::default())?)?;
+    println!("{}", serde_json::json!({"name": name, "address": keys.public_key, "store": store.backend()}));
+    Ok(())
+}
+
+fn handle_seed_show(
+    name: String,
+    seed_dir: Option<String>,
+    password_env: String,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let password = keystore_password(&password_env)?;
+    let keystore = seed_store(seed_dir).load(&name)?;
+    println!("{}", serde_json::to_string_pretty(&keystore.decrypt(&password)?)?);
+    Ok(())
+}
+
+fn handle_seed_delete(name: String, seed_dir: Option<String>) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let deleted = seed_store(seed_dir).delete(&name)?;
+    println!("{}", serde_json::json!({"name": name, "deleted": deleted}));
+    Ok(())
+}
+
+#[cfg(feature = "qr")]
+fn handle_qr(
+    address: Option<String>,
+    amount: Option<u128>,
+    asset: String,
+    label: Option<String>,
+    message: Option<String>,
+    uri: Option<String>,
+    svg: Option<String>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let payload = match (uri, address) {
+        (Some(uri), _) => uri,
+        (None, Some(address)) => {
+            let mut request = PaymentRequest::new(&address)?;
+            if let Some(amount) = amount {
+                let asset = match asset.strip_prefix("Token:") {
+                    Some(symbol) => Asset::Token(symbol.to_string()),
+                    None if asset == "PLP" => Asset::PLP,
+                    None => return Err(format!("invalid asset {:?}", asset).into()),
+                };
+                request = request.with_amount(amount, asset);
+            }
+            if let Some(label) = label {
+                request = request.with_label(label);
+            }
+            if let Some(message) = message {
+                request = request.with_message(message);
+            }
+            request.to_uri()
+        }
+        (None, None) => return Err("--address or --uri is required".into()),
+    };
+    match svg {
+        Some(path) => {
+            std::fs::write(&path, render_qr_svg(&payload)?)?;
+            println!("{}", serde_json::json!({"payload": payload, "svg": path}));
+        }
+        None => println!("{}\n{}", render_qr_terminal(&payload)?, payload),
+    }
+    Ok(())
+}
+
+fn handle_export_wif(
+    mnemonic: String,
+    alphanumeric: String,
+    seed_index: u32,
+    testnet: bool,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let language = mnemonic_language(&mnemonic).map_err(|_| "Invalid mnemonic phrase")?;
+    let keys = KeyGenerator::new(seed_index, None, None, None)?
+        .with_language(language)
+        .restore_keys(&mnemonic, &alphanumeric, seed_index, None)?;
+    let network = if testnet { WifNetwork::Testnet } else { WifNetwork::Mainnet };
+    let wif = keys.export_wif(network)?;
+    println!(
+        "{}",
+        serde_json::json!({"address": keys.public_key, "network": wif.network, "main": wif.main, "signature": wif.signature})
+    );
+    Ok(())
+}
+
+fn handle_import_wif(main: String, signature: String) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let keys = KeyPair::from_wif(&main, &signature)?;
+    let network = decode_wif(&main)?.network;
+    println!(
+        "{}",
+        serde_json::json!({
+            "network": network,
+            "public_key": keys.public_key,
+            "private_key": keys.private_key,
+            "signature_key": keys.signature_key,
+        })
+    );
+    Ok(())
+}
+
+fn handle_export_xpub(mnemonic: String, alphanumeric: String) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let xpub = export_xpub(&mnemonic, &alphanumeric)?;
+    println!("{}", serde_json::json!({"xpub": xpub, "path": ACCOUNT_PATH}));
+    Ok(())
+}
+
+fn handle_validate_address(address: String) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let canonical = address::decode(&address)?;
+    let checksummed = address::encode(&canonical)?;
+    println!("{}", serde_json::json!({"address": canonical, "checksummed": checksummed}));
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_vanity_address(
+    prefix: String,
+    mnemonic: Option<String>,
+    alphanumeric: Option<String>,
+    language: Language,
+    start_index: u32,
+    seed_hex: Option<String>,
+    threads: Option<usize>,
+    max_attempts: Option<u64>,
+    timeout_secs: Option<u64>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let mut search = VanitySearch::new(&prefix)?;
+    if let Some(threads) = threads {
+        search = search.threads(threads);
+    }
+    if let Some(max_attempts) = max_attempts {
+        search = search.max_attempts(max_attempts);
+    }
+    let cancel = VanityCancel::new();
+    if let Some(secs) = timeout_secs {
+        let cancel = cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_secs(secs));
+            cancel.cancel();
+        });
+    }
+    eprintln!(
+        "[vanity] searching for {} (~{} attempts expected)",
+        search.prefix(),
+        search.expected_attempts()
+    );
+
+    let output = match mnemonic {
+        Some(mnemonic) => {
+            let alphanumeric = alphanumeric.ok_or("--alphanumeric is required with --mnemonic")?;
+            let key_gen = KeyGenerator::default().with_language(language);
+            search
+                .search_indices(&key_gen, &mnemonic, &alphanumeric, start_index, &cancel)?
+                .map(|found| {
+                    serde_json::json!({
+                        "seed_index": found.index,
+                        "main_path": DerivationPath::default().with_address_index(found.index as u32).to_string(),
+                        "address": found.public_key,
+                        "attempts": found.attempts,
+                    })
+                })
+        }
+        None => {
+            let seed: [u8; 32] = match seed_hex {
+                Some(seed_hex) => hex::decode(seed_hex.trim())
+                    .map_err(|e| format!("invalid seed_hex: {}", e))?
+                    .try_into()
+                    .map_err(|_| "seed_hex must be 32 bytes")?,
+                None => rand::random(),
+            };
+            search.search_ephemeral(&seed, &cancel)?.map(|found| {
+                serde_json::json!({
+                    "seed_hex": hex::encode(seed),
+                    "counter": found.index,
+                    "address": found.public_key,
+                    "private_key": found.private_key,
+                    "attempts": found.attempts,
+                })
+            })
+        }
+    };
+    match output {
+        Some(output) => {
+            println!("{}", output);
+            Ok(())
+        }
+        None => Err(format!("no address with prefix {} found (cancelled or out of attempts)", search.prefix()).into()),
+    }
+}
+
+fn handle_discover_accounts(
+    mnemonic: String,
+    alphanumeric: String,
+    rpc: String,
+    state_file: String,
+    gap_limit: u32,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let lookup = RpcBalanceLookup { endpoint: rpc, state_file };
+    let found = discover_accounts(&mnemonic, &alphanumeric, &lookup, gap_limit)?;
+    println!("{}", serde_json::to_string(&found)?);
+    Ok(())
+}
+
+fn handle_watch_addresses(xpub: String, start: u32, count: u32) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let wallet = WatchOnlyWallet::from_xpub(&xpub)?;
+    let addresses: Vec<_> = wallet
+        .addresses(start, count)?
+        .into_iter()
+        .zip(start..)
+        .map(|(address, index)| serde_json::json!({"index": index, "address": address}))
+        .collect();
+    println!("{}", serde_json::json!({ "addresses": addresses }));
+    Ok(())
+}
+
+fn handle_watch_balances(
+    xpub: String,
+    rpc: String,
+    state_file: String,
+    asset: String,
+    start: u32,
+    count: u32,
+    db_path: Option<String>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let wallet = WatchOnlyWallet::from_xpub(&xpub)?;
+    let mut accounts = Vec::new();
+    for (address, index) in wallet.addresses(start, count)?.into_iter().zip(start..) {
+        let mut entry = rpc_call(
+            &rpc,
+            "state_query",
+            serde_json::json!({"state_file": state_file, "address": address, "asset": asset}),
+        )?;
+        entry["index"] = index.into();
+        if let Some(db) = &db_path {
+            let txs = rpc_call(
+                &rpc,
+                "rocks_list_address_txs",
+                serde_json::json!({"db_path": db, "address": address}),
+            )?;
+            entry["tx_hashes"] = txs["tx_hashes"].clone();
+        }
+        accounts.push(entry);
+    }
+    println!("{}", serde_json::json!({ "accounts": accounts }));
+    Ok(())
+}
+
+fn handle_watch_unsigned_tx(
+    xpub: String,
+    index: u32,
+    to: String,
+    asset: String,
+    amount: u64,
+    fee_uplp: u64,
+    nonce: u64,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let wallet = WatchOnlyWallet::from_xpub(&xpub)?;
+    let asset_enum = if asset == "PLP" {
+        Asset::PLP
+    } else if let Some(symbol) = asset.strip_prefix("Token:") {
+        Asset::Token(symbol.to_string())
+    } else {
+        Asset::Token(asset.clone())
+    };
+    let tx = wallet.unsigned_transfer(index, &to, asset_enum, amount as u128, fee_uplp as u128, nonce)?;
+    println!("{}", serde_json::to_string(&tx)?);
+    Ok(())
+}
+
+fn handle_export_unsigned_tx(
+    tx: String,
+    pub_main: Option<String>,
+    pub_derived: Option<String>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let tx: Transaction = serde_json::from_str(&resolve_cli_json_arg(&tx)?)?;
+    let mut unsigned = UnsignedTransaction::new(tx)?;
+    if let Some(pub_derived) = pub_derived {
+        let pub_main = pub_main.or_else(|| unsigned.transaction().pub_main.clone());
+        unsigned = unsigned.with_public_keys(pub_main, pub_derived);
+    }
+    // Typed rather than `json!`: a `Value` cannot hold amounts above u64::MAX.
+    #[derive(serde::Serialize)]
+    struct Exported<'a> {
+        hash: &'a str,
+        signing_payload: String,
+        unsigned: &'a UnsignedTransaction,
+    }
+    let out = Exported {
+        hash: unsigned.hash(),
+        signing_payload: hex::encode(unsigned.signing_payload()),
+        unsigned: &unsigned,
+    };
     println!("{}", serde_json::to_string(&out)?);
     Ok(())
-}
\ No newline at end of file
+}
+
+fn handle_attach_signatures(
+    unsigned: String,
+    sig_main: String,
+    sig_derived: String,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    #[derive(serde::Deserialize)]
+    struct Exported {
+        unsigned: UnsignedTransaction,
+    }
+    let text = resolve_cli_json_arg(&unsigned)?;
+    let value: serde_json::Value = serde_json::from_str(&text)?;
+    let unsigned = if value.get("unsigned").is_some() {
+        serde_json::from_str::<Exported>(&text)?.unsigned
+    } else {
+        serde_json::from_str::<UnsignedTransaction>(&text)?
+    };
+    let tx = unsigned.with_signatures(&sig_main, &sig_derived)?;
+    println!("{}", serde_json::to_string(&tx)?);
+    Ok(())
+}
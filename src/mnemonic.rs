@@ -1,48 +1,283 @@
-use bip39::{Language, Mnemonic};
+use bip39::Mnemonic;
 use rand::Rng;
 use crate::error::{PlatariumError, Result};
 
+pub use bip39::Language;
+
 pub const CHARACTER_SET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
 
+/// Length of the alphanumeric part generated with a mnemonic.
+pub const DEFAULT_ALPHANUMERIC_LEN: usize = 12;
+
+/// Entropy the default [`CharsetPolicy`] requires, in bits. 12 characters of [`CHARACTER_SET`] give 62.
+pub const MIN_ALPHANUMERIC_ENTROPY_BITS: f64 = 60.0;
+
+/// CLI/RPC names of the BIP39 wordlists, in `Language::ALL` order.
+const LANGUAGE_NAMES: [(&str, Language); 10] = [
+    ("english", Language::English),
+    ("chinese-simplified", Language::SimplifiedChinese),
+    ("chinese-traditional", Language::TraditionalChinese),
+    ("czech", Language::Czech),
+    ("french", Language::French),
+    ("italian", Language::Italian),
+    ("japanese", Language::Japanese),
+    ("korean", Language::Korean),
+    ("portuguese", Language::Portuguese),
+    ("spanish", Language::Spanish),
+];
+
+/// Parses a wordlist name (`english`, `japanese`, `chinese-simplified`, ...; case-insensitive).
+pub fn parse_language(name: &str) -> Result<Language> {
+    let wanted = name.trim().to_ascii_lowercase().replace('_', "-");
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(n, _)| *n == wanted)
+        .map(|(_, l)| *l)
+        .ok_or_else(|| {
+            let names: Vec<&str> = LANGUAGE_NAMES.iter().map(|(n, _)| *n).collect();
+            PlatariumError::Validation(format!(
+                "Unknown mnemonic language '{}' (expected one of: {})",
+                name,
+                names.join(", ")
+            ))
+        })
+}
+
+/// Name accepted by [`parse_language`] for `language`.
+pub fn language_name(language: Language) -> &'static str {
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(_, l)| *l == language)
+        .map(|(n, _)| *n)
+        .unwrap_or("english")
+}
+
+/// Word counts defined by BIP39 (128 to 256 bits of entropy in 32-bit steps).
+pub const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Word count used when none is given (256 bits of entropy).
+pub const DEFAULT_MNEMONIC_WORDS: usize = 24;
+
+/// Wordlist and length of generated mnemonics; validation against a config also checks the length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MnemonicConfig {
+    pub language: Language,
+    pub word_count: usize,
+}
+
+impl MnemonicConfig {
+    /// English config with `word_count` words; fails unless the count is one of [`MNEMONIC_WORD_COUNTS`].
+    pub fn new(word_count: usize) -> Result<Self> {
+        if !MNEMONIC_WORD_COUNTS.contains(&word_count) {
+            return Err(PlatariumError::Validation(format!(
+                "Mnemonic word count must be one of {:?}, got {}",
+                MNEMONIC_WORD_COUNTS, word_count
+            )));
+        }
+        Ok(Self {
+            language: Language::English,
+            word_count,
+        })
+    }
+
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Entropy size in bytes: 4 bytes per 3 words.
+    pub fn entropy_bytes(&self) -> usize {
+        self.word_count / 3 * 4
+    }
+}
+
+impl Default for MnemonicConfig {
+    fn default() -> Self {
+        Self {
+            language: Language::English,
+            word_count: DEFAULT_MNEMONIC_WORDS,
+        }
+    }
+}
+
+/// Length and alphabet of generated alphanumeric parts, and the entropy they must reach.
+///
+/// The alphanumeric part is the BIP39 passphrase: anyone holding the mnemonic still has to guess it, so its
+/// entropy is what protects a leaked mnemonic backup. Generated parts have exactly
+/// `length * log2(charset size)` bits; user-chosen ones are rated with [`estimate_alphanumeric_entropy`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharsetPolicy {
+    pub length: usize,
+    /// Distinct, printable, non-space characters.
+    pub charset: String,
+    pub min_entropy_bits: f64,
+}
+
+impl Default for CharsetPolicy {
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_ALPHANUMERIC_LEN,
+            charset: CHARACTER_SET.to_string(),
+            min_entropy_bits: MIN_ALPHANUMERIC_ENTROPY_BITS,
+        }
+    }
+}
+
+impl CharsetPolicy {
+    /// Policy generating `length` characters of `charset`, validated against the default minimum entropy.
+    pub fn new(length: usize, charset: &str) -> Result<Self> {
+        let policy = Self { length, charset: charset.to_string(), ..Self::default() };
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    pub fn with_min_entropy_bits(mut self, bits: f64) -> Self {
+        self.min_entropy_bits = bits;
+        self
+    }
+
+    /// Entropy of a generated part in bits.
+    pub fn entropy_bits(&self) -> f64 {
+        self.length as f64 * (self.charset.chars().count() as f64).log2()
+    }
+
+    /// Checks the length and charset, and that generated parts reach `min_entropy_bits`.
+    pub fn validate(&self) -> Result<()> {
+        if self.length == 0 {
+            return Err(PlatariumError::Validation("length must be a positive integer".to_string()));
+        }
+        let mut chars: Vec<char> = self.charset.chars().collect();
+        if chars.iter().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(PlatariumError::Validation("charset must not contain spaces or control characters".to_string()));
+        }
+        chars.sort_unstable();
+        chars.dedup();
+        if chars.len() < 2 || chars.len() != self.charset.chars().count() {
+            return Err(PlatariumError::Validation("charset needs at least 2 distinct characters, each once".to_string()));
+        }
+        if self.entropy_bits() < self.min_entropy_bits {
+            return Err(PlatariumError::Validation(format!(
+                "{} characters from a {}-character set give {:.1} bits, below the required {:.1}",
+                self.length,
+                chars.len(),
+                self.entropy_bits(),
+                self.min_entropy_bits
+            )));
+        }
+        Ok(())
+    }
+
+    /// A random alphanumeric part under this policy.
+    pub fn generate(&self) -> Result<String> {
+        self.validate()?;
+        let mut rng = rand::thread_rng();
+        let chars: Vec<char> = self.charset.chars().collect();
+        Ok((0..self.length).map(|_| chars[rng.gen_range(0..chars.len())]).collect())
+    }
+
+    /// A warning if a user-supplied `code` is estimated below `min_entropy_bits`, `None` if it is strong enough.
+    pub fn strength_warning(&self, code: &str) -> Option<String> {
+        let bits = estimate_alphanumeric_entropy(code);
+        (bits < self.min_entropy_bits).then(|| {
+            format!(
+                "alphanumeric code has about {:.0} bits of entropy, below the recommended {:.0}; it is the BIP39 \
+                 passphrase, so a weak code leaves a leaked mnemonic unprotected",
+                bits, self.min_entropy_bits
+            )
+        })
+    }
+}
+
+/// Estimated entropy in bits of a user-chosen code. Each character counts `log2` of the pool of the character
+/// classes the code uses (upper case 26, lower case 26, digits 10, other ASCII 33, non-ASCII 100), except that
+/// a character repeating or continuing a run (`AAAA`, `1234`, `dcba`) counts 1 bit. An upper bound for codes
+/// picked by people, who rarely choose uniformly; generated parts are rated by [`CharsetPolicy::entropy_bits`].
+pub fn estimate_alphanumeric_entropy(code: &str) -> f64 {
+    let chars: Vec<char> = code.chars().collect();
+    let has = |f: fn(&char) -> bool| chars.iter().any(f);
+    let pool = [
+        (has(|c| c.is_ascii_uppercase()), 26.0),
+        (has(|c| c.is_ascii_lowercase()), 26.0),
+        (has(|c| c.is_ascii_digit()), 10.0),
+        (has(|c| c.is_ascii() && !c.is_ascii_alphanumeric()), 33.0),
+        (has(|c| !c.is_ascii()), 100.0),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum::<f64>();
+    if pool == 0.0 {
+        return 0.0;
+    }
+    let per_char = pool.log2();
+    let mut step: Option<i64> = None;
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let Some(&prev) = i.checked_sub(1).and_then(|j| chars.get(j)) else {
+                return per_char;
+            };
+            let delta = c as i64 - prev as i64;
+            let predictable = delta == 0 || (delta.abs() == 1 && step.is_none_or(|s| s == delta));
+            step = (delta.abs() == 1).then_some(delta);
+            if predictable { 1.0 } else { per_char }
+        })
+        .sum()
+}
+
 /// Generates a BIP39 mnemonic phrase and an alphanumeric part
 /// 
 /// Returns a tuple of (mnemonic_phrase, alphanumeric_part)
 pub fn generate_mnemonic() -> Result<(String, String)> {
-    // Generate 24-word mnemonic (256 bits of entropy = 32 bytes)
-    let mut entropy = [0u8; 32];
-    rand::thread_rng().fill(&mut entropy);
+    generate_mnemonic_with(&MnemonicConfig::default())
+}
+
+/// Generates a 24-word mnemonic phrase from the `language` wordlist and an alphanumeric part
+pub fn generate_mnemonic_in(language: Language) -> Result<(String, String)> {
+    generate_mnemonic_with(&MnemonicConfig::default().with_language(language))
+}
+
+/// Generates a mnemonic phrase of `config.word_count` words from the `config.language` wordlist and an alphanumeric part
+pub fn generate_mnemonic_with(config: &MnemonicConfig) -> Result<(String, String)> {
+    let config = MnemonicConfig::new(config.word_count)?.with_language(config.language);
+    let mut entropy = vec![0u8; config.entropy_bytes()];
+    rand::thread_rng().fill(entropy.as_mut_slice());
     
-    let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy)?;
+    let mnemonic = Mnemonic::from_entropy_in(config.language, &entropy)?;
     let mnemonic_phrase = mnemonic.to_string();
     
-    // Generate 12-character alphanumeric part
-    let alphanumeric_part = generate_alphanumeric_string(12)?;
+    let alphanumeric_part = CharsetPolicy::default().generate()?;
     
     Ok((mnemonic_phrase, alphanumeric_part))
 }
 
-/// Generates a random alphanumeric string of given length
-fn generate_alphanumeric_string(length: usize) -> Result<String> {
-    if length == 0 {
-        return Err(PlatariumError::Validation("Length must be greater than 0".to_string()));
+/// Validates a BIP39 mnemonic phrase
+pub fn validate_mnemonic(mnemonic: &str) -> bool {
+    validate_mnemonic_in(mnemonic, Language::English)
+}
+
+/// Validates a mnemonic phrase against the `language` wordlist (input is NFKD-normalized first)
+pub fn validate_mnemonic_in(mnemonic: &str, language: Language) -> bool {
+    Mnemonic::parse_in(language, mnemonic).is_ok()
+}
+
+/// Validates a mnemonic phrase against `config`: its wordlist and exact word count
+pub fn validate_mnemonic_with(mnemonic: &str, config: &MnemonicConfig) -> bool {
+    match Mnemonic::parse_in(config.language, mnemonic) {
+        Ok(m) => m.word_count() == config.word_count,
+        Err(_) => false,
     }
-    
-    let mut rng = rand::thread_rng();
-    let chars: Vec<char> = CHARACTER_SET.chars().collect();
-    
-    let result: String = (0..length)
-        .map(|_| {
-            let idx = rng.gen_range(0..chars.len());
-            chars[idx]
-        })
-        .collect();
-    
-    Ok(result)
 }
 
-/// Validates a BIP39 mnemonic phrase
-pub fn validate_mnemonic(mnemonic: &str) -> bool {
-    Mnemonic::parse_in_normalized(Language::English, mnemonic).is_ok()
+/// Detects the wordlist of a valid mnemonic phrase, for callers that are not told the language
+pub fn mnemonic_language(mnemonic: &str) -> Result<Language> {
+    Ok(parse_any_language(mnemonic)?.language())
+}
+
+/// Parses a mnemonic in whichever compiled-in wordlist it belongs to.
+pub(crate) fn parse_any_language(mnemonic: &str) -> Result<Mnemonic> {
+    Ok(Mnemonic::parse(mnemonic)?)
 }
 
 #[cfg(test)]
@@ -63,5 +298,61 @@ mod tests {
         assert!(validate_mnemonic(&mnemonic));
         assert!(!validate_mnemonic("invalid mnemonic phrase here"));
     }
+
+    #[test]
+    fn test_word_counts() {
+        for words in MNEMONIC_WORD_COUNTS {
+            let config = MnemonicConfig::new(words).unwrap();
+            let (mnemonic, _) = generate_mnemonic_with(&config).unwrap();
+            assert_eq!(mnemonic.split_whitespace().count(), words);
+            assert!(validate_mnemonic(&mnemonic));
+            assert!(validate_mnemonic_with(&mnemonic, &config));
+            let other = if words == 24 { 12 } else { 24 };
+            assert!(!validate_mnemonic_with(&mnemonic, &MnemonicConfig::new(other).unwrap()));
+        }
+        assert!(MnemonicConfig::new(13).is_err());
+        let (default, _) = generate_mnemonic().unwrap();
+        assert_eq!(default.split_whitespace().count(), DEFAULT_MNEMONIC_WORDS);
+    }
+
+    #[test]
+    fn test_non_english_mnemonics() {
+        for language in [Language::Japanese, Language::Spanish, Language::French] {
+            let (mnemonic, _) = generate_mnemonic_in(language).unwrap();
+            assert!(validate_mnemonic_in(&mnemonic, language));
+            assert!(!validate_mnemonic(&mnemonic));
+            assert_eq!(mnemonic_language(&mnemonic).unwrap(), language);
+            assert_eq!(parse_language(language_name(language)).unwrap(), language);
+        }
+        assert_eq!(parse_language("Chinese_Simplified").unwrap(), Language::SimplifiedChinese);
+        assert!(parse_language("klingon").is_err());
+    }
+
+    #[test]
+    fn test_charset_policy() {
+        let policy = CharsetPolicy::default();
+        assert!((policy.entropy_bits() - 62.04).abs() < 0.01);
+        let hex = CharsetPolicy::new(16, "0123456789abcdef").unwrap();
+        let part = hex.generate().unwrap();
+        assert_eq!(part.len(), 16);
+        assert!(part.chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(CharsetPolicy::new(8, CHARACTER_SET).is_err());
+        assert!(CharsetPolicy::new(40, "AAB").is_err());
+        assert!(CharsetPolicy::new(40, "A B").is_err());
+        assert!(CharsetPolicy::default().with_min_entropy_bits(0.0).validate().is_ok());
+    }
+
+    #[test]
+    fn test_entropy_estimate() {
+        assert_eq!(estimate_alphanumeric_entropy(""), 0.0);
+        let runs = estimate_alphanumeric_entropy("AAAAAAAAAAAA");
+        let sequence = estimate_alphanumeric_entropy("123456789012");
+        let random = estimate_alphanumeric_entropy("Q7XK2M9PZ4TR");
+        assert!(runs < 20.0 && sequence < 20.0, "{} {}", runs, sequence);
+        assert!(random > 60.0, "{}", random);
+        let policy = CharsetPolicy::default();
+        assert!(policy.strength_warning("PASSWORD").is_some());
+        assert!(policy.strength_warning("Q7XK2M9PZ4TR").is_none());
+    }
 }
 
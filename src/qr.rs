@@ -0,0 +1,326 @@
+//! QR payloads for mobile and air-gapped workflows.
+//!
+//! Three payloads are defined, each a URI so a scanner can tell them apart:
+//!
+//! - an address: `platarium:<address>`;
+//! - a payment request: `platarium:<address>?amount=<units>&asset=<asset>&label=<text>&message=<text>`, BIP21
+//!   style. `amount` is an integer in the asset's minimal units, as in transactions, `asset` is `PLP` (the
+//!   default) or `Token:<symbol>`, and text values are percent-encoded UTF-8. Unknown parameters are
+//!   ignored unless they start with `req-`, which a payer must understand, so those are rejected;
+//! - a signed transaction: `platarium-tx:<transaction JSON>`, carried from an offline signer to an online
+//!   broadcaster. Parsing checks the hash and both signatures, so a damaged or edited scan is refused.
+//!
+//! Building and parsing payloads needs no extra dependencies. Rendering them as QR codes (terminal text or SVG)
+//! requires the `qr` feature.
+
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use crate::address;
+use crate::core::asset::Asset;
+use crate::core::transaction::Transaction;
+use crate::error::PlatariumError;
+
+/// URI scheme of address and payment request payloads.
+pub const PAYMENT_URI_SCHEME: &str = "platarium";
+
+/// URI scheme of signed transaction payloads.
+pub const SIGNED_TX_URI_SCHEME: &str = "platarium-tx";
+
+/// Largest payload that fits one QR code (version 40, byte mode, low error correction).
+pub const MAX_QR_PAYLOAD_BYTES: usize = 2953;
+
+/// Errors produced while building, parsing or rendering QR payloads.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum QrError {
+    #[error("expected a {expected}: URI")]
+    WrongScheme { expected: &'static str },
+
+    #[error("invalid address: {0}")]
+    InvalidAddress(String),
+
+    #[error("invalid amount {0:?}")]
+    InvalidAmount(String),
+
+    #[error("invalid asset {0:?}")]
+    InvalidAsset(String),
+
+    #[error("malformed URI: {0}")]
+    Malformed(String),
+
+    #[error("unsupported required parameter {0}")]
+    UnsupportedRequired(String),
+
+    #[error("invalid signed transaction: {0}")]
+    InvalidTransaction(String),
+
+    #[error("payload is {len} bytes, a QR code holds at most {MAX_QR_PAYLOAD_BYTES}")]
+    TooLarge { len: usize },
+
+    #[error("QR encoding failed: {0}")]
+    Encoding(String),
+}
+
+impl From<QrError> for PlatariumError {
+    fn from(e: QrError) -> Self {
+        PlatariumError::Validation(format!("QR payload error: {}", e))
+    }
+}
+
+/// `platarium:<address>`, with the address in canonical form.
+pub fn address_uri(address: &str) -> Result<String, QrError> {
+    let canonical = address::decode(address).map_err(|e| QrError::InvalidAddress(e.to_string()))?;
+    Ok(format!("{}:{}", PAYMENT_URI_SCHEME, canonical))
+}
+
+/// A request to pay `address`, optionally a fixed `amount` of `asset`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentRequest {
+    /// Canonical address.
+    pub address: String,
+    pub amount: Option<u128>,
+    pub asset: Asset,
+    /// Name of the payee, for display.
+    pub label: Option<String>,
+    /// What the payment is for, for display.
+    pub message: Option<String>,
+}
+
+impl PaymentRequest {
+    /// Request to pay `address` any amount of PLP.
+    pub fn new(address: &str) -> Result<Self, QrError> {
+        Ok(PaymentRequest {
+            address: address::decode(address).map_err(|e| QrError::InvalidAddress(e.to_string()))?,
+            amount: None,
+            asset: Asset::PLP,
+            label: None,
+            message: None,
+        })
+    }
+
+    pub fn with_amount(mut self, amount: u128, asset: Asset) -> Self {
+        self.amount = Some(amount);
+        self.asset = asset;
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// The request as a `platarium:` URI. Parameters are written in a fixed order, `asset` only if not PLP.
+    pub fn to_uri(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(amount) = self.amount {
+            params.push(format!("amount={}", amount));
+        }
+        if self.asset != Asset::PLP {
+            params.push(format!("asset={}", percent_encode(&self.asset.as_canonical())));
+        }
+        if let Some(label) = &self.label {
+            params.push(format!("label={}", percent_encode(label)));
+        }
+        if let Some(message) = &self.message {
+            params.push(format!("message={}", percent_encode(message)));
+        }
+        let mut uri = format!("{}:{}", PAYMENT_URI_SCHEME, self.address);
+        if !params.is_empty() {
+            uri.push('?');
+            uri.push_str(&params.join("&"));
+        }
+        uri
+    }
+}
+
+impl fmt::Display for PaymentRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_uri())
+    }
+}
+
+impl FromStr for PaymentRequest {
+    type Err = QrError;
+
+    /// Parses a payment request or plain address URI. The scheme is case-insensitive and a checksummed address
+    /// is accepted; the result holds the canonical address.
+    fn from_str(uri: &str) -> Result<Self, QrError> {
+        let rest = strip_scheme(uri.trim(), PAYMENT_URI_SCHEME)?;
+        let (addr, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let mut request = PaymentRequest::new(addr)?;
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(|| QrError::Malformed(format!("parameter {:?}", pair)))?;
+            let value = percent_decode(value)?;
+            match key {
+                "amount" if request.amount.is_none() => {
+                    let amount = value.parse().map_err(|_| QrError::InvalidAmount(value.clone()))?;
+                    request.amount = Some(amount);
+                }
+                "asset" => request.asset = parse_asset(&value)?,
+                "label" => request.label = Some(value),
+                "message" => request.message = Some(value),
+                "amount" => return Err(QrError::Malformed("amount given twice".to_string())),
+                other if other.starts_with("req-") => return Err(QrError::UnsupportedRequired(other.to_string())),
+                _ => {}
+            }
+        }
+        Ok(request)
+    }
+}
+
+/// `platarium-tx:<JSON>` for a signed transaction.
+pub fn signed_transaction_uri(tx: &Transaction) -> Result<String, QrError> {
+    check_signed(tx)?;
+    let json = serde_json::to_string(tx).map_err(|e| QrError::InvalidTransaction(e.to_string()))?;
+    let uri = format!("{}:{}", SIGNED_TX_URI_SCHEME, json);
+    if uri.len() > MAX_QR_PAYLOAD_BYTES {
+        return Err(QrError::TooLarge { len: uri.len() });
+    }
+    Ok(uri)
+}
+
+/// The transaction in a `platarium-tx:` URI, after checking its hash and signatures.
+pub fn parse_signed_transaction_uri(uri: &str) -> Result<Transaction, QrError> {
+    let json = strip_scheme(uri.trim(), SIGNED_TX_URI_SCHEME)?;
+    let tx: Transaction = serde_json::from_str(json).map_err(|e| QrError::InvalidTransaction(e.to_string()))?;
+    check_signed(&tx)?;
+    Ok(tx)
+}
+
+fn check_signed(tx: &Transaction) -> Result<(), QrError> {
+    let invalid = |reason: &str| QrError::InvalidTransaction(reason.to_string());
+    if tx.compute_hash().map_err(|e| QrError::InvalidTransaction(e.to_string()))? != tx.hash {
+        return Err(invalid("hash does not match the contents"));
+    }
+    match tx.verify_signatures() {
+        Ok(true) => Ok(()),
+        _ => Err(invalid("signatures do not verify")),
+    }
+}
+
+fn strip_scheme<'a>(uri: &'a str, scheme: &'static str) -> Result<&'a str, QrError> {
+    match uri.split_once(':') {
+        Some((s, rest)) if s.eq_ignore_ascii_case(scheme) => Ok(rest),
+        _ => Err(QrError::WrongScheme { expected: scheme }),
+    }
+}
+
+fn parse_asset(s: &str) -> Result<Asset, QrError> {
+    match s {
+        "PLP" => Ok(Asset::PLP),
+        _ => match s.strip_prefix("Token:") {
+            Some(symbol) if !symbol.is_empty() => Ok(Asset::Token(symbol.to_string())),
+            _ => Err(QrError::InvalidAsset(s.to_string())),
+        },
+    }
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters.
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> Result<String, QrError> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = s
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+                .ok_or_else(|| QrError::Malformed(format!("bad percent escape in {:?}", s)))?;
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| QrError::Malformed(format!("{:?} is not UTF-8", s)))
+}
+
+/// The QR code of `payload` as text for a terminal with a dark background: two modules per character cell, light
+/// modules drawn as blocks, with the standard four-module quiet zone.
+#[cfg(feature = "qr")]
+#[cfg_attr(docsrs, doc(cfg(feature = "qr")))]
+pub fn render_qr_terminal(payload: &str) -> Result<String, QrError> {
+    use qrcode::render::unicode::Dense1x2;
+    Ok(qr_code(payload)?
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// The QR code of `payload` as a standalone SVG document.
+#[cfg(feature = "qr")]
+#[cfg_attr(docsrs, doc(cfg(feature = "qr")))]
+pub fn render_qr_svg(payload: &str) -> Result<String, QrError> {
+    use qrcode::render::svg;
+    Ok(qr_code(payload)?.render::<svg::Color>().min_dimensions(256, 256).build())
+}
+
+#[cfg(feature = "qr")]
+fn qr_code(payload: &str) -> Result<qrcode::QrCode, QrError> {
+    if payload.len() > MAX_QR_PAYLOAD_BYTES {
+        return Err(QrError::TooLarge { len: payload.len() });
+    }
+    qrcode::QrCode::with_error_correction_level(payload.as_bytes(), qrcode::EcLevel::L)
+        .map_err(|e| QrError::Encoding(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDR: &str = "Px02d57104355feba6c9bdba00f55775ffaf201eb18cadc5f77266acbb1fea186555";
+
+    #[test]
+    fn test_payment_request_round_trip() {
+        let request = PaymentRequest::new(ADDR)
+            .unwrap()
+            .with_amount(2_500, Asset::Token("USDT".into()))
+            .with_label("Café & Co")
+            .with_message("invoice #7");
+        let uri = request.to_uri();
+        assert_eq!(
+            uri,
+            format!("platarium:{}?amount=2500&asset=Token%3AUSDT&label=Caf%C3%A9%20%26%20Co&message=invoice%20%237", ADDR)
+        );
+        assert_eq!(uri.parse::<PaymentRequest>().unwrap(), request);
+        assert_eq!(address_uri(ADDR).unwrap().parse::<PaymentRequest>().unwrap(), PaymentRequest::new(ADDR).unwrap());
+    }
+
+    #[test]
+    fn test_payment_request_rejects_bad_uris() {
+        let ok = |q: &str| format!("platarium:{}?{}", ADDR, q).parse::<PaymentRequest>();
+        assert!(ok("amount=5&foo=bar").is_ok());
+        assert_eq!(ok("req-expiry=10"), Err(QrError::UnsupportedRequired("req-expiry".into())));
+        assert!(matches!(ok("amount=-1"), Err(QrError::InvalidAmount(_))));
+        assert!(matches!(ok("amount=1&amount=2"), Err(QrError::Malformed(_))));
+        assert!(matches!(ok("asset=DOGE"), Err(QrError::InvalidAsset(_))));
+        assert!(matches!(ok("label=%ZZ"), Err(QrError::Malformed(_))));
+        assert!(matches!(format!("bitcoin:{}", ADDR).parse::<PaymentRequest>(), Err(QrError::WrongScheme { .. })));
+        assert!(matches!("platarium:Px1234".parse::<PaymentRequest>(), Err(QrError::InvalidAddress(_))));
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_render() {
+        let uri = address_uri(ADDR).unwrap();
+        assert!(render_qr_svg(&uri).unwrap().starts_with("<?xml"));
+        assert!(render_qr_terminal(&uri).unwrap().lines().count() > 10);
+        assert!(matches!(render_qr_svg(&"x".repeat(MAX_QR_PAYLOAD_BYTES + 1)), Err(QrError::TooLarge { .. })));
+    }
+}
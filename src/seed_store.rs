@@ -0,0 +1,216 @@
+//! Named storage for encrypted seeds, so the CLI can refer to a wallet by name instead of taking its mnemonic
+//! on the command line (where it would end up in shell history and the process list).
+//!
+//! A [`SeedStore`] holds [`Keystore`] documents: the secret is encrypted with the keystore password before it
+//! reaches the store, so a store only ever sees ciphertext. [`FileSeedStore`] keeps one `<name>.json` keystore
+//! per seed in a directory. With the `keyring` feature, [`KeyringSeedStore`] keeps the same JSON in the OS
+//! credential store (macOS Keychain, Windows Credential Manager, Secret Service on Linux), and
+//! [`default_seed_store`] prefers it when the credential store is reachable.
+
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use crate::error::{PlatariumError, Result};
+use crate::keystore::Keystore;
+
+/// Longest accepted seed name.
+pub const MAX_SEED_NAME_LEN: usize = 64;
+
+/// Service name seeds are filed under in the OS credential store.
+pub const KEYRING_SERVICE: &str = "platarium-core";
+
+/// Errors produced by seed stores.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SeedStoreError {
+    #[error("Invalid seed name {0:?} (1-{MAX_SEED_NAME_LEN} characters from [A-Za-z0-9_-])")]
+    InvalidName(String),
+
+    #[error("No seed named {0}")]
+    NotFound(String),
+
+    #[error("Seed store backend: {0}")]
+    Backend(String),
+}
+
+impl From<SeedStoreError> for PlatariumError {
+    fn from(e: SeedStoreError) -> Self {
+        PlatariumError::State(format!("Seed store error: {}", e))
+    }
+}
+
+/// Storage for encrypted seeds, keyed by name.
+pub trait SeedStore {
+    /// Stores `keystore` under `name`, replacing any previous entry.
+    fn save(&self, name: &str, keystore: &Keystore) -> Result<()>;
+
+    /// The keystore stored under `name`.
+    fn load(&self, name: &str) -> Result<Keystore>;
+
+    /// Removes `name`. Returns `false` if there was nothing to remove.
+    fn delete(&self, name: &str) -> Result<bool>;
+
+    /// Short description of the backend, for CLI output.
+    fn backend(&self) -> String;
+}
+
+/// Checks `name` is usable as a file name and credential-store account on every platform.
+pub fn validate_seed_name(name: &str) -> std::result::Result<(), SeedStoreError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SEED_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(SeedStoreError::InvalidName(name.to_string()))
+    }
+}
+
+/// Seeds as keystore files `<dir>/<name>.json` (mode 0600 on Unix, see [`Keystore::save`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSeedStore {
+    dir: PathBuf,
+}
+
+impl FileSeedStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileSeedStore { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        validate_seed_name(name)?;
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
+
+impl SeedStore for FileSeedStore {
+    fn save(&self, name: &str, keystore: &Keystore) -> Result<()> {
+        let path = self.path(name)?;
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| SeedStoreError::Backend(format!("cannot create {}: {}", self.dir.display(), e)))?;
+        keystore.save(&path)
+    }
+
+    fn load(&self, name: &str) -> Result<Keystore> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Err(SeedStoreError::NotFound(name.to_string()).into());
+        }
+        Keystore::load(&path)
+    }
+
+    fn delete(&self, name: &str) -> Result<bool> {
+        match std::fs::remove_file(self.path(name)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(SeedStoreError::Backend(e.to_string()).into()),
+        }
+    }
+
+    fn backend(&self) -> String {
+        format!("file:{}", self.dir.display())
+    }
+}
+
+/// Seeds in the OS credential store, one entry per name under `service`.
+#[cfg(feature = "keyring")]
+#[cfg_attr(docsrs, doc(cfg(feature = "keyring")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyringSeedStore {
+    service: String,
+}
+
+#[cfg(feature = "keyring")]
+impl KeyringSeedStore {
+    pub fn new(service: impl Into<String>) -> Self {
+        KeyringSeedStore { service: service.into() }
+    }
+
+    /// Whether the credential store answers: a lookup of a missing entry succeeds with "no entry" rather than
+    /// failing (no Secret Service daemon, locked-down session, ...).
+    pub fn is_available(&self) -> bool {
+        matches!(
+            keyring::Entry::new(&self.service, "__platarium_probe__").and_then(|e| e.get_password()),
+            Ok(_) | Err(keyring::Error::NoEntry)
+        )
+    }
+
+    fn entry(&self, name: &str) -> Result<keyring::Entry> {
+        validate_seed_name(name)?;
+        keyring::Entry::new(&self.service, name).map_err(|e| SeedStoreError::Backend(e.to_string()).into())
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl SeedStore for KeyringSeedStore {
+    fn save(&self, name: &str, keystore: &Keystore) -> Result<()> {
+        self.entry(name)?
+            .set_password(&keystore.to_json()?)
+            .map_err(|e| SeedStoreError::Backend(e.to_string()).into())
+    }
+
+    fn load(&self, name: &str) -> Result<Keystore> {
+        match self.entry(name)?.get_password() {
+            Ok(json) => Keystore::from_json(&json),
+            Err(keyring::Error::NoEntry) => Err(SeedStoreError::NotFound(name.to_string()).into()),
+            Err(e) => Err(SeedStoreError::Backend(e.to_string()).into()),
+        }
+    }
+
+    fn delete(&self, name: &str) -> Result<bool> {
+        match self.entry(name)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(SeedStoreError::Backend(e.to_string()).into()),
+        }
+    }
+
+    fn backend(&self) -> String {
+        format!("keyring:{}", self.service)
+    }
+}
+
+/// The OS credential store if the `keyring` feature is enabled and the store is reachable, otherwise keystore
+/// files in `fallback_dir`.
+pub fn default_seed_store(fallback_dir: &Path) -> Box<dyn SeedStore> {
+    #[cfg(feature = "keyring")]
+    {
+        let store = KeyringSeedStore::new(KEYRING_SERVICE);
+        if store.is_available() {
+            return Box::new(store);
+        }
+    }
+    Box::new(FileSeedStore::new(fallback_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystore::KdfParams;
+
+    #[test]
+    fn test_seed_names() {
+        assert!(validate_seed_name("cold-wallet_2").is_ok());
+        for bad in ["", "../etc/passwd", "a b", "a.json", &"x".repeat(MAX_SEED_NAME_LEN + 1)] {
+            assert!(validate_seed_name(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("platarium-seed-store-{}", std::process::id()));
+        let store = FileSeedStore::new(&dir);
+        let params = KdfParams { log_n: 4, r: 8, p: 1 };
+        let keystore = Keystore::encrypt_seed(&[7u8; 64], "pw", params).unwrap();
+
+        assert!(store.load("main").unwrap_err().to_string().contains("No seed named main"));
+        store.save("main", &keystore).unwrap();
+        assert_eq!(store.load("main").unwrap().decrypt_seed("pw").unwrap(), vec![7u8; 64]);
+        assert!(store.delete("main").unwrap());
+        assert!(!store.delete("main").unwrap());
+        assert!(store.save("../main", &keystore).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,366 @@
+//! Threshold backups of a mnemonic: split its entropy into N shares of which any T rebuild it.
+//!
+//! This is a Platarium-specific share format, not SLIP-39: share phrases only restore through
+//! [`combine_mnemonic`] and cannot be imported into or produced by other wallets. The secret is shared
+//! with Shamir polynomials over GF(256) (AES field, `x^8 + x^4 + x^3 + x + 1`), evaluated byte-wise,
+//! with the secret at `x = 255` and a digest share at `x = 254` holding `HMAC-SHA256(R, secret)[..4] || R`.
+//! Combining the wrong set of shares therefore fails the digest check instead of silently yielding a
+//! different wallet. There is no passphrase encryption layer.
+//!
+//! Shares are written as phrases over the BIP39 English wordlist (11 bits per word). Phrase layout,
+//! before padding to a whole number of words:
+//!
+//! ```text
+//! identifier (2 bytes) | threshold (1) | index (1) | length (1) | value (length) | SHA256 checksum (4)
+//! ```
+
+use bip39::{Language, Mnemonic};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use crate::error::{PlatariumError, Result};
+use crate::key_generator::{DerivationPath, KeyGenerator, KeyPair};
+
+/// Maximum number of shares (and threshold) per split.
+pub const MAX_SHARE_COUNT: u8 = 16;
+
+/// Shortest secret that can be shared (128-bit entropy, a 12-word mnemonic).
+pub const MIN_SECRET_LEN: usize = 16;
+
+const SECRET_INDEX: u8 = 255;
+const DIGEST_INDEX: u8 = 254;
+const DIGEST_LEN: usize = 4;
+const CHECKSUM_LEN: usize = 4;
+const HEADER_LEN: usize = 5;
+const BITS_PER_WORD: usize = 11;
+
+/// Errors produced while splitting or combining shares.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ShareError {
+    #[error("Invalid threshold {threshold} for {count} shares (need 1 <= threshold <= shares <= {MAX_SHARE_COUNT})")]
+    InvalidThreshold { threshold: u8, count: u8 },
+
+    #[error("Secret must be an even number of bytes, at least {MIN_SECRET_LEN} (got {0})")]
+    InvalidSecretLength(usize),
+
+    #[error("Malformed share: {0}")]
+    Malformed(String),
+
+    #[error("Share checksum mismatch")]
+    ChecksumMismatch,
+
+    #[error("Shares belong to different splits")]
+    MixedSplits,
+
+    #[error("Duplicate share index {0}")]
+    DuplicateIndex(u8),
+
+    #[error("Need {threshold} shares, got {got}")]
+    NotEnoughShares { threshold: u8, got: usize },
+
+    #[error("Shares do not reconstruct a consistent secret")]
+    DigestMismatch,
+}
+
+impl From<ShareError> for PlatariumError {
+    fn from(e: ShareError) -> Self {
+        PlatariumError::Crypto(format!("Secret sharing: {}", e))
+    }
+}
+
+/// One share of a split secret.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretShare {
+    /// Random tag shared by all shares of one split.
+    pub identifier: u16,
+    pub threshold: u8,
+    /// Evaluation point, `0..share_count`.
+    pub index: u8,
+    pub value: Vec<u8>,
+}
+
+impl SecretShare {
+    /// Encodes the share as a phrase of BIP39 English words.
+    pub fn to_phrase(&self) -> String {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.value.len() + CHECKSUM_LEN);
+        bytes.extend_from_slice(&self.identifier.to_be_bytes());
+        bytes.push(self.threshold);
+        bytes.push(self.index);
+        bytes.push(self.value.len() as u8);
+        bytes.extend_from_slice(&self.value);
+        let checksum = Sha256::digest(&bytes);
+        bytes.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+
+        let words = Language::English.word_list();
+        let word_count = (bytes.len() * 8).div_ceil(BITS_PER_WORD);
+        (0..word_count)
+            .map(|w| {
+                let idx = (0..BITS_PER_WORD).fold(0usize, |acc, b| (acc << 1) | bit(&bytes, w * BITS_PER_WORD + b));
+                words[idx]
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parses a phrase produced by [`SecretShare::to_phrase`], verifying its checksum.
+    pub fn from_phrase(phrase: &str) -> std::result::Result<Self, ShareError> {
+        let mut bits = Vec::new();
+        for word in phrase.split_whitespace() {
+            let idx = Language::English
+                .find_word(&word.to_lowercase())
+                .ok_or_else(|| ShareError::Malformed(format!("unknown word '{}'", word)))?;
+            bits.extend((0..BITS_PER_WORD).rev().map(|b| (idx >> b) & 1 == 1));
+        }
+        let mut bytes: Vec<u8> = bits
+            .chunks_exact(8)
+            .map(|c| c.iter().fold(0u8, |acc, &b| (acc << 1) | b as u8))
+            .collect();
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err(ShareError::Malformed("too short".to_string()));
+        }
+        let total = HEADER_LEN + bytes[4] as usize + CHECKSUM_LEN;
+        if bits.len() != (total * 8).div_ceil(BITS_PER_WORD) * BITS_PER_WORD {
+            return Err(ShareError::Malformed(format!("wrong word count for a {}-byte share", total)));
+        }
+        if bits[total * 8..].iter().any(|&b| b) {
+            return Err(ShareError::Malformed("non-zero padding".to_string()));
+        }
+        bytes.truncate(total);
+        let checksum = bytes.split_off(total - CHECKSUM_LEN);
+        if Sha256::digest(&bytes)[..CHECKSUM_LEN] != checksum[..] {
+            return Err(ShareError::ChecksumMismatch);
+        }
+        Ok(SecretShare {
+            identifier: u16::from_be_bytes([bytes[0], bytes[1]]),
+            threshold: bytes[2],
+            index: bytes[3],
+            value: bytes[HEADER_LEN..].to_vec(),
+        })
+    }
+}
+
+fn bit(bytes: &[u8], i: usize) -> usize {
+    bytes.get(i / 8).map_or(0, |b| ((b >> (7 - i % 8)) & 1) as usize)
+}
+
+/// Splits `secret` into `share_count` shares, any `threshold` of which reconstruct it.
+pub fn split_secret(secret: &[u8], threshold: u8, share_count: u8) -> std::result::Result<Vec<SecretShare>, ShareError> {
+    if threshold == 0 || threshold > share_count || share_count > MAX_SHARE_COUNT {
+        return Err(ShareError::InvalidThreshold { threshold, count: share_count });
+    }
+    if secret.len() < MIN_SECRET_LEN || !secret.len().is_multiple_of(2) {
+        return Err(ShareError::InvalidSecretLength(secret.len()));
+    }
+    let mut rng = rand::thread_rng();
+    let identifier = (rng.next_u32() & 0xffff) as u16;
+    let share = |index: u8, value: Vec<u8>| SecretShare { identifier, threshold, index, value };
+
+    if threshold == 1 {
+        return Ok((0..share_count).map(|i| share(i, secret.to_vec())).collect());
+    }
+
+    let mut random_part = vec![0u8; secret.len() - DIGEST_LEN];
+    rng.fill_bytes(&mut random_part);
+    let mut digest = secret_digest(&random_part, secret);
+    digest.extend_from_slice(&random_part);
+
+    // T-2 random shares plus the digest and the secret fix a polynomial of degree T-1.
+    let mut base: Vec<(u8, Vec<u8>)> = (0..threshold - 2)
+        .map(|i| {
+            let mut value = vec![0u8; secret.len()];
+            rng.fill_bytes(&mut value);
+            (i, value)
+        })
+        .collect();
+    base.push((DIGEST_INDEX, digest));
+    base.push((SECRET_INDEX, secret.to_vec()));
+
+    let mut shares: Vec<SecretShare> = base[..threshold as usize - 2].iter().map(|(i, v)| share(*i, v.clone())).collect();
+    for i in threshold - 2..share_count {
+        shares.push(share(i, interpolate(&base, i)));
+    }
+    Ok(shares)
+}
+
+/// Rebuilds the secret from at least `threshold` shares of one split.
+pub fn combine_shares(shares: &[SecretShare]) -> std::result::Result<Vec<u8>, ShareError> {
+    let first = shares.first().ok_or(ShareError::NotEnoughShares { threshold: 1, got: 0 })?;
+    let mut points: Vec<(u8, Vec<u8>)> = Vec::with_capacity(shares.len());
+    for share in shares {
+        if share.identifier != first.identifier || share.threshold != first.threshold || share.value.len() != first.value.len() {
+            return Err(ShareError::MixedSplits);
+        }
+        if points.iter().any(|(i, _)| *i == share.index) {
+            return Err(ShareError::DuplicateIndex(share.index));
+        }
+        points.push((share.index, share.value.clone()));
+    }
+    if first.threshold == 0 || points.len() < first.threshold as usize {
+        return Err(ShareError::NotEnoughShares { threshold: first.threshold, got: points.len() });
+    }
+    if first.value.len() < MIN_SECRET_LEN || !first.value.len().is_multiple_of(2) {
+        return Err(ShareError::InvalidSecretLength(first.value.len()));
+    }
+    if first.threshold == 1 {
+        return Ok(first.value.clone());
+    }
+
+    points.truncate(first.threshold as usize);
+    let secret = interpolate(&points, SECRET_INDEX);
+    let digest = interpolate(&points, DIGEST_INDEX);
+    let (check, random_part) = digest.split_at(DIGEST_LEN);
+    if check != secret_digest(random_part, &secret).as_slice() {
+        return Err(ShareError::DigestMismatch);
+    }
+    Ok(secret)
+}
+
+fn secret_digest(random_part: &[u8], secret: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(random_part).expect("HMAC accepts any key length");
+    mac.update(secret);
+    mac.finalize().into_bytes()[..DIGEST_LEN].to_vec()
+}
+
+/// Splits a mnemonic's entropy into share phrases; the mnemonic must be in the `language` wordlist.
+pub fn split_mnemonic(mnemonic: &str, language: Language, threshold: u8, share_count: u8) -> Result<Vec<String>> {
+    let entropy = Mnemonic::parse_in(language, mnemonic)?.to_entropy();
+    Ok(split_secret(&entropy, threshold, share_count)?.iter().map(SecretShare::to_phrase).collect())
+}
+
+/// Rebuilds the mnemonic, in the `language` wordlist, from share phrases made by [`split_mnemonic`].
+pub fn combine_mnemonic<S: AsRef<str>>(share_phrases: &[S], language: Language) -> Result<String> {
+    let shares = share_phrases
+        .iter()
+        .map(|p| SecretShare::from_phrase(p.as_ref()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let entropy = combine_shares(&shares)?;
+    Ok(Mnemonic::from_entropy_in(language, &entropy)?.to_string())
+}
+
+impl KeyGenerator {
+    /// Restores keys from share phrases: combines them into the mnemonic (in the generator's language), then as [`KeyGenerator::restore_keys`]
+    pub fn restore_keys_from_shares<S: AsRef<str>>(
+        &self,
+        share_phrases: &[S],
+        alphanumeric_part: &str,
+        seed_index: u32,
+        custom_path: Option<DerivationPath>,
+    ) -> Result<KeyPair> {
+        let mnemonic = combine_mnemonic(share_phrases, self.language())?;
+        self.restore_keys(&mnemonic, alphanumeric_part, seed_index, custom_path)
+    }
+}
+
+/// Lagrange interpolation at `x`, byte-wise over GF(256).
+fn interpolate(points: &[(u8, Vec<u8>)], x: u8) -> Vec<u8> {
+    if let Some((_, value)) = points.iter().find(|(i, _)| *i == x) {
+        return value.clone();
+    }
+    let len = points[0].1.len();
+    let mut result = vec![0u8; len];
+    for (j, (xj, yj)) in points.iter().enumerate() {
+        let mut basis = 1u8;
+        for (m, (xm, _)) in points.iter().enumerate() {
+            if m != j {
+                basis = gf_mul(basis, gf_div(x ^ xm, xj ^ xm));
+            }
+        }
+        for (r, y) in result.iter_mut().zip(yj) {
+            *r ^= gf_mul(basis, *y);
+        }
+    }
+    result
+}
+
+const fn gf_tables() -> ([u8; 256], [u8; 256]) {
+    let mut exp = [0u8; 256];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        // Multiply by the generator 3 (x * 2 + x), reducing by the AES polynomial.
+        x ^= x << 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11b;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const GF: ([u8; 256], [u8; 256]) = gf_tables();
+
+fn gf_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    GF.0[(GF.1[a as usize] as usize + GF.1[b as usize] as usize) % 255]
+}
+
+fn gf_div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    GF.0[(GF.1[a as usize] as usize + 255 - GF.1[b as usize] as usize) % 255]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mnemonic::{generate_mnemonic_with, MnemonicConfig};
+
+    #[test]
+    fn any_threshold_subset_recovers_secret() {
+        let secret: Vec<u8> = (0..32).collect();
+        let shares = split_secret(&secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[c].clone(), shares[a].clone(), shares[b].clone()];
+                    assert_eq!(combine_shares(&subset).unwrap(), secret);
+                }
+            }
+        }
+        assert_eq!(
+            combine_shares(&shares[..2]),
+            Err(ShareError::NotEnoughShares { threshold: 3, got: 2 })
+        );
+
+        let mut tampered = shares[..3].to_vec();
+        tampered[1].value[0] ^= 1;
+        assert_eq!(combine_shares(&tampered), Err(ShareError::DigestMismatch));
+
+        let other = split_secret(&secret, 3, 5).unwrap();
+        if other[0].identifier != shares[0].identifier {
+            assert_eq!(combine_shares(&[shares[0].clone(), shares[1].clone(), other[2].clone()]), Err(ShareError::MixedSplits));
+        }
+        assert!(split_secret(&secret, 4, 3).is_err());
+        assert!(split_secret(&secret[..15], 2, 3).is_err());
+    }
+
+    #[test]
+    fn phrases_round_trip_and_restore_keys() {
+        for words in [12, 24] {
+            let (mnemonic, alphanumeric) = generate_mnemonic_with(&MnemonicConfig::new(words).unwrap()).unwrap();
+            let phrases = split_mnemonic(&mnemonic, Language::English, 2, 3).unwrap();
+            let share = SecretShare::from_phrase(&phrases[2]).unwrap();
+            assert_eq!(share.to_phrase(), phrases[2]);
+            assert_eq!(combine_mnemonic(&phrases[1..], Language::English).unwrap(), mnemonic);
+
+            let generator = KeyGenerator::default();
+            let expected = generator.restore_keys(&mnemonic, &alphanumeric, 0, None).unwrap();
+            let restored = generator.restore_keys_from_shares(&[&phrases[2], &phrases[0]], &alphanumeric, 0, None).unwrap();
+            assert_eq!(restored, expected);
+        }
+
+        let (mnemonic, _) = generate_mnemonic_with(&MnemonicConfig::new(12).unwrap()).unwrap();
+        let phrase = split_mnemonic(&mnemonic, Language::English, 1, 1).unwrap().remove(0);
+        let mut words: Vec<&str> = phrase.split(' ').collect();
+        words[3] = if words[3] == "abandon" { "ability" } else { "abandon" };
+        assert!(SecretShare::from_phrase(&words.join(" ")).is_err());
+    }
+}
@@ -1,10 +1,65 @@
-use secp256k1::{Secp256k1, SecretKey, PublicKey, Message};
-use secp256k1::ecdsa::Signature;
-use sha2::{Sha256, Digest};
-use serde_json;
+use secp256k1::{Keypair, Secp256k1, SecretKey, PublicKey, Message, XOnlyPublicKey};
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId, Signature};
+use secp256k1::schnorr;
+use platarium_consensus::wire::{Decode, Encode, Reader, WireError};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use crate::error::{PlatariumError, Result};
+pub use platarium_consensus::{MessageHasher, SigningDomain};
 
-const DOMAIN_SEPARATOR: &str = "PlatariumSignature:";
+
+/// Signature algorithm over secp256k1. Both sign the same domain-separated SHA256 hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    /// ECDSA, compact or DER encoded, low-S.
+    #[default]
+    Ecdsa,
+    /// BIP-340 Schnorr: 64-byte signature, x-only public key. Linear, so validator signatures can later be aggregated.
+    Schnorr,
+}
+
+impl SignatureScheme {
+    pub fn is_ecdsa(&self) -> bool {
+        *self == SignatureScheme::Ecdsa
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureScheme::Ecdsa => "ecdsa",
+            SignatureScheme::Schnorr => "schnorr",
+        }
+    }
+
+    /// Parses `ecdsa` or `schnorr` (case-insensitive).
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ecdsa" => Ok(SignatureScheme::Ecdsa),
+            "schnorr" => Ok(SignatureScheme::Schnorr),
+            other => Err(PlatariumError::Signature(format!("Unknown signature scheme: {}", other))),
+        }
+    }
+}
+
+/// Tag `0` for ECDSA, `1` for Schnorr.
+impl Encode for SignatureScheme {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(match self {
+            SignatureScheme::Ecdsa => 0,
+            SignatureScheme::Schnorr => 1,
+        });
+    }
+}
+
+impl Decode for SignatureScheme {
+    fn decode(reader: &mut Reader<'_>) -> std::result::Result<Self, WireError> {
+        match reader.u8()? {
+            0 => Ok(SignatureScheme::Ecdsa),
+            1 => Ok(SignatureScheme::Schnorr),
+            tag => Err(WireError::InvalidTag(tag)),
+        }
+    }
+}
 
 /// Normalizes CLI compact signatures (128 hex + optional recovery suffix) to 64-byte compact hex.
 pub fn normalize_signature_hex(signature_hex: &str) -> String {
@@ -19,82 +74,274 @@ pub fn normalize_signature_hex(signature_hex: &str) -> String {
     }
 }
 
-/// Hashes a message with domain separator
+/// Normalizes a secp256k1 public key (hex, optionally `Px`-prefixed) to lowercase hex without prefix. Errors if it does not parse as a key.
+pub fn normalize_public_key_hex(pub_key: &str) -> Result<String> {
+    let hex_key = pub_key.strip_prefix("Px").unwrap_or(pub_key).to_ascii_lowercase();
+    let bytes = hex::decode(&hex_key)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid public key hex: {}", e)))?;
+    PublicKey::from_slice(&bytes)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid public key: {}", e)))?;
+    Ok(hex_key)
+}
+
+/// Bytes hashed by [`hash_message`]: the domain separator followed by the compact JSON of `message`
+pub fn message_preimage<T: serde::Serialize>(message: &T) -> Result<Vec<u8>> {
+    platarium_consensus::message_preimage(message)
+        .map_err(|e| PlatariumError::Validation(format!("Failed to serialize message: {}", e)))
+}
+
+/// Hashes a message with domain separator. The JSON is streamed into the hash, never held in memory as a whole.
 pub fn hash_message<T: serde::Serialize>(message: &T) -> Result<[u8; 32]> {
-    let json = serde_json::to_string(message)
-        .map_err(|e| PlatariumError::Validation(format!("Failed to serialize message: {}", e)))?;
-    
-    let mut hasher = Sha256::new();
-    hasher.update(DOMAIN_SEPARATOR.as_bytes());
-    hasher.update(json.as_bytes());
-    let hash = hasher.finalize();
-    
-    let mut result = [0u8; 32];
-    result.copy_from_slice(&hash);
-    Ok(result)
+    let mut hasher = MessageHasher::new();
+    update_json(&mut hasher, message)?;
+    Ok(hasher.finalize())
+}
+
+/// Feeds the compact JSON of `message` to `hasher` as it is serialized
+pub fn update_json<T: serde::Serialize>(hasher: &mut MessageHasher, message: &T) -> Result<()> {
+    serde_json::to_writer(HashWriter(hasher), message)
+        .map_err(|e| PlatariumError::Validation(format!("Failed to serialize message: {}", e)))
+}
+
+struct HashWriter<'a>(&'a mut MessageHasher);
+
+impl std::io::Write for HashWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// [`message_preimage`] under `domain` instead of the legacy mainnet domain
+pub fn message_preimage_in<T: serde::Serialize>(domain: &SigningDomain, message: &T) -> Result<Vec<u8>> {
+    platarium_consensus::message_preimage_in(domain, message)
+        .map_err(|e| PlatariumError::Validation(format!("Failed to serialize message: {}", e)))
+}
+
+/// Hashes a message with the separator of `domain`
+pub fn hash_message_in<T: serde::Serialize>(domain: &SigningDomain, message: &T) -> Result<[u8; 32]> {
+    let mut hasher = MessageHasher::in_domain(domain);
+    update_json(&mut hasher, message)?;
+    Ok(hasher.finalize())
 }
 
 /// Signs a message and returns signature components
 pub fn sign_message(private_key: &SecretKey, message: &impl serde::Serialize) -> Result<SignatureComponents> {
-    let secp = Secp256k1::new();
-    
     // Hash the message
     let hash = hash_message(message)?;
-    let msg = Message::from_digest_slice(&hash)
-        .map_err(|e| PlatariumError::Signature(format!("Invalid message hash: {}", e)))?;
+    sign_hash(private_key, &hash)
+}
+
+/// Signs an already computed 32-byte hash, e.g. from a [`MessageHasher`]. Verify with [`verify_signature_hash`].
+pub fn sign_hash(private_key: &SecretKey, hash: &[u8; 32]) -> Result<SignatureComponents> {
+    let secp = Secp256k1::new();
     
     // Sign
-    let signature = secp.sign_ecdsa(&msg, private_key);
+    let signature = secp.sign_ecdsa(&Message::from_digest(*hash), private_key);
     
     // Get public key
     let public_key = PublicKey::from_secret_key(&secp, private_key);
     
+    signature_components(&signature, &public_key)
+}
+
+/// Signs a message under `domain`. The signature only verifies with [`verify_signature_in`] and the same domain,
+/// so a testnet signature cannot be replayed on mainnet.
+pub fn sign_message_in(
+    private_key: &SecretKey,
+    domain: &SigningDomain,
+    message: &impl serde::Serialize,
+) -> Result<SignatureComponents> {
+    sign_hash(private_key, &hash_message_in(domain, message)?)
+}
+
+/// Signs raw bytes (firmware images, files, protocol frames) without a JSON round-trip. The hash is
+/// [`platarium_consensus::hash_bytes`], which uses its own domain separator, so the signature cannot be
+/// passed off as a [`sign_message`] signature.
+pub fn sign_bytes(private_key: &SecretKey, bytes: &[u8]) -> Result<SignatureComponents> {
+    sign_hash(private_key, &platarium_consensus::hash_bytes(bytes))
+}
+
+/// Verifies a signature made by [`sign_bytes`]
+pub fn verify_bytes(bytes: &[u8], signature_hex: &str, pub_key_hex: &str) -> Result<bool> {
+    verify_signature_hash(&platarium_consensus::hash_bytes(bytes), signature_hex, pub_key_hex)
+}
+
+/// Splits a signature into the components returned by [`sign_message`]
+pub(crate) fn signature_components(signature: &Signature, public_key: &PublicKey) -> Result<SignatureComponents> {
+    // Ensure low-S (canonical form) before anything is encoded
+    let signature_low_s = ensure_low_s(signature)?;
+    
     // Extract r and s values
-    let sig_bytes = signature.serialize_compact();
+    let sig_bytes = signature_low_s.serialize_compact();
     let r_hex = hex::encode(&sig_bytes[..32]);
     let s_hex = hex::encode(&sig_bytes[32..]);
     
-    // Ensure low-S (canonical form)
-    let signature_low_s = ensure_low_s(&signature)?;
-    
     Ok(SignatureComponents {
+        scheme: SignatureScheme::Ecdsa,
         r: format!("{:0>64}", r_hex),
         s: format!("{:0>64}", s_hex),
         pub_key: hex::encode(public_key.serialize()),
         der: signature_low_s.serialize_der().to_vec(),
-        signature_compact: format!("{}{}", hex::encode(signature_low_s.serialize_compact()), "01"),
+        signature_compact: format!("{}{}", hex::encode(sig_bytes), "01"),
+    })
+}
+
+/// Signs a message like [`sign_message`], but `signature_compact` ends in the real recovery id (`00`-`03`)
+/// instead of the fixed `01`, so [`recover_pubkey`] can rebuild the public key from the signature alone. The
+/// first 128 hex characters are the same compact signature and verify as usual.
+pub fn sign_message_recoverable(private_key: &SecretKey, message: &impl serde::Serialize) -> Result<SignatureComponents> {
+    let secp = Secp256k1::new();
+    let msg = Message::from_digest(hash_message(message)?);
+    let recoverable = secp.sign_ecdsa_recoverable(&msg, private_key);
+    let (recovery_id, compact) = recoverable.serialize_compact();
+    let public_key = PublicKey::from_secret_key(&secp, private_key);
+    let mut components = signature_components(&recoverable.to_standard(), &public_key)?;
+    components.signature_compact = format!("{}{:02x}", hex::encode(compact), recovery_id.to_i32());
+    Ok(components)
+}
+
+/// Public key (compressed hex, as in [`SignatureComponents::pub_key`]) that produced a recoverable signature
+/// over `message`. The signature must be 65 bytes: compact `r || s` followed by the recovery id, as returned by
+/// [`sign_message_recoverable`]. Recovery succeeds for any well-formed signature, so compare the result with
+/// the expected signer (e.g. its address) rather than treating success as verification.
+pub fn recover_pubkey(message: &impl serde::Serialize, signature_hex: &str) -> Result<String> {
+    recover_pubkey_hash(&hash_message(message)?, signature_hex)
+}
+
+/// [`recover_pubkey`] over an already computed 32-byte message hash.
+pub fn recover_pubkey_hash(hash: &[u8], signature_hex: &str) -> Result<String> {
+    let msg = Message::from_digest_slice(hash)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid message hash: {}", e)))?;
+    let sig_bytes = hex::decode(signature_hex)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid signature hex: {}", e)))?;
+    if sig_bytes.len() != 65 {
+        return Err(PlatariumError::Signature(format!(
+            "Recoverable signature must be 65 bytes, got {}",
+            sig_bytes.len()
+        )));
+    }
+    let recovery_id = RecoveryId::from_i32(i32::from(sig_bytes[64]))
+        .map_err(|_| PlatariumError::Signature(format!("Invalid recovery id: {}", sig_bytes[64])))?;
+    let signature = RecoverableSignature::from_compact(&sig_bytes[..64], recovery_id)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid compact signature: {}", e)))?;
+    let public_key = Secp256k1::verification_only()
+        .recover_ecdsa(&msg, &signature)
+        .map_err(|e| PlatariumError::Signature(format!("Public key recovery failed: {}", e)))?;
+    Ok(hex::encode(public_key.serialize()))
+}
+
+/// Signs a message with BIP-340 Schnorr over the same hash as [`sign_message`]. Deterministic (no auxiliary
+/// randomness). `r` is the nonce point's x coordinate, `der` is empty and `signature_compact` is the 64-byte signature.
+pub fn sign_message_schnorr(private_key: &SecretKey, message: &impl serde::Serialize) -> Result<SignatureComponents> {
+    let secp = Secp256k1::new();
+    let hash = hash_message(message)?;
+    let msg = Message::from_digest(hash);
+    let keypair = Keypair::from_secret_key(&secp, private_key);
+    let signature = secp.sign_schnorr_no_aux_rand(&msg, &keypair);
+    let sig_bytes = signature.serialize();
+    Ok(SignatureComponents {
+        scheme: SignatureScheme::Schnorr,
+        r: hex::encode(&sig_bytes[..32]),
+        s: hex::encode(&sig_bytes[32..]),
+        pub_key: hex::encode(PublicKey::from_secret_key(&secp, private_key).serialize()),
+        der: Vec::new(),
+        signature_compact: hex::encode(sig_bytes),
     })
 }
 
+/// Verifies a BIP-340 Schnorr signature over a 32-byte hash. The public key may be compressed (33 bytes) or x-only (32 bytes).
+pub fn verify_schnorr_hash(hash: &[u8], signature_hex: &str, pub_key_hex: &str) -> Result<bool> {
+    let msg = Message::from_digest_slice(hash)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid message hash: {}", e)))?;
+    let sig_bytes = hex::decode(normalize_signature_hex(signature_hex))
+        .map_err(|e| PlatariumError::Signature(format!("Invalid signature hex: {}", e)))?;
+    let signature = schnorr::Signature::from_slice(&sig_bytes)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid Schnorr signature: {}", e)))?;
+    let pub_key_bytes = hex::decode(pub_key_hex)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid public key hex: {}", e)))?;
+    let x_only = if pub_key_bytes.len() == 32 {
+        XOnlyPublicKey::from_slice(&pub_key_bytes)
+    } else {
+        PublicKey::from_slice(&pub_key_bytes).map(|pk| pk.x_only_public_key().0)
+    }
+    .map_err(|e| PlatariumError::Signature(format!("Invalid public key: {}", e)))?;
+    Ok(Secp256k1::verification_only().verify_schnorr(&signature, &msg, &x_only).is_ok())
+}
+
+/// Verifies a signature over a 32-byte hash under `scheme`.
+pub fn verify_signature_hash_with(
+    scheme: SignatureScheme,
+    hash: &[u8],
+    signature_hex: &str,
+    pub_key_hex: &str,
+) -> Result<bool> {
+    match scheme {
+        SignatureScheme::Ecdsa => verify_signature_hash(hash, signature_hex, pub_key_hex),
+        SignatureScheme::Schnorr => verify_schnorr_hash(hash, signature_hex, pub_key_hex),
+    }
+}
+
 /// Verifies a signature
 pub fn verify_signature(
     message: &impl serde::Serialize,
     signature_hex: &str,
     pub_key_hex: &str,
 ) -> Result<bool> {
-    let secp = Secp256k1::new();
-    
-    // Hash the message
     let hash = hash_message(message)?;
-    let msg = Message::from_digest_slice(&hash)
+    verify_signature_hash(&hash, signature_hex, pub_key_hex)
+}
+
+/// Verifies a signature made by [`sign_message_in`] under `domain`
+pub fn verify_signature_in(
+    domain: &SigningDomain,
+    message: &impl serde::Serialize,
+    signature_hex: &str,
+    pub_key_hex: &str,
+) -> Result<bool> {
+    verify_signature_hash(&hash_message_in(domain, message)?, signature_hex, pub_key_hex)
+}
+
+/// Verifies a signature over an already computed 32-byte message hash (e.g. a decoded transaction hash).
+///
+/// Lenient about the encoding: compact (with or without the CLI's trailing recovery byte) or DER. A high-S
+/// signature parses but does not verify. Use [`verify_signature_hash_strict`] to also reject malformed input.
+pub fn verify_signature_hash(hash: &[u8], signature_hex: &str, pub_key_hex: &str) -> Result<bool> {
+    let signature = parse_ecdsa_signature(signature_hex, SignatureFormat::Any)?;
+    verify_parsed(hash, &signature, pub_key_hex)
+}
+
+/// Verifies a signature over a 32-byte hash in strict mode: `signature_hex` must be exactly one encoding allowed
+/// by `format` (no recovery byte, no separators), DER must be canonical, and a high-S signature is an error rather
+/// than a failed verification.
+pub fn verify_signature_hash_strict(
+    hash: &[u8],
+    signature_hex: &str,
+    pub_key_hex: &str,
+    format: SignatureFormat,
+) -> Result<bool> {
+    let signature = parse_ecdsa_signature_strict(signature_hex, format)?;
+    verify_parsed(hash, &signature, pub_key_hex)
+}
+
+/// [`verify_signature`] in strict mode (see [`verify_signature_hash_strict`])
+pub fn verify_signature_strict(
+    message: &impl serde::Serialize,
+    signature_hex: &str,
+    pub_key_hex: &str,
+    format: SignatureFormat,
+) -> Result<bool> {
+    verify_signature_hash_strict(&hash_message(message)?, signature_hex, pub_key_hex, format)
+}
+
+fn verify_parsed(hash: &[u8], signature: &Signature, pub_key_hex: &str) -> Result<bool> {
+    let msg = Message::from_digest_slice(hash)
         .map_err(|e| PlatariumError::Signature(format!("Invalid message hash: {}", e)))?;
     
-    // Parse signature (compact may include trailing recovery byte from CLI)
-    let sig_bytes = hex::decode(normalize_signature_hex(signature_hex))
-        .map_err(|e| PlatariumError::Signature(format!("Invalid signature hex: {}", e)))?;
-    
-    let signature = if sig_bytes.len() == 64 {
-        // Compact format
-        let compact: [u8; 64] = sig_bytes.try_into()
-            .map_err(|_| PlatariumError::Signature("Invalid signature length".to_string()))?;
-        Signature::from_compact(&compact)
-            .map_err(|e| PlatariumError::Signature(format!("Invalid compact signature: {}", e)))?
-    } else {
-        // DER format
-        Signature::from_der(&sig_bytes)
-            .map_err(|e| PlatariumError::Signature(format!("Invalid DER signature: {}", e)))?
-    };
-    
     // Parse public key
     let pub_key_bytes = hex::decode(pub_key_hex)
         .map_err(|e| PlatariumError::Signature(format!("Invalid public key hex: {}", e)))?;
@@ -103,25 +350,328 @@ pub fn verify_signature(
         .map_err(|e| PlatariumError::Signature(format!("Invalid public key: {}", e)))?;
     
     // Verify
-    Ok(secp.verify_ecdsa(&msg, &signature, &pub_key).is_ok())
+    Ok(Secp256k1::verification_only().verify_ecdsa(&msg, signature, &pub_key).is_ok())
+}
+
+/// Parses an ECDSA signature as [`verify_signature_hash`] does. `format` limits the accepted encodings; with
+/// [`SignatureFormat::Any`], 64 or 65 bytes is compact (the 65th being the CLI's recovery byte) and anything
+/// else is DER. Characters other than hex digits are ignored.
+pub fn parse_ecdsa_signature(signature_hex: &str, format: SignatureFormat) -> Result<Signature> {
+    let hex_digits: String = signature_hex.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+    let format = match format {
+        SignatureFormat::Any if matches!(hex_digits.len(), 128 | 130) => SignatureFormat::Compact,
+        SignatureFormat::Any => SignatureFormat::Der,
+        other => other,
+    };
+    // Compact may include trailing recovery byte from CLI; DER is decoded whole
+    let hex_sig = if format == SignatureFormat::Compact { normalize_signature_hex(&hex_digits) } else { hex_digits };
+    let sig_bytes = hex::decode(hex_sig)
+        .map_err(|e| PlatariumError::Signature(format!("Invalid signature hex: {}", e)))?;
+    decode_signature(&sig_bytes, format)
+}
+
+/// Parses an ECDSA signature in strict mode ([`strict::parse`]), additionally requiring an encoding allowed by
+/// `format`.
+pub fn parse_ecdsa_signature_strict(signature_hex: &str, format: SignatureFormat) -> Result<Signature> {
+    let compact = signature_hex.len() == 128;
+    match (format, compact) {
+        (SignatureFormat::Compact, false) => Err(PlatariumError::Signature("Expected a compact signature".to_string())),
+        (SignatureFormat::Der, true) => Err(PlatariumError::Signature("Expected a DER signature".to_string())),
+        _ => Ok(strict::parse(signature_hex)?),
+    }
+}
+
+fn decode_signature(sig_bytes: &[u8], format: SignatureFormat) -> Result<Signature> {
+    let compact = match format {
+        SignatureFormat::Any => sig_bytes.len() == 64,
+        SignatureFormat::Compact => true,
+        SignatureFormat::Der => false,
+    };
+    if compact {
+        Signature::from_compact(sig_bytes)
+            .map_err(|e| PlatariumError::Signature(format!("Invalid compact signature: {}", e)))
+    } else {
+        Signature::from_der(sig_bytes)
+            .map_err(|e| PlatariumError::Signature(format!("Invalid DER signature: {}", e)))
+    }
 }
 
-/// Ensures signature is in low-S (canonical) form
+/// Returns the signature with S in the lower half of the curve order (the canonical, non-malleable form)
 fn ensure_low_s(signature: &Signature) -> Result<Signature> {
-    // secp256k1 library's sign_ecdsa already ensures low-S
-    // Return as-is
-    Ok(*signature)
+    // sign_ecdsa already produces low-S, but signatures from devices or other libraries may not
+    let mut normalized = *signature;
+    normalized.normalize_s();
+    Ok(normalized)
+}
+
+/// ECDSA signature encodings accepted by a parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureFormat {
+    /// Compact if 64 bytes, DER otherwise.
+    #[default]
+    Any,
+    /// 64-byte `r || s`.
+    Compact,
+    /// ASN.1 DER.
+    Der,
 }
 
-#[derive(Debug, Clone)]
+/// A signature with its public key. In JSON, `der` is hex and a missing `scheme` means ECDSA.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SignatureComponents {
+    #[serde(default)]
+    pub scheme: SignatureScheme,
     pub r: String,
     pub s: String,
     pub pub_key: String,
+    #[serde(with = "hex_bytes")]
     pub der: Vec<u8>,
     pub signature_compact: String,
 }
 
+impl SignatureComponents {
+    /// ECDSA components from a compact signature (128 hex, optionally followed by the recovery byte) and a public
+    /// key. High-S signatures are normalized.
+    pub fn from_compact_hex(signature_hex: &str, pub_key_hex: &str) -> Result<Self> {
+        Self::from_hex(signature_hex, SignatureFormat::Compact, pub_key_hex)
+    }
+
+    /// ECDSA components from a DER signature and a public key. High-S signatures are normalized.
+    pub fn from_der_hex(der_hex: &str, pub_key_hex: &str) -> Result<Self> {
+        Self::from_hex(der_hex, SignatureFormat::Der, pub_key_hex)
+    }
+
+    fn from_hex(signature_hex: &str, format: SignatureFormat, pub_key_hex: &str) -> Result<Self> {
+        let signature = parse_ecdsa_signature(signature_hex, format)?;
+        signature_components(&signature, &PublicKey::from_str(&normalize_public_key_hex(pub_key_hex)?)
+            .map_err(|e| PlatariumError::Signature(format!("Invalid public key: {}", e)))?)
+    }
+
+    /// The 64-byte signature as 128 hex characters, without the recovery byte.
+    pub fn to_compact_hex(&self) -> String {
+        normalize_signature_hex(&self.signature_compact)
+    }
+
+    /// The DER signature as hex; empty for Schnorr, which has no DER form.
+    pub fn to_der_hex(&self) -> String {
+        hex::encode(&self.der)
+    }
+}
+
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<u8>, D::Error> {
+        hex::decode(String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Malleability-hard ECDSA verification.
+///
+/// A signature is accepted only in one exact form: 64-byte compact, or canonical DER of at most
+/// [`MAX_DER_LEN`] bytes with nothing after it, with neither scalar zero and S in the lower half of the curve
+/// order. Anything else is rejected with a [`StrictSignatureError`] saying why, before the key is looked at.
+pub mod strict {
+    use secp256k1::ecdsa::Signature;
+    use serde::Serialize;
+    use thiserror::Error;
+    use crate::error::{PlatariumError, Result};
+
+    /// Longest DER encoding of a secp256k1 signature (two 33-byte integers plus headers).
+    pub const MAX_DER_LEN: usize = 72;
+
+    /// Why a signature was rejected by [`parse`].
+    #[derive(Error, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum StrictSignatureError {
+        #[error("signature is not hex")]
+        InvalidHex,
+
+        #[error("signature of {0} bytes is neither compact nor DER")]
+        InvalidLength(usize),
+
+        #[error("bytes after the end of the signature")]
+        TrailingBytes,
+
+        #[error("DER signature of {0} bytes is longer than {MAX_DER_LEN}")]
+        DerTooLong(usize),
+
+        #[error("non-canonical DER signature")]
+        NonCanonicalDer,
+
+        #[error("malformed signature (scalar out of range)")]
+        Malformed,
+
+        #[error("zero r or s")]
+        ZeroScalar,
+
+        #[error("High-S signature")]
+        HighS,
+    }
+
+    impl From<StrictSignatureError> for PlatariumError {
+        fn from(e: StrictSignatureError) -> Self {
+            PlatariumError::Signature(format!("Strict signature check failed: {}", e))
+        }
+    }
+
+    /// Parses `signature_hex` under the strict rules.
+    pub fn parse(signature_hex: &str) -> std::result::Result<Signature, StrictSignatureError> {
+        let bytes = hex::decode(signature_hex).map_err(|_| StrictSignatureError::InvalidHex)?;
+        let signature = match bytes.len() {
+            64 => {
+                if bytes[..32].iter().all(|&b| b == 0) || bytes[32..].iter().all(|&b| b == 0) {
+                    return Err(StrictSignatureError::ZeroScalar);
+                }
+                Signature::from_compact(&bytes).map_err(|_| StrictSignatureError::Malformed)?
+            }
+            len if len > 2 && bytes[0] == 0x30 => {
+                if usize::from(bytes[1]) + 2 < len {
+                    return Err(StrictSignatureError::TrailingBytes);
+                }
+                if len > MAX_DER_LEN {
+                    return Err(StrictSignatureError::DerTooLong(len));
+                }
+                let signature = Signature::from_der(&bytes).map_err(|_| StrictSignatureError::NonCanonicalDer)?;
+                if signature.serialize_der().as_ref() != bytes.as_slice() {
+                    return Err(StrictSignatureError::NonCanonicalDer);
+                }
+                let compact = signature.serialize_compact();
+                if compact[..32].iter().all(|&b| b == 0) || compact[32..].iter().all(|&b| b == 0) {
+                    return Err(StrictSignatureError::ZeroScalar);
+                }
+                signature
+            }
+            65 => return Err(StrictSignatureError::TrailingBytes),
+            len => return Err(StrictSignatureError::InvalidLength(len)),
+        };
+        let mut low_s = signature;
+        low_s.normalize_s();
+        if low_s != signature {
+            return Err(StrictSignatureError::HighS);
+        }
+        Ok(signature)
+    }
+
+    /// Verifies a signature over a 32-byte hash, rejecting every encoding [`parse`] rejects.
+    pub fn verify_hash(hash: &[u8], signature_hex: &str, pub_key_hex: &str) -> Result<bool> {
+        let signature = parse(signature_hex)?;
+        super::verify_parsed(hash, &signature, pub_key_hex)
+    }
+
+    /// [`verify_hash`] over [`super::hash_message`] of `message`.
+    pub fn verify(message: &impl serde::Serialize, signature_hex: &str, pub_key_hex: &str) -> Result<bool> {
+        verify_hash(&super::hash_message(message)?, signature_hex, pub_key_hex)
+    }
+}
+
+/// Known-bad signature encodings for checking that a verifier rejects malleable or malformed signatures.
+///
+/// Every vector signs the same hash with the same key; [`reference`] is the valid signature the others are
+/// mangled from. They are built from fixed inputs, so they are identical on every run, and serialize to JSON
+/// for use in other languages.
+pub mod test_vectors {
+    use secp256k1::{PublicKey, Secp256k1, SecretKey};
+    use serde::Serialize;
+    use super::strict::StrictSignatureError;
+
+    /// Secret key of every vector. Public test data: never use it for funds.
+    pub const SECRET_KEY: [u8; 32] = [0x01; 32];
+
+    /// Message whose [`super::hash_message`] every vector signs.
+    pub const MESSAGE: &str = "platarium strict signature test vector";
+
+    /// One signature to check, with the rejection [`super::strict::parse`] gives (`None` if valid).
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+    pub struct SignatureVector {
+        pub name: &'static str,
+        /// Hex of the 32-byte message hash.
+        pub hash: String,
+        /// Compressed public key hex.
+        pub pub_key: String,
+        /// Signature hex.
+        pub signature: String,
+        pub rejection: Option<StrictSignatureError>,
+    }
+
+    /// The valid low-S compact signature.
+    pub fn reference() -> SignatureVector {
+        let (hash, pub_key, compact) = parts();
+        vector("valid_compact", &hash, &pub_key, compact.to_vec(), None)
+    }
+
+    /// Signatures a strict verifier must reject.
+    pub fn negative() -> Vec<SignatureVector> {
+        use StrictSignatureError::*;
+        let (hash, pub_key, compact) = parts();
+        let (r, s) = compact.split_at(32);
+        let high_s = SecretKey::from_slice(s).expect("s is a valid scalar").negate().secret_bytes();
+        let der = der_encoding(&minimal(r), &minimal(s));
+        let over_length = der_encoding(&[&[0u8; 4][..], r].concat(), &minimal(s));
+        let non_minimal_r = der_encoding(&[&[0u8][..], &minimal(r)].concat(), &minimal(s));
+        let order = hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141").unwrap();
+        let v = |name, signature: Vec<u8>, rejection| vector(name, &hash, &pub_key, signature, Some(rejection));
+        vec![
+            v("high_s_compact", [r, &high_s[..]].concat(), HighS),
+            v("high_s_der", der_encoding(&minimal(r), &minimal(&high_s)), HighS),
+            v("compact_recovery_byte", [&compact[..], &[0x01]].concat(), TrailingBytes),
+            v("der_trailing_byte", [&der[..], &[0x00]].concat(), TrailingBytes),
+            v("der_over_length", over_length.clone(), DerTooLong(over_length.len())),
+            v("der_non_minimal_r", non_minimal_r, NonCanonicalDer),
+            v("zero_r", [&[0u8; 32][..], s].concat(), ZeroScalar),
+            v("zero_s", [r, &[0u8; 32][..]].concat(), ZeroScalar),
+            v("r_equals_order", [&order[..], s].concat(), Malformed),
+            v("truncated", compact[..63].to_vec(), InvalidLength(63)),
+            v("empty", Vec::new(), InvalidLength(0)),
+        ]
+    }
+
+    fn parts() -> ([u8; 32], PublicKey, [u8; 64]) {
+        let secret_key = SecretKey::from_slice(&SECRET_KEY).expect("fixed key is valid");
+        let hash = super::hash_message(&MESSAGE).expect("a string serializes");
+        let signature = Secp256k1::new().sign_ecdsa(&secp256k1::Message::from_digest(hash), &secret_key);
+        (hash, PublicKey::from_secret_key(&Secp256k1::new(), &secret_key), signature.serialize_compact())
+    }
+
+    fn vector(
+        name: &'static str,
+        hash: &[u8; 32],
+        pub_key: &PublicKey,
+        signature: Vec<u8>,
+        rejection: Option<StrictSignatureError>,
+    ) -> SignatureVector {
+        SignatureVector {
+            name,
+            hash: hex::encode(hash),
+            pub_key: hex::encode(pub_key.serialize()),
+            signature: hex::encode(signature),
+            rejection,
+        }
+    }
+
+    /// Big-endian integer as DER content: no leading zeros, and a zero byte in front if the top bit is set.
+    fn minimal(bytes: &[u8]) -> Vec<u8> {
+        let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len() - 1);
+        let mut out = if bytes[start] >= 0x80 { vec![0x00] } else { Vec::new() };
+        out.extend_from_slice(&bytes[start..]);
+        out
+    }
+
+    /// DER `SEQUENCE { INTEGER r, INTEGER s }` from the integers' content bytes, taken as given.
+    fn der_encoding(r: &[u8], s: &[u8]) -> Vec<u8> {
+        let mut out = vec![0x30, (4 + r.len() + s.len()) as u8, 0x02, r.len() as u8];
+        out.extend_from_slice(r);
+        out.extend_from_slice(&[0x02, s.len() as u8]);
+        out.extend_from_slice(s);
+        out
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,5 +695,138 @@ mod tests {
         let verified = verify_signature(&message, &sig_components.signature_compact[..128], &sig_components.pub_key).unwrap();
         assert!(verified);
     }
+
+    #[test]
+    fn test_signing_domain() {
+        let secret_key = SecretKey::from_slice(&[2; 32]).unwrap();
+        let message = serde_json::json!({"test": "domain"});
+        let testnet = SigningDomain::for_chain("platarium-testnet");
+        let sig = sign_message_in(&secret_key, &testnet, &message).unwrap();
+        assert!(verify_signature_in(&testnet, &message, &sig.signature_compact, &sig.pub_key).unwrap());
+        assert!(!verify_signature(&message, &sig.signature_compact, &sig.pub_key).unwrap());
+        assert!(!verify_signature_in(&SigningDomain::for_chain("platarium-mainnet"), &message, &sig.signature_compact, &sig.pub_key).unwrap());
+
+        let legacy = sign_message_in(&secret_key, &SigningDomain::default(), &message).unwrap();
+        assert_eq!(legacy.signature_compact, sign_message(&secret_key, &message).unwrap().signature_compact);
+    }
+
+    #[test]
+    fn test_sign_bytes() {
+        let secret_key = SecretKey::from_slice(&[4; 32]).unwrap();
+        let payload = br#"{"test":"bytes"}"#;
+        let sig = sign_bytes(&secret_key, payload).unwrap();
+        assert!(verify_bytes(payload, &sig.signature_compact, &sig.pub_key).unwrap());
+        assert!(!verify_bytes(b"tampered", &sig.signature_compact, &sig.pub_key).unwrap());
+
+        let as_json: serde_json::Value = serde_json::from_slice(payload).unwrap();
+        assert!(!verify_signature(&as_json, &sig.signature_compact, &sig.pub_key).unwrap());
+    }
+
+    #[test]
+    fn test_strict_parsing() {
+        let secret_key = SecretKey::from_slice(&[5; 32]).unwrap();
+        let message = serde_json::json!({"test": "strict"});
+        let sig = sign_message(&secret_key, &message).unwrap();
+        let compact = &sig.signature_compact[..128];
+        let der = hex::encode(&sig.der);
+        for (signature, format) in [(compact, SignatureFormat::Compact), (der.as_str(), SignatureFormat::Der), (compact, SignatureFormat::Any)] {
+            assert!(verify_signature_strict(&message, signature, &sig.pub_key, format).unwrap());
+        }
+        assert!(verify_signature_strict(&message, &der, &sig.pub_key, SignatureFormat::Compact).is_err());
+        assert!(verify_signature_strict(&message, &sig.signature_compact, &sig.pub_key, SignatureFormat::Any).is_err());
+        assert!(verify_signature_strict(&message, &format!("{}00", der), &sig.pub_key, SignatureFormat::Der).is_err());
+
+        // n - s: same (r, s) pair up to sign, rejected by verification and an error in strict mode
+        let high_s = SecretKey::from_slice(&hex::decode(&sig.s).unwrap()).unwrap().negate();
+        let high_s = format!("{}{}", sig.r, hex::encode(high_s.secret_bytes()));
+        assert!(!verify_signature(&message, &high_s, &sig.pub_key).unwrap());
+        let err = verify_signature_strict(&message, &high_s, &sig.pub_key, SignatureFormat::Compact).unwrap_err();
+        assert!(err.to_string().contains("High-S"), "{}", err);
+        let normalized = ensure_low_s(&parse_ecdsa_signature(&high_s, SignatureFormat::Compact).unwrap()).unwrap();
+        assert_eq!(hex::encode(normalized.serialize_compact()), compact);
+    }
+
+    #[test]
+    fn test_streaming_hash() {
+        let body: Vec<u64> = (0..50_000).collect();
+        let message = serde_json::json!({"height": 7, "body": body});
+        let hash = hash_message(&message).unwrap();
+        assert_eq!(hash, platarium_consensus::hash_message(&message).unwrap());
+
+        let secret_key = SecretKey::from_slice(&[6; 32]).unwrap();
+        let mut hasher = MessageHasher::for_bytes();
+        for chunk in [b"part one ".as_slice(), b"part two"] {
+            hasher.update(chunk);
+        }
+        let sig = sign_hash(&secret_key, &hasher.finalize()).unwrap();
+        assert!(verify_bytes(b"part one part two", &sig.signature_compact, &sig.pub_key).unwrap());
+    }
+
+    #[test]
+    fn test_components_serde_and_hex() {
+        let secret_key = SecretKey::from_slice(&[8; 32]).unwrap();
+        let sig = sign_message(&secret_key, &serde_json::json!({"test": "serde"})).unwrap();
+        let json = serde_json::to_value(&sig).unwrap();
+        assert_eq!(json["der"], sig.to_der_hex());
+        assert_eq!(serde_json::from_value::<SignatureComponents>(json).unwrap(), sig);
+
+        assert_eq!(SignatureComponents::from_compact_hex(&sig.signature_compact, &sig.pub_key).unwrap(), sig);
+        let from_der = SignatureComponents::from_der_hex(&sig.to_der_hex(), &format!("Px{}", sig.pub_key)).unwrap();
+        assert_eq!(from_der, sig);
+        assert_eq!(from_der.to_compact_hex().len(), 128);
+        assert!(SignatureComponents::from_der_hex(&sig.to_compact_hex(), &sig.pub_key).is_err());
+        assert!(verify_signature(&serde_json::json!({"test": "serde"}), &sig.to_der_hex(), &sig.pub_key).unwrap());
+    }
+
+    #[test]
+    fn test_negative_vectors() {
+        let reference = test_vectors::reference();
+        let hash = hex::decode(&reference.hash).unwrap();
+        assert_eq!(hex::encode(strict::parse(&reference.signature).unwrap().serialize_compact()), reference.signature);
+        assert!(strict::verify_hash(&hash, &reference.signature, &reference.pub_key).unwrap());
+
+        for vector in test_vectors::negative() {
+            assert_eq!(strict::parse(&vector.signature).err(), vector.rejection, "{}", vector.name);
+            assert!(strict::verify_hash(&hash, &vector.signature, &vector.pub_key).is_err(), "{}", vector.name);
+            // The lenient verifier only tolerates the CLI's trailing recovery byte
+            let lenient = verify_signature_hash(&hash, &vector.signature, &vector.pub_key);
+            assert_eq!(matches!(lenient, Ok(true)), vector.name == "compact_recovery_byte", "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_recover_pubkey() {
+        let secret_key = SecretKey::from_slice(&[3; 32]).unwrap();
+        let message = serde_json::json!({"test": "recover"});
+        let sig = sign_message_recoverable(&secret_key, &message).unwrap();
+        assert_eq!(sig.signature_compact.len(), 130);
+        assert_eq!(recover_pubkey(&message, &sig.signature_compact).unwrap(), sig.pub_key);
+        assert!(verify_signature(&message, &sig.signature_compact, &sig.pub_key).unwrap());
+
+        let other = serde_json::json!({"test": "other"});
+        assert_ne!(recover_pubkey(&other, &sig.signature_compact).ok(), Some(sig.pub_key.clone()));
+        assert!(recover_pubkey(&message, &sig.signature_compact[..128]).is_err());
+        let bad_id = format!("{}04", &sig.signature_compact[..128]);
+        assert!(recover_pubkey(&message, &bad_id).is_err());
+    }
+
+    #[test]
+    fn test_schnorr_sign_and_verify() {
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let message = serde_json::json!({"test": "message"});
+        let sig = sign_message_schnorr(&secret_key, &message).unwrap();
+        assert_eq!(sig.scheme, SignatureScheme::Schnorr);
+        assert_eq!(sig.signature_compact, sign_message_schnorr(&secret_key, &message).unwrap().signature_compact);
+
+        let hash = hash_message(&message).unwrap();
+        let x_only = &sig.pub_key[2..];
+        assert!(verify_schnorr_hash(&hash, &sig.signature_compact, &sig.pub_key).unwrap());
+        assert!(verify_schnorr_hash(&hash, &sig.signature_compact, x_only).unwrap());
+        assert!(!verify_signature_hash_with(SignatureScheme::Schnorr, &[0; 32], &sig.signature_compact, &sig.pub_key).unwrap());
+        assert!(!matches!(
+            verify_signature_hash_with(SignatureScheme::Ecdsa, &hash, &sig.signature_compact, &sig.pub_key),
+            Ok(true)
+        ));
+    }
 }
 
@@ -1,85 +1,268 @@
-use bip39::{Language, Mnemonic};
-use secp256k1::SecretKey;
+use secp256k1::ecdsa::Signature;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
 use sha2::Sha256;
 use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+use crate::core::transaction::{same_public_key, SigningPayload, Transaction, TransactionValidationError};
 use crate::error::{PlatariumError, Result};
-use crate::signature::{sign_message, hash_message};
+use crate::mnemonic::parse_any_language;
+use crate::signature::{
+    hash_message, normalize_public_key_hex, signature_components, verify_signature, verify_signature_hash, SignatureComponents,
+};
+use crate::utils::NetworkId;
 
-/// Generates master seed from mnemonic with alphanumeric part
-fn generate_master_seed(mnemonic: &str, alphanumeric_part: &str) -> Result<Vec<u8>> {
-    let mnemonic_obj = Mnemonic::parse_in_normalized(Language::English, mnemonic)?;
-    Ok(mnemonic_obj.to_seed(alphanumeric_part).to_vec())
+/// Source of secp256k1 ECDSA signatures over 32-byte digests.
+///
+/// Implemented by [`SoftwareSigner`] for in-memory keys; a hardware wallet or HSM implements it by
+/// forwarding the digest to the device, so the private key never enters this process. Signatures are
+/// normalized to low-S and checked against [`Signer::public_key`] before they are used.
+pub trait Signer {
+    /// Public key the device signs for.
+    fn public_key(&self) -> Result<PublicKey>;
+
+    /// Signs `digest` (already hashed; the signer must not hash it again).
+    fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature>;
+}
+
+/// [`Signer`] over a raw secret key held in memory. The key is erased when the signer is dropped.
+#[derive(Debug, Clone)]
+pub struct SoftwareSigner {
+    secret_key: SecretKey,
+}
+
+impl SoftwareSigner {
+    pub fn new(secret_key: SecretKey) -> Self {
+        Self { secret_key }
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        Ok(Self::new(SecretKey::from_slice(bytes)?))
+    }
+
+    /// Signer for an exported private key string: a main key (`PSx` + 64 hex) or signature key (`Sx` + 64 hex).
+    pub fn from_private_key_str(private_key: &str) -> Result<Self> {
+        let hex_key = private_key
+            .strip_prefix("PSx")
+            .or_else(|| private_key.strip_prefix("Sx"))
+            .ok_or_else(|| PlatariumError::Validation("private key must start with PSx or Sx".to_string()))?;
+        if hex_key.len() != 64 {
+            return Err(PlatariumError::Validation("private key must have 64 hex characters".to_string()));
+        }
+        let bytes = Zeroizing::new(
+            hex::decode(hex_key).map_err(|e| PlatariumError::Crypto(format!("Invalid private key: {}", e)))?,
+        );
+        Self::from_slice(&bytes)
+    }
+}
+
+impl Drop for SoftwareSigner {
+    fn drop(&mut self) {
+        self.secret_key.non_secure_erase();
+    }
+}
+
+impl Signer for SoftwareSigner {
+    fn public_key(&self) -> Result<PublicKey> {
+        Ok(PublicKey::from_secret_key(&Secp256k1::new(), &self.secret_key))
+    }
+
+    fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature> {
+        Ok(Secp256k1::new().sign_ecdsa(&Message::from_digest(*digest), &self.secret_key))
+    }
+}
+
+/// Signs `digest` with `signer`, normalizing to low-S and rejecting a signature that does not verify under the signer's public key
+pub fn sign_digest_checked(signer: &dyn Signer, digest: &[u8; 32]) -> Result<(Signature, PublicKey)> {
+    let public_key = signer.public_key()?;
+    let mut signature = signer.sign_digest(digest)?;
+    signature.normalize_s();
+    Secp256k1::verification_only()
+        .verify_ecdsa(&Message::from_digest(*digest), &signature, &public_key)
+        .map_err(|_| PlatariumError::Signature("Signer returned a signature that does not verify".to_string()))?;
+    Ok((signature, public_key))
+}
+
+/// Signs a message with `signer` and returns signature components (same output as [`crate::sign_message`])
+pub fn sign_message_with<T: serde::Serialize>(signer: &dyn Signer, message: &T) -> Result<SignatureComponents> {
+    let (signature, public_key) = sign_digest_checked(signer, &hash_message(message)?)?;
+    signature_components(&signature, &public_key)
+}
+
+/// Signs a message with an exported `PSx…` or `Sx…` private key, for services that keep keys rather than mnemonics
+pub fn sign_with_private_key_str<T: serde::Serialize>(private_key: &str, message: &T) -> Result<SignatureComponents> {
+    sign_message_with(&SoftwareSigner::from_private_key_str(private_key)?, message)
+}
+
+/// Verifies a signature from [`sign_with_private_key_str`] against a public key given as an address (`Px…`) or bare hex
+pub fn verify_with_public_key_str<T: serde::Serialize>(message: &T, signature_hex: &str, public_key: &str) -> Result<bool> {
+    verify_signature(message, signature_hex, &normalize_public_key_hex(public_key)?)
+}
+
+/// Generates master seed from mnemonic (any supported wordlist) with alphanumeric part
+fn generate_master_seed(mnemonic: &str, alphanumeric_part: &str) -> Result<Zeroizing<[u8; 64]>> {
+    let mnemonic_obj = parse_any_language(mnemonic)?;
+    Ok(Zeroizing::new(mnemonic_obj.to_seed(alphanumeric_part)))
 }
 
 /// Derives HKDF key from seed with info
-fn derive_hkdf_key(seed: &[u8], info: &[u8]) -> Result<[u8; 32]> {
-    let hk = Hkdf::<Sha256>::new(None, seed);
-    let mut okm = [0u8; 32];
-    hk.expand(info, &mut okm)
+fn derive_hkdf_key(seed: &[u8], salt: Option<&[u8]>, info: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    let hk = Hkdf::<Sha256>::new(salt, seed);
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hk.expand(info, okm.as_mut_slice())
         .map_err(|e| PlatariumError::KeyDerivation(format!("HKDF expansion failed: {}", e)))?;
     Ok(okm)
 }
 
-/// Signs a message with both keys (main key and HKDF-derived key)
-pub fn sign_with_both_keys<T: serde::Serialize>(
-    message: &T,
+/// Derives the main and HKDF software signers for a mnemonic and alphanumeric part
+pub fn software_signers(mnemonic: &str, alphanumeric_part: &str) -> Result<(SoftwareSigner, SoftwareSigner)> {
+    software_signers_for_network(mnemonic, alphanumeric_part, &NetworkId::Mainnet)
+}
+
+/// [`software_signers`] for `network`: off mainnet, the network's HKDF salt is used, so each network has its own
+/// signing keys
+pub fn software_signers_for_network(
     mnemonic: &str,
     alphanumeric_part: &str,
-) -> Result<DualSignature> {
+    network: &NetworkId,
+) -> Result<(SoftwareSigner, SoftwareSigner)> {
     // Generate master seed
     let seed = generate_master_seed(mnemonic, alphanumeric_part)?;
     
-    // Hash the message
-    let hash = hash_message(message)?;
-    let hash_hex = hex::encode(hash);
-    
     // Derive keys using HKDF with different info strings
     let main_key_info = format!("mainKey-{}", alphanumeric_part);
     let hkdf_key_info = format!("hkdfKey-{}", alphanumeric_part);
+    let salt = (*network != NetworkId::Mainnet).then(|| network.hkdf_salt());
     
-    let main_private_key_bytes = derive_hkdf_key(&seed, main_key_info.as_bytes())?;
-    let hkdf_private_key_bytes = derive_hkdf_key(&seed, hkdf_key_info.as_bytes())?;
+    let main_private_key_bytes = derive_hkdf_key(seed.as_slice(), salt.as_deref(), main_key_info.as_bytes())?;
+    let hkdf_private_key_bytes = derive_hkdf_key(seed.as_slice(), salt.as_deref(), hkdf_key_info.as_bytes())?;
     
-    let main_private_key = SecretKey::from_slice(&main_private_key_bytes)
+    let main_private_key = SecretKey::from_slice(main_private_key_bytes.as_slice())
         .map_err(|e| PlatariumError::Crypto(format!("Invalid main private key: {}", e)))?;
     
-    let hkdf_private_key = SecretKey::from_slice(&hkdf_private_key_bytes)
+    let hkdf_private_key = SecretKey::from_slice(hkdf_private_key_bytes.as_slice())
         .map_err(|e| PlatariumError::Crypto(format!("Invalid HKDF private key: {}", e)))?;
     
+    Ok((SoftwareSigner::new(main_private_key), SoftwareSigner::new(hkdf_private_key)))
+}
+
+/// Signs a message with both keys (main key and HKDF-derived key)
+pub fn sign_with_both_keys<T: serde::Serialize>(
+    message: &T,
+    mnemonic: &str,
+    alphanumeric_part: &str,
+) -> Result<DualSignature> {
+    sign_with_both_keys_for_network(message, mnemonic, alphanumeric_part, &NetworkId::Mainnet)
+}
+
+/// [`sign_with_both_keys`] with the signing keys of `network`
+pub fn sign_with_both_keys_for_network<T: serde::Serialize>(
+    message: &T,
+    mnemonic: &str,
+    alphanumeric_part: &str,
+    network: &NetworkId,
+) -> Result<DualSignature> {
+    let (main, hkdf) = software_signers_for_network(mnemonic, alphanumeric_part, network)?;
+    sign_with_signers(message, &main, &hkdf)
+}
+
+/// Signs every message in `messages` with both keys, deriving the keys once. Returns the signatures in order,
+/// each the same as [`sign_with_both_keys`] would give for that message.
+pub fn sign_messages_with_both_keys<T: serde::Serialize>(
+    messages: &[T],
+    mnemonic: &str,
+    alphanumeric_part: &str,
+) -> Result<Vec<DualSignature>> {
+    let (main, hkdf) = software_signers(mnemonic, alphanumeric_part)?;
+    messages.iter().map(|message| sign_with_signers(message, &main, &hkdf)).collect()
+}
+
+/// Signs a message with a main and an HKDF signer, which may be in-memory keys or external devices
+pub fn sign_with_signers<T: serde::Serialize>(
+    message: &T,
+    main: &dyn Signer,
+    hkdf: &dyn Signer,
+) -> Result<DualSignature> {
+    // Hash the message
+    let hash = hash_message(message)?;
+    let hash_hex = hex::encode(hash);
+    
     // Sign with both keys
-    let main_signature = sign_message(&main_private_key, message)?;
-    let hkdf_signature = sign_message(&hkdf_private_key, message)?;
+    let main_signature = sign_message_with(main, message)?;
+    let hkdf_signature = sign_message_with(hkdf, message)?;
     
     Ok(DualSignature {
         hash: hash_hex,
         signatures: vec![
-            SignatureWithType {
-                sig_type: "main".to_string(),
-                r: main_signature.r,
-                s: main_signature.s,
-                pub_key: main_signature.pub_key,
-                der: hex::encode(&main_signature.der),
-                signature_compact: main_signature.signature_compact,
-            },
-            SignatureWithType {
-                sig_type: "hkdf".to_string(),
-                r: hkdf_signature.r,
-                s: hkdf_signature.s,
-                pub_key: hkdf_signature.pub_key,
-                der: hex::encode(&hkdf_signature.der),
-                signature_compact: hkdf_signature.signature_compact,
-            },
+            SignatureWithType::new("main", main_signature),
+            SignatureWithType::new("hkdf", hkdf_signature),
         ],
     })
 }
 
-#[derive(Debug, Clone)]
+/// Checks a [`DualSignature`] as a whole: `hash` is the hash of `message`, the signatures are the `main` and
+/// `hkdf` ones in that order, the main key is `expected_master_pubkey` and the HKDF key is `expected_hkdf_pubkey`
+/// (hex, `Px` prefix optional), the two keys differ, and both signatures verify.
+///
+/// The HKDF key has no public relation to the main key, so the pair must be known to the verifier (e.g. the
+/// account's installed keys); a seed holder can confirm it with [`crate::verify_correlation`]. Malformed input is
+/// an error, a signature or key that does not match is `Ok(false)`.
+pub fn verify_dual_signature<T: serde::Serialize>(
+    message: &T,
+    dual: &DualSignature,
+    expected_master_pubkey: &str,
+    expected_hkdf_pubkey: &str,
+) -> Result<bool> {
+    let [main, hkdf] = dual.signatures.as_slice() else {
+        return Err(PlatariumError::Signature(format!(
+            "dual signature must have 2 signatures, got {}",
+            dual.signatures.len()
+        )));
+    };
+    if main.sig_type != "main" || hkdf.sig_type != "hkdf" {
+        return Err(PlatariumError::Signature(format!(
+            "expected main and hkdf signatures, got {} and {}",
+            main.sig_type, hkdf.sig_type
+        )));
+    }
+    if same_public_key(&main.pub_key, &hkdf.pub_key) {
+        return Err(PlatariumError::Signature("HKDF key must differ from the main key".to_string()));
+    }
+    let hash = hash_message(message)?;
+    if !dual.hash.eq_ignore_ascii_case(&hex::encode(hash))
+        || !same_public_key(&main.pub_key, expected_master_pubkey)
+        || !same_public_key(&hkdf.pub_key, expected_hkdf_pubkey)
+    {
+        return Ok(false);
+    }
+    Ok(verify_signature_hash(&hash, &main.signature_compact, &main.pub_key)?
+        && verify_signature_hash(&hash, &hkdf.signature_compact, &hkdf.pub_key)?)
+}
+
+/// Fills in `pub_main`/`pub_derived` and signs the transaction hash with the main and derived signers. The hash is
+/// recomputed from the transaction data (`SigningPayload`); errors with `HashMismatch` if `tx.hash` is stale or was
+/// set to anything else, so only the data the signers see is ever signed.
+pub fn sign_transaction(tx: &mut Transaction, main: &dyn Signer, derived: &dyn Signer) -> Result<()> {
+    let digest = SigningPayload::from_transaction(tx).hash();
+    if !tx.hash.eq_ignore_ascii_case(&hex::encode(digest)) {
+        return Err(TransactionValidationError::HashMismatch(hex::encode(digest), tx.hash.clone()).into());
+    }
+    let (sig_main, pub_main) = sign_digest_checked(main, &digest)?;
+    let (sig_derived, pub_derived) = sign_digest_checked(derived, &digest)?;
+    tx.pub_main = Some(hex::encode(pub_main.serialize()));
+    tx.pub_derived = Some(hex::encode(pub_derived.serialize()));
+    tx.sig_main = hex::encode(sig_main.serialize_compact());
+    tx.sig_derived = hex::encode(sig_derived.serialize_compact());
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DualSignature {
     pub hash: String,
     pub signatures: Vec<SignatureWithType>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SignatureWithType {
     pub sig_type: String,
     pub r: String,
@@ -89,6 +272,24 @@ pub struct SignatureWithType {
     pub signature_compact: String,
 }
 
+impl SignatureWithType {
+    pub fn new(sig_type: impl Into<String>, components: SignatureComponents) -> Self {
+        SignatureWithType {
+            sig_type: sig_type.into(),
+            der: components.to_der_hex(),
+            r: components.r,
+            s: components.s,
+            pub_key: components.pub_key,
+            signature_compact: components.signature_compact,
+        }
+    }
+
+    /// The ECDSA signature as [`SignatureComponents`], re-parsed from `signature_compact` and `pub_key`.
+    pub fn components(&self) -> Result<SignatureComponents> {
+        SignatureComponents::from_compact_hex(&self.signature_compact, &self.pub_key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,5 +307,157 @@ mod tests {
         assert_eq!(result.signatures[0].sig_type, "main");
         assert_eq!(result.signatures[1].sig_type, "hkdf");
     }
+
+    #[test]
+    fn test_sign_messages_with_both_keys() {
+        use crate::mnemonic::generate_mnemonic;
+
+        let (mnemonic, alphanumeric) = generate_mnemonic().unwrap();
+        let messages: Vec<_> = (0..3).map(|i| serde_json::json!({"withdrawal": i})).collect();
+        let batch = sign_messages_with_both_keys(&messages, &mnemonic, &alphanumeric).unwrap();
+        assert_eq!(batch.len(), 3);
+        for (message, dual) in messages.iter().zip(&batch) {
+            assert_eq!(*dual, sign_with_both_keys(message, &mnemonic, &alphanumeric).unwrap());
+        }
+        assert!(sign_messages_with_both_keys::<u8>(&[], &mnemonic, &alphanumeric).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sign_with_private_key_str() {
+        let keys = crate::key_generator::KeyGenerator::default().generate_keys().unwrap();
+        let message = serde_json::json!({"test": "prefixed"});
+        let sig = sign_with_private_key_str(&keys.private_key, &message).unwrap();
+        assert!(verify_with_public_key_str(&message, &sig.signature_compact, &keys.public_key).unwrap());
+        assert!(!verify_with_public_key_str(&serde_json::json!({}), &sig.signature_compact, &keys.public_key).unwrap());
+
+        let sig = sign_with_private_key_str(&keys.signature_key, &message).unwrap();
+        assert!(verify_with_public_key_str(&message, &sig.signature_compact, &sig.pub_key).unwrap());
+        for bad in [&keys.private_key[3..], "PSx1234", &format!("Px{}", &keys.private_key[3..])] {
+            assert!(sign_with_private_key_str(bad, &message).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_verify_dual_signature() {
+        use crate::mnemonic::generate_mnemonic;
+
+        let (mnemonic, alphanumeric) = generate_mnemonic().unwrap();
+        let message = serde_json::json!({"test": "dual"});
+        let dual = sign_with_both_keys(&message, &mnemonic, &alphanumeric).unwrap();
+        let master = format!("Px{}", dual.signatures[0].pub_key);
+        let hkdf = dual.signatures[1].pub_key.clone();
+        assert!(verify_dual_signature(&message, &dual, &master, &hkdf).unwrap());
+        assert!(!verify_dual_signature(&serde_json::json!({"test": "other"}), &dual, &master, &hkdf).unwrap());
+        assert!(!verify_dual_signature(&message, &dual, &hkdf, &hkdf).unwrap());
+
+        let mut swapped = dual.clone();
+        swapped.signatures.swap(0, 1);
+        assert!(verify_dual_signature(&message, &swapped, &master, &hkdf).is_err());
+        let mut same_key = dual.clone();
+        same_key.signatures[1] = SignatureWithType { sig_type: "hkdf".to_string(), ..dual.signatures[0].clone() };
+        assert!(verify_dual_signature(&message, &same_key, &master, &hkdf).is_err());
+        let mut forged = dual.clone();
+        forged.signatures[1].signature_compact = dual.signatures[0].signature_compact.clone();
+        assert!(!verify_dual_signature(&message, &forged, &master, &hkdf).unwrap());
+
+        // A validly signed second key that is not the account's HKDF key is rejected.
+        let (other_mnemonic, other_alphanumeric) = generate_mnemonic().unwrap();
+        let other = sign_with_both_keys(&message, &other_mnemonic, &other_alphanumeric).unwrap();
+        let mut foreign = dual.clone();
+        foreign.signatures[1] = other.signatures[1].clone();
+        assert!(!verify_dual_signature(&message, &foreign, &master, &hkdf).unwrap());
+        let foreign_hkdf = foreign.signatures[1].pub_key.clone();
+        assert!(verify_dual_signature(&message, &foreign, &master, &foreign_hkdf).unwrap());
+    }
+
+    #[test]
+    fn test_signing_keys_differ_per_network() {
+        use crate::mnemonic::generate_mnemonic;
+
+        let (mnemonic, alphanumeric) = generate_mnemonic().unwrap();
+        let message = serde_json::json!({"test": "message"});
+        let pub_keys = |network: &NetworkId| {
+            let result = sign_with_both_keys_for_network(&message, &mnemonic, &alphanumeric, network).unwrap();
+            (result.signatures[0].pub_key.clone(), result.signatures[1].pub_key.clone())
+        };
+        let mainnet = pub_keys(&NetworkId::Mainnet);
+        let default = sign_with_both_keys(&message, &mnemonic, &alphanumeric).unwrap();
+        assert_eq!(mainnet.0, default.signatures[0].pub_key);
+        let testnet = pub_keys(&NetworkId::Testnet);
+        assert_ne!(mainnet.0, testnet.0);
+        assert_ne!(mainnet.1, testnet.1);
+    }
+
+    /// Stands in for a hardware device: holds its key privately and counts requests.
+    struct CountingDevice {
+        inner: SoftwareSigner,
+        requests: std::cell::Cell<usize>,
+    }
+
+    impl Signer for CountingDevice {
+        fn public_key(&self) -> Result<PublicKey> {
+            self.inner.public_key()
+        }
+
+        fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature> {
+            self.requests.set(self.requests.get() + 1);
+            self.inner.sign_digest(digest)
+        }
+    }
+
+    #[test]
+    fn test_external_signers() {
+        use std::collections::HashSet;
+        use crate::core::asset::Asset;
+        use crate::mnemonic::generate_mnemonic;
+
+        let (mnemonic, alphanumeric) = generate_mnemonic().unwrap();
+        let (main, hkdf) = software_signers(&mnemonic, &alphanumeric).unwrap();
+        let device = CountingDevice { inner: main.clone(), requests: Default::default() };
+        let message = serde_json::json!({"test": "message"});
+        let from_device = sign_with_signers(&message, &device, &hkdf).unwrap();
+        let from_mnemonic = sign_with_both_keys(&message, &mnemonic, &alphanumeric).unwrap();
+        assert_eq!(device.requests.get(), 1);
+        assert_eq!(from_device.signatures[0].signature_compact, from_mnemonic.signatures[0].signature_compact);
+
+        let address = format!("Px{}", hex::encode(main.public_key().unwrap().serialize()));
+        let mut tx = Transaction::new(
+            address,
+            "receiver".to_string(),
+            Asset::PLP,
+            5,
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap();
+        // A stale hash (data changed after hashing) or a substituted one is refused, not signed.
+        let mut stale = tx.clone();
+        stale.amount = 500;
+        assert!(sign_transaction(&mut stale, &device, &hkdf).is_err());
+        let mut substituted = tx.clone();
+        substituted.hash = "11".repeat(32);
+        assert!(sign_transaction(&mut substituted, &device, &hkdf).is_err());
+        assert!(substituted.sig_main.is_empty());
+
+        sign_transaction(&mut tx, &device, &hkdf).unwrap();
+        assert!(tx.verify_signatures().unwrap());
+
+        // A device answering with a signature for another key is rejected.
+        struct Mismatched(SoftwareSigner, PublicKey);
+        impl Signer for Mismatched {
+            fn public_key(&self) -> Result<PublicKey> {
+                Ok(self.1)
+            }
+            fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature> {
+                self.0.sign_digest(digest)
+            }
+        }
+        let bad = Mismatched(hkdf.clone(), main.public_key().unwrap());
+        assert!(sign_transaction(&mut tx, &bad, &hkdf).is_err());
+    }
 }
 
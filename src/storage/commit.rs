@@ -1,10 +1,16 @@
 //! Atomic block commit via RocksDB WriteBatch.
 
+use crate::core::address_bloom::AddressBloom;
+use crate::core::events::Event;
+use crate::core::transaction::{Transaction, TxKind, TX_FRAME_PREFIX_BYTES};
+use crate::core::load::FeeBucketRecord;
+use crate::core::outbound::{OutboundMessage, check_block_messages, messages_root};
+use crate::core::receipt::BalanceDelta;
 use crate::error::{PlatariumError, Result};
 use crate::storage::rocks::RocksStore;
 use crate::storage::schema::{
     KEY_META_HEAD, encode_u64, key_account, key_block, key_idx_addr, key_idx_block, key_receipt,
-    key_state_root, key_tx,
+    key_fee_bucket, key_outbound_message, key_slashing_event, key_state_root, key_tx,
 };
 use crate::storage::snapshot::create_snapshot_if_due;
 use rocksdb::WriteBatch;
@@ -24,6 +30,25 @@ pub struct ReceiptRecord {
     pub status: String,
     pub fee_uplp: u64,
     pub block_height: u64,
+    /// Fee rebate credited back to the sender (μPLP); 0 when no rebate policy applied.
+    #[serde(default)]
+    pub rebate_uplp: u64,
+    /// Events emitted while executing the transaction (approvals, delegated transfers, freezes).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<Event>,
+    /// Account charged the fee (`core::receipt::Receipt::fee_payer`). Indexed like from/to. Absent on failed
+    /// receipts and on records written before it was filled in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<String>,
+    /// Position of the transaction in its block; absent on receipts written before execution receipts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    /// `PlatariumError::code` of a failed transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// Net balance changes (see `core::receipt::Receipt`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balance_deltas: Vec<BalanceDelta>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -36,6 +61,17 @@ pub struct BlockRecordStored {
     pub state_root: String,
     pub block_hash: String,
     pub producer_id: String,
+    /// Bloom filter over the addresses the block's transactions and receipts touch (see `core::address_bloom`).
+    /// Filled in by `commit_block`; `None` on blocks committed before it was introduced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_bloom: Option<AddressBloom>,
+    /// Merkle root of the block's outbound messages (`core::outbound`); absent for a block without messages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages_root: Option<String>,
+    /// Total weight of the block's transactions in bytes (`Transaction::weight`), which the size target
+    /// (`query::block_size_target`) is derived from. Filled in by `commit_block`; `None` on older blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_used: Option<u64>,
 }
 
 /// Full atomic commit payload for one finalized block.
@@ -47,6 +83,92 @@ pub struct BlockCommit {
     pub accounts: Vec<AccountRecord>,
     pub receipts: Vec<ReceiptRecord>,
     pub state_root: String,
+    /// `NodeSlashed` / `NodeSuspended` events produced while finalizing the block, stored for historical queries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slashing_events: Vec<Event>,
+    /// Fee bucket and mempool occupancy at assembly time, kept as a per-height sidecar for load analysis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_bucket: Option<FeeBucketRecord>,
+    /// Outbound bridge messages sealed at this height; their root must equal `block.messages_root`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<OutboundMessage>,
+}
+
+/// Address bloom of the block: `from`, `to`, reads, writes and delegating owner of each transaction JSON, plus the
+/// fee payer and event accounts of each receipt.
+pub fn block_address_bloom(commit: &BlockCommit) -> AddressBloom {
+    let mut bloom = AddressBloom::new();
+    for tx_json in &commit.tx_jsons {
+        let Ok(v) = serde_json::from_str::<serde_json::Value>(tx_json) else {
+            continue;
+        };
+        for field in ["from", "to"] {
+            if let Some(a) = v.get(field).and_then(|x| x.as_str()) {
+                bloom.insert(a);
+            }
+        }
+        for field in ["reads", "writes"] {
+            for a in v.get(field).and_then(|x| x.as_array()).into_iter().flatten() {
+                if let Some(a) = a.as_str() {
+                    bloom.insert(a);
+                }
+            }
+        }
+        if let Some(Ok(TxKind::TransferFrom { owner })) = v.get("kind").map(|k| serde_json::from_value(k.clone())) {
+            bloom.insert(&owner);
+        }
+    }
+    for receipt in &commit.receipts {
+        if let Some(payer) = &receipt.fee_payer {
+            bloom.insert(payer);
+        }
+        for event in &receipt.events {
+            bloom.insert_event(event);
+        }
+    }
+    bloom
+}
+
+/// Block record as stored: the commit's header with its address bloom. A bloom supplied by the producer must
+/// match the one computed from the block's contents, and the header's messages root must commit to exactly the
+/// commit's outbound messages.
+fn encode_block_record(commit: &BlockCommit) -> Result<Vec<u8>> {
+    check_block_messages(commit.block.height, &commit.messages)?;
+    if messages_root(&commit.messages) != commit.block.messages_root {
+        return Err(PlatariumError::State(
+            "block.messages_root does not match the block's outbound messages".into(),
+        ));
+    }
+    let bloom = block_address_bloom(commit);
+    if commit.block.address_bloom.as_ref().is_some_and(|b| *b != bloom) {
+        return Err(PlatariumError::State(
+            "block.address_bloom does not match the block's transactions and receipts".into(),
+        ));
+    }
+    let bytes_used = block_bytes_used(&commit.tx_jsons);
+    if commit.block.bytes_used.is_some_and(|b| b != bytes_used) {
+        return Err(PlatariumError::State(
+            "block.bytes_used does not match the weight of the block's transactions".into(),
+        ));
+    }
+    let record = BlockRecordStored {
+        address_bloom: Some(bloom),
+        bytes_used: Some(bytes_used),
+        ..commit.block.clone()
+    };
+    serde_json::to_vec(&record).map_err(|e| PlatariumError::State(format!("encode block: {}", e)))
+}
+
+/// Total weight of `tx_jsons` (`Transaction::weight`); an entry that does not parse counts its framed JSON size.
+pub fn block_bytes_used(tx_jsons: &[String]) -> u64 {
+    tx_jsons
+        .iter()
+        .map(|json| {
+            Transaction::from_gateway_json(json)
+                .map(|tx| tx.weight())
+                .unwrap_or(json.len() as u64 + TX_FRAME_PREFIX_BYTES)
+        })
+        .sum()
 }
 
 /// Commit block + txs + accounts + receipts + indexes in a single WriteBatch.
@@ -74,8 +196,7 @@ pub fn commit_block(store: &RocksStore, commit: &BlockCommit) -> Result<()> {
 
     let mut batch = WriteBatch::default();
 
-    let block_bytes = serde_json::to_vec(&commit.block)
-        .map_err(|e| PlatariumError::State(format!("encode block: {}", e)))?;
+    let block_bytes = encode_block_record(commit)?;
     batch.put(key_block(height), block_bytes);
     batch.put(key_state_root(height), commit.state_root.as_bytes());
     batch.put(KEY_META_HEAD, encode_u64(height));
@@ -111,6 +232,25 @@ pub fn commit_block(store: &RocksStore, commit: &BlockCommit) -> Result<()> {
         let bytes = serde_json::to_vec(receipt)
             .map_err(|e| PlatariumError::State(format!("encode receipt: {}", e)))?;
         batch.put(key_receipt(&receipt.tx_hash), bytes);
+        if let Some(payer) = &receipt.fee_payer {
+            batch.put(key_idx_addr(payer, height, &receipt.tx_hash), b"1");
+        }
+    }
+
+    for (i, event) in commit.slashing_events.iter().filter(|e| e.is_slashing()).enumerate() {
+        let bytes = serde_json::to_vec(event)
+            .map_err(|e| PlatariumError::State(format!("encode slashing event: {}", e)))?;
+        batch.put(key_slashing_event(height, i as u32), bytes);
+    }
+    if let Some(record) = &commit.fee_bucket {
+        let bytes = serde_json::to_vec(record)
+            .map_err(|e| PlatariumError::State(format!("encode fee bucket: {}", e)))?;
+        batch.put(key_fee_bucket(height), bytes);
+    }
+    for message in &commit.messages {
+        let bytes = serde_json::to_vec(message)
+            .map_err(|e| PlatariumError::State(format!("encode outbound message: {}", e)))?;
+        batch.put(key_outbound_message(height, message.index), bytes);
     }
 
     store.write_batch(batch)?;
@@ -122,8 +262,7 @@ pub fn commit_block(store: &RocksStore, commit: &BlockCommit) -> Result<()> {
 pub fn build_commit_batch(commit: &BlockCommit) -> Result<WriteBatch> {
     let height = commit.block.height;
     let mut batch = WriteBatch::default();
-    let block_bytes = serde_json::to_vec(&commit.block)
-        .map_err(|e| PlatariumError::State(format!("encode block: {}", e)))?;
+    let block_bytes = encode_block_record(commit)?;
     batch.put(key_block(height), block_bytes);
     batch.put(key_state_root(height), commit.state_root.as_bytes());
     batch.put(KEY_META_HEAD, encode_u64(height));
@@ -147,13 +286,29 @@ pub fn build_commit_batch(commit: &BlockCommit) -> Result<WriteBatch> {
             .map_err(|e| PlatariumError::State(format!("encode receipt: {}", e)))?;
         batch.put(key_receipt(&receipt.tx_hash), bytes);
     }
+    for (i, event) in commit.slashing_events.iter().filter(|e| e.is_slashing()).enumerate() {
+        let bytes = serde_json::to_vec(event)
+            .map_err(|e| PlatariumError::State(format!("encode slashing event: {}", e)))?;
+        batch.put(key_slashing_event(height, i as u32), bytes);
+    }
+    if let Some(record) = &commit.fee_bucket {
+        let bytes = serde_json::to_vec(record)
+            .map_err(|e| PlatariumError::State(format!("encode fee bucket: {}", e)))?;
+        batch.put(key_fee_bucket(height), bytes);
+    }
+    for message in &commit.messages {
+        let bytes = serde_json::to_vec(message)
+            .map_err(|e| PlatariumError::State(format!("encode outbound message: {}", e)))?;
+        batch.put(key_outbound_message(height, message.index), bytes);
+    }
     Ok(batch)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::storage::query::{get_account, get_block, get_head, get_tx};
+    use crate::core::block_assembly::DEFAULT_MAX_BLOCK_SIZE;
+    use crate::storage::query::{block_size_target, get_account, get_block, get_head, get_tx};
     use tempfile::TempDir;
 
     fn sample_commit(height: u64) -> BlockCommit {
@@ -167,19 +322,22 @@ mod tests {
                 state_root: "root1".into(),
                 block_hash: "bh1".into(),
                 producer_id: "n1".into(),
+                address_bloom: None,
+                messages_root: None,
+                bytes_used: None,
             },
             tx_jsons: vec![
-                r#"{"hash":"aabb","from":"PxA","to":"PxB","asset":"PLP","amount":10,"fee_uplp":1,"nonce":0,"reads":[],"writes":[],"sig_main":"aa","sig_derived":"bb"}"#.into(),
+                r#"{"hash":"aabb","from":"alice","to":"bob","asset":"PLP","amount":10,"fee_uplp":1,"nonce":0,"reads":[],"writes":[],"sig_main":"aa","sig_derived":"bb"}"#.into(),
             ],
             accounts: vec![
                 AccountRecord {
-                    address: "PxA".into(),
+                    address: "alice".into(),
                     balance: "90".into(),
                     uplp_balance: "0".into(),
                     nonce: 1,
                 },
                 AccountRecord {
-                    address: "PxB".into(),
+                    address: "bob".into(),
                     balance: "10".into(),
                     uplp_balance: "0".into(),
                     nonce: 0,
@@ -190,8 +348,17 @@ mod tests {
                 status: "ok".into(),
                 fee_uplp: 1,
                 block_height: height,
+                rebate_uplp: 0,
+                events: Vec::new(),
+                fee_payer: None,
+                index: None,
+                error_code: None,
+                balance_deltas: Vec::new(),
             }],
             state_root: "root1".into(),
+            slashing_events: Vec::new(),
+            fee_bucket: None,
+            messages: Vec::new(),
         }
     }
 
@@ -205,7 +372,7 @@ mod tests {
         assert_eq!(get_head(&store).unwrap(), 1);
         assert!(get_tx(&store, "aabb").unwrap().is_some());
         assert!(get_block(&store, 1).unwrap().is_some());
-        let a = get_account(&store, "PxA").unwrap().unwrap();
+        let a = get_account(&store, "alice").unwrap().unwrap();
         assert_eq!(a.balance, "90");
         assert_eq!(a.nonce, 1);
     }
@@ -219,4 +386,109 @@ mod tests {
         assert_eq!(store.head_height().unwrap(), 0);
         assert!(get_tx(&store, "aabb").unwrap().is_none());
     }
+
+    #[test]
+    fn slashing_events_are_queryable_by_node_and_height() {
+        use crate::core::protocol_params::SlashDestination;
+        use crate::core::slashing::SlashingReason;
+        use crate::storage::query::list_slashing_events;
+
+        let dir = TempDir::new().unwrap();
+        let store = RocksStore::open(dir.path().join("db")).unwrap();
+        let slashed = |node: &str, height| Event::NodeSlashed {
+            node_id: node.into(),
+            reason: SlashingReason::NoVote,
+            height,
+            reputation_penalty: 1,
+            stake_slashed: 1,
+            destination: SlashDestination::Burn,
+        };
+        for height in 1..=3 {
+            let mut commit = sample_commit(height);
+            commit.slashing_events = vec![slashed("n1", height), slashed("n2", height)];
+            commit_block(&store, &commit).unwrap();
+        }
+        assert_eq!(list_slashing_events(&store, None, 1, 3).unwrap().len(), 6);
+        assert_eq!(
+            list_slashing_events(&store, Some("n2"), 2, 2).unwrap(),
+            vec![slashed("n2", 2)]
+        );
+        assert!(list_slashing_events(&store, None, 4, 9).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fee_buckets_are_recorded_per_height() {
+        use crate::core::load::NetworkLoad;
+        use crate::core::Core;
+
+        let dir = TempDir::new().unwrap();
+        let store = RocksStore::open(dir.path().join("db")).unwrap();
+        for height in 1..=4 {
+            let mut commit = sample_commit(height);
+            if height != 2 {
+                let load = NetworkLoad::from_pending(height as usize * 200);
+                commit.fee_bucket = Some(load.fee_bucket_record(height, height as usize * 1_000, 10_000));
+            }
+            commit_block(&store, &commit).unwrap();
+        }
+        let core = Core::with_store(store);
+        let history = core.fee_bucket_history(1..=3).unwrap();
+        let heights: Vec<u64> = history.iter().map(|r| r.block_number).collect();
+        assert_eq!(heights, vec![1, 3]);
+        assert_eq!(history[0].multiplier, 1);
+        assert_eq!(history[1].multiplier, 2);
+        assert_eq!(history[1].pending_tx_count, 600);
+        assert_eq!(history[1].mempool_bytes, 3_000);
+        assert_eq!(core.fee_bucket_history(4..=4).unwrap()[0].multiplier, 3);
+        assert!(Core::new().fee_bucket_history(0..=10).is_err());
+    }
+
+    #[test]
+    fn bytes_used_is_stored_and_drives_the_size_target() {
+        let dir = TempDir::new().unwrap();
+        let store = RocksStore::open(dir.path().join("db")).unwrap();
+        assert_eq!(block_size_target(&store).unwrap().target_bytes(), DEFAULT_MAX_BLOCK_SIZE);
+        let full = format!("\"{}\"", "x".repeat(DEFAULT_MAX_BLOCK_SIZE as usize));
+        for height in 1..=3 {
+            let mut commit = sample_commit(height);
+            commit.tx_jsons[0] = full.clone();
+            commit_block(&store, &commit).unwrap();
+        }
+        let stored = get_block(&store, 1).unwrap().unwrap().bytes_used;
+        assert_eq!(stored, Some(full.len() as u64 + TX_FRAME_PREFIX_BYTES));
+        assert!(block_size_target(&store).unwrap().target_bytes() > DEFAULT_MAX_BLOCK_SIZE);
+
+        let mut forged = sample_commit(4);
+        forged.block.bytes_used = Some(1);
+        assert!(commit_block(&store, &forged).is_err());
+    }
+
+    #[test]
+    fn address_bloom_is_stored_and_scanned() {
+        use crate::storage::query::list_bloom_matches;
+
+        let dir = TempDir::new().unwrap();
+        let store = RocksStore::open(dir.path().join("db")).unwrap();
+        for height in 1..=3 {
+            let mut commit = sample_commit(height);
+            if height == 2 {
+                commit.tx_jsons[0] = commit.tx_jsons[0].replace("bob", "carol");
+                commit.receipts[0].fee_payer = Some("sponsor".into());
+            }
+            commit_block(&store, &commit).unwrap();
+        }
+        let bloom = get_block(&store, 2).unwrap().unwrap().address_bloom.unwrap();
+        assert!(bloom.might_contain("alice") && bloom.might_contain("carol") && bloom.might_contain("sponsor"));
+        assert!(!bloom.might_contain("bob"));
+
+        assert_eq!(list_bloom_matches(&store, "alice", 0, 10).unwrap(), vec![1, 2, 3]);
+        assert_eq!(list_bloom_matches(&store, "bob", 0, 10).unwrap(), vec![1, 3]);
+        assert_eq!(list_bloom_matches(&store, "sponsor", 2, 3).unwrap(), vec![2]);
+
+        let mut forged = sample_commit(4);
+        forged.block.address_bloom = Some(AddressBloom::new());
+        assert!(commit_block(&store, &forged).is_err());
+        forged.block.address_bloom = Some(block_address_bloom(&forged));
+        commit_block(&store, &forged).unwrap();
+    }
 }
@@ -0,0 +1,223 @@
+//! Double-entry ledger export for accounting and audit systems.
+//!
+//! Every committed transaction is expanded into balanced debit/credit lines per account and asset:
+//! the transferred, minted or bridged amount (only when the receipt status is `ok`), the fee paid to
+//! the treasury, and any fee rebate paid back. A `Mint` is booked from its issuer and a `BridgeClaim`
+//! from `BRIDGE_ADDRESS`, the issuer of every wrapped asset. Fees are in μPLP and use the asset code
+//! `uPLP`. For each transaction the debits and credits of every asset sum to the same total.
+
+use crate::core::bridge::BRIDGE_ADDRESS;
+use crate::core::state::{BURN_ADDRESS, STAKING_ADDRESS, TREASURY_ADDRESS};
+use crate::core::transaction::{Transaction, TxKind};
+use crate::error::{PlatariumError, Result};
+use crate::storage::commit::ReceiptRecord;
+use crate::storage::query::{get_block, get_head, get_receipt, get_tx};
+use crate::storage::rocks::RocksStore;
+use serde::Serialize;
+
+/// Asset code used for fee lines.
+pub const FEE_ASSET: &str = "uPLP";
+
+/// One side of a double-entry posting. Exactly one of `debit` and `credit` is non-zero.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub block_height: u64,
+    pub tx_hash: String,
+    pub account: String,
+    pub asset: String,
+    pub debit: u128,
+    pub credit: u128,
+}
+
+fn post(out: &mut Vec<LedgerEntry>, height: u64, tx_hash: &str, asset: &str, from: &str, to: &str, amount: u128) {
+    if amount == 0 {
+        return;
+    }
+    let line = |account: &str, debit, credit| LedgerEntry {
+        block_height: height,
+        tx_hash: tx_hash.to_string(),
+        account: account.to_string(),
+        asset: asset.to_string(),
+        debit,
+        credit,
+    };
+    out.push(line(from, amount, 0));
+    out.push(line(to, 0, amount));
+}
+
+/// Ledger lines for one committed transaction. Without a receipt the transaction is treated as
+/// successful with its declared fee and no rebate.
+pub fn ledger_entries_for_tx(height: u64, tx: &Transaction, receipt: Option<&ReceiptRecord>) -> Vec<LedgerEntry> {
+    let mut out = Vec::new();
+    let ok = receipt.is_none_or(|r| r.status == "ok");
+    if ok {
+        let movement = match &tx.kind {
+            TxKind::Transfer | TxKind::SessionTransfer { .. } => Some((tx.from.as_str(), tx.to.as_str())),
+            TxKind::TransferFrom { owner } => Some((owner.as_str(), tx.to.as_str())),
+            TxKind::Mint => Some((tx.from.as_str(), tx.to.as_str())),
+            TxKind::BridgeClaim { .. } => Some((BRIDGE_ADDRESS, tx.to.as_str())),
+            TxKind::Burn => Some((tx.from.as_str(), BURN_ADDRESS)),
+            TxKind::Stake => Some((tx.from.as_str(), STAKING_ADDRESS)),
+            TxKind::Unstake => Some((STAKING_ADDRESS, tx.from.as_str())),
+            _ => None,
+        };
+        if let Some((source, destination)) = movement {
+            post(&mut out, height, &tx.hash, &tx.asset.as_canonical(), source, destination, tx.amount);
+        }
+        if let TxKind::MultiTransfer { outputs } = &tx.kind {
+            for output in outputs {
+                post(&mut out, height, &tx.hash, &output.asset.as_canonical(), &tx.from, &output.to, output.amount);
+            }
+        }
+    }
+    let payer = receipt
+        .and_then(|r| r.fee_payer.as_deref())
+        .unwrap_or(tx.from.as_str());
+    let fee = receipt.map_or(tx.fee_uplp, |r| r.fee_uplp as u128);
+    let rebate = receipt.map_or(0, |r| r.rebate_uplp as u128);
+    post(&mut out, height, &tx.hash, FEE_ASSET, payer, TREASURY_ADDRESS, fee);
+    post(&mut out, height, &tx.hash, FEE_ASSET, TREASURY_ADDRESS, payer, rebate);
+    out
+}
+
+/// Ledger lines for all committed blocks in `from_height..=to_height`, in block and transaction order.
+/// `to_height` is clamped to the head height, so an open-ended range stops at the last committed block.
+pub fn export_ledger(store: &RocksStore, from_height: u64, to_height: u64) -> Result<Vec<LedgerEntry>> {
+    let mut out = Vec::new();
+    for height in from_height..=to_height.min(get_head(store)?) {
+        let Some(block) = get_block(store, height)? else {
+            continue;
+        };
+        for hash in &block.tx_hashes {
+            let json = get_tx(store, hash)?
+                .ok_or_else(|| PlatariumError::State(format!("ledger: missing tx {}", hash)))?;
+            let tx = Transaction::from_gateway_json(&json)?;
+            let receipt = get_receipt(store, hash)?;
+            out.extend(ledger_entries_for_tx(height, &tx, receipt.as_ref()));
+        }
+    }
+    Ok(out)
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Renders entries as CSV with a header row.
+pub fn ledger_to_csv(entries: &[LedgerEntry]) -> String {
+    let mut out = String::from("block_height,tx_hash,account,asset,debit,credit\n");
+    for e in entries {
+        out.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            e.block_height,
+            csv_field(&e.tx_hash),
+            csv_field(&e.account),
+            csv_field(&e.asset),
+            e.debit,
+            e.credit
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::asset::Asset;
+    use crate::core::bridge::ForeignHeaderProof;
+    use std::collections::{BTreeMap, HashSet};
+
+    fn tx(kind: TxKind) -> Transaction {
+        Transaction::new(
+            "spender".into(),
+            "shop".into(),
+            Asset::PLP,
+            50,
+            3,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap()
+        .with_kind(kind)
+        .unwrap()
+    }
+
+    fn receipt(status: &str, rebate: u64) -> ReceiptRecord {
+        ReceiptRecord {
+            tx_hash: "h".into(),
+            status: status.into(),
+            fee_uplp: 3,
+            block_height: 7,
+            rebate_uplp: rebate,
+            events: Vec::new(),
+            fee_payer: None,
+            index: None,
+            error_code: None,
+            balance_deltas: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_entries_balance_per_asset() {
+        let t = tx(TxKind::TransferFrom { owner: "owner".into() });
+        let entries = ledger_entries_for_tx(7, &t, Some(&receipt("ok", 1)));
+        let mut sums: BTreeMap<&str, (u128, u128)> = BTreeMap::new();
+        for e in &entries {
+            let s = sums.entry(e.asset.as_str()).or_default();
+            s.0 += e.debit;
+            s.1 += e.credit;
+        }
+        assert!(sums.values().all(|(d, c)| d == c));
+        assert_eq!(entries[0].account, "owner");
+        assert_eq!(entries[0].debit, 50);
+        assert_eq!(entries.len(), 6);
+    }
+
+    #[test]
+    fn test_mint_and_bridge_claim_credit_the_recipient() {
+        let mint = ledger_entries_for_tx(7, &tx(TxKind::Mint), Some(&receipt("ok", 0)));
+        assert_eq!((mint[0].account.as_str(), mint[0].debit), ("spender", 50));
+        assert_eq!((mint[1].account.as_str(), mint[1].credit), ("shop", 50));
+
+        let claim = tx(TxKind::BridgeClaim {
+            claim_id: "c1".into(),
+            proof: Box::new(ForeignHeaderProof {
+                chain_id: "eth".into(),
+                height: 7,
+                claims_root: "ab".into(),
+                siblings: Vec::new(),
+                finality: String::new(),
+            }),
+        });
+        let entries = ledger_entries_for_tx(7, &claim, Some(&receipt("ok", 0)));
+        assert_eq!((entries[0].account.as_str(), entries[0].debit), (BRIDGE_ADDRESS, 50));
+        assert_eq!((entries[1].account.as_str(), entries[1].credit), ("shop", 50));
+        assert_eq!(entries.len(), 4);
+    }
+
+    #[test]
+    fn test_export_range_stops_at_head() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = RocksStore::open(dir.path().join("db")).unwrap();
+        assert!(export_ledger(&store, 0, u64::MAX).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_tx_books_fee_only_and_csv_escapes() {
+        let t = tx(TxKind::Transfer);
+        let entries = ledger_entries_for_tx(7, &t, Some(&receipt("failed", 0)));
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.asset == FEE_ASSET));
+        let mut odd = entries[0].clone();
+        odd.account = "a,\"b\"".into();
+        let csv = ledger_to_csv(&[odd]);
+        assert!(csv.lines().nth(1).unwrap().contains("\"a,\"\"b\"\"\""));
+    }
+}
@@ -8,23 +8,30 @@ pub mod rocks;
 pub mod commit;
 pub mod snapshot;
 pub mod query;
+pub mod ledger;
 pub mod rpc;
 
 pub use commit::{
-    AccountRecord, BlockCommit, BlockRecordStored, ReceiptRecord, build_commit_batch, commit_block,
+    AccountRecord, BlockCommit, BlockRecordStored, ReceiptRecord, block_address_bloom, block_bytes_used,
+    build_commit_batch, commit_block,
 };
 pub use query::{
-    get_account, get_block, get_head, get_receipt, get_state_root, get_tx,
-    list_tx_hashes_for_address,
+    MAX_RECEIPT_PAGE_SIZE, ReceiptPage, block_size_target, get_account, get_block, get_head, get_receipt,
+    get_message_proof, get_state_root, get_tx, list_block_receipts, list_bloom_matches, list_fee_buckets,
+    list_outbound_messages, list_receipts_for_address, list_slashing_events, list_tx_hashes_for_address,
+    recent_load_samples,
 };
+pub use ledger::{FEE_ASSET, LedgerEntry, export_ledger, ledger_entries_for_tx, ledger_to_csv};
 pub use rocks::{RocksStore, open_store};
 pub use schema::SCHEMA_VERSION;
 pub use snapshot::{
     SNAPSHOT_INTERVAL, SnapshotMeta, bootstrap_from_snapshot, create_snapshot_if_due, list_snapshots,
 };
 pub use rpc::{
-    migrate_json_to_rocks, rocks_bootstrap_snapshot_json, rocks_commit_block_json,
-    rocks_get_account_json, rocks_get_block_json, rocks_get_head_json, rocks_get_receipt_json,
+    migrate_json_to_rocks, rocks_block_size_target_json, rocks_bootstrap_snapshot_json, rocks_commit_block_json,
+    rocks_export_ledger, rocks_get_account_json, rocks_get_block_json, rocks_get_head_json,
+    rocks_get_receipt_json,
     rocks_get_snapshot_json, rocks_get_state_root_json, rocks_get_tx_json,
-    rocks_list_address_txs_json, rocks_list_snapshots_json,
+    rocks_list_address_receipts_json, rocks_list_address_txs_json, rocks_list_slashing_events_json,
+    rocks_list_snapshots_json,
 };
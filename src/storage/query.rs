@@ -1,12 +1,29 @@
 //! Read APIs over RocksDB.
 
+use crate::core::block_assembly::{BlockSizeController, BLOCK_TARGET_REPLAY_BLOCKS};
+use crate::core::events::Event;
+use crate::core::load::{BlockLoadSample, FeeBucketRecord, LOAD_WINDOW_BLOCKS};
+use crate::core::outbound::{MessageProof, OutboundMessage, message_proof};
 use crate::error::{PlatariumError, Result};
 use crate::storage::commit::{AccountRecord, BlockRecordStored, ReceiptRecord};
 use crate::storage::rocks::RocksStore;
 use crate::storage::schema::{
-    KEY_META_HEAD, PREFIX_IDX_ADDR, decode_u64, key_account, key_block, key_receipt, key_state_root,
-    key_tx,
+    KEY_META_HEAD, PREFIX_FEE_BUCKET, PREFIX_IDX_ADDR, PREFIX_OUTBOUND_MESSAGE, PREFIX_SLASHING, decode_u64,
+    key_account, key_block, key_fee_bucket, key_idx_addr, key_outbound_message, key_receipt, key_slashing_event,
+    key_state_root, key_tx,
 };
+use serde::Serialize;
+
+/// Largest page `list_receipts_for_address` returns; larger limits are clamped.
+pub const MAX_RECEIPT_PAGE_SIZE: usize = 1_000;
+
+/// One page of receipts for an address, oldest first.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ReceiptPage {
+    pub receipts: Vec<ReceiptRecord>,
+    /// Pass back to fetch the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
+}
 
 pub fn get_head(store: &RocksStore) -> Result<u64> {
     store.head_height()
@@ -51,6 +68,20 @@ pub fn get_receipt(store: &RocksStore, tx_hash: &str) -> Result<Option<ReceiptRe
     }
 }
 
+/// Receipts of the transactions in block `height`, in block order; empty for an unknown block.
+pub fn list_block_receipts(store: &RocksStore, height: u64) -> Result<Vec<ReceiptRecord>> {
+    let Some(block) = get_block(store, height)? else {
+        return Ok(Vec::new());
+    };
+    let mut receipts = Vec::with_capacity(block.tx_hashes.len());
+    for tx_hash in &block.tx_hashes {
+        if let Some(receipt) = get_receipt(store, tx_hash)? {
+            receipts.push(receipt);
+        }
+    }
+    Ok(receipts)
+}
+
 pub fn get_state_root(store: &RocksStore, height: u64) -> Result<Option<String>> {
     Ok(store
         .get(&key_state_root(height))?
@@ -83,6 +114,194 @@ pub fn list_tx_hashes_for_address(store: &RocksStore, address: &str) -> Result<V
     Ok(hashes)
 }
 
+/// Cursor `"{height}:{tx_hash}"` naming the last index entry already returned.
+fn parse_cursor(cursor: &str) -> Result<(u64, &str)> {
+    cursor
+        .split_once(':')
+        .and_then(|(h, hash)| h.parse::<u64>().ok().map(|h| (h, hash)))
+        .ok_or_else(|| PlatariumError::State(format!("invalid receipt cursor: {}", cursor)))
+}
+
+/// Receipts for transactions where `address` was sender, receiver or fee payer, ordered by
+/// (block height, tx hash). The cursor is stable across later commits: new blocks only append.
+pub fn list_receipts_for_address(
+    store: &RocksStore,
+    address: &str,
+    cursor: Option<&str>,
+    limit: usize,
+) -> Result<ReceiptPage> {
+    let limit = limit.clamp(1, MAX_RECEIPT_PAGE_SIZE);
+    let mut prefix = PREFIX_IDX_ADDR.to_vec();
+    prefix.extend_from_slice(address.as_bytes());
+    prefix.push(b'/');
+    let start = match cursor {
+        Some(c) => {
+            let (height, hash) = parse_cursor(c)?;
+            key_idx_addr(address, height, hash)
+        }
+        None => prefix.clone(),
+    };
+
+    let mut receipts = Vec::new();
+    let mut last = None;
+    let mut more = false;
+    for item in store.db().prefix_iterator(&start) {
+        let (key, _) = item.map_err(|e| PlatariumError::State(format!("iter: {}", e)))?;
+        if !key.starts_with(&prefix) {
+            break;
+        }
+        if cursor.is_some() && key[..] == start[..] {
+            continue;
+        }
+        // key: i/a/{addr}/{height_be}/{tx_hash}
+        let rest = &key[prefix.len()..];
+        if rest.len() < 10 {
+            continue;
+        }
+        if receipts.len() == limit {
+            more = true;
+            break;
+        }
+        let height = decode_u64(&rest[..8]).unwrap_or(0);
+        let hash = String::from_utf8_lossy(&rest[9..]).into_owned();
+        if let Some(r) = get_receipt(store, &hash)? {
+            receipts.push(r);
+        }
+        last = Some(format!("{}:{}", height, hash));
+    }
+    Ok(ReceiptPage {
+        receipts,
+        next_cursor: if more { last } else { None },
+    })
+}
+
+/// Slashing and suspension events committed at heights `from..=to`, in commit order, optionally only for `node_id`.
+pub fn list_slashing_events(
+    store: &RocksStore,
+    node_id: Option<&str>,
+    from_height: u64,
+    to_height: u64,
+) -> Result<Vec<Event>> {
+    let mut events = Vec::new();
+    if from_height > to_height {
+        return Ok(events);
+    }
+    for item in store.db().prefix_iterator(key_slashing_event(from_height, 0)) {
+        let (key, value) = item.map_err(|e| PlatariumError::State(format!("iter: {}", e)))?;
+        if !key.starts_with(PREFIX_SLASHING) {
+            break;
+        }
+        // key: sl/{height_be}/{idx_be}
+        let rest = &key[PREFIX_SLASHING.len()..];
+        match rest.get(..8).and_then(decode_u64) {
+            Some(height) if height <= to_height => {}
+            _ => break,
+        }
+        let event: Event = serde_json::from_slice(&value)
+            .map_err(|e| PlatariumError::State(format!("decode slashing event: {}", e)))?;
+        if node_id.is_none_or(|id| event.node_id() == Some(id)) {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+/// Size target for the block after the head: [`BlockSizeController::from_history`] over the stored `bytes_used`
+/// of the last `BLOCK_TARGET_REPLAY_BLOCKS` blocks, oldest first; blocks committed without it are skipped. Read from
+/// committed headers only, so every node on the same chain derives the same target.
+pub fn block_size_target(store: &RocksStore) -> Result<BlockSizeController> {
+    let head = store.head_height()?;
+    let from = head.saturating_sub(BLOCK_TARGET_REPLAY_BLOCKS - 1).max(1);
+    let mut sizes = Vec::new();
+    for height in from..=head {
+        if let Some(bytes_used) = get_block(store, height)?.and_then(|b| b.bytes_used) {
+            sizes.push(bytes_used);
+        }
+    }
+    Ok(BlockSizeController::from_history(&sizes))
+}
+
+/// Load samples of the last `LOAD_WINDOW_BLOCKS` stored blocks, oldest first, for `NetworkLoad::estimate`.
+pub fn recent_load_samples(store: &RocksStore) -> Result<Vec<BlockLoadSample>> {
+    let head = store.head_height()?;
+    let from = head.saturating_sub(LOAD_WINDOW_BLOCKS as u64 - 1).max(1);
+    let mut samples = Vec::new();
+    for height in from..=head {
+        if let Some(block) = get_block(store, height)? {
+            samples.push(BlockLoadSample {
+                block_number: block.height,
+                timestamp: block.timestamp,
+                tx_count: block.tx_hashes.len() as u64,
+            });
+        }
+    }
+    Ok(samples)
+}
+
+/// Fee bucket records committed at heights `from..=to`, by height. Blocks committed without one are skipped.
+pub fn list_fee_buckets(store: &RocksStore, from_height: u64, to_height: u64) -> Result<Vec<FeeBucketRecord>> {
+    let mut records = Vec::new();
+    if from_height > to_height {
+        return Ok(records);
+    }
+    for item in store.db().prefix_iterator(key_fee_bucket(from_height)) {
+        let (key, value) = item.map_err(|e| PlatariumError::State(format!("iter: {}", e)))?;
+        if !key.starts_with(PREFIX_FEE_BUCKET) {
+            break;
+        }
+        match key[PREFIX_FEE_BUCKET.len()..].get(..8).and_then(decode_u64) {
+            Some(height) if height <= to_height => {}
+            _ => break,
+        }
+        records.push(
+            serde_json::from_slice(&value)
+                .map_err(|e| PlatariumError::State(format!("decode fee bucket: {}", e)))?,
+        );
+    }
+    Ok(records)
+}
+
+/// Heights in `from..=to` (up to the head) whose address bloom may contain `address`, lowest first. Blocks stored
+/// without a bloom are always included. `address` may be checksummed.
+pub fn list_bloom_matches(store: &RocksStore, address: &str, from_height: u64, to_height: u64) -> Result<Vec<u64>> {
+    let address = crate::address::normalize(address)?;
+    let to_height = to_height.min(store.head_height()?);
+    let mut heights = Vec::new();
+    for height in from_height.max(1)..=to_height {
+        let Some(block) = get_block(store, height)? else {
+            continue;
+        };
+        if block.address_bloom.is_none_or(|b| b.might_contain(&address)) {
+            heights.push(height);
+        }
+    }
+    Ok(heights)
+}
+
+/// Outbound messages committed at `height`, by index.
+pub fn list_outbound_messages(store: &RocksStore, height: u64) -> Result<Vec<OutboundMessage>> {
+    let mut messages = Vec::new();
+    // key: om/{height_be}/{idx_be}
+    let start = key_outbound_message(height, 0);
+    let block_prefix = &start[..PREFIX_OUTBOUND_MESSAGE.len() + 9];
+    for item in store.db().prefix_iterator(&start) {
+        let (key, value) = item.map_err(|e| PlatariumError::State(format!("iter: {}", e)))?;
+        if !key.starts_with(block_prefix) {
+            break;
+        }
+        messages.push(
+            serde_json::from_slice(&value)
+                .map_err(|e| PlatariumError::State(format!("decode outbound message: {}", e)))?,
+        );
+    }
+    Ok(messages)
+}
+
+/// Proof that message `index` of block `height` is committed in that block's `messages_root`.
+pub fn get_message_proof(store: &RocksStore, height: u64, index: u32) -> Result<Option<MessageProof>> {
+    Ok(message_proof(&list_outbound_messages(store, height)?, index))
+}
+
 pub fn head_meta_json(store: &RocksStore) -> Result<String> {
     let head = match store.get(KEY_META_HEAD)? {
         Some(b) => decode_u64(&b).unwrap_or(0),
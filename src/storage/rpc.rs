@@ -2,10 +2,13 @@
 
 use crate::error::{PlatariumError, Result};
 use crate::storage::commit::{BlockCommit, commit_block};
+use crate::core::block_assembly::{BLOCK_TARGET_MAX_BYTES, BLOCK_TARGET_MIN_BYTES};
 use crate::storage::query::{
-    get_account, get_block, get_head, get_receipt, get_state_root, get_tx, head_meta_json,
+    block_size_target, get_account, get_block, get_head, get_message_proof, get_receipt, get_state_root, get_tx,
+    head_meta_json, list_bloom_matches, list_fee_buckets, list_receipts_for_address, list_slashing_events,
     list_tx_hashes_for_address,
 };
+use crate::storage::ledger::{export_ledger, ledger_to_csv};
 use crate::storage::rocks::RocksStore;
 use crate::storage::snapshot::{bootstrap_from_snapshot, get_snapshot, list_snapshots};
 use std::path::Path;
@@ -19,6 +22,20 @@ pub fn rocks_get_head_json(db_path: &str) -> Result<String> {
     head_meta_json(&store)
 }
 
+/// Size target for the next block, derived from the stored headers (`block_size_target`), with its bounds.
+pub fn rocks_block_size_target_json(db_path: &str) -> Result<String> {
+    let store = open(db_path)?;
+    let target = block_size_target(&store)?;
+    Ok(serde_json::json!({
+        "height": store.head_height()? + 1,
+        "target_bytes": target.target_bytes(),
+        "avg_fullness_pct": target.avg_fullness_pct(),
+        "min_bytes": BLOCK_TARGET_MIN_BYTES,
+        "max_bytes": BLOCK_TARGET_MAX_BYTES,
+    })
+    .to_string())
+}
+
 pub fn rocks_get_tx_json(db_path: &str, tx_hash: &str) -> Result<String> {
     let store = open(db_path)?;
     match get_tx(&store, tx_hash)? {
@@ -57,6 +74,65 @@ pub fn rocks_list_address_txs_json(db_path: &str, address: &str) -> Result<Strin
     Ok(serde_json::json!({"address": address, "tx_hashes": hashes}).to_string())
 }
 
+pub fn rocks_list_address_receipts_json(
+    db_path: &str,
+    address: &str,
+    cursor: Option<&str>,
+    limit: usize,
+) -> Result<String> {
+    let store = open(db_path)?;
+    let page = list_receipts_for_address(&store, address, cursor, limit)?;
+    Ok(serde_json::to_string(&serde_json::json!({"address": address, "page": page})).unwrap())
+}
+
+/// Slashing and suspension events for heights `from..=to`, optionally for one validator.
+pub fn rocks_list_slashing_events_json(db_path: &str, node_id: Option<&str>, from: u64, to: u64) -> Result<String> {
+    let store = open(db_path)?;
+    let events = list_slashing_events(&store, node_id, from, to)?;
+    Ok(serde_json::to_string(&serde_json::json!({"node_id": node_id, "from": from, "to": to, "events": events})).unwrap())
+}
+
+/// Fee bucket history for heights `from..=to`.
+pub fn rocks_fee_bucket_history_json(db_path: &str, from: u64, to: u64) -> Result<String> {
+    let store = open(db_path)?;
+    let records = list_fee_buckets(&store, from, to)?;
+    Ok(serde_json::to_string(&serde_json::json!({"from": from, "to": to, "records": records})).unwrap())
+}
+
+/// Heights in `from..=to` whose address bloom may contain `address`.
+pub fn rocks_address_bloom_scan_json(db_path: &str, address: &str, from: u64, to: u64) -> Result<String> {
+    let store = open(db_path)?;
+    let heights = list_bloom_matches(&store, address, from, to)?;
+    Ok(serde_json::to_string(&serde_json::json!({"address": address, "from": from, "to": to, "heights": heights})).unwrap())
+}
+
+/// Inclusion proof for outbound message `index` of block `height`, with the block's `messages_root`.
+pub fn rocks_message_proof_json(db_path: &str, height: u64, index: u32) -> Result<String> {
+    let store = open(db_path)?;
+    let messages_root = get_block(&store, height)?.and_then(|b| b.messages_root);
+    match get_message_proof(&store, height, index)? {
+        Some(proof) => Ok(serde_json::to_string(&serde_json::json!({
+            "found": true,
+            "height": height,
+            "messages_root": messages_root,
+            "proof": proof,
+        }))
+        .unwrap()),
+        None => Ok(serde_json::json!({"found": false}).to_string()),
+    }
+}
+
+/// Ledger for heights `from..=to` as `csv` or `json`.
+pub fn rocks_export_ledger(db_path: &str, from: u64, to: u64, format: &str) -> Result<String> {
+    let store = open(db_path)?;
+    let entries = export_ledger(&store, from, to)?;
+    match format {
+        "csv" => Ok(ledger_to_csv(&entries)),
+        "json" => Ok(serde_json::to_string(&serde_json::json!({"from": from, "to": to, "entries": entries})).unwrap()),
+        other => Err(PlatariumError::State(format!("unknown ledger format: {}", other))),
+    }
+}
+
 pub fn rocks_commit_block_json(db_path: &str, commit_json: &str) -> Result<String> {
     let store = open(db_path)?;
     let commit: BlockCommit = serde_json::from_str(commit_json)
@@ -256,6 +332,9 @@ pub fn migrate_json_to_rocks(
                     .and_then(|x| x.as_str())
                     .unwrap_or("")
                     .to_string(),
+                address_bloom: None,
+                messages_root: None,
+                bytes_used: None,
             },
             tx_jsons,
             accounts: vec![],
@@ -266,9 +345,18 @@ pub fn migrate_json_to_rocks(
                     status: "ok".into(),
                     fee_uplp: 0,
                     block_height: height,
+                    rebate_uplp: 0,
+                    events: Vec::new(),
+                    fee_payer: None,
+                    index: None,
+                    error_code: None,
+                    balance_deltas: Vec::new(),
                 })
                 .collect(),
             state_root,
+            slashing_events: Vec::new(),
+            fee_bucket: None,
+            messages: Vec::new(),
         };
         // Bypass sequential height check for migration by writing batch directly when needed.
         if store.head_height()? + 1 != height && !(store.head_height()? == 0 && height == 1) {
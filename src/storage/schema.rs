@@ -10,6 +10,9 @@ pub const PREFIX_STATE_ROOT: &[u8] = b"s/";
 pub const PREFIX_IDX_ADDR: &[u8] = b"i/a/";
 pub const PREFIX_IDX_BLOCK: &[u8] = b"i/b/";
 pub const PREFIX_SNAPSHOT: &[u8] = b"snap/";
+pub const PREFIX_SLASHING: &[u8] = b"sl/";
+pub const PREFIX_FEE_BUCKET: &[u8] = b"fb/";
+pub const PREFIX_OUTBOUND_MESSAGE: &[u8] = b"om/";
 pub const KEY_META_HEAD: &[u8] = b"meta/head";
 pub const KEY_META_SCHEMA: &[u8] = b"meta/schema";
 
@@ -67,6 +70,28 @@ pub fn key_snapshot(height: u64) -> Vec<u8> {
     k
 }
 
+pub fn key_slashing_event(height: u64, idx: u32) -> Vec<u8> {
+    let mut k = PREFIX_SLASHING.to_vec();
+    k.extend_from_slice(&height.to_be_bytes());
+    k.push(b'/');
+    k.extend_from_slice(&idx.to_be_bytes());
+    k
+}
+
+pub fn key_fee_bucket(height: u64) -> Vec<u8> {
+    let mut k = PREFIX_FEE_BUCKET.to_vec();
+    k.extend_from_slice(&height.to_be_bytes());
+    k
+}
+
+pub fn key_outbound_message(height: u64, idx: u32) -> Vec<u8> {
+    let mut k = PREFIX_OUTBOUND_MESSAGE.to_vec();
+    k.extend_from_slice(&height.to_be_bytes());
+    k.push(b'/');
+    k.extend_from_slice(&idx.to_be_bytes());
+    k
+}
+
 pub fn encode_u64(n: u64) -> [u8; 8] {
     n.to_be_bytes()
 }
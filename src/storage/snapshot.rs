@@ -163,11 +163,17 @@ mod tests {
                 state_root: "r".into(),
                 block_hash: "h".into(),
                 producer_id: "p".into(),
+                address_bloom: None,
+                messages_root: None,
+                bytes_used: None,
             },
             tx_jsons: vec![],
             accounts: vec![],
             receipts: vec![],
             state_root: "r".into(),
+            slashing_events: Vec::new(),
+            fee_bucket: None,
+            messages: Vec::new(),
         };
         commit_block(&store, &commit).unwrap();
         assert!(list_snapshots(&store).unwrap().is_empty());
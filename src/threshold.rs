@@ -0,0 +1,731 @@
+//! 2-of-3 threshold ECDSA: three custody parties share one secp256k1 key, and any two co-sign.
+//!
+//! **Experimental; not for custody of real funds.** The protocol is secure only against honest-but-curious parties:
+//! it carries no zero-knowledge proofs, so an actively malicious party can bias the nonce or corrupt the
+//! presignature without being identified. It has not been audited.
+//!
+//! No party holds the full key. Key generation is distributed: each party deals a random degree-1 Shamir polynomial
+//! with Feldman commitments ([`keygen_dealing`]), and every party sums the shares it receives into its [`KeyShare`].
+//! Each share in a [`Dealing`] is sealed to its recipient's [`Transport`] key (ECDH, HKDF-SHA256, AES-256-GCM, bound
+//! to the dealer, recipient and commitments), so a whole dealing can be broadcast: other parties see only the
+//! commitments. The group public key is an ordinary compressed key, so its `Px` address and signatures are
+//! indistinguishable from a single-key account's.
+//!
+//! Signing has an offline and an online phase:
+//! - **Presigning** (all three parties, before the message is known). The parties jointly share a random nonce `k`
+//!   and mask `γ`, open `kγ` to invert the nonce, and re-share the product `k⁻¹·x` down to degree 1 (the
+//!   Gennaro–Jarecki–Krawczyk–Rabin construction). [`PresignSession`] runs the three rounds; each party ends with a
+//!   [`Presignature`] for the common nonce point `R`.
+//! - **Signing** (any two parties, online). Each computes a partial signature ([`SignatureShare`]) over the
+//!   32-byte digest (for a transaction, its hash); [`aggregate_signature`] interpolates them into a low-S ECDSA
+//!   signature and verifies it against the group key, so a bad partial is detected rather than published.
+//!
+//! A presignature signs exactly one digest: [`Presignature::sign_partial`] consumes it, since two signatures with
+//! the same nonce reveal the key. Scalar arithmetic is `k256`'s constant-time implementation.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use k256::elliptic_curve::ff::{Field, PrimeField};
+use k256::elliptic_curve::ops::Reduce;
+use k256::{FieldBytes, U256};
+use rand::RngCore;
+use secp256k1::ecdh::SharedSecret;
+use secp256k1::ecdsa::Signature;
+use secp256k1::{Message, PublicKey, SecretKey, SECP256K1};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use zeroize::Zeroize;
+use crate::error::PlatariumError;
+
+/// Number of parties holding a share.
+pub const PARTIES: u8 = 3;
+
+/// Number of parties needed to sign.
+pub const THRESHOLD: u8 = 2;
+
+/// HKDF info prefix for the key sealing one share.
+const SHARE_KEY_INFO: &[u8] = b"platarium-threshold-share/v1";
+
+/// Errors produced by threshold key generation and signing.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ThresholdError {
+    #[error("Party index {0} out of range 1..={PARTIES}")]
+    InvalidParty(u8),
+
+    #[error("Need one message from each of parties {expected:?}, got {got:?}")]
+    WrongParties { expected: Vec<u8>, got: Vec<u8> },
+
+    #[error("Message belongs to session {got:?}, expected {expected:?}")]
+    SessionMismatch { expected: String, got: String },
+
+    #[error("Share dealt by party {0} does not match its commitments")]
+    InvalidShare(u8),
+
+    #[error("Share dealt by party {0} cannot be opened with this party's transport key")]
+    SealedShare(u8),
+
+    #[error("Malformed message: {0}")]
+    Malformed(String),
+
+    #[error("Degenerate session value (zero scalar or point at infinity); start a new session")]
+    Degenerate,
+
+    #[error("Parties disagree on the nonce point")]
+    InconsistentNonce,
+
+    #[error("Aggregated signature does not verify against the group key")]
+    InvalidSignature,
+}
+
+impl From<ThresholdError> for PlatariumError {
+    fn from(e: ThresholdError) -> Self {
+        PlatariumError::Crypto(format!("Threshold signing: {}", e))
+    }
+}
+
+type Result<T> = std::result::Result<T, ThresholdError>;
+
+type Scalar = k256::Scalar;
+
+/// A party's index, its transport secret for opening shares dealt to it, and every party's transport public key
+/// (`roster[j - 1]` is party `j`'s), exchanged over an authenticated channel before key generation.
+#[derive(Clone)]
+pub struct Transport {
+    index: u8,
+    secret: SecretKey,
+    roster: Vec<PublicKey>,
+}
+
+impl std::fmt::Debug for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transport")
+            .field("index", &self.index)
+            .field("roster", &self.roster)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Transport {
+    /// Errors unless `roster` has one key per party and names `secret`'s public key at `index`.
+    pub fn new(index: u8, secret: SecretKey, roster: Vec<PublicKey>) -> Result<Self> {
+        check_party(index)?;
+        if roster.len() != PARTIES as usize || roster[index as usize - 1] != secret.public_key(SECP256K1) {
+            return Err(ThresholdError::Malformed(format!("roster does not match party {}", index)));
+        }
+        Ok(Self { index, secret, roster })
+    }
+
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+}
+
+/// A share sealed to one recipient: ephemeral public key and AES-256-GCM ciphertext, hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedShare {
+    pub ephemeral: String,
+    pub ciphertext: String,
+}
+
+/// Feldman dealing of one secret, safe to broadcast: `commitments` are the dealer's two polynomial coefficients
+/// times the generator, `shares[j - 1]` is party `j`'s evaluation sealed to its transport key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dealing {
+    pub dealer: u8,
+    pub commitments: Vec<String>,
+    pub shares: Vec<SealedShare>,
+}
+
+impl Dealing {
+    fn new(transport: &Transport, secret: Scalar, rng: &mut impl RngCore) -> Result<Self> {
+        let slope = random_scalar(rng);
+        let commitments = vec![point_hex(&mul_g(secret)?), point_hex(&mul_g(slope)?)];
+        let mut shares = Vec::with_capacity(PARTIES as usize);
+        for (j, recipient) in (1..=PARTIES).zip(&transport.roster) {
+            let mut share = secret + slope * Scalar::from(j as u64);
+            let aad = share_aad(transport.index, j, &commitments);
+            shares.push(seal(recipient, &share.to_bytes().into(), &aad, rng)?);
+            share.zeroize();
+        }
+        Ok(Self { dealer: transport.index, commitments, shares })
+    }
+
+    /// The share dealt to `transport`'s party, opened and checked against the commitments.
+    fn share_for(&self, transport: &Transport) -> Result<Scalar> {
+        check_party(self.dealer)?;
+        if self.commitments.len() != 2 || self.shares.len() != PARTIES as usize {
+            return Err(ThresholdError::Malformed(format!("dealing from party {}", self.dealer)));
+        }
+        let party = transport.index;
+        let aad = share_aad(self.dealer, party, &self.commitments);
+        let mut bytes = open(&transport.secret, &self.shares[party as usize - 1], &aad)
+            .ok_or(ThresholdError::SealedShare(self.dealer))?;
+        let share = Option::<Scalar>::from(Scalar::from_repr(FieldBytes::from(bytes)));
+        bytes.zeroize();
+        let share = share.ok_or_else(|| ThresholdError::Malformed("scalar not below the group order".into()))?;
+        let c0 = parse_point(&self.commitments[0])?;
+        let c1 = parse_point(&self.commitments[1])?;
+        let expected = add_points(&[c0, mul_point(&c1, Scalar::from(party as u64))?])?;
+        if mul_g(share)? != expected {
+            return Err(ThresholdError::InvalidShare(self.dealer));
+        }
+        Ok(share)
+    }
+}
+
+/// Party `transport`'s contribution to key generation: a dealing of a fresh random secret.
+pub fn keygen_dealing(transport: &Transport, rng: &mut impl RngCore) -> Result<Dealing> {
+    Dealing::new(transport, random_scalar(rng), rng)
+}
+
+/// One party's share of the group key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyShare {
+    pub index: u8,
+    /// Group public key (compressed hex).
+    pub public_key: String,
+    secret: Scalar,
+}
+
+impl std::fmt::Debug for KeyShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("index", &self.index)
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for KeyShare {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl KeyShare {
+    /// `transport`'s party's share from the keygen dealings of all parties.
+    pub fn from_dealings(transport: &Transport, dealings: &[Dealing]) -> Result<Self> {
+        let dealings = all_parties(dealings, |d| d.dealer)?;
+        let mut secret = Scalar::ZERO;
+        for d in &dealings {
+            secret += d.share_for(transport)?;
+        }
+        let group = dealings
+            .iter()
+            .map(|d| parse_point(&d.commitments[0]))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            index: transport.index,
+            public_key: point_hex(&add_points(&group)?),
+            secret,
+        })
+    }
+
+    /// `Px` address of the group key.
+    pub fn address(&self) -> String {
+        format!("Px{}", self.public_key)
+    }
+}
+
+/// Presigning round 1: dealings of the party's nonce and mask contributions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresignRound1 {
+    pub session_id: String,
+    pub nonce: Dealing,
+    pub mask: Dealing,
+}
+
+/// Presigning round 2: the party's share of `kγ` (degree 2, safe to open) and its nonce share times the generator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresignRound2 {
+    pub session_id: String,
+    pub from: u8,
+    pub masked_nonce: String,
+    pub nonce_point: String,
+}
+
+/// Presigning round 3: re-sharing of the party's degree-2 share of `k⁻¹·x`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresignRound3 {
+    pub session_id: String,
+    pub product: Dealing,
+}
+
+/// One party's state while presigning; see the module docs.
+pub struct PresignSession {
+    transport: Transport,
+    session_id: String,
+    public_key: String,
+    secret: Scalar,
+    nonce: Scalar,
+    mask: Scalar,
+    nonce_inv: Scalar,
+    nonce_point: Option<PublicKey>,
+}
+
+impl Drop for PresignSession {
+    fn drop(&mut self) {
+        for s in [&mut self.secret, &mut self.nonce, &mut self.mask, &mut self.nonce_inv] {
+            s.zeroize();
+        }
+    }
+}
+
+impl PresignSession {
+    /// Starts presigning session `session_id` (unique per presignature) and returns the round 1 broadcast.
+    pub fn start(
+        share: &KeyShare,
+        transport: &Transport,
+        session_id: &str,
+        rng: &mut impl RngCore,
+    ) -> Result<(Self, PresignRound1)> {
+        if share.index != transport.index {
+            return Err(ThresholdError::Malformed(format!(
+                "key share of party {} with transport of party {}",
+                share.index, transport.index
+            )));
+        }
+        let round1 = PresignRound1 {
+            session_id: session_id.to_string(),
+            nonce: Dealing::new(transport, random_scalar(rng), rng)?,
+            mask: Dealing::new(transport, random_scalar(rng), rng)?,
+        };
+        let session = Self {
+            transport: transport.clone(),
+            session_id: session_id.to_string(),
+            public_key: share.public_key.clone(),
+            secret: share.secret,
+            nonce: Scalar::ZERO,
+            mask: Scalar::ZERO,
+            nonce_inv: Scalar::ZERO,
+            nonce_point: None,
+        };
+        Ok((session, round1))
+    }
+
+    /// Processes all parties' round 1 messages (own included) and returns the round 2 broadcast.
+    pub fn round2(&mut self, messages: &[PresignRound1]) -> Result<PresignRound2> {
+        self.check_session(messages.iter().map(|m| &m.session_id))?;
+        let messages = all_parties(messages, |m| m.nonce.dealer)?;
+        let (mut nonce, mut mask) = (Scalar::ZERO, Scalar::ZERO);
+        for m in &messages {
+            if m.mask.dealer != m.nonce.dealer {
+                return Err(ThresholdError::Malformed("nonce and mask dealt by different parties".into()));
+            }
+            nonce += m.nonce.share_for(&self.transport)?;
+            mask += m.mask.share_for(&self.transport)?;
+        }
+        self.nonce = nonce;
+        self.mask = mask;
+        Ok(PresignRound2 {
+            session_id: self.session_id.clone(),
+            from: self.transport.index,
+            masked_nonce: scalar_hex(&(nonce * mask)),
+            nonce_point: point_hex(&mul_g(nonce)?),
+        })
+    }
+
+    /// Processes all parties' round 2 messages: opens `kγ`, derives the nonce point and the party's share of `k⁻¹`,
+    /// and returns the round 3 re-sharing of `k⁻¹·x`.
+    pub fn round3(&mut self, messages: &[PresignRound2], rng: &mut impl RngCore) -> Result<PresignRound3> {
+        self.check_session(messages.iter().map(|m| &m.session_id))?;
+        let messages = all_parties(messages, |m| m.from)?;
+        let everyone: Vec<u8> = (1..=PARTIES).collect();
+        let mut masked = Scalar::ZERO;
+        for m in &messages {
+            masked += lagrange(m.from, &everyone) * parse_scalar(&m.masked_nonce)?;
+        }
+        let masked_inv = Option::<Scalar>::from(masked.invert()).ok_or(ThresholdError::Degenerate)?;
+        // Nonce shares have degree 1: every pair must interpolate to the same point.
+        let points = messages
+            .iter()
+            .map(|m| parse_point(&m.nonce_point))
+            .collect::<Result<Vec<_>>>()?;
+        let mut nonce_point = None;
+        for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+            let pair = [messages[a].from, messages[b].from];
+            let r = add_points(&[
+                mul_point(&points[a], lagrange(pair[0], &pair))?,
+                mul_point(&points[b], lagrange(pair[1], &pair))?,
+            ])?;
+            if nonce_point.is_some_and(|p| p != r) {
+                return Err(ThresholdError::InconsistentNonce);
+            }
+            nonce_point = Some(r);
+        }
+        self.nonce_point = nonce_point;
+        self.nonce_inv = masked_inv * self.mask;
+        Ok(PresignRound3 {
+            session_id: self.session_id.clone(),
+            product: Dealing::new(&self.transport, self.nonce_inv * self.secret, rng)?,
+        })
+    }
+
+    /// Processes all parties' round 3 messages and returns this party's presignature.
+    pub fn finish(self, messages: &[PresignRound3]) -> Result<Presignature> {
+        self.check_session(messages.iter().map(|m| &m.session_id))?;
+        let messages = all_parties(messages, |m| m.product.dealer)?;
+        let nonce_point = self.nonce_point.ok_or(ThresholdError::Malformed("round 3 before round 2".into()))?;
+        let everyone: Vec<u8> = (1..=PARTIES).collect();
+        let mut product = Scalar::ZERO;
+        for m in &messages {
+            product += lagrange(m.product.dealer, &everyone) * m.product.share_for(&self.transport)?;
+        }
+        let r = x_coordinate(&nonce_point);
+        if bool::from(r.is_zero()) {
+            return Err(ThresholdError::Degenerate);
+        }
+        Ok(Presignature {
+            index: self.transport.index,
+            info: PresignatureInfo {
+                session_id: self.session_id.clone(),
+                public_key: self.public_key.clone(),
+                nonce_point: point_hex(&nonce_point),
+            },
+            r,
+            nonce_inv: self.nonce_inv,
+            product,
+        })
+    }
+
+    fn check_session<'a>(&self, ids: impl Iterator<Item = &'a String>) -> Result<()> {
+        for id in ids {
+            if *id != self.session_id {
+                return Err(ThresholdError::SessionMismatch {
+                    expected: self.session_id.clone(),
+                    got: id.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Public part of a presignature, shared by all parties of the session; what [`aggregate_signature`] needs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresignatureInfo {
+    pub session_id: String,
+    /// Group public key (compressed hex).
+    pub public_key: String,
+    /// Nonce point `R` (compressed hex).
+    pub nonce_point: String,
+}
+
+/// One party's single-use signing material for one nonce.
+pub struct Presignature {
+    pub index: u8,
+    pub info: PresignatureInfo,
+    r: Scalar,
+    nonce_inv: Scalar,
+    product: Scalar,
+}
+
+impl Drop for Presignature {
+    fn drop(&mut self) {
+        self.nonce_inv.zeroize();
+        self.product.zeroize();
+    }
+}
+
+impl Presignature {
+    /// This party's share of the signature over `digest`. Consumes the presignature: its nonce must sign once.
+    pub fn sign_partial(self, digest: &[u8; 32]) -> SignatureShare {
+        let m = reduce(digest);
+        let s = m * self.nonce_inv + self.r * self.product;
+        SignatureShare {
+            session_id: self.info.session_id.clone(),
+            from: self.index,
+            value: scalar_hex(&s),
+        }
+    }
+}
+
+/// Partial signature of party `from`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureShare {
+    pub session_id: String,
+    pub from: u8,
+    pub value: String,
+}
+
+/// Combines at least [`THRESHOLD`] partial signatures over `digest` into a low-S ECDSA signature and checks it
+/// against the group key.
+pub fn aggregate_signature(
+    info: &PresignatureInfo,
+    digest: &[u8; 32],
+    partials: &[SignatureShare],
+) -> Result<Signature> {
+    let mut signers: Vec<u8> = Vec::new();
+    for p in partials {
+        check_party(p.from)?;
+        if p.session_id != info.session_id {
+            return Err(ThresholdError::SessionMismatch {
+                expected: info.session_id.clone(),
+                got: p.session_id.clone(),
+            });
+        }
+        if signers.contains(&p.from) {
+            return Err(ThresholdError::Malformed(format!("two partial signatures from party {}", p.from)));
+        }
+        signers.push(p.from);
+    }
+    if signers.len() < THRESHOLD as usize {
+        return Err(ThresholdError::WrongParties {
+            expected: (1..=PARTIES).collect(),
+            got: signers,
+        });
+    }
+    let mut s = Scalar::ZERO;
+    for p in partials {
+        s += lagrange(p.from, &signers) * parse_scalar(&p.value)?;
+    }
+    let r = x_coordinate(&parse_point(&info.nonce_point)?);
+    let mut compact = [0u8; 64];
+    compact[..32].copy_from_slice(&r.to_bytes());
+    compact[32..].copy_from_slice(&s.to_bytes());
+    let mut signature = Signature::from_compact(&compact).map_err(|_| ThresholdError::InvalidSignature)?;
+    signature.normalize_s();
+    let public_key = parse_point(&info.public_key)?;
+    SECP256K1
+        .verify_ecdsa(&Message::from_digest(*digest), &signature, &public_key)
+        .map_err(|_| ThresholdError::InvalidSignature)?;
+    Ok(signature)
+}
+
+fn check_party(index: u8) -> Result<()> {
+    if (1..=PARTIES).contains(&index) {
+        Ok(())
+    } else {
+        Err(ThresholdError::InvalidParty(index))
+    }
+}
+
+/// Exactly one message from each party, ordered by party index.
+fn all_parties<T>(messages: &[T], from: impl Fn(&T) -> u8) -> Result<Vec<&T>> {
+    let mut sorted: Vec<&T> = messages.iter().collect();
+    sorted.sort_by_key(|m| from(m));
+    let got: Vec<u8> = sorted.iter().map(|m| from(m)).collect();
+    let expected: Vec<u8> = (1..=PARTIES).collect();
+    if got != expected {
+        return Err(ThresholdError::WrongParties { expected, got });
+    }
+    Ok(sorted)
+}
+
+/// Additional data sealing a share: dealer, recipient and the dealing's commitments, so a sealed share cannot be
+/// moved to another dealing or recipient.
+fn share_aad(dealer: u8, recipient: u8, commitments: &[String]) -> Vec<u8> {
+    let mut aad = vec![dealer, recipient];
+    for c in commitments {
+        aad.extend_from_slice(c.as_bytes());
+    }
+    aad
+}
+
+/// AES-256-GCM key from the ECDH secret of an ephemeral and a transport key. Each ephemeral key seals one share, so
+/// the fixed nonce is never reused under a key.
+fn share_cipher(shared: &SharedSecret, ephemeral: &PublicKey) -> Result<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    let info = [SHARE_KEY_INFO, &ephemeral.serialize()].concat();
+    Hkdf::<Sha256>::new(None, &shared.secret_bytes())
+        .expand(&info, &mut key)
+        .map_err(|_| ThresholdError::Degenerate)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| ThresholdError::Degenerate);
+    key.zeroize();
+    cipher
+}
+
+fn seal(recipient: &PublicKey, plaintext: &[u8; 32], aad: &[u8], rng: &mut impl RngCore) -> Result<SealedShare> {
+    let ephemeral = loop {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        if let Ok(key) = SecretKey::from_slice(&bytes) {
+            break key;
+        }
+    };
+    let ephemeral_public = ephemeral.public_key(SECP256K1);
+    let cipher = share_cipher(&SharedSecret::new(recipient, &ephemeral), &ephemeral_public)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&[0u8; 12]), Payload { msg: plaintext, aad })
+        .map_err(|_| ThresholdError::Degenerate)?;
+    Ok(SealedShare {
+        ephemeral: point_hex(&ephemeral_public),
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+fn open(secret: &SecretKey, sealed: &SealedShare, aad: &[u8]) -> Option<[u8; 32]> {
+    let ephemeral = parse_point(&sealed.ephemeral).ok()?;
+    let ciphertext = hex::decode(&sealed.ciphertext).ok()?;
+    let cipher = share_cipher(&SharedSecret::new(&ephemeral, secret), &ephemeral).ok()?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&[0u8; 12]), Payload { msg: &ciphertext, aad })
+        .ok()?;
+    plaintext.try_into().ok()
+}
+
+/// Lagrange coefficient at zero of party `i` within `set`.
+fn lagrange(i: u8, set: &[u8]) -> Scalar {
+    let (mut num, mut den) = (Scalar::ONE, Scalar::ONE);
+    for &j in set.iter().filter(|&&j| j != i) {
+        num *= Scalar::from(j as u64);
+        den *= Scalar::from(j as u64) - Scalar::from(i as u64);
+    }
+    num * den.invert().unwrap_or(Scalar::ZERO)
+}
+
+/// Uniform non-zero scalar.
+fn random_scalar(rng: &mut impl RngCore) -> Scalar {
+    loop {
+        let s = Scalar::random(&mut *rng);
+        if !bool::from(s.is_zero()) {
+            return s;
+        }
+    }
+}
+
+/// Big-endian bytes reduced modulo the group order.
+fn reduce(bytes: &[u8; 32]) -> Scalar {
+    <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(*bytes))
+}
+
+fn mul_g(s: Scalar) -> Result<PublicKey> {
+    let key = SecretKey::from_slice(&s.to_bytes()).map_err(|_| ThresholdError::Degenerate)?;
+    Ok(PublicKey::from_secret_key(SECP256K1, &key))
+}
+
+fn mul_point(p: &PublicKey, s: Scalar) -> Result<PublicKey> {
+    let tweak = secp256k1::Scalar::from_be_bytes(s.to_bytes().into()).map_err(|_| ThresholdError::Degenerate)?;
+    p.mul_tweak(SECP256K1, &tweak).map_err(|_| ThresholdError::Degenerate)
+}
+
+fn add_points(points: &[PublicKey]) -> Result<PublicKey> {
+    let refs: Vec<&PublicKey> = points.iter().collect();
+    PublicKey::combine_keys(&refs).map_err(|_| ThresholdError::Degenerate)
+}
+
+/// `x(R) mod n`, the ECDSA `r`.
+fn x_coordinate(p: &PublicKey) -> Scalar {
+    reduce(p.serialize()[1..].try_into().expect("33-byte point"))
+}
+
+fn point_hex(p: &PublicKey) -> String {
+    hex::encode(p.serialize())
+}
+
+fn scalar_hex(s: &Scalar) -> String {
+    hex::encode(s.to_bytes())
+}
+
+fn parse_point(s: &str) -> Result<PublicKey> {
+    let bytes = hex::decode(s).map_err(|e| ThresholdError::Malformed(format!("point: {}", e)))?;
+    PublicKey::from_slice(&bytes).map_err(|e| ThresholdError::Malformed(format!("point: {}", e)))
+}
+
+fn parse_scalar(s: &str) -> Result<Scalar> {
+    let bytes: [u8; 32] = hex::decode(s)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| ThresholdError::Malformed(format!("scalar {:?}", s)))?;
+    Option::from(Scalar::from_repr(FieldBytes::from(bytes)))
+        .ok_or_else(|| ThresholdError::Malformed("scalar not below the group order".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::DeterministicRng;
+
+    fn transports(rng: &mut DeterministicRng) -> Vec<Transport> {
+        let secrets: Vec<SecretKey> = (0..PARTIES).map(|_| rng.secret_key()).collect();
+        let roster: Vec<PublicKey> = secrets.iter().map(|s| s.public_key(SECP256K1)).collect();
+        (1..=PARTIES)
+            .zip(secrets)
+            .map(|(i, s)| Transport::new(i, s, roster.clone()).unwrap())
+            .collect()
+    }
+
+    fn keygen(transports: &[Transport], rng: &mut DeterministicRng) -> Vec<KeyShare> {
+        let dealings: Vec<Dealing> = transports.iter().map(|t| keygen_dealing(t, rng).unwrap()).collect();
+        transports.iter().map(|t| KeyShare::from_dealings(t, &dealings).unwrap()).collect()
+    }
+
+    fn presign(
+        shares: &[KeyShare],
+        transports: &[Transport],
+        session_id: &str,
+        rng: &mut DeterministicRng,
+    ) -> Vec<Presignature> {
+        let (mut sessions, round1): (Vec<_>, Vec<_>) = shares
+            .iter()
+            .zip(transports)
+            .map(|(s, t)| PresignSession::start(s, t, session_id, rng).unwrap())
+            .unzip();
+        let round2: Vec<_> = sessions.iter_mut().map(|s| s.round2(&round1).unwrap()).collect();
+        let round3: Vec<_> = sessions.iter_mut().map(|s| s.round3(&round2, rng).unwrap()).collect();
+        sessions.into_iter().map(|s| s.finish(&round3).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_shares_are_sealed_to_their_recipient() {
+        let mut rng = DeterministicRng::new(1);
+        let transports = transports(&mut rng);
+        let dealing = keygen_dealing(&transports[0], &mut rng).unwrap();
+        let own = dealing.share_for(&transports[1]).unwrap();
+        // Party 1 cannot open party 2's share by posing as party 2.
+        let impostor = Transport { index: 2, ..transports[0].clone() };
+        assert_eq!(dealing.share_for(&impostor), Err(ThresholdError::SealedShare(1)));
+        // Nor can a share be moved to another slot.
+        let mut moved = dealing.clone();
+        moved.shares.swap(0, 1);
+        assert_eq!(moved.share_for(&transports[1]), Err(ThresholdError::SealedShare(1)));
+        assert!(!dealing.shares[1].ciphertext.contains(&scalar_hex(&own)));
+    }
+
+    #[test]
+    fn test_any_two_parties_sign() {
+        let mut rng = DeterministicRng::new(2);
+        let transports = transports(&mut rng);
+        let shares = keygen(&transports, &mut rng);
+        assert!(shares.iter().all(|s| s.public_key == shares[0].public_key));
+        let digest = [7u8; 32];
+        for pair in [[0, 1], [0, 2], [1, 2]] {
+            let mut presigs = presign(&shares, &transports, &format!("s{:?}", pair), &mut rng);
+            let info = presigs[0].info.clone();
+            let second = presigs.remove(pair[1]);
+            let first = presigs.remove(pair[0]);
+            let partials = [first.sign_partial(&digest), second.sign_partial(&digest)];
+            let signature = aggregate_signature(&info, &digest, &partials).unwrap();
+            let signature_hex = hex::encode(signature.serialize_compact());
+            assert!(crate::signature::verify_signature_hash(&digest, &signature_hex, &info.public_key).unwrap());
+
+            assert!(matches!(
+                aggregate_signature(&info, &digest, &partials[..1]),
+                Err(ThresholdError::WrongParties { .. })
+            ));
+            assert_eq!(
+                aggregate_signature(&info, &[8u8; 32], &partials),
+                Err(ThresholdError::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn test_tampered_share_is_detected() {
+        let mut rng = DeterministicRng::new(3);
+        let transports = transports(&mut rng);
+        let mut dealings: Vec<Dealing> = transports.iter().map(|t| keygen_dealing(t, &mut rng).unwrap()).collect();
+        // A dealer that seals a share inconsistent with its commitments.
+        let aad = share_aad(2, 3, &dealings[1].commitments);
+        let bogus = random_scalar(&mut rng).to_bytes().into();
+        dealings[1].shares[2] = seal(&transports[2].roster[2], &bogus, &aad, &mut rng).unwrap();
+        assert!(KeyShare::from_dealings(&transports[0], &dealings).is_ok());
+        assert_eq!(KeyShare::from_dealings(&transports[2], &dealings).unwrap_err(), ThresholdError::InvalidShare(2));
+        assert!(matches!(
+            KeyShare::from_dealings(&transports[0], &dealings[..2]),
+            Err(ThresholdError::WrongParties { .. })
+        ));
+    }
+}
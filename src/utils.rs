@@ -1,18 +1,99 @@
+use std::fmt;
+use std::str::FromStr;
 use sha2::Sha256;
 use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
 use crate::error::{PlatariumError, Result};
+use crate::signature::SigningDomain;
 
 /// Default HKDF salt for signature key derivation
 pub const HKDF_SALT: &[u8] = b"PlatariumSignatureKeySalt2050";
 /// Default HKDF info for signature key derivation
 pub const HKDF_INFO: &[u8] = b"Signature Key Derivation";
 
-/// Derives a 32-byte signature seed from master seed using HKDF
+/// Chain that keys are derived for. The chain identifier is mixed into the HKDF salt and info, so one mnemonic
+/// gives unrelated signature keys on each network and a key cannot be replayed across them.
+///
+/// `Mainnet` keeps the original domain ([`HKDF_SALT`], [`HKDF_INFO`]) so existing wallets derive the same
+/// keys; every other network appends `/<chain id>` to both.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkId {
+    #[default]
+    Mainnet,
+    Testnet,
+    /// Any other chain (devnets, private deployments), by chain identifier.
+    Custom(String),
+}
+
+impl NetworkId {
+    pub fn chain_id(&self) -> &str {
+        match self {
+            NetworkId::Mainnet => "platarium-mainnet",
+            NetworkId::Testnet => "platarium-testnet",
+            NetworkId::Custom(id) => id,
+        }
+    }
+
+    /// HKDF salt of the network's signature keys.
+    pub fn hkdf_salt(&self) -> Vec<u8> {
+        self.domain(HKDF_SALT)
+    }
+
+    /// HKDF info of the network's signature keys.
+    pub fn hkdf_info(&self) -> Vec<u8> {
+        self.domain(HKDF_INFO)
+    }
+
+    /// Signing domain of the network's messages. Mainnet keeps the legacy `PlatariumSignature:` separator.
+    pub fn signing_domain(&self) -> SigningDomain {
+        match self {
+            NetworkId::Mainnet => SigningDomain::legacy(),
+            _ => SigningDomain::for_chain(self.chain_id()),
+        }
+    }
+
+    fn domain(&self, base: &[u8]) -> Vec<u8> {
+        let mut out = base.to_vec();
+        if *self != NetworkId::Mainnet {
+            out.push(b'/');
+            out.extend_from_slice(self.chain_id().as_bytes());
+        }
+        out
+    }
+}
+
+impl fmt::Display for NetworkId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkId::Mainnet => f.write_str("mainnet"),
+            NetworkId::Testnet => f.write_str("testnet"),
+            NetworkId::Custom(id) => f.write_str(id),
+        }
+    }
+}
+
+impl FromStr for NetworkId {
+    type Err = PlatariumError;
+
+    /// `mainnet`, `testnet` or a custom chain identifier (non-empty, printable ASCII without spaces).
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "mainnet" | "platarium-mainnet" => Ok(NetworkId::Mainnet),
+            "testnet" | "platarium-testnet" => Ok(NetworkId::Testnet),
+            id if !id.is_empty() && id.bytes().all(|b| b.is_ascii_graphic()) => Ok(NetworkId::Custom(id.to_string())),
+            _ => Err(PlatariumError::Validation(format!("Invalid network id {:?}", s))),
+        }
+    }
+}
+
+/// Derives a 32-byte signature seed from master seed using HKDF. The seed is wiped when dropped.
 pub fn derive_signature_seed_from_master_seed(
     master_seed: &[u8],
     salt: Option<&[u8]>,
     info: Option<&[u8]>,
-) -> Result<[u8; 32]> {
+) -> Result<Zeroizing<[u8; 32]>> {
     if master_seed.is_empty() {
         return Err(PlatariumError::Validation("masterSeed must be non-empty".to_string()));
     }
@@ -21,13 +102,18 @@ pub fn derive_signature_seed_from_master_seed(
     let info = info.unwrap_or(HKDF_INFO);
 
     let hk = Hkdf::<Sha256>::new(Some(salt), master_seed);
-    let mut okm = [0u8; 32];
-    hk.expand(info, &mut okm)
+    let mut okm = Zeroizing::new([0u8; 32]);
+    hk.expand(info, okm.as_mut_slice())
         .map_err(|e| PlatariumError::KeyDerivation(format!("HKDF expansion failed: {}", e)))?;
 
     Ok(okm)
 }
 
+/// [`derive_signature_seed_from_master_seed`] in the domain of `network`.
+pub fn derive_signature_seed_for_network(master_seed: &[u8], network: &NetworkId) -> Result<Zeroizing<[u8; 32]>> {
+    derive_signature_seed_from_master_seed(master_seed, Some(&network.hkdf_salt()), Some(&network.hkdf_info()))
+}
+
 /// Verifies correlation between private key and signature key
 /// Both should be derivable from the same master seed
 pub fn verify_correlation(
@@ -47,10 +133,10 @@ pub fn verify_correlation(
     let signature_seed = derive_signature_seed_from_master_seed(master_seed, hkdf_salt, hkdf_info)?;
     
     // Convert signature seed to hex
-    let derived_sig_hex = hex::encode(signature_seed);
+    let derived_sig_hex = Zeroizing::new(hex::encode(signature_seed.as_slice()));
     
-    // Compare (case-insensitive)
-    let is_match = derived_sig_hex.to_lowercase() == signature_key_hex.to_lowercase();
+    // Compare (case-insensitive, without lowercased copies of the key)
+    let is_match = derived_sig_hex.eq_ignore_ascii_case(signature_key_hex);
     
     Ok(is_match)
 }
@@ -78,6 +164,21 @@ mod tests {
         assert_eq!(result.len(), 32);
     }
 
+    #[test]
+    fn test_network_domains() {
+        let seed = [9u8; 64];
+        let legacy = derive_signature_seed_from_master_seed(&seed, None, None).unwrap();
+        assert_eq!(derive_signature_seed_for_network(&seed, &NetworkId::Mainnet).unwrap(), legacy);
+        let testnet = derive_signature_seed_for_network(&seed, &NetworkId::Testnet).unwrap();
+        let devnet = derive_signature_seed_for_network(&seed, &"devnet-7".parse().unwrap()).unwrap();
+        assert_ne!(testnet, legacy);
+        assert_ne!(devnet, testnet);
+        assert_eq!("testnet".parse::<NetworkId>().unwrap(), NetworkId::Testnet);
+        assert!("".parse::<NetworkId>().is_err());
+        assert_eq!(NetworkId::Mainnet.signing_domain(), SigningDomain::default());
+        assert_eq!(NetworkId::Testnet.signing_domain().separator(), "PlatariumSignature/v1/platarium-testnet:");
+    }
+
     #[test]
     fn test_bn_to_hex32() {
         let value = b"test";
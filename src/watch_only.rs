@@ -0,0 +1,326 @@
+//! Watch-only wallets backed by an account-level extended public key (xpub).
+//!
+//! `export_xpub` runs on the device holding the mnemonic and exports the public node at
+//! [`ACCOUNT_PATH`]. Child `i` of that node is the main key `KeyGenerator` derives for seed index `i`,
+//! so a watch-only machine can list the same `Px…` addresses, query them over the Core RPC, and build
+//! unsigned transactions for an offline device to sign with `sign-transaction`.
+//!
+//! Restoring a wallet from its mnemonic uses the same addresses: [`discover_accounts`] scans seed indices in
+//! order and asks a [`BalanceLookup`] (a local [`State`], or a node through [`RpcBalanceLookup`]) whether each was
+//! used. The scan stops after `gap_limit` unused indices in a row, as BIP44 wallets do.
+
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::str::FromStr;
+use bip32::{ChildNumber, DerivationPath, Prefix, PublicKey as _, XPrv, XPub};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use zeroize::Zeroizing;
+use crate::core::asset::Asset;
+use crate::core::state::State;
+use crate::core::transaction::Transaction;
+use crate::error::{PlatariumError, Result};
+use crate::mnemonic::parse_any_language;
+
+/// Account-level path; receive addresses are its non-hardened children (`m/44'/60'/0'/0/i`).
+pub const ACCOUNT_PATH: &str = "m/44'/60'/0'/0";
+
+/// Exports the xpub at [`ACCOUNT_PATH`] for a mnemonic (any supported wordlist) and alphanumeric part.
+pub fn export_xpub(mnemonic: &str, alphanumeric_part: &str) -> Result<String> {
+    let seed = Zeroizing::new(
+        parse_any_language(mnemonic)
+            .map_err(|_| {
+                PlatariumError::Validation("Provided mnemonic is not valid according to BIP39".to_string())
+            })?
+            .to_seed(alphanumeric_part),
+    );
+    xpub_from_seed(seed.as_slice(), ACCOUNT_PATH)
+}
+
+/// Serializes the public node at `path` below the master key for `seed`.
+pub(crate) fn xpub_from_seed(seed: &[u8], path: &str) -> Result<String> {
+    let path: DerivationPath = path
+        .parse()
+        .map_err(|e| PlatariumError::Bip32(format!("Invalid derivation path: {}", e)))?;
+    let node = path
+        .iter()
+        .try_fold(XPrv::new(seed)?, |acc, child| acc.derive_child(child))?;
+    Ok(node.public_key().to_string(Prefix::XPUB))
+}
+
+/// Public half of a `KeyPair`, restored from an xpub alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchOnlyKeys {
+    pub seed_index: u32,
+    /// Full path of the key, assuming the xpub was exported at [`ACCOUNT_PATH`].
+    pub main_path: String,
+    /// `Px`-prefixed compressed main public key; equals `KeyPair::public_key`.
+    pub public_key: String,
+}
+
+/// Derives the main public key for `index` from an xpub, without the mnemonic. The HKDF signature key is
+/// derived from the master seed rather than along the BIP32 tree, so it cannot be recovered from an xpub.
+pub fn derive_public_keys(xpub: &str, index: u32) -> Result<WatchOnlyKeys> {
+    Ok(WatchOnlyKeys {
+        seed_index: index,
+        main_path: format!("{}/{}", ACCOUNT_PATH, index),
+        public_key: WatchOnlyWallet::from_xpub(xpub)?.address(index)?,
+    })
+}
+
+/// Derives receive addresses from an xpub; holds no private key material.
+#[derive(Clone)]
+pub struct WatchOnlyWallet {
+    xpub: XPub,
+}
+
+impl WatchOnlyWallet {
+    /// Parses an xpub produced by [`export_xpub`].
+    pub fn from_xpub(xpub: &str) -> Result<Self> {
+        let xpub = XPub::from_str(xpub.trim())?;
+        Ok(Self { xpub })
+    }
+
+    /// `Px`-prefixed compressed public key of child `index`; equals `KeyPair::public_key` for seed index `index`.
+    pub fn address(&self, index: u32) -> Result<String> {
+        let child = self.xpub.derive_child(ChildNumber::new(index, false)?)?;
+        Ok(format!("Px{}", hex::encode(child.public_key().to_bytes())))
+    }
+
+    /// Addresses for indices `start..start + count`.
+    pub fn addresses(&self, start: u32, count: u32) -> Result<Vec<String>> {
+        (start..start.saturating_add(count)).map(|i| self.address(i)).collect()
+    }
+
+    /// Builds an unsigned transfer from address `index`, as gateway JSON with empty signatures and the
+    /// canonical hash filled in. The offline signer must reproduce the same hash.
+    pub fn unsigned_transfer(
+        &self,
+        index: u32,
+        to: &str,
+        asset: Asset,
+        amount: u128,
+        fee_uplp: u128,
+        nonce: u64,
+    ) -> Result<Value> {
+        let tx = Transaction::new(
+            self.address(index)?,
+            to.to_string(),
+            asset,
+            amount,
+            fee_uplp,
+            nonce,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )?;
+        serde_json::to_value(&tx).map_err(|e| PlatariumError::State(format!("encode tx: {}", e)))
+    }
+
+    /// Scans indices from 0 until `gap_limit` consecutive ones are unused, and returns the used ones.
+    pub fn discover(&self, lookup: &impl BalanceLookup, gap_limit: u32) -> Result<AccountDiscovery> {
+        if gap_limit == 0 {
+            return Err(PlatariumError::Validation("gap limit must be positive".to_string()));
+        }
+        let mut accounts = Vec::new();
+        let mut next_index = 0;
+        let mut index = 0;
+        while index - next_index < gap_limit && index < ChildNumber::HARDENED_FLAG {
+            let address = self.address(index)?;
+            let activity = lookup.activity(&address)?;
+            if activity.is_used() {
+                accounts.push(DiscoveredAccount { seed_index: index, address, activity });
+                next_index = index + 1;
+            }
+            index += 1;
+        }
+        Ok(AccountDiscovery { accounts, next_index })
+    }
+}
+
+/// Unused indices in a row after which [`discover_accounts`] stops (the BIP44 default).
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// On-chain activity of one address.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountActivity {
+    pub balance: u128,
+    pub uplp_balance: u128,
+    pub nonce: u64,
+}
+
+impl AccountActivity {
+    /// Whether the address has sent a transaction or holds PLP or μPLP.
+    pub fn is_used(&self) -> bool {
+        self.nonce > 0 || self.balance > 0 || self.uplp_balance > 0
+    }
+}
+
+/// Source of account activity for [`discover_accounts`].
+pub trait BalanceLookup {
+    fn activity(&self, address: &str) -> Result<AccountActivity>;
+}
+
+impl BalanceLookup for State {
+    fn activity(&self, address: &str) -> Result<AccountActivity> {
+        let address = address.to_string();
+        Ok(AccountActivity {
+            balance: self.get_balance(&address),
+            uplp_balance: self.get_uplp_balance(&address),
+            nonce: self.get_nonce(&address),
+        })
+    }
+}
+
+/// Looks accounts up with the `state_query` method of a Core RPC server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcBalanceLookup {
+    /// `host:port` of the server.
+    pub endpoint: String,
+    /// State file path on the server.
+    pub state_file: String,
+}
+
+impl BalanceLookup for RpcBalanceLookup {
+    fn activity(&self, address: &str) -> Result<AccountActivity> {
+        let v = rpc_call(
+            &self.endpoint,
+            "state_query",
+            json!({"state_file": self.state_file, "address": address, "asset": "PLP"}),
+        )?;
+        let amount = |key: &str| -> Result<u128> {
+            v[key]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| PlatariumError::State(format!("state_query: bad {}", key)))
+        };
+        Ok(AccountActivity {
+            balance: amount("balance")?,
+            uplp_balance: amount("uplp_balance")?,
+            nonce: v["nonce"]
+                .as_u64()
+                .ok_or_else(|| PlatariumError::State("state_query: bad nonce".to_string()))?,
+        })
+    }
+}
+
+/// A used address found by discovery.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoveredAccount {
+    pub seed_index: u32,
+    pub address: String,
+    pub activity: AccountActivity,
+}
+
+/// Result of a gap-limit scan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDiscovery {
+    /// Used accounts, by seed index.
+    pub accounts: Vec<DiscoveredAccount>,
+    /// First index after the last used one: where the wallet should hand out its next address.
+    pub next_index: u32,
+}
+
+/// Finds the used accounts of a mnemonic (any supported wordlist): see [`WatchOnlyWallet::discover`].
+pub fn discover_accounts(
+    mnemonic: &str,
+    alphanumeric_part: &str,
+    lookup: &impl BalanceLookup,
+    gap_limit: u32,
+) -> Result<AccountDiscovery> {
+    WatchOnlyWallet::from_xpub(&export_xpub(mnemonic, alphanumeric_part)?)?.discover(lookup, gap_limit)
+}
+
+/// Sends one JSON-RPC request to a Core RPC server at `host:port` and returns its `result`.
+pub fn rpc_call(endpoint: &str, method: &str, params: Value) -> Result<Value> {
+    let mut stream = TcpStream::connect(endpoint)
+        .map_err(|e| PlatariumError::State(format!("connect {}: {}", endpoint, e)))?;
+    let request = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+    writeln!(stream, "{}", request).map_err(|e| PlatariumError::State(format!("rpc write: {}", e)))?;
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| PlatariumError::State(format!("rpc read: {}", e)))?;
+    let mut response: Value = serde_json::from_str(&line)
+        .map_err(|e| PlatariumError::State(format!("rpc response: {}", e)))?;
+    if let Some(err) = response.get("error") {
+        return Err(PlatariumError::State(format!("rpc error: {}", err)));
+    }
+    Ok(response["result"].take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_generator::KeyGenerator;
+
+    #[test]
+    fn test_xpub_addresses_match_generated_keys() {
+        let key_gen = KeyGenerator::default();
+        let keys = key_gen.generate_keys().unwrap();
+        let xpub = export_xpub(&keys.mnemonic, &keys.alphanumeric_part).unwrap();
+        assert!(xpub.starts_with("xpub"));
+        let wallet = WatchOnlyWallet::from_xpub(&xpub).unwrap();
+        let restored = key_gen.restore_keys(&keys.mnemonic, &keys.alphanumeric_part, 3, None).unwrap();
+        assert_eq!(wallet.address(0).unwrap(), keys.public_key);
+        assert_eq!(wallet.addresses(3, 1).unwrap(), vec![restored.public_key.clone()]);
+
+        let tx = wallet.unsigned_transfer(0, "merchant", Asset::PLP, 5, 1, 0).unwrap();
+        let parsed = Transaction::from_gateway_json(&tx.to_string()).unwrap();
+        assert_eq!(parsed.from, keys.public_key);
+        assert_eq!(parsed.hash, parsed.compute_hash().unwrap());
+        assert!(parsed.sig_main.is_empty());
+    }
+
+    #[test]
+    fn test_discovery_stops_after_gap_limit() {
+        let keys = KeyGenerator::default().generate_keys().unwrap();
+        let wallet = WatchOnlyWallet::from_xpub(&export_xpub(&keys.mnemonic, &keys.alphanumeric_part).unwrap()).unwrap();
+        let state = State::new();
+        state.set_balance(&wallet.address(0).unwrap(), 5);
+        state.set_nonce(&wallet.address(3).unwrap(), 1);
+        // Beyond a gap of 3 unused indices: found with gap limit 4, not with 3.
+        state.set_uplp_balance(&wallet.address(7).unwrap(), 9);
+
+        let found = discover_accounts(&keys.mnemonic, &keys.alphanumeric_part, &state, 3).unwrap();
+        let indices: Vec<u32> = found.accounts.iter().map(|a| a.seed_index).collect();
+        assert_eq!(indices, vec![0, 3]);
+        assert_eq!(found.next_index, 4);
+
+        let found = wallet.discover(&state, 4).unwrap();
+        assert_eq!(found.accounts.last().unwrap().activity.uplp_balance, 9);
+        assert_eq!(found.next_index, 8);
+        assert!(wallet.discover(&State::new(), 0).is_err());
+        assert_eq!(wallet.discover(&State::new(), 5).unwrap().next_index, 0);
+    }
+
+    #[test]
+    fn test_key_generator_xpub_and_public_key_restore() {
+        let key_gen = KeyGenerator::default().with_language(bip39::Language::Spanish);
+        let keys = key_gen.generate_keys().unwrap();
+        let xpub = key_gen.export_xpub(&keys.mnemonic, &keys.alphanumeric_part).unwrap();
+        assert_eq!(xpub, export_xpub(&keys.mnemonic, &keys.alphanumeric_part).unwrap());
+
+        let restored = key_gen.restore_keys(&keys.mnemonic, &keys.alphanumeric_part, 7, None).unwrap();
+        let watch = derive_public_keys(&xpub, 7).unwrap();
+        assert_eq!(watch.public_key, restored.public_key);
+        assert_eq!(watch.main_path, restored.derivation_paths.main_path);
+
+        let custom = KeyGenerator::new(0, None, None, Some("m/44'/60'/1'/0/4".parse().unwrap()))
+            .unwrap()
+            .with_language(bip39::Language::Spanish);
+        let custom_keys = custom
+            .restore_keys(&keys.mnemonic, &keys.alphanumeric_part, 0, Some("m/44'/60'/1'/0/4".parse().unwrap()))
+            .unwrap();
+        let custom_xpub = custom.export_xpub(&keys.mnemonic, &keys.alphanumeric_part).unwrap();
+        assert_ne!(custom_xpub, xpub);
+        assert_eq!(derive_public_keys(&custom_xpub, 4).unwrap().public_key, custom_keys.public_key);
+        assert!(KeyGenerator::new(0, None, None, Some("m/44'/60'/0'/0/4'".parse().unwrap()))
+            .unwrap()
+            .with_language(bip39::Language::Spanish)
+            .export_xpub(&keys.mnemonic, &keys.alphanumeric_part)
+            .is_err());
+    }
+}
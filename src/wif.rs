@@ -0,0 +1,255 @@
+//! Compact, checksummed private key strings (WIF-style) for moving keys between tools.
+//!
+//! A key is encoded as Base58Check over 34 bytes: a network byte, the 32-byte secret key and a kind byte saying
+//! whether it is a main (`PSx`) or HKDF signature (`Sx`) key. The 4-byte checksum is the start of
+//! `SHA256(SHA256(payload))`, as in Bitcoin WIF, so a mistyped character is caught on import. The network byte
+//! keeps testnet keys from being imported into a mainnet wallet by accident.
+//!
+//! Keys exported here are the same secrets as the hex forms; they are only shorter and checked.
+
+use bs58::decode::Error as Base58Error;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::Zeroizing;
+use crate::address::address_from_public_key;
+use crate::error::{PlatariumError, Result};
+use crate::key_generator::{DerivationPaths, KeyPair};
+
+/// Decoded payload length: network byte, secret key, kind byte.
+const WIF_PAYLOAD_LEN: usize = 34;
+
+/// Derivation path recorded on a [`KeyPair`] rebuilt from WIF keys, which carry no path.
+pub const WIF_IMPORTED_PATH: &str = "imported";
+
+/// Errors produced while decoding a WIF key.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WifError {
+    #[error("not valid Base58")]
+    InvalidEncoding,
+
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+
+    #[error("expected {WIF_PAYLOAD_LEN} payload bytes, got {0}")]
+    InvalidLength(usize),
+
+    #[error("unknown network byte {0:#04x}")]
+    UnknownNetwork(u8),
+
+    #[error("unknown key kind byte {0:#04x}")]
+    UnknownKind(u8),
+
+    #[error("not a valid secp256k1 secret key")]
+    InvalidKey,
+
+    #[error("expected a {expected:?} key, got a {got:?} key")]
+    WrongKind { expected: WifKind, got: WifKind },
+
+    #[error("main key is for {main:?}, signature key for {signature:?}")]
+    NetworkMismatch { main: WifNetwork, signature: WifNetwork },
+}
+
+impl From<WifError> for PlatariumError {
+    fn from(e: WifError) -> Self {
+        PlatariumError::Crypto(format!("Invalid WIF key: {}", e))
+    }
+}
+
+/// Network a WIF key is meant for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WifNetwork {
+    Mainnet,
+    Testnet,
+}
+
+impl WifNetwork {
+    pub const fn byte(self) -> u8 {
+        match self {
+            WifNetwork::Mainnet => 0x50,
+            WifNetwork::Testnet => 0x74,
+        }
+    }
+
+    pub fn from_byte(byte: u8) -> std::result::Result<Self, WifError> {
+        match byte {
+            0x50 => Ok(WifNetwork::Mainnet),
+            0x74 => Ok(WifNetwork::Testnet),
+            other => Err(WifError::UnknownNetwork(other)),
+        }
+    }
+}
+
+/// Which of a [`KeyPair`]'s private keys a WIF string holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WifKind {
+    /// BIP32 main key, `PSx…`.
+    Main,
+    /// HKDF signature key, `Sx…`.
+    Signature,
+}
+
+impl WifKind {
+    pub const fn byte(self) -> u8 {
+        match self {
+            WifKind::Main => 0x01,
+            WifKind::Signature => 0x02,
+        }
+    }
+
+    pub fn from_byte(byte: u8) -> std::result::Result<Self, WifError> {
+        match byte {
+            0x01 => Ok(WifKind::Main),
+            0x02 => Ok(WifKind::Signature),
+            other => Err(WifError::UnknownKind(other)),
+        }
+    }
+
+    /// Prefix of the hex form (`PSx` or `Sx`).
+    pub const fn hex_prefix(self) -> &'static str {
+        match self {
+            WifKind::Main => "PSx",
+            WifKind::Signature => "Sx",
+        }
+    }
+}
+
+/// A decoded WIF key. The hex form is wiped when it is dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifKey {
+    pub network: WifNetwork,
+    pub kind: WifKind,
+    /// The key in its usual form: `PSx` or `Sx` followed by 64 hex characters.
+    pub private_key: Zeroizing<String>,
+}
+
+/// Both private keys of a [`KeyPair`] in WIF form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WifKeys {
+    pub network: WifNetwork,
+    pub main: String,
+    pub signature: String,
+}
+
+/// Encodes a `PSx…` or `Sx…` private key for `network`.
+pub fn encode_wif(private_key: &str, network: WifNetwork) -> Result<String> {
+    let (kind, hex_key) = if let Some(hex_key) = private_key.strip_prefix(WifKind::Main.hex_prefix()) {
+        (WifKind::Main, hex_key)
+    } else if let Some(hex_key) = private_key.strip_prefix(WifKind::Signature.hex_prefix()) {
+        (WifKind::Signature, hex_key)
+    } else {
+        return Err(PlatariumError::Validation("private key must start with PSx or Sx".to_string()));
+    };
+    let secret = hex::decode(hex_key).map_err(|e| PlatariumError::Crypto(format!("Invalid private key: {}", e)))?;
+    let secret = Zeroizing::new(secret);
+    SecretKey::from_slice(&secret).map_err(|_| WifError::InvalidKey)?;
+    let mut payload = Zeroizing::new(Vec::with_capacity(WIF_PAYLOAD_LEN));
+    payload.push(network.byte());
+    payload.extend_from_slice(&secret);
+    payload.push(kind.byte());
+    Ok(bs58::encode(payload.as_slice()).with_check().into_string())
+}
+
+/// Decodes and checks a WIF key.
+pub fn decode_wif(wif: &str) -> std::result::Result<WifKey, WifError> {
+    let payload = Zeroizing::new(bs58::decode(wif.trim()).with_check(None).into_vec().map_err(|e| match e {
+        Base58Error::InvalidChecksum { .. } => WifError::ChecksumMismatch,
+        _ => WifError::InvalidEncoding,
+    })?);
+    if payload.len() != WIF_PAYLOAD_LEN {
+        return Err(WifError::InvalidLength(payload.len()));
+    }
+    let network = WifNetwork::from_byte(payload[0])?;
+    let kind = WifKind::from_byte(payload[WIF_PAYLOAD_LEN - 1])?;
+    let secret = &payload[1..WIF_PAYLOAD_LEN - 1];
+    SecretKey::from_slice(secret).map_err(|_| WifError::InvalidKey)?;
+    Ok(WifKey {
+        network,
+        kind,
+        private_key: Zeroizing::new(format!("{}{}", kind.hex_prefix(), hex::encode(secret))),
+    })
+}
+
+impl KeyPair {
+    /// Both private keys in WIF form.
+    pub fn export_wif(&self, network: WifNetwork) -> Result<WifKeys> {
+        Ok(WifKeys {
+            network,
+            main: encode_wif(&self.private_key, network)?,
+            signature: encode_wif(&self.signature_key, network)?,
+        })
+    }
+
+    /// Rebuilds a key pair from its two WIF keys, which must be for the same network. WIF carries neither the
+    /// mnemonic nor the derivation path: `mnemonic` and `alphanumeric_part` are empty and both paths are
+    /// [`WIF_IMPORTED_PATH`]. The public key is recomputed from the main key.
+    pub fn from_wif(main: &str, signature: &str) -> Result<KeyPair> {
+        let main = decode_wif(main)?;
+        let signature = decode_wif(signature)?;
+        for (key, expected) in [(&main, WifKind::Main), (&signature, WifKind::Signature)] {
+            if key.kind != expected {
+                return Err(WifError::WrongKind { expected, got: key.kind }.into());
+            }
+        }
+        if main.network != signature.network {
+            return Err(WifError::NetworkMismatch { main: main.network, signature: signature.network }.into());
+        }
+        let main_hex = &main.private_key[WifKind::Main.hex_prefix().len()..];
+        let secret_bytes = Zeroizing::new(hex::decode(main_hex).map_err(|_| WifError::InvalidKey)?);
+        let mut secret = SecretKey::from_slice(&secret_bytes).map_err(|_| WifError::InvalidKey)?;
+        let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &secret);
+        secret.non_secure_erase();
+        Ok(KeyPair {
+            mnemonic: String::new(),
+            alphanumeric_part: String::new(),
+            derivation_paths: DerivationPaths {
+                main_path: WIF_IMPORTED_PATH.to_string(),
+                signature_path: WIF_IMPORTED_PATH.to_string(),
+            },
+            public_key: address_from_public_key(&public_key),
+            private_key: main.private_key.to_string(),
+            signature_key: signature.private_key.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_generator::KeyGenerator;
+
+    #[test]
+    fn test_key_pair_round_trip() {
+        let keys = KeyGenerator::default().generate_keys().unwrap();
+        let wif = keys.export_wif(WifNetwork::Mainnet).unwrap();
+        assert_ne!(wif.main, wif.signature);
+        let decoded = decode_wif(&wif.main).unwrap();
+        assert_eq!((decoded.network, decoded.kind), (WifNetwork::Mainnet, WifKind::Main));
+        assert_eq!(*decoded.private_key, keys.private_key);
+
+        let imported = KeyPair::from_wif(&wif.main, &wif.signature).unwrap();
+        assert_eq!(imported.public_key, keys.public_key);
+        assert_eq!(imported.private_key, keys.private_key);
+        assert_eq!(imported.signature_key, keys.signature_key);
+        assert_eq!(imported.export_wif(WifNetwork::Mainnet).unwrap(), wif);
+
+        assert!(KeyPair::from_wif(&wif.signature, &wif.main).is_err());
+        let testnet = keys.export_wif(WifNetwork::Testnet).unwrap();
+        assert!(KeyPair::from_wif(&wif.main, &testnet.signature).is_err());
+    }
+
+    #[test]
+    fn test_rejects_corrupted_keys() {
+        let wif = encode_wif(&format!("PSx{}", "11".repeat(32)), WifNetwork::Testnet).unwrap();
+        let mut typo: Vec<char> = wif.chars().collect();
+        typo[10] = if typo[10] == '2' { '3' } else { '2' };
+        assert_eq!(decode_wif(&typo.into_iter().collect::<String>()), Err(WifError::ChecksumMismatch));
+        assert_eq!(decode_wif("0OIl"), Err(WifError::InvalidEncoding));
+        let short = bs58::encode([0x50u8; 10]).with_check().into_string();
+        assert_eq!(decode_wif(&short), Err(WifError::InvalidLength(10)));
+        assert!(encode_wif(&format!("PSx{}", "00".repeat(32)), WifNetwork::Mainnet).is_err());
+        assert!(encode_wif("0x1234", WifNetwork::Mainnet).is_err());
+    }
+}
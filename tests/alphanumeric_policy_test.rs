@@ -0,0 +1,29 @@
+//! Alphanumeric policy end to end: a part generated under a custom policy restores the same keys, and the
+//! strength check flags guessable user codes but not generated ones.
+
+use platarium_core::*;
+
+#[test]
+fn generated_parts_restore_keys_and_pass_the_strength_check() {
+    let policy = CharsetPolicy::new(20, "abcdefghijklmnopqrstuvwxyz234567").unwrap();
+    assert_eq!(policy.entropy_bits(), 100.0);
+    let code = generate_alphanumeric_part_with(&policy).unwrap();
+    assert_eq!(code.len(), 20);
+    assert!(policy.strength_warning(&code).is_none());
+
+    let (mnemonic, _) = generate_mnemonic().unwrap();
+    let key_gen = KeyGenerator::default();
+    let keys = key_gen.restore_keys(&mnemonic, &code, 0, None).unwrap();
+    assert_eq!(keys.alphanumeric_part, code);
+    assert_eq!(key_gen.restore_keys(&mnemonic, &code, 0, None).unwrap().public_key, keys.public_key);
+}
+
+#[test]
+fn weak_user_codes_are_flagged() {
+    let policy = CharsetPolicy::default();
+    for weak in ["", "1234", "AAAAAAAAAAAA", "password"] {
+        assert!(policy.strength_warning(weak).is_some(), "{:?}", weak);
+    }
+    assert!(estimate_alphanumeric_entropy("ABCDEFGH") < estimate_alphanumeric_entropy("QX7M2KP9"));
+    assert!(CharsetPolicy::new(4, "AB").is_err());
+}
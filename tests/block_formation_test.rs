@@ -5,7 +5,7 @@ use platarium_core::{
     block_proposal_status, bootstrap_from_snapshot, build_commit_batch, calculate_fee_from_load,
     commit_block, create_snapshot_if_due, get_account, get_block, get_head, get_receipt,
     get_state_root, get_tx, list_snapshots, mempool_admit, parse_mempool_snapshot, select_block_txs,
-    AccountRecord,
+    recent_load_samples, AccountRecord, Core, HeadInfo, NetworkLoad, NETWORK_CAPACITY_TPS,
     BlockCommit, BlockRecordStored, ReceiptRecord, RocksStore, State, BLOCK_GAS_CAP_UPLP,
     BLOCK_MAX_WAIT_SEC, SNAPSHOT_INTERVAL,
 };
@@ -126,6 +126,9 @@ fn make_commit(height: u64, hashes: &[&str], fees: &[u64]) -> BlockCommit {
             state_root: format!("root{}", height),
             block_hash: format!("bh{}", height),
             producer_id: "n1".into(),
+            address_bloom: None,
+            messages_root: None,
+            bytes_used: None,
         },
         tx_jsons,
         accounts: vec![
@@ -150,9 +153,18 @@ fn make_commit(height: u64, hashes: &[&str], fees: &[u64]) -> BlockCommit {
                 status: "ok".into(),
                 fee_uplp: fees.get(i).copied().unwrap_or(1),
                 block_height: height,
+                rebate_uplp: 0,
+                events: Vec::new(),
+                fee_payer: None,
+                index: None,
+                error_code: None,
+                balance_deltas: Vec::new(),
             })
             .collect(),
         state_root: format!("root{}", height),
+        slashing_events: Vec::new(),
+        fee_bucket: None,
+        messages: Vec::new(),
     }
 }
 
@@ -225,6 +237,37 @@ fn e2e_restart_reopen_db() {
     assert_eq!(get_account(&store, "PxA").unwrap().unwrap().balance, "100");
 }
 
+#[test]
+fn core_commit_publishes_head() {
+    let dir = TempDir::new().unwrap();
+    let store = RocksStore::open(dir.path().join("db")).unwrap();
+    commit_block(&store, &make_commit(1, &["tx1"], &[1])).unwrap();
+    let core = Core::with_store(store);
+    assert_eq!(core.head().block_hash, "bh1");
+
+    let mut updates = core.head_updates_sync();
+    let follower = std::thread::spawn(move || updates.changed().unwrap());
+    let head = core.commit_block(&make_commit(2, &["tx2"], &[1])).unwrap();
+    assert_eq!(
+        head,
+        HeadInfo {
+            height: 2,
+            block_hash: "bh2".into(),
+            state_root: "root2".into(),
+            finalized_height: 2,
+        }
+    );
+    assert_eq!(follower.join().unwrap(), head);
+    // The commit path records the block's fee bucket.
+    let buckets = core.fee_bucket_history(1..=2).unwrap();
+    assert_eq!(buckets.len(), 1);
+    assert_eq!(buckets[0].block_number, 2);
+    // A rejected commit leaves the head alone.
+    assert!(core.commit_block(&make_commit(5, &["tx5"], &[1])).is_err());
+    assert_eq!(core.head(), head);
+    assert!(Core::new().commit_block(&make_commit(1, &["tx1"], &[1])).is_err());
+}
+
 #[test]
 fn crash_no_partial_block() {
     let dir = TempDir::new().unwrap();
@@ -299,3 +342,36 @@ fn snapshot_at_interval_and_bootstrap() {
         "42"
     );
 }
+
+#[test]
+fn validator_selection_load_comes_from_stored_headers() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("db");
+    let samples = {
+        let store = RocksStore::open(&db_path).unwrap();
+        // Full blocks one second apart: throughput at capacity.
+        for height in 1..=3 {
+            let hashes: Vec<String> = (0..NETWORK_CAPACITY_TPS).map(|i| format!("h{}_{}", height, i)).collect();
+            let refs: Vec<&str> = hashes.iter().map(String::as_str).collect();
+            commit_block(&store, &make_commit(height, &refs, &[])).unwrap();
+        }
+        let samples = recent_load_samples(&store).unwrap();
+        assert_eq!(samples.len(), 3);
+        let load = Core::with_store(store).stored_network_load().unwrap();
+        assert_eq!((load.avg_tps, load.network_load_pct), (NETWORK_CAPACITY_TPS, 100));
+        assert_eq!(Core::new().stored_network_load().unwrap().network_load_pct, 0);
+        samples
+    };
+
+    let db = db_path.to_str().unwrap();
+    let call = |method: &str, params: serde_json::Value| -> serde_json::Value {
+        serde_json::from_str(&platarium_core::core::core_rpc::dispatch_rpc(method, &params).unwrap()).unwrap()
+    };
+    let loaded = NetworkLoad::estimate(0, &samples);
+    let count = call("committee_count", serde_json::json!({"candidates": 40, "pending_count": 0, "db_path": db}));
+    assert_eq!(count["count"], loaded.committee_count(40));
+    assert_ne!(loaded.committee_count(40), NetworkLoad::from_pending(0).committee_count(40));
+    let percent = call("selection_percent_from_load", serde_json::json!({"pending_count": 0, "db_path": db}));
+    assert_eq!(percent["percent"], loaded.l1_selection_percent().unwrap());
+    assert_eq!(percent["network_load_pct"], 100);
+}
@@ -0,0 +1,189 @@
+//! Inbound bridge end to end: governance registers a wrapped asset and attests a foreign header, a relayer's
+//! `BridgeClaim` mints the claimed amount once, and unproven or replayed claims mint nothing, also after the state
+//! is saved and reloaded.
+
+use platarium_core::core::execution::{ExecutionContext, ExecutionLogic};
+use platarium_core::testing::{DeterministicRng, TestAccount};
+use platarium_core::*;
+use std::collections::HashSet;
+
+/// Three validators with their node keys.
+fn validators(rng: &mut DeterministicRng) -> (NodeRegistry, Vec<(String, secp256k1::SecretKey)>) {
+    let registry = NodeRegistry::new();
+    let keys: Vec<_> = (0..3).map(|i| (format!("n{}", i), rng.secret_key())).collect();
+    for (id, key) in &keys {
+        let public_key = hex::encode(key.public_key(secp256k1::SECP256K1).serialize());
+        registry.register(id.clone(), public_key, 100, 10).unwrap();
+    }
+    (registry, keys)
+}
+
+fn approve(validators: &[(String, secp256k1::SecretKey)], action: &GovernanceAction) -> Vec<Approval> {
+    validators
+        .iter()
+        .map(|(id, key)| Approval::sign(id.clone(), action, key).unwrap())
+        .collect()
+}
+
+fn attest(validators: &[(String, secp256k1::SecretKey)], height: u64, claims_root: &str) -> Vec<Approval> {
+    approve(
+        validators,
+        &GovernanceAction::AttestForeignHeader {
+            chain_id: "eth".into(),
+            height,
+            claims_root: claims_root.to_string(),
+        },
+    )
+}
+
+fn weth() -> Asset {
+    Asset::Token("wETH".to_string())
+}
+
+fn claim(id: &str, recipient: &str, amount: u128) -> BridgeClaim {
+    BridgeClaim {
+        source_chain: "eth".into(),
+        claim_id: id.into(),
+        recipient: recipient.into(),
+        asset: weth().as_canonical(),
+        amount,
+    }
+}
+
+/// Claims root of a foreign header holding `claims`, and the proof for `claims[index]`.
+fn header_proof(claims: &[BridgeClaim], index: usize, height: u64) -> ForeignHeaderProof {
+    let leaves: Vec<[u8; 32]> = claims.iter().map(BridgeClaim::leaf).collect();
+    let root = platarium_consensus::merkle_root(leaves.clone()).unwrap();
+    let siblings = platarium_consensus::merkle_proof(leaves, claims[index].leaf()).unwrap();
+    ForeignHeaderProof {
+        chain_id: "eth".into(),
+        height,
+        claims_root: hex::encode(root),
+        siblings: siblings.iter().map(hex::encode).collect(),
+        finality: String::new(),
+    }
+}
+
+fn claim_tx(relayer: &TestAccount, claim: &BridgeClaim, proof: ForeignHeaderProof, nonce: u64) -> Transaction {
+    relayer.sign(
+        Transaction::new(
+            relayer.address.clone(),
+            claim.recipient.clone(),
+            weth(),
+            claim.amount,
+            1,
+            nonce,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap()
+        .with_kind(TxKind::BridgeClaim {
+            claim_id: claim.claim_id.clone(),
+            proof: Box::new(proof),
+        })
+        .unwrap(),
+    )
+}
+
+#[test]
+fn verified_claim_mints_once() {
+    let mut rng = DeterministicRng::new(51);
+    let relayer = rng.account();
+    let recipient = rng.account().address;
+    let state = State::new();
+    state.set_balance(&relayer.address, 100);
+    let (registry, validators) = validators(&mut rng);
+    let register = |asset: &Asset, chain: &str| GovernanceAction::RegisterWrappedAsset {
+        asset: asset.as_canonical(),
+        source_chain: chain.into(),
+    };
+    let wsol = Asset::Token("wSOL".into());
+    let approvals = approve(&validators, &register(&weth(), "eth"));
+    register_wrapped_asset(&state, &registry, &weth(), "eth", &approvals).unwrap();
+    let too_few = approve(&validators[..1], &register(&wsol, "sol"));
+    assert!(register_wrapped_asset(&state, &registry, &wsol, "sol", &too_few).is_err());
+    // Approvals of one registration do not carry over to another.
+    let other_chain = approve(&validators, &register(&wsol, "eth"));
+    assert!(register_wrapped_asset(&state, &registry, &wsol, "sol", &other_chain).is_err());
+
+    let claims = vec![claim("lock-1", &recipient, 500), claim("lock-2", &recipient, 70)];
+    let proof = header_proof(&claims, 0, 9);
+
+    // Nothing is minted before the header is attested.
+    let early = claim_tx(&relayer, &claims[0], proof.clone(), 0);
+    let err = ExecutionLogic::execute_transaction(&state, &early, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("No attested header"), "{}", err);
+
+    // Signatures over another height, or bare node ids with forged signatures, attest nothing.
+    let approvals = attest(&validators, 9, &proof.claims_root);
+    assert!(attest_foreign_header(&state, &registry, "eth", 10, &proof.claims_root, &approvals).is_err());
+    let forged: Vec<Approval> = approvals
+        .iter()
+        .map(|a| Approval { approver: a.approver.clone(), signature: approvals[0].signature.clone() })
+        .collect();
+    let err = attest_foreign_header(&state, &registry, "eth", 9, &proof.claims_root, &forged).unwrap_err();
+    assert!(err.to_string().contains("does not verify"), "{}", err);
+    attest_foreign_header(&state, &registry, "eth", 9, &proof.claims_root.to_uppercase(), &approvals).unwrap();
+    let snapshot = state.create_snapshot();
+    ExecutionLogic::execute_transaction(&state, &early, ExecutionContext::Production).unwrap();
+    assert_eq!(state.get_asset_balance(&recipient, &weth()), 500);
+    assert_eq!(state.get_balance(&relayer.address), 99);
+    assert!(state.asset_registry().bridge().is_claimed("eth", "lock-1"));
+    assert!(state
+        .take_events()
+        .iter()
+        .any(|e| matches!(e, Event::BridgeClaimed { amount: 500, .. })));
+
+    let replay = claim_tx(&relayer, &claims[0], proof.clone(), 1);
+    let err = ExecutionLogic::execute_transaction(&state, &replay, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("already minted"), "{}", err);
+
+    // The ledger of minted claims survives a restart.
+    let path = std::env::temp_dir().join(format!("platarium_bridge_{}.json", std::process::id()));
+    save_state_file(&path, &state).unwrap();
+    let reloaded = load_state_file(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let err = ExecutionLogic::execute_transaction(&reloaded, &replay, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("already minted"), "{}", err);
+
+    // A claim for more than was locked is not under the attested root.
+    let inflated = claim_tx(&relayer, &claim("lock-2", &recipient, 7_000), header_proof(&claims, 1, 9), 1);
+    assert!(ExecutionLogic::execute_transaction(&state, &inflated, ExecutionContext::Production).is_err());
+    let second = claim_tx(&relayer, &claims[1], header_proof(&claims, 1, 9), 1);
+    ExecutionLogic::execute_transaction(&state, &second, ExecutionContext::Production).unwrap();
+    assert_eq!(state.get_asset_balance(&recipient, &weth()), 570);
+
+    state.restore(&snapshot);
+    assert!(!state.asset_registry().bridge().is_claimed("eth", "lock-1"));
+    assert_eq!(state.get_asset_balance(&recipient, &weth()), 0);
+}
+
+#[test]
+fn only_wrapped_assets_of_the_claimed_chain_are_minted() {
+    let mut rng = DeterministicRng::new(52);
+    let relayer = rng.account();
+    let recipient = rng.account().address;
+    let state = State::new();
+    state.set_balance(&relayer.address, 100);
+    let (registry, validators) = validators(&mut rng);
+    state.register_token(&weth(), &relayer.address).unwrap();
+
+    let claims = vec![claim("lock-1", &recipient, 5)];
+    let proof = header_proof(&claims, 0, 1);
+    attest_foreign_header(&state, &registry, "eth", 1, &proof.claims_root, &attest(&validators, 1, &proof.claims_root))
+        .unwrap();
+    let conflicting = hex::encode([7u8; 32]);
+    let approvals = attest(&validators, 1, &conflicting);
+    assert!(attest_foreign_header(&state, &registry, "eth", 1, &conflicting, &approvals).is_err());
+
+    // Issued by the relayer, not the bridge: not mintable by claims.
+    let tx = claim_tx(&relayer, &claims[0], proof, 0);
+    let err = ExecutionLogic::execute_transaction(&state, &tx, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("not a wrapped asset"), "{}", err);
+    assert_eq!(state.get_nonce(&relayer.address), 0);
+
+    let json = serde_json::to_string(&tx).unwrap();
+    assert_eq!(serde_json::from_str::<Transaction>(&json).unwrap(), tx);
+}
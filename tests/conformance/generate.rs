@@ -0,0 +1,204 @@
+//! Deterministic generation of the conformance vectors from this crate.
+//!
+//! Keys are fixed secret scalars and ECDSA signing is deterministic (RFC 6979), so the same crate
+//! version always produces byte-identical vectors.
+
+use std::collections::HashSet;
+use platarium_core::*;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use serde_json::{json, Value};
+
+use crate::run;
+
+struct Keys {
+    main: SecretKey,
+    derived: SecretKey,
+}
+
+impl Keys {
+    fn new(main: u8, derived: u8) -> Self {
+        Self {
+            main: SecretKey::from_slice(&[main; 32]).unwrap(),
+            derived: SecretKey::from_slice(&[derived; 32]).unwrap(),
+        }
+    }
+
+    fn pub_hex(sk: &SecretKey) -> String {
+        hex::encode(PublicKey::from_secret_key(&Secp256k1::new(), sk).serialize())
+    }
+
+    fn address(&self) -> String {
+        format!("Px{}", Self::pub_hex(&self.main))
+    }
+}
+
+fn sign(sk: &SecretKey, hash: &str) -> String {
+    let msg = Message::from_digest_slice(&hex::decode(hash).unwrap()).unwrap();
+    hex::encode(Secp256k1::new().sign_ecdsa(&msg, sk).serialize_compact())
+}
+
+fn transfer(keys: &Keys, to: &str, amount: u128, fee_uplp: u128, nonce: u64) -> Transaction {
+    Transaction::new(
+        keys.address(),
+        to.to_string(),
+        Asset::PLP,
+        amount,
+        fee_uplp,
+        nonce,
+        HashSet::new(),
+        HashSet::new(),
+        String::new(),
+        String::new(),
+    )
+    .unwrap()
+}
+
+fn signed(mut tx: Transaction, keys: &Keys) -> Transaction {
+    tx.pub_main = Some(Keys::pub_hex(&keys.main));
+    tx.pub_derived = Some(Keys::pub_hex(&keys.derived));
+    tx.sig_main = sign(&keys.main, &tx.hash);
+    tx.sig_derived = sign(&keys.derived, &tx.hash);
+    tx
+}
+
+fn tx_case(name: &str, description: &str, pre_state: Value, tx: Transaction) -> Value {
+    let mut case = json!({
+        "name": name,
+        "description": description,
+        "pre_state": pre_state,
+        "tx": serde_json::to_value(&tx).unwrap(),
+    });
+    case["expected"] = run::run_transaction_case(&case);
+    case
+}
+
+fn funded(address: &str, plp: u128, uplp: u128) -> Value {
+    json!({"balances": [{"address": address, "plp": plp.to_string(), "uplp": uplp.to_string()}]})
+}
+
+fn transactions() -> Vec<Value> {
+    let alice = Keys::new(0x11, 0x12);
+    let bob = Keys::new(0x21, 0x22);
+    let rich = funded(&alice.address(), 1_000, 100);
+    let mut cases = vec![
+        tx_case(
+            "transfer_valid",
+            "Signed PLP transfer from a funded account with the expected nonce.",
+            rich.clone(),
+            signed(transfer(&alice, &bob.address(), 250, 1, 0), &alice),
+        ),
+        tx_case(
+            "transfer_insufficient_balance",
+            "Well-formed transfer that exceeds the sender's PLP balance: passes stateless checks, fails L1 verification.",
+            funded(&alice.address(), 10, 100),
+            signed(transfer(&alice, &bob.address(), 250, 1, 0), &alice),
+        ),
+        tx_case(
+            "transfer_wrong_nonce",
+            "Nonce ahead of the account nonce.",
+            rich.clone(),
+            signed(transfer(&alice, &bob.address(), 5, 1, 3), &alice),
+        ),
+        tx_case(
+            "fee_below_minimum",
+            "Zero fee is rejected before signatures are checked.",
+            rich.clone(),
+            signed(transfer(&alice, &bob.address(), 5, 0, 0), &alice),
+        ),
+        tx_case(
+            "zero_amount_transfer",
+            "Transfers must move a positive amount.",
+            rich.clone(),
+            signed(transfer(&alice, &bob.address(), 0, 1, 0), &alice),
+        ),
+        tx_case(
+            "signed_by_other_account",
+            "Valid signatures by another account's keys, naming that account's main key; the main key does not control the sender.",
+            rich.clone(),
+            signed(transfer(&alice, &bob.address(), 5, 1, 0), &bob),
+        ),
+        tx_case(
+            "same_main_and_derived_key",
+            "The derived key must differ from the main key.",
+            rich.clone(),
+            signed(transfer(&alice, &bob.address(), 5, 1, 0), &Keys::new(0x11, 0x11)),
+        ),
+    ];
+
+    let mut tampered = signed(transfer(&alice, &bob.address(), 5, 1, 0), &alice);
+    tampered.amount = 500;
+    tampered.hash = tampered.compute_hash().unwrap();
+    cases.push(tx_case(
+        "amount_changed_after_signing",
+        "Amount altered and hash recomputed after signing; signatures no longer match.",
+        rich.clone(),
+        tampered,
+    ));
+
+    let mut bad_hash = signed(transfer(&alice, &bob.address(), 5, 1, 0), &alice);
+    bad_hash.hash = "00".repeat(32);
+    cases.push(tx_case(
+        "declared_hash_mismatch",
+        "Declared hash differs from the canonical hash of the transaction data.",
+        rich,
+        bad_hash,
+    ));
+    cases
+}
+
+fn blocks() -> Vec<Value> {
+    let state = json!({"balances": [
+        {"address": "PxA", "plp": "90", "uplp": "9"},
+        {"address": "PxB", "plp": "10", "uplp": "0"},
+    ]});
+    let case = |name: &str, tx_hashes: Vec<String>, pre_state: Value| {
+        let mut case = json!({
+            "name": name,
+            "block_number": 7,
+            "previous_hash": "ab".repeat(32),
+            "timestamp": 1_700_000_000,
+            "producer_id": "node-1",
+            "tx_hashes": tx_hashes,
+            "pre_state": pre_state,
+        });
+        case["expected"] = run::run_block_case(&case);
+        case
+    };
+    let hash = |i: u8| hex::encode([i; 32]);
+    vec![
+        case("empty_block_empty_state", vec![], json!({"balances": []})),
+        case("single_tx", vec![hash(1)], state.clone()),
+        case("odd_tx_count", vec![hash(1), hash(2), hash(3)], state.clone()),
+        case("tx_order_independent_root", vec![hash(3), hash(2), hash(1)], state),
+    ]
+}
+
+fn votes() -> Vec<Value> {
+    let case = |name: &str, layer: &str, votes: &[(&str, &str)]| {
+        let votes: Vec<Value> = votes.iter().map(|(n, v)| json!({"node_id": n, "vote": v})).collect();
+        let mut case = json!({"name": name, "layer": layer, "votes": votes});
+        case["expected"] = run::run_vote_case(&case);
+        case
+    };
+    let split = [("n1", "confirm"), ("n2", "confirm"), ("n3", "reject")];
+    let strong = [("n1", "confirm"), ("n2", "confirm"), ("n3", "confirm"), ("n4", "reject")];
+    vec![
+        case("l1_unanimous", "l1", &[("n1", "confirm"), ("n2", "confirm")]),
+        case("l1_two_of_three_below_threshold", "l1", &split),
+        case("l1_majority_reject", "l1", &[("n1", "reject"), ("n2", "reject"), ("n3", "confirm")]),
+        case("l1_no_votes", "l1", &[]),
+        case("l2_two_of_three_below_threshold", "l2", &split),
+        case("l2_three_quarters", "l2", &strong),
+        case("l2_tie", "l2", &[("n1", "confirm"), ("n2", "reject")]),
+    ]
+}
+
+/// The full suite as written to `vectors.json`.
+pub fn generate() -> Value {
+    json!({
+        "format_version": 1,
+        "transactions": transactions(),
+        "blocks": blocks(),
+        "votes": votes(),
+    })
+}
@@ -0,0 +1,69 @@
+//! Protocol conformance suite.
+//!
+//! `vectors.json` holds serialized transactions, blocks and vote sets with the outcomes this crate
+//! produces for them. Alternative implementations can run the same file: every case carries its
+//! inputs and an `expected` object (see `README.md` in this directory for the format).
+//!
+//! The vectors are generated deterministically by `generate.rs`. After an intentional protocol change,
+//! regenerate with `PLATARIUM_UPDATE_CONFORMANCE=1 cargo test --test conformance` and review the diff.
+//! Releases are gated on `cargo test --test conformance` passing.
+
+mod generate;
+mod run;
+
+use std::path::PathBuf;
+use serde_json::Value;
+
+fn vectors_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/conformance/vectors.json")
+}
+
+fn load_vectors() -> Value {
+    let text = std::fs::read_to_string(vectors_path()).expect("tests/conformance/vectors.json is readable");
+    serde_json::from_str(&text).expect("vectors.json is valid JSON")
+}
+
+fn check(section: &str, runner: fn(&Value) -> Value) {
+    let vectors = load_vectors();
+    let cases = vectors[section].as_array().expect("section is an array");
+    assert!(!cases.is_empty(), "no {} vectors", section);
+    let failures: Vec<String> = cases
+        .iter()
+        .filter_map(|case| {
+            let got = runner(case);
+            (got != case["expected"]).then(|| {
+                format!("{}: expected {} got {}", case["name"], case["expected"], got)
+            })
+        })
+        .collect();
+    assert!(failures.is_empty(), "{} conformance failures:\n{}", section, failures.join("\n"));
+}
+
+#[test]
+fn vectors_match_generator() {
+    let generated = serde_json::to_string_pretty(&generate::generate()).unwrap() + "\n";
+    if std::env::var_os("PLATARIUM_UPDATE_CONFORMANCE").is_some() {
+        std::fs::write(vectors_path(), &generated).unwrap();
+        return;
+    }
+    let committed = std::fs::read_to_string(vectors_path()).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "tests/conformance/vectors.json is stale; rerun with PLATARIUM_UPDATE_CONFORMANCE=1 and review the diff"
+    );
+}
+
+#[test]
+fn transaction_vectors() {
+    check("transactions", run::run_transaction_case);
+}
+
+#[test]
+fn block_vectors() {
+    check("blocks", run::run_block_case);
+}
+
+#[test]
+fn vote_vectors() {
+    check("votes", run::run_vote_case);
+}
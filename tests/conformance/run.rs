@@ -0,0 +1,100 @@
+//! Evaluates one conformance case against this crate and returns its observed outcome.
+//!
+//! Outcomes use stable names (error variant names, `confirmed`/`rejected`) so other implementations
+//! can compare without matching this crate's error messages.
+
+use platarium_core::*;
+use platarium_core::core::transaction::TransactionValidationError;
+use serde_json::{json, Value};
+
+fn load_state(pre_state: &Value) -> State {
+    let state = State::new();
+    for b in pre_state["balances"].as_array().into_iter().flatten() {
+        let address = b["address"].as_str().unwrap().to_string();
+        state.set_balance(&address, b["plp"].as_str().unwrap().parse().unwrap());
+        state.set_uplp_balance(&address, b["uplp"].as_str().unwrap().parse().unwrap());
+    }
+    state
+}
+
+/// Variant name of a validation error, e.g. `InvalidFee`.
+fn error_code(err: &TransactionValidationError) -> String {
+    let debug = format!("{:?}", err);
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+pub fn run_transaction_case(case: &Value) -> Value {
+    let tx = match Transaction::from_gateway_json(&case["tx"].to_string()) {
+        Ok(tx) => tx,
+        Err(_) => return json!({"decodes": false}),
+    };
+    let canonical_hash = tx.compute_hash().unwrap();
+    let hash_matches = canonical_hash == tx.hash;
+    let basic = match tx.validate_basic() {
+        Ok(()) => "ok".to_string(),
+        Err(e) => error_code(&e),
+    };
+    let l1_valid = hash_matches && verify_tx_for_l1(&load_state(&case["pre_state"]), &tx).unwrap();
+    json!({
+        "decodes": true,
+        "canonical_hash": canonical_hash,
+        "hash_matches": hash_matches,
+        "basic_validation": basic,
+        "l1_valid": l1_valid,
+    })
+}
+
+pub fn run_block_case(case: &Value) -> Value {
+    let tx_hashes: Vec<String> = case["tx_hashes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|h| h.as_str().unwrap().to_string())
+        .collect();
+    let snapshot = load_state(&case["pre_state"]).snapshot();
+    let block = assemble_block(
+        case["block_number"].as_u64().unwrap(),
+        case["previous_hash"].as_str().unwrap().to_string(),
+        case["timestamp"].as_i64().unwrap(),
+        tx_hashes,
+        &snapshot,
+        case["producer_id"].as_str().unwrap().to_string(),
+        String::new(),
+    );
+    json!({
+        "merkle_root": block.merkle_root,
+        "state_root": block.state_root,
+        "block_hash": block.block_hash,
+    })
+}
+
+pub fn run_vote_case(case: &Value) -> Value {
+    let votes: Vec<(String, Vote)> = case["votes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            let vote = match v["vote"].as_str().unwrap() {
+                "confirm" => Vote::Confirm,
+                _ => Vote::Reject,
+            };
+            (v["node_id"].as_str().unwrap().to_string(), vote)
+        })
+        .collect();
+    let outcome = match case["layer"].as_str().unwrap() {
+        "l1" => process_l1_confirmation(&votes)
+            .map(|(r, p)| (r == ConfirmationResult::Confirmed, p)),
+        _ => process_l2_block_votes(&votes).map(|(r, p)| (block_finalized(r), p)),
+    };
+    match outcome {
+        Ok((confirmed, to_penalize)) => json!({
+            "result": if confirmed { "confirmed" } else { "rejected" },
+            "to_penalize": to_penalize,
+        }),
+        Err(_) => json!({"result": "error"}),
+    }
+}
@@ -0,0 +1,160 @@
+//! Verification matrix: transactions signed for one deployment (chainspec salt) never validate on another,
+//! even when every deployment shares the same key material.
+
+use platarium_core::core::transaction::TransactionValidationError;
+use platarium_core::*;
+use std::collections::HashSet;
+
+const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const ALPHANUMERIC: &str = "test123";
+
+/// Mirrors the canonical transaction hash data, including the optional deployment salt.
+#[derive(serde::Serialize)]
+struct TxHashData {
+    from: String,
+    to: String,
+    asset: String,
+    amount: u128,
+    fee_uplp: u128,
+    nonce: u64,
+    reads: Vec<String>,
+    writes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deployment_salt: Option<String>,
+}
+
+fn signed_tx(salt: Option<&str>) -> Transaction {
+    let tx = Transaction::new(
+        "sender".to_string(),
+        "receiver".to_string(),
+        Asset::PLP,
+        1_000,
+        10,
+        0,
+        HashSet::new(),
+        HashSet::new(),
+        String::new(),
+        String::new(),
+    )
+    .unwrap()
+    .with_deployment_salt(salt.map(String::from))
+    .unwrap();
+    let message = TxHashData {
+        from: tx.from.clone(),
+        to: tx.to.clone(),
+        asset: tx.asset.as_canonical(),
+        amount: tx.amount,
+        fee_uplp: tx.fee_uplp,
+        nonce: tx.nonce,
+        reads: vec![],
+        writes: vec![],
+        deployment_salt: tx.deployment_salt.clone(),
+    };
+    let sig = sign_with_both_keys(&message, MNEMONIC, ALPHANUMERIC).unwrap();
+    assert_eq!(sig.hash, tx.hash, "test hash data must match Transaction::compute_hash");
+    let mut tx = tx;
+    tx.sig_main = normalize_signature_hex(&sig.signatures[0].signature_compact);
+    tx.sig_derived = normalize_signature_hex(&sig.signatures[1].signature_compact);
+    tx.pub_main = Some(sig.signatures[0].pub_key.clone());
+    tx.pub_derived = Some(sig.signatures[1].pub_key.clone());
+    tx
+}
+
+fn deployments() -> Vec<ChainSpec> {
+    vec![
+        ChainSpec::new("mainnet", None),
+        ChainSpec::new("staging", Some("staging-2026")),
+        ChainSpec::new("testnet", Some("testnet-7")),
+    ]
+}
+
+#[test]
+fn transactions_validate_only_on_their_own_deployment() {
+    let specs = deployments();
+    for signed_for in &specs {
+        let tx = signed_tx(signed_for.deployment_salt.as_deref());
+        for validated_on in &specs {
+            let result = validated_on.check_transaction(&tx);
+            if signed_for == validated_on {
+                assert_eq!(result, Ok(()), "{} -> {}", signed_for.name, validated_on.name);
+            } else {
+                assert!(
+                    matches!(result, Err(TransactionValidationError::DeploymentMismatch { .. })),
+                    "{} -> {}: {:?}",
+                    signed_for.name,
+                    validated_on.name,
+                    result
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn relabelled_salt_breaks_signatures() {
+    let specs = deployments();
+    for signed_for in &specs {
+        let tx = signed_tx(signed_for.deployment_salt.as_deref());
+        for target in specs.iter().filter(|s| *s != signed_for) {
+            let mut forged = tx.clone();
+            forged.deployment_salt = target.deployment_salt.clone();
+            forged.hash = forged.compute_hash().unwrap();
+            assert!(
+                matches!(
+                    target.check_transaction(&forged),
+                    Err(TransactionValidationError::InvalidSignature(_))
+                ),
+                "{} relabelled for {} must not verify",
+                signed_for.name,
+                target.name
+            );
+        }
+    }
+}
+
+#[test]
+fn state_and_core_accept_only_their_deployment() {
+    let mut rng = platarium_core::testing::DeterministicRng::new(3976);
+    let (sender, receiver) = (rng.account(), rng.account());
+    let transfer = |salt: Option<&str>| {
+        let tx = sender.transfer(&receiver.address, 10, 1, 0);
+        sender.sign(tx.with_deployment_salt(salt.map(String::from)).unwrap())
+    };
+    for spec in deployments() {
+        let core = Core::new();
+        core.state().set_chain_spec(spec.clone());
+        core.state().set_balance(&sender.address, 100);
+        core.state().set_uplp_balance(&sender.address, 10);
+        for other in deployments().into_iter().filter(|s| *s != spec) {
+            let foreign = transfer(other.deployment_salt.as_deref());
+            assert!(core.submit_transaction(foreign.clone()).is_err(), "{} on {}", other.name, spec.name);
+            assert!(core.state().apply_transaction(&foreign).is_err(), "{} on {}", other.name, spec.name);
+        }
+        let own = transfer(spec.deployment_salt.as_deref());
+        core.submit_transaction(own.clone()).unwrap();
+        core.state().apply_transaction(&own).unwrap();
+        assert_eq!(core.state().get_balance(&receiver.address), 10);
+    }
+}
+
+#[test]
+fn block_execution_and_l1_check_the_chain_id() {
+    use platarium_core::core::confirmation_layer::verify_tx_for_l1;
+    use platarium_core::core::execution::ExecutionLogic;
+
+    let mut rng = platarium_core::testing::DeterministicRng::new(4045);
+    let (sender, receiver) = (rng.account(), rng.account());
+    let testnet_id = NetworkId::Testnet.chain_id().to_string();
+    let tx = sender.sign(sender.transfer(&receiver.address, 10, 1, 0).with_chain_id(Some(testnet_id)).unwrap());
+    for network in [NetworkId::Mainnet, NetworkId::Testnet] {
+        let state = State::new();
+        state.set_chain_spec(ChainSpec { network: network.clone(), ..ChainSpec::default() });
+        state.set_balance(&sender.address, 100);
+        state.set_uplp_balance(&sender.address, 10);
+        let accepted = network == NetworkId::Testnet;
+        assert_eq!(verify_tx_for_l1(&state, &tx).unwrap(), accepted, "{:?}", network);
+        let executed = ExecutionLogic::execute_block(&state, std::slice::from_ref(&tx), 1).unwrap();
+        assert_eq!(executed.receipts[0].success, accepted, "{:?}", network);
+        assert_eq!(state.get_balance(&receiver.address), if accepted { 10 } else { 0 });
+    }
+}
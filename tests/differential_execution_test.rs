@@ -0,0 +1,109 @@
+//! Differential test: random transfer batches run through `ExecutionLogic` and through the naive
+//! reference executor in `tests/reference_executor`; both must accept the same transactions and end
+//! at the same state roots. Guards optimized execution paths against drifting from the plain rules.
+
+mod reference_executor;
+
+use platarium_core::testing::{DeterministicRng, TestAccount, TEST_SEED_ENV};
+use platarium_core::*;
+use rand::Rng;
+use reference_executor::{RefTransfer, ReferenceExecutor};
+
+const SEEDS: [u64; 6] = [1, 2, 3, 42, 0xdead_beef, 20_240_601];
+const ACCOUNTS: usize = 5;
+const BATCH_LEN: usize = 120;
+
+/// Mostly valid transfers (correct nonce, affordable amount), with some overspends, stale or future
+/// nonces and self-transfers mixed in.
+fn random_transfer(
+    rng: &mut DeterministicRng,
+    accounts: &[TestAccount],
+    reference: &ReferenceExecutor,
+) -> (usize, RefTransfer) {
+    let from = rng.gen_range(0..accounts.len());
+    let to = rng.gen_range(0..accounts.len());
+    let address = &accounts[from].address;
+    let balance = reference.plp.get(address).copied().unwrap_or(0);
+    let mut nonce = reference.nonces.get(address).copied().unwrap_or(0);
+    let amount = match rng.gen_range(0..10) {
+        0 => balance + rng.gen_range(1..100),
+        _ => rng.gen_range(1..=balance.max(1) / 2 + 1),
+    };
+    match rng.gen_range(0..20) {
+        0 => nonce += 1,
+        1 if nonce > 0 => nonce -= 1,
+        _ => {}
+    }
+    let transfer = RefTransfer {
+        from: address.clone(),
+        to: accounts[to].address.clone(),
+        amount,
+        fee_uplp: rng.gen_range(1..=30),
+        nonce,
+    };
+    (from, transfer)
+}
+
+fn run_seed(seed: u64) {
+    let mut rng = DeterministicRng::new(seed);
+    let accounts = rng.accounts(ACCOUNTS);
+    let state = State::new();
+    let mut reference = ReferenceExecutor::default();
+    for account in &accounts {
+        let plp = rng.gen_range(0..5_000u128);
+        let uplp = rng.gen_range(0..200u128);
+        state.set_balance(&account.address, plp);
+        state.set_uplp_balance(&account.address, uplp);
+        reference.fund(&account.address, plp, uplp);
+    }
+
+    let mut accepted = 0;
+    for i in 0..BATCH_LEN {
+        let (from, transfer) = random_transfer(&mut rng, &accounts, &reference);
+        let tx = accounts[from].transfer(&transfer.to, transfer.amount, transfer.fee_uplp, transfer.nonce);
+        let fast = ExecutionLogic::execute_transaction(&state, &tx, ExecutionContext::Production);
+        let slow = reference.execute(&transfer);
+        assert_eq!(
+            fast.is_ok(),
+            slow.is_ok(),
+            "seed {} tx {}: {:?}: executor {:?}, reference {:?}",
+            seed, i, transfer, fast, slow
+        );
+        accepted += usize::from(slow.is_ok());
+    }
+    assert!(accepted > BATCH_LEN / 2, "seed {}: only {} of {} transfers valid", seed, accepted, BATCH_LEN);
+
+    for account in &accounts {
+        let address = &account.address;
+        assert_eq!(state.get_balance(address), reference.plp[address], "seed {}: PLP of {}", seed, address);
+        assert_eq!(
+            state.get_uplp_balance(address),
+            reference.uplp[address],
+            "seed {}: μPLP of {}",
+            seed,
+            address
+        );
+    }
+    assert_eq!(
+        state.get_uplp_balance(&TREASURY_ADDRESS.to_string()),
+        reference.uplp.get(TREASURY_ADDRESS).copied().unwrap_or(0),
+        "seed {}: treasury",
+        seed
+    );
+    assert_eq!(state.snapshot().compute_state_root(), reference.snapshot_root(), "seed {}: snapshot root", seed);
+    let registries = state.registries_digest();
+    assert_eq!(state.current_root(), reference.commitment_root(&registries), "seed {}: commitment root", seed);
+    assert_eq!(state.recompute_root(), reference.commitment_root(&registries), "seed {}: rebuilt root", seed);
+}
+
+/// Runs the fixed seeds, or only `PLATARIUM_TEST_SEED` when replaying a failure.
+#[test]
+fn executor_matches_reference_on_random_batches() {
+    let seeds = match std::env::var(TEST_SEED_ENV) {
+        Ok(seed) => vec![seed.trim().parse().expect("PLATARIUM_TEST_SEED is a u64")],
+        Err(_) => SEEDS.to_vec(),
+    };
+    for seed in seeds {
+        run_seed(seed);
+    }
+}
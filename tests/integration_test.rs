@@ -102,7 +102,7 @@ fn test_key_correlation_verification() {
 #[test]
 fn test_custom_derivation_path() {
     let custom_path = "m/44'/60'/1'/0/0";
-    let key_gen = KeyGenerator::new(0, None, None, Some(custom_path.to_string())).unwrap();
+    let key_gen = KeyGenerator::new(0, None, None, Some(custom_path.parse().unwrap())).unwrap();
     let keys = key_gen.generate_keys().unwrap();
     
     assert_eq!(keys.derivation_paths.main_path, custom_path);
@@ -0,0 +1,155 @@
+//! Multisig accounts end to end: an account converts itself to 2-of-3, then moves funds only with a witness
+//! carrying two signatures from its registered signer set.
+
+use platarium_core::core::execution::{ExecutionContext, ExecutionLogic};
+use platarium_core::testing::{DeterministicRng, TestAccount};
+use platarium_core::*;
+use secp256k1::SecretKey;
+use std::collections::HashSet;
+
+fn tx(from: &TestAccount, to: &str, amount: u128, nonce: u64, kind: TxKind) -> Transaction {
+    Transaction::new(
+        from.address.clone(),
+        to.to_string(),
+        Asset::PLP,
+        amount,
+        1,
+        nonce,
+        HashSet::new(),
+        HashSet::new(),
+        String::new(),
+        String::new(),
+    )
+    .unwrap()
+    .with_kind(kind)
+    .unwrap()
+}
+
+fn key_hex(sk: &SecretKey) -> String {
+    hex::encode(secp256k1::PublicKey::from_secret_key(&secp256k1::Secp256k1::new(), sk).serialize())
+}
+
+fn multisig_transfer(
+    account: &TestAccount,
+    signers: &[SecretKey],
+    threshold: u32,
+    signing: &[usize],
+    to: &str,
+    nonce: u64,
+) -> Transaction {
+    let mut t = tx(account, to, 10, nonce, TxKind::Transfer);
+    t.multisig = Some(MultisigWitness::new(signers.iter().map(key_hex).collect(), threshold).unwrap());
+    for &i in signing {
+        t.sign_multisig(&signer::SoftwareSigner::new(signers[i])).unwrap();
+    }
+    t
+}
+
+#[test]
+fn two_of_three_account_needs_two_signatures() {
+    let mut rng = DeterministicRng::new(41);
+    let account = rng.account();
+    let signers: Vec<SecretKey> = (0..3).map(|_| rng.secret_key()).collect();
+    let state = State::new();
+    state.set_balance(&account.address, 1_000);
+    let to = rng.account().address;
+
+    let setup = account.sign(tx(
+        &account,
+        &account.address,
+        0,
+        0,
+        TxKind::SetMultisig {
+            signers: signers.iter().map(|k| format!("Px{}", key_hex(k))).collect(),
+            threshold: 2,
+        },
+    ));
+    ExecutionLogic::execute_transaction(&state, &setup, ExecutionContext::Production).unwrap();
+    assert!(matches!(state.account_kind(&account.address), AccountKind::Multisig { threshold: 2, .. }));
+
+    // The original key pair alone no longer authorizes the account.
+    let single = account.sign(tx(&account, &to, 10, 1, TxKind::Transfer));
+    let err = ExecutionLogic::execute_transaction(&state, &single, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("needs a multisig witness"), "{}", err);
+
+    let one = multisig_transfer(&account, &signers, 2, &[2], &to, 1);
+    assert!(ExecutionLogic::validate_transaction(&one).is_err());
+
+    let two = multisig_transfer(&account, &signers, 2, &[0, 2], &to, 1);
+    ExecutionLogic::execute_transaction(&state, &two, ExecutionContext::Production).unwrap();
+    assert_eq!(state.get_balance(&to), 10);
+    assert_eq!(state.get_nonce(&account.address), 2);
+
+    // A witness for a different set is rejected even if its signatures verify.
+    let others: Vec<SecretKey> = (0..3).map(|_| rng.secret_key()).collect();
+    let foreign = multisig_transfer(&account, &others, 2, &[0, 1], &to, 2);
+    assert!(ExecutionLogic::validate_transaction(&foreign).is_ok());
+    let err = ExecutionLogic::execute_transaction(&state, &foreign, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("does not match"), "{}", err);
+
+    let json = serde_json::to_string(&two).unwrap();
+    assert_eq!(Transaction::from_gateway_json(&json).unwrap(), two);
+    let reloaded = core::state_file::StateFileData::from_state(&state).into_state().unwrap();
+    assert_eq!(reloaded.account_kind(&account.address), state.account_kind(&account.address));
+}
+
+#[test]
+fn conversion_needs_the_accounts_own_main_key() {
+    let mut rng = DeterministicRng::new(43);
+    let account = rng.account();
+    let attacker = rng.account();
+    let state = State::new();
+    state.set_balance(&account.address, 1_000);
+    let signers: Vec<SecretKey> = (0..2).map(|_| rng.secret_key()).collect();
+    let kind = TxKind::SetMultisig {
+        signers: signers.iter().map(|k| format!("Px{}", key_hex(k))).collect(),
+        threshold: 2,
+    };
+
+    // Signed by another key pair that names its own keys: the signatures verify, the account is not converted.
+    let hijack = attacker.sign(tx(&account, &account.address, 0, 0, kind.clone()));
+    assert!(ExecutionLogic::validate_transaction(&hijack).is_ok());
+    let err = ExecutionLogic::execute_transaction(&state, &hijack, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("does not control"), "{}", err);
+    assert_eq!(state.account_kind(&account.address), AccountKind::Single);
+
+    let setup = account.sign(tx(&account, &account.address, 0, 0, kind));
+    ExecutionLogic::execute_transaction(&state, &setup, ExecutionContext::Production).unwrap();
+    assert!(matches!(state.account_kind(&account.address), AccountKind::Multisig { .. }));
+}
+
+#[test]
+fn signer_set_can_be_replaced_by_its_signers() {
+    let mut rng = DeterministicRng::new(42);
+    let account = rng.account();
+    let signers: Vec<SecretKey> = (0..2).map(|_| rng.secret_key()).collect();
+    let state = State::new();
+    state.set_balance(&account.address, 1_000);
+    let set = |keys: &[SecretKey], threshold| TxKind::SetMultisig {
+        signers: keys.iter().map(key_hex).collect(),
+        threshold,
+    };
+    let setup = account.sign(tx(&account, &account.address, 0, 0, set(&signers, 1)));
+    ExecutionLogic::execute_transaction(&state, &setup, ExecutionContext::Production).unwrap();
+
+    let replacement: Vec<SecretKey> = (0..3).map(|_| rng.secret_key()).collect();
+    let mut update = tx(&account, &account.address, 0, 1, set(&replacement, 3));
+    update.multisig = Some(MultisigWitness::new(signers.iter().map(key_hex).collect(), 1).unwrap());
+    update.sign_multisig(&signer::SoftwareSigner::new(signers[1])).unwrap();
+    ExecutionLogic::execute_transaction(&state, &update, ExecutionContext::Production).unwrap();
+    assert_eq!(
+        state.account_kind(&account.address),
+        AccountKind::Multisig {
+            signers: replacement.iter().map(key_hex).collect(),
+            threshold: 3,
+        }
+    );
+
+    let mut recovery = tx(&account, &account.address, 0, 2, TxKind::CancelRecovery);
+    recovery.multisig = Some(MultisigWitness::new(replacement.iter().map(key_hex).collect(), 3).unwrap());
+    for k in &replacement {
+        recovery.sign_multisig(&signer::SoftwareSigner::new(*k)).unwrap();
+    }
+    let err = ExecutionLogic::execute_transaction(&state, &recovery, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("not available to multisig"), "{}", err);
+}
@@ -0,0 +1,116 @@
+//! Outbound messages end to end: a `SendMessage` transaction emits a message, the producer seals it into the
+//! block's `messages_root`, storage keeps it, and the served proof verifies against the committed header.
+
+use platarium_core::core::block_assembly::assemble_block;
+use platarium_core::core::transaction::TransactionValidationError;
+use platarium_core::storage::rpc::rocks_message_proof_json;
+use platarium_core::storage::{commit_block, get_block, BlockCommit, BlockRecordStored, RocksStore};
+use platarium_core::*;
+use std::collections::HashSet;
+use tempfile::TempDir;
+
+/// Key address of the secret key 1 (the secp256k1 generator).
+const USER: &str = "Px0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+fn send_message(destination: &str, payload: &str, nonce: u64) -> Transaction {
+    Transaction::new(
+        USER.to_string(),
+        USER.to_string(),
+        Asset::PLP,
+        0,
+        1,
+        nonce,
+        HashSet::new(),
+        HashSet::new(),
+        String::new(),
+        String::new(),
+    )
+    .unwrap()
+    .with_kind(TxKind::SendMessage {
+        destination: destination.to_string(),
+        payload: payload.to_string(),
+    })
+    .unwrap()
+}
+
+#[test]
+fn send_message_is_committed_and_provable() {
+    let state = State::new();
+    state.set_balance(&USER.to_string(), 100);
+    state.apply_effects(&send_message("eth:bridge", "CAFE", 0)).unwrap();
+    state.apply_effects(&send_message("eth:bridge", "01", 1)).unwrap();
+    assert_eq!(state.get_nonce(&USER.to_string()), 2);
+
+    let mut queue = OutboundQueue::new();
+    queue.push_events(&state.take_events()).unwrap();
+    let messages = queue.seal(1);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].payload, "cafe");
+
+    let block = assemble_block(1, "0".into(), 1, vec![], &state.snapshot(), "n1".into(), String::new())
+        .with_messages(&messages);
+    let record = BlockRecordStored {
+        height: 1,
+        previous_hash: block.previous_hash.clone(),
+        timestamp: block.timestamp,
+        tx_hashes: vec![],
+        merkle_root: block.merkle_root.clone(),
+        state_root: block.state_root.clone(),
+        block_hash: block.block_hash.clone(),
+        producer_id: block.producer_id.clone(),
+        address_bloom: None,
+        messages_root: block.messages_root.clone(),
+        bytes_used: None,
+    };
+    let commit = BlockCommit {
+        block: record,
+        tx_jsons: vec![],
+        accounts: vec![],
+        receipts: vec![],
+        state_root: block.state_root.clone(),
+        slashing_events: Vec::new(),
+        fee_bucket: None,
+        messages: messages.clone(),
+    };
+
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("db");
+    {
+        let store = RocksStore::open(&db_path).unwrap();
+        // The header must commit to exactly the stored messages.
+        let mut dropped = commit.clone();
+        dropped.messages.pop();
+        assert!(commit_block(&store, &dropped).is_err());
+        commit_block(&store, &commit).unwrap();
+        assert_eq!(get_block(&store, 1).unwrap().unwrap().messages_root, block.messages_root);
+    }
+
+    let response: serde_json::Value =
+        serde_json::from_str(&rocks_message_proof_json(db_path.to_str().unwrap(), 1, 1).unwrap()).unwrap();
+    assert_eq!(response["found"], true);
+    let proof: MessageProof = serde_json::from_value(response["proof"].clone()).unwrap();
+    assert_eq!(proof.message, messages[1]);
+    assert!(proof.verify(block.messages_root.as_deref().unwrap()));
+
+    let missing: serde_json::Value =
+        serde_json::from_str(&rocks_message_proof_json(db_path.to_str().unwrap(), 1, 2).unwrap()).unwrap();
+    assert_eq!(missing["found"], false);
+}
+
+#[test]
+fn invalid_messages_fail_basic_validation() {
+    let message_error = |destination: &str, payload: &str| {
+        matches!(
+            send_message(destination, payload, 0).validate_basic(),
+            Err(TransactionValidationError::InvalidMessage(_))
+        )
+    };
+    // Well-formed but unsigned: rejected only at the signature check.
+    assert!(matches!(
+        send_message("eth:bridge", "00", 0).validate_basic(),
+        Err(TransactionValidationError::InvalidSignature(_))
+    ));
+    assert!(message_error("", "00"));
+    assert!(message_error("eth:bridge", "zz"));
+    assert!(message_error("eth:bridge", &"00".repeat(MAX_MESSAGE_PAYLOAD_BYTES + 1)));
+}
@@ -0,0 +1,65 @@
+//! Pins the stable surface in `compat::v1`. A failure here means a breaking change to an API that
+//! downstream nodes rely on: bump the major version (and `API_VERSION`) instead of editing the test.
+
+use platarium_core::compat::{self, v1};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+#[test]
+fn api_version_and_item_list() {
+    assert_eq!(compat::API_VERSION, (2, 0));
+    assert_eq!(compat::V1_ITEMS.len(), 25);
+    let unique: HashSet<&str> = compat::V1_ITEMS.iter().copied().collect();
+    assert_eq!(unique.len(), compat::V1_ITEMS.len());
+}
+
+#[test]
+fn transaction_signatures_are_stable() {
+    #[allow(clippy::type_complexity)]
+    let _new: fn(
+        String,
+        String,
+        v1::Asset,
+        u128,
+        u128,
+        u64,
+        HashSet<String>,
+        HashSet<String>,
+        String,
+        String,
+    ) -> v1::Result<v1::Transaction> = v1::Transaction::new;
+    let _hash: fn(&v1::Transaction) -> v1::Result<String> = v1::Transaction::compute_hash;
+    let _verify: fn(&v1::Transaction) -> v1::Result<bool> = v1::Transaction::verify_signatures;
+    let _basic: fn(&v1::Transaction) -> Result<(), v1::TransactionValidationError> =
+        v1::Transaction::validate_basic;
+    assert_eq!(v1::MIN_FEE_UPLP, 1);
+}
+
+#[test]
+fn state_and_execution_signatures_are_stable() {
+    let _balance: fn(&v1::State, &v1::Address) -> u128 = v1::State::get_balance;
+    let _nonce: fn(&v1::State, &v1::Address) -> u64 = v1::State::get_nonce;
+    let _root: fn(&v1::StateSnapshot) -> String = v1::StateSnapshot::compute_state_root;
+    let _snap: fn(&v1::State) -> v1::StateSnapshot = <v1::State as v1::SnapshotableState>::snapshot;
+    let _restore: fn(&v1::State, &v1::StateSnapshot) = v1::State::restore;
+    let _exec: fn(&v1::State, &v1::Transaction, v1::ExecutionContext) -> v1::Result<()> =
+        v1::ExecutionLogic::execute_transaction;
+    let _add: fn(&v1::Mempool, v1::Transaction) -> v1::Result<()> = v1::Mempool::add_transaction;
+    assert_eq!(v1::TREASURY_ADDRESS, "treasury");
+}
+
+#[test]
+fn block_signatures_are_stable() {
+    let _merkle: fn(&[String]) -> String = v1::compute_merkle_root;
+    let _assemble: fn(u64, String, i64, Vec<String>, &v1::StateSnapshot, String, String) -> v1::Block =
+        v1::assemble_block;
+    assert_eq!(v1::compute_merkle_root(&[]), "0");
+}
+
+#[test]
+fn ordering_signatures_are_stable() {
+    let _arrival: fn(&v1::Transaction, &v1::Transaction) -> Ordering = v1::by_fee_then_hash;
+    let mut ids = vec!["b".to_string(), "a".to_string()];
+    ids.sort_by(v1::by_node_id);
+    assert_eq!(ids, vec!["a", "b"]);
+}
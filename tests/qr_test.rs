@@ -0,0 +1,50 @@
+//! QR payloads end to end: a transaction signed on an offline device crosses to the online side as a
+//! `platarium-tx:` URI, and a scan that was damaged or edited on the way is refused.
+
+use platarium_core::testing::DeterministicRng;
+use platarium_core::*;
+use std::collections::HashSet;
+
+#[test]
+fn signed_transaction_survives_the_air_gap() {
+    let mut rng = DeterministicRng::new(91);
+    let signer = rng.account();
+    let payee = rng.account().address;
+    let request: PaymentRequest = PaymentRequest::new(&payee)
+        .unwrap()
+        .with_amount(750, Asset::PLP)
+        .to_uri()
+        .parse()
+        .unwrap();
+
+    let tx = signer.sign(
+        Transaction::new(
+            signer.address.clone(),
+            request.address.clone(),
+            request.asset.clone(),
+            request.amount.unwrap(),
+            1,
+            0,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap(),
+    );
+    let uri = signed_transaction_uri(&tx).unwrap();
+    assert!(uri.starts_with("platarium-tx:{"));
+    assert!(uri.len() <= MAX_QR_PAYLOAD_BYTES);
+    assert_eq!(parse_signed_transaction_uri(&uri).unwrap(), tx);
+
+    let edited = uri.replace("\"amount\":750", "\"amount\":7500");
+    assert_ne!(edited, uri);
+    assert!(matches!(parse_signed_transaction_uri(&edited), Err(QrError::InvalidTransaction(_))));
+    let mut unsigned = tx.clone();
+    unsigned.sig_main.clear();
+    assert!(signed_transaction_uri(&unsigned).is_err());
+    assert!(matches!(
+        parse_signed_transaction_uri(&request.to_uri()),
+        Err(QrError::WrongScheme { .. })
+    ));
+}
@@ -0,0 +1,104 @@
+//! Receipt queries by address over committed blocks, with cursor pagination.
+
+use platarium_core::*;
+use tempfile::TempDir;
+
+fn commit(store: &RocksStore, height: u64, txs: &[(&str, &str, &str)], fee_payer: Option<&str>) {
+    let hashes: Vec<String> = txs.iter().map(|(h, _, _)| h.to_string()).collect();
+    let commit = BlockCommit {
+        block: BlockRecordStored {
+            height,
+            previous_hash: "0".into(),
+            timestamp: height as i64,
+            tx_hashes: hashes.clone(),
+            merkle_root: "m".into(),
+            state_root: format!("root{}", height),
+            block_hash: format!("bh{}", height),
+            producer_id: "n1".into(),
+            address_bloom: None,
+            messages_root: None,
+            bytes_used: None,
+        },
+        tx_jsons: txs
+            .iter()
+            .map(|(h, from, to)| serde_json::json!({"hash": h, "from": from, "to": to}).to_string())
+            .collect(),
+        accounts: vec![],
+        receipts: hashes
+            .iter()
+            .map(|h| ReceiptRecord {
+                tx_hash: h.clone(),
+                status: "ok".into(),
+                fee_uplp: 1,
+                block_height: height,
+                rebate_uplp: 0,
+                events: Vec::new(),
+                fee_payer: fee_payer.map(str::to_string),
+                index: None,
+                error_code: None,
+                balance_deltas: Vec::new(),
+            })
+            .collect(),
+        state_root: format!("root{}", height),
+        slashing_events: Vec::new(),
+        fee_bucket: None,
+        messages: Vec::new(),
+    };
+    commit_block(store, &commit).unwrap();
+}
+
+fn hashes(page: &ReceiptPage) -> Vec<&str> {
+    page.receipts.iter().map(|r| r.tx_hash.as_str()).collect()
+}
+
+#[test]
+fn receipts_page_in_block_order() {
+    let dir = TempDir::new().unwrap();
+    let store = RocksStore::open(dir.path().join("db")).unwrap();
+    commit(&store, 1, &[("bb", "alice", "bob"), ("aa", "bob", "carol")], None);
+    commit(&store, 2, &[("cc", "carol", "alice")], None);
+    commit(&store, 3, &[("dd", "dave", "erin")], Some("alice"));
+    let core = Core::with_store(store);
+
+    let first = core.get_receipts_by_address("alice", None, 2).unwrap();
+    assert_eq!(hashes(&first), vec!["bb", "cc"]);
+    let cursor = first.next_cursor.clone().unwrap();
+    let second = core.get_receipts_by_address("alice", Some(&cursor), 2).unwrap();
+    assert_eq!(hashes(&second), vec!["dd"]);
+    assert_eq!(second.next_cursor, None);
+
+    // Same cursor, same page.
+    assert_eq!(core.get_receipts_by_address("alice", Some(&cursor), 2).unwrap(), second);
+
+    let bob = core.get_receipts_by_address("bob", None, 10).unwrap();
+    assert_eq!(hashes(&bob), vec!["aa", "bb"]);
+    assert!(core.get_receipts_by_address("bob", Some("nope"), 10).is_err());
+}
+
+#[test]
+fn receipts_page_over_rpc() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("db");
+    {
+        let store = RocksStore::open(&db_path).unwrap();
+        commit(&store, 1, &[("bb", "alice", "bob"), ("aa", "bob", "carol")], None);
+        commit(&store, 2, &[("dd", "dave", "erin")], Some("alice"));
+    }
+    let call = |params: serde_json::Value| -> serde_json::Value {
+        let out = platarium_core::core::core_rpc::dispatch_rpc("rocks_list_address_receipts", &params).unwrap();
+        serde_json::from_str(&out).unwrap()
+    };
+    let db = db_path.to_str().unwrap();
+    let first = call(serde_json::json!({"db_path": db, "address": "alice", "limit": 1}));
+    assert_eq!(first["page"]["receipts"][0]["tx_hash"], "bb");
+    let cursor = first["page"]["next_cursor"].as_str().unwrap().to_string();
+    let second = call(serde_json::json!({"db_path": db, "address": "alice", "cursor": cursor}));
+    assert_eq!(second["page"]["receipts"][0]["tx_hash"], "dd");
+    assert_eq!(second["page"]["receipts"][0]["fee_payer"], "alice");
+    assert!(second["page"]["next_cursor"].is_null());
+}
+
+#[test]
+fn receipts_query_requires_store() {
+    assert!(Core::new().get_receipts_by_address("alice", None, 10).is_err());
+}
@@ -0,0 +1,129 @@
+//! Reference executor for PLP transfers: the protocol rules written out as plainly as possible.
+//!
+//! Sorted maps, one step per rule, full recomputation of both roots on every call. It shares no
+//! execution code with the crate (only the leaf hash of the account tree), so a divergence in a
+//! differential test points at the optimized path. Registries are not modeled: the commitment root takes
+//! their digest from the caller.
+
+use std::collections::BTreeMap;
+use platarium_core::core::transaction::MIN_FEE_UPLP;
+use platarium_core::{account_leaf, TREASURY_ADDRESS};
+use sha2::{Digest, Sha256};
+
+/// The transfer fields execution depends on; signatures are checked by the caller.
+#[derive(Debug, Clone)]
+pub struct RefTransfer {
+    pub from: String,
+    pub to: String,
+    pub amount: u128,
+    pub fee_uplp: u128,
+    pub nonce: u64,
+}
+
+/// Account state as three plain maps. An entry exists once an account has been written, even if zero,
+/// because the snapshot root hashes every entry.
+#[derive(Debug, Default, Clone)]
+pub struct ReferenceExecutor {
+    pub plp: BTreeMap<String, u128>,
+    pub uplp: BTreeMap<String, u128>,
+    pub nonces: BTreeMap<String, u64>,
+}
+
+impl ReferenceExecutor {
+    pub fn fund(&mut self, address: &str, plp: u128, uplp: u128) {
+        self.plp.insert(address.to_string(), plp);
+        self.uplp.insert(address.to_string(), uplp);
+    }
+
+    /// Applies one transfer, or leaves the state untouched and says why not.
+    pub fn execute(&mut self, tx: &RefTransfer) -> Result<(), &'static str> {
+        if tx.amount == 0 {
+            return Err("zero amount");
+        }
+        if tx.fee_uplp < MIN_FEE_UPLP {
+            return Err("fee below minimum");
+        }
+        let nonce = self.nonces.get(&tx.from).copied().unwrap_or(0);
+        if nonce != tx.nonce {
+            return Err("wrong nonce");
+        }
+
+        // The fee is paid from the μPLP pool first, then from PLP.
+        let uplp = self.uplp.get(&tx.from).copied().unwrap_or(0);
+        let fee_from_uplp = tx.fee_uplp.min(uplp);
+        let fee_from_plp = tx.fee_uplp - fee_from_uplp;
+        let plp = self.plp.get(&tx.from).copied().unwrap_or(0);
+        if plp < tx.amount + fee_from_plp {
+            return Err("insufficient balance");
+        }
+
+        self.uplp.insert(tx.from.clone(), uplp - fee_from_uplp);
+        let treasury = self.uplp.get(TREASURY_ADDRESS).copied().unwrap_or(0);
+        self.uplp.insert(TREASURY_ADDRESS.to_string(), treasury + tx.fee_uplp);
+
+        self.plp.insert(tx.from.clone(), plp - fee_from_plp - tx.amount);
+        let to_plp = self.plp.get(&tx.to).copied().unwrap_or(0);
+        self.plp.insert(tx.to.clone(), to_plp + tx.amount);
+
+        self.nonces.insert(tx.from.clone(), nonce + 1);
+        Ok(())
+    }
+
+    /// Same definition as `StateSnapshot::compute_state_root`: SHA256 over sorted (address, PLP balance), then sorted (address, nonce).
+    pub fn snapshot_root(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (address, balance) in &self.plp {
+            hasher.update(address.as_bytes());
+            hasher.update(balance.to_le_bytes());
+        }
+        for (address, nonce) in &self.nonces {
+            hasher.update(address.as_bytes());
+            hasher.update(nonce.to_le_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Same definition as the state commitment: a sparse Merkle tree over `SHA256("acctkey" || address)`, where a
+    /// subtree with one account is its leaf node and an empty one is all zero, recomputed from scratch, then
+    /// `SHA256("state" || tree root || registries)` with the hex `registries` digest.
+    pub fn commitment_root(&self, registries: &str) -> String {
+        let mut addresses: Vec<&String> = self.plp.keys().chain(self.nonces.keys()).chain(self.uplp.keys()).collect();
+        addresses.sort();
+        addresses.dedup();
+        let mut leaves: Vec<([u8; 32], [u8; 32])> = Vec::new();
+        for address in addresses {
+            let balance = self.plp.get(address).copied().unwrap_or(0);
+            let nonce = self.nonces.get(address).copied().unwrap_or(0);
+            let uplp = self.uplp.get(address).copied().unwrap_or(0);
+            if let Some(leaf) = account_leaf(address, balance, nonce, uplp) {
+                let key: [u8; 32] = Sha256::new().chain_update(b"acctkey").chain_update(address).finalize().into();
+                leaves.push((key, leaf));
+            }
+        }
+        leaves.sort();
+        let (root, _) = tree_node(0, &leaves);
+        let registries = hex::decode(registries).expect("hex registries digest");
+        hex::encode(Sha256::new().chain_update(b"state").chain_update(root).chain_update(registries).finalize())
+    }
+}
+
+/// Hash and account count of the subtree at `depth` holding `leaves`.
+fn tree_node(depth: usize, leaves: &[([u8; 32], [u8; 32])]) -> ([u8; 32], usize) {
+    if leaves.is_empty() {
+        return ([0u8; 32], 0);
+    }
+    if leaves.len() == 1 {
+        let (key, leaf) = &leaves[0];
+        return (Sha256::new().chain_update([0u8]).chain_update(key).chain_update(leaf).finalize().into(), 1);
+    }
+    let bit = |key: &[u8; 32]| key[depth / 8] & (0x80 >> (depth % 8)) != 0;
+    let left: Vec<_> = leaves.iter().filter(|(k, _)| !bit(k)).copied().collect();
+    let right: Vec<_> = leaves.iter().filter(|(k, _)| bit(k)).copied().collect();
+    let (l, ln) = tree_node(depth + 1, &left);
+    let (r, rn) = tree_node(depth + 1, &right);
+    match (ln, rn) {
+        (1, 0) => (l, 1),
+        (0, 1) => (r, 1),
+        _ => (Sha256::new().chain_update([1u8]).chain_update(l).chain_update(r).finalize().into(), ln + rn),
+    }
+}
@@ -18,6 +18,10 @@ echo ""
 echo "Running determinism invariants tests (Step 10)..."
 cargo test --test determinism_invariants -- --nocapture
 
+echo ""
+echo "Running protocol conformance suite (release gate)..."
+cargo test --test conformance -- --nocapture || exit 1
+
 echo ""
 echo "Running unit tests..."
 cargo test --lib -- --nocapture
@@ -0,0 +1,31 @@
+//! Seed store end to end: a key pair saved under a name is restored from the store alone and re-derives the same
+//! address, and the stored entry is never plaintext.
+
+use platarium_core::*;
+
+#[test]
+fn saved_seed_signs_for_the_same_address() {
+    let dir = std::env::temp_dir().join(format!("platarium-seed-store-test-{}", std::process::id()));
+    let store = default_seed_store(&dir);
+    let keys = KeyGenerator::default().generate_keys().unwrap();
+    let keystore = Keystore::encrypt_key_pair(&keys, "correct horse", KdfParams { log_n: 4, r: 8, p: 1 }).unwrap();
+    store.save("daily", &keystore).unwrap();
+
+    #[cfg(not(feature = "keyring"))]
+    {
+        let on_disk = std::fs::read_to_string(dir.join("daily.json")).unwrap();
+        assert!(!on_disk.contains(&keys.mnemonic));
+        assert!(!on_disk.contains(keys.private_key.trim_start_matches("PSx")));
+    }
+
+    let restored = store.load("daily").unwrap().decrypt_key_pair("correct horse").unwrap();
+    assert!(store.load("daily").unwrap().decrypt_key_pair("wrong").is_err());
+    assert_eq!(restored, keys);
+    let again = KeyGenerator::default()
+        .restore_keys(&restored.mnemonic, &restored.alphanumeric_part, 0, None)
+        .unwrap();
+    assert_eq!(again.public_key, keys.public_key);
+
+    assert!(store.delete("daily").unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,139 @@
+//! Session keys end to end: an app holding only a session key signs transfers for an account, within the
+//! registered asset, per-block cap and expiry.
+
+use platarium_core::core::execution::{ExecutionContext, ExecutionLogic};
+use platarium_core::*;
+use secp256k1::SecretKey;
+use std::collections::HashSet;
+
+/// Key address of the secret key 1 (the secp256k1 generator).
+const ACCOUNT: &str = "Px0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+/// Mirrors the canonical transaction hash data for non-transfer kinds.
+#[derive(serde::Serialize)]
+struct TxHashData {
+    from: String,
+    to: String,
+    asset: String,
+    amount: u128,
+    fee_uplp: u128,
+    nonce: u64,
+    reads: Vec<String>,
+    writes: Vec<String>,
+    kind: TxKind,
+}
+
+fn session_secret() -> SecretKey {
+    SecretKey::from_slice(&[7u8; 32]).unwrap()
+}
+
+fn session_pub() -> String {
+    let secp = secp256k1::Secp256k1::new();
+    hex::encode(secp256k1::PublicKey::from_secret_key(&secp, &session_secret()).serialize())
+}
+
+fn base_tx(kind: TxKind, asset: Asset, amount: u128, nonce: u64) -> Transaction {
+    Transaction::new(
+        ACCOUNT.to_string(),
+        "merchant".to_string(),
+        asset,
+        amount,
+        1,
+        nonce,
+        HashSet::new(),
+        HashSet::new(),
+        String::new(),
+        String::new(),
+    )
+    .unwrap()
+    .with_kind(kind)
+    .unwrap()
+}
+
+fn session_transfer(signer: &SecretKey, amount: u128, nonce: u64) -> Transaction {
+    let kind = TxKind::SessionTransfer { session_key: session_pub() };
+    let mut tx = base_tx(kind.clone(), Asset::PLP, amount, nonce);
+    let message = TxHashData {
+        from: tx.from.clone(),
+        to: tx.to.clone(),
+        asset: tx.asset.as_canonical(),
+        amount,
+        fee_uplp: tx.fee_uplp,
+        nonce,
+        reads: vec![],
+        writes: vec![],
+        kind,
+    };
+    assert_eq!(hex::encode(hash_message(&message).unwrap()), tx.hash);
+    tx.sig_main = sign_message(signer, &message).unwrap().signature_compact;
+    tx
+}
+
+fn setup(expiry_height: u64) -> State {
+    let state = State::new();
+    state.set_balance(&ACCOUNT.to_string(), 1_000);
+    let register = base_tx(
+        TxKind::RegisterSessionKey {
+            session_key: format!("Px{}", session_pub()),
+            max_amount_per_block: 50,
+            expiry_height,
+        },
+        Asset::PLP,
+        0,
+        0,
+    );
+    state.apply_effects(&register).unwrap();
+    state
+}
+
+#[test]
+fn session_key_transfers_within_cap() {
+    let state = setup(100);
+    state.set_block_height(5);
+    let tx = session_transfer(&session_secret(), 30, 1);
+    ExecutionLogic::execute_transaction(&state, &tx, ExecutionContext::Production).unwrap();
+    assert_eq!(state.get_balance(&"merchant".to_string()), 30);
+
+    // Same block: only 20 left under the cap.
+    let over = session_transfer(&session_secret(), 21, 2);
+    let err = ExecutionLogic::execute_transaction(&state, &over, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("cap exceeded"), "{}", err);
+
+    // Next block: the cap resets.
+    state.set_block_height(6);
+    ExecutionLogic::execute_transaction(&state, &over, ExecutionContext::Production).unwrap();
+    assert_eq!(state.get_balance(&"merchant".to_string()), 51);
+}
+
+#[test]
+fn session_key_rejects_wrong_signer_asset_and_expiry() {
+    let state = setup(10);
+    state.set_block_height(5);
+    let other = SecretKey::from_slice(&[9u8; 32]).unwrap();
+    let forged = session_transfer(&other, 1, 1);
+    assert!(ExecutionLogic::validate_transaction(&forged).is_err());
+
+    let mut wrong_asset = base_tx(
+        TxKind::SessionTransfer { session_key: session_pub() },
+        Asset::Token("GOLD".to_string()),
+        1,
+        1,
+    );
+    wrong_asset.sig_main = String::new();
+    assert!(ExecutionLogic::check_transaction_applicability(&state, &wrong_asset).is_err());
+
+    state.set_block_height(10);
+    let expired = session_transfer(&session_secret(), 1, 1);
+    let err = ExecutionLogic::check_transaction_applicability(&state, &expired).unwrap_err();
+    assert!(err.to_string().contains("expired"), "{}", err);
+}
+
+#[test]
+fn revoked_session_key_is_rejected() {
+    let state = setup(100);
+    let revoke = base_tx(TxKind::RevokeSessionKey { session_key: session_pub() }, Asset::PLP, 0, 1);
+    state.apply_effects(&revoke).unwrap();
+    assert!(state.session_key(&ACCOUNT.to_string(), &session_pub()).is_none());
+    let tx = session_transfer(&session_secret(), 1, 2);
+    assert!(ExecutionLogic::check_transaction_applicability(&state, &tx).is_err());
+}
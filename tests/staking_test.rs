@@ -0,0 +1,113 @@
+//! On-chain staking end to end: `Stake` transactions back a validator's registry stake, slashing takes from the
+//! account's stake (unbonding included), and unstaked PLP comes back only after the unbonding delay.
+
+use platarium_core::core::execution::ExecutionLogic;
+use platarium_core::core::slashing::{apply_slash_at, SlashingReason};
+use platarium_core::core::state::{BURN_ADDRESS, STAKING_ADDRESS, UNBONDING_DELAY_BLOCKS};
+use platarium_core::testing::{DeterministicRng, TestAccount};
+use platarium_core::*;
+use std::collections::HashSet;
+
+fn staking(account: &TestAccount, kind: TxKind, amount: u128, nonce: u64) -> Transaction {
+    account.sign(
+        Transaction::new(
+            account.address.clone(),
+            STAKING_ADDRESS.to_string(),
+            Asset::PLP,
+            amount,
+            1,
+            nonce,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap()
+        .with_kind(kind)
+        .unwrap(),
+    )
+}
+
+#[test]
+fn stake_backs_validator_and_unbonds_after_delay() {
+    let mut rng = DeterministicRng::new(4042);
+    let (alice, bob) = (rng.account(), rng.account());
+    let state = State::new();
+    for account in [&alice, &bob] {
+        state.set_balance(&account.address, 10_000);
+        state.set_uplp_balance(&account.address, 100);
+    }
+    let registry = NodeRegistry::new();
+    registry.register("alice".into(), alice.pub_main(), 0, 10).unwrap();
+    registry.register("bob".into(), bob.pub_main(), 0, 10).unwrap();
+    assert_eq!(registry.get(&"alice".into()).unwrap().stake_account(), Some(alice.address.clone()));
+
+    let block = [staking(&alice, TxKind::Stake, 5_000, 0), staking(&bob, TxKind::Stake, 1_000, 0)];
+    let executed = ExecutionLogic::execute_block(&state, &block, 1).unwrap();
+    assert!(executed.receipts.iter().all(|r| r.success), "{:?}", executed.receipts);
+    registry.sync_stakes(&state);
+    let (a, b) = (registry.get(&"alice".into()).unwrap(), registry.get(&"bob".into()).unwrap());
+    assert_eq!((a.stake, b.stake), (5_000, 1_000));
+    assert!(a.reputation_score > b.reputation_score, "stake weight feeds selection");
+
+    let executed = ExecutionLogic::execute_block(&state, &[staking(&alice, TxKind::Unstake, 2_000, 1)], 2).unwrap();
+    assert!(executed.receipts[0].success, "{:?}", executed.receipts);
+    registry.sync_stakes(&state);
+    assert_eq!(registry.get(&"alice".into()).unwrap().stake, 3_000);
+    assert_eq!(state.get_unbonding(&alice.address), 2_000);
+    assert_eq!(state.get_balance(&alice.address), 5_000);
+
+    // Equivocation slashes 100: taken from alice's bonded stake and moved from the pool to the burn address.
+    let slashed = apply_slash_at(
+        &registry,
+        &state,
+        &ProtocolParamsSchedule::default(),
+        3,
+        &"alice".into(),
+        SlashingReason::Equivocation,
+    )
+    .unwrap();
+    assert_eq!(slashed, 100);
+    assert_eq!(state.get_stake(&alice.address), 2_900);
+    assert_eq!(state.get_balance(&BURN_ADDRESS.to_string()), 100);
+    assert_eq!(state.get_balance(&STAKING_ADDRESS.to_string()), 1_000 + 2_900 + 2_000);
+    registry.sync_stakes(&state);
+    assert_eq!(registry.get(&"alice".into()).unwrap().stake, 2_900);
+
+    let release = 2 + UNBONDING_DELAY_BLOCKS;
+    assert!(ExecutionLogic::execute_block(&state, &[], release - 1).unwrap().released_stake.is_empty());
+    let executed = ExecutionLogic::execute_block(&state, &[], release).unwrap();
+    assert_eq!(executed.released_stake, vec![(alice.address.clone(), 2_000)]);
+    assert_eq!(state.get_balance(&alice.address), 7_000);
+    assert_eq!(state.get_unbonding(&alice.address), 0);
+}
+
+#[test]
+fn unbonding_stake_is_still_slashable() {
+    let mut rng = DeterministicRng::new(4043);
+    let carol = rng.account();
+    let state = State::new();
+    state.set_balance(&carol.address, 1_000);
+    state.set_uplp_balance(&carol.address, 10);
+    let registry = NodeRegistry::new();
+    registry.register("carol".into(), carol.pub_main(), 0, 10).unwrap();
+
+    let block = [staking(&carol, TxKind::Stake, 150, 0), staking(&carol, TxKind::Unstake, 120, 1)];
+    assert!(ExecutionLogic::execute_block(&state, &block, 1).unwrap().receipts.iter().all(|r| r.success));
+    registry.sync_stakes(&state);
+    assert_eq!(registry.get(&"carol".into()).unwrap().stake, 30);
+
+    // The registry holds only the bonded 30, so that is all one slash can remove.
+    let schedule = ProtocolParamsSchedule::default();
+    let slash = || apply_slash_at(&registry, &state, &schedule, 2, &"carol".into(), SlashingReason::Equivocation);
+    assert_eq!(slash().unwrap(), 30);
+    assert_eq!((state.get_stake(&carol.address), state.get_unbonding(&carol.address)), (0, 120));
+
+    // An account slashed directly loses unbonding stake once the bonded stake is gone.
+    assert_eq!(state.slash_stake(&carol.address, 50, Default::default()), 50);
+    assert_eq!(state.get_unbonding(&carol.address), 70);
+    assert_eq!(state.get_balance(&BURN_ADDRESS.to_string()), 80);
+    let executed = ExecutionLogic::execute_block(&state, &[], 1 + UNBONDING_DELAY_BLOCKS).unwrap();
+    assert_eq!(executed.released_stake, vec![(carol.address.clone(), 70)]);
+    assert_eq!(state.get_balance(&STAKING_ADDRESS.to_string()), 0);
+}
@@ -0,0 +1,115 @@
+//! Standing orders end to end: a signed order pays on schedule at block finalization, survives a state file round
+//! trip, and stops when the sender cancels it.
+
+use platarium_core::core::execution::{ExecutionContext, ExecutionLogic};
+use platarium_core::core::standing_orders::{execute_standing_orders, StandingOrderStatus};
+use platarium_core::core::state_file::StateFileData;
+use platarium_core::testing::{DeterministicRng, TestAccount};
+use platarium_core::*;
+use std::collections::HashSet;
+
+fn signed(sender: &TestAccount, to: &str, amount: u128, nonce: u64, kind: TxKind) -> Transaction {
+    sender.sign(
+        Transaction::new(
+            sender.address.clone(),
+            to.to_string(),
+            Asset::PLP,
+            amount,
+            1,
+            nonce,
+            HashSet::new(),
+            HashSet::new(),
+            String::new(),
+            String::new(),
+        )
+        .unwrap()
+        .with_kind(kind)
+        .unwrap(),
+    )
+}
+
+#[test]
+fn order_pays_each_interval_until_cancelled() {
+    let mut rng = DeterministicRng::new(81);
+    let payer = rng.account();
+    let landlord = rng.account().address;
+    let state = State::new();
+    state.set_balance(&payer.address, 1_000);
+    state.set_block_height(100);
+
+    let create = signed(
+        &payer,
+        &landlord,
+        250,
+        0,
+        TxKind::CreateStandingOrder { interval_blocks: 10, end_height: 1_000 },
+    );
+    ExecutionLogic::execute_transaction(&state, &create, ExecutionContext::Production).unwrap();
+    assert_eq!(state.get_balance(&landlord), 0);
+    let order = state.standing_order(&payer.address, &create.hash).unwrap();
+    assert_eq!(order.next_height, 110);
+    let bad = signed(&payer, &landlord, 5, 1, TxKind::CreateStandingOrder { interval_blocks: 10, end_height: 105 });
+    assert!(ExecutionLogic::execute_transaction(&state, &bad, ExecutionContext::Production).is_err());
+
+    state.set_block_height(110);
+    let receipts = execute_standing_orders(&state);
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].order_id, create.hash);
+    assert_eq!(receipts[0].status, StandingOrderStatus::Paid);
+    assert_eq!(state.get_balance(&landlord), 250);
+    assert!(state
+        .take_events()
+        .iter()
+        .any(|e| matches!(e, Event::StandingOrderPaid { amount: 250, .. })));
+
+    // The order is part of the persisted state.
+    let json = serde_json::to_string(&StateFileData::from_state(&state)).unwrap();
+    let reloaded = serde_json::from_str::<StateFileData>(&json).unwrap().into_state().unwrap();
+    assert_eq!(reloaded.standing_orders(), state.standing_orders());
+
+    let cancel = signed(&payer, &payer.address, 0, 1, TxKind::CancelStandingOrder { order_id: create.hash.clone() });
+    ExecutionLogic::execute_transaction(&state, &cancel, ExecutionContext::Production).unwrap();
+    state.set_block_height(120);
+    assert!(execute_standing_orders(&state).is_empty());
+    assert_eq!(state.get_balance(&landlord), 250);
+
+    let again = signed(&payer, &payer.address, 0, 2, TxKind::CancelStandingOrder { order_id: create.hash.clone() });
+    let err = ExecutionLogic::execute_transaction(&state, &again, ExecutionContext::Production).unwrap_err();
+    assert!(err.to_string().contains("not registered"), "{}", err);
+}
+
+#[test]
+fn block_execution_pays_due_orders_before_the_state_root() {
+    let mut rng = DeterministicRng::new(82);
+    let payer = rng.account();
+    let landlord = rng.account().address;
+    let producer = State::new();
+    producer.set_balance(&payer.address, 1_000);
+    let validator = State::new();
+    validator.restore(&producer.snapshot());
+
+    let create = signed(&payer, &landlord, 100, 0, TxKind::CreateStandingOrder { interval_blocks: 5, end_height: 20 });
+    let blocks = vec![
+        (10, vec![create.clone()]),
+        (14, vec![]),
+        (15, vec![signed(&payer, &landlord, 1, 1, TxKind::Transfer)]),
+    ];
+    let mut paid_at = Vec::new();
+    for (height, txs) in &blocks {
+        let produced = ExecutionLogic::execute_block(&producer, txs, *height).unwrap();
+        let validated = ExecutionLogic::execute_block(&validator, txs, *height).unwrap();
+        assert_eq!(produced, validated);
+        assert!(produced.receipts.iter().all(|r| r.success));
+        assert_eq!(validator.current_root(), producer.current_root(), "height {}", height);
+        for r in produced.standing_orders {
+            assert_eq!((r.order_id.as_str(), &r.status), (create.hash.as_str(), &StandingOrderStatus::Paid));
+            paid_at.push(r.height);
+        }
+    }
+
+    // Nothing is due before height 15; there the payment runs after the block's transfer.
+    assert_eq!(paid_at, vec![15]);
+    assert_eq!(producer.block_height(), 15);
+    assert_eq!(producer.get_balance(&landlord), 101);
+    assert_eq!(producer.standing_order(&payer.address, &create.hash).unwrap().next_height, 20);
+}
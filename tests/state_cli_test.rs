@@ -13,8 +13,8 @@ fn state_file_init_query_credit_root() {
     let _ = std::fs::remove_file(&path);
     init_state_file(&path).expect("init");
 
-    state_credit_json(&path, "PxAlice", 1_000_000, 10_000, true).expect("credit");
-    let query = state_query_json(&path, "PxAlice", "PLP").expect("query");
+    state_credit_json(&path, "alice", 1_000_000, 10_000, true).expect("credit");
+    let query = state_query_json(&path, "alice", "PLP").expect("query");
     assert!(query.contains("\"balance\":\"1000000\""));
     assert!(query.contains("\"nonce\":0"));
 
@@ -87,9 +87,9 @@ fn state_credit_accumulates_balance() {
     let _ = std::fs::remove_file(&path);
     init_state_file(&path).expect("init");
 
-    state_credit_json(&path, "PxAlice", 5000, 0, true).expect("credit1");
-    state_credit_json(&path, "PxAlice", 5000, 0, true).expect("credit2");
-    let query = state_query_json(&path, "PxAlice", "PLP").expect("query");
+    state_credit_json(&path, "alice", 5000, 0, true).expect("credit1");
+    state_credit_json(&path, "alice", 5000, 0, true).expect("credit2");
+    let query = state_query_json(&path, "alice", "PLP").expect("query");
     assert!(
         query.contains("\"balance\":\"10000\""),
         "expected cumulative 10000 PLP, got {query}"
@@ -97,3 +97,157 @@ fn state_credit_accumulates_balance() {
 
     let _ = std::fs::remove_file(&path);
 }
+
+#[test]
+fn state_file_round_trips_genesis_vesting() {
+    let path = temp_state_path("vesting");
+    let _ = std::fs::remove_file(&path);
+    let state = State::new();
+    state.set_balance(&"founder".to_string(), 1_000);
+    let schedule = VestingSchedule {
+        total: 1_000,
+        start_height: 0,
+        cliff_blocks: 0,
+        duration_blocks: 10,
+    };
+    state.set_vesting_schedule(&"founder".to_string(), schedule).expect("vesting");
+    save_state_file(&path, &state).expect("save");
+
+    let loaded = load_state_file(&path).expect("load");
+    assert_eq!(loaded.vesting_schedule(&"founder".to_string()), Some(schedule));
+    assert_eq!(loaded.vested_balance(&"founder".to_string(), 5), 500);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn state_file_round_trips_asset_registry() {
+    let path = temp_state_path("asset_registry");
+    let _ = std::fs::remove_file(&path);
+    let state = State::new();
+    let issuer = "issuer".to_string();
+    let usdt = Asset::Token("USDT".into());
+    state.set_uplp_balance(&issuer, 10);
+    state.register_token(&usdt, &issuer).expect("register");
+    state.apply_mint(&issuer, &"holder".to_string(), &usdt, 500, 1, Some(0)).expect("mint");
+    state.apply_asset_freeze(&issuer, &usdt, true, 1, Some(1)).expect("freeze");
+    save_state_file(&path, &state).expect("save");
+
+    let loaded = load_state_file(&path).expect("load");
+    assert_eq!(loaded.asset_registry(), state.asset_registry());
+    assert_eq!(loaded.total_supply(&usdt), Some(500));
+    assert!(loaded.is_asset_frozen(&usdt));
+    // The token id stays taken after a reload.
+    assert!(loaded.register_token(&usdt, &"squatter".to_string()).is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn state_file_round_trips_allowances() {
+    let path = temp_state_path("allowances");
+    let _ = std::fs::remove_file(&path);
+    let state = State::new();
+    let (owner, exchange) = ("owner".to_string(), "exchange".to_string());
+    let usdt = Asset::Token("USDT".into());
+    state.set_uplp_balance(&owner, 10);
+    state.set_uplp_balance(&exchange, 10);
+    state.set_asset_balance(&owner, &usdt, 100);
+    state.apply_approve(&owner, &exchange, &usdt, 60, 1, Some(0)).expect("approve");
+    state.apply_approve(&owner, &exchange, &Asset::PLP, 5, 1, Some(1)).expect("approve PLP");
+    state
+        .apply_transfer_from(&exchange, &owner, &"shop".to_string(), &usdt, 25, 1, Some(0))
+        .expect("transfer_from");
+    save_state_file(&path, &state).expect("save");
+
+    let loaded = load_state_file(&path).expect("load");
+    assert_eq!(loaded.allowances(), state.allowances());
+    assert_eq!(loaded.get_allowance(&owner, &exchange, &usdt), 35);
+    assert_eq!(loaded.get_allowance(&owner, &exchange, &Asset::PLP), 5);
+    assert_eq!(loaded.current_root(), state.current_root());
+    // The reloaded allowance caps the spender like the original one.
+    assert!(loaded
+        .apply_transfer_from(&exchange, &owner, &"shop".to_string(), &usdt, 36, 1, Some(1))
+        .is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn state_file_round_trips_freeze_orders() {
+    let path = temp_state_path("freeze_orders");
+    let _ = std::fs::remove_file(&path);
+    let mut rng = testing::DeterministicRng::new(31);
+    let registry = NodeRegistry::new();
+    let key = rng.secret_key();
+    registry
+        .register("n0".into(), hex::encode(key.public_key(secp256k1::SECP256K1).serialize()), 100, 10)
+        .unwrap();
+    let sign = |action: GovernanceAction| vec![Approval::sign("n0".into(), &action, &key).unwrap()];
+
+    let state = State::new();
+    let (thief, fence) = ("thief".to_string(), "fence".to_string());
+    let standing = AddressFreezeOrder::new(thief.clone(), 0, None, "incident-1".into()).unwrap();
+    let lifted = AddressFreezeOrder::new(fence.clone(), 0, Some(100), "incident-2".into()).unwrap();
+    let standing_id = standing.id();
+    let lifted_id = lifted.id();
+    let approvals = sign(GovernanceAction::FreezeAddress { order_id: standing_id.clone() });
+    enact_freeze_order(&state, &registry, standing, &approvals).unwrap();
+    let approvals = sign(GovernanceAction::FreezeAddress { order_id: lifted_id.clone() });
+    enact_freeze_order(&state, &registry, lifted, &approvals).unwrap();
+    state.set_block_height(3);
+    let approvals = sign(GovernanceAction::LiftFreezeOrder { order_id: lifted_id.clone() });
+    lift_freeze_order(&state, &registry, &lifted_id, &approvals).unwrap();
+    save_state_file(&path, &state).expect("save");
+
+    let loaded = load_state_file(&path).expect("load");
+    assert_eq!(loaded.freeze_orders(), state.freeze_orders());
+    assert!(loaded.check_not_frozen(&thief).unwrap_err().to_string().contains(&standing_id));
+    assert!(loaded.check_not_frozen(&fence).is_ok());
+    assert_eq!(loaded.current_root(), state.current_root());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+fn bump_fee_balances(state: &State) -> platarium_core::error::Result<()> {
+    state.set_uplp_balance(&"a1".to_string(), state.get_uplp_balance(&"a1".to_string()) * 2);
+    Ok(())
+}
+
+#[test]
+fn state_file_migrations_run_at_activation_height_and_persist_log() {
+    let path = temp_state_path("migrations");
+    let _ = std::fs::remove_file(&path);
+    let state = State::new();
+    state.set_uplp_balance(&"a1".to_string(), 5);
+    state.set_block_height(40);
+    save_state_file(&path, &state).expect("save");
+
+    let mut registry = MigrationRegistry::new(STATE_VERSION);
+    registry
+        .register(StateMigration {
+            from_version: STATE_VERSION,
+            name: "double_fee_balances",
+            activation_height: 50,
+            kind: MigrationKind::State(bump_fee_balances),
+        })
+        .expect("register");
+
+    let loaded = load_state_file_with(&path, &registry).expect("load before activation");
+    assert_eq!(loaded.layout().version, STATE_VERSION);
+    loaded.set_block_height(50);
+    let applied = registry.apply_due(&loaded, 50).expect("activate");
+    assert_eq!(applied.len(), 1);
+    assert_eq!(loaded.get_uplp_balance(&"a1".to_string()), 10);
+    save_state_file(&path, &loaded).expect("save migrated");
+
+    let reloaded = load_state_file_with(&path, &registry).expect("reload");
+    assert_eq!(reloaded.layout().version, STATE_VERSION + 1);
+    assert_eq!(reloaded.layout().migration_log, applied);
+    assert_eq!(reloaded.get_uplp_balance(&"a1".to_string()), 10);
+
+    let err = load_state_file(&path).expect_err("future version refused");
+    assert!(err.to_string().contains("newer than this binary"));
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,26 @@
+//! Strict verification from an integrator's side: the published negative vectors survive a JSON round trip and
+//! every one of them is rejected, while signatures this crate produces pass.
+
+use platarium_core::signature::{strict, test_vectors};
+use platarium_core::*;
+
+#[test]
+fn negative_vectors_are_rejected_and_own_signatures_pass() {
+    let vectors = serde_json::to_value(test_vectors::negative()).unwrap();
+    let vectors = vectors.as_array().unwrap();
+    assert!(vectors.len() >= 8);
+    for vector in vectors {
+        let field = |name: &str| vector[name].as_str().unwrap().to_string();
+        let hash = hex::decode(field("hash")).unwrap();
+        let err = strict::verify_hash(&hash, &field("signature"), &field("pub_key")).unwrap_err();
+        assert!(err.to_string().contains("Strict signature check failed"), "{}: {}", field("name"), err);
+        assert!(vector["rejection"].is_string() || vector["rejection"].is_object(), "{}", field("name"));
+    }
+
+    let secret_key = secp256k1::SecretKey::from_slice(&[0x42; 32]).unwrap();
+    let message = serde_json::json!({"withdrawal": 1});
+    let sig = sign_message(&secret_key, &message).unwrap();
+    assert!(strict::verify(&message, &sig.to_compact_hex(), &sig.pub_key).unwrap());
+    assert!(strict::verify(&message, &sig.to_der_hex(), &sig.pub_key).unwrap());
+    assert!(strict::verify(&message, &sig.signature_compact, &sig.pub_key).is_err());
+}
@@ -0,0 +1,104 @@
+//! Threshold custody end to end: two 2-of-3 groups hold an account's main and derived keys, and a different pair
+//! of custodians co-signs each key of a transfer that executes like any single-key transaction.
+
+#![cfg(feature = "experimental")]
+
+use platarium_core::core::execution::{ExecutionContext, ExecutionLogic};
+use platarium_core::testing::DeterministicRng;
+use platarium_core::threshold::PARTIES;
+use platarium_core::*;
+use std::collections::HashSet;
+
+/// Each custodian's transport key pair, with the roster of all three public keys.
+fn transports(rng: &mut DeterministicRng) -> Vec<Transport> {
+    let secrets: Vec<_> = (0..PARTIES).map(|_| rng.secret_key()).collect();
+    let roster: Vec<_> = secrets.iter().map(|s| s.public_key(secp256k1::SECP256K1)).collect();
+    (1..=PARTIES)
+        .zip(secrets)
+        .map(|(p, s)| Transport::new(p, s, roster.clone()).unwrap())
+        .collect()
+}
+
+fn keygen(transports: &[Transport], rng: &mut DeterministicRng) -> Vec<KeyShare> {
+    let dealings: Vec<Dealing> = transports.iter().map(|t| keygen_dealing(t, rng).unwrap()).collect();
+    transports.iter().map(|t| KeyShare::from_dealings(t, &dealings).unwrap()).collect()
+}
+
+/// Runs a presigning session and co-signs `digest` with parties `signers` (1-based).
+fn co_sign(
+    shares: &[KeyShare],
+    transports: &[Transport],
+    signers: [u8; 2],
+    digest: &[u8; 32],
+    rng: &mut DeterministicRng,
+) -> String {
+    let session_id = hex::encode(digest);
+    let (mut sessions, round1): (Vec<_>, Vec<_>) = shares
+        .iter()
+        .zip(transports)
+        .map(|(s, t)| PresignSession::start(s, t, &session_id, rng).unwrap())
+        .unzip();
+    let round2: Vec<_> = sessions.iter_mut().map(|s| s.round2(&round1).unwrap()).collect();
+    // Messages may arrive in any order.
+    let mut round2_shuffled = round2.clone();
+    round2_shuffled.reverse();
+    let round3: Vec<_> = sessions.iter_mut().map(|s| s.round3(&round2_shuffled, rng).unwrap()).collect();
+    let presigs: Vec<Presignature> = sessions.into_iter().map(|s| s.finish(&round3).unwrap()).collect();
+    let info = presigs[0].info.clone();
+    let partials: Vec<SignatureShare> = presigs
+        .into_iter()
+        .filter(|p| signers.contains(&p.index))
+        .map(|p| p.sign_partial(digest))
+        .collect();
+    hex::encode(aggregate_signature(&info, digest, &partials).unwrap().serialize_compact())
+}
+
+#[test]
+fn custodians_co_sign_a_transfer() {
+    let mut rng = DeterministicRng::new(61);
+    let transports = transports(&mut rng);
+    let main = keygen(&transports, &mut rng);
+    let derived = keygen(&transports, &mut rng);
+    let account = main[0].address();
+    let to = rng.account().address;
+    let state = State::new();
+    state.set_balance(&account, 1_000);
+
+    let mut tx = Transaction::new(
+        account.clone(),
+        to.clone(),
+        Asset::PLP,
+        25,
+        1,
+        0,
+        HashSet::new(),
+        HashSet::new(),
+        String::new(),
+        String::new(),
+    )
+    .unwrap();
+    tx.pub_main = Some(main[0].public_key.clone());
+    tx.pub_derived = Some(derived[0].public_key.clone());
+    let digest: [u8; 32] = hex::decode(&tx.hash).unwrap().try_into().unwrap();
+    tx.sig_main = co_sign(&main, &transports, [1, 3], &digest, &mut rng);
+    tx.sig_derived = co_sign(&derived, &transports, [2, 3], &digest, &mut rng);
+
+    ExecutionLogic::execute_transaction(&state, &tx, ExecutionContext::Production).unwrap();
+    assert_eq!(state.get_balance(&to), 25);
+    assert_eq!(state.get_nonce(&account), 1);
+}
+
+#[test]
+fn presigning_rejects_mixed_sessions_and_missing_parties() {
+    let mut rng = DeterministicRng::new(62);
+    let transports = transports(&mut rng);
+    let shares = keygen(&transports, &mut rng);
+    let (mut a, a1) = PresignSession::start(&shares[0], &transports[0], "a", &mut rng).unwrap();
+    let (_, b1) = PresignSession::start(&shares[1], &transports[1], "b", &mut rng).unwrap();
+    let (_, c1) = PresignSession::start(&shares[2], &transports[2], "a", &mut rng).unwrap();
+    assert!(matches!(
+        a.round2(&[a1.clone(), b1, c1.clone()]),
+        Err(ThresholdError::SessionMismatch { .. })
+    ));
+    assert!(matches!(a.round2(&[a1, c1]), Err(ThresholdError::WrongParties { .. })));
+}
@@ -0,0 +1,24 @@
+//! WIF export/import end to end: keys exported by one tool and imported by another give a signer for the same
+//! address, and the transport format rejects keys for the wrong network or with a typo.
+
+use platarium_core::*;
+
+#[test]
+fn exported_keys_import_into_a_working_signer() {
+    let keys = KeyGenerator::default().generate_keys().unwrap();
+    let exported = serde_json::to_string(&keys.export_wif(WifNetwork::Testnet).unwrap()).unwrap();
+
+    let received: WifKeys = serde_json::from_str(&exported).unwrap();
+    assert_eq!(received.network, WifNetwork::Testnet);
+    let imported = KeyPair::from_wif(&received.main, &received.signature).unwrap();
+    assert_eq!(imported.derivation_paths.main_path, WIF_IMPORTED_PATH);
+    let signer = imported.main_signer().unwrap();
+    assert_eq!(address_from_public_key(&signer.public_key().unwrap()), keys.public_key);
+
+    let mainnet = keys.export_wif(WifNetwork::Mainnet).unwrap();
+    assert_ne!(mainnet.main, received.main);
+    let err = KeyPair::from_wif(&mainnet.main, &received.signature).unwrap_err();
+    assert!(err.to_string().contains("Mainnet"), "{}", err);
+    let typo = format!("{}1", &received.main[..received.main.len() - 1]);
+    assert!(typo == received.main || decode_wif(&typo).is_err());
+}